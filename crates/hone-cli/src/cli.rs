@@ -5,7 +5,7 @@
 
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
 /// Hone - Find and eliminate wasteful spending
 #[derive(Parser)]
@@ -96,6 +96,24 @@ pub enum Commands {
         /// Example: --mcp-port 3001
         #[arg(long)]
         mcp_port: Option<u16>,
+
+        /// Watch a folder for new CSV/OFX statements and import them
+        ///
+        /// Account mappings come from watch.toml (or HONE_WATCH_CONFIG).
+        #[arg(long)]
+        watch: Option<PathBuf>,
+    },
+
+    /// Watch a folder and import new CSV/OFX statements as they appear
+    ///
+    /// Imported files move to <dir>/archive, failures to <dir>/quarantine.
+    Watch {
+        /// Folder to watch (defaults to `dir` in watch.toml)
+        dir: Option<PathBuf>,
+
+        /// Scan once, process the imports, and exit
+        #[arg(long)]
+        once: bool,
     },
 
     /// Show dashboard summary
@@ -166,6 +184,48 @@ pub enum Commands {
         action: Option<EntitiesAction>,
     },
 
+    /// Manage household users, roles and account visibility
+    Users {
+        #[command(subcommand)]
+        action: Option<UsersAction>,
+    },
+
+    /// Show or change detection settings (global, per-merchant, per-tag)
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
+
+    /// Refunds linked to their original purchases (list, match, link, unlink)
+    Refunds {
+        #[command(subcommand)]
+        action: Option<RefundsAction>,
+    },
+
+    /// Warranties and return windows for purchases (list, add, remove)
+    Warranties {
+        #[command(subcommand)]
+        action: Option<WarrantiesAction>,
+    },
+
+    /// Manage scoped API keys (create, list, revoke)
+    Keys {
+        #[command(subcommand)]
+        action: Option<KeysAction>,
+    },
+
+    /// Notification channels, test messages and delivery history
+    Notify {
+        #[command(subcommand)]
+        action: Option<NotifyAction>,
+    },
+
+    /// Query, verify, export and archive the audit log
+    Audit {
+        #[command(subcommand)]
+        action: Option<AuditAction>,
+    },
+
     /// Manage database backups (create, list, restore, prune)
     Backup {
         #[command(subcommand)]
@@ -297,6 +357,10 @@ pub enum ReportType {
         /// Show child categories
         #[arg(long)]
         expand: bool,
+
+        /// Subtract linked refunds from their original purchases
+        #[arg(long)]
+        net_refunds: bool,
     },
 
     /// Spending trends over time
@@ -312,6 +376,10 @@ pub enum ReportType {
         /// Filter to a specific tag
         #[arg(long)]
         tag: Option<String>,
+
+        /// Subtract linked refunds from their original purchases
+        #[arg(long)]
+        net_refunds: bool,
     },
 
     /// Top merchants by spending
@@ -389,6 +457,28 @@ pub enum SubscriptionsAction {
         /// Custom cancellation date (YYYY-MM-DD), defaults to today
         #[arg(long)]
         date: Option<String>,
+        /// How it was cancelled (website, app, phone, email, chat, mail, in_person, bank, other)
+        #[arg(long)]
+        method: Option<String>,
+        /// Confirmation number from the merchant
+        #[arg(long)]
+        confirmation: Option<String>,
+        /// Expected final charge date (YYYY-MM-DD); later charges raise an alert
+        #[arg(long)]
+        final_charge: Option<String>,
+        /// Refund expected from the merchant
+        #[arg(long)]
+        refund: Option<f64>,
+        /// Free-form notes
+        #[arg(long)]
+        notes: Option<String>,
+    },
+
+    /// List tracked cancellations and their verification status
+    Cancellations {
+        /// Filter by status (pending, confirmed, charged_after)
+        #[arg(long)]
+        status: Option<String>,
     },
 }
 
@@ -474,6 +564,336 @@ pub enum RulesAction {
     },
 }
 
+#[derive(Subcommand)]
+pub enum UsersAction {
+    /// List household users
+    List,
+
+    /// Add a household user (the first user must be an owner)
+    Add {
+        /// Email address (must match the Cloudflare Access email or API key scope)
+        email: String,
+        /// Role: owner, editor, viewer
+        #[arg(long, short = 'r', default_value = "viewer")]
+        role: String,
+        /// Display name
+        #[arg(long)]
+        name: Option<String>,
+    },
+
+    /// Change a user's role
+    SetRole {
+        /// User ID
+        id: i64,
+        /// Role: owner, editor, viewer
+        role: String,
+    },
+
+    /// Remove a household user
+    Remove {
+        /// User ID
+        id: i64,
+    },
+
+    /// Show or restrict which users can see an account (owners always can)
+    Visibility {
+        /// Account ID
+        account_id: i64,
+        /// User IDs to restrict the account to (omit to show current visibility)
+        user_ids: Vec<i64>,
+    },
+
+    /// Share an account with the whole household again
+    Share {
+        /// Account ID
+        account_id: i64,
+    },
+
+    /// Set a user's built-in login password (read from stdin)
+    SetPassword {
+        /// User ID
+        id: i64,
+    },
+
+    /// Clear a user's password, TOTP, passkeys and sessions (lockout recovery)
+    ResetAuth {
+        /// User ID
+        id: i64,
+    },
+}
+
+/// Audit log filters shared by `audit list` and `audit export`
+#[derive(Args, Default)]
+pub struct AuditFilterArgs {
+    /// Filter by user email
+    #[arg(long)]
+    pub user: Option<String>,
+    /// Filter by action (e.g., update, login_failed)
+    #[arg(long)]
+    pub action: Option<String>,
+    /// Filter by entity type (e.g., transaction, user)
+    #[arg(long)]
+    pub entity_type: Option<String>,
+    /// Filter by entity ID
+    #[arg(long)]
+    pub entity_id: Option<i64>,
+    /// Start date (YYYY-MM-DD)
+    #[arg(long)]
+    pub from: Option<String>,
+    /// End date (YYYY-MM-DD, inclusive)
+    #[arg(long)]
+    pub to: Option<String>,
+    /// Include archived entries
+    #[arg(long)]
+    pub archived: bool,
+}
+
+#[derive(Subcommand)]
+pub enum AuditAction {
+    /// List recent audit entries
+    List {
+        #[command(flatten)]
+        filter: AuditFilterArgs,
+        /// Maximum entries to show
+        #[arg(long, short = 'n', default_value = "50")]
+        limit: i64,
+    },
+
+    /// Verify the hash chain (exits non-zero if it is broken)
+    Verify,
+
+    /// Export audit entries to CSV or JSON
+    Export {
+        #[command(flatten)]
+        filter: AuditFilterArgs,
+        /// Output format: csv, json
+        #[arg(long, short = 'f', default_value = "csv")]
+        format: String,
+        /// Output file (defaults to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Move entries older than N days to the archive table
+    Archive {
+        /// Archive entries older than this many days
+        #[arg(long)]
+        older_than_days: i64,
+    },
+}
+
+/// Which detection settings a `hone config` change applies to
+#[derive(Args, Default)]
+pub struct ConfigScopeArgs {
+    /// Apply to one merchant instead of the whole household
+    #[arg(long, conflicts_with = "tag")]
+    pub merchant: Option<String>,
+    /// Apply to a tag and its children (e.g. "Utilities")
+    #[arg(long)]
+    pub tag: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Show detection settings and overrides
+    Show,
+
+    /// Set a detection setting
+    Set {
+        /// Setting name (e.g. price_increase_percent)
+        key: String,
+        /// New value
+        value: String,
+        #[command(flatten)]
+        scope: ConfigScopeArgs,
+    },
+
+    /// Clear a detection setting (falls back to the inherited value)
+    Unset {
+        /// Setting name
+        key: String,
+        #[command(flatten)]
+        scope: ConfigScopeArgs,
+    },
+
+    /// Remove all settings for a scope
+    Reset {
+        #[command(flatten)]
+        scope: ConfigScopeArgs,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RefundsAction {
+    /// List linked refunds
+    List {
+        /// Maximum number to show
+        #[arg(long, short = 'n', default_value = "20")]
+        limit: i64,
+    },
+
+    /// Link unmatched credits to their original purchases
+    Match,
+
+    /// Show purchases a credit may refund
+    Candidates {
+        /// Refund (credit) transaction ID
+        refund_id: i64,
+    },
+
+    /// Link a credit to its original purchase
+    Link {
+        /// Refund (credit) transaction ID
+        refund_id: i64,
+        /// Original purchase transaction ID
+        original_id: i64,
+        /// Returned split item on the purchase
+        #[arg(long)]
+        split: Option<i64>,
+        /// Portion refunded (defaults to the whole credit)
+        #[arg(long)]
+        amount: Option<f64>,
+    },
+
+    /// Unlink a refund (it won't be matched automatically again)
+    Unlink {
+        /// Refund (credit) transaction ID
+        refund_id: i64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WarrantiesAction {
+    /// List items under warranty or still returnable
+    List {
+        /// Match item, merchant, serial number or notes
+        search: Option<String>,
+        /// Include expired warranties
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Track a warranty or return window
+    Add {
+        #[command(flatten)]
+        args: Box<WarrantyAddArgs>,
+    },
+
+    /// Stop tracking a warranty
+    Remove {
+        /// Warranty ID
+        id: i64,
+    },
+}
+
+/// Warranty details for `warranties add`
+///
+/// Blank fields are filled from the split, receipt or transaction.
+#[derive(Args, Default)]
+pub struct WarrantyAddArgs {
+    /// Receipt the item was bought on
+    #[arg(long)]
+    pub receipt: Option<i64>,
+    /// Split item for the purchase
+    #[arg(long)]
+    pub split: Option<i64>,
+    /// Transaction for the purchase
+    #[arg(long)]
+    pub transaction: Option<i64>,
+    /// Item name (defaults to the split description)
+    #[arg(long)]
+    pub item: Option<String>,
+    /// Merchant (defaults to the receipt or transaction merchant)
+    #[arg(long)]
+    pub merchant: Option<String>,
+    /// Purchase date (YYYY-MM-DD)
+    #[arg(long)]
+    pub date: Option<String>,
+    /// Price paid
+    #[arg(long)]
+    pub price: Option<f64>,
+    /// Return window in days from the purchase date
+    #[arg(long)]
+    pub return_days: Option<i64>,
+    /// Last day to return the item (YYYY-MM-DD)
+    #[arg(long)]
+    pub return_by: Option<String>,
+    /// Warranty length in months from the purchase date
+    #[arg(long)]
+    pub warranty_months: Option<u32>,
+    /// Last day of warranty coverage (YYYY-MM-DD)
+    #[arg(long)]
+    pub warranty_until: Option<String>,
+    /// Serial number
+    #[arg(long)]
+    pub serial: Option<String>,
+    /// Supplementary receipt holding the warranty document
+    #[arg(long)]
+    pub document: Option<i64>,
+    /// Free-form notes
+    #[arg(long)]
+    pub notes: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum KeysAction {
+    /// List API keys
+    List {
+        /// Include revoked keys
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Create a key (the secret is shown once)
+    Create {
+        /// Key name (recorded in the audit log)
+        name: String,
+        /// Scope: read-only, import-only, mcp, admin
+        #[arg(long, short = 's')]
+        scope: String,
+        /// Expire the key after this many days (at most 3650)
+        #[arg(long)]
+        expires_days: Option<i64>,
+    },
+
+    /// Revoke a key by ID or name
+    Revoke {
+        /// Key ID or name
+        key: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum NotifyAction {
+    /// Show configured channels, routes and quiet hours
+    Channels,
+
+    /// Send a test message to a channel
+    Test {
+        /// Channel name
+        channel: String,
+    },
+
+    /// List recent deliveries
+    Deliveries {
+        /// Filter by status: pending, sent, failed
+        #[arg(long, short = 's')]
+        status: Option<String>,
+        /// Maximum number to show
+        #[arg(long, short = 'n', default_value = "20")]
+        limit: i64,
+    },
+
+    /// Re-queue a failed delivery
+    Retry {
+        /// Delivery ID
+        id: i64,
+    },
+
+    /// Route new alerts/insights and send due deliveries now
+    Run,
+}
+
 #[derive(Subcommand)]
 pub enum EntitiesAction {
     /// Add a new entity
@@ -630,9 +1050,9 @@ pub enum PromptsAction {
 
 #[derive(Subcommand)]
 pub enum ReceiptsAction {
-    /// Upload a receipt image (creates pending receipt for later matching)
+    /// Upload a receipt image, PDF or HTML page (creates pending receipt for later matching)
     Add {
-        /// Path to receipt image file
+        /// Path to receipt file (image, PDF, HTML or text)
         #[arg(short, long)]
         file: PathBuf,
 
@@ -672,6 +1092,21 @@ pub enum ReceiptsAction {
         #[arg(long)]
         reason: Option<String>,
     },
+
+    /// File receipts from email (mailboxes in mail.toml, or a Maildir/mbox)
+    Mail {
+        /// Config file (default: HONE_MAIL_CONFIG or ~/.local/share/hone/config/mail.toml)
+        #[arg(long)]
+        config: Option<PathBuf>,
+
+        /// Also read this Maildir folder
+        #[arg(long)]
+        maildir: Option<PathBuf>,
+
+        /// Also read this mbox file
+        #[arg(long)]
+        mbox: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -681,7 +1116,7 @@ pub enum TrainingAction {
 
     /// Export training data in JSONL format for fine-tuning
     Export {
-        /// Task to export: classify_merchant, normalize_merchant, classify_subscription
+        /// Task to export: classify_merchant, normalize_merchant, classify_subscription, suggest_entity, suggest_split, evaluate_receipt_match, parse_receipt
         #[arg(long)]
         task: String,
 
@@ -699,7 +1134,7 @@ pub enum TrainingAction {
 
     /// Create a new training experiment
     Create {
-        /// Task: classify_merchant, normalize_merchant, classify_subscription
+        /// Task: classify_merchant, normalize_merchant, classify_subscription, suggest_entity, suggest_split, evaluate_receipt_match, parse_receipt
         #[arg(long)]
         task: String,
 
@@ -729,9 +1164,13 @@ pub enum TrainingAction {
         #[arg(long)]
         id: i64,
 
-        /// Skip MLX training (just show instructions)
+        /// Trainer backend: mlx, llama_cpp (default: HONE_TRAINER, else mlx on macOS and llama_cpp elsewhere)
         #[arg(long)]
-        skip_mlx: bool,
+        trainer: Option<String>,
+
+        /// Skip training (just show instructions)
+        #[arg(long, alias = "skip-mlx")]
+        skip_training: bool,
     },
 
     /// Create Ollama model from trained adapter
@@ -0,0 +1,163 @@
+//! Audit log command implementations (list, verify, export, archive)
+
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveDate, Utc};
+use hone_core::db::Database;
+use hone_core::AuditFilter;
+
+use super::truncate;
+use crate::cli::AuditFilterArgs;
+
+fn build_filter(args: &AuditFilterArgs) -> Result<AuditFilter> {
+    let parse_date = |value: &Option<String>, flag: &str| {
+        value
+            .as_deref()
+            .map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d"))
+            .transpose()
+            .with_context(|| format!("Invalid --{} date format (use YYYY-MM-DD)", flag))
+    };
+
+    Ok(AuditFilter {
+        user_email: args.user.clone(),
+        action: args.action.clone(),
+        entity_type: args.entity_type.clone(),
+        entity_id: args.entity_id,
+        from: parse_date(&args.from, "from")?,
+        to: parse_date(&args.to, "to")?,
+        include_archived: args.archived,
+        ..Default::default()
+    })
+}
+
+/// List recent audit entries
+pub fn cmd_audit_list(db: &Database, args: &AuditFilterArgs, limit: i64) -> Result<()> {
+    let filter = AuditFilter {
+        limit: Some(limit.max(1)),
+        ..build_filter(args)?
+    };
+    let entries = db.query_audit_log(&filter)?;
+    let total = db.count_audit_log(&filter)?;
+
+    if entries.is_empty() {
+        println!("No audit entries found.");
+        return Ok(());
+    }
+
+    println!();
+    println!("📜 Audit Log ({} of {})", entries.len(), total);
+    println!("   ─────────────────────────────────────────────────────────────");
+
+    for entry in entries {
+        let entity = match (&entry.entity_type, entry.entity_id) {
+            (Some(t), Some(id)) => format!("{}:{}", t, id),
+            (Some(t), None) => t.clone(),
+            _ => "-".to_string(),
+        };
+        println!(
+            "   {:>6} │ {} │ {:24} │ {:18} │ {:16} │ {}",
+            entry.id,
+            entry.timestamp,
+            truncate(&entry.user_email, 24),
+            truncate(&entry.action, 18),
+            truncate(&entity, 16),
+            truncate(entry.details.as_deref().unwrap_or(""), 40)
+        );
+    }
+
+    Ok(())
+}
+
+/// Verify the audit log hash chain
+pub fn cmd_audit_verify(db: &Database) -> Result<()> {
+    let result = db.verify_audit_log()?;
+
+    if result.valid {
+        println!(
+            "✅ Audit log intact: {} entries verified ({} archived)",
+            result.entries_checked, result.archived_entries
+        );
+        if let Some(head) = &result.head_hash {
+            println!("   Head hash: {}", head);
+            println!("   Record this hash elsewhere to detect removal of the newest entries.");
+        }
+        Ok(())
+    } else {
+        println!(
+            "❌ Audit log chain is broken after {} valid entries",
+            result.entries_checked
+        );
+        if let Some(error) = &result.error {
+            println!("   {}", error);
+        }
+        anyhow::bail!("Audit log verification failed")
+    }
+}
+
+/// Export audit entries to CSV or JSON
+pub fn cmd_audit_export(
+    db: &Database,
+    args: &AuditFilterArgs,
+    format: &str,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let filter = build_filter(args)?;
+
+    let (content, count) = match format {
+        "csv" => {
+            let csv = db.export_audit_log_csv(&filter)?;
+            let count = csv.lines().count().saturating_sub(1);
+            (csv, count)
+        }
+        "json" => {
+            let mut entries = db.query_audit_log(&filter)?;
+            entries.reverse();
+            (serde_json::to_string_pretty(&entries)?, entries.len())
+        }
+        _ => anyhow::bail!("Invalid format '{}' (use csv or json)", format),
+    };
+
+    match output {
+        Some(path) => {
+            let mut file = File::create(&path)
+                .with_context(|| format!("Failed to create output file: {}", path.display()))?;
+            file.write_all(content.as_bytes())?;
+            println!("✅ Exported {} audit entries to {}", count, path.display());
+        }
+        None => print!("{}", content),
+    }
+
+    Ok(())
+}
+
+/// Move old audit entries to the archive table
+pub fn cmd_audit_archive(db: &Database, older_than_days: i64) -> Result<()> {
+    if older_than_days < 1 {
+        anyhow::bail!("--older-than-days must be at least 1");
+    }
+
+    let cutoff = Duration::try_days(older_than_days)
+        .and_then(|age| Utc::now().checked_sub_signed(age))
+        .ok_or_else(|| anyhow::anyhow!("--older-than-days {} is out of range", older_than_days))?;
+    let archived = db.archive_audit_log(cutoff)?;
+    if archived > 0 {
+        db.log_audit(
+            "cli",
+            "archive",
+            Some("audit_log"),
+            None,
+            Some(&format!(
+                "older_than_days={}, archived={}",
+                older_than_days, archived
+            )),
+        )?;
+    }
+    println!(
+        "✓ Archived {} audit entries older than {} days",
+        archived, older_than_days
+    );
+    Ok(())
+}
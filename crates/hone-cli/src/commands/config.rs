@@ -0,0 +1,135 @@
+//! Detection settings commands (show, set, unset, reset)
+
+use anyhow::{Context, Result};
+use hone_core::db::{Database, DetectionScope};
+use hone_core::detect::{DetectionConfig, DetectionOverrides};
+
+use crate::cli::ConfigScopeArgs;
+
+/// Resolve --merchant / --tag into a settings scope
+fn resolve_scope(db: &Database, scope: &ConfigScopeArgs) -> Result<DetectionScope> {
+    if let Some(merchant) = &scope.merchant {
+        return Ok(DetectionScope::Merchant(merchant.clone()));
+    }
+    if let Some(path) = &scope.tag {
+        let tag = db
+            .get_tag_by_path(path)?
+            .with_context(|| format!("Tag not found: {}", path))?;
+        return Ok(DetectionScope::Tag(tag.id));
+    }
+    Ok(DetectionScope::Global)
+}
+
+fn scope_label(scope: &ConfigScopeArgs) -> String {
+    match (&scope.merchant, &scope.tag) {
+        (Some(merchant), _) => format!("merchant '{}'", merchant),
+        (_, Some(tag)) => format!("tag '{}'", tag),
+        _ => "household".to_string(),
+    }
+}
+
+fn print_overrides(overrides: &DetectionOverrides) {
+    if let Ok(serde_json::Value::Object(map)) = serde_json::to_value(overrides) {
+        for (key, value) in map {
+            println!("      {:34} {}", key, value);
+        }
+    }
+}
+
+/// Show effective settings and all overrides
+pub fn cmd_config_show(db: &Database) -> Result<()> {
+    let settings = db.get_detection_settings()?;
+    let defaults = serde_json::to_value(DetectionConfig::default())?;
+    let effective = serde_json::to_value(settings.config())?;
+
+    println!();
+    println!("⚙️  Detection Settings");
+    println!("   ─────────────────────────────────────────────────────────────");
+    for key in DetectionOverrides::KEYS {
+        let value = &effective[*key];
+        if *value == defaults[*key] {
+            println!("   {:36} {}", key, value);
+        } else {
+            println!("   {:36} {} (default {})", key, value, defaults[*key]);
+        }
+    }
+
+    if !settings.tags.is_empty() {
+        println!();
+        println!("   Tag overrides (apply to child tags too):");
+        for tag in &settings.tags {
+            println!("   🏷️  {}", tag.tag);
+            print_overrides(&tag.overrides);
+        }
+    }
+    if !settings.merchants.is_empty() {
+        println!();
+        println!("   Merchant overrides:");
+        for merchant in &settings.merchants {
+            println!("   🏪 {}", merchant.merchant);
+            print_overrides(&merchant.overrides);
+        }
+    }
+    println!();
+    Ok(())
+}
+
+/// Set one setting for a scope
+pub fn cmd_config_set(
+    db: &Database,
+    scope: &ConfigScopeArgs,
+    key: &str,
+    value: &str,
+) -> Result<()> {
+    let target = resolve_scope(db, scope)?;
+    let mut overrides = db.get_detection_overrides(&target)?;
+    overrides.set(key, value)?;
+    db.set_detection_overrides(&target, &overrides, "cli")?;
+    db.log_audit(
+        "cli",
+        "update",
+        Some("detection_settings"),
+        None,
+        Some(&format!("{}: {}={}", scope_label(scope), key, value)),
+    )?;
+    println!("✅ Set {} = {} for {}", key, value, scope_label(scope));
+    Ok(())
+}
+
+/// Clear one setting for a scope
+pub fn cmd_config_unset(db: &Database, scope: &ConfigScopeArgs, key: &str) -> Result<()> {
+    let target = resolve_scope(db, scope)?;
+    let mut overrides = db.get_detection_overrides(&target)?;
+    if !overrides.unset(key)? {
+        println!("{} isn't set for {}", key, scope_label(scope));
+        return Ok(());
+    }
+    db.set_detection_overrides(&target, &overrides, "cli")?;
+    db.log_audit(
+        "cli",
+        "update",
+        Some("detection_settings"),
+        None,
+        Some(&format!("{}: unset {}", scope_label(scope), key)),
+    )?;
+    println!("✅ Cleared {} for {}", key, scope_label(scope));
+    Ok(())
+}
+
+/// Remove all settings for a scope
+pub fn cmd_config_reset(db: &Database, scope: &ConfigScopeArgs) -> Result<()> {
+    let target = resolve_scope(db, scope)?;
+    if !db.delete_detection_overrides(&target)? {
+        println!("No settings saved for {}", scope_label(scope));
+        return Ok(());
+    }
+    db.log_audit(
+        "cli",
+        "delete",
+        Some("detection_settings"),
+        None,
+        Some(&scope_label(scope)),
+    )?;
+    println!("✅ Reset detection settings for {}", scope_label(scope));
+    Ok(())
+}
@@ -109,9 +109,22 @@ pub async fn cmd_detect(db_path: &Path, kind: &str, no_encrypt: bool) -> Result<
         results.price_increases_detected
     );
     println!("   👯 Duplicate services: {}", results.duplicates_detected);
+    println!("   ⏳ Trials ending: {}", results.trials_detected);
+    println!(
+        "   🔍 Unusual transactions: {}",
+        results.transaction_anomalies_detected
+    );
+    println!(
+        "   🧾 Return windows and warranties closing: {}",
+        results.warranty_reminders
+    );
 
-    let total =
-        results.zombies_detected + results.price_increases_detected + results.duplicates_detected;
+    let total = results.zombies_detected
+        + results.price_increases_detected
+        + results.duplicates_detected
+        + results.trials_detected
+        + results.transaction_anomalies_detected
+        + results.warranty_reminders;
     if total > 0 {
         println!();
         println!(
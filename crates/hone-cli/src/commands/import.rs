@@ -170,10 +170,28 @@ pub async fn cmd_import(
         if results.duplicates_detected > 0 {
             println!("   👯 Duplicate services: {}", results.duplicates_detected);
         }
+        if results.trials_detected > 0 {
+            println!("   ⏳ Trials ending: {}", results.trials_detected);
+        }
+        if results.transaction_anomalies_detected > 0 {
+            println!(
+                "   🔍 Unusual transactions: {}",
+                results.transaction_anomalies_detected
+            );
+        }
+        if results.warranty_reminders > 0 {
+            println!(
+                "   🧾 Return windows and warranties closing: {}",
+                results.warranty_reminders
+            );
+        }
 
         let total = results.zombies_detected
             + results.price_increases_detected
-            + results.duplicates_detected;
+            + results.duplicates_detected
+            + results.trials_detected
+            + results.transaction_anomalies_detected
+            + results.warranty_reminders;
         if total > 0 {
             println!();
             println!(
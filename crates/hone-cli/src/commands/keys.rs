@@ -0,0 +1,102 @@
+//! Scoped API key command implementations (list, create, revoke)
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use hone_core::db::{Database, MAX_API_KEY_DAYS};
+use hone_core::models::ApiKeyScope;
+
+use super::truncate;
+
+/// List API keys
+pub fn cmd_keys_list(db: &Database, include_revoked: bool) -> Result<()> {
+    let keys = db.list_api_keys(include_revoked)?;
+
+    if keys.is_empty() {
+        println!("No API keys. Create one with:");
+        println!("  hone keys create <name> --scope read-only");
+        return Ok(());
+    }
+
+    println!();
+    println!("🔑 API Keys");
+    println!("   ─────────────────────────────────────────────────────────────");
+    println!(
+        "   {:>4} │ {:20} │ {:13} │ {:11} │ {:16} │ {:16} │ Status",
+        "ID", "Name", "Prefix", "Scope", "Expires", "Last Used"
+    );
+
+    let format_time = |t: Option<chrono::DateTime<Utc>>, none: &str| {
+        t.map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| none.to_string())
+    };
+    for key in keys {
+        let status = if key.revoked_at.is_some() {
+            "revoked"
+        } else if key.expires_at.is_some_and(|t| t <= Utc::now()) {
+            "expired"
+        } else {
+            "active"
+        };
+        println!(
+            "   {:>4} │ {:20} │ {:13} │ {:11} │ {:16} │ {:16} │ {}",
+            key.id,
+            truncate(&key.name, 20),
+            key.prefix,
+            key.scope.as_str(),
+            format_time(key.expires_at, "never"),
+            format_time(key.last_used_at, "never"),
+            status
+        );
+    }
+
+    Ok(())
+}
+
+/// Create an API key and print its secret
+pub fn cmd_keys_create(
+    db: &Database,
+    name: &str,
+    scope: &str,
+    expires_days: Option<i64>,
+) -> Result<()> {
+    let scope: ApiKeyScope = scope.parse().map_err(|e: String| {
+        anyhow::anyhow!("{} (valid scopes: read-only, import-only, mcp, admin)", e)
+    })?;
+    let expires_at = match expires_days {
+        Some(days) if !(1..=MAX_API_KEY_DAYS).contains(&days) => {
+            anyhow::bail!("--expires-days must be between 1 and {}", MAX_API_KEY_DAYS)
+        }
+        Some(days) => Some(Utc::now() + Duration::days(days)),
+        None => None,
+    };
+
+    let (key, secret) = db.create_api_key(name, scope, expires_at, Some("cli"))?;
+
+    println!("✓ Created API key {} ({} scope)", key.name, key.scope);
+    if let Some(expires_at) = key.expires_at {
+        println!("  Expires: {}", expires_at.format("%Y-%m-%d %H:%M"));
+    }
+    println!();
+    println!("  {}", secret);
+    println!();
+    println!("  Copy it now - it won't be shown again.");
+    println!("  Send it as: Authorization: Bearer <key>");
+    Ok(())
+}
+
+/// Revoke an API key by ID or name
+pub fn cmd_keys_revoke(db: &Database, key: &str) -> Result<()> {
+    let found = match key.parse::<i64>() {
+        Ok(id) => db.get_api_key(id)?,
+        Err(_) => db.get_api_key_by_name(key)?,
+    };
+    let Some(found) = found else {
+        anyhow::bail!("API key not found: {}", key);
+    };
+
+    if !db.revoke_api_key(found.id)? {
+        anyhow::bail!("API key {} is already revoked", found.name);
+    }
+    println!("✓ Revoked API key {}", found.name);
+    Ok(())
+}
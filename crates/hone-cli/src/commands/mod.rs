@@ -1,29 +1,42 @@
 //! CLI command implementations
 //!
 //! Commands are organized by domain:
+//! - `audit` - Audit log commands (list, verify, export, archive)
 //! - `backup` - Backup management commands (create, list, restore, prune)
+//! - `config` - Detection settings commands (show, set, unset, reset)
 //! - `core` - Core commands (init, detect) and shared utilities (open_db)
 //! - `entities` - Entity management commands (people, pets, vehicles, properties)
 //! - `import` - Import/export commands (CSV import, transaction export, full backup)
+//! - `keys` - Scoped API key commands (list, create, revoke)
+//! - `notify` - Notification commands (channels, test, deliveries, retry, run)
 //! - `ollama` - Ollama AI commands (test, normalize)
 //! - `prompts` - Prompt library management commands
 //! - `rebuild` - Re-process transactions with current models/rules
 //! - `receipts` - Receipt workflow commands
+//! - `refunds` - Refund matching commands (list, match, link, unlink)
 //! - `reports` - Report generation commands
 //! - `serve` - Web server command
 //! - `status` - Status/dashboard/accounts/alerts/reset commands
 //! - `subscriptions` - Subscription management commands
 //! - `tags` - Tag management commands
 //! - `transactions` - Transaction commands (list, archive, unarchive)
+//! - `users` - Household user commands (users, roles, account visibility)
+//! - `warranties` - Warranty and return-window commands (list, add, remove)
+//! - `watch` - Import folder watcher
 
+pub mod audit;
 pub mod backup;
+pub mod config;
 pub mod core;
 pub mod entities;
 pub mod import;
+pub mod keys;
+pub mod notify;
 pub mod ollama;
 pub mod prompts;
 pub mod rebuild;
 pub mod receipts;
+pub mod refunds;
 pub mod reports;
 pub mod serve;
 pub mod status;
@@ -31,16 +44,24 @@ pub mod subscriptions;
 pub mod tags;
 pub mod training;
 pub mod transactions;
+pub mod users;
+pub mod warranties;
+pub mod watch;
 
 // Re-export command functions for main.rs
+pub use audit::*;
 pub use backup::*;
+pub use config::*;
 pub use core::*;
 pub use entities::*;
 pub use import::*;
+pub use keys::*;
+pub use notify::*;
 pub use ollama::*;
 pub use prompts::*;
 pub use rebuild::*;
 pub use receipts::*;
+pub use refunds::*;
 pub use reports::*;
 pub use serve::*;
 pub use status::*;
@@ -48,6 +69,9 @@ pub use subscriptions::*;
 pub use tags::*;
 pub use training::*;
 pub use transactions::*;
+pub use users::*;
+pub use warranties::*;
+pub use watch::*;
 
 /// Truncate a string to a maximum length, adding "..." if truncated
 pub fn truncate(s: &str, max: usize) -> String {
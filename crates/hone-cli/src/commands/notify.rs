@@ -0,0 +1,160 @@
+//! Notification command implementations (channels, test, deliveries, retry, run)
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use hone_core::db::Database;
+use hone_core::models::DeliveryStatus;
+use hone_core::notify::{default_config_path, Notifier, NotifyConfig};
+
+use super::truncate;
+
+/// Load the notifications config or explain where it goes
+fn load_config() -> Result<NotifyConfig> {
+    NotifyConfig::load(None)?.ok_or_else(|| {
+        let path = std::env::var("HONE_NOTIFY_CONFIG")
+            .ok()
+            .or_else(|| default_config_path().map(|p| p.display().to_string()));
+        anyhow::anyhow!(
+            "No notifications config found. Create {} (or set HONE_NOTIFY_CONFIG)",
+            path.unwrap_or_else(|| "notifications.toml".to_string())
+        )
+    })
+}
+
+/// Show configured channels and routes
+pub fn cmd_notify_channels() -> Result<()> {
+    let config = load_config()?;
+
+    println!();
+    println!("🔔 Notification Channels");
+    println!("   ─────────────────────────────────────────────");
+    if config.channels.is_empty() {
+        println!("   (none)");
+    }
+    for channel in &config.channels {
+        println!("   {:20} │ {}", channel.name, channel.kind_name());
+    }
+
+    println!();
+    println!("   Routes:");
+    if config.routes.is_empty() {
+        println!("   (none - nothing will be sent)");
+    }
+    for route in &config.routes {
+        let mut flags = Vec::new();
+        if route.digest {
+            flags.push("digest".to_string());
+        }
+        if route.bypass_quiet_hours {
+            flags.push("bypasses quiet hours".to_string());
+        }
+        if let Some(min) = route.min_severity {
+            flags.push(format!("≥ {}", min));
+        }
+        println!(
+            "   {} → {}{}",
+            route.events.join(", "),
+            route.channels.join(", "),
+            if flags.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", flags.join(", "))
+            }
+        );
+    }
+
+    println!();
+    if let Some(quiet) = &config.quiet_hours {
+        println!("   Quiet hours: {} - {}", quiet.start, quiet.end);
+    }
+    println!("   Digest sent at: {:02}:00", config.digest_hour);
+    println!("   Max attempts: {}", config.max_attempts);
+
+    Ok(())
+}
+
+/// Send a test message to a channel
+pub async fn cmd_notify_test(db: &Database, channel: &str) -> Result<()> {
+    let config = load_config()?;
+    if config.channel(channel).is_none() {
+        anyhow::bail!("Unknown channel: {}", channel);
+    }
+
+    let delivery = Notifier::new(db.clone(), config).send_test(channel).await?;
+    match delivery.status {
+        DeliveryStatus::Sent => println!("✓ Test notification sent to {}", channel),
+        _ => anyhow::bail!(
+            "Test notification to {} failed: {}",
+            channel,
+            delivery.last_error.unwrap_or_default()
+        ),
+    }
+    Ok(())
+}
+
+/// List recent deliveries
+pub fn cmd_notify_deliveries(db: &Database, status: Option<&str>, limit: i64) -> Result<()> {
+    let status: Option<DeliveryStatus> = status
+        .map(|s| s.parse())
+        .transpose()
+        .map_err(|e: String| anyhow::anyhow!("{} (valid: pending, sent, failed)", e))?;
+    let deliveries = db.list_notification_deliveries(status, limit)?;
+
+    if deliveries.is_empty() {
+        println!("No notification deliveries.");
+        return Ok(());
+    }
+
+    println!();
+    println!("📬 Notification Deliveries");
+    println!("   ─────────────────────────────────────────────────────────────");
+    println!(
+        "   {:>5} │ {:12} │ {:24} │ {:30} │ {:7} │ {:>3} │ Created",
+        "ID", "Channel", "Event", "Title", "Status", "Try"
+    );
+    for d in deliveries {
+        println!(
+            "   {:>5} │ {:12} │ {:24} │ {:30} │ {:7} │ {:>3} │ {}",
+            d.id,
+            truncate(&d.channel, 12),
+            truncate(&d.event, 24),
+            truncate(&d.title, 30),
+            d.status.as_str(),
+            d.attempts,
+            d.created_at.format("%Y-%m-%d %H:%M")
+        );
+        if let Some(error) = &d.last_error {
+            if d.status != DeliveryStatus::Sent {
+                println!("          └ {}", truncate(error, 80));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Re-queue a failed delivery
+pub fn cmd_notify_retry(db: &Database, id: i64) -> Result<()> {
+    db.get_notification_delivery(id)?
+        .with_context(|| format!("Delivery {} not found", id))?;
+    if !db.retry_notification_delivery(id)? {
+        anyhow::bail!(
+            "Delivery {} has not failed; only failed deliveries can be retried",
+            id
+        );
+    }
+    db.log_audit("cli", "retry", Some("notification"), Some(id), None)?;
+    println!("✓ Delivery {} queued for retry", id);
+    Ok(())
+}
+
+/// Run the dispatcher once
+pub async fn cmd_notify_run(db: &Database) -> Result<()> {
+    let notifier = Notifier::new(db.clone(), load_config()?);
+    let summary = notifier.run_once(Utc::now()).await?;
+    println!(
+        "✓ {} queued, {} sent, {} failed, {} held for quiet hours",
+        summary.queued, summary.sent, summary.failed, summary.deferred
+    );
+    Ok(())
+}
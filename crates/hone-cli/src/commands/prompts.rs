@@ -57,6 +57,7 @@ pub fn cmd_prompts_show(prompt_id: &str) -> Result<()> {
         "normalize_merchant" => PromptId::NormalizeMerchant,
         "normalize_merchant_with_context" => PromptId::NormalizeMerchantWithContext,
         "parse_receipt" => PromptId::ParseReceipt,
+        "parse_receipt_text" => PromptId::ParseReceiptText,
         "suggest_entity" => PromptId::SuggestEntity,
         "classify_subscription" => PromptId::ClassifySubscription,
         "suggest_split" => PromptId::SuggestSplit,
@@ -3,8 +3,11 @@
 use std::path::Path;
 
 use anyhow::{anyhow, Context, Result};
-use hone_core::ai::{AIBackend, AIClient};
+use hone_core::ai::{parse_receipt_document, AIClient, ReceiptFormat};
 use hone_core::db::Database;
+use hone_core::mail::{
+    default_config_path, DocumentOutcome, MailConfig, MailIngester, MailboxKind,
+};
 use hone_core::models::{NewReceipt, ReceiptRole, ReceiptStatus};
 use sha2::{Digest, Sha256};
 
@@ -57,7 +60,7 @@ pub fn cmd_receipts_list(db: &Database, status: &str) -> Result<()> {
     Ok(())
 }
 
-/// Upload a receipt image
+/// Upload a receipt (image, PDF, HTML or text)
 pub async fn cmd_receipts_add(db: &Database, file: &Path) -> Result<()> {
     // Verify file exists
     if !file.exists() {
@@ -87,7 +90,11 @@ pub async fn cmd_receipts_add(db: &Database, file: &Path) -> Result<()> {
 
     // Generate filename
     let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-    let extension = file.extension().and_then(|e| e.to_str()).unwrap_or("jpg");
+    let format = ReceiptFormat::detect(&image_data);
+    let extension = file
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_else(|| format.extension(&image_data));
     let filename = format!("receipt_pending_{}.{}", timestamp, extension);
     let image_path = receipts_dir.join(&filename);
 
@@ -100,9 +107,11 @@ pub async fn cmd_receipts_add(db: &Database, file: &Path) -> Result<()> {
     println!("Uploading receipt...");
 
     let parsed = if let Some(ai) = AIClient::from_env() {
-        println!("Parsing receipt with AI...");
-        match ai.parse_receipt(&image_data, None).await {
-            Ok(p) => {
+        println!("Parsing {} receipt with AI...", format.as_str());
+        match parse_receipt_document(&ai, db, &image_data, format, None).await {
+            Ok(parse) => {
+                let p = parse.receipt;
+                println!("  Parsed:   via {} ({})", parse.path.as_str(), parse.model);
                 println!("  Merchant: {}", p.merchant.as_deref().unwrap_or("Unknown"));
                 println!("  Date:     {}", p.date.as_deref().unwrap_or("Unknown"));
                 println!("  Total:    ${:.2}", p.total.unwrap_or(0.0));
@@ -243,3 +252,85 @@ pub fn cmd_receipts_dismiss(db: &Database, receipt_id: i64) -> Result<()> {
 
     Ok(())
 }
+
+/// File receipts from the configured mailboxes (plus any given on the command line)
+pub async fn cmd_receipts_mail(
+    db: &Database,
+    config_path: Option<&Path>,
+    maildir: Option<&Path>,
+    mbox: Option<&Path>,
+) -> Result<()> {
+    let mut config = MailConfig::load(config_path)
+        .context("Failed to load mail config")?
+        .unwrap_or_default();
+    if let Some(path) = maildir {
+        config = config.with_mailbox(
+            path.display().to_string(),
+            MailboxKind::Maildir {
+                path: path.to_path_buf(),
+            },
+        );
+    }
+    if let Some(path) = mbox {
+        config = config.with_mailbox(
+            path.display().to_string(),
+            MailboxKind::Mbox {
+                path: path.to_path_buf(),
+            },
+        );
+    }
+    if config.mailboxes.is_empty() {
+        let path = config_path
+            .map(|p| p.display().to_string())
+            .or_else(|| std::env::var("HONE_MAIL_CONFIG").ok())
+            .or_else(|| default_config_path().map(|p| p.display().to_string()));
+        return Err(anyhow!(
+            "No mailboxes to read. Pass --maildir or --mbox, or add [[mailboxes]] to {}",
+            path.unwrap_or_else(|| "mail.toml".to_string())
+        ));
+    }
+
+    let ai = AIClient::from_env();
+    if ai.is_none() {
+        println!("  ℹ️  AI backend not configured - receipts will be saved without parsing");
+    }
+    println!("📬 Reading {} mailbox(es)...", config.mailboxes.len());
+
+    let ingester = MailIngester::new(db.clone(), config, ai);
+    let scan = ingester.scan(chrono::Local::now().date_naive()).await?;
+
+    for message in &scan.messages {
+        let subject = message.subject.as_deref().unwrap_or("(no subject)");
+        for document in &message.documents {
+            match &document.outcome {
+                DocumentOutcome::Created {
+                    receipt_id, parsed, ..
+                } => println!(
+                    "   ✅ #{} {} ({}{})",
+                    receipt_id,
+                    subject,
+                    document.kind.as_str(),
+                    if *parsed { "" } else { ", unparsed" }
+                ),
+                DocumentOutcome::Duplicate { receipt_id } => {
+                    println!("   ↩️  {} already filed as #{}", subject, receipt_id)
+                }
+                DocumentOutcome::NotReceipt => {}
+            }
+        }
+    }
+    for error in &scan.errors {
+        println!("   ⚠️  {}: {}", error.mailbox, error.error);
+    }
+
+    println!(
+        "\n✓ {} receipt(s) filed from {} new email(s)",
+        scan.created(),
+        scan.messages.len()
+    );
+    println!(
+        "  Auto-matched {} of {} pending receipt(s)",
+        scan.matched, scan.checked
+    );
+    Ok(())
+}
@@ -0,0 +1,134 @@
+//! Refund matching commands (list, match, candidates, link, unlink)
+
+use anyhow::Result;
+use hone_core::db::Database;
+
+use super::truncate;
+
+/// List refunds linked to their original purchases
+pub fn cmd_refunds_list(db: &Database, limit: i64) -> Result<()> {
+    let matches = db.list_refund_matches(limit, 0)?;
+
+    if matches.is_empty() {
+        println!("No linked refunds. Link unmatched credits with:");
+        println!("  hone refunds match");
+        return Ok(());
+    }
+
+    println!();
+    println!("↩️  Refunds");
+    println!("   ─────────────────────────────────────────────────────────────");
+    println!(
+        "   {:>6} │ {:10} │ {:>9} │ {:28} │ {:>6} │ {:10} │ Source",
+        "Refund", "Date", "Amount", "Original purchase", "ID", "Bought"
+    );
+    for m in matches {
+        println!(
+            "   {:>6} │ {:10} │ {:>9.2} │ {:28} │ {:>6} │ {:10} │ {}",
+            m.refund.id,
+            m.refund.date,
+            m.refund_match.amount,
+            truncate(&m.original.description, 28),
+            m.original.id,
+            m.original.date,
+            m.refund_match.source.as_str()
+        );
+        if let Some(item) = &m.split_description {
+            println!("          └ returned item: {}", truncate(item, 60));
+        }
+    }
+
+    Ok(())
+}
+
+/// Link unmatched credits to their original purchases
+pub fn cmd_refunds_match(db: &Database) -> Result<()> {
+    let (matched, checked) = db.auto_match_refunds()?;
+    db.log_audit(
+        "cli",
+        "auto_match",
+        Some("refunds"),
+        None,
+        Some(&format!("matched={}, checked={}", matched, checked)),
+    )?;
+    println!(
+        "✓ Linked {} of {} unmatched credits to their purchases",
+        matched, checked
+    );
+    Ok(())
+}
+
+/// Show purchases a credit may refund
+pub fn cmd_refunds_candidates(db: &Database, refund_id: i64) -> Result<()> {
+    let candidates = db.find_refund_candidates(refund_id)?;
+
+    if candidates.is_empty() {
+        println!(
+            "No purchases from the same merchant in the last {} days cover this credit.",
+            hone_core::db::REFUND_WINDOW_DAYS
+        );
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "   {:>6} │ {:10} │ {:30} │ {:>9} │ Score",
+        "ID", "Date", "Description", "Left"
+    );
+    for c in candidates {
+        println!(
+            "   {:>6} │ {:10} │ {:30} │ {:>9.2} │ {:.2}",
+            c.transaction.id,
+            c.transaction.date,
+            truncate(&c.transaction.description, 30),
+            c.remaining,
+            c.score
+        );
+        if let Some(split) = &c.split {
+            println!(
+                "          └ item {}: {} ({:.2})",
+                split.id,
+                split.description.as_deref().unwrap_or("(no description)"),
+                split.amount
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Link a credit to its original purchase
+pub fn cmd_refunds_link(
+    db: &Database,
+    refund_id: i64,
+    original_id: i64,
+    split_id: Option<i64>,
+    amount: Option<f64>,
+) -> Result<()> {
+    let m = db.link_refund(refund_id, original_id, split_id, amount)?;
+    db.log_audit(
+        "cli",
+        "link",
+        Some("refund"),
+        Some(refund_id),
+        Some(&format!(
+            "original_transaction_id={}, split_id={:?}, amount={:.2}",
+            m.original_transaction_id, m.split_id, m.amount
+        )),
+    )?;
+    println!(
+        "✓ Linked refund {} (${:.2}) to purchase {}",
+        refund_id, m.amount, original_id
+    );
+    Ok(())
+}
+
+/// Unlink a refund
+pub fn cmd_refunds_unlink(db: &Database, refund_id: i64) -> Result<()> {
+    if !db.unlink_refund(refund_id)? {
+        anyhow::bail!("Transaction {} is not a linked refund", refund_id);
+    }
+    db.log_audit("cli", "unlink", Some("refund"), Some(refund_id), None)?;
+    println!("✓ Unlinked refund {}", refund_id);
+    Ok(())
+}
@@ -3,7 +3,7 @@
 use anyhow::{Context, Result};
 use chrono::{Datelike, NaiveDate, Utc};
 use hone_core::db::Database;
-use hone_core::models::Granularity;
+use hone_core::models::{CancellationStatus, Granularity};
 
 use super::truncate;
 
@@ -76,8 +76,9 @@ pub fn cmd_report_spending(
     to: NaiveDate,
     tag_filter: Option<&str>,
     expand: bool,
+    net_refunds: bool,
 ) -> Result<()> {
-    let summary = db.get_spending_summary(from, to, tag_filter, expand, None, None)?;
+    let summary = db.get_spending_summary(from, to, tag_filter, expand, None, None, net_refunds)?;
 
     println!();
     println!("📊 Spending Summary");
@@ -138,8 +139,10 @@ pub fn cmd_report_trends(
     to: NaiveDate,
     granularity: Granularity,
     tag_filter: Option<&str>,
+    net_refunds: bool,
 ) -> Result<()> {
-    let report = db.get_spending_trends(from, to, granularity, tag_filter, None, None)?;
+    let report =
+        db.get_spending_trends(from, to, granularity, tag_filter, None, None, net_refunds)?;
 
     println!();
     println!("📈 Spending Trends ({})", granularity.as_str());
@@ -305,13 +308,19 @@ pub fn cmd_report_savings(db: &Database) -> Result<()> {
         "   Total Saved: ${:.2} (capped at 12 months per subscription)",
         report.total_savings
     );
+    println!(
+        "   Confirmed: ${:.2} from {} verified cancellation(s)",
+        report.confirmed_savings, report.confirmed_count
+    );
     println!();
 
     println!(
-        "   {:20} │ {:>8} │ {:>12} │ {:>8} │ {:>10}",
-        "Merchant", "$/mo", "Cancelled", "Months", "Saved"
+        "   {:20} │ {:>8} │ {:>12} │ {:>8} │ {:>8} │ {:>10} │ Verified",
+        "Merchant", "$/mo", "Final charge", "Months", "Refund", "Saved"
+    );
+    println!(
+        "   ─────────────────────┼──────────┼──────────────┼──────────┼──────────┼────────────┼─────────"
     );
-    println!("   ─────────────────────┼──────────┼──────────────┼──────────┼────────────");
 
     for sub in &report.cancelled {
         let months_str = if sub.months_remaining > 0 {
@@ -323,13 +332,21 @@ pub fn cmd_report_savings(db: &Database) -> Result<()> {
         } else {
             format!("{} ✓", sub.months_counted)
         };
+        let verified = match sub.verification {
+            Some(CancellationStatus::Confirmed) => "✅",
+            Some(CancellationStatus::Pending) => "⏳",
+            Some(CancellationStatus::ChargedAfter) => "🚨",
+            None => "-",
+        };
         println!(
-            "   {:20} │ {:>8.2} │ {:>12} │ {:>8} │ {:>10.2}",
+            "   {:20} │ {:>8.2} │ {:>12} │ {:>8} │ {:>8.2} │ {:>10.2} │ {}",
             truncate(&sub.merchant, 20),
             sub.monthly_amount,
-            sub.cancelled_at,
+            sub.final_charge,
             months_str,
-            sub.savings
+            sub.refund,
+            sub.savings,
+            verified
         );
     }
 
@@ -4,8 +4,11 @@ use std::path::Path;
 
 use anyhow::{Context, Result};
 
+use hone_core::models::UserRole;
+
 use super::open_db;
 
+#[allow(clippy::too_many_arguments)]
 pub async fn cmd_serve(
     db_path: &Path,
     host: &str,
@@ -14,6 +17,7 @@ pub async fn cmd_serve(
     no_encrypt: bool,
     static_dir: Option<&Path>,
     mcp_port: Option<u16>,
+    watch_dir: Option<&Path>,
 ) -> Result<()> {
     println!("🚀 Starting Hone web server...");
     println!("   Database: {}", db_path.display());
@@ -25,13 +29,17 @@ pub async fn cmd_serve(
         println!("   MCP server: http://{}:{}/mcp", host, mcp);
     }
 
-    // Parse API keys from environment (comma-separated)
+    // Parse API keys from environment (comma-separated, each `key[:email][:role]`)
     let api_keys: Vec<String> = std::env::var("HONE_API_KEYS")
         .unwrap_or_default()
         .split(',')
         .map(|s| s.trim().to_string())
         .filter(|s| !s.is_empty())
         .collect();
+    for entry in &api_keys {
+        hone_server::ApiKeyEntry::parse(entry)
+            .map_err(|e| anyhow::anyhow!("HONE_API_KEYS: {}", e))?;
+    }
 
     // Parse Cloudflare Access JWT configuration
     let cf_team_name = std::env::var("CF_TEAM_NAME").ok().filter(|s| !s.is_empty());
@@ -41,11 +49,38 @@ pub async fn cmd_serve(
     // Parse trusted networks (for local network access without auth)
     let trusted_networks_str = std::env::var("HONE_TRUSTED_NETWORKS").unwrap_or_default();
     let trusted_networks = hone_server::parse_trusted_networks(&trusted_networks_str);
+    let trusted_network_role: UserRole = match std::env::var("HONE_TRUSTED_NETWORK_ROLE") {
+        Ok(role) if !role.trim().is_empty() => role
+            .parse()
+            .map_err(|e| anyhow::anyhow!("HONE_TRUSTED_NETWORK_ROLE: {}", e))?,
+        _ => UserRole::Owner,
+    };
 
     // Parse trusted proxies (for extracting real client IP behind reverse proxies)
     let trusted_proxies_str = std::env::var("HONE_TRUSTED_PROXIES").unwrap_or_default();
     let trusted_proxies = hone_server::parse_trusted_networks(&trusted_proxies_str);
 
+    // Built-in password/passkey logins (HONE_LOCAL_AUTH=true)
+    let local_auth = hone_server::LocalAuthConfig::from_env();
+
+    // Prometheus endpoint (HONE_METRICS=true), with its own access control
+    let metrics = hone_server::MetricsConfig::from_env();
+
+    // Outbound notifications (notifications.toml or HONE_NOTIFY_CONFIG)
+    let notifications = hone_core::notify::NotifyConfig::load(None)
+        .context("Failed to load notifications config")?;
+
+    // Cron-scheduled tasks (schedules.toml or HONE_SCHEDULE_CONFIG)
+    let schedules = hone_core::schedule::ScheduleConfig::load(None)
+        .context("Failed to load schedules config")?
+        .unwrap_or_default();
+
+    // Import folder watcher (--watch, with mappings from watch.toml)
+    let watch = match watch_dir {
+        Some(dir) => Some(super::load_watch_config(Some(dir))?),
+        None => None,
+    };
+
     if no_auth {
         println!();
         println!("   ⚠️  Authentication DISABLED - do not expose to network!");
@@ -56,6 +91,19 @@ pub async fn cmd_serve(
             println!("   🔒 Authentication: Cloudflare Access (header only)");
             println!("      Set CF_TEAM_NAME and CF_AUD_TAG for cryptographic JWT validation");
         }
+        if local_auth.enabled {
+            println!(
+                "   🔐 Built-in login: enabled (sessions last {}h)",
+                local_auth.session_ttl_hours
+            );
+            match &local_auth.relying_party {
+                Some(rp) => println!("      Passkeys: {} ({})", rp.id, rp.origin),
+                None => println!("      Passkeys: disabled (set HONE_WEBAUTHN_ORIGIN)"),
+            }
+            if !local_auth.secure_cookies {
+                println!("      ⚠️  Cookies are not marked Secure (HONE_INSECURE_COOKIES)");
+            }
+        }
         if !api_keys.is_empty() {
             println!(
                 "   🔑 API keys: {} configured (HONE_API_KEYS)",
@@ -71,6 +119,10 @@ pub async fn cmd_serve(
                     .collect::<Vec<_>>()
                     .join(", ")
             );
+            println!(
+                "      Trusted network role: {} (HONE_TRUSTED_NETWORK_ROLE)",
+                trusted_network_role
+            );
         }
         if !trusted_proxies.is_empty() {
             println!(
@@ -83,6 +135,53 @@ pub async fn cmd_serve(
             );
         }
     }
+    if metrics.enabled {
+        let access = match (&metrics.token, metrics.allowed_networks.is_empty()) {
+            (Some(_), true) => "bearer token".to_string(),
+            (None, true) => "no access configured".to_string(),
+            (token, false) => format!(
+                "{}{}",
+                if token.is_some() {
+                    "bearer token or "
+                } else {
+                    ""
+                },
+                metrics
+                    .allowed_networks
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        println!("   📈 Metrics: /metrics ({})", access);
+    }
+    if let Some(notify) = &notifications {
+        println!(
+            "   🔔 Notifications: {} channel(s), {} route(s)",
+            notify.channels.len(),
+            notify.routes.len()
+        );
+    }
+    if !schedules.tasks.is_empty() {
+        println!(
+            "   ⏰ Scheduled tasks: {}",
+            schedules
+                .tasks
+                .iter()
+                .filter(|t| t.enabled)
+                .map(|t| format!("{} ({})", t.name, t.cron))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    if let Some(watch) = &watch {
+        println!(
+            "   📂 Watching: {} ({} account mapping(s))",
+            watch.dir.as_deref().unwrap_or(Path::new("")).display(),
+            watch.accounts.len()
+        );
+    }
     if no_encrypt {
         println!("   ⚠️  Encryption DISABLED (--no-encrypt)");
     }
@@ -94,6 +193,11 @@ pub async fn cmd_serve(
     // Ensure root tags are seeded (idempotent)
     db.seed_root_tags().context("Failed to seed root tags")?;
 
+    let user_count = db.count_users().context("Failed to count users")?;
+    if user_count > 0 {
+        println!("   👥 Household users: {}", user_count);
+    }
+
     let config = hone_server::ServerConfig {
         require_auth: !no_auth,
         allowed_origins: vec![],
@@ -105,6 +209,12 @@ pub async fn cmd_serve(
         },
         trusted_networks,
         trusted_proxies,
+        trusted_network_role,
+        local_auth,
+        metrics,
+        notifications,
+        schedules,
+        watch,
     };
 
     // Start MCP server if port specified
@@ -148,6 +148,14 @@ pub fn cmd_alerts(db_path: &Path, include_dismissed: bool, no_encrypt: bool) ->
             hone_core::models::AlertType::Resume => "🔄",
             hone_core::models::AlertType::SpendingAnomaly => "📊",
             hone_core::models::AlertType::TipDiscrepancy => "💸",
+            hone_core::models::AlertType::TrialEnding => "⏳",
+            hone_core::models::AlertType::ChargedAfterCancel => "🚨",
+            hone_core::models::AlertType::UnusualAmount => "💰",
+            hone_core::models::AlertType::NewMerchant => "🆕",
+            hone_core::models::AlertType::UnusualActivity => "🌍",
+            hone_core::models::AlertType::DuplicateCharge => "♊",
+            hone_core::models::AlertType::ReturnWindowClosing => "↩️",
+            hone_core::models::AlertType::WarrantyExpiring => "🛡️",
         };
 
         let dismissed_mark = if alert.dismissed { " (dismissed)" } else { "" };
@@ -3,6 +3,7 @@
 use anyhow::{Context, Result};
 use chrono::NaiveDate;
 use hone_core::db::Database;
+use hone_core::models::{CancellationMethod, CancellationStatus, NewSubscriptionCancellation};
 
 use super::truncate;
 
@@ -31,7 +32,7 @@ pub fn cmd_subscriptions_list(db: &Database) -> Result<()> {
             .amount
             .map(|a| format!("${:.2}", a))
             .unwrap_or_else(|| "?".to_string());
-        let freq_str = sub.frequency.map(|f| f.as_str()).unwrap_or("?");
+        let freq_str = sub.frequency.map(|f| f.as_str()).unwrap_or("?".into());
 
         println!(
             "   {} {:20} │ {:>8}/{:<7} │ since {}",
@@ -48,30 +49,127 @@ pub fn cmd_subscriptions_list(db: &Database) -> Result<()> {
     Ok(())
 }
 
-pub fn cmd_subscriptions_cancel(db: &Database, name_or_id: &str, date: Option<&str>) -> Result<()> {
+/// Cancel a subscription and track the cancellation for verification
+#[allow(clippy::too_many_arguments)]
+pub fn cmd_subscriptions_cancel(
+    db: &Database,
+    name_or_id: &str,
+    date: Option<&str>,
+    method: Option<&str>,
+    confirmation: Option<String>,
+    final_charge: Option<&str>,
+    refund: Option<f64>,
+    notes: Option<String>,
+) -> Result<()> {
     // Find subscription by name or ID
     let sub_id = db
         .find_subscription_by_merchant_or_id(name_or_id)?
         .ok_or_else(|| anyhow::anyhow!("Subscription not found: {}", name_or_id))?;
 
-    // Parse optional date
+    // Parse optional dates and method
     let cancel_date = date
         .map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d"))
         .transpose()
         .context("Invalid --date format (use YYYY-MM-DD)")?;
+    let expected_final_charge = final_charge
+        .map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d"))
+        .transpose()
+        .context("Invalid --final-charge format (use YYYY-MM-DD)")?;
+    let method = method
+        .map(|m| m.parse::<CancellationMethod>())
+        .transpose()
+        .map_err(|e| anyhow::anyhow!(e))?;
 
-    // Cancel the subscription
-    db.cancel_subscription(sub_id, cancel_date)?;
+    let cancellation = db.record_cancellation(
+        sub_id,
+        &NewSubscriptionCancellation {
+            cancelled_on: cancel_date,
+            method,
+            confirmation_number: confirmation,
+            expected_final_charge,
+            expected_refund: refund,
+            notes,
+        },
+    )?;
 
-    let date_str = cancel_date
-        .map(|d| d.to_string())
-        .unwrap_or_else(|| "today".to_string());
+    db.log_audit(
+        "cli",
+        "cancel",
+        Some("subscription"),
+        Some(sub_id),
+        Some(&format!(
+            "cancelled_on={}, method={}, final_charge={:?}",
+            cancellation.cancelled_on,
+            cancellation.method.map_or("unknown", |m| m.as_str()),
+            cancellation.expected_final_charge
+        )),
+    )?;
 
     println!(
         "✅ Subscription cancelled (ID: {}) as of {}",
-        sub_id, date_str
+        sub_id, cancellation.cancelled_on
     );
+    if let Some(number) = &cancellation.confirmation_number {
+        println!("   Confirmation: {}", number);
+    }
+    if let Some(final_charge) = cancellation.expected_final_charge {
+        println!(
+            "   Any charge after {} will raise an alert on the next detection run",
+            final_charge
+        );
+    }
+    if let Some(refund) = cancellation.expected_refund {
+        println!("   Expecting a refund of ${:.2}", refund);
+    }
     println!("   Savings will be tracked in: hone report savings");
 
     Ok(())
 }
+
+/// List tracked cancellations and their verification status
+pub fn cmd_subscriptions_cancellations(db: &Database, status: Option<&str>) -> Result<()> {
+    let status = status
+        .map(|s| s.parse::<CancellationStatus>())
+        .transpose()
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let cancellations = db.list_cancellations(status)?;
+
+    if cancellations.is_empty() {
+        println!("No tracked cancellations. Record one with:");
+        println!("  hone subscriptions cancel <name> --final-charge YYYY-MM-DD");
+        return Ok(());
+    }
+
+    println!();
+    println!("✂️  Cancellations");
+    println!("   ─────────────────────────────────────────────────────────────");
+    println!(
+        "   {:20} │ {:10} │ {:10} │ {:9} │ {:14} │ Status",
+        "Merchant", "Cancelled", "Final", "Method", "Confirmation"
+    );
+    for c in cancellations {
+        let status = match c.status {
+            CancellationStatus::Pending => "⏳ pending",
+            CancellationStatus::Confirmed => "✅ confirmed",
+            CancellationStatus::ChargedAfter => "🚨 charged after",
+        };
+        println!(
+            "   {:20} │ {:10} │ {:10} │ {:9} │ {:14} │ {}",
+            truncate(&c.merchant, 20),
+            c.cancelled_on,
+            c.expected_final_charge
+                .map(|d| d.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            c.method.map_or("-", |m| m.as_str()),
+            truncate(c.confirmation_number.as_deref().unwrap_or("-"), 14),
+            status
+        );
+        match (c.expected_refund, c.refund_amount) {
+            (_, Some(received)) => println!("          └ refund received: ${:.2}", received),
+            (Some(expected), None) => println!("          └ refund expected: ${:.2}", expected),
+            (None, None) => {}
+        }
+    }
+
+    Ok(())
+}
@@ -6,6 +6,7 @@ use std::path::Path;
 
 use anyhow::{Context, Result};
 use hone_core::db::Database;
+use hone_core::trainer::{Trainer, TrainerBackend};
 use hone_core::training::{TrainingDataGenerator, TrainingTask};
 use hone_core::training_pipeline::{ExperimentStatus, PipelineConfig, TrainingPipeline};
 
@@ -17,7 +18,7 @@ pub fn cmd_export_training_data(
     min_confidence: f64,
 ) -> Result<()> {
     let task = TrainingTask::from_str(task)
-        .ok_or_else(|| anyhow::anyhow!("Unknown task: {}. Valid tasks: classify_merchant, normalize_merchant, classify_subscription", task))?;
+        .ok_or_else(|| anyhow::anyhow!("Unknown task: {}. Valid tasks: classify_merchant, normalize_merchant, classify_subscription, suggest_entity, suggest_split, evaluate_receipt_match, parse_receipt", task))?;
 
     println!("📊 Exporting training data for: {}", task);
     println!();
@@ -43,6 +44,19 @@ pub fn cmd_export_training_data(
             TrainingTask::ClassifySubscription => {
                 println!("  - Subscription exclusions (merchant_subscription_cache)");
             }
+            TrainingTask::SuggestEntity => {
+                println!("  - Entity assignments on transaction splits");
+            }
+            TrainingTask::SuggestSplit => {
+                println!("  - Transactions split into multiple items");
+                println!("  - Acknowledged subscriptions (never split)");
+            }
+            TrainingTask::EvaluateReceiptMatch => {
+                println!("  - Manual receipt link/unlink decisions (receipt_match_feedback)");
+            }
+            TrainingTask::ParseReceipt => {
+                println!("  - Edited receipt parses (receipt_parse_corrections)");
+            }
         }
         return Ok(());
     }
@@ -293,8 +307,24 @@ pub fn cmd_training_prepare(db: &Database, experiment_id: i64) -> Result<()> {
 }
 
 /// Run training for an experiment (MLX on Mac)
-pub fn cmd_training_train(db: &Database, experiment_id: i64, skip_mlx: bool) -> Result<()> {
-    println!("🚀 Starting training for experiment #{}...", experiment_id);
+pub fn cmd_training_train(
+    db: &Database,
+    experiment_id: i64,
+    trainer: Option<&str>,
+    skip_training: bool,
+) -> Result<()> {
+    let trainer = match trainer {
+        Some(name) => TrainerBackend::from_name(name).ok_or_else(|| {
+            anyhow::anyhow!("Unknown trainer: {}. Valid trainers: mlx, llama_cpp", name)
+        })?,
+        None => TrainerBackend::from_env(),
+    };
+
+    println!(
+        "🚀 Starting training for experiment #{} ({} trainer)...",
+        experiment_id,
+        trainer.name()
+    );
 
     let pipeline = TrainingPipeline::new(db);
 
@@ -308,26 +338,39 @@ pub fn cmd_training_train(db: &Database, experiment_id: i64, skip_mlx: bool) ->
         pipeline.prepare_training_data(experiment_id)?;
     }
 
-    if skip_mlx {
+    if skip_training {
         println!();
-        println!("⚠️  MLX training skipped (--skip-mlx flag)");
+        println!("⚠️  Training skipped (--skip-training flag)");
         println!();
         println!("To train manually:");
-        println!("  1. Install mlx-lm: pip install mlx-lm");
-        println!(
-            "  2. Run: mlx_lm.lora --model {} --train --data <training_data.jsonl>",
-            experiment.base_model
-        );
-        println!(
-            "  3. Update experiment: hone training update --id {} --adapter <path>",
-            experiment_id
-        );
+        match trainer {
+            TrainerBackend::LlamaCpp(_) => {
+                println!("  1. Build llama.cpp and put llama-finetune on PATH");
+                println!(
+                    "  2. Run: hone training train --id {} --trainer llama_cpp",
+                    experiment_id
+                );
+            }
+            _ => {
+                println!("  1. Install mlx-lm: pip install mlx-lm");
+                println!(
+                    "  2. Run: mlx_lm.lora --model {} --train --data <training_data.jsonl>",
+                    experiment.base_model
+                );
+            }
+        }
         return Ok(());
     }
 
-    match pipeline.run_mlx_finetuning(experiment_id) {
-        Ok(()) => {
+    match pipeline.run_finetuning(experiment_id, &trainer) {
+        Ok(experiment) => {
             println!("✅ Training completed!");
+            if let Some(ref adapter) = experiment.adapter_path {
+                println!("   Adapter: {}", adapter);
+            }
+            if let Some(ref log) = experiment.log_path {
+                println!("   Log: {}", log);
+            }
             println!();
             println!("Next: Create Ollama model and test:");
             println!("  hone training create-model --id {}", experiment_id);
@@ -336,7 +379,18 @@ pub fn cmd_training_train(db: &Database, experiment_id: i64, skip_mlx: bool) ->
             println!("❌ Training failed: {}", e);
             println!();
             println!("Troubleshooting:");
-            println!("  - Ensure mlx-lm is installed: pip install mlx-lm");
+            match trainer {
+                TrainerBackend::LlamaCpp(_) => {
+                    println!("  - Ensure llama-finetune is on PATH (or set HONE_LLAMA_FINETUNE)");
+                    println!(
+                        "  - The base model must be pulled in Ollama or given as a .gguf path"
+                    );
+                }
+                _ => {
+                    println!("  - Ensure mlx-lm is installed: pip install mlx-lm");
+                    println!("  - MLX requires Apple Silicon; use --trainer llama_cpp on Linux");
+                }
+            }
             println!("  - Check you have enough RAM for the base model");
         }
     }
@@ -432,6 +486,19 @@ pub fn cmd_training_experiments(
         println!("   Model: {} → {}", exp.base_model, exp.model_name);
         println!("   Examples: {}", exp.training_examples);
         println!("   Created: {}", exp.created_at.format("%Y-%m-%d %H:%M"));
+        if let Some(ref adapter) = exp.adapter_path {
+            println!(
+                "   Adapter: {} ({})",
+                adapter,
+                exp.trainer.as_deref().unwrap_or("unknown trainer")
+            );
+        }
+        if let Some(ref log) = exp.log_path {
+            println!("   Log: {}", log);
+        }
+        if let Some(ref modelfile) = exp.modelfile_path {
+            println!("   Modelfile: {}", modelfile);
+        }
         if let Some(ref notes) = exp.notes {
             println!("   Notes: {}", notes);
         }
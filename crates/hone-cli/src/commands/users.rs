@@ -0,0 +1,171 @@
+//! Household user command implementations (users, roles, account visibility)
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use anyhow::Result;
+use hone_core::db::Database;
+use hone_core::local_auth::hash_password;
+use hone_core::models::UserRole;
+
+use super::truncate;
+
+fn parse_role(role: &str) -> Result<UserRole> {
+    role.parse()
+        .map_err(|e: String| anyhow::anyhow!("{} (valid roles: owner, editor, viewer)", e))
+}
+
+/// List household users
+pub fn cmd_users_list(db: &Database) -> Result<()> {
+    let users = db.list_users()?;
+
+    if users.is_empty() {
+        println!("No household users. Every authenticated identity has full access.");
+        println!("Add the first owner with:");
+        println!("  hone users add <email> --role owner");
+        return Ok(());
+    }
+
+    println!();
+    println!("👥 Household Users");
+    println!("   ─────────────────────────────────────────────────────────────");
+    println!(
+        "   {:>4} │ {:30} │ {:20} │ {:6} │ Last Seen",
+        "ID", "Email", "Name", "Role"
+    );
+    println!("   ─────┼────────────────────────────────┼──────────────────────┼────────┼─────────────────");
+
+    for user in users {
+        let last_seen = user
+            .last_seen_at
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "never".to_string());
+        println!(
+            "   {:>4} │ {:30} │ {:20} │ {:6} │ {}",
+            user.id,
+            truncate(&user.email, 30),
+            truncate(user.display_name.as_deref().unwrap_or("-"), 20),
+            user.role.as_str(),
+            last_seen
+        );
+    }
+
+    Ok(())
+}
+
+/// Add a household user
+pub fn cmd_users_add(
+    db: &Database,
+    email: &str,
+    role: &str,
+    display_name: Option<&str>,
+) -> Result<()> {
+    let role = parse_role(role)?;
+    if db.count_users()? == 0 && role != UserRole::Owner {
+        anyhow::bail!("The first user must be an owner");
+    }
+
+    let id = db.create_user(email, display_name, role)?;
+    println!("✓ Added {} as {} (id: {})", email.trim(), role, id);
+    Ok(())
+}
+
+/// Change a user's role
+pub fn cmd_users_set_role(db: &Database, id: i64, role: &str) -> Result<()> {
+    let role = parse_role(role)?;
+    let user = db
+        .get_user(id)?
+        .ok_or_else(|| anyhow::anyhow!("User {} not found", id))?;
+
+    db.update_user(id, None, Some(role))?;
+    println!("✓ {} is now {}", user.email, role);
+    Ok(())
+}
+
+/// Remove a household user
+pub fn cmd_users_remove(db: &Database, id: i64) -> Result<()> {
+    let user = db
+        .get_user(id)?
+        .ok_or_else(|| anyhow::anyhow!("User {} not found", id))?;
+
+    db.delete_user(id)?;
+    println!("✓ Removed {}", user.email);
+    Ok(())
+}
+
+/// Show or set which users can see an account
+pub fn cmd_users_visibility(db: &Database, account_id: i64, user_ids: &[i64]) -> Result<()> {
+    let account = db
+        .get_account(account_id)?
+        .ok_or_else(|| anyhow::anyhow!("Account {} not found", account_id))?;
+
+    if !user_ids.is_empty() {
+        for user_id in user_ids {
+            if db.get_user(*user_id)?.is_none() {
+                anyhow::bail!("User {} not found", user_id);
+            }
+        }
+        db.set_account_visibility(account_id, user_ids)?;
+    }
+
+    let visible_to = db.get_account_visibility(account_id)?;
+    if visible_to.is_empty() {
+        println!("{} is shared with the whole household", account.name);
+    } else {
+        let emails = visible_to
+            .iter()
+            .filter_map(|id| db.get_user(*id).ok().flatten())
+            .map(|u| u.email)
+            .collect::<Vec<_>>();
+        println!(
+            "{} is visible to owners and: {}",
+            account.name,
+            emails.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Make an account visible to the whole household again
+pub fn cmd_users_share(db: &Database, account_id: i64) -> Result<()> {
+    let account = db
+        .get_account(account_id)?
+        .ok_or_else(|| anyhow::anyhow!("Account {} not found", account_id))?;
+
+    db.set_account_visibility(account_id, &[])?;
+    println!("✓ {} is shared with the whole household", account.name);
+    Ok(())
+}
+
+/// Set a user's built-in login password (first line of stdin)
+pub fn cmd_users_set_password(db: &Database, id: i64) -> Result<()> {
+    let user = db
+        .get_user(id)?
+        .ok_or_else(|| anyhow::anyhow!("User {} not found", id))?;
+
+    if io::stdin().is_terminal() {
+        print!("New password for {} (input is shown): ", user.email);
+        io::stdout().flush()?;
+    }
+    let mut password = String::new();
+    io::stdin().lock().read_line(&mut password)?;
+    let password = password.trim_end_matches(['\r', '\n']);
+
+    let hash = hash_password(password)?;
+    db.set_user_password_hash(id, Some(&hash))?;
+    println!("✓ Password set for {}", user.email);
+    Ok(())
+}
+
+/// Clear a user's built-in credentials so they can set them up again
+pub fn cmd_users_reset_auth(db: &Database, id: i64) -> Result<()> {
+    let user = db
+        .get_user(id)?
+        .ok_or_else(|| anyhow::anyhow!("User {} not found", id))?;
+
+    db.reset_user_credentials(id)?;
+    println!(
+        "✓ Cleared password, TOTP, passkeys and sessions for {}",
+        user.email
+    );
+    Ok(())
+}
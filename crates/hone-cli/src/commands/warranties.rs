@@ -0,0 +1,147 @@
+//! Warranty and return-window commands (list, add, remove)
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, Utc};
+use hone_core::db::Database;
+use hone_core::models::NewWarranty;
+
+use super::truncate;
+use crate::cli::WarrantyAddArgs;
+
+/// List items under warranty or still returnable (or every tracked item)
+pub fn cmd_warranties_list(db: &Database, search: Option<&str>, all: bool) -> Result<()> {
+    let today = Utc::now().date_naive();
+
+    if all {
+        let warranties = db.list_warranties(search, None)?;
+        if warranties.is_empty() {
+            println!("No warranties tracked.");
+            return Ok(());
+        }
+
+        println!();
+        println!(
+            "   {:>5} │ {:28} │ {:10} │ {:10} │ {:10} │ Status",
+            "ID", "Item", "Bought", "Return by", "Warranty"
+        );
+        for w in warranties {
+            println!(
+                "   {:>5} │ {:28} │ {:10} │ {:10} │ {:10} │ {}",
+                w.id,
+                truncate(&w.item, 28),
+                w.purchase_date,
+                w.return_by.map(|d| d.to_string()).unwrap_or_default(),
+                w.warranty_expires
+                    .map(|d| d.to_string())
+                    .unwrap_or_default(),
+                w.status(today).as_str()
+            );
+        }
+        return Ok(());
+    }
+
+    let report = db.get_warranty_report(search, today)?;
+    if report.items.is_empty() {
+        println!("Nothing under warranty or within its return window.");
+        if report.expired_count > 0 {
+            println!(
+                "{} expired. Run 'hone warranties list --all' to see them.",
+                report.expired_count
+            );
+        }
+        return Ok(());
+    }
+
+    println!();
+    println!("🛡️  Under warranty");
+    println!("   ─────────────────────────────────────────────────────────────");
+    println!(
+        "   {:>5} │ {:28} │ {:18} │ {:>9} │ Deadline",
+        "ID", "Item", "Merchant", "Price"
+    );
+    for item in &report.items {
+        let w = &item.warranty;
+        let deadline = match (item.return_days_left, item.warranty_days_left) {
+            (Some(days), _) => format!("return within {} days", days),
+            (None, Some(days)) => format!("warranty ends in {} days", days),
+            (None, None) => String::new(),
+        };
+        println!(
+            "   {:>5} │ {:28} │ {:18} │ {:>9} │ {}",
+            w.id,
+            truncate(&w.item, 28),
+            truncate(w.merchant.as_deref().unwrap_or(""), 18),
+            w.price.map(|p| format!("{:.2}", p)).unwrap_or_default(),
+            deadline
+        );
+        if let Some(serial) = &w.serial_number {
+            println!("         └ serial: {}", serial);
+        }
+    }
+    println!();
+    println!(
+        "   {} returnable, {} covered (${:.2}); {} expired",
+        report.returnable_count, report.covered_count, report.covered_value, report.expired_count
+    );
+
+    Ok(())
+}
+
+/// Track a warranty or return window
+pub fn cmd_warranties_add(db: &Database, args: &WarrantyAddArgs) -> Result<()> {
+    let parse_date = |value: &Option<String>, flag: &str| {
+        value
+            .as_deref()
+            .map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d"))
+            .transpose()
+            .with_context(|| format!("Invalid --{} date format (use YYYY-MM-DD)", flag))
+    };
+
+    let new = NewWarranty {
+        item: args.item.clone(),
+        merchant: args.merchant.clone(),
+        purchase_date: parse_date(&args.date, "date")?,
+        price: args.price,
+        return_by: parse_date(&args.return_by, "return-by")?,
+        return_days: args.return_days,
+        warranty_expires: parse_date(&args.warranty_until, "warranty-until")?,
+        warranty_months: args.warranty_months,
+        serial_number: args.serial.clone(),
+        receipt_id: args.receipt,
+        split_id: args.split,
+        transaction_id: args.transaction,
+        document_receipt_id: args.document,
+        notes: args.notes.clone(),
+    };
+
+    let w = db.create_warranty(&new)?;
+    db.log_audit(
+        "cli",
+        "create",
+        Some("warranty"),
+        Some(w.id),
+        Some(&format!(
+            "receipt_id={:?}, split_id={:?}, return_by={:?}, warranty_expires={:?}",
+            w.receipt_id, w.split_id, w.return_by, w.warranty_expires
+        )),
+    )?;
+
+    println!("✓ Tracking {} (ID {})", w.item, w.id);
+    if let Some(date) = w.return_by {
+        println!("  Return by:        {}", date);
+    }
+    if let Some(date) = w.warranty_expires {
+        println!("  Warranty until:   {}", date);
+    }
+    Ok(())
+}
+
+/// Stop tracking a warranty
+pub fn cmd_warranties_remove(db: &Database, id: i64) -> Result<()> {
+    if !db.delete_warranty(id)? {
+        anyhow::bail!("Warranty {} not found", id);
+    }
+    db.log_audit("cli", "delete", Some("warranty"), Some(id), None)?;
+    println!("✓ Removed warranty {}", id);
+    Ok(())
+}
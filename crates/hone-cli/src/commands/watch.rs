@@ -0,0 +1,97 @@
+//! Import folder watcher command
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use hone_core::ai::AIClient;
+use hone_core::watch::{
+    default_config_path, ImportWatcher, WatchConfig, WatchOutcome, WatchedFile,
+};
+use hone_server::{start_job_workers, JobLane, JobRunner};
+
+use super::open_db;
+
+/// Load watch.toml (if any) and apply the folder from the command line
+pub fn load_watch_config(dir: Option<&Path>) -> Result<WatchConfig> {
+    let config = WatchConfig::load(None)
+        .context("Failed to load watch config")?
+        .unwrap_or_default();
+    let config = match dir {
+        Some(dir) => config.with_dir(dir),
+        None => config,
+    };
+    if config.dir.is_none() {
+        let path = std::env::var("HONE_WATCH_CONFIG")
+            .ok()
+            .or_else(|| default_config_path().map(|p| p.display().to_string()));
+        anyhow::bail!(
+            "No folder to watch. Pass one, or set `dir` in {}",
+            path.unwrap_or_else(|| "watch.toml".to_string())
+        );
+    }
+    Ok(config)
+}
+
+fn print_handled(handled: &[WatchedFile]) {
+    for file in handled {
+        match &file.outcome {
+            WatchOutcome::Imported {
+                account,
+                imported,
+                skipped,
+                ..
+            } => println!(
+                "   ✅ {} → {} ({} new, {} duplicates)",
+                file.filename, account, imported, skipped
+            ),
+            WatchOutcome::Quarantined { error, .. } => {
+                println!("   ⚠️  {} quarantined: {}", file.filename, error)
+            }
+        }
+    }
+}
+
+pub async fn cmd_watch(
+    db_path: &Path,
+    dir: Option<&Path>,
+    once: bool,
+    no_encrypt: bool,
+) -> Result<()> {
+    let config = load_watch_config(dir)?;
+    let poll_secs = config.poll_secs;
+    let db = open_db(db_path, no_encrypt)?;
+    db.seed_root_tags().context("Failed to seed root tags")?;
+    let watcher = ImportWatcher::new(db.clone(), config)?;
+    let runner = JobRunner::new(db, AIClient::from_env());
+
+    println!(
+        "📂 Watching {} ({} account mapping(s))",
+        watcher.dir().display(),
+        watcher.config().accounts.len()
+    );
+
+    if once {
+        let handled = watcher.scan_once(SystemTime::now())?;
+        if handled.is_empty() {
+            println!("   No new statements");
+            return Ok(());
+        }
+        print_handled(&handled);
+
+        // Tag and detect the new imports before exiting
+        println!("   Processing imports...");
+        while runner.run_next(JobLane::Ai).await?.is_some() {}
+        while runner.run_next(JobLane::General).await?.is_some() {}
+        println!("✨ Done");
+        return Ok(());
+    }
+
+    println!("   Press Ctrl+C to stop");
+    start_job_workers(runner);
+    let mut ticker = tokio::time::interval(Duration::from_secs(poll_secs));
+    loop {
+        ticker.tick().await;
+        print_handled(&watcher.scan_once(SystemTime::now())?);
+    }
+}
@@ -64,6 +64,7 @@ async fn main() -> Result<()> {
             no_auth,
             static_dir,
             mcp_port,
+            watch,
         } => {
             commands::cmd_serve(
                 &cli.db,
@@ -73,9 +74,13 @@ async fn main() -> Result<()> {
                 cli.no_encrypt,
                 static_dir.as_deref(),
                 mcp_port,
+                watch.as_deref(),
             )
             .await
         }
+        Commands::Watch { dir, once } => {
+            commands::cmd_watch(&cli.db, dir.as_deref(), once, cli.no_encrypt).await
+        }
         Commands::Dashboard => commands::cmd_dashboard(&cli.db, cli.no_encrypt),
         Commands::Status => commands::cmd_status(&cli.db, cli.no_encrypt),
         Commands::Accounts => commands::cmd_accounts(&cli.db, cli.no_encrypt),
@@ -102,8 +107,26 @@ async fn main() -> Result<()> {
         Commands::Subscriptions { action } => {
             let db = commands::open_db(&cli.db, cli.no_encrypt)?;
             match action {
-                Some(SubscriptionsAction::Cancel { name_or_id, date }) => {
-                    commands::cmd_subscriptions_cancel(&db, &name_or_id, date.as_deref())
+                Some(SubscriptionsAction::Cancel {
+                    name_or_id,
+                    date,
+                    method,
+                    confirmation,
+                    final_charge,
+                    refund,
+                    notes,
+                }) => commands::cmd_subscriptions_cancel(
+                    &db,
+                    &name_or_id,
+                    date.as_deref(),
+                    method.as_deref(),
+                    confirmation,
+                    final_charge.as_deref(),
+                    refund,
+                    notes,
+                ),
+                Some(SubscriptionsAction::Cancellations { status }) => {
+                    commands::cmd_subscriptions_cancellations(&db, status.as_deref())
                 }
                 None => commands::cmd_subscriptions_list(&db),
             }
@@ -171,15 +194,24 @@ async fn main() -> Result<()> {
                     to,
                     tag,
                     expand,
+                    net_refunds,
                 } => {
                     let (from_date, to_date) =
                         commands::resolve_period(&period, from.as_deref(), to.as_deref())?;
-                    commands::cmd_report_spending(&db, from_date, to_date, tag.as_deref(), expand)
+                    commands::cmd_report_spending(
+                        &db,
+                        from_date,
+                        to_date,
+                        tag.as_deref(),
+                        expand,
+                        net_refunds,
+                    )
                 }
                 ReportType::Trends {
                     granularity,
                     period,
                     tag,
+                    net_refunds,
                 } => {
                     let (from_date, to_date) = commands::resolve_period(&period, None, None)?;
                     let granularity: hone_core::models::Granularity = granularity
@@ -191,6 +223,7 @@ async fn main() -> Result<()> {
                         to_date,
                         granularity,
                         tag.as_deref(),
+                        net_refunds,
                     )
                 }
                 ReportType::Merchants { limit, period, tag } => {
@@ -257,6 +290,117 @@ async fn main() -> Result<()> {
                 }
             }
         }
+        Commands::Config { action } => {
+            let db = commands::open_db(&cli.db, cli.no_encrypt)?;
+            match action {
+                None | Some(ConfigAction::Show) => commands::cmd_config_show(&db),
+                Some(ConfigAction::Set { key, value, scope }) => {
+                    commands::cmd_config_set(&db, &scope, &key, &value)
+                }
+                Some(ConfigAction::Unset { key, scope }) => {
+                    commands::cmd_config_unset(&db, &scope, &key)
+                }
+                Some(ConfigAction::Reset { scope }) => commands::cmd_config_reset(&db, &scope),
+            }
+        }
+        Commands::Refunds { action } => {
+            let db = commands::open_db(&cli.db, cli.no_encrypt)?;
+            match action {
+                None => commands::cmd_refunds_list(&db, 20),
+                Some(RefundsAction::List { limit }) => commands::cmd_refunds_list(&db, limit),
+                Some(RefundsAction::Match) => commands::cmd_refunds_match(&db),
+                Some(RefundsAction::Candidates { refund_id }) => {
+                    commands::cmd_refunds_candidates(&db, refund_id)
+                }
+                Some(RefundsAction::Link {
+                    refund_id,
+                    original_id,
+                    split,
+                    amount,
+                }) => commands::cmd_refunds_link(&db, refund_id, original_id, split, amount),
+                Some(RefundsAction::Unlink { refund_id }) => {
+                    commands::cmd_refunds_unlink(&db, refund_id)
+                }
+            }
+        }
+        Commands::Warranties { action } => {
+            let db = commands::open_db(&cli.db, cli.no_encrypt)?;
+            match action {
+                None => commands::cmd_warranties_list(&db, None, false),
+                Some(WarrantiesAction::List { search, all }) => {
+                    commands::cmd_warranties_list(&db, search.as_deref(), all)
+                }
+                Some(WarrantiesAction::Add { args }) => commands::cmd_warranties_add(&db, &args),
+                Some(WarrantiesAction::Remove { id }) => commands::cmd_warranties_remove(&db, id),
+            }
+        }
+        Commands::Keys { action } => {
+            let db = commands::open_db(&cli.db, cli.no_encrypt)?;
+            match action {
+                None => commands::cmd_keys_list(&db, false),
+                Some(KeysAction::List { all }) => commands::cmd_keys_list(&db, all),
+                Some(KeysAction::Create {
+                    name,
+                    scope,
+                    expires_days,
+                }) => commands::cmd_keys_create(&db, &name, &scope, expires_days),
+                Some(KeysAction::Revoke { key }) => commands::cmd_keys_revoke(&db, &key),
+            }
+        }
+        Commands::Notify { action } => {
+            let db = commands::open_db(&cli.db, cli.no_encrypt)?;
+            match action {
+                None | Some(NotifyAction::Channels) => commands::cmd_notify_channels(),
+                Some(NotifyAction::Test { channel }) => {
+                    commands::cmd_notify_test(&db, &channel).await
+                }
+                Some(NotifyAction::Deliveries { status, limit }) => {
+                    commands::cmd_notify_deliveries(&db, status.as_deref(), limit)
+                }
+                Some(NotifyAction::Retry { id }) => commands::cmd_notify_retry(&db, id),
+                Some(NotifyAction::Run) => commands::cmd_notify_run(&db).await,
+            }
+        }
+        Commands::Audit { action } => {
+            let db = commands::open_db(&cli.db, cli.no_encrypt)?;
+            match action {
+                None => commands::cmd_audit_list(&db, &AuditFilterArgs::default(), 50),
+                Some(AuditAction::List { filter, limit }) => {
+                    commands::cmd_audit_list(&db, &filter, limit)
+                }
+                Some(AuditAction::Verify) => commands::cmd_audit_verify(&db),
+                Some(AuditAction::Export {
+                    filter,
+                    format,
+                    output,
+                }) => commands::cmd_audit_export(&db, &filter, &format, output),
+                Some(AuditAction::Archive { older_than_days }) => {
+                    commands::cmd_audit_archive(&db, older_than_days)
+                }
+            }
+        }
+        Commands::Users { action } => {
+            let db = commands::open_db(&cli.db, cli.no_encrypt)?;
+            match action {
+                None | Some(UsersAction::List) => commands::cmd_users_list(&db),
+                Some(UsersAction::Add { email, role, name }) => {
+                    commands::cmd_users_add(&db, &email, &role, name.as_deref())
+                }
+                Some(UsersAction::SetRole { id, role }) => {
+                    commands::cmd_users_set_role(&db, id, &role)
+                }
+                Some(UsersAction::Remove { id }) => commands::cmd_users_remove(&db, id),
+                Some(UsersAction::Visibility {
+                    account_id,
+                    user_ids,
+                }) => commands::cmd_users_visibility(&db, account_id, &user_ids),
+                Some(UsersAction::Share { account_id }) => {
+                    commands::cmd_users_share(&db, account_id)
+                }
+                Some(UsersAction::SetPassword { id }) => commands::cmd_users_set_password(&db, id),
+                Some(UsersAction::ResetAuth { id }) => commands::cmd_users_reset_auth(&db, id),
+            }
+        }
         Commands::Backup { action } => match action {
             BackupAction::Create { name, dir } => {
                 let db = commands::open_db(&cli.db, cli.no_encrypt)?;
@@ -305,6 +449,19 @@ async fn main() -> Result<()> {
                     receipt_id,
                     reason: _,
                 }) => commands::cmd_receipts_dismiss(&db, receipt_id),
+                Some(ReceiptsAction::Mail {
+                    config,
+                    maildir,
+                    mbox,
+                }) => {
+                    commands::cmd_receipts_mail(
+                        &db,
+                        config.as_deref(),
+                        maildir.as_deref(),
+                        mbox.as_deref(),
+                    )
+                    .await
+                }
             }
         }
         Commands::Reset { soft, yes } => commands::cmd_reset(&cli.db, soft, yes, cli.no_encrypt),
@@ -363,9 +520,11 @@ async fn main() -> Result<()> {
                     notes.as_deref(),
                 ),
                 TrainingAction::Prepare { id } => commands::cmd_training_prepare(&db, id),
-                TrainingAction::Train { id, skip_mlx } => {
-                    commands::cmd_training_train(&db, id, skip_mlx)
-                }
+                TrainingAction::Train {
+                    id,
+                    trainer,
+                    skip_training,
+                } => commands::cmd_training_train(&db, id, trainer.as_deref(), skip_training),
                 TrainingAction::CreateModel { id } => commands::cmd_training_create_model(&db, id),
                 TrainingAction::Promote { id } => commands::cmd_training_promote(&db, id),
                 TrainingAction::Experiments { task, branch } => {
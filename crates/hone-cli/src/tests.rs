@@ -356,7 +356,7 @@ fn test_cmd_report_spending_empty() {
     let from = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
     let to = chrono::NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
 
-    let result = commands::cmd_report_spending(&db, from, to, None, false);
+    let result = commands::cmd_report_spending(&db, from, to, None, false, false);
     assert!(result.is_ok());
 }
 
@@ -372,7 +372,7 @@ fn test_cmd_report_spending_with_data() {
     let from = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
     let to = chrono::NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
 
-    let result = commands::cmd_report_spending(&db, from, to, None, false);
+    let result = commands::cmd_report_spending(&db, from, to, None, false, false);
     assert!(result.is_ok());
 }
 
@@ -388,7 +388,7 @@ fn test_cmd_report_spending_with_tag_filter() {
     let from = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
     let to = chrono::NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
 
-    let result = commands::cmd_report_spending(&db, from, to, Some("Dining"), false);
+    let result = commands::cmd_report_spending(&db, from, to, Some("Dining"), false, false);
     if let Err(ref e) = result {
         eprintln!("Error: {:?}", e);
     }
@@ -401,8 +401,14 @@ fn test_cmd_report_trends_empty() {
     let from = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
     let to = chrono::NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
 
-    let result =
-        commands::cmd_report_trends(&db, from, to, hone_core::models::Granularity::Monthly, None);
+    let result = commands::cmd_report_trends(
+        &db,
+        from,
+        to,
+        hone_core::models::Granularity::Monthly,
+        None,
+        false,
+    );
     assert!(result.is_ok());
 }
 
@@ -415,8 +421,14 @@ fn test_cmd_report_trends_with_data() {
     let from = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
     let to = chrono::NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
 
-    let result =
-        commands::cmd_report_trends(&db, from, to, hone_core::models::Granularity::Monthly, None);
+    let result = commands::cmd_report_trends(
+        &db,
+        from,
+        to,
+        hone_core::models::Granularity::Monthly,
+        None,
+        false,
+    );
     assert!(result.is_ok());
 }
 
@@ -426,8 +438,14 @@ fn test_cmd_report_trends_weekly() {
     let from = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
     let to = chrono::NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
 
-    let result =
-        commands::cmd_report_trends(&db, from, to, hone_core::models::Granularity::Weekly, None);
+    let result = commands::cmd_report_trends(
+        &db,
+        from,
+        to,
+        hone_core::models::Granularity::Weekly,
+        None,
+        false,
+    );
     assert!(result.is_ok());
 }
 
@@ -535,7 +553,8 @@ fn test_cmd_subscriptions_cancel() {
     .unwrap();
     drop(conn);
 
-    let result = commands::cmd_subscriptions_cancel(&db, "Netflix", None);
+    let result =
+        commands::cmd_subscriptions_cancel(&db, "Netflix", None, None, None, None, None, None);
     if let Err(ref e) = result {
         eprintln!("Error: {:?}", e);
     }
@@ -556,7 +575,8 @@ fn test_cmd_subscriptions_cancel() {
 fn test_cmd_subscriptions_cancel_not_found() {
     let db = setup_test_db();
 
-    let result = commands::cmd_subscriptions_cancel(&db, "NonExistent", None);
+    let result =
+        commands::cmd_subscriptions_cancel(&db, "NonExistent", None, None, None, None, None, None);
     assert!(result.is_err());
     assert!(result.unwrap_err().to_string().contains("not found"));
 }
@@ -1225,7 +1245,16 @@ fn test_cmd_subscriptions_cancel_with_date() {
     .unwrap();
     drop(conn);
 
-    let result = commands::cmd_subscriptions_cancel(&db, "Gym", Some("2024-06-15"));
+    let result = commands::cmd_subscriptions_cancel(
+        &db,
+        "Gym",
+        Some("2024-06-15"),
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
     assert!(result.is_ok());
 
     let conn = db.conn().unwrap();
@@ -1260,7 +1289,16 @@ fn test_cmd_subscriptions_cancel_invalid_date() {
     .unwrap();
     drop(conn);
 
-    let result = commands::cmd_subscriptions_cancel(&db, "Service", Some("invalid-date"));
+    let result = commands::cmd_subscriptions_cancel(
+        &db,
+        "Service",
+        Some("invalid-date"),
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
     assert!(result.is_err());
     assert!(result
         .unwrap_err()
@@ -1268,6 +1306,59 @@ fn test_cmd_subscriptions_cancel_invalid_date() {
         .contains("Invalid --date format"));
 }
 
+#[test]
+fn test_cmd_subscriptions_cancel_tracked() {
+    let db = setup_test_db();
+
+    let conn = db.conn().unwrap();
+    conn.execute(
+        "INSERT INTO subscriptions (merchant, amount, frequency, first_seen, last_seen, status)
+         VALUES ('Hulu', 17.99, 'monthly', '2024-01-01', '2024-06-01', 'active')",
+        [],
+    )
+    .unwrap();
+    drop(conn);
+
+    let result = commands::cmd_subscriptions_cancel(
+        &db,
+        "Hulu",
+        Some("2024-06-10"),
+        Some("phone"),
+        Some("HX-4412".to_string()),
+        Some("2024-07-01"),
+        Some(5.0),
+        None,
+    );
+    assert!(result.is_ok());
+
+    let cancellations = db.list_cancellations(None).unwrap();
+    assert_eq!(cancellations.len(), 1);
+    assert_eq!(
+        cancellations[0].method,
+        Some(hone_core::models::CancellationMethod::Phone)
+    );
+    assert_eq!(
+        cancellations[0].confirmation_number.as_deref(),
+        Some("HX-4412")
+    );
+    assert_eq!(cancellations[0].expected_refund, Some(5.0));
+
+    assert!(commands::cmd_subscriptions_cancellations(&db, Some("pending")).is_ok());
+    assert!(commands::cmd_subscriptions_cancellations(&db, Some("bogus")).is_err());
+
+    let result = commands::cmd_subscriptions_cancel(
+        &db,
+        "Hulu",
+        None,
+        Some("carrier-pigeon"),
+        None,
+        None,
+        None,
+        None,
+    );
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_open_db_unencrypted() {
     use tempfile::tempdir;
@@ -1609,3 +1700,105 @@ fn test_cmd_export_full() {
         .iter()
         .any(|t| t.description.contains("BACKUP TEST")));
 }
+
+#[test]
+fn test_cmd_audit_verify_and_export() {
+    use tempfile::tempdir;
+
+    let dir = tempdir().unwrap();
+    let output_path = dir.path().join("audit.csv");
+
+    let db = setup_test_db();
+    db.log_audit(
+        "a@example.com",
+        "update",
+        Some("transaction"),
+        Some(1),
+        None,
+    )
+    .unwrap();
+    db.log_audit("b@example.com", "delete", Some("tag"), Some(2), None)
+        .unwrap();
+
+    assert!(commands::cmd_audit_verify(&db).is_ok());
+
+    let filter = crate::cli::AuditFilterArgs {
+        user: Some("a@example.com".to_string()),
+        ..Default::default()
+    };
+    commands::cmd_audit_export(&db, &filter, "csv", Some(output_path.clone())).unwrap();
+    let contents = std::fs::read_to_string(&output_path).unwrap();
+    assert!(contents.contains("a@example.com"));
+    assert!(!contents.contains("b@example.com"));
+
+    assert!(commands::cmd_audit_export(&db, &filter, "xml", None).is_err());
+
+    // Out-of-range ages are an error, not a panic
+    assert!(commands::cmd_audit_archive(&db, 0).is_err());
+    assert!(commands::cmd_audit_archive(&db, i64::MAX).is_err());
+    assert!(commands::cmd_audit_archive(&db, 30).is_ok());
+
+    // Tampering makes verify fail
+    db.conn()
+        .unwrap()
+        .execute("UPDATE audit_log SET action = 'view' WHERE id = 1", [])
+        .unwrap();
+    assert!(commands::cmd_audit_verify(&db).is_err());
+}
+
+#[test]
+fn test_cmd_keys_create_and_revoke() {
+    let db = setup_test_db();
+
+    assert!(commands::cmd_keys_create(&db, "sync", "import-only", Some(30)).is_ok());
+    assert!(commands::cmd_keys_create(&db, "sync", "admin", None).is_err());
+    assert!(commands::cmd_keys_create(&db, "bad", "superuser", None).is_err());
+    assert!(commands::cmd_keys_create(&db, "bad", "admin", Some(0)).is_err());
+    assert!(commands::cmd_keys_create(&db, "bad", "admin", Some(i64::MAX)).is_err());
+
+    let key = db.get_api_key_by_name("sync").unwrap().unwrap();
+    assert_eq!(key.scope, hone_core::models::ApiKeyScope::ImportOnly);
+    assert_eq!(key.created_by.as_deref(), Some("cli"));
+    assert!(commands::cmd_keys_list(&db, false).is_ok());
+
+    assert!(commands::cmd_keys_revoke(&db, "sync").is_ok());
+    assert!(commands::cmd_keys_revoke(&db, &key.id.to_string()).is_err());
+    assert!(commands::cmd_keys_revoke(&db, "missing").is_err());
+    assert!(db.list_api_keys(false).unwrap().is_empty());
+}
+
+#[test]
+fn test_cmd_notify_deliveries_and_retry() {
+    use hone_core::models::NewNotificationDelivery;
+
+    let db = setup_test_db();
+    let id = db
+        .create_notification_delivery(&NewNotificationDelivery {
+            channel: "hook".to_string(),
+            source_type: "alert".to_string(),
+            source_id: Some(1),
+            event: "alert:zombie".to_string(),
+            severity: "warning".to_string(),
+            title: "Zombie subscription".to_string(),
+            body: String::new(),
+            digest: false,
+            urgent: false,
+            next_attempt_at: chrono::Utc::now(),
+        })
+        .unwrap()
+        .unwrap();
+
+    assert!(commands::cmd_notify_deliveries(&db, None, 20).is_ok());
+    assert!(commands::cmd_notify_deliveries(&db, Some("bogus"), 20).is_err());
+
+    // Pending deliveries can't be retried; failed ones can
+    assert!(commands::cmd_notify_retry(&db, id).is_err());
+    db.mark_notification_failed(id, "connection refused", None)
+        .unwrap();
+    assert!(commands::cmd_notify_deliveries(&db, Some("failed"), 20).is_ok());
+    assert!(commands::cmd_notify_retry(&db, id).is_ok());
+    assert!(commands::cmd_notify_retry(&db, 9999).is_err());
+
+    let delivery = db.get_notification_delivery(id).unwrap().unwrap();
+    assert_eq!(delivery.status, hone_core::models::DeliveryStatus::Pending);
+}
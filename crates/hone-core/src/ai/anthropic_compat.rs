@@ -35,6 +35,8 @@
 //! The `ANTHROPIC_COMPATIBLE_*` prefix makes it clear this is the local Ollama server
 //! using an Anthropic-compatible protocol, NOT the actual Anthropic cloud service.
 
+use std::ops::ControlFlow;
+
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tracing::debug;
@@ -51,6 +53,9 @@ pub struct MessagesRequest {
     pub system: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<Tool>>,
+    /// Request server-sent events instead of a single JSON body
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
 }
 
 /// Message in conversation
@@ -241,6 +246,201 @@ impl MessagesResponse {
     }
 }
 
+/// Server-sent event from a streaming Messages API response
+///
+/// Only the fields needed to rebuild the final `MessagesResponse` are modelled.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    MessageStart {
+        message: StreamMessageStart,
+    },
+    ContentBlockStart {
+        index: usize,
+        content_block: ContentBlock,
+    },
+    ContentBlockDelta {
+        index: usize,
+        delta: StreamDelta,
+    },
+    ContentBlockStop {
+        index: usize,
+    },
+    MessageDelta {
+        delta: StreamMessageDelta,
+    },
+    MessageStop,
+    Ping,
+    Error {
+        error: serde_json::Value,
+    },
+}
+
+/// Message metadata sent at the start of a stream
+#[derive(Debug, Deserialize)]
+pub struct StreamMessageStart {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub model: String,
+}
+
+/// Incremental content for a single content block
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamDelta {
+    TextDelta {
+        text: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// Top-level message changes (stop reason) sent near the end of a stream
+#[derive(Debug, Deserialize)]
+pub struct StreamMessageDelta {
+    pub stop_reason: Option<String>,
+    pub stop_sequence: Option<String>,
+}
+
+/// Rebuilds a `MessagesResponse` from a sequence of stream events
+///
+/// Tool-use inputs arrive as JSON fragments and are parsed once the block stops.
+#[derive(Debug, Default)]
+pub struct StreamAccumulator {
+    id: String,
+    model: String,
+    blocks: Vec<ContentBlock>,
+    partial_json: Vec<String>,
+    stop_reason: Option<String>,
+    stop_sequence: Option<String>,
+}
+
+impl StreamAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply an event, returning any new assistant text it carried
+    pub fn apply(&mut self, event: StreamEvent) -> Result<Option<String>> {
+        match event {
+            StreamEvent::MessageStart { message } => {
+                self.id = message.id;
+                self.model = message.model;
+            }
+            StreamEvent::ContentBlockStart {
+                index,
+                content_block,
+            } => {
+                while self.blocks.len() <= index {
+                    self.blocks.push(ContentBlock::text(""));
+                    self.partial_json.push(String::new());
+                }
+                self.blocks[index] = content_block;
+            }
+            StreamEvent::ContentBlockDelta { index, delta } => {
+                let block = self.blocks.get_mut(index).ok_or_else(|| {
+                    Error::InvalidData(format!("Stream delta for unknown block {}", index))
+                })?;
+                match (block, delta) {
+                    (ContentBlock::Text { text }, StreamDelta::TextDelta { text: delta }) => {
+                        text.push_str(&delta);
+                        return Ok(Some(delta));
+                    }
+                    (
+                        ContentBlock::ToolUse { .. },
+                        StreamDelta::InputJsonDelta { partial_json },
+                    ) => {
+                        self.partial_json[index].push_str(&partial_json);
+                    }
+                    _ => {}
+                }
+            }
+            StreamEvent::ContentBlockStop { index } => {
+                let json = self
+                    .partial_json
+                    .get_mut(index)
+                    .map(std::mem::take)
+                    .unwrap_or_default();
+                if let Some(ContentBlock::ToolUse { input, .. }) = self.blocks.get_mut(index) {
+                    if !json.trim().is_empty() {
+                        *input = serde_json::from_str(&json)?;
+                    }
+                }
+            }
+            StreamEvent::MessageDelta { delta } => {
+                self.stop_reason = delta.stop_reason;
+                self.stop_sequence = delta.stop_sequence;
+            }
+            StreamEvent::MessageStop | StreamEvent::Ping => {}
+            StreamEvent::Error { error } => {
+                return Err(Error::InvalidData(format!(
+                    "Anthropic-compat stream error: {}",
+                    error
+                )));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Finish the stream and return the assembled response
+    pub fn finish(self) -> MessagesResponse {
+        MessagesResponse {
+            id: self.id,
+            response_type: "message".to_string(),
+            role: "assistant".to_string(),
+            content: self.blocks,
+            model: self.model,
+            stop_reason: self.stop_reason,
+            stop_sequence: self.stop_sequence,
+            usage: None,
+        }
+    }
+}
+
+/// Drain complete server-sent events from `buffer`, returning their `data:` payloads
+///
+/// The buffer holds raw bytes so a UTF-8 character split across network
+/// chunks is only decoded once its event is complete. Incomplete trailing
+/// events are left in the buffer for the next chunk.
+fn drain_sse_data(buffer: &mut Vec<u8>) -> Vec<String> {
+    let mut payloads = Vec::new();
+    while let Some((end, terminator)) = sse_event_end(buffer) {
+        let event: Vec<u8> = buffer.drain(..end + terminator).collect();
+        let event = String::from_utf8_lossy(&event[..end]);
+        let data: Vec<&str> = event
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|d| d.trim_start().trim_end_matches('\r'))
+            .collect();
+        if !data.is_empty() {
+            payloads.push(data.join("\n"));
+        }
+    }
+    payloads
+}
+
+/// Position and length of the first blank line ending an event (`\n\n` or `\n\r\n`)
+fn sse_event_end(buffer: &[u8]) -> Option<(usize, usize)> {
+    buffer
+        .iter()
+        .enumerate()
+        .filter(|(_, &b)| b == b'\n')
+        .find_map(|(i, _)| {
+            let rest = &buffer[i + 1..];
+            if rest.starts_with(b"\n") {
+                Some((i, 2))
+            } else if rest.starts_with(b"\r\n") {
+                Some((i, 3))
+            } else {
+                None
+            }
+        })
+}
+
 /// Anthropic-compatible backend for Ollama (local only - no cloud!)
 ///
 /// Uses Ollama's Anthropic Messages API (`/v1/messages`) for tool-calling.
@@ -316,6 +516,7 @@ impl AnthropicCompatBackend {
             messages,
             system: system.map(String::from),
             tools: tools.map(|t| t.to_vec()),
+            stream: false,
         };
 
         debug!(
@@ -353,6 +554,81 @@ impl AnthropicCompatBackend {
         Ok(messages_response)
     }
 
+    /// Send a streaming messages request, reporting assistant text as it arrives
+    ///
+    /// `on_text` is called with each text fragment; returning `ControlFlow::Break`
+    /// closes the connection and fails with `Error::Cancelled`. The assembled
+    /// response (including any tool_use blocks) is returned once the stream ends.
+    pub async fn messages_streaming<F>(
+        &self,
+        system: Option<&str>,
+        messages: Vec<Message>,
+        tools: Option<&[Tool]>,
+        mut on_text: F,
+    ) -> Result<MessagesResponse>
+    where
+        F: FnMut(&str) -> ControlFlow<()>,
+    {
+        let request = MessagesRequest {
+            model: self.model.clone(),
+            max_tokens: 4096,
+            messages,
+            system: system.map(String::from),
+            tools: tools.map(|t| t.to_vec()),
+            stream: true,
+        };
+
+        debug!(
+            model = %self.model,
+            tools_count = tools.map(|t| t.len()).unwrap_or(0),
+            "Sending streaming Anthropic-compat request to local Ollama"
+        );
+
+        let mut response = self
+            .http_client
+            .post(format!("{}/v1/messages", self.base_url))
+            .header("x-api-key", "ollama") // Ollama ignores but requires
+            .header("anthropic-version", "2023-06-01")
+            .json(&request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::InvalidData(format!(
+                "Anthropic-compat API error ({}): {}",
+                status, body
+            )));
+        }
+
+        let mut accumulator = StreamAccumulator::new();
+        let mut buffer: Vec<u8> = Vec::new();
+
+        while let Some(chunk) = response.chunk().await? {
+            buffer.extend_from_slice(&chunk);
+
+            for data in drain_sse_data(&mut buffer) {
+                let event: StreamEvent = serde_json::from_str(&data)?;
+                if let Some(text) = accumulator.apply(event)? {
+                    if on_text(&text).is_break() {
+                        return Err(Error::Cancelled("Streaming request cancelled".into()));
+                    }
+                }
+            }
+        }
+
+        let messages_response = accumulator.finish();
+
+        debug!(
+            stop_reason = ?messages_response.stop_reason,
+            tool_uses = messages_response.tool_uses().len(),
+            "Received streaming Anthropic-compat response from local Ollama"
+        );
+
+        Ok(messages_response)
+    }
+
     /// Simple text completion without tools
     ///
     /// Convenience method for non-agentic use cases.
@@ -681,6 +957,7 @@ mod tests {
             messages: vec![Message::user("Hello")],
             system: Some("You are a helpful assistant.".to_string()),
             tools: None,
+            stream: false,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -704,11 +981,106 @@ mod tests {
             messages: vec![Message::user("Search for stuff")],
             system: None,
             tools: Some(tools),
+            stream: false,
         };
 
         let json = serde_json::to_string(&request).unwrap();
         assert!(json.contains("search"));
         assert!(json.contains("Search for items"));
+        assert!(!json.contains("stream"));
+    }
+
+    #[test]
+    fn test_drain_sse_data_keeps_partial_event() {
+        let mut buffer =
+            b"event: ping\ndata: {\"type\":\"ping\"}\n\nevent: message_stop\ndata: {\"type\""
+                .to_vec();
+
+        let payloads = drain_sse_data(&mut buffer);
+        assert_eq!(payloads, vec![r#"{"type":"ping"}"#.to_string()]);
+        assert_eq!(buffer, b"event: message_stop\ndata: {\"type\"");
+    }
+
+    #[test]
+    fn test_drain_sse_data_handles_crlf() {
+        let mut buffer = b"event: ping\r\ndata: {\"type\":\"ping\"}\r\n\r\nevent: x".to_vec();
+
+        let payloads = drain_sse_data(&mut buffer);
+        assert_eq!(payloads, vec![r#"{"type":"ping"}"#.to_string()]);
+        assert_eq!(buffer, b"event: x");
+    }
+
+    #[test]
+    fn test_drain_sse_data_character_split_across_chunks() {
+        let event = "data: {\"text\":\"caf\u{e9} \u{1f355}\"}\n\n".as_bytes();
+        // Split inside the two-byte "é" and again inside the four-byte emoji
+        let e_acute = event.iter().position(|&b| b == 0xc3).unwrap();
+        let emoji = event.iter().position(|&b| b == 0xf0).unwrap();
+        let chunks = [
+            &event[..e_acute + 1],
+            &event[e_acute + 1..emoji + 2],
+            &event[emoji + 2..],
+        ];
+
+        let mut buffer = Vec::new();
+        let mut payloads = Vec::new();
+        for chunk in chunks {
+            buffer.extend_from_slice(chunk);
+            payloads.extend(drain_sse_data(&mut buffer));
+        }
+        assert_eq!(
+            payloads,
+            vec!["{\"text\":\"caf\u{e9} \u{1f355}\"}".to_string()]
+        );
+        assert!(!payloads[0].contains('\u{fffd}'));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_stream_accumulator_text_and_tool_use() {
+        let events = [
+            r#"{"type":"message_start","message":{"id":"msg_1","model":"test-model"}}"#,
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Let me "}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"check."}}"#,
+            r#"{"type":"content_block_stop","index":0}"#,
+            r#"{"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"tu_1","name":"get_alerts","input":{}}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"limit\":"}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"5}"}}"#,
+            r#"{"type":"content_block_stop","index":1}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"tool_use","stop_sequence":null}}"#,
+            r#"{"type":"message_stop"}"#,
+        ];
+
+        let mut accumulator = StreamAccumulator::new();
+        let mut deltas = Vec::new();
+        for data in events {
+            let event: StreamEvent = serde_json::from_str(data).unwrap();
+            if let Some(text) = accumulator.apply(event).unwrap() {
+                deltas.push(text);
+            }
+        }
+
+        assert_eq!(deltas, vec!["Let me ", "check."]);
+
+        let response = accumulator.finish();
+        assert_eq!(response.id, "msg_1");
+        assert_eq!(response.text(), Some("Let me check.".to_string()));
+        assert!(response.has_tool_use());
+        let tool_uses = response.tool_uses();
+        assert_eq!(tool_uses[0].1, "get_alerts");
+        assert_eq!(tool_uses[0].2["limit"], 5);
+    }
+
+    #[test]
+    fn test_stream_accumulator_error_event() {
+        let event: StreamEvent = serde_json::from_str(
+            r#"{"type":"error","error":{"type":"overloaded_error","message":"busy"}}"#,
+        )
+        .unwrap();
+
+        let mut accumulator = StreamAccumulator::new();
+        assert!(accumulator.apply(event).is_err());
     }
 
     #[tokio::test]
@@ -6,6 +6,7 @@
 use async_trait::async_trait;
 
 use crate::error::Result;
+use crate::model_router::TaskType;
 use crate::models::SpendingChangeExplanation;
 
 use super::types::{
@@ -160,6 +161,22 @@ impl AIBackend for MockBackend {
         })
     }
 
+    async fn parse_receipt_text(&self, text: &str) -> Result<ParsedReceipt> {
+        // Text without a total isn't treated as a receipt
+        if !text.to_lowercase().contains("total") {
+            return Ok(ParsedReceipt {
+                merchant: None,
+                date: None,
+                items: Vec::new(),
+                subtotal: None,
+                tax: None,
+                tip: None,
+                total: None,
+            });
+        }
+        self.parse_receipt(text.as_bytes(), None).await
+    }
+
     async fn suggest_entity(
         &self,
         merchant: &str,
@@ -337,6 +354,10 @@ impl AIBackend for MockBackend {
             task_models: vec![],
         }
     }
+
+    fn task_model(&self, _task: TaskType) -> String {
+        "mock".to_string()
+    }
 }
 
 #[cfg(test)]
@@ -38,18 +38,24 @@ mod ollama;
 mod openai_compatible;
 pub mod orchestrator;
 pub mod parsing;
+pub mod receipts;
 pub mod types;
 
 pub use anthropic_compat::{AnthropicCompatBackend, Message};
 pub use mock::MockBackend;
 pub use ollama::OllamaBackend;
 pub use openai_compatible::OpenAICompatibleBackend;
-pub use orchestrator::{AIOrchestrator, OrchestratorResult, ToolCallRecord};
+pub use orchestrator::{
+    AIOrchestrator, OrchestratorEvent, OrchestratorEventCallback, OrchestratorResult,
+    ToolCallRecord,
+};
+pub use receipts::{parse_receipt_document, ReceiptFormat, ReceiptParse, ReceiptParsePath};
 pub use types::*;
 
 use async_trait::async_trait;
 
 use crate::error::Result;
+use crate::model_router::TaskType;
 use crate::models::SpendingChangeExplanation;
 
 /// Trait defining the interface for all AI backends
@@ -81,13 +87,18 @@ pub trait AIBackend: Send + Sync {
         context: &MerchantContext,
     ) -> Result<String>;
 
-    /// Parse a receipt image and extract line items
+    /// Parse a receipt image and extract line items (`Vision` task)
     async fn parse_receipt(
         &self,
         image_data: &[u8],
         vision_model: Option<&str>,
     ) -> Result<ParsedReceipt>;
 
+    /// Parse the text of a digital receipt (HTML email body, PDF text layer)
+    ///
+    /// Uses the `StructuredExtraction` task model.
+    async fn parse_receipt_text(&self, text: &str) -> Result<ParsedReceipt>;
+
     /// Suggest an entity for a transaction
     async fn suggest_entity(
         &self,
@@ -146,6 +157,9 @@ pub trait AIBackend: Send + Sync {
 
     /// Get router configuration info
     fn router_info(&self) -> RouterInfo;
+
+    /// Model that handles a task type (for metrics)
+    fn task_model(&self, task: TaskType) -> String;
 }
 
 /// Concrete AI client enum
@@ -277,6 +291,14 @@ impl AIBackend for AIClient {
         }
     }
 
+    async fn parse_receipt_text(&self, text: &str) -> Result<ParsedReceipt> {
+        match self {
+            AIClient::Ollama(b) => b.parse_receipt_text(text).await,
+            AIClient::OpenAICompatible(b) => b.parse_receipt_text(text).await,
+            AIClient::Mock(b) => b.parse_receipt_text(text).await,
+        }
+    }
+
     async fn suggest_entity(
         &self,
         merchant: &str,
@@ -463,6 +485,14 @@ impl AIBackend for AIClient {
             AIClient::Mock(b) => b.router_info(),
         }
     }
+
+    fn task_model(&self, task: TaskType) -> String {
+        match self {
+            AIClient::Ollama(b) => b.task_model(task),
+            AIClient::OpenAICompatible(b) => b.task_model(task),
+            AIClient::Mock(b) => b.task_model(task),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -309,7 +309,9 @@ impl AIBackend for OllamaBackend {
         image_data: &[u8],
         vision_model: Option<&str>,
     ) -> Result<ParsedReceipt> {
-        let model = vision_model.unwrap_or("llava");
+        let model = vision_model
+            .map(str::to_string)
+            .unwrap_or_else(|| self.task_model(TaskType::Vision));
         let base64_image = base64::engine::general_purpose::STANDARD.encode(image_data);
 
         let prompt = {
@@ -323,7 +325,7 @@ impl AIBackend for OllamaBackend {
         };
 
         let request = OllamaVisionRequest {
-            model: model.to_string(),
+            model,
             prompt,
             images: vec![base64_image],
             stream: false,
@@ -349,6 +351,44 @@ impl AIBackend for OllamaBackend {
         parse_receipt_response(&ollama_response.response)
     }
 
+    async fn parse_receipt_text(&self, text: &str) -> Result<ParsedReceipt> {
+        let prompt = {
+            let mut prompts = self
+                .prompts
+                .write()
+                .map_err(|_| Error::InvalidData("Failed to acquire prompt library lock".into()))?;
+            let template = prompts.get(PromptId::ParseReceiptText)?;
+            let mut vars = HashMap::new();
+            vars.insert("receipt_text", text);
+            template.render_user(&vars)
+        };
+
+        let request = OllamaRequest {
+            model: self.task_model(TaskType::StructuredExtraction),
+            prompt,
+            stream: false,
+        };
+
+        let response = self
+            .http_client
+            .post(format!("{}/api/generate", self.base_url))
+            .json(&request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Error::Http(response.error_for_status().unwrap_err()));
+        }
+
+        let ollama_response: OllamaResponse = response.json().await?;
+        debug!(
+            "Ollama receipt text parsing response: {}",
+            ollama_response.response
+        );
+
+        parse_receipt_response(&ollama_response.response)
+    }
+
     async fn suggest_entity(
         &self,
         merchant: &str,
@@ -720,4 +760,11 @@ impl AIBackend for OllamaBackend {
             task_models,
         }
     }
+
+    fn task_model(&self, task: TaskType) -> String {
+        self.router
+            .read()
+            .map(|router| router.model_for_task(task).to_string())
+            .unwrap_or_else(|_| self.default_model.clone())
+    }
 }
@@ -24,7 +24,7 @@ use serde::{Deserialize, Serialize};
 use tracing::{debug, warn};
 
 use crate::error::{Error, Result};
-use crate::model_router::ModelRouter;
+use crate::model_router::{ModelRouter, TaskType};
 use crate::models::SpendingChangeExplanation;
 use crate::prompts::{PromptId, PromptLibrary};
 
@@ -427,6 +427,27 @@ impl AIBackend for OpenAICompatibleBackend {
         parse_receipt_response(&response)
     }
 
+    async fn parse_receipt_text(&self, text: &str) -> Result<ParsedReceipt> {
+        let prompt = {
+            let mut prompts = self
+                .prompts
+                .write()
+                .map_err(|_| Error::InvalidData("Failed to acquire prompt library lock".into()))?;
+            let template = prompts.get(PromptId::ParseReceiptText)?;
+            let mut vars = HashMap::new();
+            vars.insert("receipt_text", text);
+            template.render_user(&vars)
+        };
+
+        let response = self.chat_completion(&prompt).await?;
+        debug!(
+            "OpenAI-compatible receipt text parsing response: {}",
+            response
+        );
+
+        parse_receipt_response(&response)
+    }
+
     async fn suggest_entity(
         &self,
         merchant: &str,
@@ -700,6 +721,10 @@ impl AIBackend for OpenAICompatibleBackend {
             task_models: vec![],
         }
     }
+
+    fn task_model(&self, _task: TaskType) -> String {
+        self.model.clone()
+    }
 }
 
 #[cfg(test)]
@@ -35,6 +35,9 @@
 //! ).await?;
 //! ```
 
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
@@ -80,6 +83,47 @@ pub struct OrchestratorResult {
     pub iterations: usize,
 }
 
+/// Progress event emitted while a streaming orchestrator run is in flight
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OrchestratorEvent {
+    /// A new model round-trip started; text deltas from earlier iterations
+    /// were preamble to tool calls rather than the final answer
+    IterationStarted { iteration: usize },
+    /// Incremental assistant text from the streaming backend
+    TextDelta { text: String },
+    /// A tool call is about to execute
+    ToolCallStarted {
+        name: String,
+        input: serde_json::Value,
+    },
+    /// A tool call finished (successfully or not)
+    ToolCallFinished { call: ToolCallRecord },
+}
+
+/// Callback for orchestrator progress events
+pub type OrchestratorEventCallback = Box<dyn Fn(OrchestratorEvent) + Send + Sync>;
+
+/// Event sink and cancellation flag for a streaming run
+struct RunObserver<'a> {
+    on_event: &'a OrchestratorEventCallback,
+    cancelled: &'a AtomicBool,
+}
+
+impl RunObserver<'_> {
+    fn emit(&self, event: OrchestratorEvent) {
+        (self.on_event)(event);
+    }
+
+    fn check_cancelled(&self) -> Result<()> {
+        if self.cancelled.load(Ordering::SeqCst) {
+            Err(Error::Cancelled("Orchestrator run cancelled".into()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
 /// AI Orchestrator for agentic workflows with tool calling
 pub struct AIOrchestrator {
     backend: AnthropicCompatBackend,
@@ -127,6 +171,16 @@ impl AIOrchestrator {
         }
     }
 
+    /// Create a new orchestrator whose tools read through a different database
+    /// handle (e.g. one limited to the accounts a user may see)
+    pub fn with_db(&self, db: Database) -> Self {
+        Self {
+            backend: self.backend.clone(),
+            db,
+            max_iterations: self.max_iterations,
+        }
+    }
+
     /// Execute an agentic task with tool calling
     ///
     /// Returns the final text response after all tool calls are resolved.
@@ -170,6 +224,54 @@ impl AIOrchestrator {
         user_message: &str,
         available_tools: &[Tool],
         prior_messages: Vec<Message>,
+    ) -> Result<OrchestratorResult> {
+        self.run(
+            system_prompt,
+            user_message,
+            available_tools,
+            prior_messages,
+            None,
+        )
+        .await
+    }
+
+    /// Execute with streaming progress events and cooperative cancellation
+    ///
+    /// Behaves like `execute_with_tracking`, but streams assistant text from the
+    /// backend and reports each tool call as it starts and finishes. Setting
+    /// `cancelled` stops the run at the next text chunk or tool boundary with
+    /// `Error::Cancelled`.
+    pub async fn execute_streaming(
+        &self,
+        system_prompt: &str,
+        user_message: &str,
+        available_tools: &[Tool],
+        prior_messages: Vec<Message>,
+        on_event: &OrchestratorEventCallback,
+        cancelled: &AtomicBool,
+    ) -> Result<OrchestratorResult> {
+        let observer = RunObserver {
+            on_event,
+            cancelled,
+        };
+        self.run(
+            system_prompt,
+            user_message,
+            available_tools,
+            prior_messages,
+            Some(&observer),
+        )
+        .await
+    }
+
+    /// Shared agentic loop for tracked and streaming execution
+    async fn run(
+        &self,
+        system_prompt: &str,
+        user_message: &str,
+        available_tools: &[Tool],
+        prior_messages: Vec<Message>,
+        observer: Option<&RunObserver<'_>>,
     ) -> Result<OrchestratorResult> {
         let history_len = prior_messages.len();
         let mut messages = prior_messages.clone();
@@ -192,10 +294,33 @@ impl AIOrchestrator {
         for iteration in 0..self.max_iterations {
             debug!(iteration, "Orchestrator iteration");
 
-            let response = self
-                .backend
-                .messages(Some(system_prompt), messages.clone(), Some(available_tools))
-                .await?;
+            let response = match observer {
+                Some(observer) => {
+                    observer.check_cancelled()?;
+                    observer.emit(OrchestratorEvent::IterationStarted { iteration });
+                    self.backend
+                        .messages_streaming(
+                            Some(system_prompt),
+                            messages.clone(),
+                            Some(available_tools),
+                            |text| {
+                                if observer.cancelled.load(Ordering::SeqCst) {
+                                    return ControlFlow::Break(());
+                                }
+                                observer.emit(OrchestratorEvent::TextDelta {
+                                    text: text.to_string(),
+                                });
+                                ControlFlow::Continue(())
+                            },
+                        )
+                        .await?
+                }
+                None => {
+                    self.backend
+                        .messages(Some(system_prompt), messages.clone(), Some(available_tools))
+                        .await?
+                }
+            };
 
             // Check for tool use (proper format)
             let tool_uses = response.tool_uses();
@@ -250,29 +375,19 @@ impl AIOrchestrator {
                 for (id, name, input) in tool_uses {
                     debug!(tool = name, "Executing tool");
 
-                    let result = self.execute_tool(name, input).await;
-                    match &result {
-                        Ok(output) => {
+                    let record = self.execute_tool_observed(name, input, observer).await?;
+                    match (record.success, &record.output) {
+                        (true, Some(output)) => {
                             debug!(tool = name, output_len = output.len(), "Tool succeeded");
                             tool_results.push(ContentBlock::tool_result(id, output.clone()));
-                            tool_calls.push(ToolCallRecord {
-                                name: name.to_string(),
-                                input: input.clone(),
-                                success: true,
-                                output: Some(output.clone()),
-                            });
                         }
-                        Err(e) => {
-                            warn!(tool = name, error = %e, "Tool failed");
-                            tool_results.push(ContentBlock::tool_error(id, e.to_string()));
-                            tool_calls.push(ToolCallRecord {
-                                name: name.to_string(),
-                                input: input.clone(),
-                                success: false,
-                                output: Some(e.to_string()),
-                            });
+                        (_, output) => {
+                            let error = output.clone().unwrap_or_default();
+                            warn!(tool = name, error = %error, "Tool failed");
+                            tool_results.push(ContentBlock::tool_error(id, error));
                         }
                     }
+                    tool_calls.push(record);
                 }
 
                 // Add tool results as user message
@@ -302,39 +417,28 @@ impl AIOrchestrator {
                 for (i, call) in xml_tool_calls.iter().enumerate() {
                     debug!(tool = %call.name, "Executing XML tool");
 
-                    let result = self.execute_tool(&call.name, &call.params).await;
-                    match &result {
-                        Ok(output) => {
-                            debug!(tool = %call.name, output_len = output.len(), "Tool succeeded");
-                            tool_outputs.push(format!(
-                                "Tool {} ({}) result:\n{}",
-                                i + 1,
-                                call.name,
-                                output
-                            ));
-                            tool_calls.push(ToolCallRecord {
-                                name: call.name.clone(),
-                                input: call.params.clone(),
-                                success: true,
-                                output: Some(output.clone()),
-                            });
-                        }
-                        Err(e) => {
-                            warn!(tool = %call.name, error = %e, "Tool failed");
-                            tool_outputs.push(format!(
-                                "Tool {} ({}) error: {}",
-                                i + 1,
-                                call.name,
-                                e
-                            ));
-                            tool_calls.push(ToolCallRecord {
-                                name: call.name.clone(),
-                                input: call.params.clone(),
-                                success: false,
-                                output: Some(e.to_string()),
-                            });
-                        }
+                    let record = self
+                        .execute_tool_observed(&call.name, &call.params, observer)
+                        .await?;
+                    let output = record.output.clone().unwrap_or_default();
+                    if record.success {
+                        debug!(tool = %call.name, output_len = output.len(), "Tool succeeded");
+                        tool_outputs.push(format!(
+                            "Tool {} ({}) result:\n{}",
+                            i + 1,
+                            call.name,
+                            output
+                        ));
+                    } else {
+                        warn!(tool = %call.name, error = %output, "Tool failed");
+                        tool_outputs.push(format!(
+                            "Tool {} ({}) error: {}",
+                            i + 1,
+                            call.name,
+                            output
+                        ));
                     }
+                    tool_calls.push(record);
                 }
 
                 // Add tool results as user message (since model expects response)
@@ -356,6 +460,47 @@ impl AIOrchestrator {
         )))
     }
 
+    /// Execute a tool call, reporting start/finish to the observer (if any)
+    ///
+    /// Tool failures are captured in the returned record; only cancellation is an error.
+    async fn execute_tool_observed(
+        &self,
+        name: &str,
+        input: &serde_json::Value,
+        observer: Option<&RunObserver<'_>>,
+    ) -> Result<ToolCallRecord> {
+        if let Some(observer) = observer {
+            observer.check_cancelled()?;
+            observer.emit(OrchestratorEvent::ToolCallStarted {
+                name: name.to_string(),
+                input: input.clone(),
+            });
+        }
+
+        let record = match self.execute_tool(name, input).await {
+            Ok(output) => ToolCallRecord {
+                name: name.to_string(),
+                input: input.clone(),
+                success: true,
+                output: Some(output),
+            },
+            Err(e) => ToolCallRecord {
+                name: name.to_string(),
+                input: input.clone(),
+                success: false,
+                output: Some(e.to_string()),
+            },
+        };
+
+        if let Some(observer) = observer {
+            observer.emit(OrchestratorEvent::ToolCallFinished {
+                call: record.clone(),
+            });
+        }
+
+        Ok(record)
+    }
+
     /// Execute a single tool call
     async fn execute_tool(&self, name: &str, input: &serde_json::Value) -> Result<String> {
         match name {
@@ -408,6 +553,20 @@ impl AIOrchestrator {
                 serde_json::to_string(&result)
                     .map_err(|e| Error::InvalidData(format!("Failed to serialize: {}", e)))
             }
+            "get_item_prices" => {
+                let params: tools::ItemPricesParams = serde_json::from_value(input.clone())
+                    .map_err(|e| Error::InvalidData(format!("Invalid params: {}", e)))?;
+                let result = tools::get_item_prices(&self.db, params)?;
+                serde_json::to_string(&result)
+                    .map_err(|e| Error::InvalidData(format!("Failed to serialize: {}", e)))
+            }
+            "get_warranties" => {
+                let params: tools::WarrantiesParams = serde_json::from_value(input.clone())
+                    .map_err(|e| Error::InvalidData(format!("Invalid params: {}", e)))?;
+                let result = tools::get_warranties(&self.db, params)?;
+                serde_json::to_string(&result)
+                    .map_err(|e| Error::InvalidData(format!("Failed to serialize: {}", e)))
+            }
             _ => Err(Error::InvalidData(format!("Unknown tool: {}", name))),
         }
     }
@@ -891,4 +1050,114 @@ mod tests {
         assert_eq!(calls[0].params["include_cancelled"], true);
         assert_eq!(calls[0].params["limit"], 50);
     }
+
+    #[tokio::test]
+    async fn test_execute_with_tracking_against_mock_server() {
+        let server = crate::test_utils::MockOllamaServer::start().await;
+        let backend = AnthropicCompatBackend::new(&server.url(), "test-model");
+        let orchestrator = AIOrchestrator::new(backend, create_test_db());
+
+        let result = orchestrator
+            .execute_with_tracking("system", "Any alerts?", &tools::hone_tools(), Vec::new())
+            .await
+            .unwrap();
+
+        assert_eq!(result.response, "You have no active alerts.");
+        assert_eq!(result.iterations, 2);
+        assert_eq!(result.tool_calls.len(), 1);
+        assert_eq!(result.tool_calls[0].name, "get_alerts");
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_emits_events() {
+        use std::sync::{Arc, Mutex};
+
+        let server = crate::test_utils::MockOllamaServer::start().await;
+        let backend = AnthropicCompatBackend::new(&server.url(), "test-model");
+        let orchestrator = AIOrchestrator::new(backend, create_test_db());
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let on_event: OrchestratorEventCallback =
+            Box::new(move |event| sink.lock().unwrap().push(event));
+        let cancelled = AtomicBool::new(false);
+
+        let result = orchestrator
+            .execute_streaming(
+                "system",
+                "Any alerts?",
+                &tools::hone_tools(),
+                Vec::new(),
+                &on_event,
+                &cancelled,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result.response, "You have no active alerts.");
+        assert_eq!(result.tool_calls.len(), 1);
+
+        let events = events.lock().unwrap();
+        let kinds: Vec<&str> = events
+            .iter()
+            .map(|e| match e {
+                OrchestratorEvent::IterationStarted { .. } => "iteration",
+                OrchestratorEvent::TextDelta { .. } => "text",
+                OrchestratorEvent::ToolCallStarted { .. } => "tool_start",
+                OrchestratorEvent::ToolCallFinished { .. } => "tool_finish",
+            })
+            .collect();
+        assert_eq!(kinds.first(), Some(&"iteration"));
+        let start = kinds.iter().position(|k| *k == "tool_start").unwrap();
+        let finish = kinds.iter().position(|k| *k == "tool_finish").unwrap();
+        assert!(start < finish);
+        assert_eq!(kinds.iter().filter(|k| **k == "iteration").count(), 2);
+
+        let final_text: String = events
+            .iter()
+            .skip(finish)
+            .filter_map(|e| match e {
+                OrchestratorEvent::TextDelta { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(final_text, "You have no active alerts.");
+
+        match &events[finish] {
+            OrchestratorEvent::ToolCallFinished { call } => {
+                assert_eq!(call.name, "get_alerts");
+                assert!(call.success);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_streaming_cancelled() {
+        let server = crate::test_utils::MockOllamaServer::start().await;
+        let backend = AnthropicCompatBackend::new(&server.url(), "test-model");
+        let orchestrator = AIOrchestrator::new(backend, create_test_db());
+
+        let cancelled = std::sync::Arc::new(AtomicBool::new(false));
+        let flag = cancelled.clone();
+        // Cancel as soon as the first tool call starts
+        let on_event: OrchestratorEventCallback = Box::new(move |event| {
+            if matches!(event, OrchestratorEvent::ToolCallStarted { .. }) {
+                flag.store(true, Ordering::SeqCst);
+            }
+        });
+
+        let result = orchestrator
+            .execute_streaming(
+                "system",
+                "Any alerts?",
+                &tools::hone_tools(),
+                Vec::new(),
+                &on_event,
+                &cancelled,
+            )
+            .await;
+
+        assert!(matches!(result, Err(Error::Cancelled(_))));
+    }
 }
@@ -0,0 +1,544 @@
+//! Receipt document pipeline
+//!
+//! Receipts arrive as photos, PDFs, HTML emails and plain text. Text is
+//! cheaper and more accurate than pixels, so every input is reduced to text
+//! first when it can be and sent to the `StructuredExtraction` model; only
+//! photos and PDFs without a text layer (scans) go to the `Vision` model.
+//!
+//! | Input               | Path         | Task                    |
+//! |---------------------|--------------|-------------------------|
+//! | Image               | `vision`     | `vision`                |
+//! | PDF with text layer | `pdf_text`   | `structured_extraction` |
+//! | Scanned PDF         | `pdf_raster` | `vision`                |
+//! | HTML                | `html`       | `structured_extraction` |
+//! | Plain text          | `text`       | `structured_extraction` |
+//!
+//! Every parse is recorded in `ollama_metrics` as `parse_receipt` with the
+//! path, input format and task in its metadata.
+//!
+//! Scanned PDFs are rasterized with poppler's `pdftoppm` (first page only).
+//! Set `HONE_PDFTOPPM` to point at the binary if it isn't on `PATH`.
+
+use std::time::Instant;
+
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::mail::{html_to_text, truncate};
+use crate::model_router::TaskType;
+use crate::models::{NewOllamaMetric, OllamaOperation};
+
+use super::{AIBackend, AIClient, ParsedReceipt};
+
+/// A PDF text layer shorter than this (or without a digit) is treated as a scan
+const MIN_PDF_TEXT_CHARS: usize = 20;
+
+/// Rasterization resolution; enough for receipt-sized print
+const RASTER_DPI: u32 = 150;
+
+/// How a receipt file is encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptFormat {
+    Image,
+    Pdf,
+    Html,
+    Text,
+}
+
+impl ReceiptFormat {
+    /// Sniff the format from file contents
+    ///
+    /// Anything that isn't a PDF, HTML or readable text is treated as an
+    /// image and left for the vision model to judge.
+    pub fn detect(data: &[u8]) -> Self {
+        if data.starts_with(b"%PDF-") {
+            return ReceiptFormat::Pdf;
+        }
+        if image_extension(data).is_some() {
+            return ReceiptFormat::Image;
+        }
+        match std::str::from_utf8(data) {
+            Ok(text) => {
+                let head: String = text.trim_start().chars().take(1024).collect();
+                let head = head.to_lowercase();
+                if head.starts_with("<!doctype html")
+                    || head.starts_with("<html")
+                    || head.contains("<body")
+                {
+                    ReceiptFormat::Html
+                } else if text.chars().any(|c| c.is_control() && !c.is_whitespace()) {
+                    ReceiptFormat::Image
+                } else {
+                    ReceiptFormat::Text
+                }
+            }
+            Err(_) => ReceiptFormat::Image,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReceiptFormat::Image => "image",
+            ReceiptFormat::Pdf => "pdf",
+            ReceiptFormat::Html => "html",
+            ReceiptFormat::Text => "text",
+        }
+    }
+
+    /// File extension to store a receipt of this format under
+    pub fn extension(&self, data: &[u8]) -> &'static str {
+        match self {
+            ReceiptFormat::Image => image_extension(data).unwrap_or("jpg"),
+            ReceiptFormat::Pdf => "pdf",
+            ReceiptFormat::Html => "html",
+            ReceiptFormat::Text => "txt",
+        }
+    }
+}
+
+/// Image type from magic bytes
+fn image_extension(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("jpg")
+    } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("png")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("gif")
+    } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        Some("webp")
+    } else if data.len() >= 12
+        && &data[4..8] == b"ftyp"
+        && matches!(&data[8..12], b"heic" | b"heix" | b"mif1" | b"msf1")
+    {
+        Some("heic")
+    } else {
+        None
+    }
+}
+
+/// Which route a receipt took to the model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptParsePath {
+    /// Photo sent to the vision model
+    Vision,
+    /// PDF text layer sent to the extraction model
+    PdfText,
+    /// Scanned PDF rasterized and sent to the vision model
+    PdfRaster,
+    /// HTML reduced to text
+    Html,
+    /// Plain text as-is
+    Text,
+}
+
+impl ReceiptParsePath {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReceiptParsePath::Vision => "vision",
+            ReceiptParsePath::PdfText => "pdf_text",
+            ReceiptParsePath::PdfRaster => "pdf_raster",
+            ReceiptParsePath::Html => "html",
+            ReceiptParsePath::Text => "text",
+        }
+    }
+
+    pub fn task(&self) -> TaskType {
+        match self {
+            ReceiptParsePath::Vision | ReceiptParsePath::PdfRaster => TaskType::Vision,
+            _ => TaskType::StructuredExtraction,
+        }
+    }
+}
+
+/// A parsed receipt and how it was parsed
+#[derive(Debug, Clone)]
+pub struct ReceiptParse {
+    pub receipt: ParsedReceipt,
+    pub path: ReceiptParsePath,
+    pub model: String,
+}
+
+/// Model input after format handling
+enum ReceiptInput {
+    Image(Vec<u8>),
+    Text(String),
+}
+
+/// Parse a receipt file of any supported format
+///
+/// Records the attempt (success or failure) in `ollama_metrics`.
+pub async fn parse_receipt_document(
+    ai: &AIClient,
+    db: &Database,
+    data: &[u8],
+    format: ReceiptFormat,
+    transaction_id: Option<i64>,
+) -> Result<ReceiptParse> {
+    let (path, input) = match format {
+        ReceiptFormat::Image => (
+            ReceiptParsePath::Vision,
+            Ok(ReceiptInput::Image(data.to_vec())),
+        ),
+        ReceiptFormat::Html => {
+            let text = html_to_text(&String::from_utf8_lossy(data));
+            (ReceiptParsePath::Html, readable(text))
+        }
+        ReceiptFormat::Text => {
+            let text = String::from_utf8_lossy(data).trim().to_string();
+            (ReceiptParsePath::Text, readable(text))
+        }
+        ReceiptFormat::Pdf => match pdf_text(data).await {
+            Some(text) => (ReceiptParsePath::PdfText, Ok(ReceiptInput::Text(text))),
+            None => (
+                ReceiptParsePath::PdfRaster,
+                rasterize_pdf(data).await.map(ReceiptInput::Image),
+            ),
+        },
+    };
+
+    let task = path.task();
+    let model = ai.task_model(task);
+    let (text_chars, input_text) = match &input {
+        Ok(ReceiptInput::Text(text)) => (
+            Some(text.chars().count()),
+            Some(text.chars().take(500).collect::<String>()),
+        ),
+        _ => (None, None),
+    };
+    debug!(
+        "Parsing {} receipt via {} ({})",
+        format.as_str(),
+        path.as_str(),
+        model
+    );
+
+    let start = Instant::now();
+    let result = match input {
+        Ok(ReceiptInput::Image(image)) => ai.parse_receipt(&image, None).await,
+        Ok(ReceiptInput::Text(text)) => ai.parse_receipt_text(&text).await,
+        Err(e) => Err(e),
+    };
+    let latency_ms = start.elapsed().as_millis() as i64;
+    let metric = NewOllamaMetric {
+        operation: OllamaOperation::ParseReceipt,
+        model: model.clone(),
+        latency_ms,
+        success: result.is_ok(),
+        error_message: result.as_ref().err().map(|e| e.to_string()),
+        confidence: None,
+        transaction_id,
+        input_text,
+        result_text: result.as_ref().ok().map(|r| {
+            format!(
+                "{} ${:.2} ({} items)",
+                r.merchant.as_deref().unwrap_or("?"),
+                r.total.unwrap_or(0.0),
+                r.items.len()
+            )
+        }),
+        metadata: Some(
+            serde_json::json!({
+                "path": path.as_str(),
+                "format": format.as_str(),
+                "task": task.as_str(),
+                "text_chars": text_chars,
+            })
+            .to_string(),
+        ),
+    };
+    if let Err(e) = db.record_ollama_metric(&metric) {
+        warn!("Failed to record Ollama metric: {}", e);
+    }
+
+    Ok(ReceiptParse {
+        receipt: result?,
+        path,
+        model,
+    })
+}
+
+/// Text input, or an error when there's nothing to read
+fn readable(text: String) -> Result<ReceiptInput> {
+    if text.is_empty() {
+        return Err(Error::InvalidData("Receipt has no readable text".into()));
+    }
+    Ok(ReceiptInput::Text(truncate(text)))
+}
+
+/// Usable text layer of a PDF, off the async runtime
+async fn pdf_text(data: &[u8]) -> Option<String> {
+    let data = data.to_vec();
+    tokio::task::spawn_blocking(move || extract_pdf_text(&data))
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Text layer of a PDF, or None for scans and unreadable files
+///
+/// Whitespace is collapsed per line. A layer too short to hold a receipt
+/// (or without a single digit) counts as no text.
+pub fn extract_pdf_text(data: &[u8]) -> Option<String> {
+    // pdf-extract panics on some malformed files instead of erroring
+    let raw = std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem(data))
+        .ok()?
+        .map_err(|e| debug!("PDF text extraction failed: {}", e))
+        .ok()?;
+
+    let text = raw
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.chars().count() < MIN_PDF_TEXT_CHARS || !text.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(truncate(text))
+}
+
+/// Render the first page of a PDF to PNG with `pdftoppm`
+pub async fn rasterize_pdf(data: &[u8]) -> Result<Vec<u8>> {
+    let binary = std::env::var("HONE_PDFTOPPM").unwrap_or_else(|_| "pdftoppm".to_string());
+    rasterize_pdf_with(&binary, data).await
+}
+
+/// Render the first page of a PDF to PNG with a specific `pdftoppm` binary
+pub async fn rasterize_pdf_with(binary: &str, data: &[u8]) -> Result<Vec<u8>> {
+    let dir = tempfile::tempdir()?;
+    let input = dir.path().join("receipt.pdf");
+    let output = dir.path().join("page");
+    tokio::fs::write(&input, data).await?;
+
+    let result = tokio::process::Command::new(binary)
+        .arg("-png")
+        .arg("-r")
+        .arg(RASTER_DPI.to_string())
+        .args(["-f", "1", "-l", "1", "-singlefile"])
+        .arg(&input)
+        .arg(&output)
+        .output()
+        .await;
+    let result = match result {
+        Ok(result) => result,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(Error::InvalidData(format!(
+                "PDF has no text layer and {} was not found; install poppler-utils or set HONE_PDFTOPPM",
+                binary
+            )));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    if !result.status.success() {
+        return Err(Error::InvalidData(format!(
+            "Failed to rasterize PDF: {}",
+            String::from_utf8_lossy(&result.stderr).trim()
+        )));
+    }
+
+    Ok(tokio::fs::read(output.with_extension("png")).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A one-page PDF; `None` gives a page with no text layer (like a scan)
+    fn pdf(text: Option<&str>) -> Vec<u8> {
+        let stream = text
+            .map(|text| {
+                let mut ops = String::from("BT /F1 12 Tf 72 720 Td 14 TL\n");
+                for line in text.lines() {
+                    ops.push_str(&format!("({}) Tj T*\n", line));
+                }
+                ops.push_str("ET");
+                ops
+            })
+            .unwrap_or_default();
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Contents 4 0 R \
+             /Resources << /Font << /F1 5 0 R >> >> >>"
+                .to_string(),
+            format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                stream.len(),
+                stream
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+        let mut out = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+        }
+        let xref = out.len();
+        out.extend_from_slice(
+            format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+        );
+        for offset in offsets {
+            out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref
+            )
+            .as_bytes(),
+        );
+        out
+    }
+
+    fn last_metric(db: &Database) -> (String, bool, serde_json::Value) {
+        let metric = db
+            .get_recent_ollama_calls(100)
+            .unwrap()
+            .into_iter()
+            .max_by_key(|m| m.id)
+            .expect("metric recorded");
+        assert_eq!(metric.operation, OllamaOperation::ParseReceipt);
+        let metadata = serde_json::from_str(metric.metadata.as_deref().unwrap()).unwrap();
+        (metric.model, metric.success, metadata)
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(ReceiptFormat::detect(&pdf(None)), ReceiptFormat::Pdf);
+        assert_eq!(
+            ReceiptFormat::detect(b"\x89PNG\r\n\x1a\n\0\0"),
+            ReceiptFormat::Image
+        );
+        assert_eq!(
+            ReceiptFormat::detect(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            ReceiptFormat::Image
+        );
+        assert_eq!(
+            ReceiptFormat::detect(b"\n<!DOCTYPE html><html><body>Total $5.00</body></html>"),
+            ReceiptFormat::Html
+        );
+        assert_eq!(
+            ReceiptFormat::detect(b"Corner Cafe\nTotal 5.00\n"),
+            ReceiptFormat::Text
+        );
+        assert_eq!(ReceiptFormat::detect(&[0, 1, 2, 3]), ReceiptFormat::Image);
+
+        assert_eq!(ReceiptFormat::Image.extension(b"GIF89a"), "gif");
+        assert_eq!(ReceiptFormat::Image.extension(&[1, 2, 3]), "jpg");
+        assert_eq!(ReceiptFormat::Pdf.extension(b""), "pdf");
+    }
+
+    #[test]
+    fn test_extract_pdf_text() {
+        let text = extract_pdf_text(&pdf(Some("Hardware Store\nHammer 24.99\nTotal 26.99")));
+        assert_eq!(
+            text.as_deref(),
+            Some("Hardware Store\nHammer 24.99\nTotal 26.99")
+        );
+        // No text layer, too little text, or not a PDF at all
+        assert_eq!(extract_pdf_text(&pdf(None)), None);
+        assert_eq!(extract_pdf_text(&pdf(Some("Page 1"))), None);
+        assert_eq!(extract_pdf_text(b"%PDF-1.4 garbage"), None);
+    }
+
+    #[tokio::test]
+    async fn test_text_inputs_use_structured_extraction() {
+        let db = Database::in_memory().unwrap();
+        let ai = AIClient::mock();
+
+        let data = pdf(Some("Hardware Store\nHammer 24.99\nTotal 26.99"));
+        let parse = parse_receipt_document(&ai, &db, &data, ReceiptFormat::Pdf, None)
+            .await
+            .unwrap();
+        assert_eq!(parse.path, ReceiptParsePath::PdfText);
+        assert_eq!(parse.receipt.merchant.as_deref(), Some("Mock Store"));
+        let (model, success, metadata) = last_metric(&db);
+        assert_eq!(model, "mock");
+        assert!(success);
+        assert_eq!(metadata["path"], "pdf_text");
+        assert_eq!(metadata["format"], "pdf");
+        assert_eq!(metadata["task"], "structured_extraction");
+        assert_eq!(metadata["text_chars"], 39);
+
+        let html = b"<html><body><table><tr><td>Latte</td><td>$4.50</td></tr>\
+                     <tr><td>Total</td><td>$4.50</td></tr></table></body></html>";
+        let parse = parse_receipt_document(&ai, &db, html, ReceiptFormat::Html, Some(7))
+            .await
+            .unwrap();
+        assert_eq!(parse.path, ReceiptParsePath::Html);
+        let (_, _, metadata) = last_metric(&db);
+        assert_eq!(metadata["path"], "html");
+        assert_eq!(metadata["task"], "structured_extraction");
+    }
+
+    #[tokio::test]
+    async fn test_images_use_vision() {
+        let db = Database::in_memory().unwrap();
+        let ai = AIClient::mock();
+
+        let parse =
+            parse_receipt_document(&ai, &db, &[0xFF, 0xD8, 0xFF], ReceiptFormat::Image, None)
+                .await
+                .unwrap();
+        assert_eq!(parse.path, ReceiptParsePath::Vision);
+        let (_, success, metadata) = last_metric(&db);
+        assert!(success);
+        assert_eq!(metadata["path"], "vision");
+        assert_eq!(metadata["task"], "vision");
+        assert!(metadata["text_chars"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_empty_html_fails_and_is_recorded() {
+        let db = Database::in_memory().unwrap();
+        let ai = AIClient::mock();
+
+        let result = parse_receipt_document(
+            &ai,
+            &db,
+            b"<html><head><style>p{}</style></head><body></body></html>",
+            ReceiptFormat::Html,
+            None,
+        )
+        .await;
+        assert!(matches!(result, Err(Error::InvalidData(_))));
+        let (_, success, metadata) = last_metric(&db);
+        assert!(!success);
+        assert_eq!(metadata["path"], "html");
+    }
+
+    #[tokio::test]
+    async fn test_rasterize_pdf_with() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // Stand-in for pdftoppm: writes "<last arg>.png"
+        let script = dir.path().join("pdftoppm");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\nfor last in \"$@\"; do :; done\nprintf 'PNGDATA' > \"$last.png\"\n",
+        )
+        .unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let png = rasterize_pdf_with(script.to_str().unwrap(), &pdf(None))
+            .await
+            .unwrap();
+        assert_eq!(png, b"PNGDATA");
+
+        let missing = dir.path().join("missing-pdftoppm");
+        let err = rasterize_pdf_with(missing.to_str().unwrap(), &pdf(None))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("HONE_PDFTOPPM"));
+    }
+}
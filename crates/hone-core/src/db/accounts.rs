@@ -47,9 +47,10 @@ impl Database {
     /// List all accounts
     pub fn list_accounts(&self) -> Result<Vec<Account>> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, name, bank, account_type, entity_id, created_at FROM accounts ORDER BY name",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, name, bank, account_type, entity_id, created_at FROM accounts WHERE 1=1 {} ORDER BY name",
+            self.account_scope_sql("id")
+        ))?;
 
         let accounts = stmt
             .query_map([], |row| {
@@ -81,7 +82,10 @@ impl Database {
         let conn = self.conn()?;
         let account = conn
             .query_row(
-                "SELECT id, name, bank, account_type, entity_id, created_at FROM accounts WHERE id = ?",
+                &format!(
+                    "SELECT id, name, bank, account_type, entity_id, created_at FROM accounts WHERE id = ? {}",
+                    self.account_scope_sql("id")
+                ),
                 params![id],
                 |row| {
                     let bank_str: String = row.get(2)?;
@@ -150,6 +154,10 @@ impl Database {
                 params![id],
             )?;
             conn.execute("DELETE FROM transactions WHERE account_id = ?", params![id])?;
+            conn.execute(
+                "DELETE FROM account_visibility WHERE account_id = ?",
+                params![id],
+            )?;
             conn.execute("DELETE FROM accounts WHERE id = ?", params![id])?;
             Ok(())
         })();
@@ -169,9 +177,10 @@ impl Database {
     /// List accounts by entity (owner)
     pub fn list_accounts_by_entity(&self, entity_id: i64) -> Result<Vec<Account>> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(
-            "SELECT id, name, bank, account_type, entity_id, created_at FROM accounts WHERE entity_id = ? ORDER BY name",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, name, bank, account_type, entity_id, created_at FROM accounts WHERE entity_id = ? {} ORDER BY name",
+            self.account_scope_sql("id")
+        ))?;
 
         let accounts = stmt
             .query_map(params![entity_id], |row| {
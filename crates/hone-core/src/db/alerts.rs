@@ -1,9 +1,12 @@
-//! Alert, dashboard, and audit log operations
+//! Alert and dashboard operations
 
 use chrono::NaiveDate;
-use rusqlite::params;
+use rusqlite::{params, Connection};
 
-use super::{parse_datetime, AuditEntry, Database};
+use super::anomalies::alert_anomaly;
+use super::trials::alert_trial;
+use super::warranties::alert_warranty;
+use super::{parse_datetime, Database};
 use crate::error::Result;
 use crate::models::{
     Alert, AlertType, DashboardStats, Frequency, SpendingAnomalyData, SpendingChangeExplanation,
@@ -11,6 +14,24 @@ use crate::models::{
 };
 use crate::ollama::DuplicateAnalysis;
 
+/// Sum the monthly equivalents of `(amount, frequency)` rows
+///
+/// Rows with no recognised frequency count as monthly.
+fn sum_monthly(conn: &Connection, sql: &str) -> Result<f64> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, f64>(0)?, row.get::<_, Option<String>>(1)?))
+    })?;
+    let mut total = 0.0;
+    for row in rows {
+        let (amount, frequency) = row?;
+        total += frequency
+            .and_then(|f| f.parse::<Frequency>().ok())
+            .map_or(amount, |f| f.monthly_amount(amount));
+    }
+    Ok(total)
+}
+
 impl Database {
     /// Create an alert
     pub fn create_alert(
@@ -142,28 +163,28 @@ impl Database {
     pub fn list_alerts(&self, include_dismissed: bool) -> Result<Vec<Alert>> {
         let conn = self.conn()?;
 
-        let sql = if include_dismissed {
-            r#"
-            SELECT a.id, a.type, a.subscription_id, a.message, a.dismissed, a.created_at, a.ollama_analysis, a.spending_anomaly_data,
-                   s.id, s.merchant, s.account_id, s.amount, s.frequency, s.first_seen, s.last_seen, s.status, s.user_acknowledged, s.acknowledged_at, s.created_at
-            FROM alerts a
-            LEFT JOIN subscriptions s ON a.subscription_id = s.id
-            ORDER BY a.created_at DESC
-            "#
-        } else {
+        let sql = format!(
             r#"
             SELECT a.id, a.type, a.subscription_id, a.message, a.dismissed, a.created_at, a.ollama_analysis, a.spending_anomaly_data,
-                   s.id, s.merchant, s.account_id, s.amount, s.frequency, s.first_seen, s.last_seen, s.status, s.user_acknowledged, s.acknowledged_at, s.created_at
+                   s.id, s.merchant, s.account_id, s.amount, s.frequency, s.first_seen, s.last_seen, s.status, s.user_acknowledged, s.acknowledged_at, s.created_at,
+                   sc.grace_days
             FROM alerts a
             LEFT JOIN subscriptions s ON a.subscription_id = s.id
-            WHERE a.dismissed = FALSE
+            LEFT JOIN subscription_cadences sc ON sc.subscription_id = s.id
+            WHERE {} {}
             ORDER BY a.created_at DESC
-            "#
-        };
+            "#,
+            if include_dismissed {
+                "1=1"
+            } else {
+                "a.dismissed = FALSE"
+            },
+            self.account_scope_sql("s.account_id")
+        );
 
-        let mut stmt = conn.prepare(sql)?;
+        let mut stmt = conn.prepare(&sql)?;
 
-        let alerts = stmt
+        let mut alerts = stmt
             .query_map([], |row| {
                 let type_str: String = row.get(1)?;
                 let alert_created_at_str: String = row.get(5)?;
@@ -172,7 +193,8 @@ impl Database {
 
                 // Parse subscription if present
                 // Columns: 8=s.id, 9=s.merchant, 10=s.account_id, 11=s.amount, 12=s.frequency,
-                //          13=s.first_seen, 14=s.last_seen, 15=s.status, 16=s.user_acknowledged, 17=s.acknowledged_at, 18=s.created_at
+                //          13=s.first_seen, 14=s.last_seen, 15=s.status, 16=s.user_acknowledged, 17=s.acknowledged_at, 18=s.created_at,
+                //          19=sc.grace_days
                 let subscription: Option<Subscription> = row.get::<_, Option<i64>>(8)?.map(|_| {
                     let freq_str: Option<String> = row.get(12).ok().flatten();
                     let status_str: String = row.get(15).unwrap_or_else(|_| "active".to_string());
@@ -186,12 +208,8 @@ impl Database {
                         merchant: row.get(9).unwrap_or_default(),
                         account_id: row.get(10).ok().flatten(),
                         amount: row.get(11).ok().flatten(),
-                        frequency: freq_str.and_then(|s| match s.as_str() {
-                            "weekly" => Some(Frequency::Weekly),
-                            "monthly" => Some(Frequency::Monthly),
-                            "yearly" => Some(Frequency::Yearly),
-                            _ => None,
-                        }),
+                        frequency: freq_str.and_then(|s| s.parse().ok()),
+                        grace_days: row.get(19).ok().flatten(),
                         first_seen: first_seen_str
                             .and_then(|s| NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok()),
                         last_seen: last_seen_str
@@ -218,25 +236,31 @@ impl Database {
 
                 Ok(Alert {
                     id: row.get(0)?,
-                    alert_type: match type_str.as_str() {
-                        "price_increase" => AlertType::PriceIncrease,
-                        "duplicate" => AlertType::Duplicate,
-                        "resume" => AlertType::Resume,
-                        "spending_anomaly" => AlertType::SpendingAnomaly,
-                        "tip_discrepancy" => AlertType::TipDiscrepancy,
-                        _ => AlertType::Zombie,
-                    },
+                    alert_type: type_str.parse().unwrap_or(AlertType::Zombie),
                     subscription_id: row.get(2)?,
                     message: row.get(3)?,
                     dismissed: row.get(4)?,
                     created_at: parse_datetime(&alert_created_at_str),
                     ollama_analysis,
                     spending_anomaly,
+                    trial: None,
+                    transaction_anomaly: None,
+                    warranty: None,
                     subscription,
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
+        for alert in alerts.iter_mut() {
+            if alert.alert_type == AlertType::TrialEnding {
+                alert.trial = alert_trial(&conn, alert.id)?;
+            } else if alert.alert_type.is_transaction_anomaly() {
+                alert.transaction_anomaly = alert_anomaly(&conn, alert.id)?;
+            } else if alert.alert_type.is_warranty_reminder() {
+                alert.warranty = alert_warranty(&conn, alert.id)?;
+            }
+        }
+
         Ok(alerts)
     }
 
@@ -244,7 +268,7 @@ impl Database {
     pub fn get_alert(&self, id: i64) -> Result<Alert> {
         let conn = self.conn()?;
 
-        conn.query_row(
+        let mut alert = conn.query_row(
             r#"
             SELECT a.id, a.type, a.subscription_id, a.message, a.dismissed, a.created_at, a.ollama_analysis, a.spending_anomaly_data
             FROM alerts a
@@ -267,25 +291,29 @@ impl Database {
 
                 Ok(Alert {
                     id: row.get(0)?,
-                    alert_type: match type_str.as_str() {
-                        "price_increase" => AlertType::PriceIncrease,
-                        "duplicate" => AlertType::Duplicate,
-                        "resume" => AlertType::Resume,
-                        "spending_anomaly" => AlertType::SpendingAnomaly,
-                        "tip_discrepancy" => AlertType::TipDiscrepancy,
-                        _ => AlertType::Zombie,
-                    },
+                    alert_type: type_str.parse().unwrap_or(AlertType::Zombie),
                     subscription_id: row.get(2)?,
                     message: row.get(3)?,
                     dismissed: row.get(4)?,
                     created_at: parse_datetime(&alert_created_at_str),
                     ollama_analysis,
                     spending_anomaly,
+                    trial: None,
+                    transaction_anomaly: None,
+                    warranty: None,
                     subscription: None, // Don't load subscription for simple get
                 })
             },
-        )
-        .map_err(|e| e.into())
+        )?;
+
+        if alert.alert_type == AlertType::TrialEnding {
+            alert.trial = alert_trial(&conn, alert.id)?;
+        } else if alert.alert_type.is_transaction_anomaly() {
+            alert.transaction_anomaly = alert_anomaly(&conn, alert.id)?;
+        } else if alert.alert_type.is_warranty_reminder() {
+            alert.warranty = alert_warranty(&conn, alert.id)?;
+        }
+        Ok(alert)
     }
 
     /// Count active (undismissed) alerts
@@ -323,77 +351,93 @@ impl Database {
     pub fn get_dashboard_stats(&self) -> Result<DashboardStats> {
         let conn = self.conn()?;
 
-        let total_transactions: i64 =
-            conn.query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))?;
+        let total_transactions: i64 = conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM transactions WHERE 1=1 {}",
+                self.account_scope_sql("account_id")
+            ),
+            [],
+            |row| row.get(0),
+        )?;
 
-        let total_accounts: i64 =
-            conn.query_row("SELECT COUNT(*) FROM accounts", [], |row| row.get(0))?;
+        let total_accounts: i64 = conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM accounts WHERE 1=1 {}",
+                self.account_scope_sql("id")
+            ),
+            [],
+            |row| row.get(0),
+        )?;
 
         let active_subscriptions: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM subscriptions WHERE status = 'active'",
+            &format!(
+                "SELECT COUNT(*) FROM subscriptions WHERE status = 'active' {}",
+                self.account_scope_sql("account_id")
+            ),
             [],
             |row| row.get(0),
         )?;
 
-        let monthly_subscription_cost: f64 = conn
-            .query_row(
+        let monthly_subscription_cost = sum_monthly(
+            &conn,
+            &format!(
                 r#"
-                SELECT COALESCE(SUM(
-                    CASE frequency
-                        WHEN 'weekly' THEN amount * 4.33
-                        WHEN 'monthly' THEN amount
-                        WHEN 'yearly' THEN amount / 12
-                        ELSE amount
-                    END
-                ), 0)
+                SELECT amount, frequency
                 FROM subscriptions
-                WHERE status = 'active' AND amount IS NOT NULL
+                WHERE status = 'active' AND amount IS NOT NULL {}
                 "#,
-                [],
-                |row| row.get(0),
-            )
-            .unwrap_or(0.0);
+                self.account_scope_sql("account_id")
+            ),
+        )
+        .unwrap_or(0.0);
 
         let active_alerts: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM alerts WHERE dismissed = FALSE",
+            &format!(
+                r#"
+                SELECT COUNT(*)
+                FROM alerts a
+                LEFT JOIN subscriptions s ON a.subscription_id = s.id
+                WHERE a.dismissed = FALSE {}
+                "#,
+                self.account_scope_sql("s.account_id")
+            ),
             [],
             |row| row.get(0),
         )?;
 
         // Potential savings from zombie subscriptions
-        let potential_monthly_savings: f64 = conn
-            .query_row(
+        let potential_monthly_savings = sum_monthly(
+            &conn,
+            &format!(
                 r#"
-                SELECT COALESCE(SUM(
-                    CASE s.frequency
-                        WHEN 'weekly' THEN s.amount * 4.33
-                        WHEN 'monthly' THEN s.amount
-                        WHEN 'yearly' THEN s.amount / 12
-                        ELSE s.amount
-                    END
-                ), 0)
+                SELECT s.amount, s.frequency
                 FROM alerts a
                 JOIN subscriptions s ON a.subscription_id = s.id
-                WHERE a.type = 'zombie' AND a.dismissed = FALSE AND s.amount IS NOT NULL
+                WHERE a.type = 'zombie' AND a.dismissed = FALSE AND s.amount IS NOT NULL {}
                 "#,
-                [],
-                |row| row.get(0),
-            )
-            .unwrap_or(0.0);
+                self.account_scope_sql("s.account_id")
+            ),
+        )
+        .unwrap_or(0.0);
 
         // Count transactions without tags
         let untagged_transactions: i64 = conn.query_row(
-            r#"
-            SELECT COUNT(*)
-            FROM transactions t
-            WHERE NOT EXISTS (
-                SELECT 1 FROM transaction_tags tt WHERE tt.transaction_id = t.id
-            )
-            "#,
+            &format!(
+                r#"
+                SELECT COUNT(*)
+                FROM transactions t
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM transaction_tags tt WHERE tt.transaction_id = t.id
+                ) {}
+                "#,
+                self.account_scope_sql("t.account_id")
+            ),
             [],
             |row| row.get(0),
         )?;
 
+        let recent_digests = self.get_recent_digests(5)?;
+
         Ok(DashboardStats {
             total_transactions,
             total_accounts,
@@ -403,59 +447,7 @@ impl Database {
             potential_monthly_savings,
             recent_imports: vec![], // TODO: Track imports separately
             untagged_transactions,
+            recent_digests,
         })
     }
-
-    /// Log an audit event
-    pub fn log_audit(
-        &self,
-        user_email: &str,
-        action: &str,
-        entity_type: Option<&str>,
-        entity_id: Option<i64>,
-        details: Option<&str>,
-    ) -> Result<i64> {
-        let conn = self.conn()?;
-
-        conn.execute(
-            r#"
-            INSERT INTO audit_log (user_email, action, entity_type, entity_id, details)
-            VALUES (?, ?, ?, ?, ?)
-            "#,
-            params![user_email, action, entity_type, entity_id, details],
-        )?;
-
-        Ok(conn.last_insert_rowid())
-    }
-
-    /// List audit log entries
-    pub fn list_audit_log(&self, limit: i64) -> Result<Vec<AuditEntry>> {
-        let conn = self.conn()?;
-
-        let mut stmt = conn.prepare(
-            r#"
-            SELECT id, timestamp, user_email, action, entity_type, entity_id, details
-            FROM audit_log
-            ORDER BY timestamp DESC
-            LIMIT ?
-            "#,
-        )?;
-
-        let entries = stmt
-            .query_map(params![limit], |row| {
-                let timestamp_str: String = row.get(1)?;
-                Ok(AuditEntry {
-                    id: row.get(0)?,
-                    timestamp: timestamp_str,
-                    user_email: row.get(2)?,
-                    action: row.get(3)?,
-                    entity_type: row.get(4)?,
-                    entity_id: row.get(5)?,
-                    details: row.get(6)?,
-                })
-            })?
-            .collect::<std::result::Result<Vec<_>, _>>()?;
-
-        Ok(entries)
-    }
 }
@@ -0,0 +1,228 @@
+//! Per-transaction anomalies
+//!
+//! Each anomaly comes with an alert of the same type. Dismissing the alert
+//! (or rating it not helpful) is recorded in `user_feedback`, and detection
+//! raises that anomaly type's thresholds from the feedback.
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use super::{parse_datetime, Database};
+use crate::error::Result;
+use crate::models::{
+    AlertType, AnomalyFeedback, FeedbackContext, NewTransactionAnomaly, TransactionAnomaly,
+};
+
+const ANOMALY_COLUMNS: &str = "id, alert_id, transaction_id, related_transaction_id, anomaly_type, account_id, merchant, amount, transaction_date, baseline_amount, threshold, detail, created_at";
+
+fn anomaly_from_row(row: &Row) -> rusqlite::Result<TransactionAnomaly> {
+    let anomaly_type: String = row.get(4)?;
+    let transaction_date: String = row.get(8)?;
+    let created_at: String = row.get(12)?;
+
+    Ok(TransactionAnomaly {
+        id: row.get(0)?,
+        alert_id: row.get(1)?,
+        transaction_id: row.get(2)?,
+        related_transaction_id: row.get(3)?,
+        anomaly_type: anomaly_type.parse().unwrap_or(AlertType::UnusualAmount),
+        account_id: row.get(5)?,
+        merchant: row.get(6)?,
+        amount: row.get(7)?,
+        transaction_date: NaiveDate::parse_from_str(&transaction_date, "%Y-%m-%d")
+            .unwrap_or_default(),
+        baseline_amount: row.get(9)?,
+        threshold: row.get(10)?,
+        detail: row.get(11)?,
+        created_at: parse_datetime(&created_at),
+    })
+}
+
+/// Anomaly behind a per-transaction alert
+pub(super) fn alert_anomaly(
+    conn: &Connection,
+    alert_id: i64,
+) -> Result<Option<TransactionAnomaly>> {
+    let anomaly = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM transaction_anomalies WHERE alert_id = ?",
+                ANOMALY_COLUMNS
+            ),
+            params![alert_id],
+            anomaly_from_row,
+        )
+        .optional()?;
+    Ok(anomaly)
+}
+
+impl Database {
+    /// Record an unusual charge and raise its alert
+    ///
+    /// Returns `None` if this charge was already flagged for the same reason.
+    pub fn create_transaction_anomaly(
+        &self,
+        anomaly: &NewTransactionAnomaly,
+        message: &str,
+    ) -> Result<Option<TransactionAnomaly>> {
+        {
+            let conn = self.conn()?;
+            let exists: bool = conn.query_row(
+                r#"
+                SELECT EXISTS(
+                    SELECT 1 FROM transaction_anomalies
+                    WHERE transaction_id = ? AND anomaly_type = ?
+                )
+                "#,
+                params![anomaly.transaction_id, anomaly.anomaly_type.as_str()],
+                |row| row.get(0),
+            )?;
+            if exists {
+                return Ok(None);
+            }
+        }
+
+        let alert_id = self.create_alert(anomaly.anomaly_type, None, Some(message))?;
+
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO transaction_anomalies
+                (alert_id, transaction_id, related_transaction_id, anomaly_type, account_id,
+                 merchant, amount, transaction_date, baseline_amount, threshold, detail)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                alert_id,
+                anomaly.transaction_id,
+                anomaly.related_transaction_id,
+                anomaly.anomaly_type.as_str(),
+                anomaly.account_id,
+                anomaly.merchant,
+                anomaly.amount,
+                anomaly.transaction_date.to_string(),
+                anomaly.baseline_amount,
+                anomaly.threshold,
+                anomaly.detail,
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        drop(conn);
+
+        self.get_transaction_anomaly(id)
+    }
+
+    /// Get a transaction anomaly by ID
+    pub fn get_transaction_anomaly(&self, id: i64) -> Result<Option<TransactionAnomaly>> {
+        let conn = self.conn()?;
+        let anomaly = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM transaction_anomalies WHERE id = ?",
+                    ANOMALY_COLUMNS
+                ),
+                params![id],
+                anomaly_from_row,
+            )
+            .optional()?;
+        Ok(anomaly)
+    }
+
+    /// Record the dismissal of an anomaly alert as feedback
+    ///
+    /// Returns false if the alert isn't a per-transaction anomaly or its
+    /// dismissal was already recorded.
+    pub fn record_anomaly_dismissal(&self, alert_id: i64) -> Result<bool> {
+        let conn = self.conn()?;
+        let anomaly = match alert_anomaly(&conn, alert_id)? {
+            Some(anomaly) => anomaly,
+            None => return Ok(false),
+        };
+        let recorded: bool = conn.query_row(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM user_feedback
+                WHERE target_type = 'alert' AND target_id = ?
+                  AND feedback_type = 'dismissal' AND reverted_at IS NULL
+            )
+            "#,
+            params![alert_id],
+            |row| row.get(0),
+        )?;
+        drop(conn);
+        if recorded {
+            return Ok(false);
+        }
+
+        self.record_alert_dismissal(
+            alert_id,
+            Some(FeedbackContext {
+                model: None,
+                prompt_version: None,
+                transaction_id: Some(anomaly.transaction_id),
+                extra: Some(serde_json::json!({
+                    "anomaly_type": anomaly.anomaly_type.as_str(),
+                    "merchant": anomaly.merchant,
+                    "amount": anomaly.amount,
+                    "threshold": anomaly.threshold,
+                })),
+            }),
+        )?;
+        Ok(true)
+    }
+
+    /// Active feedback on anomaly alerts, per anomaly type and merchant
+    ///
+    /// Dismissals and "not helpful" ratings count against an alert; "helpful"
+    /// ratings count for it.
+    pub fn get_anomaly_feedback(&self) -> Result<Vec<AnomalyFeedback>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT ta.anomaly_type, ta.merchant,
+                   SUM(CASE WHEN f.feedback_type IN ('dismissal', 'not_helpful') THEN 1 ELSE 0 END),
+                   SUM(CASE WHEN f.feedback_type = 'helpful' THEN 1 ELSE 0 END)
+            FROM transaction_anomalies ta
+            JOIN user_feedback f ON f.target_id = ta.alert_id
+            WHERE f.target_type IN ('alert', 'explanation') AND f.reverted_at IS NULL
+            GROUP BY ta.anomaly_type, ta.merchant
+            "#,
+        )?;
+        let feedback = stmt
+            .query_map([], |row| {
+                let anomaly_type: String = row.get(0)?;
+                Ok((anomaly_type, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .collect::<std::result::Result<Vec<(String, String, i64, i64)>, _>>()?
+            .into_iter()
+            .filter_map(|(anomaly_type, merchant, dismissed, helpful)| {
+                Some(AnomalyFeedback {
+                    anomaly_type: anomaly_type.parse().ok()?,
+                    merchant,
+                    dismissed,
+                    helpful,
+                })
+            })
+            .collect();
+        Ok(feedback)
+    }
+
+    /// Country of each transaction with a known purchase location
+    pub fn get_purchase_countries(&self) -> Result<HashMap<i64, String>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT t.id, UPPER(l.country)
+            FROM transactions t
+            JOIN locations l ON l.id = t.purchase_location_id
+            WHERE l.country IS NOT NULL AND l.country != ''
+            "#,
+        )?;
+        let countries = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+        Ok(countries)
+    }
+}
@@ -0,0 +1,194 @@
+//! Database-managed API keys
+//!
+//! Secrets are `hone_` plus 32 random bytes (hex) and are shown once at
+//! creation. Only their SHA-256 is stored: the secrets are high-entropy, so a
+//! fast hash is enough and lookups can go straight through the unique index.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension};
+use sha2::{Digest, Sha256};
+
+use super::{parse_datetime, Database};
+use crate::error::{Error, Result};
+use crate::local_auth::random_token;
+use crate::models::{ApiKey, ApiKeyScope};
+
+/// Prefix that marks database-managed key secrets
+pub const API_KEY_PREFIX: &str = "hone_";
+
+/// Longest expiry a key can be created with (about ten years)
+pub const MAX_API_KEY_DAYS: i64 = 3650;
+
+/// Characters of the secret kept for display (`hone_` + 8 hex)
+const DISPLAY_PREFIX_LEN: usize = API_KEY_PREFIX.len() + 8;
+
+const API_KEY_COLUMNS: &str =
+    "id, name, key_prefix, scope, created_by, created_at, expires_at, last_used_at, revoked_at";
+
+fn hash_api_key(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+impl Database {
+    /// Create an API key, returning the record and the secret (shown once)
+    pub fn create_api_key(
+        &self,
+        name: &str,
+        scope: ApiKeyScope,
+        expires_at: Option<DateTime<Utc>>,
+        created_by: Option<&str>,
+    ) -> Result<(ApiKey, String)> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::InvalidData("API key name is required".to_string()));
+        }
+        if name.contains(char::is_whitespace) {
+            return Err(Error::InvalidData(
+                "API key names can't contain spaces".to_string(),
+            ));
+        }
+        if self.get_api_key_by_name(name)?.is_some() {
+            return Err(Error::InvalidData(format!(
+                "API key {} already exists",
+                name
+            )));
+        }
+
+        let secret = format!("{}{}", API_KEY_PREFIX, random_token(32)?);
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO api_keys (name, key_prefix, key_hash, scope, created_by, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                name,
+                &secret[..DISPLAY_PREFIX_LEN],
+                hash_api_key(&secret),
+                scope.as_str(),
+                created_by,
+                expires_at.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()),
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        drop(conn);
+
+        let key = self
+            .get_api_key(id)?
+            .ok_or_else(|| Error::NotFound(format!("API key {}", id)))?;
+        Ok((key, secret))
+    }
+
+    /// Get an API key by ID
+    pub fn get_api_key(&self, id: i64) -> Result<Option<ApiKey>> {
+        let conn = self.conn()?;
+        let sql = format!("SELECT {} FROM api_keys WHERE id = ?", API_KEY_COLUMNS);
+        let key = conn
+            .query_row(&sql, params![id], Self::row_to_api_key)
+            .optional()?;
+        Ok(key)
+    }
+
+    /// Get an API key by name
+    pub fn get_api_key_by_name(&self, name: &str) -> Result<Option<ApiKey>> {
+        let conn = self.conn()?;
+        let sql = format!("SELECT {} FROM api_keys WHERE name = ?", API_KEY_COLUMNS);
+        let key = conn
+            .query_row(&sql, params![name.trim()], Self::row_to_api_key)
+            .optional()?;
+        Ok(key)
+    }
+
+    /// List API keys (revoked keys only when asked)
+    pub fn list_api_keys(&self, include_revoked: bool) -> Result<Vec<ApiKey>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            "SELECT {} FROM api_keys {} ORDER BY name",
+            API_KEY_COLUMNS,
+            if include_revoked {
+                ""
+            } else {
+                "WHERE revoked_at IS NULL"
+            }
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([], Self::row_to_api_key)?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Look up an active (unrevoked, unexpired) key by secret and mark it used
+    pub fn authenticate_api_key(&self, secret: &str) -> Result<Option<ApiKey>> {
+        if !secret.starts_with(API_KEY_PREFIX) {
+            return Ok(None);
+        }
+
+        let conn = self.conn()?;
+        let key_hash = hash_api_key(secret);
+        let sql = format!(
+            r#"
+            SELECT {} FROM api_keys
+            WHERE key_hash = ?
+              AND revoked_at IS NULL
+              AND (expires_at IS NULL OR expires_at > datetime('now'))
+            "#,
+            API_KEY_COLUMNS
+        );
+        let key = conn
+            .query_row(&sql, params![key_hash], Self::row_to_api_key)
+            .optional()?;
+        if let Some(key) = &key {
+            conn.execute(
+                "UPDATE api_keys SET last_used_at = CURRENT_TIMESTAMP WHERE id = ?",
+                params![key.id],
+            )?;
+        }
+        Ok(key)
+    }
+
+    /// Whether any active key has this scope
+    pub fn has_active_api_key_scope(&self, scope: ApiKeyScope) -> Result<bool> {
+        let conn = self.conn()?;
+        let exists = conn.query_row(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM api_keys
+                WHERE scope = ?
+                  AND revoked_at IS NULL
+                  AND (expires_at IS NULL OR expires_at > datetime('now'))
+            )
+            "#,
+            params![scope.as_str()],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
+
+    /// Revoke a key (kept for the audit trail; returns false if missing or already revoked)
+    pub fn revoke_api_key(&self, id: i64) -> Result<bool> {
+        let conn = self.conn()?;
+        let updated = conn.execute(
+            "UPDATE api_keys SET revoked_at = CURRENT_TIMESTAMP WHERE id = ? AND revoked_at IS NULL",
+            params![id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    fn row_to_api_key(row: &rusqlite::Row) -> rusqlite::Result<ApiKey> {
+        let scope: String = row.get(3)?;
+        let created_at: String = row.get(5)?;
+        let expires_at: Option<String> = row.get(6)?;
+        let last_used_at: Option<String> = row.get(7)?;
+        let revoked_at: Option<String> = row.get(8)?;
+        Ok(ApiKey {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            prefix: row.get(2)?,
+            scope: scope.parse().unwrap_or(ApiKeyScope::ReadOnly),
+            created_by: row.get(4)?,
+            created_at: parse_datetime(&created_at),
+            expires_at: expires_at.map(|s| parse_datetime(&s)),
+            last_used_at: last_used_at.map(|s| parse_datetime(&s)),
+            revoked_at: revoked_at.map(|s| parse_datetime(&s)),
+        })
+    }
+}
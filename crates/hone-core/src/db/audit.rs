@@ -0,0 +1,303 @@
+//! Hash-chained audit log: recording, querying, verification and archiving
+//!
+//! Every entry stores the previous entry's hash and a SHA-256 over its own
+//! fields plus that hash, so editing or deleting an entry breaks the chain
+//! from that point on. Retention moves old entries to `audit_log_archive`
+//! (hashes intact) rather than deleting them, so the full chain stays
+//! verifiable.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension, TransactionBehavior};
+use sha2::{Digest, Sha256};
+
+use super::{AuditEntry, AuditFilter, AuditVerification, Database};
+use crate::error::Result;
+
+/// `prev_hash` of the first entry in the chain
+pub const AUDIT_GENESIS_HASH: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+
+const AUDIT_COLUMNS: &str =
+    "id, timestamp, user_email, action, entity_type, entity_id, details, prev_hash, entry_hash";
+
+/// Hash of an entry's fields chained to `prev_hash`
+///
+/// Fields are JSON-encoded as a tuple so values containing separators can't
+/// be shifted between fields without changing the hash.
+fn audit_entry_hash(entry: &AuditEntry) -> String {
+    let canonical = serde_json::to_string(&(
+        entry.id,
+        &entry.timestamp,
+        &entry.user_email,
+        &entry.action,
+        &entry.entity_type,
+        entry.entity_id,
+        &entry.details,
+        &entry.prev_hash,
+    ))
+    .unwrap_or_default();
+    hex::encode(Sha256::digest(canonical.as_bytes()))
+}
+
+impl AuditFilter {
+    /// WHERE clause (including "WHERE", empty without conditions) and its parameters
+    fn where_clause(&self) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        let mut conditions = Vec::new();
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(user) = &self.user_email {
+            conditions.push("user_email = ? COLLATE NOCASE");
+            params.push(Box::new(user.clone()));
+        }
+        if let Some(action) = &self.action {
+            conditions.push("action = ?");
+            params.push(Box::new(action.clone()));
+        }
+        if let Some(entity_type) = &self.entity_type {
+            conditions.push("entity_type = ?");
+            params.push(Box::new(entity_type.clone()));
+        }
+        if let Some(entity_id) = self.entity_id {
+            conditions.push("entity_id = ?");
+            params.push(Box::new(entity_id));
+        }
+        if let Some(from) = self.from {
+            conditions.push("date(timestamp) >= ?");
+            params.push(Box::new(from.to_string()));
+        }
+        if let Some(to) = self.to {
+            conditions.push("date(timestamp) <= ?");
+            params.push(Box::new(to.to_string()));
+        }
+
+        let clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        (clause, params)
+    }
+
+    /// Source table (the archive is unioned in when requested)
+    fn source(&self) -> String {
+        if self.include_archived {
+            format!(
+                "(SELECT {0} FROM audit_log UNION ALL SELECT {0} FROM audit_log_archive)",
+                AUDIT_COLUMNS
+            )
+        } else {
+            "audit_log".to_string()
+        }
+    }
+}
+
+impl Database {
+    /// Log an audit event
+    ///
+    /// Runs in an immediate transaction so concurrent writers can't fork the chain.
+    pub fn log_audit(
+        &self,
+        user_email: &str,
+        action: &str,
+        entity_type: Option<&str>,
+        entity_id: Option<i64>,
+        details: Option<&str>,
+    ) -> Result<i64> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+
+        // IDs are assigned explicitly: SQLite would reuse IDs freed by archiving
+        let head = |table: &str| {
+            tx.query_row(
+                &format!(
+                    "SELECT id, entry_hash FROM {} ORDER BY id DESC LIMIT 1",
+                    table
+                ),
+                [],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
+            )
+            .optional()
+        };
+        let (id, prev_hash) = match head("audit_log")?.or(head("audit_log_archive")?) {
+            Some((last_id, last_hash)) => (last_id + 1, last_hash),
+            None => (1, AUDIT_GENESIS_HASH.to_string()),
+        };
+
+        let mut entry = AuditEntry {
+            id,
+            timestamp: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            user_email: user_email.to_string(),
+            action: action.to_string(),
+            entity_type: entity_type.map(String::from),
+            entity_id,
+            details: details.map(String::from),
+            prev_hash,
+            entry_hash: String::new(),
+        };
+        entry.entry_hash = audit_entry_hash(&entry);
+
+        tx.execute(
+            r#"
+            INSERT INTO audit_log
+                (id, timestamp, user_email, action, entity_type, entity_id, details, prev_hash, entry_hash)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                entry.id,
+                entry.timestamp,
+                entry.user_email,
+                entry.action,
+                entry.entity_type,
+                entry.entity_id,
+                entry.details,
+                entry.prev_hash,
+                entry.entry_hash,
+            ],
+        )?;
+        tx.commit()?;
+
+        Ok(id)
+    }
+
+    /// List the most recent audit log entries
+    pub fn list_audit_log(&self, limit: i64) -> Result<Vec<AuditEntry>> {
+        self.query_audit_log(&AuditFilter {
+            limit: Some(limit),
+            ..Default::default()
+        })
+    }
+
+    /// Query audit log entries (newest first)
+    pub fn query_audit_log(&self, filter: &AuditFilter) -> Result<Vec<AuditEntry>> {
+        let conn = self.conn()?;
+        let (where_clause, mut query_params) = filter.where_clause();
+
+        let mut sql = format!(
+            "SELECT {} FROM {} {} ORDER BY id DESC",
+            AUDIT_COLUMNS,
+            filter.source(),
+            where_clause
+        );
+        if let Some(limit) = filter.limit {
+            sql.push_str(" LIMIT ? OFFSET ?");
+            query_params.push(Box::new(limit));
+            query_params.push(Box::new(filter.offset));
+        }
+
+        let mut stmt = conn.prepare(&sql)?;
+        let param_refs: Vec<&dyn rusqlite::ToSql> =
+            query_params.iter().map(|p| p.as_ref()).collect();
+        let entries = stmt
+            .query_map(param_refs.as_slice(), Self::row_to_audit_entry)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// Count audit log entries matching a filter (ignores limit/offset)
+    pub fn count_audit_log(&self, filter: &AuditFilter) -> Result<i64> {
+        let conn = self.conn()?;
+        let (where_clause, query_params) = filter.where_clause();
+        let sql = format!("SELECT COUNT(*) FROM {} {}", filter.source(), where_clause);
+        let param_refs: Vec<&dyn rusqlite::ToSql> =
+            query_params.iter().map(|p| p.as_ref()).collect();
+        let count = conn.query_row(&sql, param_refs.as_slice(), |row| row.get(0))?;
+        Ok(count)
+    }
+
+    /// Walk the hash chain (archive first, then the live log) and report the first break
+    pub fn verify_audit_log(&self) -> Result<AuditVerification> {
+        let conn = self.conn()?;
+        let archived_entries: i64 =
+            conn.query_row("SELECT COUNT(*) FROM audit_log_archive", [], |row| {
+                row.get(0)
+            })?;
+
+        let mut result = AuditVerification {
+            valid: true,
+            entries_checked: 0,
+            archived_entries,
+            head_hash: None,
+            first_invalid_id: None,
+            error: None,
+        };
+        let mut expected_prev = AUDIT_GENESIS_HASH.to_string();
+
+        for table in ["audit_log_archive", "audit_log"] {
+            let sql = format!("SELECT {} FROM {} ORDER BY id", AUDIT_COLUMNS, table);
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map([], Self::row_to_audit_entry)?;
+
+            for entry in rows {
+                let entry = entry?;
+                let error = if entry.prev_hash != expected_prev {
+                    Some("previous hash does not match (an entry was removed or reordered)")
+                } else if audit_entry_hash(&entry) != entry.entry_hash {
+                    Some("entry hash does not match its contents (the entry was modified)")
+                } else {
+                    None
+                };
+
+                if let Some(error) = error {
+                    result.valid = false;
+                    result.first_invalid_id = Some(entry.id);
+                    result.error = Some(format!("Entry {}: {}", entry.id, error));
+                    return Ok(result);
+                }
+
+                result.entries_checked += 1;
+                expected_prev = entry.entry_hash;
+            }
+        }
+
+        if result.entries_checked > 0 {
+            result.head_hash = Some(expected_prev);
+        }
+        Ok(result)
+    }
+
+    /// Move entries logged before `cutoff` to the archive table (returns entries moved)
+    ///
+    /// Everything up to the newest entry older than `cutoff` is moved by id, so
+    /// the archive stays an unbroken prefix of the hash chain even when
+    /// timestamps are out of order (e.g. after a clock change).
+    pub fn archive_audit_log(&self, cutoff: DateTime<Utc>) -> Result<usize> {
+        let cutoff = cutoff.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        let mut conn = self.conn()?;
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let last_id: Option<i64> = tx.query_row(
+            "SELECT MAX(id) FROM audit_log WHERE timestamp < ?",
+            params![cutoff],
+            |row| row.get(0),
+        )?;
+        let Some(last_id) = last_id else {
+            return Ok(0);
+        };
+        tx.execute(
+            &format!(
+                "INSERT INTO audit_log_archive ({0}) SELECT {0} FROM audit_log WHERE id <= ?",
+                AUDIT_COLUMNS
+            ),
+            params![last_id],
+        )?;
+        let moved = tx.execute("DELETE FROM audit_log WHERE id <= ?", params![last_id])?;
+        tx.commit()?;
+
+        Ok(moved)
+    }
+
+    fn row_to_audit_entry(row: &rusqlite::Row) -> rusqlite::Result<AuditEntry> {
+        Ok(AuditEntry {
+            id: row.get(0)?,
+            timestamp: row.get(1)?,
+            user_email: row.get(2)?,
+            action: row.get(3)?,
+            entity_type: row.get(4)?,
+            entity_id: row.get(5)?,
+            details: row.get(6)?,
+            prev_hash: row.get(7)?,
+            entry_hash: row.get(8)?,
+        })
+    }
+}
@@ -0,0 +1,217 @@
+//! Tracked subscription cancellations
+//!
+//! Recording a cancellation marks the subscription cancelled and keeps the
+//! details (method, confirmation number, expected final charge and refund)
+//! so detection can verify it against later charges.
+
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension, Row};
+
+use super::{parse_date, parse_datetime, Database};
+use crate::error::{Error, Result};
+use crate::models::{CancellationStatus, NewSubscriptionCancellation, SubscriptionCancellation};
+
+const CANCELLATION_COLUMNS: &str = "c.id, c.subscription_id, s.merchant, c.cancelled_on, c.method, c.confirmation_number, c.expected_final_charge, c.expected_refund, c.refund_transaction_id, c.refund_amount, c.status, c.late_charge_transaction_id, c.verified_at, c.notes, c.created_at";
+
+fn cancellation_from_row(row: &Row) -> rusqlite::Result<SubscriptionCancellation> {
+    let method: Option<String> = row.get(4)?;
+    let status: String = row.get(10)?;
+    let verified_at: Option<String> = row.get(12)?;
+    let created_at: String = row.get(14)?;
+
+    Ok(SubscriptionCancellation {
+        id: row.get(0)?,
+        subscription_id: row.get(1)?,
+        merchant: row.get(2)?,
+        cancelled_on: parse_date(row.get(3)?).unwrap_or_default(),
+        method: method.and_then(|m| m.parse().ok()),
+        confirmation_number: row.get(5)?,
+        expected_final_charge: parse_date(row.get(6)?),
+        expected_refund: row.get(7)?,
+        refund_transaction_id: row.get(8)?,
+        refund_amount: row.get(9)?,
+        status: status.parse().unwrap_or(CancellationStatus::Pending),
+        late_charge_transaction_id: row.get(11)?,
+        verified_at: verified_at.map(|s| parse_datetime(&s)),
+        notes: row.get(13)?,
+        created_at: parse_datetime(&created_at),
+    })
+}
+
+impl Database {
+    /// Cancel a subscription and track the cancellation for verification
+    ///
+    /// Replaces any earlier cancellation of the subscription that is still
+    /// pending verification.
+    pub fn record_cancellation(
+        &self,
+        subscription_id: i64,
+        details: &NewSubscriptionCancellation,
+    ) -> Result<SubscriptionCancellation> {
+        if self.get_subscription(subscription_id)?.is_none() {
+            return Err(Error::NotFound(format!(
+                "Subscription {} not found",
+                subscription_id
+            )));
+        }
+        if details.expected_refund.is_some_and(|r| r < 0.0) {
+            return Err(Error::InvalidData(
+                "Expected refund can't be negative".to_string(),
+            ));
+        }
+        let cancelled_on = details
+            .cancelled_on
+            .unwrap_or_else(|| Utc::now().date_naive());
+        let confirmation_number = details
+            .confirmation_number
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty());
+
+        self.cancel_subscription(subscription_id, Some(cancelled_on))?;
+
+        let conn = self.conn()?;
+        conn.execute(
+            "DELETE FROM subscription_cancellations WHERE subscription_id = ? AND status = 'pending'",
+            params![subscription_id],
+        )?;
+        conn.execute(
+            r#"
+            INSERT INTO subscription_cancellations
+                (subscription_id, cancelled_on, method, confirmation_number,
+                 expected_final_charge, expected_refund, notes)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                subscription_id,
+                cancelled_on.to_string(),
+                details.method.map(|m| m.as_str()),
+                confirmation_number,
+                details.expected_final_charge.map(|d| d.to_string()),
+                details.expected_refund,
+                details.notes,
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        drop(conn);
+
+        self.get_cancellation(id)?
+            .ok_or_else(|| Error::NotFound(format!("Cancellation {} not found", id)))
+    }
+
+    /// Get a tracked cancellation by ID
+    pub fn get_cancellation(&self, id: i64) -> Result<Option<SubscriptionCancellation>> {
+        let conn = self.conn()?;
+        let cancellation = conn
+            .query_row(
+                &format!(
+                    r#"
+                    SELECT {} FROM subscription_cancellations c
+                    JOIN subscriptions s ON s.id = c.subscription_id
+                    WHERE c.id = ? {}
+                    "#,
+                    CANCELLATION_COLUMNS,
+                    self.account_scope_sql("s.account_id")
+                ),
+                params![id],
+                cancellation_from_row,
+            )
+            .optional()?;
+        Ok(cancellation)
+    }
+
+    /// Latest tracked cancellation of a subscription
+    pub fn get_subscription_cancellation(
+        &self,
+        subscription_id: i64,
+    ) -> Result<Option<SubscriptionCancellation>> {
+        let conn = self.conn()?;
+        let cancellation = conn
+            .query_row(
+                &format!(
+                    r#"
+                    SELECT {} FROM subscription_cancellations c
+                    JOIN subscriptions s ON s.id = c.subscription_id
+                    WHERE c.subscription_id = ? {}
+                    ORDER BY c.id DESC
+                    LIMIT 1
+                    "#,
+                    CANCELLATION_COLUMNS,
+                    self.account_scope_sql("s.account_id")
+                ),
+                params![subscription_id],
+                cancellation_from_row,
+            )
+            .optional()?;
+        Ok(cancellation)
+    }
+
+    /// List tracked cancellations, most recent first
+    pub fn list_cancellations(
+        &self,
+        status: Option<CancellationStatus>,
+    ) -> Result<Vec<SubscriptionCancellation>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT {} FROM subscription_cancellations c
+            JOIN subscriptions s ON s.id = c.subscription_id
+            WHERE (?1 IS NULL OR c.status = ?1) {}
+            ORDER BY c.cancelled_on DESC, c.id DESC
+            "#,
+            CANCELLATION_COLUMNS,
+            self.account_scope_sql("s.account_id")
+        ))?;
+        let cancellations = stmt
+            .query_map(params![status.map(|s| s.as_str())], cancellation_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(cancellations)
+    }
+
+    /// Mark a cancellation verified: no charges after the expected final charge
+    pub fn confirm_cancellation(&self, id: i64) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            UPDATE subscription_cancellations
+            SET status = 'confirmed', verified_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// Record a charge that posted after the expected final charge
+    pub fn mark_cancellation_charged(&self, id: i64, transaction_id: i64) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            UPDATE subscription_cancellations
+            SET status = 'charged_after', late_charge_transaction_id = ?, verified_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+            params![transaction_id, id],
+        )?;
+        Ok(())
+    }
+
+    /// Record the merchant's refund credit for a cancellation
+    pub fn record_cancellation_refund(
+        &self,
+        id: i64,
+        transaction_id: i64,
+        amount: f64,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            UPDATE subscription_cancellations
+            SET refund_transaction_id = ?, refund_amount = ?
+            WHERE id = ?
+            "#,
+            params![transaction_id, amount, id],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,363 @@
+//! Item catalog
+//!
+//! Item splits (receipt line items) are linked to a catalog item per
+//! merchant, keyed by a normalized name, so the same product bought on
+//! different receipts can be tracked over time. Merging an item into another
+//! keeps its key pointing at the target, so later receipts follow the merge.
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use super::{parse_date, parse_datetime, Database};
+use crate::error::{Error, Result};
+use crate::models::CatalogItem;
+
+/// Units that mark a size or quantity token ("12oz", "2 lb", "x2")
+const SIZE_UNITS: &[&str] = &[
+    "oz", "fl", "floz", "lb", "lbs", "g", "gr", "kg", "ml", "l", "lt", "ltr", "gal", "qt", "pt",
+    "ct", "pk", "pack", "ea", "each", "dz", "doz", "pc", "pcs", "x",
+];
+
+/// Receipt abbreviations expanded before comparing names
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("org", "organic"),
+    ("orgnc", "organic"),
+    ("whl", "whole"),
+    ("chkn", "chicken"),
+    ("chk", "chicken"),
+    ("bnls", "boneless"),
+    ("sknls", "skinless"),
+    ("brst", "breast"),
+    ("grnd", "ground"),
+    ("bf", "beef"),
+    ("veg", "vegetable"),
+    ("lg", "large"),
+    ("med", "medium"),
+    ("sm", "small"),
+];
+
+const CATALOG_GROUP: &str = "GROUP BY ci.id";
+
+fn catalog_item_from_row(row: &Row) -> rusqlite::Result<CatalogItem> {
+    let created_at: String = row.get(9)?;
+
+    Ok(CatalogItem {
+        id: row.get(0)?,
+        merchant: row.get(1)?,
+        name: row.get(2)?,
+        normalized_name: row.get(3)?,
+        purchase_count: row.get(4)?,
+        first_seen: parse_date(row.get(5)?),
+        last_seen: parse_date(row.get(6)?),
+        last_price: row.get(7)?,
+        avg_price: row.get(8)?,
+        created_at: parse_datetime(&created_at),
+    })
+}
+
+fn is_number(token: &str) -> bool {
+    !token.is_empty() && token.chars().all(|c| c.is_ascii_digit() || c == '.')
+}
+
+/// "12oz", "1.5lb", "x2", "2ct"
+fn is_size_token(token: &str) -> bool {
+    let split = token
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(token.len());
+    let (number, unit) = token.split_at(split);
+    if !number.is_empty() {
+        return SIZE_UNITS.contains(&unit);
+    }
+    token
+        .strip_prefix('x')
+        .is_some_and(|qty| !qty.is_empty() && is_number(qty))
+}
+
+/// Normalize a receipt line item name for matching across receipts
+///
+/// Lowercases, drops punctuation, SKU/UPC numbers, prices, sizes and
+/// quantities, expands common abbreviations and sorts the remaining words,
+/// so "ORG WHL MILK 1GAL" and "Whole Milk, Organic" share a key. Returns an
+/// empty string if nothing identifying is left.
+pub fn normalize_item_name(name: &str) -> String {
+    // Unit prices ("2 @ 3.99") follow an '@'
+    let name = name.split('@').next().unwrap_or_default().to_lowercase();
+    let chars: Vec<char> = name.chars().collect();
+    let cleaned: String = chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            let decimal_point = c == '.'
+                && i > 0
+                && chars[i - 1].is_ascii_digit()
+                && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit());
+            if c.is_alphanumeric() || decimal_point {
+                c
+            } else {
+                ' '
+            }
+        })
+        .collect();
+
+    let tokens: Vec<&str> = cleaned.split_whitespace().collect();
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        if is_number(token) {
+            if tokens.get(i + 1).is_some_and(|u| SIZE_UNITS.contains(u)) {
+                i += 2;
+                continue;
+            }
+            // SKUs, UPCs and prices; short counts ("2% milk") are kept
+            if token.len() >= 4 || token.contains('.') {
+                i += 1;
+                continue;
+            }
+        } else if is_size_token(token) {
+            i += 1;
+            continue;
+        }
+        let word = ABBREVIATIONS
+            .iter()
+            .find(|(abbr, _)| *abbr == token)
+            .map_or(token, |(_, full)| full);
+        words.push(word);
+        i += 1;
+    }
+
+    words.sort_unstable();
+    words.dedup();
+    words.join(" ")
+}
+
+/// Follow merges from a catalog item to the item it now belongs to
+fn resolve_merged(conn: &Connection, id: i64) -> Result<i64> {
+    let mut current = id;
+    // Merges never form cycles, but bound the walk anyway
+    for _ in 0..32 {
+        let merged_into: Option<i64> = conn.query_row(
+            "SELECT merged_into FROM catalog_items WHERE id = ?",
+            params![current],
+            |row| row.get(0),
+        )?;
+        match merged_into {
+            Some(next) => current = next,
+            None => break,
+        }
+    }
+    Ok(current)
+}
+
+impl Database {
+    /// Link item splits that aren't in the catalog yet
+    ///
+    /// Creates catalog items for names not seen before at the merchant.
+    /// Returns the number of splits linked.
+    pub fn sync_item_catalog(&self) -> Result<usize> {
+        let mut conn = self.conn()?;
+        let pending: Vec<(i64, String, String)> = {
+            let mut stmt = conn.prepare(
+                r#"
+                SELECT s.id, s.description, COALESCE(tx.merchant_normalized, tx.description)
+                FROM transaction_splits s
+                JOIN transactions tx ON tx.id = s.transaction_id
+                LEFT JOIN split_catalog_items l ON l.split_id = s.id
+                WHERE s.split_type = 'item' AND l.split_id IS NULL
+                  AND s.description IS NOT NULL AND TRIM(s.description) != ''
+                ORDER BY tx.date, s.id
+                "#,
+            )?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            rows
+        };
+
+        let tx = conn.transaction()?;
+        let mut linked = 0;
+        for (split_id, description, merchant) in pending {
+            let key = normalize_item_name(&description);
+            if key.is_empty() {
+                continue;
+            }
+            let existing: Option<i64> = tx
+                .query_row(
+                    "SELECT id FROM catalog_items WHERE merchant = ? AND normalized_name = ?",
+                    params![merchant, key],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let item_id = match existing {
+                Some(id) => resolve_merged(&tx, id)?,
+                None => {
+                    tx.execute(
+                        "INSERT INTO catalog_items (merchant, name, normalized_name) VALUES (?, ?, ?)",
+                        params![merchant, description.trim(), key],
+                    )?;
+                    tx.last_insert_rowid()
+                }
+            };
+            tx.execute(
+                "INSERT INTO split_catalog_items (split_id, catalog_item_id) VALUES (?, ?)",
+                params![split_id, item_id],
+            )?;
+            linked += 1;
+        }
+        tx.commit()?;
+
+        Ok(linked)
+    }
+
+    /// Catalog items with stats from purchases on visible, unarchived
+    /// transactions; callers append a WHERE clause followed by [`CATALOG_GROUP`]
+    fn catalog_select(&self) -> String {
+        format!(
+            r#"
+    SELECT ci.id, ci.merchant, ci.name, ci.normalized_name,
+           COUNT(p.split_id), MIN(p.date), MAX(p.date),
+           (SELECT s2.amount FROM split_catalog_items l2
+            JOIN transaction_splits s2 ON s2.id = l2.split_id
+            JOIN transactions t2 ON t2.id = s2.transaction_id
+            WHERE l2.catalog_item_id = ci.id AND t2.archived = 0 {}
+            ORDER BY t2.date DESC, s2.id DESC
+            LIMIT 1),
+           AVG(p.amount), ci.created_at
+    FROM catalog_items ci
+    LEFT JOIN (
+        SELECT l.catalog_item_id, s.id AS split_id, s.amount, tx.date
+        FROM split_catalog_items l
+        JOIN transaction_splits s ON s.id = l.split_id
+        JOIN transactions tx ON tx.id = s.transaction_id
+        WHERE tx.archived = 0 {}
+    ) p ON p.catalog_item_id = ci.id
+"#,
+            self.account_scope_sql("t2.account_id"),
+            self.account_scope_sql("tx.account_id")
+        )
+    }
+
+    /// Get a catalog item with its purchase stats
+    pub fn get_catalog_item(&self, id: i64) -> Result<Option<CatalogItem>> {
+        let conn = self.conn()?;
+        let item = conn
+            .query_row(
+                &format!(
+                    "{} WHERE ci.id = ? {}",
+                    self.catalog_select(),
+                    CATALOG_GROUP
+                ),
+                params![id],
+                catalog_item_from_row,
+            )
+            .optional()?;
+        Ok(item)
+    }
+
+    /// List catalog items with purchases, most purchased first
+    ///
+    /// `search` matches the display or normalized name.
+    pub fn list_catalog_items(
+        &self,
+        merchant: Option<&str>,
+        search: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<CatalogItem>> {
+        self.sync_item_catalog()?;
+
+        let conn = self.conn()?;
+        let search = search
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| format!("%{}%", s.to_lowercase()));
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            {}
+            WHERE ci.merged_into IS NULL
+              AND (?1 IS NULL OR ci.merchant = ?1 COLLATE NOCASE)
+              AND (?2 IS NULL OR LOWER(ci.name) LIKE ?2 OR ci.normalized_name LIKE ?2)
+            {}
+            HAVING COUNT(p.split_id) > 0
+            ORDER BY COUNT(p.split_id) DESC, ci.merchant, ci.name
+            LIMIT ?3
+            "#,
+            self.catalog_select(),
+            CATALOG_GROUP
+        ))?;
+        let items = stmt
+            .query_map(params![merchant, search, limit], catalog_item_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(items)
+    }
+
+    /// Merge one catalog item into another from the same merchant
+    ///
+    /// Purchases move to the target, and later receipt lines that normalize
+    /// to the merged item's name are linked to the target too.
+    pub fn merge_catalog_items(&self, from_id: i64, into_id: i64) -> Result<CatalogItem> {
+        if from_id == into_id {
+            return Err(Error::InvalidData(
+                "Can't merge a catalog item into itself".to_string(),
+            ));
+        }
+        let from = self
+            .get_catalog_item(from_id)?
+            .ok_or_else(|| Error::NotFound(format!("Catalog item {} not found", from_id)))?;
+        let into = self
+            .get_catalog_item(into_id)?
+            .ok_or_else(|| Error::NotFound(format!("Catalog item {} not found", into_id)))?;
+        if !from.merchant.eq_ignore_ascii_case(&into.merchant) {
+            return Err(Error::InvalidData(format!(
+                "Catalog items are from different merchants ({} and {})",
+                from.merchant, into.merchant
+            )));
+        }
+
+        let mut conn = self.conn()?;
+        let into_id = resolve_merged(&conn, into_id)?;
+        if into_id == from_id {
+            return Err(Error::InvalidData(format!(
+                "Catalog item {} was merged into {}",
+                into.id, from_id
+            )));
+        }
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE split_catalog_items SET catalog_item_id = ? WHERE catalog_item_id = ?",
+            params![into_id, from_id],
+        )?;
+        tx.execute(
+            "UPDATE catalog_items SET merged_into = ? WHERE id = ? OR merged_into = ?",
+            params![into_id, from_id, from_id],
+        )?;
+        tx.commit()?;
+        drop(conn);
+
+        self.get_catalog_item(into_id)?
+            .ok_or_else(|| Error::NotFound(format!("Catalog item {} not found", into_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_item_name;
+
+    #[test]
+    fn test_normalize_item_name() {
+        assert_eq!(
+            normalize_item_name("ORG WHL MILK 1GAL"),
+            normalize_item_name("Whole Milk, Organic")
+        );
+        assert_eq!(
+            normalize_item_name("ORG WHL MILK 1GAL"),
+            "milk organic whole"
+        );
+        assert_eq!(
+            normalize_item_name("BANANAS 3.02 lb @ 0.59"),
+            normalize_item_name("Bananas")
+        );
+        assert_eq!(normalize_item_name("041220576463 KS EGGS 24CT"), "eggs ks");
+        assert_eq!(normalize_item_name("Coffee x2"), "coffee");
+        assert_eq!(normalize_item_name("2% Milk"), "2 milk");
+        assert_eq!(normalize_item_name("12 oz"), "");
+    }
+}
@@ -0,0 +1,182 @@
+//! Saved detection settings
+//!
+//! One row per scope: the household-wide settings, plus overrides for
+//! individual merchants and tags. Only changed values are stored (as JSON),
+//! so defaults still apply to everything else.
+
+use rusqlite::{params, OptionalExtension};
+
+use super::Database;
+use crate::detect::{
+    DetectionOverrides, DetectionSettings, MerchantDetectionOverride, TagDetectionOverride,
+};
+use crate::error::{Error, Result};
+
+/// What a detection settings row applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DetectionScope {
+    Global,
+    Merchant(String),
+    Tag(i64),
+}
+
+impl DetectionScope {
+    fn columns(&self) -> (&'static str, Option<&str>, Option<i64>) {
+        match self {
+            DetectionScope::Global => ("global", None, None),
+            DetectionScope::Merchant(m) => ("merchant", Some(m.as_str()), None),
+            DetectionScope::Tag(id) => ("tag", None, Some(*id)),
+        }
+    }
+}
+
+fn parse_overrides(json: &str) -> Result<DetectionOverrides> {
+    serde_json::from_str(json)
+        .map_err(|e| Error::InvalidData(format!("Invalid saved detection settings: {}", e)))
+}
+
+impl Database {
+    /// Load all saved detection settings
+    pub fn get_detection_settings(&self) -> Result<DetectionSettings> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT scope, merchant, tag_id, overrides FROM detection_settings ORDER BY id",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut settings = DetectionSettings::default();
+        for (scope, merchant, tag_id, overrides) in rows {
+            let overrides = parse_overrides(&overrides)?;
+            match (scope.as_str(), merchant, tag_id) {
+                ("global", _, _) => settings.global = overrides,
+                ("merchant", Some(merchant), _) => {
+                    settings.merchants.push(MerchantDetectionOverride {
+                        merchant,
+                        overrides,
+                    })
+                }
+                ("tag", _, Some(tag_id)) => settings.tags.push(TagDetectionOverride {
+                    tag_id,
+                    tag: self.build_tag_path(&conn, tag_id)?,
+                    overrides,
+                }),
+                _ => {}
+            }
+        }
+        Ok(settings)
+    }
+
+    /// Get the saved overrides for one scope (empty if none)
+    pub fn get_detection_overrides(&self, scope: &DetectionScope) -> Result<DetectionOverrides> {
+        let conn = self.conn()?;
+        let (kind, merchant, tag_id) = scope.columns();
+        let json: Option<String> = conn
+            .query_row(
+                r#"
+                SELECT overrides FROM detection_settings
+                WHERE scope = ? AND LOWER(merchant) IS LOWER(?) AND tag_id IS ?
+                "#,
+                params![kind, merchant, tag_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        json.map_or_else(
+            || Ok(DetectionOverrides::default()),
+            |j| parse_overrides(&j),
+        )
+    }
+
+    /// Save the overrides for one scope, replacing what was there
+    ///
+    /// Saving empty overrides removes the scope's row.
+    pub fn set_detection_overrides(
+        &self,
+        scope: &DetectionScope,
+        overrides: &DetectionOverrides,
+        updated_by: &str,
+    ) -> Result<()> {
+        overrides.validate()?;
+        if let DetectionScope::Merchant(merchant) = scope {
+            if merchant.trim().is_empty() {
+                return Err(Error::InvalidData("Merchant name can't be empty".into()));
+            }
+        }
+        if let DetectionScope::Tag(id) = scope {
+            if self.get_tag(*id)?.is_none() {
+                return Err(Error::NotFound(format!("Tag {}", id)));
+            }
+        }
+        if overrides.is_empty() {
+            self.delete_detection_overrides(scope)?;
+            return Ok(());
+        }
+
+        let json = serde_json::to_string(overrides)?;
+        let conn = self.conn()?;
+        let (kind, merchant, tag_id) = scope.columns();
+        let updated = conn.execute(
+            r#"
+            UPDATE detection_settings
+            SET overrides = ?, updated_by = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE scope = ? AND LOWER(merchant) IS LOWER(?) AND tag_id IS ?
+            "#,
+            params![json, updated_by, kind, merchant, tag_id],
+        )?;
+        if updated == 0 {
+            conn.execute(
+                r#"
+                INSERT INTO detection_settings (scope, merchant, tag_id, overrides, updated_by)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+                params![kind, merchant, tag_id, json, updated_by],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Remove the overrides for one scope; returns false if there were none
+    pub fn delete_detection_overrides(&self, scope: &DetectionScope) -> Result<bool> {
+        let conn = self.conn()?;
+        let (kind, merchant, tag_id) = scope.columns();
+        let deleted = conn.execute(
+            "DELETE FROM detection_settings WHERE scope = ? AND LOWER(merchant) IS LOWER(?) AND tag_id IS ?",
+            params![kind, merchant, tag_id],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// Tags on a merchant's transactions, with all their ancestors
+    ///
+    /// Used to pick per-tag detection overrides for a merchant.
+    pub fn get_merchant_tag_lineage(&self, merchant: &str) -> Result<Vec<i64>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            WITH RECURSIVE lineage(id) AS (
+                SELECT tt.tag_id
+                FROM transaction_tags tt
+                JOIN transactions t ON t.id = tt.transaction_id
+                WHERE LOWER(COALESCE(t.merchant_normalized, t.description)) = LOWER(?)
+                UNION
+                SELECT tags.parent_id
+                FROM tags JOIN lineage ON tags.id = lineage.id
+                WHERE tags.parent_id IS NOT NULL
+            )
+            SELECT id FROM lineage
+            "#,
+        )?;
+        let ids = stmt
+            .query_map(params![merchant], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<i64>, _>>()?;
+        Ok(ids)
+    }
+}
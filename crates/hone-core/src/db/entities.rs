@@ -413,6 +413,13 @@ impl Database {
                 params![split_type.as_str(), id],
             )?;
         }
+        if description.is_some() || split_type.is_some() {
+            // Relinked to the item catalog on its next sync
+            conn.execute(
+                "DELETE FROM split_catalog_items WHERE split_id = ?",
+                params![id],
+            )?;
+        }
         if let Some(entity_id) = entity_id {
             conn.execute(
                 "UPDATE transaction_splits SET entity_id = ? WHERE id = ?",
@@ -663,12 +670,13 @@ impl Database {
     /// Get transactions for a trip
     pub fn get_trip_transactions(&self, trip_id: i64) -> Result<Vec<Transaction>> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT id, account_id, date, description, amount, category, merchant_normalized,
                     import_hash, purchase_location_id, vendor_location_id, trip_id,
                     source, expected_amount, archived, original_data, import_format, card_member, payment_method, created_at
-             FROM transactions WHERE trip_id = ? AND archived = 0 ORDER BY date DESC",
-        )?;
+             FROM transactions WHERE trip_id = ? AND archived = 0 {} ORDER BY date DESC",
+            self.account_scope_sql("account_id")
+        ))?;
 
         let transactions = stmt
             .query_map(params![trip_id], |row| Self::row_to_transaction(row))?
@@ -681,7 +689,10 @@ impl Database {
     pub fn get_trip_spending(&self, trip_id: i64) -> Result<(f64, i64)> {
         let conn = self.conn()?;
         let (total, count): (f64, i64) = conn.query_row(
-            "SELECT COALESCE(SUM(ABS(amount)), 0), COUNT(*) FROM transactions WHERE trip_id = ? AND amount < 0",
+            &format!(
+                "SELECT COALESCE(SUM(ABS(amount)), 0), COUNT(*) FROM transactions WHERE trip_id = ? AND amount < 0 {}",
+                self.account_scope_sql("account_id")
+            ),
             params![trip_id],
             |row| Ok((row.get(0)?, row.get(1)?)),
         )?;
@@ -785,16 +796,17 @@ impl Database {
         to: NaiveDate,
     ) -> Result<Vec<LocationSpending>> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT l.id, l.name, l.city, l.country,
                     COALESCE(SUM(ABS(t.amount)), 0) as total, COUNT(t.id) as count
              FROM locations l
              LEFT JOIN transactions t ON t.purchase_location_id = l.id
-                  AND t.date BETWEEN ? AND ? AND t.amount < 0
+                  AND t.date BETWEEN ? AND ? AND t.amount < 0 {}
              GROUP BY l.id
              HAVING count > 0
              ORDER BY total DESC",
-        )?;
+            self.account_scope_sql("t.account_id")
+        ))?;
 
         let results = stmt
             .query_map(params![from.to_string(), to.to_string()], |row| {
@@ -834,16 +846,17 @@ impl Database {
         to: NaiveDate,
     ) -> Result<Vec<(Entity, f64, i64)>> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT e.id, e.name, e.type, e.icon, e.color, e.archived, e.created_at,
                     COALESCE(SUM(ABS(s.amount)), 0) as total, COUNT(s.id) as count
              FROM entities e
-             LEFT JOIN transaction_splits s ON s.entity_id = e.id
+             LEFT JOIN transaction_splits s ON s.entity_id = e.id {}
              LEFT JOIN transactions t ON s.transaction_id = t.id AND t.date BETWEEN ? AND ?
              WHERE e.archived = 0
              GROUP BY e.id
              ORDER BY total DESC",
-        )?;
+            self.transaction_scope_sql("s.transaction_id")
+        ))?;
 
         let results = stmt
             .query_map(params![from.to_string(), to.to_string()], |row| {
@@ -885,15 +898,16 @@ impl Database {
             .ok_or_else(|| Error::NotFound(format!("Entity {} not found", entity_id)))?;
 
         // Get total spending by tag for this vehicle entity
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT t.name, COALESCE(SUM(ABS(s.amount)), 0) as total
              FROM transaction_splits s
              LEFT JOIN split_tags st ON st.split_id = s.id
              LEFT JOIN tags t ON st.tag_id = t.id
              LEFT JOIN transactions tx ON s.transaction_id = tx.id
-             WHERE s.entity_id = ? AND tx.date BETWEEN ? AND ?
+             WHERE s.entity_id = ? AND tx.date BETWEEN ? AND ? {}
              GROUP BY t.id",
-        )?;
+            self.account_scope_sql("tx.account_id")
+        ))?;
 
         let mut fuel_cost = 0.0;
         let mut maintenance_cost = 0.0;
@@ -961,15 +975,16 @@ impl Database {
             .ok_or_else(|| Error::NotFound(format!("Entity {} not found", entity_id)))?;
 
         // Get total spending by tag for this property entity
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT t.name, COALESCE(SUM(ABS(s.amount)), 0) as total
              FROM transaction_splits s
              LEFT JOIN split_tags st ON st.split_id = s.id
              LEFT JOIN tags t ON st.tag_id = t.id
              LEFT JOIN transactions tx ON s.transaction_id = tx.id
-             WHERE s.entity_id = ? AND tx.date BETWEEN ? AND ?
+             WHERE s.entity_id = ? AND tx.date BETWEEN ? AND ? {}
              GROUP BY t.id",
-        )?;
+            self.account_scope_sql("tx.account_id")
+        ))?;
 
         let mut mortgage_rent = 0.0;
         let mut utilities = 0.0;
@@ -0,0 +1,227 @@
+//! Explore session persistence
+//!
+//! Explore conversations are stored as a session row plus one row per
+//! query/response turn. Older turns can be folded into a rolling summary
+//! (`summarized = 1`) so the context sent to the model stays bounded while the
+//! full history remains available for display and export.
+
+use rusqlite::{params, OptionalExtension};
+
+use super::{parse_datetime, Database};
+use crate::error::{Error, Result};
+use crate::models::{ExploreSession, ExploreTurn, NewExploreTurn};
+
+const SESSION_COLUMNS: &str = r#"
+    s.id, s.title, s.model, s.summary, s.created_by, s.created_at, s.updated_at,
+    (SELECT COUNT(*) FROM explore_turns t WHERE t.session_id = s.id) AS turn_count
+"#;
+
+/// Escape LIKE wildcards so a search term matches literally (use with `ESCAPE '\'`)
+fn escape_like(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len());
+    for c in term.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl Database {
+    /// Create a new explore session
+    pub fn create_explore_session(
+        &self,
+        id: &str,
+        title: &str,
+        created_by: Option<&str>,
+    ) -> Result<ExploreSession> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT INTO explore_sessions (id, title, created_by) VALUES (?, ?, ?)",
+            params![id, title, created_by],
+        )?;
+        drop(conn);
+
+        self.get_explore_session(id)?
+            .ok_or_else(|| Error::NotFound(format!("Explore session {}", id)))
+    }
+
+    /// Get an explore session by ID
+    pub fn get_explore_session(&self, id: &str) -> Result<Option<ExploreSession>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            "SELECT {} FROM explore_sessions s WHERE s.id = ?",
+            SESSION_COLUMNS
+        );
+        let session = conn
+            .query_row(&sql, params![id], Self::row_to_explore_session)
+            .optional()?;
+        Ok(session)
+    }
+
+    /// List explore sessions, most recently active first
+    ///
+    /// `created_by` limits the list to one user's sessions. `search` matches
+    /// (case-insensitively) against the session title and the text of every
+    /// query and response in the session.
+    pub fn list_explore_sessions(
+        &self,
+        created_by: Option<&str>,
+        search: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ExploreSession>> {
+        let conn = self.conn()?;
+
+        let pattern = search
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|term| format!("%{}%", escape_like(term)));
+        let sql = format!(
+            r#"
+            SELECT {} FROM explore_sessions s
+            WHERE (?1 IS NULL OR s.created_by = ?1)
+              AND (?2 IS NULL
+                   OR s.title LIKE ?2 COLLATE NOCASE ESCAPE '\'
+                   OR EXISTS (
+                       SELECT 1 FROM explore_turns t
+                       WHERE t.session_id = s.id
+                         AND (t.query LIKE ?2 COLLATE NOCASE ESCAPE '\'
+                              OR t.response LIKE ?2 COLLATE NOCASE ESCAPE '\')
+                   ))
+            ORDER BY s.updated_at DESC, s.created_at DESC
+            LIMIT ?3 OFFSET ?4
+            "#,
+            SESSION_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let sessions = stmt
+            .query_map(
+                params![created_by, pattern, limit, offset],
+                Self::row_to_explore_session,
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(sessions)
+    }
+
+    /// Rename an explore session, returning whether it exists
+    pub fn rename_explore_session(&self, id: &str, title: &str) -> Result<bool> {
+        let conn = self.conn()?;
+        let updated = conn.execute(
+            "UPDATE explore_sessions SET title = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            params![title, id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Delete an explore session and all of its turns
+    pub fn delete_explore_session(&self, id: &str) -> Result<bool> {
+        let conn = self.conn()?;
+        conn.execute(
+            "DELETE FROM explore_turns WHERE session_id = ?",
+            params![id],
+        )?;
+        let deleted = conn.execute("DELETE FROM explore_sessions WHERE id = ?", params![id])?;
+        Ok(deleted > 0)
+    }
+
+    /// Append a completed turn to a session and bump its activity time
+    pub fn add_explore_turn(&self, session_id: &str, turn: &NewExploreTurn) -> Result<i64> {
+        let conn = self.conn()?;
+        let tool_calls_json = serde_json::to_string(&turn.tool_calls)?;
+
+        conn.execute(
+            r#"
+            INSERT INTO explore_turns (session_id, query, response, tool_calls, iterations, model)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                session_id,
+                turn.query,
+                turn.response,
+                tool_calls_json,
+                turn.iterations,
+                turn.model
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+
+        conn.execute(
+            r#"
+            UPDATE explore_sessions
+            SET updated_at = CURRENT_TIMESTAMP, model = COALESCE(?, model)
+            WHERE id = ?
+            "#,
+            params![turn.model, session_id],
+        )?;
+
+        Ok(id)
+    }
+
+    /// Get all turns of a session in conversation order
+    pub fn get_explore_turns(&self, session_id: &str) -> Result<Vec<ExploreTurn>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, session_id, query, response, tool_calls, iterations, model, summarized, created_at
+            FROM explore_turns
+            WHERE session_id = ?
+            ORDER BY id
+            "#,
+        )?;
+        let rows = stmt.query_map(params![session_id], Self::row_to_explore_turn)?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Replace the session summary and mark turns up to `through_turn_id` as summarized
+    pub fn summarize_explore_turns(
+        &self,
+        session_id: &str,
+        summary: &str,
+        through_turn_id: i64,
+    ) -> Result<usize> {
+        let conn = self.conn()?;
+        conn.execute(
+            "UPDATE explore_sessions SET summary = ? WHERE id = ?",
+            params![summary, session_id],
+        )?;
+        let marked = conn.execute(
+            "UPDATE explore_turns SET summarized = 1 WHERE session_id = ? AND id <= ? AND summarized = 0",
+            params![session_id, through_turn_id],
+        )?;
+        Ok(marked)
+    }
+
+    fn row_to_explore_session(row: &rusqlite::Row) -> rusqlite::Result<ExploreSession> {
+        let created_at: String = row.get(5)?;
+        let updated_at: String = row.get(6)?;
+        Ok(ExploreSession {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            model: row.get(2)?,
+            summary: row.get(3)?,
+            created_by: row.get(4)?,
+            turn_count: row.get(7)?,
+            created_at: parse_datetime(&created_at),
+            updated_at: parse_datetime(&updated_at),
+        })
+    }
+
+    fn row_to_explore_turn(row: &rusqlite::Row) -> rusqlite::Result<ExploreTurn> {
+        let tool_calls_json: String = row.get(4)?;
+        let created_at: String = row.get(8)?;
+        Ok(ExploreTurn {
+            id: row.get(0)?,
+            session_id: row.get(1)?,
+            query: row.get(2)?,
+            response: row.get(3)?,
+            tool_calls: serde_json::from_str(&tool_calls_json).unwrap_or_default(),
+            iterations: row.get(5)?,
+            model: row.get(6)?,
+            summarized: row.get(7)?,
+            created_at: parse_datetime(&created_at),
+        })
+    }
+}
@@ -328,6 +328,22 @@ impl Database {
         })
     }
 
+    /// Revert the active dismissal feedback on an alert (it was restored)
+    pub fn revert_alert_dismissals(&self, alert_id: i64) -> Result<usize> {
+        let conn = self.conn()?;
+
+        let reverted = conn.execute(
+            r#"
+            UPDATE user_feedback SET reverted_at = CURRENT_TIMESTAMP
+            WHERE target_type = 'alert' AND target_id = ?
+              AND feedback_type = 'dismissal' AND reverted_at IS NULL
+            "#,
+            params![alert_id],
+        )?;
+
+        Ok(reverted)
+    }
+
     /// Record explicit helpful/not helpful feedback on an explanation
     pub fn record_explanation_feedback(
         &self,
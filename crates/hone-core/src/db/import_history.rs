@@ -2,16 +2,21 @@
 
 use chrono::NaiveDate;
 use rusqlite::params;
+use tracing::warn;
 
-use super::{parse_datetime, Database};
+use super::{parse_datetime, Database, TransactionInsertResult};
 use crate::error::Result;
 use crate::models::{
-    Bank, ImportSession, ImportSessionWithAccount, ImportStatus, ImportTaggingBreakdown,
-    MerchantChange, NewImportSession, NewReprocessRun, ReprocessRun, ReprocessRunStatus,
-    ReprocessRunSummary, ReprocessRunWithComparison, ReprocessSnapshot, RunComparison,
-    SkippedTransaction, TagChange, TagDifference, TaggingBreakdownDiff, Transaction,
+    Bank, ImportIdempotencyKey, ImportSession, ImportSessionWithAccount, ImportStatus,
+    ImportTaggingBreakdown, JobType, MerchantChange, NewImportSession, NewJob, NewReprocessRun,
+    NewTransaction, ReprocessRun, ReprocessRunStatus, ReprocessRunSummary,
+    ReprocessRunWithComparison, ReprocessSnapshot, RunComparison, SkippedTransaction, TagChange,
+    TagDifference, TaggingBreakdownDiff, Transaction,
 };
 
+/// How long JSON import idempotency keys are remembered
+pub const IDEMPOTENCY_KEY_DAYS: i64 = 30;
+
 impl Database {
     /// Create a new import session
     pub fn create_import_session(&self, session: &NewImportSession) -> Result<i64> {
@@ -33,6 +38,187 @@ impl Database {
         Ok(conn.last_insert_rowid())
     }
 
+    /// Create an import session and insert its transactions (the synchronous phase)
+    ///
+    /// Duplicates are recorded as skipped. Returns the session ID with the
+    /// imported and skipped counts; AI processing is queued separately with
+    /// [`Database::queue_import_processing`].
+    pub fn import_into_session(
+        &self,
+        session: &NewImportSession,
+        transactions: &[NewTransaction],
+    ) -> Result<(i64, usize, usize)> {
+        let (session_id, results) = self.import_rows_into_session(session, transactions)?;
+        let imported = results
+            .iter()
+            .filter(|r| matches!(r, TransactionInsertResult::Inserted(_)))
+            .count();
+        Ok((session_id, imported, results.len() - imported))
+    }
+
+    /// Like [`Database::import_into_session`], with the result of each row
+    pub fn import_rows_into_session(
+        &self,
+        session: &NewImportSession,
+        transactions: &[NewTransaction],
+    ) -> Result<(i64, Vec<TransactionInsertResult>)> {
+        let session_id = self.create_import_session(session)?;
+        let mut results = Vec::with_capacity(transactions.len());
+        let mut imported = 0;
+        let mut skipped = 0;
+
+        for tx in transactions {
+            let result =
+                self.insert_transaction_with_session(session.account_id, tx, session_id)?;
+            match result {
+                TransactionInsertResult::Inserted(_) => imported += 1,
+                TransactionInsertResult::Duplicate(existing_id) => {
+                    skipped += 1;
+                    // Record the skipped transaction for history
+                    if let Err(e) = self.record_skipped_transaction(
+                        session_id,
+                        tx.date,
+                        &tx.description,
+                        tx.amount,
+                        &tx.import_hash,
+                        Some(existing_id),
+                    ) {
+                        warn!("Failed to record skipped transaction: {}", e);
+                    }
+                }
+            }
+            results.push(result);
+        }
+
+        if let Err(e) = self.update_import_session_results(
+            session_id,
+            imported as i64,
+            skipped as i64,
+            &ImportTaggingBreakdown::default(),
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+        ) {
+            warn!("Failed to update import session results: {}", e);
+        }
+
+        Ok((session_id, results))
+    }
+
+    /// Queue AI processing (tagging, normalization, detection) for an import
+    ///
+    /// Sessions with nothing imported are completed immediately. If the job
+    /// can't be queued the session is marked failed and the error returned.
+    pub fn queue_import_processing(
+        &self,
+        session_id: i64,
+        imported: usize,
+        model: Option<&str>,
+        actor: &str,
+    ) -> Result<Option<i64>> {
+        if imported == 0 {
+            self.mark_import_completed(session_id)?;
+            return Ok(None);
+        }
+
+        let job = NewJob::new(
+            JobType::ImportProcessing,
+            serde_json::json!({
+                "session_id": session_id,
+                "imported_count": imported as i64,
+                "model": model,
+            }),
+        )
+        .for_entity("import_session", session_id)
+        .created_by(actor);
+
+        match self.create_job(&job) {
+            Ok(id) => Ok(Some(id)),
+            Err(e) => {
+                if let Err(e2) = self.mark_import_failed(session_id, &e.to_string()) {
+                    warn!("Failed to mark import as failed: {}", e2);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Claim an idempotency key for a JSON import
+    ///
+    /// Keys are scoped to the caller that sent them, so two callers never
+    /// share a key. Returns None when the key is new (and now reserved), or
+    /// the caller's existing key so its response can be replayed. Keys older
+    /// than [`IDEMPOTENCY_KEY_DAYS`] are forgotten.
+    pub fn reserve_import_idempotency_key(
+        &self,
+        key: &str,
+        created_by: &str,
+        request_hash: &str,
+    ) -> Result<Option<ImportIdempotencyKey>> {
+        let conn = self.conn()?;
+        conn.execute(
+            "DELETE FROM import_idempotency_keys WHERE created_at < datetime('now', ?)",
+            params![format!("-{} days", IDEMPOTENCY_KEY_DAYS)],
+        )?;
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO import_idempotency_keys (key, created_by, request_hash) VALUES (?, ?, ?)",
+            params![key, created_by, request_hash],
+        )?;
+        if inserted == 1 {
+            return Ok(None);
+        }
+
+        let existing = conn.query_row(
+            r#"
+            SELECT key, created_by, request_hash, import_session_id, response, created_at
+            FROM import_idempotency_keys WHERE key = ? AND created_by = ?
+            "#,
+            params![key, created_by],
+            |row| {
+                let created_at: String = row.get(5)?;
+                Ok(ImportIdempotencyKey {
+                    key: row.get(0)?,
+                    created_by: row.get(1)?,
+                    request_hash: row.get(2)?,
+                    import_session_id: row.get(3)?,
+                    response: row.get(4)?,
+                    created_at: parse_datetime(&created_at),
+                })
+            },
+        )?;
+        Ok(Some(existing))
+    }
+
+    /// Store the response for a reserved idempotency key
+    pub fn complete_import_idempotency_key(
+        &self,
+        key: &str,
+        created_by: &str,
+        import_session_id: i64,
+        response: &str,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "UPDATE import_idempotency_keys SET import_session_id = ?, response = ? WHERE key = ? AND created_by = ?",
+            params![import_session_id, response, key, created_by],
+        )?;
+        Ok(())
+    }
+
+    /// Release a reserved key whose import failed, so it can be retried
+    pub fn release_import_idempotency_key(&self, key: &str, created_by: &str) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "DELETE FROM import_idempotency_keys WHERE key = ? AND created_by = ? AND response IS NULL",
+            params![key, created_by],
+        )?;
+        Ok(())
+    }
+
     /// Update import session with final results
     pub fn update_import_session_results(
         &self,
@@ -289,17 +475,18 @@ impl Database {
     ) -> Result<Vec<Transaction>> {
         let conn = self.conn()?;
 
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             r#"
             SELECT id, account_id, date, description, amount, category, merchant_normalized,
                    import_hash, purchase_location_id, vendor_location_id, trip_id, source,
                    expected_amount, archived, original_data, import_format, card_member, payment_method, created_at
             FROM transactions
-            WHERE import_session_id = ?
+            WHERE import_session_id = ? {}
             ORDER BY date DESC
             LIMIT ? OFFSET ?
             "#,
-        )?;
+            self.account_scope_sql("account_id")
+        ))?;
 
         let transactions = stmt
             .query_map(params![session_id, limit, offset], |row| {
@@ -340,7 +527,10 @@ impl Database {
     pub fn count_import_session_transactions(&self, session_id: i64) -> Result<i64> {
         let conn = self.conn()?;
         let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM transactions WHERE import_session_id = ?",
+            &format!(
+                "SELECT COUNT(*) FROM transactions WHERE import_session_id = ? {}",
+                self.account_scope_sql("account_id")
+            ),
             params![session_id],
             |row| row.get(0),
         )?;
@@ -351,15 +541,16 @@ impl Database {
     pub fn get_skipped_transactions(&self, session_id: i64) -> Result<Vec<SkippedTransaction>> {
         let conn = self.conn()?;
 
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             r#"
             SELECT id, import_session_id, date, description, amount, import_hash,
                    existing_transaction_id, created_at
             FROM import_skipped_transactions
-            WHERE import_session_id = ?
+            WHERE import_session_id = ? {}
             ORDER BY date DESC
             "#,
-        )?;
+            self.transaction_scope_sql("existing_transaction_id")
+        ))?;
 
         let skipped = stmt
             .query_map(params![session_id], |row| {
@@ -495,7 +686,8 @@ impl Database {
     }
 
     /// Recover any import sessions that were left in 'processing' state
-    /// (e.g., due to server restart mid-import). Marks them as failed.
+    /// (e.g., due to server restart mid-import). Marks them as failed unless
+    /// a queued background job will resume them.
     /// Returns the number of sessions recovered.
     pub fn recover_stuck_imports(&self) -> Result<i64> {
         let conn = self.conn()?;
@@ -503,7 +695,11 @@ impl Database {
             r#"UPDATE import_sessions SET
                 status = 'failed',
                 processing_error = 'Server restarted during import. Please re-import the file.'
-            WHERE status = 'processing'"#,
+            WHERE status = 'processing'
+              AND id NOT IN (
+                  SELECT entity_id FROM jobs
+                  WHERE entity_type = 'import_session' AND status = 'queued'
+              )"#,
             [],
         )?;
         Ok(count as i64)
@@ -879,7 +1075,8 @@ impl Database {
     }
 
     /// Recover any reprocess runs that were left in 'running' state
-    /// (e.g., due to server restart mid-reprocess). Marks them as failed.
+    /// (e.g., due to server restart mid-reprocess). Marks them as failed unless
+    /// a queued background job will resume them.
     /// Returns the number of runs recovered.
     pub fn recover_stuck_reprocess_runs(&self) -> Result<i64> {
         let conn = self.conn()?;
@@ -887,7 +1084,12 @@ impl Database {
             r#"UPDATE reprocess_runs SET
                 status = 'failed',
                 completed_at = CURRENT_TIMESTAMP
-            WHERE status = 'running'"#,
+            WHERE status = 'running'
+              AND id NOT IN (
+                  SELECT json_extract(params, '$.run_id') FROM jobs
+                  WHERE job_type = 'reprocess' AND status = 'queued'
+                    AND json_extract(params, '$.run_id') IS NOT NULL
+              )"#,
             [],
         )?;
         Ok(count as i64)
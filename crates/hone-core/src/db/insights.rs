@@ -80,10 +80,17 @@ impl Database {
     }
 
     /// List insight findings with optional status filter
+    ///
+    /// A handle hiding accounts sees no findings: they are computed across
+    /// every account and can't be narrowed to the visible ones.
     pub fn list_insight_findings(
         &self,
         status: Option<InsightStatus>,
     ) -> Result<Vec<InsightFinding>> {
+        if !self.hidden_accounts().is_empty() {
+            return Ok(Vec::new());
+        }
+
         let conn = self.conn()?;
 
         let findings = if let Some(s) = status {
@@ -129,6 +136,10 @@ impl Database {
 
     /// Get top N active insights for dashboard display
     pub fn get_top_insights(&self, limit: usize) -> Result<Vec<InsightFinding>> {
+        if !self.hidden_accounts().is_empty() {
+            return Ok(Vec::new());
+        }
+
         let conn = self.conn()?;
 
         let mut stmt = conn.prepare(
@@ -160,6 +171,10 @@ impl Database {
 
     /// Get a single insight finding by ID
     pub fn get_insight_finding(&self, id: i64) -> Result<Option<InsightFinding>> {
+        if !self.hidden_accounts().is_empty() {
+            return Ok(None);
+        }
+
         let conn = self.conn()?;
 
         let result = conn.query_row(
@@ -226,6 +241,10 @@ impl Database {
 
     /// Count active insights
     pub fn count_active_insights(&self) -> Result<i64> {
+        if !self.hidden_accounts().is_empty() {
+            return Ok(0);
+        }
+
         let conn = self.conn()?;
         let count: i64 = conn.query_row(
             r#"
@@ -0,0 +1,387 @@
+//! Persistent background job queue
+//!
+//! Jobs are claimed atomically by workers, so a job never runs twice at
+//! once. Jobs linked to an import session report the session's processing
+//! progress, which the import pipeline already keeps up to date.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension};
+
+use super::{format_datetime, parse_datetime, Database};
+use crate::error::Result;
+use crate::models::{Job, JobStatus, JobType, NewJob};
+
+/// How long finished jobs are kept before the task scheduler prunes them
+pub const JOB_RETENTION_DAYS: i64 = 30;
+
+/// Job columns, with import progress filled in from the linked session
+const JOB_SELECT: &str = r#"
+    SELECT j.id, j.job_type, j.status, j.params, j.entity_type, j.entity_id,
+           COALESCE(j.progress_phase, s.processing_phase),
+           CASE WHEN j.progress_phase IS NULL AND s.processing_phase IS NOT NULL
+                THEN COALESCE(s.processing_current, 0) ELSE j.progress_current END,
+           CASE WHEN j.progress_phase IS NULL AND s.processing_phase IS NOT NULL
+                THEN COALESCE(s.processing_total, 0) ELSE j.progress_total END,
+           j.result, j.error, j.attempts, j.max_attempts, j.cancel_requested,
+           j.created_by, j.created_at, j.run_after, j.started_at, j.finished_at
+    FROM jobs j
+    LEFT JOIN import_sessions s
+        ON j.entity_type = 'import_session' AND s.id = j.entity_id AND j.status = 'running'
+"#;
+
+impl Database {
+    /// Enqueue a job to run as soon as a worker is free
+    pub fn create_job(&self, job: &NewJob) -> Result<i64> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO jobs (job_type, params, entity_type, entity_id, max_attempts, created_by)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                job.job_type.as_str(),
+                job.params.to_string(),
+                job.entity_type,
+                job.entity_id,
+                job.max_attempts
+                    .unwrap_or_else(|| job.job_type.default_max_attempts())
+                    .max(1),
+                job.created_by,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Get a job by ID
+    pub fn get_job(&self, id: i64) -> Result<Option<Job>> {
+        let conn = self.conn()?;
+        let sql = format!("{} WHERE j.id = ?", JOB_SELECT);
+        let job = conn
+            .query_row(&sql, params![id], Self::row_to_job)
+            .optional()?;
+        Ok(job)
+    }
+
+    /// List jobs, newest first
+    pub fn list_jobs(
+        &self,
+        status: Option<JobStatus>,
+        job_type: Option<JobType>,
+        limit: i64,
+    ) -> Result<Vec<Job>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            "{} WHERE (?1 IS NULL OR j.status = ?1) AND (?2 IS NULL OR j.job_type = ?2)
+             ORDER BY j.id DESC LIMIT ?3",
+            JOB_SELECT
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let jobs = stmt
+            .query_map(
+                params![
+                    status.map(|s| s.as_str()),
+                    job_type.map(|t| t.as_str()),
+                    limit
+                ],
+                Self::row_to_job,
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(jobs)
+    }
+
+    /// The oldest queued or running job of a type with exactly these parameters
+    pub fn find_active_job(
+        &self,
+        job_type: JobType,
+        params: &serde_json::Value,
+    ) -> Result<Option<i64>> {
+        let conn = self.conn()?;
+        let id = conn
+            .query_row(
+                r#"
+                SELECT id FROM jobs
+                WHERE job_type = ? AND params = ? AND status IN ('queued', 'running')
+                ORDER BY id LIMIT 1
+                "#,
+                params![job_type.as_str(), params.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(id)
+    }
+
+    /// Claim the oldest due queued job of the given types and mark it running
+    ///
+    /// Returns None when nothing is due.
+    pub fn claim_next_job(&self, job_types: &[JobType], now: DateTime<Utc>) -> Result<Option<Job>> {
+        if job_types.is_empty() {
+            return Ok(None);
+        }
+        let conn = self.conn()?;
+        let types = job_types
+            .iter()
+            .map(|t| format!("'{}'", t.as_str()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        // The status check in the UPDATE makes the claim atomic across workers
+        let id: Option<i64> = conn
+            .query_row(
+                &format!(
+                    r#"
+                    UPDATE jobs
+                    SET status = 'running', attempts = attempts + 1, started_at = ?1,
+                        finished_at = NULL, error = NULL,
+                        progress_phase = NULL, progress_current = 0, progress_total = 0
+                    WHERE id = (
+                        SELECT id FROM jobs
+                        WHERE status = 'queued' AND run_after <= ?1 AND job_type IN ({})
+                        ORDER BY run_after, id
+                        LIMIT 1
+                    ) AND status = 'queued'
+                    RETURNING id
+                    "#,
+                    types
+                ),
+                params![format_datetime(now)],
+                |row| row.get(0),
+            )
+            .optional()?;
+        drop(conn);
+
+        match id {
+            Some(id) => self.get_job(id),
+            None => Ok(None),
+        }
+    }
+
+    /// Record progress for a running job
+    pub fn update_job_progress(
+        &self,
+        id: i64,
+        phase: &str,
+        current: i64,
+        total: i64,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "UPDATE jobs SET progress_phase = ?, progress_current = ?, progress_total = ? WHERE id = ?",
+            params![phase, current, total, id],
+        )?;
+        Ok(())
+    }
+
+    /// Mark a job completed with its result
+    pub fn complete_job(&self, id: i64, result: &serde_json::Value) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            UPDATE jobs
+            SET status = 'completed', result = ?, error = NULL, finished_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+            params![result.to_string(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Record a failed attempt: queue again at `retry_at`, or give up when None
+    pub fn fail_job(&self, id: i64, error: &str, retry_at: Option<DateTime<Utc>>) -> Result<()> {
+        let conn = self.conn()?;
+        match retry_at {
+            Some(retry_at) => conn.execute(
+                r#"
+                UPDATE jobs
+                SET status = 'queued', error = ?, run_after = ?, started_at = NULL
+                WHERE id = ?
+                "#,
+                params![error, format_datetime(retry_at), id],
+            )?,
+            None => conn.execute(
+                r#"
+                UPDATE jobs
+                SET status = 'failed', error = ?, finished_at = CURRENT_TIMESTAMP
+                WHERE id = ?
+                "#,
+                params![error, id],
+            )?,
+        };
+        Ok(())
+    }
+
+    /// Ask a job to stop
+    ///
+    /// Queued jobs are cancelled immediately; running jobs are flagged and
+    /// stopped by their worker. Returns false if the job already finished.
+    pub fn request_job_cancel(&self, id: i64) -> Result<bool> {
+        let conn = self.conn()?;
+        let cancelled = conn.execute(
+            r#"
+            UPDATE jobs
+            SET status = 'cancelled', cancel_requested = 1, finished_at = CURRENT_TIMESTAMP
+            WHERE id = ? AND status = 'queued'
+            "#,
+            params![id],
+        )?;
+        if cancelled > 0 {
+            return Ok(true);
+        }
+        let flagged = conn.execute(
+            "UPDATE jobs SET cancel_requested = 1 WHERE id = ? AND status = 'running'",
+            params![id],
+        )?;
+        Ok(flagged > 0)
+    }
+
+    /// Ask every unfinished job for a record to stop (e.g. when an import is cancelled)
+    ///
+    /// Returns the number of jobs cancelled or flagged.
+    pub fn request_entity_jobs_cancel(&self, entity_type: &str, entity_id: i64) -> Result<usize> {
+        let conn = self.conn()?;
+        let ids: Vec<i64> = conn
+            .prepare(
+                "SELECT id FROM jobs WHERE entity_type = ? AND entity_id = ? AND status IN ('queued', 'running')",
+            )?
+            .query_map(params![entity_type, entity_id], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        drop(conn);
+
+        let mut count = 0;
+        for id in ids {
+            if self.request_job_cancel(id)? {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Whether cancellation was requested for a job
+    pub fn is_job_cancel_requested(&self, id: i64) -> Result<bool> {
+        let conn = self.conn()?;
+        let requested: Option<bool> = conn
+            .query_row(
+                "SELECT cancel_requested FROM jobs WHERE id = ?",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(requested.unwrap_or(false))
+    }
+
+    /// Mark a running job as cancelled (called by the worker that stopped it)
+    pub fn mark_job_cancelled(&self, id: i64) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            UPDATE jobs
+            SET status = 'cancelled', error = 'Cancelled by user', finished_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// Queue a failed or cancelled job again with a fresh set of attempts
+    pub fn retry_job(&self, id: i64) -> Result<bool> {
+        let conn = self.conn()?;
+        let updated = conn.execute(
+            r#"
+            UPDATE jobs
+            SET status = 'queued', attempts = 0, cancel_requested = 0, error = NULL,
+                result = NULL, run_after = CURRENT_TIMESTAMP, started_at = NULL, finished_at = NULL
+            WHERE id = ? AND status IN ('failed', 'cancelled')
+            "#,
+            params![id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Delete completed, failed and cancelled jobs that finished before `older_than`
+    ///
+    /// The last job of each scheduled task is kept so `/api/schedules` can
+    /// still show its outcome. Returns the number of jobs deleted.
+    pub fn prune_finished_jobs(&self, older_than: DateTime<Utc>) -> Result<usize> {
+        let conn = self.conn()?;
+        let deleted = conn.execute(
+            r#"
+            DELETE FROM jobs
+            WHERE status IN ('completed', 'failed', 'cancelled') AND finished_at < ?
+              AND id NOT IN (
+                  SELECT last_job_id FROM schedule_state WHERE last_job_id IS NOT NULL
+              )
+            "#,
+            params![format_datetime(older_than)],
+        )?;
+        Ok(deleted)
+    }
+
+    /// Recover jobs left running by a server restart
+    ///
+    /// Jobs with attempts left are queued again; the rest are marked failed.
+    /// Cancelled-while-running jobs become cancelled. Returns the number of
+    /// jobs queued again.
+    pub fn recover_interrupted_jobs(&self) -> Result<usize> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            UPDATE jobs
+            SET status = 'cancelled', error = 'Cancelled by user', finished_at = CURRENT_TIMESTAMP
+            WHERE status = 'running' AND cancel_requested = 1
+            "#,
+            [],
+        )?;
+        let requeued = conn.execute(
+            r#"
+            UPDATE jobs
+            SET status = 'queued', error = 'Server restarted while job was running',
+                run_after = CURRENT_TIMESTAMP, started_at = NULL
+            WHERE status = 'running' AND attempts < max_attempts
+            "#,
+            [],
+        )?;
+        conn.execute(
+            r#"
+            UPDATE jobs
+            SET status = 'failed', error = 'Server restarted while job was running',
+                finished_at = CURRENT_TIMESTAMP
+            WHERE status = 'running'
+            "#,
+            [],
+        )?;
+        Ok(requeued)
+    }
+
+    fn row_to_job(row: &rusqlite::Row) -> rusqlite::Result<Job> {
+        let job_type: String = row.get(1)?;
+        let status: String = row.get(2)?;
+        let params: String = row.get(3)?;
+        let result: Option<String> = row.get(9)?;
+        let created_at: String = row.get(15)?;
+        let run_after: String = row.get(16)?;
+        let started_at: Option<String> = row.get(17)?;
+        let finished_at: Option<String> = row.get(18)?;
+        Ok(Job {
+            id: row.get(0)?,
+            job_type: job_type.parse().map_err(|e: String| {
+                rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, e.into())
+            })?,
+            status: status.parse().unwrap_or(JobStatus::Failed),
+            params: serde_json::from_str(&params).unwrap_or(serde_json::Value::Null),
+            entity_type: row.get(4)?,
+            entity_id: row.get(5)?,
+            progress_phase: row.get(6)?,
+            progress_current: row.get(7)?,
+            progress_total: row.get(8)?,
+            result: result.and_then(|r| serde_json::from_str(&r).ok()),
+            error: row.get(10)?,
+            attempts: row.get(11)?,
+            max_attempts: row.get(12)?,
+            cancel_requested: row.get(13)?,
+            created_by: row.get(14)?,
+            created_at: parse_datetime(&created_at),
+            run_after: parse_datetime(&run_after),
+            started_at: started_at.map(|s| parse_datetime(&s)),
+            finished_at: finished_at.map(|s| parse_datetime(&s)),
+        })
+    }
+}
@@ -0,0 +1,311 @@
+//! Built-in auth storage: passwords, TOTP secrets, sessions and passkeys
+
+use rusqlite::{params, OptionalExtension};
+use sha2::{Digest, Sha256};
+
+use super::{parse_datetime, Database};
+use crate::error::Result;
+use crate::models::{AuthSession, NewAuthSession, WebAuthnCredential};
+
+const SESSION_COLUMNS: &str = "id, user_id, csrf_token, auth_method, ip_address, user_agent, created_at, last_used_at, expires_at";
+
+const CREDENTIAL_COLUMNS: &str =
+    "id, user_id, credential_id, public_key, sign_count, name, created_at, last_used_at";
+
+/// Sessions are looked up by the SHA-256 of the cookie token, so a leaked
+/// database doesn't hand out live sessions
+fn hash_session_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+impl Database {
+    /// Set or clear a user's password hash
+    pub fn set_user_password_hash(&self, user_id: i64, hash: Option<&str>) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "UPDATE users SET password_hash = ? WHERE id = ?",
+            params![hash, user_id],
+        )?;
+        Ok(())
+    }
+
+    /// Get a user's password hash
+    pub fn get_user_password_hash(&self, user_id: i64) -> Result<Option<String>> {
+        let conn = self.conn()?;
+        let hash = conn
+            .query_row(
+                "SELECT password_hash FROM users WHERE id = ?",
+                params![user_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(hash)
+    }
+
+    /// Store a TOTP secret; `enabled` = false while the user confirms their first code
+    ///
+    /// Forgets the last accepted time step.
+    pub fn set_user_totp(&self, user_id: i64, secret: Option<&str>, enabled: bool) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "UPDATE users SET totp_secret = ?, totp_enabled = ?, totp_last_step = NULL WHERE id = ?",
+            params![secret, enabled && secret.is_some(), user_id],
+        )?;
+        Ok(())
+    }
+
+    /// Get a user's TOTP secret and whether it is enabled
+    pub fn get_user_totp(&self, user_id: i64) -> Result<Option<(String, bool)>> {
+        let conn = self.conn()?;
+        let totp = conn
+            .query_row(
+                "SELECT totp_secret, totp_enabled FROM users WHERE id = ? AND totp_secret IS NOT NULL",
+                params![user_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        Ok(totp)
+    }
+
+    /// Record the TOTP time step a code was accepted for
+    ///
+    /// Returns false when a code for this step or a later one was already
+    /// accepted, so each code can only be used once.
+    pub fn accept_totp_step(&self, user_id: i64, step: i64) -> Result<bool> {
+        let conn = self.conn()?;
+        let updated = conn.execute(
+            r#"
+            UPDATE users SET totp_last_step = ?1
+            WHERE id = ?2 AND (totp_last_step IS NULL OR totp_last_step < ?1)
+            "#,
+            params![step, user_id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Create a session (the cookie token is hashed before storage)
+    pub fn create_auth_session(&self, session: &NewAuthSession) -> Result<i64> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO auth_sessions
+                (token_hash, user_id, csrf_token, auth_method, ip_address, user_agent, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                hash_session_token(&session.token),
+                session.user_id,
+                session.csrf_token,
+                session.auth_method,
+                session.ip_address,
+                session.user_agent,
+                session.expires_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Look up an unexpired session by cookie token and mark it used
+    pub fn get_auth_session(&self, token: &str) -> Result<Option<AuthSession>> {
+        let conn = self.conn()?;
+        let token_hash = hash_session_token(token);
+        let sql = format!(
+            "SELECT {} FROM auth_sessions WHERE token_hash = ? AND expires_at > datetime('now')",
+            SESSION_COLUMNS
+        );
+        let session = conn
+            .query_row(&sql, params![token_hash], Self::row_to_auth_session)
+            .optional()?;
+        if session.is_some() {
+            conn.execute(
+                "UPDATE auth_sessions SET last_used_at = CURRENT_TIMESTAMP WHERE token_hash = ?",
+                params![token_hash],
+            )?;
+        }
+        Ok(session)
+    }
+
+    /// List a user's active sessions (most recent first)
+    pub fn list_auth_sessions(&self, user_id: i64) -> Result<Vec<AuthSession>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            r#"
+            SELECT {} FROM auth_sessions
+            WHERE user_id = ? AND expires_at > datetime('now')
+            ORDER BY last_used_at DESC, id DESC
+            "#,
+            SESSION_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![user_id], Self::row_to_auth_session)?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// End a session by cookie token
+    pub fn delete_auth_session(&self, token: &str) -> Result<bool> {
+        let conn = self.conn()?;
+        let deleted = conn.execute(
+            "DELETE FROM auth_sessions WHERE token_hash = ?",
+            params![hash_session_token(token)],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// End one of a user's sessions by ID
+    pub fn delete_user_auth_session(&self, user_id: i64, session_id: i64) -> Result<bool> {
+        let conn = self.conn()?;
+        let deleted = conn.execute(
+            "DELETE FROM auth_sessions WHERE id = ? AND user_id = ?",
+            params![session_id, user_id],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// End a user's sessions, optionally keeping the current one (password change)
+    pub fn delete_user_auth_sessions(
+        &self,
+        user_id: i64,
+        except_token: Option<&str>,
+    ) -> Result<usize> {
+        let conn = self.conn()?;
+        let keep = except_token.map(hash_session_token).unwrap_or_default();
+        let deleted = conn.execute(
+            "DELETE FROM auth_sessions WHERE user_id = ? AND token_hash != ?",
+            params![user_id, keep],
+        )?;
+        Ok(deleted)
+    }
+
+    /// Delete expired sessions
+    pub fn prune_expired_auth_sessions(&self) -> Result<usize> {
+        let conn = self.conn()?;
+        let deleted = conn.execute(
+            "DELETE FROM auth_sessions WHERE expires_at <= datetime('now')",
+            [],
+        )?;
+        Ok(deleted)
+    }
+
+    /// Register a passkey
+    pub fn add_webauthn_credential(
+        &self,
+        user_id: i64,
+        credential_id: &str,
+        public_key: &[u8],
+        sign_count: u32,
+        name: &str,
+    ) -> Result<i64> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO webauthn_credentials (user_id, credential_id, public_key, sign_count, name)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+            params![user_id, credential_id, public_key, sign_count, name],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Look up a passkey by its credential ID
+    pub fn get_webauthn_credential(
+        &self,
+        credential_id: &str,
+    ) -> Result<Option<WebAuthnCredential>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            "SELECT {} FROM webauthn_credentials WHERE credential_id = ?",
+            CREDENTIAL_COLUMNS
+        );
+        let credential = conn
+            .query_row(
+                &sql,
+                params![credential_id],
+                Self::row_to_webauthn_credential,
+            )
+            .optional()?;
+        Ok(credential)
+    }
+
+    /// List a user's passkeys
+    pub fn list_webauthn_credentials(&self, user_id: i64) -> Result<Vec<WebAuthnCredential>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            "SELECT {} FROM webauthn_credentials WHERE user_id = ? ORDER BY created_at, id",
+            CREDENTIAL_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![user_id], Self::row_to_webauthn_credential)?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Record a successful passkey login
+    pub fn update_webauthn_sign_count(&self, id: i64, sign_count: u32) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "UPDATE webauthn_credentials SET sign_count = ?, last_used_at = CURRENT_TIMESTAMP WHERE id = ?",
+            params![sign_count, id],
+        )?;
+        Ok(())
+    }
+
+    /// Remove one of a user's passkeys
+    pub fn delete_webauthn_credential(&self, user_id: i64, id: i64) -> Result<bool> {
+        let conn = self.conn()?;
+        let deleted = conn.execute(
+            "DELETE FROM webauthn_credentials WHERE id = ? AND user_id = ?",
+            params![id, user_id],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// Clear every built-in credential for a user (lockout recovery)
+    pub fn reset_user_credentials(&self, user_id: i64) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "UPDATE users SET password_hash = NULL, totp_secret = NULL, totp_enabled = 0, totp_last_step = NULL WHERE id = ?",
+            params![user_id],
+        )?;
+        conn.execute(
+            "DELETE FROM webauthn_credentials WHERE user_id = ?",
+            params![user_id],
+        )?;
+        conn.execute(
+            "DELETE FROM auth_sessions WHERE user_id = ?",
+            params![user_id],
+        )?;
+        Ok(())
+    }
+
+    fn row_to_auth_session(row: &rusqlite::Row) -> rusqlite::Result<AuthSession> {
+        let created_at: String = row.get(6)?;
+        let last_used_at: String = row.get(7)?;
+        let expires_at: String = row.get(8)?;
+        Ok(AuthSession {
+            id: row.get(0)?,
+            user_id: row.get(1)?,
+            csrf_token: row.get(2)?,
+            auth_method: row.get(3)?,
+            ip_address: row.get(4)?,
+            user_agent: row.get(5)?,
+            created_at: parse_datetime(&created_at),
+            last_used_at: parse_datetime(&last_used_at),
+            expires_at: parse_datetime(&expires_at),
+        })
+    }
+
+    fn row_to_webauthn_credential(row: &rusqlite::Row) -> rusqlite::Result<WebAuthnCredential> {
+        let created_at: String = row.get(6)?;
+        let last_used_at: Option<String> = row.get(7)?;
+        Ok(WebAuthnCredential {
+            id: row.get(0)?,
+            user_id: row.get(1)?,
+            credential_id: row.get(2)?,
+            public_key: row.get(3)?,
+            sign_count: row.get(4)?,
+            name: row.get(5)?,
+            created_at: parse_datetime(&created_at),
+            last_used_at: last_used_at.map(|s| parse_datetime(&s)),
+        })
+    }
+}
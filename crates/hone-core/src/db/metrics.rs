@@ -0,0 +1,82 @@
+//! Point-in-time aggregates for Prometheus export
+//!
+//! Everything here is computed at scrape time from existing tables, so the
+//! figures survive restarts and need no separate bookkeeping.
+
+use super::Database;
+use crate::error::Result;
+use crate::models::{AiCallMetrics, MetricsSnapshot, AI_LATENCY_BUCKETS};
+
+impl Database {
+    /// Collect the figures exported on `/metrics`
+    pub fn metrics_snapshot(&self) -> Result<MetricsSnapshot> {
+        let pool_state = self.pool.state();
+        let pool_max_size = self.pool.max_size();
+        let conn = self.conn()?;
+
+        // One SUM per histogram bucket, so the whole histogram is a single query
+        let bucket_columns: Vec<String> = AI_LATENCY_BUCKETS
+            .iter()
+            .map(|le| format!("SUM(latency_ms <= {})", (le * 1000.0) as i64))
+            .collect();
+        let sql = format!(
+            r#"
+            SELECT operation, model, COUNT(*), SUM(NOT success), SUM(latency_ms), {}
+            FROM ollama_metrics
+            GROUP BY operation, model
+            ORDER BY operation, model
+            "#,
+            bucket_columns.join(", ")
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let ai_calls = stmt
+            .query_map([], |row| {
+                let latency_buckets = (0..AI_LATENCY_BUCKETS.len())
+                    .map(|i| row.get(5 + i))
+                    .collect::<rusqlite::Result<Vec<i64>>>()?;
+                Ok(AiCallMetrics {
+                    operation: row.get(0)?,
+                    model: row.get(1)?,
+                    calls: row.get(2)?,
+                    errors: row.get(3)?,
+                    latency_sum_ms: row.get(4)?,
+                    latency_buckets,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let grouped = |sql: &str| -> Result<Vec<(String, i64)>> {
+            let mut stmt = conn.prepare(sql)?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            Ok(rows)
+        };
+        let import_sessions = grouped(
+            "SELECT COALESCE(status, 'pending'), COUNT(*) FROM import_sessions GROUP BY 1 ORDER BY 1",
+        )?;
+        let active_alerts = grouped(
+            "SELECT type, COUNT(*) FROM alerts WHERE dismissed = 0 GROUP BY type ORDER BY type",
+        )?;
+        let active_insights = grouped(
+            "SELECT insight_type, COUNT(*) FROM insight_findings WHERE status = 'active' GROUP BY insight_type ORDER BY insight_type",
+        )?;
+
+        let db_size_bytes = conn.query_row(
+            "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+            [],
+            |row| row.get(0),
+        )?;
+
+        Ok(MetricsSnapshot {
+            ai_calls,
+            import_sessions,
+            active_alerts,
+            active_insights,
+            db_size_bytes,
+            pool_connections: pool_state.connections,
+            pool_idle_connections: pool_state.idle_connections,
+            pool_max_size,
+        })
+    }
+}
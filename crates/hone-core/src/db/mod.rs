@@ -4,14 +4,32 @@
 //! - `accounts` - Bank account operations
 //! - `transactions` - Transaction CRUD
 //! - `subscriptions` - Subscription detection and management
+//! - `cancellations` - Tracked subscription cancellations and their verification
 //! - `alerts` - Alert and dashboard operations
+//! - `trials` - Free-trial and intro-price forecasts
+//! - `detection_settings` - Saved detection thresholds with merchant/tag overrides
+//! - `api_keys` - Database-managed API keys (hashed secrets, scopes, expiry)
+//! - `audit` - Hash-chained audit log (query, verify, archive)
 //! - `tags` - Hierarchical tags, rules, and transaction-tag associations
 //! - `entities` - Entities, splits, locations, trips, mileage
+//! - `catalog` - Item catalog (receipt line items normalized per merchant)
 //! - `receipts` - Receipt workflow operations
+//! - `warranties` - Warranties and return windows for purchased items
+//! - `refunds` - Refund matching (credits linked to original purchases)
 //! - `reports` - Spending reports and analytics
 //! - `ollama_metrics` - Ollama LLM call tracking and quality metrics
-
-use chrono::{DateTime, Utc};
+//! - `metrics` - Point-in-time aggregates for Prometheus export
+//! - `notifications` - Notification delivery tracking and new-event cursors
+//! - `jobs` - Persistent background job queue
+//! - `schedules` - Next/last run bookkeeping for cron-scheduled tasks
+//! - `explore` - Persisted explore mode conversations
+//! - `saved_questions` - Scheduled explore questions and their digests
+//! - `users` - Household users, roles and per-account visibility
+//! - `local_auth` - Built-in auth: passwords, TOTP, sessions and passkeys
+
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDate, Utc};
 use r2d2::{Pool, PooledConnection};
 use r2d2_sqlite::SqliteConnectionManager;
 use tracing::info;
@@ -20,21 +38,46 @@ use crate::error::{Error, Result};
 
 mod accounts;
 mod alerts;
+mod anomalies;
+mod api_keys;
+mod audit;
 mod backup;
+mod cancellations;
+mod catalog;
+mod detection_settings;
 mod entities;
+mod explore;
 mod feedback;
 mod import_history;
 mod insights;
+mod jobs;
+mod local_auth;
+mod metrics;
+mod notifications;
 mod ollama_metrics;
 mod receipts;
+mod refunds;
 mod reports;
+mod saved_questions;
+mod schedules;
 mod subscriptions;
 mod tags;
 mod transaction_filter;
 mod transactions;
-
+mod trials;
+mod users;
+mod warranties;
+
+pub use api_keys::MAX_API_KEY_DAYS;
+pub use audit::AUDIT_GENESIS_HASH;
+pub use catalog::normalize_item_name;
+pub use detection_settings::DetectionScope;
+pub use import_history::IDEMPOTENCY_KEY_DAYS;
+pub use jobs::JOB_RETENTION_DAYS;
+pub use refunds::REFUND_WINDOW_DAYS;
 pub use transaction_filter::{FilterResult, TransactionFilter};
 pub use transactions::TransactionInsertResult;
+pub use warranties::{RETURN_REMINDER_DAYS, WARRANTY_REMINDER_DAYS};
 
 pub type DbPool = Pool<SqliteConnectionManager>;
 pub type DbConn = PooledConnection<SqliteConnectionManager>;
@@ -76,12 +119,24 @@ pub(crate) fn parse_datetime(s: &str) -> DateTime<Utc> {
         .unwrap_or_else(|_| Utc::now())
 }
 
+/// Format a DateTime<Utc> the way SQLite's `datetime()` stores it
+pub(crate) fn format_datetime(dt: DateTime<Utc>) -> String {
+    dt.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Parse an optional SQLite date column ("YYYY-MM-DD")
+pub(crate) fn parse_date(s: Option<String>) -> Option<NaiveDate> {
+    s.and_then(|s| NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok())
+}
+
 /// Database wrapper with connection pooling
 #[derive(Clone)]
 pub struct Database {
     pool: DbPool,
     /// Path to the database file
     db_path: String,
+    /// Accounts left out of account-scoped reads (see `with_hidden_accounts`)
+    hidden_accounts: Arc<[i64]>,
 }
 
 impl Database {
@@ -135,6 +190,7 @@ impl Database {
         let db = Self {
             pool,
             db_path: path.to_string(),
+            hidden_accounts: Arc::from([]),
         };
         db.run_migrations()?;
 
@@ -177,33 +233,107 @@ impl Database {
         Ok(self.pool.get()?)
     }
 
+    /// A handle to the same database that leaves these accounts out of every
+    /// account-scoped read
+    ///
+    /// Accounts, transactions, reports, receipts, warranties, subscriptions,
+    /// alerts and exports read through the returned handle skip the accounts
+    /// (and everything linked to their transactions). Writes are unaffected.
+    pub fn with_hidden_accounts(&self, account_ids: Vec<i64>) -> Self {
+        Self {
+            hidden_accounts: account_ids.into(),
+            ..self.clone()
+        }
+    }
+
+    /// Accounts this handle hides (empty for an unscoped handle)
+    pub fn hidden_accounts(&self) -> &[i64] {
+        &self.hidden_accounts
+    }
+
+    /// `AND` condition leaving out rows of hidden accounts, or "" when unscoped
+    ///
+    /// Rows with a NULL account (unlinked subscriptions, unmatched outer joins)
+    /// are kept. IDs are integers, so they are inlined rather than bound.
+    pub(crate) fn account_scope_sql(&self, account_column: &str) -> String {
+        if self.hidden_accounts.is_empty() {
+            return String::new();
+        }
+        format!(
+            "AND ({col} IS NULL OR {col} NOT IN ({ids}))",
+            col = account_column,
+            ids = self.hidden_account_list()
+        )
+    }
+
+    /// `AND` condition keeping rows whose (nullable) transaction link is visible
+    pub(crate) fn transaction_scope_sql(&self, transaction_column: &str) -> String {
+        if self.hidden_accounts.is_empty() {
+            return String::new();
+        }
+        format!(
+            "AND ({col} IS NULL OR {col} NOT IN (SELECT id FROM transactions WHERE account_id IN ({ids})))",
+            col = transaction_column,
+            ids = self.hidden_account_list()
+        )
+    }
+
+    fn hidden_account_list(&self) -> String {
+        self.hidden_accounts
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
     /// Soft reset: clear all transactional data but preserve configuration
     ///
-    /// Clears: transactions, subscriptions, alerts, receipts, audit_log, ollama_metrics,
-    ///         transaction_tags, transaction_splits, split_tags, price_history, mileage_logs,
-    ///         import_sessions, import_skipped_transactions
-    /// Preserves: accounts, tags, tag_rules, entities, locations, trips, merchant_aliases
+    /// Clears: transactions, subscriptions, subscription_cancellations, subscription_cadences,
+    ///         alerts, trial_forecasts, transaction_anomalies,
+    ///         receipts, warranties, ollama_metrics, transaction_tags, transaction_splits, split_tags,
+    ///         split_catalog_items, price_history, mileage_logs,
+    ///         import_sessions, import_skipped_transactions, explore_sessions, explore_turns,
+    ///         explore_digests, receipt_match_feedback, receipt_parse_corrections, mail_messages,
+    ///         alert notification deliveries (the alert cursor restarts at 0),
+    ///         jobs linked to import sessions
+    /// Preserves: accounts, tags, tag_rules, entities, locations, trips, merchant_aliases,
+    ///            catalog_items (item names and merges are relinked on the next sync),
+    ///            saved_questions, audit_log (the audit trail survives resets)
     pub fn soft_reset(&self) -> Result<()> {
         let conn = self.conn()?;
 
         // Delete in order respecting foreign key constraints
         conn.execute_batch(
             r#"
+            DELETE FROM warranties;
             DELETE FROM split_tags;
+            DELETE FROM split_catalog_items;
             DELETE FROM transaction_splits;
             DELETE FROM transaction_tags;
             DELETE FROM mileage_logs;
             DELETE FROM price_history;
+            DELETE FROM receipt_match_feedback;
+            DELETE FROM receipt_parse_corrections;
             DELETE FROM receipts;
+            DELETE FROM mail_messages;
+            DELETE FROM trial_forecasts;
+            DELETE FROM transaction_anomalies;
             DELETE FROM alerts;
+            DELETE FROM subscription_cancellations;
+            DELETE FROM subscription_cadences;
             DELETE FROM subscriptions;
             DELETE FROM import_skipped_transactions;
             DELETE FROM transactions;
             DELETE FROM import_sessions;
-            DELETE FROM audit_log;
             DELETE FROM ollama_metrics;
             DELETE FROM ollama_corrections;
             DELETE FROM user_feedback;
+            DELETE FROM explore_turns;
+            DELETE FROM explore_sessions;
+            DELETE FROM explore_digests;
+            DELETE FROM notification_deliveries WHERE source_type = 'alert';
+            UPDATE notification_state SET value = '0' WHERE key = 'alert_cursor';
+            DELETE FROM jobs WHERE entity_type = 'import_session';
             "#,
         )?;
 
@@ -247,6 +377,71 @@ impl Database {
 
             CREATE INDEX IF NOT EXISTS idx_accounts_entity ON accounts(entity_id);
 
+            -- Household users (who may sign in and with which role)
+            CREATE TABLE IF NOT EXISTS users (
+                id INTEGER PRIMARY KEY,
+                email TEXT NOT NULL UNIQUE COLLATE NOCASE,  -- CF Access email / API key identity
+                display_name TEXT,
+                role TEXT NOT NULL DEFAULT 'viewer',        -- owner, editor, viewer
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                last_seen_at DATETIME,
+                password_hash TEXT,                         -- Argon2id PHC string (built-in auth)
+                totp_secret TEXT,                           -- base32, set up but unconfirmed until totp_enabled
+                totp_enabled INTEGER NOT NULL DEFAULT 0,
+                totp_last_step INTEGER                      -- last accepted TOTP time step (codes are single use)
+            );
+
+            -- Built-in auth sessions (the cookie holds the token, we store its SHA-256)
+            CREATE TABLE IF NOT EXISTS auth_sessions (
+                id INTEGER PRIMARY KEY,
+                token_hash TEXT NOT NULL UNIQUE,
+                user_id INTEGER NOT NULL REFERENCES users(id),
+                csrf_token TEXT NOT NULL,
+                auth_method TEXT NOT NULL,                  -- password, password+totp, passkey
+                ip_address TEXT,
+                user_agent TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                last_used_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                expires_at DATETIME NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_auth_sessions_user ON auth_sessions(user_id);
+
+            -- WebAuthn passkeys (ES256 public keys)
+            CREATE TABLE IF NOT EXISTS webauthn_credentials (
+                id INTEGER PRIMARY KEY,
+                user_id INTEGER NOT NULL REFERENCES users(id),
+                credential_id TEXT NOT NULL UNIQUE,         -- base64url
+                public_key BLOB NOT NULL,                   -- SEC1 uncompressed P-256 point
+                sign_count INTEGER NOT NULL DEFAULT 0,
+                name TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                last_used_at DATETIME
+            );
+            CREATE INDEX IF NOT EXISTS idx_webauthn_credentials_user ON webauthn_credentials(user_id);
+
+            -- Database-managed API keys (only a SHA-256 of the secret is stored)
+            CREATE TABLE IF NOT EXISTS api_keys (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL UNIQUE,
+                key_prefix TEXT NOT NULL,
+                key_hash TEXT NOT NULL UNIQUE,
+                scope TEXT NOT NULL,
+                created_by TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                expires_at DATETIME,
+                last_used_at DATETIME,
+                revoked_at DATETIME
+            );
+
+            -- Per-account visibility: an account with no rows is shared with the
+            -- whole household; otherwise only the listed users (and owners) see it
+            CREATE TABLE IF NOT EXISTS account_visibility (
+                account_id INTEGER NOT NULL REFERENCES accounts(id),
+                user_id INTEGER NOT NULL REFERENCES users(id),
+                PRIMARY KEY (account_id, user_id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_account_visibility_user ON account_visibility(user_id);
+
             -- Locations (for tracking where purchases were made)
             -- Defined before transactions because transactions references locations
             CREATE TABLE IF NOT EXISTS locations (
@@ -317,6 +512,35 @@ impl Database {
             );
 
             CREATE INDEX IF NOT EXISTS idx_subscriptions_status ON subscriptions(status);
+
+            -- Tracked cancellations (verified against later charges)
+            CREATE TABLE IF NOT EXISTS subscription_cancellations (
+                id INTEGER PRIMARY KEY,
+                subscription_id INTEGER NOT NULL REFERENCES subscriptions(id) ON DELETE CASCADE,
+                cancelled_on DATE NOT NULL,
+                method TEXT,                     -- 'website', 'app', 'phone', ...
+                confirmation_number TEXT,
+                expected_final_charge DATE,
+                expected_refund REAL,
+                refund_transaction_id INTEGER REFERENCES transactions(id) ON DELETE SET NULL,
+                refund_amount REAL,
+                status TEXT NOT NULL DEFAULT 'pending',  -- 'pending', 'confirmed', 'charged_after'
+                late_charge_transaction_id INTEGER REFERENCES transactions(id) ON DELETE SET NULL,
+                verified_at DATETIME,
+                notes TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_subscription_cancellations_sub ON subscription_cancellations(subscription_id);
+            CREATE INDEX IF NOT EXISTS idx_subscription_cancellations_status ON subscription_cancellations(status);
+
+            -- Grace windows learned from each subscription's charge history
+            CREATE TABLE IF NOT EXISTS subscription_cadences (
+                subscription_id INTEGER PRIMARY KEY REFERENCES subscriptions(id) ON DELETE CASCADE,
+                grace_days INTEGER NOT NULL,
+                samples INTEGER NOT NULL,        -- intervals the window was learned from
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
             CREATE INDEX IF NOT EXISTS idx_subscriptions_account ON subscriptions(account_id);
 
             -- Price History (track subscription price changes)
@@ -342,7 +566,49 @@ impl Database {
             CREATE INDEX IF NOT EXISTS idx_alerts_type ON alerts(type);
             CREATE INDEX IF NOT EXISTS idx_alerts_dismissed ON alerts(dismissed);
 
+            -- Trial forecasts (free trials / intro prices expected to convert)
+            CREATE TABLE IF NOT EXISTS trial_forecasts (
+                id INTEGER PRIMARY KEY,
+                merchant TEXT NOT NULL,
+                account_id INTEGER NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+                transaction_id INTEGER NOT NULL UNIQUE REFERENCES transactions(id) ON DELETE CASCADE,
+                signal TEXT NOT NULL,            -- 'authorization', 'intro_price', 'known_merchant'
+                trial_amount REAL NOT NULL,
+                started_on DATE NOT NULL,
+                expected_conversion DATE NOT NULL,
+                expected_amount REAL,
+                status TEXT NOT NULL DEFAULT 'pending',  -- 'pending', 'converted', 'lapsed'
+                alert_id INTEGER REFERENCES alerts(id) ON DELETE SET NULL,
+                converted_transaction_id INTEGER REFERENCES transactions(id) ON DELETE SET NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_trial_forecasts_status ON trial_forecasts(status);
+            CREATE INDEX IF NOT EXISTS idx_trial_forecasts_alert ON trial_forecasts(alert_id);
+
+            -- Per-transaction anomalies (unusual amount, new merchant, unusual activity, duplicate charge)
+            CREATE TABLE IF NOT EXISTS transaction_anomalies (
+                id INTEGER PRIMARY KEY,
+                alert_id INTEGER NOT NULL REFERENCES alerts(id) ON DELETE CASCADE,
+                transaction_id INTEGER NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+                related_transaction_id INTEGER REFERENCES transactions(id) ON DELETE SET NULL,
+                anomaly_type TEXT NOT NULL,      -- alert type: 'unusual_amount', 'new_merchant', ...
+                account_id INTEGER NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+                merchant TEXT NOT NULL,
+                amount REAL NOT NULL,
+                transaction_date DATE NOT NULL,
+                baseline_amount REAL,            -- merchant's usual charge
+                threshold REAL NOT NULL,         -- threshold crossed, after feedback tuning
+                detail TEXT,                     -- country code or 'card_not_present'
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(transaction_id, anomaly_type)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_transaction_anomalies_alert ON transaction_anomalies(alert_id);
+            CREATE INDEX IF NOT EXISTS idx_transaction_anomalies_merchant ON transaction_anomalies(anomaly_type, merchant);
+
             -- Audit log (tracks all API access for security)
+            -- Hash-chained: entry_hash covers the entry's fields and prev_hash
             CREATE TABLE IF NOT EXISTS audit_log (
                 id INTEGER PRIMARY KEY,
                 timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
@@ -350,12 +616,31 @@ impl Database {
                 action TEXT NOT NULL,
                 entity_type TEXT,
                 entity_id INTEGER,
-                details TEXT
+                details TEXT,
+                prev_hash TEXT NOT NULL,
+                entry_hash TEXT NOT NULL
             );
 
             CREATE INDEX IF NOT EXISTS idx_audit_log_user ON audit_log(user_email);
             CREATE INDEX IF NOT EXISTS idx_audit_log_timestamp ON audit_log(timestamp);
             CREATE INDEX IF NOT EXISTS idx_audit_log_action ON audit_log(action);
+            CREATE INDEX IF NOT EXISTS idx_audit_log_entity ON audit_log(entity_type, entity_id);
+
+            -- Audit entries moved out of audit_log by retention (chain intact)
+            CREATE TABLE IF NOT EXISTS audit_log_archive (
+                id INTEGER PRIMARY KEY,
+                timestamp DATETIME NOT NULL,
+                user_email TEXT NOT NULL,
+                action TEXT NOT NULL,
+                entity_type TEXT,
+                entity_id INTEGER,
+                details TEXT,
+                prev_hash TEXT NOT NULL,
+                entry_hash TEXT NOT NULL,
+                archived_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_audit_log_archive_timestamp ON audit_log_archive(timestamp);
 
             -- Tags (hierarchical categorization)
             CREATE TABLE IF NOT EXISTS tags (
@@ -438,6 +723,44 @@ impl Database {
 
             CREATE INDEX IF NOT EXISTS idx_split_tags_tag ON split_tags(tag_id);
 
+            -- Item catalog: receipt line items normalized per merchant
+            CREATE TABLE IF NOT EXISTS catalog_items (
+                id INTEGER PRIMARY KEY,
+                merchant TEXT NOT NULL,
+                name TEXT NOT NULL,
+                normalized_name TEXT NOT NULL,
+                merged_into INTEGER REFERENCES catalog_items(id) ON DELETE SET NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE(merchant, normalized_name)
+            );
+
+            -- Item splits linked to their catalog item
+            CREATE TABLE IF NOT EXISTS split_catalog_items (
+                split_id INTEGER PRIMARY KEY REFERENCES transaction_splits(id) ON DELETE CASCADE,
+                catalog_item_id INTEGER NOT NULL REFERENCES catalog_items(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_split_catalog_items_item ON split_catalog_items(catalog_item_id);
+
+            -- Refunds linked to the purchases they reverse (partial refunds allowed)
+            CREATE TABLE IF NOT EXISTS refund_matches (
+                id INTEGER PRIMARY KEY,
+                refund_transaction_id INTEGER NOT NULL UNIQUE REFERENCES transactions(id) ON DELETE CASCADE,
+                original_transaction_id INTEGER NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+                split_id INTEGER REFERENCES transaction_splits(id) ON DELETE SET NULL,
+                amount REAL NOT NULL,                       -- portion refunded (positive)
+                source TEXT NOT NULL DEFAULT 'auto',        -- auto, manual
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_refund_matches_original ON refund_matches(original_transaction_id);
+
+            -- Credits a user unlinked, so the matcher leaves them alone
+            CREATE TABLE IF NOT EXISTS refund_match_dismissals (
+                refund_transaction_id INTEGER PRIMARY KEY REFERENCES transactions(id) ON DELETE CASCADE,
+                dismissed_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
             -- Receipts (for AI parsing and receipt-first workflow)
             CREATE TABLE IF NOT EXISTS receipts (
                 id INTEGER PRIMARY KEY,
@@ -459,6 +782,62 @@ impl Database {
             CREATE INDEX IF NOT EXISTS idx_receipts_status ON receipts(status);
             CREATE INDEX IF NOT EXISTS idx_receipts_hash ON receipts(content_hash);
 
+            -- Emails already scanned for receipts (mailboxes are only read, never marked)
+            CREATE TABLE IF NOT EXISTS mail_messages (
+                message_key TEXT PRIMARY KEY,              -- Message-ID, or SHA256 of the raw message
+                mailbox TEXT NOT NULL,
+                subject TEXT,
+                receipts_created INTEGER NOT NULL DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- Manual receipt match decisions (link = match, unlink = not a match)
+            -- Used as training data for receipt match evaluation
+            CREATE TABLE IF NOT EXISTS receipt_match_feedback (
+                id INTEGER PRIMARY KEY,
+                receipt_id INTEGER NOT NULL REFERENCES receipts(id) ON DELETE CASCADE,
+                transaction_id INTEGER NOT NULL REFERENCES transactions(id) ON DELETE CASCADE,
+                is_match BOOLEAN NOT NULL,
+                created_by TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_receipt_match_feedback_receipt ON receipt_match_feedback(receipt_id);
+
+            -- User corrections to AI-parsed receipt data
+            -- original_json keeps the first AI output, corrected_json the latest user edit
+            CREATE TABLE IF NOT EXISTS receipt_parse_corrections (
+                receipt_id INTEGER PRIMARY KEY REFERENCES receipts(id) ON DELETE CASCADE,
+                original_json TEXT,
+                corrected_json TEXT NOT NULL,
+                corrected_by TEXT,
+                corrected_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
+            -- Warranties and return windows for purchased items
+            CREATE TABLE IF NOT EXISTS warranties (
+                id INTEGER PRIMARY KEY,
+                item TEXT NOT NULL,
+                merchant TEXT,
+                purchase_date DATE NOT NULL,
+                price REAL,
+                return_by DATE,
+                warranty_expires DATE,
+                serial_number TEXT,
+                receipt_id INTEGER REFERENCES receipts(id) ON DELETE SET NULL,
+                split_id INTEGER REFERENCES transaction_splits(id) ON DELETE SET NULL,
+                transaction_id INTEGER REFERENCES transactions(id) ON DELETE SET NULL,
+                document_receipt_id INTEGER REFERENCES receipts(id) ON DELETE SET NULL,  -- supplementary warranty document
+                notes TEXT,
+                return_alert_id INTEGER REFERENCES alerts(id) ON DELETE SET NULL,
+                warranty_alert_id INTEGER REFERENCES alerts(id) ON DELETE SET NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_warranties_receipt ON warranties(receipt_id);
+            CREATE INDEX IF NOT EXISTS idx_warranties_return_by ON warranties(return_by);
+            CREATE INDEX IF NOT EXISTS idx_warranties_expires ON warranties(warranty_expires);
+
             -- Merchant aliases (learned name variations)
             CREATE TABLE IF NOT EXISTS merchant_aliases (
                 id INTEGER PRIMARY KEY,
@@ -638,6 +1017,30 @@ impl Database {
 
             CREATE INDEX IF NOT EXISTS idx_import_skipped_session ON import_skipped_transactions(import_session_id);
 
+            -- Saved detection settings (household-wide, per merchant, per tag)
+            CREATE TABLE IF NOT EXISTS detection_settings (
+                id INTEGER PRIMARY KEY,
+                scope TEXT NOT NULL,                        -- global, merchant, tag
+                merchant TEXT,                              -- merchant scope: normalized merchant name
+                tag_id INTEGER REFERENCES tags(id) ON DELETE CASCADE,
+                overrides TEXT NOT NULL,                    -- JSON of changed thresholds only
+                updated_by TEXT,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_detection_settings_scope ON detection_settings(scope);
+
+            -- Idempotency keys for the JSON import API (retries replay the stored response)
+            CREATE TABLE IF NOT EXISTS import_idempotency_keys (
+                key TEXT NOT NULL,
+                created_by TEXT NOT NULL,                   -- caller email; keys are per caller
+                request_hash TEXT NOT NULL,                 -- fingerprint of the first request
+                import_session_id INTEGER REFERENCES import_sessions(id) ON DELETE SET NULL,
+                response TEXT,                              -- JSON response, NULL while in progress
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (key, created_by)
+            );
+
             -- User feedback for tracking explicit and implicit signals
             CREATE TABLE IF NOT EXISTS user_feedback (
                 id INTEGER PRIMARY KEY,
@@ -669,6 +1072,9 @@ impl Database {
                 training_examples INTEGER NOT NULL,       -- number of training examples
                 training_data_path TEXT,                  -- path to JSONL training data
                 adapter_path TEXT,                        -- path to LoRA adapter
+                trainer TEXT,                             -- backend that produced the adapter (mlx, llama_cpp)
+                log_path TEXT,                            -- path to trainer output log
+                modelfile_path TEXT,                      -- Ollama Modelfile built from the adapter
                 metrics TEXT,                             -- JSON evaluation metrics
                 notes TEXT,                               -- user notes/description
                 created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
@@ -734,6 +1140,129 @@ impl Database {
             CREATE INDEX IF NOT EXISTS idx_insights_status ON insight_findings(status, last_detected_at);
             CREATE INDEX IF NOT EXISTS idx_insights_type ON insight_findings(insight_type);
             CREATE INDEX IF NOT EXISTS idx_insights_severity ON insight_findings(severity);
+
+            -- Outbound notifications: one row per (event, channel), retried with backoff
+            CREATE TABLE IF NOT EXISTS notification_deliveries (
+                id INTEGER PRIMARY KEY,
+                channel TEXT NOT NULL,
+                source_type TEXT NOT NULL,               -- alert, insight, test
+                source_id INTEGER,
+                event TEXT NOT NULL,                     -- routing key, e.g. alert:zombie
+                severity TEXT NOT NULL DEFAULT 'info',
+                title TEXT NOT NULL,
+                body TEXT NOT NULL,
+                digest BOOLEAN NOT NULL DEFAULT FALSE,
+                urgent BOOLEAN NOT NULL DEFAULT FALSE,   -- ignores quiet hours
+                status TEXT NOT NULL DEFAULT 'pending',  -- pending, sent, failed
+                attempts INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                next_attempt_at DATETIME,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                sent_at DATETIME,
+                UNIQUE(channel, source_type, source_id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_notification_deliveries_due ON notification_deliveries(status, next_attempt_at);
+
+            -- Notifier bookkeeping (highest alert/insight IDs already routed)
+            CREATE TABLE IF NOT EXISTS notification_state (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+
+            -- Background jobs (import processing, reprocess, detection, insights, backups)
+            CREATE TABLE IF NOT EXISTS jobs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                job_type TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'queued',
+                params TEXT NOT NULL DEFAULT '{}',
+                entity_type TEXT,
+                entity_id INTEGER,
+                progress_phase TEXT,
+                progress_current INTEGER NOT NULL DEFAULT 0,
+                progress_total INTEGER NOT NULL DEFAULT 0,
+                result TEXT,
+                error TEXT,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                max_attempts INTEGER NOT NULL DEFAULT 1,
+                cancel_requested INTEGER NOT NULL DEFAULT 0,
+                created_by TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                run_after DATETIME DEFAULT CURRENT_TIMESTAMP,
+                started_at DATETIME,
+                finished_at DATETIME
+            );
+            CREATE INDEX IF NOT EXISTS idx_jobs_status ON jobs(status, run_after);
+            CREATE INDEX IF NOT EXISTS idx_jobs_entity ON jobs(entity_type, entity_id);
+
+            -- Scheduled task bookkeeping (next and last run per configured task)
+            CREATE TABLE IF NOT EXISTS schedule_state (
+                name TEXT PRIMARY KEY,
+                cron TEXT NOT NULL,
+                next_run_at DATETIME,
+                last_run_at DATETIME,
+                last_job_id INTEGER,
+                last_error TEXT
+            );
+
+            -- Explore mode conversations (persisted so they survive restarts)
+            CREATE TABLE IF NOT EXISTS explore_sessions (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                model TEXT,
+                summary TEXT,                            -- rolling summary of summarized turns
+                created_by TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE INDEX IF NOT EXISTS idx_explore_sessions_updated ON explore_sessions(updated_at);
+
+            -- Explore conversation turns (one query and its response)
+            CREATE TABLE IF NOT EXISTS explore_turns (
+                id INTEGER PRIMARY KEY,
+                session_id TEXT NOT NULL REFERENCES explore_sessions(id) ON DELETE CASCADE,
+                query TEXT NOT NULL,
+                response TEXT NOT NULL,
+                tool_calls TEXT NOT NULL DEFAULT '[]',   -- JSON: ToolCallRecord array
+                iterations INTEGER NOT NULL DEFAULT 0,
+                model TEXT,
+                summarized BOOLEAN NOT NULL DEFAULT 0,   -- folded into the session summary
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE INDEX IF NOT EXISTS idx_explore_turns_session ON explore_turns(session_id, id);
+
+            -- Saved explore questions run on a schedule
+            CREATE TABLE IF NOT EXISTS saved_questions (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                query TEXT NOT NULL,
+                model TEXT,                              -- NULL = orchestrator default
+                interval_hours INTEGER NOT NULL DEFAULT 24,
+                enabled BOOLEAN NOT NULL DEFAULT 1,
+                last_run_at DATETIME,
+                next_run_at DATETIME,                    -- NULL = due now
+                created_by TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE INDEX IF NOT EXISTS idx_saved_questions_due ON saved_questions(enabled, next_run_at);
+
+            -- Results of saved question runs, with tool-call traces
+            CREATE TABLE IF NOT EXISTS explore_digests (
+                id INTEGER PRIMARY KEY,
+                saved_question_id INTEGER NOT NULL REFERENCES saved_questions(id) ON DELETE CASCADE,
+                success BOOLEAN NOT NULL,
+                response TEXT NOT NULL DEFAULT '',
+                error_message TEXT,
+                tool_calls TEXT NOT NULL DEFAULT '[]',   -- JSON: ToolCallRecord array
+                iterations INTEGER NOT NULL DEFAULT 0,
+                model TEXT,
+                latency_ms INTEGER NOT NULL DEFAULT 0,
+                change_score REAL,                       -- difference from previous successful run
+                changed BOOLEAN NOT NULL DEFAULT 0,
+                insight_id INTEGER REFERENCES insight_findings(id) ON DELETE SET NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            CREATE INDEX IF NOT EXISTS idx_explore_digests_question ON explore_digests(saved_question_id, id);
             "#,
         )?;
 
@@ -752,6 +1281,41 @@ pub struct AuditEntry {
     pub entity_type: Option<String>,
     pub entity_id: Option<i64>,
     pub details: Option<String>,
+    /// Hash of the previous entry (`AUDIT_GENESIS_HASH` for the first)
+    pub prev_hash: String,
+    /// SHA-256 over this entry's fields and `prev_hash`
+    pub entry_hash: String,
+}
+
+/// Filters for querying the audit log
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub user_email: Option<String>,
+    pub action: Option<String>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i64>,
+    /// Start date (inclusive)
+    pub from: Option<chrono::NaiveDate>,
+    /// End date (inclusive)
+    pub to: Option<chrono::NaiveDate>,
+    /// Include entries moved to `audit_log_archive`
+    pub include_archived: bool,
+    /// None = no limit (exports)
+    pub limit: Option<i64>,
+    pub offset: i64,
+}
+
+/// Result of verifying the audit log hash chain
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AuditVerification {
+    pub valid: bool,
+    /// Entries verified before the first break (or all of them)
+    pub entries_checked: i64,
+    pub archived_entries: i64,
+    /// Hash of the newest entry; record it elsewhere to detect truncation
+    pub head_hash: Option<String>,
+    pub first_invalid_id: Option<i64>,
+    pub error: Option<String>,
 }
 
 #[cfg(test)]
@@ -0,0 +1,291 @@
+//! Notification delivery tracking
+//!
+//! New alerts and insights are found by ID cursors kept in
+//! `notification_state`, so each one is routed exactly once. Deliveries are
+//! unique per (channel, source), which makes enqueueing idempotent.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension};
+
+use super::{format_datetime, parse_datetime, Database};
+use crate::error::Result;
+use crate::models::{
+    AlertType, DeliveryStatus, NewNotificationDelivery, NotificationDelivery, NotificationSource,
+};
+
+const DELIVERY_COLUMNS: &str = "id, channel, source_type, source_id, event, severity, title, body, digest, urgent, status, attempts, last_error, next_attempt_at, created_at, sent_at";
+
+impl Database {
+    /// Get a notifier cursor (None until the notifier first runs)
+    pub fn get_notification_cursor(&self, key: &str) -> Result<Option<i64>> {
+        let conn = self.conn()?;
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM notification_state WHERE key = ?",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value.and_then(|v| v.parse().ok()))
+    }
+
+    /// Set a notifier cursor
+    pub fn set_notification_cursor(&self, key: &str, value: i64) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT INTO notification_state (key, value) VALUES (?, ?)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Highest alert and insight IDs (for starting the cursors without a backlog)
+    pub fn max_notification_source_ids(&self) -> Result<(i64, i64)> {
+        let conn = self.conn()?;
+        let ids = conn.query_row(
+            "SELECT (SELECT COALESCE(MAX(id), 0) FROM alerts),
+                    (SELECT COALESCE(MAX(id), 0) FROM insight_findings)",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(ids)
+    }
+
+    /// Undismissed alerts with an ID above `after_id`, oldest first
+    pub fn alert_notification_sources(&self, after_id: i64) -> Result<Vec<NotificationSource>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT a.id, a.type, a.message, s.merchant
+            FROM alerts a
+            LEFT JOIN subscriptions s ON a.subscription_id = s.id
+            WHERE a.id > ? AND a.dismissed = FALSE
+            ORDER BY a.id
+            "#,
+        )?;
+        let sources = stmt
+            .query_map(params![after_id], |row| {
+                let type_str: String = row.get(1)?;
+                let message: Option<String> = row.get(2)?;
+                let merchant: Option<String> = row.get(3)?;
+                Ok((row.get::<_, i64>(0)?, type_str, message, merchant))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?
+            .into_iter()
+            .map(|(id, type_str, message, merchant)| {
+                let alert_type: Option<AlertType> =
+                    serde_json::from_value(serde_json::Value::String(type_str.clone())).ok();
+                let label = alert_type.map_or(type_str.as_str(), |t| t.label());
+                NotificationSource {
+                    source_type: "alert".to_string(),
+                    source_id: id,
+                    event: format!("alert:{}", type_str),
+                    severity: "warning".to_string(),
+                    title: match merchant {
+                        Some(merchant) => format!("{}: {}", label, merchant),
+                        None => label.to_string(),
+                    },
+                    body: message
+                        .or_else(|| alert_type.map(|t| t.description().to_string()))
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+        Ok(sources)
+    }
+
+    /// Active insights with an ID above `after_id`, oldest first
+    pub fn insight_notification_sources(&self, after_id: i64) -> Result<Vec<NotificationSource>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, insight_type, severity, title, summary
+            FROM insight_findings
+            WHERE id > ? AND status = 'active'
+            ORDER BY id
+            "#,
+        )?;
+        let sources = stmt
+            .query_map(params![after_id], |row| {
+                let insight_type: String = row.get(1)?;
+                Ok(NotificationSource {
+                    source_type: "insight".to_string(),
+                    source_id: row.get(0)?,
+                    event: format!("insight:{}", insight_type),
+                    severity: row.get(2)?,
+                    title: row.get(3)?,
+                    body: row.get(4)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(sources)
+    }
+
+    /// Queue a delivery (None if this source was already queued for the channel)
+    pub fn create_notification_delivery(
+        &self,
+        delivery: &NewNotificationDelivery,
+    ) -> Result<Option<i64>> {
+        let conn = self.conn()?;
+        let inserted = conn.execute(
+            r#"
+            INSERT OR IGNORE INTO notification_deliveries
+                (channel, source_type, source_id, event, severity, title, body, digest, urgent, next_attempt_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                delivery.channel,
+                delivery.source_type,
+                delivery.source_id,
+                delivery.event,
+                delivery.severity,
+                delivery.title,
+                delivery.body,
+                delivery.digest,
+                delivery.urgent,
+                format_datetime(delivery.next_attempt_at),
+            ],
+        )?;
+        Ok((inserted > 0).then(|| conn.last_insert_rowid()))
+    }
+
+    /// Get a delivery by ID
+    pub fn get_notification_delivery(&self, id: i64) -> Result<Option<NotificationDelivery>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            "SELECT {} FROM notification_deliveries WHERE id = ?",
+            DELIVERY_COLUMNS
+        );
+        let delivery = conn
+            .query_row(&sql, params![id], Self::row_to_notification_delivery)
+            .optional()?;
+        Ok(delivery)
+    }
+
+    /// List deliveries, newest first
+    pub fn list_notification_deliveries(
+        &self,
+        status: Option<DeliveryStatus>,
+        limit: i64,
+    ) -> Result<Vec<NotificationDelivery>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            "SELECT {} FROM notification_deliveries WHERE (?1 IS NULL OR status = ?1) ORDER BY id DESC LIMIT ?2",
+            DELIVERY_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let deliveries = stmt
+            .query_map(
+                params![status.map(|s| s.as_str()), limit],
+                Self::row_to_notification_delivery,
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(deliveries)
+    }
+
+    /// Pending deliveries whose next attempt is due, oldest first
+    pub fn due_notification_deliveries(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<NotificationDelivery>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            "SELECT {} FROM notification_deliveries
+             WHERE status = 'pending' AND next_attempt_at <= ?
+             ORDER BY id",
+            DELIVERY_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let deliveries = stmt
+            .query_map(
+                params![format_datetime(now)],
+                Self::row_to_notification_delivery,
+            )?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(deliveries)
+    }
+
+    /// Record a successful send
+    pub fn mark_notification_sent(&self, id: i64) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            UPDATE notification_deliveries
+            SET status = 'sent', attempts = attempts + 1, last_error = NULL,
+                next_attempt_at = NULL, sent_at = CURRENT_TIMESTAMP
+            WHERE id = ?
+            "#,
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// Record a failed send: retry at `retry_at`, or give up when None
+    pub fn mark_notification_failed(
+        &self,
+        id: i64,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            UPDATE notification_deliveries
+            SET status = ?, attempts = attempts + 1, last_error = ?, next_attempt_at = ?
+            WHERE id = ?
+            "#,
+            params![
+                if retry_at.is_some() {
+                    DeliveryStatus::Pending
+                } else {
+                    DeliveryStatus::Failed
+                }
+                .as_str(),
+                error,
+                retry_at.map(format_datetime),
+                id
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Queue a failed delivery again with a fresh set of attempts
+    pub fn retry_notification_delivery(&self, id: i64) -> Result<bool> {
+        let conn = self.conn()?;
+        let updated = conn.execute(
+            r#"
+            UPDATE notification_deliveries
+            SET status = 'pending', attempts = 0, next_attempt_at = CURRENT_TIMESTAMP
+            WHERE id = ? AND status = 'failed'
+            "#,
+            params![id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    fn row_to_notification_delivery(row: &rusqlite::Row) -> rusqlite::Result<NotificationDelivery> {
+        let status: String = row.get(10)?;
+        let next_attempt_at: Option<String> = row.get(13)?;
+        let created_at: String = row.get(14)?;
+        let sent_at: Option<String> = row.get(15)?;
+        Ok(NotificationDelivery {
+            id: row.get(0)?,
+            channel: row.get(1)?,
+            source_type: row.get(2)?,
+            source_id: row.get(3)?,
+            event: row.get(4)?,
+            severity: row.get(5)?,
+            title: row.get(6)?,
+            body: row.get(7)?,
+            digest: row.get(8)?,
+            urgent: row.get(9)?,
+            status: status.parse().unwrap_or(DeliveryStatus::Pending),
+            attempts: row.get(11)?,
+            last_error: row.get(12)?,
+            next_attempt_at: next_attempt_at.map(|s| parse_datetime(&s)),
+            created_at: parse_datetime(&created_at),
+            sent_at: sent_at.map(|s| parse_datetime(&s)),
+        })
+    }
+}
@@ -39,6 +39,18 @@ impl Database {
         Ok(conn.last_insert_rowid())
     }
 
+    /// Delete metrics older than `keep_days` days
+    ///
+    /// Returns the number of metrics deleted.
+    pub fn prune_ollama_metrics(&self, keep_days: i64) -> Result<usize> {
+        let conn = self.conn()?;
+        let deleted = conn.execute(
+            "DELETE FROM ollama_metrics WHERE started_at < datetime('now', ?)",
+            params![format!("-{} days", keep_days.max(0))],
+        )?;
+        Ok(deleted)
+    }
+
     /// Record a user correction of an Ollama tag assignment
     pub fn record_ollama_correction(
         &self,
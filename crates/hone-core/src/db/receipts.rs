@@ -49,12 +49,13 @@ impl Database {
     /// Get receipt by ID
     pub fn get_receipt(&self, id: i64) -> Result<Option<Receipt>> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT id, transaction_id, image_path, parsed_json, parsed_at,
                     status, role, receipt_date, receipt_total, receipt_merchant,
                     content_hash, created_at
-             FROM receipts WHERE id = ?",
-        )?;
+             FROM receipts WHERE id = ? {}",
+            self.transaction_scope_sql("transaction_id")
+        ))?;
 
         let receipt = stmt
             .query_row(params![id], |row| Self::row_to_receipt(row))
@@ -83,12 +84,13 @@ impl Database {
     /// Get receipts for a transaction
     pub fn get_receipts_for_transaction(&self, transaction_id: i64) -> Result<Vec<Receipt>> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT id, transaction_id, image_path, parsed_json, parsed_at,
                     status, role, receipt_date, receipt_total, receipt_merchant,
                     content_hash, created_at
-             FROM receipts WHERE transaction_id = ? ORDER BY role ASC, created_at DESC",
-        )?;
+             FROM receipts WHERE transaction_id = ? {} ORDER BY role ASC, created_at DESC",
+            self.transaction_scope_sql("transaction_id")
+        ))?;
 
         let receipts = stmt
             .query_map(params![transaction_id], |row| Self::row_to_receipt(row))?
@@ -104,7 +106,7 @@ impl Database {
             "SELECT id, transaction_id, image_path, parsed_json, parsed_at,
                     status, role, receipt_date, receipt_total, receipt_merchant,
                     content_hash, created_at
-             FROM receipts WHERE status = 'pending' ORDER BY created_at DESC",
+             FROM receipts WHERE status = 'pending' ORDER BY created_at DESC, id DESC",
         )?;
 
         let receipts = stmt
@@ -117,12 +119,13 @@ impl Database {
     /// Get receipts by status
     pub fn get_receipts_by_status(&self, status: ReceiptStatus) -> Result<Vec<Receipt>> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT id, transaction_id, image_path, parsed_json, parsed_at,
                     status, role, receipt_date, receipt_total, receipt_merchant,
                     content_hash, created_at
-             FROM receipts WHERE status = ? ORDER BY created_at DESC",
-        )?;
+             FROM receipts WHERE status = ? {} ORDER BY created_at DESC, id DESC",
+            self.transaction_scope_sql("transaction_id")
+        ))?;
 
         let receipts = stmt
             .query_map(params![status.as_str()], |row| Self::row_to_receipt(row))?
@@ -245,10 +248,95 @@ impl Database {
     /// Delete a receipt
     pub fn delete_receipt(&self, id: i64) -> Result<()> {
         let conn = self.conn()?;
+        conn.execute(
+            "DELETE FROM receipt_match_feedback WHERE receipt_id = ?",
+            params![id],
+        )?;
+        conn.execute(
+            "DELETE FROM receipt_parse_corrections WHERE receipt_id = ?",
+            params![id],
+        )?;
         conn.execute("DELETE FROM receipts WHERE id = ?", params![id])?;
         Ok(())
     }
 
+    /// Whether an email was already scanned for receipts
+    pub fn is_mail_message_ingested(&self, message_key: &str) -> Result<bool> {
+        let conn = self.conn()?;
+        let found = conn
+            .query_row(
+                "SELECT 1 FROM mail_messages WHERE message_key = ?",
+                params![message_key],
+                |_| Ok(()),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    /// Remember a scanned email so later mailbox scans skip it
+    pub fn record_mail_message(
+        &self,
+        message_key: &str,
+        mailbox: &str,
+        subject: Option<&str>,
+        receipts_created: usize,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT OR IGNORE INTO mail_messages (message_key, mailbox, subject, receipts_created)
+             VALUES (?, ?, ?, ?)",
+            params![message_key, mailbox, subject, receipts_created as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Record a manual match decision (link = match, unlink = not a match)
+    pub fn record_receipt_match_feedback(
+        &self,
+        receipt_id: i64,
+        transaction_id: i64,
+        is_match: bool,
+        created_by: Option<&str>,
+    ) -> Result<i64> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT INTO receipt_match_feedback (receipt_id, transaction_id, is_match, created_by)
+             VALUES (?, ?, ?, ?)",
+            params![receipt_id, transaction_id, is_match, created_by],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Replace a receipt's parsed data with a user correction
+    ///
+    /// The first AI output is kept in `receipt_parse_corrections.original_json` so
+    /// repeated edits don't lose what the model originally produced.
+    pub fn correct_receipt_parsed_data(
+        &self,
+        id: i64,
+        corrected_json: &str,
+        merchant: Option<&str>,
+        date: Option<NaiveDate>,
+        total: Option<f64>,
+        corrected_by: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO receipt_parse_corrections (receipt_id, original_json, corrected_json, corrected_by)
+            SELECT id, parsed_json, ?, ? FROM receipts WHERE id = ?
+            ON CONFLICT(receipt_id) DO UPDATE SET
+                corrected_json = excluded.corrected_json,
+                corrected_by = excluded.corrected_by,
+                corrected_at = CURRENT_TIMESTAMP
+            "#,
+            params![corrected_json, corrected_by, id],
+        )?;
+        drop(conn);
+
+        self.update_receipt_parsed_data(id, corrected_json, merchant, date, total)
+    }
+
     // ========== Auto-Matching Functions ==========
 
     /// Find candidate transactions that could match a receipt
@@ -274,23 +362,26 @@ impl Database {
                             import_hash, purchase_location_id, vendor_location_id, trip_id,
                             source, expected_amount, archived, original_data, import_format, card_member, payment_method, created_at
                      FROM transactions
-                     WHERE date >= ? AND date <= ? AND archived = 0
+                     WHERE date >= ? AND date <= ? AND archived = 0 {}
                      ORDER BY date DESC
-                     LIMIT 100"
+                     LIMIT 100",
+                    self.account_scope_sql("account_id")
                 ),
                 vec![from_date.to_string(), to_date.to_string()],
             )
         } else {
             // No date, search recent transactions
             (
-                "SELECT id, account_id, date, description, amount, category, merchant_normalized,
-                        import_hash, purchase_location_id, vendor_location_id, trip_id,
-                        source, expected_amount, archived, original_data, import_format, card_member, payment_method, created_at
-                 FROM transactions
-                 WHERE archived = 0
-                 ORDER BY date DESC
-                 LIMIT 100"
-                    .to_string(),
+                format!(
+                    "SELECT id, account_id, date, description, amount, category, merchant_normalized,
+                            import_hash, purchase_location_id, vendor_location_id, trip_id,
+                            source, expected_amount, archived, original_data, import_format, card_member, payment_method, created_at
+                     FROM transactions
+                     WHERE archived = 0 {}
+                     ORDER BY date DESC
+                     LIMIT 100",
+                    self.account_scope_sql("account_id")
+                ),
                 vec![],
             )
         };
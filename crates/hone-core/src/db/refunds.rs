@@ -0,0 +1,430 @@
+//! Refund matching: link credits to the purchases they reverse
+
+use chrono::Duration;
+use rusqlite::{params, OptionalExtension};
+
+use super::{parse_datetime, Database};
+use crate::error::{Error, Result};
+use crate::models::*;
+
+/// How far back from a refund to look for the original purchase
+pub const REFUND_WINDOW_DAYS: i64 = 90;
+/// Rounding slack when comparing amounts
+const AMOUNT_EPSILON: f64 = 0.005;
+/// Minimum score for the matcher to link a refund on its own
+const AUTO_MATCH_THRESHOLD: f64 = 0.6;
+
+const TRANSACTION_COLUMNS: &str = "t.id, t.account_id, t.date, t.description, t.amount, t.category,
+    t.merchant_normalized, t.import_hash, t.purchase_location_id, t.vendor_location_id, t.trip_id,
+    t.source, t.expected_amount, t.archived, t.original_data, t.import_format, t.card_member,
+    t.payment_method, t.created_at";
+
+const MATCH_COLUMNS: &str =
+    "id, refund_transaction_id, original_transaction_id, split_id, amount, source, created_at";
+
+/// Words banks add to refund descriptions ("AMAZON REFUND", "RETURN - TARGET")
+const REFUND_WORDS: &[&str] = &[
+    "REFUND", "REFUNDS", "RFND", "RETURN", "RETURNS", "RETURNED", "CREDIT", "REVERSAL",
+];
+
+/// Payment processors that prefix the merchant name ("SQ *JOES PIZZA", "TST* CAFE")
+const PROCESSOR_PREFIXES: &[&str] = &["SQ", "TST", "SP", "PAYPAL", "APLPAY", "APPLEPAY"];
+
+/// Leading words of a merchant name, ignoring processor prefixes, refund
+/// wording and store numbers
+fn merchant_tokens(name: &str) -> Vec<String> {
+    name.to_uppercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| {
+            !w.is_empty() && !w.chars().any(|c| c.is_ascii_digit()) && !REFUND_WORDS.contains(w)
+        })
+        .skip_while(|w| PROCESSOR_PREFIXES.contains(w))
+        .take(3)
+        .map(String::from)
+        .collect()
+}
+
+/// Whether two names look like the same merchant: the shorter name's words
+/// are a prefix of the longer one's ("AMAZON" matches "AMAZON.COM*1A2B3")
+fn same_merchant(a: &str, b: &str) -> bool {
+    let (a, b) = (merchant_tokens(a), merchant_tokens(b));
+    let n = a.len().min(b.len());
+    n > 0 && a[..n] == b[..n]
+}
+
+fn share_merchant(refund: &Transaction, purchase: &Transaction) -> bool {
+    if let (Some(a), Some(b)) = (&refund.merchant_normalized, &purchase.merchant_normalized) {
+        if same_merchant(a, b) {
+            return true;
+        }
+    }
+    same_merchant(&refund.description, &purchase.description)
+}
+
+/// Score a purchase for a refund: exact amounts (whole purchase or one split
+/// item) beat partial refunds; same account and recent purchases break ties
+fn score_candidate(refund: &Transaction, purchase: &Transaction, exact: bool) -> f64 {
+    let days = (refund.date - purchase.date).num_days().max(0) as f64;
+    let mut score = if exact { 0.8 } else { 0.5 };
+    if refund.account_id == purchase.account_id {
+        score += 0.1;
+    }
+    score + 0.1 * (1.0 - days / REFUND_WINDOW_DAYS as f64).max(0.0)
+}
+
+impl Database {
+    fn row_to_refund_match(row: &rusqlite::Row) -> rusqlite::Result<RefundMatch> {
+        let source: String = row.get(5)?;
+        let created_at: String = row.get(6)?;
+        Ok(RefundMatch {
+            id: row.get(0)?,
+            refund_transaction_id: row.get(1)?,
+            original_transaction_id: row.get(2)?,
+            split_id: row.get(3)?,
+            amount: row.get(4)?,
+            source: source.parse().unwrap_or(RefundMatchSource::Auto),
+            created_at: parse_datetime(&created_at),
+        })
+    }
+
+    /// Purchases a refund may belong to, best first
+    pub fn find_refund_candidates(&self, refund_id: i64) -> Result<Vec<RefundCandidate>> {
+        let refund = self.get_refund_transaction(refund_id)?;
+        self.refund_candidates_for(&refund)
+    }
+
+    fn get_refund_transaction(&self, id: i64) -> Result<Transaction> {
+        let refund = self
+            .get_transaction(id)?
+            .ok_or_else(|| Error::NotFound("Transaction not found".to_string()))?;
+        if refund.amount <= 0.0 {
+            return Err(Error::InvalidData(
+                "Only credits (positive amounts) can be refunds".to_string(),
+            ));
+        }
+        Ok(refund)
+    }
+
+    fn refund_candidates_for(&self, refund: &Transaction) -> Result<Vec<RefundCandidate>> {
+        let from = refund.date - Duration::days(REFUND_WINDOW_DAYS);
+        let purchases: Vec<(Transaction, f64)> = {
+            let conn = self.conn()?;
+            let sql = format!(
+                r#"
+                SELECT {},
+                       COALESCE((SELECT SUM(rm.amount) FROM refund_matches rm
+                                 WHERE rm.original_transaction_id = t.id), 0)
+                FROM transactions t
+                WHERE t.amount < 0 AND t.archived = 0 AND t.date >= ? AND t.date <= ? {}
+                ORDER BY t.date DESC
+                "#,
+                TRANSACTION_COLUMNS,
+                self.account_scope_sql("t.account_id")
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt
+                .query_map(params![from.to_string(), refund.date.to_string()], |row| {
+                    Ok((Self::row_to_transaction(row)?, row.get(19)?))
+                })?;
+            rows.collect::<std::result::Result<Vec<_>, _>>()?
+        };
+
+        let mut candidates = Vec::new();
+        for (purchase, refunded) in purchases {
+            if !share_merchant(refund, &purchase) {
+                continue;
+            }
+            let remaining = purchase.amount.abs() - refunded;
+            if refund.amount > remaining + AMOUNT_EPSILON {
+                continue;
+            }
+
+            // A credit for less than the purchase may be one returned item
+            let full = (remaining - refund.amount).abs() <= AMOUNT_EPSILON;
+            let split = if full {
+                None
+            } else {
+                let returned = self.get_refunded_split_ids(purchase.id)?;
+                self.get_splits_for_transaction(purchase.id)?
+                    .into_iter()
+                    .find(|s| {
+                        (s.amount.abs() - refund.amount).abs() <= AMOUNT_EPSILON
+                            && !returned.contains(&s.id)
+                    })
+            };
+
+            let score = score_candidate(refund, &purchase, full || split.is_some());
+            candidates.push(RefundCandidate {
+                transaction: purchase,
+                split,
+                remaining,
+                score,
+            });
+        }
+
+        candidates.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        candidates.truncate(10);
+        Ok(candidates)
+    }
+
+    fn get_refunded_split_ids(&self, original_id: i64) -> Result<Vec<i64>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT split_id FROM refund_matches
+             WHERE original_transaction_id = ? AND split_id IS NOT NULL",
+        )?;
+        let ids = stmt
+            .query_map(params![original_id], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<i64>, _>>()?;
+        Ok(ids)
+    }
+
+    /// Credits not yet linked to a purchase (and not dismissed by a user)
+    fn get_unmatched_refunds(&self) -> Result<Vec<Transaction>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            r#"
+            SELECT {}
+            FROM transactions t
+            WHERE t.amount > 0 AND t.archived = 0
+              AND NOT EXISTS (SELECT 1 FROM refund_matches rm WHERE rm.refund_transaction_id = t.id)
+              AND NOT EXISTS (SELECT 1 FROM refund_match_dismissals d
+                              WHERE d.refund_transaction_id = t.id)
+            ORDER BY t.date, t.id
+            "#,
+            TRANSACTION_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let refunds = stmt
+            .query_map([], Self::row_to_transaction)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(refunds)
+    }
+
+    /// Link unmatched credits to their original purchases
+    /// Returns (matched_count, refunds_checked)
+    pub fn auto_match_refunds(&self) -> Result<(usize, usize)> {
+        let refunds = self.get_unmatched_refunds()?;
+        let mut matched = 0;
+
+        // One at a time, so partial refunds see what earlier ones used up
+        for refund in &refunds {
+            let candidates = self.refund_candidates_for(refund)?;
+            if let Some(best) = candidates.first() {
+                if best.score >= AUTO_MATCH_THRESHOLD {
+                    self.insert_refund_match(
+                        refund.id,
+                        best.transaction.id,
+                        best.split.as_ref().map(|s| s.id),
+                        refund.amount,
+                        RefundMatchSource::Auto,
+                    )?;
+                    matched += 1;
+                }
+            }
+        }
+
+        Ok((matched, refunds.len()))
+    }
+
+    fn insert_refund_match(
+        &self,
+        refund_id: i64,
+        original_id: i64,
+        split_id: Option<i64>,
+        amount: f64,
+        source: RefundMatchSource,
+    ) -> Result<i64> {
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT INTO refund_matches
+                (refund_transaction_id, original_transaction_id, split_id, amount, source)
+             VALUES (?, ?, ?, ?, ?)",
+            params![refund_id, original_id, split_id, amount, source.as_str()],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.execute(
+            "DELETE FROM refund_match_dismissals WHERE refund_transaction_id = ?",
+            params![refund_id],
+        )?;
+        Ok(id)
+    }
+
+    /// Link a refund to its original purchase by hand
+    ///
+    /// `amount` defaults to the whole credit (or the split item's amount when
+    /// a split is given) and may not exceed what's left of the purchase.
+    pub fn link_refund(
+        &self,
+        refund_id: i64,
+        original_id: i64,
+        split_id: Option<i64>,
+        amount: Option<f64>,
+    ) -> Result<RefundMatch> {
+        let refund = self.get_refund_transaction(refund_id)?;
+        let original = self
+            .get_transaction(original_id)?
+            .ok_or_else(|| Error::NotFound("Original transaction not found".to_string()))?;
+        if original.amount >= 0.0 {
+            return Err(Error::InvalidData(
+                "The original transaction must be a purchase (negative amount)".to_string(),
+            ));
+        }
+        if original.date > refund.date {
+            return Err(Error::InvalidData(
+                "The original purchase can't be after the refund".to_string(),
+            ));
+        }
+        if self.get_refund_match(refund_id)?.is_some() {
+            return Err(Error::InvalidData(
+                "This refund is already linked; unlink it first".to_string(),
+            ));
+        }
+
+        let split = match split_id {
+            Some(id) => {
+                let split = self
+                    .get_split_by_id(id)?
+                    .filter(|s| s.transaction_id == original_id)
+                    .ok_or_else(|| {
+                        Error::NotFound("Split not found on the original transaction".to_string())
+                    })?;
+                Some(split)
+            }
+            None => None,
+        };
+
+        let amount = amount
+            .or_else(|| split.as_ref().map(|s| s.amount.abs().min(refund.amount)))
+            .unwrap_or(refund.amount);
+        if !amount.is_finite() || amount <= 0.0 || amount > refund.amount + AMOUNT_EPSILON {
+            return Err(Error::InvalidData(format!(
+                "Refund amount must be between 0 and {:.2}",
+                refund.amount
+            )));
+        }
+        let remaining = original.amount.abs() - self.get_refunded_amount(original_id)?;
+        if amount > remaining + AMOUNT_EPSILON {
+            return Err(Error::InvalidData(format!(
+                "Only ${:.2} of the original purchase is left to refund",
+                remaining.max(0.0)
+            )));
+        }
+
+        self.insert_refund_match(
+            refund_id,
+            original_id,
+            split_id,
+            amount,
+            RefundMatchSource::Manual,
+        )?;
+        self.get_refund_match(refund_id)?
+            .ok_or_else(|| Error::NotFound("Refund match not found".to_string()))
+    }
+
+    /// Unlink a refund; the matcher won't link it again automatically
+    /// Returns false if the refund wasn't linked
+    pub fn unlink_refund(&self, refund_id: i64) -> Result<bool> {
+        let conn = self.conn()?;
+        let deleted = conn.execute(
+            "DELETE FROM refund_matches WHERE refund_transaction_id = ?",
+            params![refund_id],
+        )?;
+        if deleted > 0 {
+            conn.execute(
+                "INSERT OR IGNORE INTO refund_match_dismissals (refund_transaction_id) VALUES (?)",
+                params![refund_id],
+            )?;
+        }
+        Ok(deleted > 0)
+    }
+
+    /// Match for a refund transaction, if linked
+    pub fn get_refund_match(&self, refund_id: i64) -> Result<Option<RefundMatch>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            "SELECT {} FROM refund_matches WHERE refund_transaction_id = ?",
+            MATCH_COLUMNS
+        );
+        let found = conn
+            .query_row(&sql, params![refund_id], Self::row_to_refund_match)
+            .optional()?;
+        Ok(found)
+    }
+
+    /// Refunds linked to a purchase
+    pub fn get_refunds_for_transaction(&self, original_id: i64) -> Result<Vec<RefundMatch>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            "SELECT {} FROM refund_matches WHERE original_transaction_id = ? ORDER BY id",
+            MATCH_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let matches = stmt
+            .query_map(params![original_id], Self::row_to_refund_match)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(matches)
+    }
+
+    /// Total refunded against a purchase
+    pub fn get_refunded_amount(&self, original_id: i64) -> Result<f64> {
+        let conn = self.conn()?;
+        let total: f64 = conn.query_row(
+            "SELECT COALESCE(SUM(amount), 0) FROM refund_matches WHERE original_transaction_id = ?",
+            params![original_id],
+            |row| row.get(0),
+        )?;
+        Ok(total)
+    }
+
+    /// List refund matches with both transactions, newest refund first
+    pub fn list_refund_matches(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<RefundMatchWithTransactions>> {
+        let matches: Vec<RefundMatch> = {
+            let conn = self.conn()?;
+            let sql = format!(
+                r#"
+                SELECT {}
+                FROM refund_matches
+                WHERE 1 = 1 {} {}
+                ORDER BY (SELECT date FROM transactions WHERE id = refund_transaction_id) DESC, id DESC
+                LIMIT ? OFFSET ?
+                "#,
+                MATCH_COLUMNS,
+                self.transaction_scope_sql("refund_transaction_id"),
+                self.transaction_scope_sql("original_transaction_id")
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params![limit, offset], Self::row_to_refund_match)?;
+            rows.collect::<std::result::Result<Vec<_>, _>>()?
+        };
+
+        let mut results = Vec::with_capacity(matches.len());
+        for refund_match in matches {
+            let (Some(refund), Some(original)) = (
+                self.get_transaction(refund_match.refund_transaction_id)?,
+                self.get_transaction(refund_match.original_transaction_id)?,
+            ) else {
+                continue;
+            };
+            let split_description = match refund_match.split_id {
+                Some(id) => self.get_split_by_id(id)?.and_then(|s| s.description),
+                None => None,
+            };
+            results.push(RefundMatchWithTransactions {
+                refund_match,
+                refund,
+                original,
+                split_description,
+            });
+        }
+        Ok(results)
+    }
+}
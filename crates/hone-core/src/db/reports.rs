@@ -11,6 +11,8 @@ impl Database {
     /// Get spending summary for a date range, grouped by root-level tags
     /// Returns categories with amounts and percentages, plus untagged summary
     /// entity_id filters by account owner, card_member filters by cardholder name
+    /// net_refunds subtracts linked refunds from their original purchases
+    #[allow(clippy::too_many_arguments)]
     pub fn get_spending_summary(
         &self,
         from: NaiveDate,
@@ -19,6 +21,7 @@ impl Database {
         expand: bool,
         entity_id: Option<i64>,
         card_member: Option<&str>,
+        net_refunds: bool,
     ) -> Result<SpendingSummary> {
         use crate::models::{ReportPeriod, SpendingSummary, UntaggedSummary};
         let conn = self.conn()?;
@@ -29,8 +32,10 @@ impl Database {
 
         // Get total spending in period (expenses only, negative amounts, exclude archived)
         let total_sql = format!(
-            "SELECT COALESCE(SUM(ABS(t.amount)), 0) FROM transactions t {} WHERE t.amount < 0 AND t.archived = 0 AND t.date BETWEEN ?1 AND ?2 {}",
-            extra_join, extra_where
+            "SELECT COALESCE(SUM({}), 0) FROM transactions t {} WHERE t.amount < 0 AND t.archived = 0 AND t.date BETWEEN ?1 AND ?2 {}",
+            spend_sql("t", net_refunds),
+            extra_join,
+            extra_where
         );
         let mut total_params: Vec<Box<dyn rusqlite::ToSql>> =
             vec![Box::new(from.to_string()), Box::new(to.to_string())];
@@ -42,7 +47,7 @@ impl Database {
         // Get untagged spending (exclude archived)
         let untagged_sql = format!(
             r#"
-            SELECT COALESCE(SUM(ABS(t.amount)), 0), COUNT(*)
+            SELECT COALESCE(SUM({}), 0), COUNT(*)
             FROM transactions t
             {}
             WHERE t.amount < 0
@@ -51,7 +56,9 @@ impl Database {
               {}
               AND NOT EXISTS (SELECT 1 FROM transaction_tags tt WHERE tt.transaction_id = t.id)
             "#,
-            extra_join, extra_where
+            spend_sql("t", net_refunds),
+            extra_join,
+            extra_where
         );
         let mut untagged_params: Vec<Box<dyn rusqlite::ToSql>> =
             vec![Box::new(from.to_string()), Box::new(to.to_string())];
@@ -75,6 +82,7 @@ impl Database {
                 total,
                 entity_id,
                 card_member,
+                net_refunds,
             )?
         } else {
             // Get root-level categories
@@ -86,6 +94,7 @@ impl Database {
                 total,
                 entity_id,
                 card_member,
+                net_refunds,
             )?
         };
 
@@ -119,6 +128,8 @@ impl Database {
     }
 
     /// Helper: build entity/card_member filter SQL clauses with custom table alias
+    ///
+    /// The where part also leaves out accounts hidden from this handle (no params).
     fn build_entity_card_filter_clauses_with_alias(
         &self,
         entity_id: Option<i64>,
@@ -140,11 +151,15 @@ impl Database {
         }
 
         let join_clause = joins.join(" ");
-        let where_clause = if conditions.is_empty() {
+        let mut where_clause = if conditions.is_empty() {
             String::new()
         } else {
             format!("AND {}", conditions.join(" AND "))
         };
+        let scope = self.account_scope_sql(&format!("{}.account_id", tx_alias));
+        if !scope.is_empty() {
+            where_clause = format!("{} {}", where_clause, scope);
+        }
 
         (join_clause, where_clause)
     }
@@ -167,6 +182,7 @@ impl Database {
     }
 
     /// Helper: get root-level category spending
+    #[allow(clippy::too_many_arguments)]
     fn get_category_spending_roots(
         &self,
         conn: &DbConn,
@@ -176,6 +192,7 @@ impl Database {
         total: f64,
         entity_id: Option<i64>,
         card_member: Option<&str>,
+        net_refunds: bool,
     ) -> Result<Vec<CategorySpending>> {
         use crate::models::CategorySpending;
 
@@ -193,7 +210,7 @@ impl Database {
             SELECT
                 root.id,
                 root.name,
-                COALESCE(SUM(ABS(tx.amount)), 0) as amount,
+                COALESCE(SUM({spend}), 0) as amount,
                 COUNT(DISTINCT tx.id) as tx_count
             FROM tags root
             LEFT JOIN tag_tree tt ON tt.root_id = root.id
@@ -209,7 +226,9 @@ impl Database {
             HAVING amount > 0 OR tx_count > 0
             ORDER BY amount DESC
             "#,
-            extra_join, extra_where
+            extra_join,
+            extra_where,
+            spend = spend_sql("tx", net_refunds)
         );
 
         let mut stmt = conn.prepare(&sql)?;
@@ -249,6 +268,7 @@ impl Database {
                     total,
                     entity_id,
                     card_member,
+                    net_refunds,
                 )?;
             }
         }
@@ -257,6 +277,7 @@ impl Database {
     }
 
     /// Helper: get spending filtered to a specific tag
+    #[allow(clippy::too_many_arguments)]
     fn get_category_spending_filtered(
         &self,
         conn: &DbConn,
@@ -267,6 +288,7 @@ impl Database {
         total: f64,
         entity_id: Option<i64>,
         card_member: Option<&str>,
+        net_refunds: bool,
     ) -> Result<Vec<CategorySpending>> {
         use crate::models::CategorySpending;
 
@@ -286,7 +308,7 @@ impl Database {
                 SELECT tags.id FROM tags JOIN tag_tree tt ON tags.parent_id = tt.id
             )
             SELECT
-                COALESCE(SUM(ABS(tx.amount)), 0) as amount,
+                COALESCE(SUM({spend}), 0) as amount,
                 COUNT(DISTINCT tx.id) as tx_count
             FROM tag_tree tt
             LEFT JOIN transaction_tags txg ON txg.tag_id = tt.id
@@ -297,7 +319,9 @@ impl Database {
             {}
             WHERE 1=1 {}
             "#,
-            extra_join, extra_where
+            extra_join,
+            extra_where,
+            spend = spend_sql("tx", net_refunds)
         );
 
         let mut stmt = conn.prepare(&sql)?;
@@ -314,7 +338,16 @@ impl Database {
             stmt.query_row(param_refs.as_slice(), |row| Ok((row.get(0)?, row.get(1)?)))?;
 
         let children = if expand {
-            self.get_category_children(conn, from, to, tag.id, total, entity_id, card_member)?
+            self.get_category_children(
+                conn,
+                from,
+                to,
+                tag.id,
+                total,
+                entity_id,
+                card_member,
+                net_refunds,
+            )?
         } else {
             vec![]
         };
@@ -334,6 +367,7 @@ impl Database {
     }
 
     /// Helper: get child category spending for a parent tag
+    #[allow(clippy::too_many_arguments)]
     fn get_category_children(
         &self,
         conn: &DbConn,
@@ -343,6 +377,7 @@ impl Database {
         total: f64,
         entity_id: Option<i64>,
         card_member: Option<&str>,
+        net_refunds: bool,
     ) -> Result<Vec<CategorySpending>> {
         use crate::models::CategorySpending;
 
@@ -360,7 +395,7 @@ impl Database {
             SELECT
                 child.id,
                 child.name,
-                COALESCE(SUM(ABS(tx.amount)), 0) as amount,
+                COALESCE(SUM({spend}), 0) as amount,
                 COUNT(DISTINCT tx.id) as tx_count
             FROM tags child
             LEFT JOIN tag_tree tt ON tt.child_root_id = child.id
@@ -375,7 +410,9 @@ impl Database {
             HAVING amount > 0 OR tx_count > 0
             ORDER BY amount DESC
             "#,
-            extra_join, extra_where
+            extra_join,
+            extra_where,
+            spend = spend_sql("tx", net_refunds)
         );
 
         let mut stmt = conn.prepare(&sql)?;
@@ -472,6 +509,8 @@ impl Database {
 
     /// Get spending trends over time
     /// entity_id filters by account owner, card_member filters by cardholder name
+    /// net_refunds subtracts linked refunds from their original purchases
+    #[allow(clippy::too_many_arguments)]
     pub fn get_spending_trends(
         &self,
         from: NaiveDate,
@@ -480,6 +519,7 @@ impl Database {
         tag_filter: Option<&str>,
         entity_id: Option<i64>,
         card_member: Option<&str>,
+        net_refunds: bool,
     ) -> Result<TrendsReport> {
         use crate::models::{Granularity, ReportPeriod, TrendDataPoint, TrendsReport};
         let conn = self.conn()?;
@@ -507,7 +547,7 @@ impl Database {
                 )
                 SELECT
                     {} as period,
-                    COALESCE(SUM(ABS(tx.amount)), 0) as amount,
+                    COALESCE(SUM({spend}), 0) as amount,
                     COUNT(DISTINCT tx.id) as tx_count
                 FROM transactions tx
                 JOIN transaction_tags txg ON txg.transaction_id = tx.id
@@ -517,7 +557,10 @@ impl Database {
                 GROUP BY period
                 ORDER BY period
                 "#,
-                period_expr, extra_join, extra_where
+                period_expr,
+                extra_join,
+                extra_where,
+                spend = spend_sql("tx", net_refunds)
             );
             (sql, Some((tag.id, tag_name.to_string())))
         } else {
@@ -526,7 +569,7 @@ impl Database {
                 r#"
                 SELECT
                     {} as period,
-                    COALESCE(SUM(ABS(tx.amount)), 0) as amount,
+                    COALESCE(SUM({spend}), 0) as amount,
                     COUNT(*) as tx_count
                 FROM transactions tx
                 {}
@@ -534,7 +577,10 @@ impl Database {
                 GROUP BY period
                 ORDER BY period
                 "#,
-                period_expr, extra_join, extra_where
+                period_expr,
+                extra_join,
+                extra_where,
+                spend = spend_sql("tx", net_refunds)
             );
             (sql, None)
         };
@@ -713,13 +759,15 @@ impl Database {
         let conn = self.conn()?;
 
         // Get all subscriptions
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             r#"
             SELECT id, merchant, amount, frequency, status, first_seen, last_seen
             FROM subscriptions
+            WHERE 1=1 {}
             ORDER BY CASE WHEN status = 'active' THEN 0 ELSE 1 END, amount DESC
             "#,
-        )?;
+            self.account_scope_sql("account_id")
+        ))?;
 
         let subscriptions: Vec<SubscriptionInfo> = stmt
             .query_map([], |row| {
@@ -754,11 +802,9 @@ impl Database {
             .iter()
             .filter(|s| s.status == "active")
             .map(|s| {
-                match s.frequency.as_str() {
-                    "weekly" => s.amount * 4.33,
-                    "yearly" => s.amount / 12.0,
-                    _ => s.amount, // monthly
-                }
+                s.frequency
+                    .parse::<Frequency>()
+                    .map_or(s.amount, |f| f.monthly_amount(s.amount)) // unknown: monthly
             })
             .sum();
 
@@ -779,36 +825,47 @@ impl Database {
         use crate::models::WasteBreakdown;
 
         // Count zombies and their monthly cost
+        let scope = self.account_scope_sql("s.account_id");
         let (zombie_count, zombie_monthly): (i64, f64) = conn.query_row(
-            r#"
-            SELECT COUNT(*), COALESCE(SUM(s.amount), 0)
-            FROM alerts a
-            JOIN subscriptions s ON s.id = a.subscription_id
-            WHERE a.type = 'zombie' AND a.dismissed = 0
-            "#,
+            &format!(
+                r#"
+                SELECT COUNT(*), COALESCE(SUM(s.amount), 0)
+                FROM alerts a
+                JOIN subscriptions s ON s.id = a.subscription_id
+                WHERE a.type = 'zombie' AND a.dismissed = 0 {}
+                "#,
+                scope
+            ),
             [],
             |row| Ok((row.get(0)?, row.get(1)?)),
         )?;
 
         // Count duplicates (each alert may cover multiple subs, so count alerts)
         let (duplicate_count, duplicate_monthly): (i64, f64) = conn.query_row(
-            r#"
-            SELECT COUNT(*), COALESCE(SUM(s.amount), 0)
-            FROM alerts a
-            LEFT JOIN subscriptions s ON s.id = a.subscription_id
-            WHERE a.type = 'duplicate' AND a.dismissed = 0
-            "#,
+            &format!(
+                r#"
+                SELECT COUNT(*), COALESCE(SUM(s.amount), 0)
+                FROM alerts a
+                LEFT JOIN subscriptions s ON s.id = a.subscription_id
+                WHERE a.type = 'duplicate' AND a.dismissed = 0 {}
+                "#,
+                scope
+            ),
             [],
             |row| Ok((row.get(0)?, row.get(1)?)),
         )?;
 
         // Count price increases and delta
         let (price_increase_count, price_increase_delta): (i64, f64) = conn.query_row(
-            r#"
-            SELECT COUNT(*), 0.0
-            FROM alerts
-            WHERE type = 'price_increase' AND dismissed = 0
-            "#,
+            &format!(
+                r#"
+                SELECT COUNT(*), 0.0
+                FROM alerts a
+                LEFT JOIN subscriptions s ON s.id = a.subscription_id
+                WHERE a.type = 'price_increase' AND a.dismissed = 0 {}
+                "#,
+                scope
+            ),
             [],
             |row| Ok((row.get(0)?, row.get(1)?)),
         )?;
@@ -827,18 +884,30 @@ impl Database {
     }
 
     /// Cancel a subscription (mark as cancelled with date and monthly amount)
+    ///
+    /// Use [`Database::record_cancellation`] to also track the cancellation
+    /// details for verification.
     pub fn cancel_subscription(&self, id: i64, cancelled_at: Option<NaiveDate>) -> Result<()> {
         let conn = self.conn()?;
         let cancel_date = cancelled_at.unwrap_or_else(|| chrono::Utc::now().date_naive());
 
-        // Get the current monthly amount before cancelling
+        // Get the current monthly-equivalent amount before cancelling
         let monthly_amount: Option<f64> = conn
             .query_row(
-                "SELECT amount FROM subscriptions WHERE id = ?1",
+                "SELECT amount, frequency FROM subscriptions WHERE id = ?1",
                 params![id],
-                |row| row.get(0),
+                |row| {
+                    let amount: Option<f64> = row.get(0)?;
+                    let frequency: Option<String> = row.get(1)?;
+                    Ok(amount.map(|amount| {
+                        frequency
+                            .and_then(|f| f.parse::<Frequency>().ok())
+                            .map_or(amount, |f| f.monthly_amount(amount))
+                    }))
+                },
             )
-            .ok();
+            .ok()
+            .flatten();
 
         conn.execute(
             r#"
@@ -855,20 +924,31 @@ impl Database {
     }
 
     /// Get savings report from cancelled subscriptions
+    ///
+    /// Savings accrue for each billing month after the final charge (the
+    /// expected final charge of a tracked cancellation, otherwise the
+    /// cancellation date), plus any refund received. Cancellations verified
+    /// by a clean billing period are also totalled separately.
     pub fn get_savings_report(&self) -> Result<SavingsReport> {
-        use crate::models::{CancelledSubscriptionInfo, SavingsReport};
+        use crate::models::{CancellationStatus, CancelledSubscriptionInfo, SavingsReport};
         let conn = self.conn()?;
         let today = chrono::Utc::now().date_naive();
         let max_months = 12; // Cap savings at 12 months per REPORTS.md
 
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             r#"
-            SELECT id, merchant, cancelled_monthly_amount, cancelled_at
-            FROM subscriptions
-            WHERE status = 'cancelled' AND cancelled_at IS NOT NULL AND cancelled_monthly_amount > 0
-            ORDER BY cancelled_at DESC
+            SELECT s.id, s.merchant, s.cancelled_monthly_amount, s.cancelled_at,
+                   c.expected_final_charge, c.refund_amount, c.status
+            FROM subscriptions s
+            LEFT JOIN subscription_cancellations c ON c.id = (
+                SELECT MAX(id) FROM subscription_cancellations WHERE subscription_id = s.id
+            )
+            WHERE s.status = 'cancelled' AND s.cancelled_at IS NOT NULL AND s.cancelled_monthly_amount > 0
+              {}
+            ORDER BY s.cancelled_at DESC
             "#,
-        )?;
+            self.account_scope_sql("s.account_id")
+        ))?;
 
         let cancelled: Vec<CancelledSubscriptionInfo> = stmt
             .query_map([], |row| {
@@ -876,27 +956,33 @@ impl Database {
                 let merchant: String = row.get(1)?;
                 let monthly_amount: f64 = row.get(2)?;
                 let cancelled_at_str: String = row.get(3)?;
-
-                // Parse cancelled_at date
-                let cancelled_at =
-                    NaiveDate::parse_from_str(&cancelled_at_str, "%Y-%m-%d").unwrap_or(today);
-
-                // Calculate months since cancellation
-                let months_since = ((today.year() - cancelled_at.year()) * 12
-                    + (today.month() as i32 - cancelled_at.month() as i32))
-                    as i64;
-                let months_counted = months_since.min(max_months).max(0);
+                let final_charge_str: Option<String> = row.get(4)?;
+                let refund: f64 = row.get::<_, Option<f64>>(5)?.unwrap_or(0.0);
+                let verification: Option<CancellationStatus> = row
+                    .get::<_, Option<String>>(6)?
+                    .and_then(|s| s.parse().ok());
+
+                // Savings start after the final charge
+                let final_charge_str = final_charge_str.unwrap_or_else(|| cancelled_at_str.clone());
+                let final_charge =
+                    NaiveDate::parse_from_str(&final_charge_str, "%Y-%m-%d").unwrap_or(today);
+
+                let months_counted =
+                    billing_months_between(final_charge, today).clamp(0, max_months);
                 let months_remaining = (max_months - months_counted).max(0);
-                let savings = monthly_amount * months_counted as f64;
+                let savings = monthly_amount * months_counted as f64 + refund;
 
                 Ok(CancelledSubscriptionInfo {
                     id,
                     merchant,
                     monthly_amount,
                     cancelled_at: cancelled_at_str,
+                    final_charge: final_charge_str,
                     months_counted,
                     months_remaining,
+                    refund,
                     savings,
+                    verification,
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -904,11 +990,19 @@ impl Database {
         let total_savings: f64 = cancelled.iter().map(|c| c.savings).sum();
         let total_monthly_saved: f64 = cancelled.iter().map(|c| c.monthly_amount).sum();
         let cancelled_count = cancelled.len() as i64;
+        let confirmed: Vec<&CancelledSubscriptionInfo> = cancelled
+            .iter()
+            .filter(|c| c.verification == Some(CancellationStatus::Confirmed))
+            .collect();
+        let confirmed_savings: f64 = confirmed.iter().map(|c| c.savings).sum();
+        let confirmed_count = confirmed.len() as i64;
 
         Ok(SavingsReport {
             total_savings,
             total_monthly_saved,
             cancelled_count,
+            confirmed_savings,
+            confirmed_count,
             cancelled,
         })
     }
@@ -943,4 +1037,254 @@ impl Database {
             Err(e) => Err(e.into()),
         }
     }
+
+    /// Price history of a catalog item, oldest purchase first
+    pub fn get_item_price_history(&self, item_id: i64) -> Result<ItemPriceHistory> {
+        use crate::error::Error;
+        use crate::models::{ItemPriceHistory, ItemPricePoint};
+
+        self.sync_item_catalog()?;
+        let item = self
+            .get_catalog_item(item_id)?
+            .ok_or_else(|| Error::NotFound(format!("Catalog item {} not found", item_id)))?;
+
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT tx.date, s.amount, tx.id, s.id, s.description
+            FROM split_catalog_items l
+            JOIN transaction_splits s ON s.id = l.split_id
+            JOIN transactions tx ON tx.id = s.transaction_id
+            WHERE l.catalog_item_id = ?1 AND tx.archived = 0 {}
+            ORDER BY tx.date, s.id
+            "#,
+            self.account_scope_sql("tx.account_id")
+        ))?;
+        let points = stmt
+            .query_map(params![item.id], |row| {
+                let date: String = row.get(0)?;
+                Ok(ItemPricePoint {
+                    date: NaiveDate::parse_from_str(&date, "%Y-%m-%d").unwrap_or_default(),
+                    price: row.get(1)?,
+                    transaction_id: row.get(2)?,
+                    split_id: row.get(3)?,
+                    description: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let first_price = points.first().map(|p| p.price);
+        let latest_price = points.last().map(|p| p.price);
+        let min_price = points.iter().map(|p| p.price).reduce(f64::min);
+        let max_price = points.iter().map(|p| p.price).reduce(f64::max);
+        let change_percent = match (first_price, latest_price) {
+            (Some(first), Some(latest)) if points.len() > 1 && first > 0.0 => {
+                Some((latest - first) / first * 100.0)
+            }
+            _ => None,
+        };
+
+        Ok(ItemPriceHistory {
+            item,
+            points,
+            first_price,
+            latest_price,
+            min_price,
+            max_price,
+            change_percent,
+        })
+    }
+
+    /// Item-level inflation: how each catalog item's price moved over a period
+    ///
+    /// Compares an item's first and latest purchase dates in the period
+    /// (averaging purchases on the same day), so items bought on at least two
+    /// dates are tracked. Items are sorted by largest increase first.
+    pub fn get_item_inflation_report(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        merchant: Option<&str>,
+    ) -> Result<ItemInflationReport> {
+        use crate::models::{ItemInflation, ItemInflationReport, ReportPeriod};
+
+        self.sync_item_catalog()?;
+
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT ci.id, ci.merchant, ci.name, tx.date, AVG(s.amount), COUNT(*)
+            FROM catalog_items ci
+            JOIN split_catalog_items l ON l.catalog_item_id = ci.id
+            JOIN transaction_splits s ON s.id = l.split_id
+            JOIN transactions tx ON tx.id = s.transaction_id
+            WHERE tx.archived = 0 AND tx.date BETWEEN ?1 AND ?2
+              AND (?3 IS NULL OR ci.merchant = ?3 COLLATE NOCASE)
+              {}
+            GROUP BY ci.id, tx.date
+            ORDER BY ci.id, tx.date
+            "#,
+            self.account_scope_sql("tx.account_id")
+        ))?;
+        let rows = stmt
+            .query_map(params![from.to_string(), to.to_string(), merchant], |row| {
+                let date: String = row.get(3)?;
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    NaiveDate::parse_from_str(&date, "%Y-%m-%d").unwrap_or_default(),
+                    row.get::<_, f64>(4)?,
+                    row.get::<_, i64>(5)?,
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut items: Vec<ItemInflation> = Vec::new();
+        for group in rows.chunk_by(|a, b| a.0 == b.0) {
+            let (first, last) = (&group[0], &group[group.len() - 1]);
+            if group.len() < 2 || first.4 <= 0.0 {
+                continue;
+            }
+            let change = last.4 / first.4 - 1.0;
+            let days = (last.3 - first.3).num_days();
+            items.push(ItemInflation {
+                item_id: first.0,
+                merchant: first.1.clone(),
+                name: first.2.clone(),
+                purchase_count: group.iter().map(|r| r.5).sum(),
+                start_date: first.3,
+                start_price: first.4,
+                end_date: last.3,
+                end_price: last.4,
+                change_percent: change * 100.0,
+                annualized_percent: (days >= 30)
+                    .then(|| ((1.0 + change).powf(365.0 / days as f64) - 1.0) * 100.0),
+            });
+        }
+        items.sort_by(|a, b| {
+            b.change_percent
+                .partial_cmp(&a.change_percent)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let start_total: f64 = items.iter().map(|i| i.start_price).sum();
+        let end_total: f64 = items.iter().map(|i| i.end_price).sum();
+        let basket_change_percent =
+            (start_total > 0.0).then(|| (end_total / start_total - 1.0) * 100.0);
+
+        Ok(ItemInflationReport {
+            period: ReportPeriod {
+                from: from.to_string(),
+                to: to.to_string(),
+            },
+            merchant: merchant.map(String::from),
+            basket_change_percent,
+            items,
+        })
+    }
+
+    /// Split totals by type (items, tax, tips, fees, discounts, rewards) across
+    /// all split transactions in a period, with each type's top merchants
+    pub fn get_split_type_report(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        merchant_limit: usize,
+    ) -> Result<SplitTypeReport> {
+        use crate::models::{MerchantSummary, ReportPeriod, SplitTypeReport, SplitTypeSummary};
+
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT s.split_type, COALESCE(tx.merchant_normalized, tx.description) as merchant,
+                   SUM(s.amount), COUNT(*), COUNT(DISTINCT tx.id)
+            FROM transaction_splits s
+            JOIN transactions tx ON tx.id = s.transaction_id
+            WHERE tx.archived = 0 AND tx.date BETWEEN ?1 AND ?2 {}
+            GROUP BY s.split_type, merchant
+            ORDER BY s.split_type, ABS(SUM(s.amount)) DESC, merchant
+            "#,
+            self.account_scope_sql("tx.account_id")
+        ))?;
+        let rows = stmt
+            .query_map(params![from.to_string(), to.to_string()], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, f64>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, i64>(4)?,
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        // A transaction belongs to one merchant, so per-merchant transaction
+        // counts add up to the type's total
+        let mut split_types: Vec<SplitTypeSummary> = Vec::new();
+        for group in rows.chunk_by(|a, b| a.0 == b.0) {
+            let Ok(split_type) = group[0].0.parse::<SplitType>() else {
+                continue;
+            };
+            let amount: f64 = group.iter().map(|r| r.2).sum();
+            let split_count: i64 = group.iter().map(|r| r.3).sum();
+            split_types.push(SplitTypeSummary {
+                split_type,
+                amount,
+                split_count,
+                transaction_count: group.iter().map(|r| r.4).sum(),
+                avg_amount: amount / split_count as f64,
+                top_merchants: group
+                    .iter()
+                    .take(merchant_limit)
+                    .map(|r| MerchantSummary {
+                        merchant: r.1.clone(),
+                        amount: r.2,
+                        transaction_count: r.4,
+                    })
+                    .collect(),
+            });
+        }
+        split_types.sort_by(|a, b| {
+            b.amount
+                .abs()
+                .partial_cmp(&a.amount.abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        Ok(SplitTypeReport {
+            period: ReportPeriod {
+                from: from.to_string(),
+                to: to.to_string(),
+            },
+            split_types,
+        })
+    }
+}
+
+/// SQL for a purchase's spending amount. With `net_refunds`, refunds linked
+/// to the purchase are subtracted, so they count in its period and category.
+fn spend_sql(alias: &str, net_refunds: bool) -> String {
+    if net_refunds {
+        format!(
+            "(ABS({a}.amount) - COALESCE((SELECT SUM(rm.amount) FROM refund_matches rm \
+             WHERE rm.original_transaction_id = {a}.id), 0))",
+            a = alias
+        )
+    } else {
+        format!("ABS({}.amount)", alias)
+    }
+}
+
+/// Billing dates passed between `start` and `end` on a monthly cycle
+/// (a charge on Jan 31 next falls due Feb 28/29, so a month counts once the
+/// same day of month, or the month's last day, is reached)
+fn billing_months_between(start: NaiveDate, end: NaiveDate) -> i64 {
+    let mut months =
+        (end.year() - start.year()) as i64 * 12 + end.month() as i64 - start.month() as i64;
+    let end_is_month_end = (end + chrono::Duration::days(1)).day() == 1;
+    if end.day() < start.day() && !end_is_month_end {
+        months -= 1;
+    }
+    months
 }
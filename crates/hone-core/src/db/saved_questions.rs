@@ -0,0 +1,332 @@
+//! Saved explore questions and their scheduled digests
+
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, OptionalExtension};
+
+use super::{format_datetime, parse_datetime, Database};
+use crate::error::{Error, Result};
+use crate::models::{
+    ExploreDigest, NewExploreDigest, NewSavedQuestion, RecentDigest, SavedQuestion,
+};
+
+const QUESTION_COLUMNS: &str = r#"
+    id, name, query, model, interval_hours, enabled, last_run_at, next_run_at,
+    created_by, created_at
+"#;
+
+const DIGEST_COLUMNS: &str = r#"
+    id, saved_question_id, success, response, error_message, tool_calls, iterations,
+    model, latency_ms, change_score, changed, insight_id, created_at
+"#;
+
+impl Database {
+    /// Create a saved question (due to run on the next scheduler pass)
+    pub fn create_saved_question(&self, question: &NewSavedQuestion) -> Result<i64> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO saved_questions (name, query, model, interval_hours, created_by)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+            params![
+                question.name,
+                question.query,
+                question.model,
+                question.interval_hours,
+                question.created_by
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Get a saved question by ID
+    pub fn get_saved_question(&self, id: i64) -> Result<Option<SavedQuestion>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            "SELECT {} FROM saved_questions WHERE id = ?",
+            QUESTION_COLUMNS
+        );
+        let question = conn
+            .query_row(&sql, params![id], Self::row_to_saved_question)
+            .optional()?;
+        Ok(question)
+    }
+
+    /// List saved questions by name
+    ///
+    /// `created_by` limits the list to one user's questions.
+    pub fn list_saved_questions(&self, created_by: Option<&str>) -> Result<Vec<SavedQuestion>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            r#"
+            SELECT {} FROM saved_questions
+            WHERE (?1 IS NULL OR created_by = ?1)
+            ORDER BY name COLLATE NOCASE
+            "#,
+            QUESTION_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![created_by], Self::row_to_saved_question)?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Update a saved question (None = leave unchanged)
+    pub fn update_saved_question(
+        &self,
+        id: i64,
+        name: Option<&str>,
+        query: Option<&str>,
+        model: Option<Option<&str>>,
+        interval_hours: Option<i64>,
+        enabled: Option<bool>,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM saved_questions WHERE id = ?)",
+            params![id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(Error::NotFound(format!("Saved question {}", id)));
+        }
+
+        if let Some(name) = name {
+            conn.execute(
+                "UPDATE saved_questions SET name = ? WHERE id = ?",
+                params![name, id],
+            )?;
+        }
+        if let Some(query) = query {
+            conn.execute(
+                "UPDATE saved_questions SET query = ? WHERE id = ?",
+                params![query, id],
+            )?;
+        }
+        if let Some(model) = model {
+            conn.execute(
+                "UPDATE saved_questions SET model = ? WHERE id = ?",
+                params![model, id],
+            )?;
+        }
+        if let Some(hours) = interval_hours {
+            // Reschedule relative to the last run so a shorter interval takes effect
+            conn.execute(
+                r#"
+                UPDATE saved_questions
+                SET interval_hours = ?1,
+                    next_run_at = CASE WHEN last_run_at IS NULL THEN NULL
+                                       ELSE datetime(last_run_at, '+' || ?1 || ' hours') END
+                WHERE id = ?2
+                "#,
+                params![hours, id],
+            )?;
+        }
+        if let Some(enabled) = enabled {
+            conn.execute(
+                "UPDATE saved_questions SET enabled = ? WHERE id = ?",
+                params![enabled, id],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Delete a saved question and its digests
+    pub fn delete_saved_question(&self, id: i64) -> Result<bool> {
+        let conn = self.conn()?;
+        conn.execute(
+            "DELETE FROM explore_digests WHERE saved_question_id = ?",
+            params![id],
+        )?;
+        let deleted = conn.execute("DELETE FROM saved_questions WHERE id = ?", params![id])?;
+        Ok(deleted > 0)
+    }
+
+    /// Enabled saved questions whose next run time has passed
+    pub fn get_due_saved_questions(&self, now: DateTime<Utc>) -> Result<Vec<SavedQuestion>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            r#"
+            SELECT {} FROM saved_questions
+            WHERE enabled = 1 AND (next_run_at IS NULL OR next_run_at <= ?)
+            ORDER BY next_run_at IS NOT NULL, next_run_at, id
+            "#,
+            QUESTION_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![format_datetime(now)], Self::row_to_saved_question)?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Store a digest and advance the question's schedule
+    pub fn record_explore_digest(&self, digest: &NewExploreDigest) -> Result<i64> {
+        let conn = self.conn()?;
+        let tool_calls_json = serde_json::to_string(&digest.tool_calls)?;
+
+        conn.execute(
+            r#"
+            INSERT INTO explore_digests (
+                saved_question_id, success, response, error_message, tool_calls, iterations,
+                model, latency_ms, change_score, changed
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                digest.saved_question_id,
+                digest.success,
+                digest.response,
+                digest.error_message,
+                tool_calls_json,
+                digest.iterations,
+                digest.model,
+                digest.latency_ms,
+                digest.change_score,
+                digest.changed
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+
+        let interval_hours: i64 = conn.query_row(
+            "SELECT interval_hours FROM saved_questions WHERE id = ?",
+            params![digest.saved_question_id],
+            |row| row.get(0),
+        )?;
+        let now = Utc::now();
+        let next_run = now + Duration::hours(interval_hours.max(1));
+        conn.execute(
+            "UPDATE saved_questions SET last_run_at = ?, next_run_at = ? WHERE id = ?",
+            params![
+                format_datetime(now),
+                format_datetime(next_run),
+                digest.saved_question_id
+            ],
+        )?;
+
+        Ok(id)
+    }
+
+    /// Link a digest to the insight finding it raised
+    pub fn set_explore_digest_insight(&self, digest_id: i64, insight_id: i64) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "UPDATE explore_digests SET insight_id = ? WHERE id = ?",
+            params![insight_id, digest_id],
+        )?;
+        Ok(())
+    }
+
+    /// List digests for a saved question, newest first
+    pub fn list_explore_digests(
+        &self,
+        saved_question_id: i64,
+        limit: i64,
+    ) -> Result<Vec<ExploreDigest>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            r#"
+            SELECT {} FROM explore_digests
+            WHERE saved_question_id = ?
+            ORDER BY id DESC
+            LIMIT ?
+            "#,
+            DIGEST_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params![saved_question_id, limit], Self::row_to_digest)?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Most recent successful digest for a saved question (the comparison baseline)
+    pub fn get_last_successful_digest(
+        &self,
+        saved_question_id: i64,
+    ) -> Result<Option<ExploreDigest>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            r#"
+            SELECT {} FROM explore_digests
+            WHERE saved_question_id = ? AND success = 1
+            ORDER BY id DESC
+            LIMIT 1
+            "#,
+            DIGEST_COLUMNS
+        );
+        let digest = conn
+            .query_row(&sql, params![saved_question_id], Self::row_to_digest)
+            .optional()?;
+        Ok(digest)
+    }
+
+    /// Latest successful digest of each enabled saved question (for the dashboard)
+    pub fn get_recent_digests(&self, limit: i64) -> Result<Vec<RecentDigest>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT q.id, q.name, d.id, d.response, d.changed, d.tool_calls, d.created_at
+            FROM saved_questions q
+            JOIN explore_digests d ON d.id = (
+                SELECT MAX(id) FROM explore_digests
+                WHERE saved_question_id = q.id AND success = 1
+            )
+            WHERE q.enabled = 1
+            ORDER BY d.id DESC
+            LIMIT ?
+            "#,
+        )?;
+        let rows = stmt.query_map(params![limit], |row| {
+            let tool_calls_json: String = row.get(5)?;
+            let tool_call_count = serde_json::from_str::<Vec<serde_json::Value>>(&tool_calls_json)
+                .map(|calls| calls.len() as i64)
+                .unwrap_or(0);
+            let created_at: String = row.get(6)?;
+            Ok(RecentDigest {
+                saved_question_id: row.get(0)?,
+                name: row.get(1)?,
+                digest_id: row.get(2)?,
+                response: row.get(3)?,
+                changed: row.get(4)?,
+                tool_call_count,
+                created_at: parse_datetime(&created_at),
+            })
+        })?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    fn row_to_saved_question(row: &rusqlite::Row) -> rusqlite::Result<SavedQuestion> {
+        let last_run_at: Option<String> = row.get(6)?;
+        let next_run_at: Option<String> = row.get(7)?;
+        let created_at: String = row.get(9)?;
+        Ok(SavedQuestion {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            query: row.get(2)?,
+            model: row.get(3)?,
+            interval_hours: row.get(4)?,
+            enabled: row.get(5)?,
+            last_run_at: last_run_at.map(|s| parse_datetime(&s)),
+            next_run_at: next_run_at.map(|s| parse_datetime(&s)),
+            created_by: row.get(8)?,
+            created_at: parse_datetime(&created_at),
+        })
+    }
+
+    fn row_to_digest(row: &rusqlite::Row) -> rusqlite::Result<ExploreDigest> {
+        let tool_calls_json: String = row.get(5)?;
+        let created_at: String = row.get(12)?;
+        Ok(ExploreDigest {
+            id: row.get(0)?,
+            saved_question_id: row.get(1)?,
+            success: row.get(2)?,
+            response: row.get(3)?,
+            error_message: row.get(4)?,
+            tool_calls: serde_json::from_str(&tool_calls_json).unwrap_or_default(),
+            iterations: row.get(6)?,
+            model: row.get(7)?,
+            latency_ms: row.get(8)?,
+            change_score: row.get(9)?,
+            changed: row.get(10)?,
+            insight_id: row.get(11)?,
+            created_at: parse_datetime(&created_at),
+        })
+    }
+}
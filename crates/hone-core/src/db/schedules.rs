@@ -0,0 +1,90 @@
+//! Scheduled task bookkeeping
+//!
+//! The scheduler keeps each task's next due time here so missed runs are
+//! caught up after a restart, and records what the last run queued.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension};
+
+use super::{format_datetime, parse_datetime, Database};
+use crate::error::Result;
+use crate::models::ScheduleState;
+
+const STATE_COLUMNS: &str = "name, cron, next_run_at, last_run_at, last_job_id, last_error";
+
+impl Database {
+    /// Get a task's schedule state (None until the scheduler first sees it)
+    pub fn get_schedule_state(&self, name: &str) -> Result<Option<ScheduleState>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            "SELECT {} FROM schedule_state WHERE name = ?",
+            STATE_COLUMNS
+        );
+        let state = conn
+            .query_row(&sql, params![name], Self::row_to_schedule_state)
+            .optional()?;
+        Ok(state)
+    }
+
+    /// All recorded schedule states
+    pub fn list_schedule_states(&self) -> Result<Vec<ScheduleState>> {
+        let conn = self.conn()?;
+        let sql = format!("SELECT {} FROM schedule_state ORDER BY name", STATE_COLUMNS);
+        let mut stmt = conn.prepare(&sql)?;
+        let states = stmt
+            .query_map([], Self::row_to_schedule_state)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(states)
+    }
+
+    /// Set when a task is next due (keeps its last-run history)
+    pub fn set_schedule_next_run(
+        &self,
+        name: &str,
+        cron: &str,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO schedule_state (name, cron, next_run_at) VALUES (?1, ?2, ?3)
+            ON CONFLICT(name) DO UPDATE SET cron = ?2, next_run_at = ?3
+            "#,
+            params![name, cron, next_run_at.map(format_datetime)],
+        )?;
+        Ok(())
+    }
+
+    /// Record a run: the job it queued, or why it couldn't be queued
+    pub fn record_schedule_run(
+        &self,
+        name: &str,
+        ran_at: DateTime<Utc>,
+        job_id: Option<i64>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            UPDATE schedule_state
+            SET last_run_at = ?, last_job_id = ?, last_error = ?
+            WHERE name = ?
+            "#,
+            params![format_datetime(ran_at), job_id, error, name],
+        )?;
+        Ok(())
+    }
+
+    fn row_to_schedule_state(row: &rusqlite::Row) -> rusqlite::Result<ScheduleState> {
+        let next_run_at: Option<String> = row.get(2)?;
+        let last_run_at: Option<String> = row.get(3)?;
+        Ok(ScheduleState {
+            name: row.get(0)?,
+            cron: row.get(1)?,
+            next_run_at: next_run_at.map(|s| parse_datetime(&s)),
+            last_run_at: last_run_at.map(|s| parse_datetime(&s)),
+            last_job_id: row.get(4)?,
+            last_error: row.get(5)?,
+        })
+    }
+}
@@ -38,11 +38,11 @@ impl Database {
         };
 
         if let Some(id) = existing {
-            // Update last_seen and amount if provided
+            // Update last_seen and amount (and the cadence, if re-detected) if provided
             if let (Some(amt), Some(ls)) = (amount, last_seen) {
                 conn.execute(
-                    "UPDATE subscriptions SET amount = ?, last_seen = ? WHERE id = ?",
-                    params![amt, ls.to_string(), id],
+                    "UPDATE subscriptions SET amount = ?, last_seen = ?, frequency = COALESCE(?, frequency) WHERE id = ?",
+                    params![amt, ls.to_string(), frequency.map(|f| f.as_str()), id],
                 )?;
             }
             return Ok(id);
@@ -66,6 +66,28 @@ impl Database {
         Ok(conn.last_insert_rowid())
     }
 
+    /// Record the grace window learned from a subscription's charge history
+    pub fn set_subscription_grace(
+        &self,
+        subscription_id: i64,
+        grace_days: i64,
+        samples: usize,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO subscription_cadences (subscription_id, grace_days, samples)
+            VALUES (?, ?, ?)
+            ON CONFLICT(subscription_id) DO UPDATE SET
+                grace_days = excluded.grace_days,
+                samples = excluded.samples,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+            params![subscription_id, grace_days, samples as i64],
+        )?;
+        Ok(())
+    }
+
     /// List all subscriptions, optionally filtered by account
     pub fn list_subscriptions(&self, account_id: Option<i64>) -> Result<Vec<Subscription>> {
         let conn = self.conn()?;
@@ -74,23 +96,29 @@ impl Database {
             account_id
         {
             (
-                r#"
-                SELECT id, merchant, account_id, amount, frequency, first_seen, last_seen, status, user_acknowledged, acknowledged_at, created_at
-                FROM subscriptions
-                WHERE account_id = ?
-                ORDER BY last_seen DESC NULLS LAST
-                "#
-                .to_string(),
+                format!(
+                    r#"
+                    SELECT s.id, s.merchant, s.account_id, s.amount, s.frequency, s.first_seen, s.last_seen, s.status, s.user_acknowledged, s.acknowledged_at, s.created_at, c.grace_days
+                    FROM subscriptions s
+                    LEFT JOIN subscription_cadences c ON c.subscription_id = s.id
+                    WHERE s.account_id = ? {}
+                    ORDER BY s.last_seen DESC NULLS LAST
+                    "#,
+                    self.account_scope_sql("s.account_id")
+                ),
                 vec![Box::new(acc_id)],
             )
         } else {
             (
-                r#"
-                SELECT id, merchant, account_id, amount, frequency, first_seen, last_seen, status, user_acknowledged, acknowledged_at, created_at
-                FROM subscriptions
-                ORDER BY last_seen DESC NULLS LAST
-                "#
-                .to_string(),
+                format!(
+                    r#"
+                    SELECT s.id, s.merchant, s.account_id, s.amount, s.frequency, s.first_seen, s.last_seen, s.status, s.user_acknowledged, s.acknowledged_at, s.created_at, c.grace_days
+                    FROM subscriptions s
+                    LEFT JOIN subscription_cadences c ON c.subscription_id = s.id
+                    WHERE 1=1 {}
+                    "#,
+                    self.account_scope_sql("s.account_id")
+                ),
                 vec![],
             )
         };
@@ -113,12 +141,8 @@ impl Database {
                     merchant: row.get(1)?,
                     account_id: row.get(2)?,
                     amount: row.get(3)?,
-                    frequency: freq_str.and_then(|s| match s.as_str() {
-                        "weekly" => Some(Frequency::Weekly),
-                        "monthly" => Some(Frequency::Monthly),
-                        "yearly" => Some(Frequency::Yearly),
-                        _ => None,
-                    }),
+                    frequency: freq_str.and_then(|s| s.parse().ok()),
+                    grace_days: row.get(11)?,
                     first_seen: first_seen_str
                         .and_then(|s| NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok()),
                     last_seen: last_seen_str
@@ -290,9 +314,10 @@ impl Database {
 
         let result = conn.query_row(
             r#"
-            SELECT id, merchant, account_id, amount, frequency, first_seen, last_seen, status, user_acknowledged, acknowledged_at, created_at
-            FROM subscriptions
-            WHERE id = ?
+            SELECT s.id, s.merchant, s.account_id, s.amount, s.frequency, s.first_seen, s.last_seen, s.status, s.user_acknowledged, s.acknowledged_at, s.created_at, c.grace_days
+            FROM subscriptions s
+            LEFT JOIN subscription_cadences c ON c.subscription_id = s.id
+            WHERE s.id = ?
             "#,
             params![id],
             |row| {
@@ -308,12 +333,8 @@ impl Database {
                     merchant: row.get(1)?,
                     account_id: row.get(2)?,
                     amount: row.get(3)?,
-                    frequency: freq_str.and_then(|s| match s.as_str() {
-                        "weekly" => Some(Frequency::Weekly),
-                        "monthly" => Some(Frequency::Monthly),
-                        "yearly" => Some(Frequency::Yearly),
-                        _ => None,
-                    }),
+                    frequency: freq_str.and_then(|s| s.parse().ok()),
+                    grace_days: row.get(11)?,
                     first_seen: first_seen_str
                         .and_then(|s| NaiveDate::parse_from_str(&s, "%Y-%m-%d").ok()),
                     last_seen: last_seen_str
@@ -589,7 +589,7 @@ impl Database {
     }
 
     /// Build the tag path for a tag
-    fn build_tag_path(&self, conn: &DbConn, tag_id: i64) -> Result<String> {
+    pub(crate) fn build_tag_path(&self, conn: &DbConn, tag_id: i64) -> Result<String> {
         let mut path_parts = Vec::new();
         let mut current_id = Some(tag_id);
 
@@ -1172,6 +1172,7 @@ impl Database {
         } else {
             format!("AND {}", conditions.join(" AND "))
         };
+        let date_filter = format!("{} {}", date_filter, self.account_scope_sql("t.account_id"));
 
         let sql = format!(
             r#"
@@ -698,7 +698,7 @@ mod tests {
         let to = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
 
         let summary = db
-            .get_spending_summary(from, to, None, false, None, None)
+            .get_spending_summary(from, to, None, false, None, None, false)
             .unwrap();
 
         assert_eq!(summary.total, 200.0); // 50 + 100 + 30 + 20
@@ -743,7 +743,7 @@ mod tests {
         let to = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
 
         let report = db
-            .get_spending_trends(from, to, Granularity::Monthly, None, None, None)
+            .get_spending_trends(from, to, Granularity::Monthly, None, None, None, false)
             .unwrap();
 
         assert_eq!(report.data.len(), 3);
@@ -802,6 +802,349 @@ mod tests {
         assert_eq!(report.merchants[2].merchant, "TARGET");
     }
 
+    #[test]
+    fn test_item_catalog_reports() {
+        use chrono::NaiveDate;
+
+        let db = Database::in_memory().unwrap();
+
+        {
+            let conn = db.conn().unwrap();
+            conn.execute(
+                "INSERT INTO accounts (name, bank) VALUES ('Test', 'chase')",
+                [],
+            )
+            .unwrap();
+            for (id, date, amount) in [
+                (1, "2024-01-10", -20.0),
+                (2, "2024-04-10", -21.0),
+                (3, "2024-07-10", -40.0),
+            ] {
+                conn.execute(
+                    "INSERT INTO transactions (id, account_id, date, description, amount, merchant_normalized, import_hash) VALUES (?, 1, ?, 'SAFEWAY #123', ?, 'Safeway', ?)",
+                    params![id, date, amount, format!("hash{}", id)],
+                )
+                .unwrap();
+            }
+        }
+
+        let split = |transaction_id: i64, description: &str, amount: f64, split_type: SplitType| {
+            db.create_split(&NewTransactionSplit {
+                transaction_id,
+                amount,
+                description: Some(description.to_string()),
+                split_type,
+                entity_id: None,
+                purchaser_id: None,
+            })
+            .unwrap()
+        };
+        split(1, "ORG WHL MILK 1GAL", 5.0, SplitType::Item);
+        split(1, "Eggs 12ct", 3.0, SplitType::Item);
+        split(1, "Sales tax", 1.0, SplitType::Tax);
+        split(2, "Whole Milk, Organic", 5.5, SplitType::Item);
+        split(2, "EGGS", 3.0, SplitType::Item);
+        split(2, "Coupon", -2.0, SplitType::Discount);
+        split(3, "Organic Whole Milk", 6.0, SplitType::Item);
+        split(3, "Large Eggs", 4.5, SplitType::Item);
+        split(3, "Tip", 2.0, SplitType::Tip);
+
+        assert_eq!(db.sync_item_catalog().unwrap(), 6);
+        assert_eq!(db.sync_item_catalog().unwrap(), 0);
+
+        let items = db.list_catalog_items(Some("safeway"), None, 10).unwrap();
+        assert_eq!(items.len(), 3);
+        let milk = items
+            .iter()
+            .find(|i| i.name == "ORG WHL MILK 1GAL")
+            .unwrap();
+        assert_eq!(milk.purchase_count, 3);
+        assert_eq!(milk.last_price, Some(6.0));
+        let eggs = items.iter().find(|i| i.name == "Eggs 12ct").unwrap();
+        let large_eggs = items.iter().find(|i| i.name == "Large Eggs").unwrap();
+        assert_eq!(eggs.purchase_count, 2);
+
+        let history = db.get_item_price_history(milk.id).unwrap();
+        assert_eq!(history.points.len(), 3);
+        assert_eq!(history.first_price, Some(5.0));
+        assert_eq!(history.latest_price, Some(6.0));
+        assert!((history.change_percent.unwrap() - 20.0).abs() < 1e-9);
+        assert!(matches!(
+            db.get_item_price_history(9999),
+            Err(crate::Error::NotFound(_))
+        ));
+
+        // Merging follows later receipts too
+        let merged = db.merge_catalog_items(large_eggs.id, eggs.id).unwrap();
+        assert_eq!(merged.purchase_count, 3);
+        split(3, "LARGE EGGS", 4.5, SplitType::Item);
+        db.sync_item_catalog().unwrap();
+        assert_eq!(
+            db.get_catalog_item(eggs.id)
+                .unwrap()
+                .unwrap()
+                .purchase_count,
+            4
+        );
+        assert!(db.merge_catalog_items(eggs.id, eggs.id).is_err());
+
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let inflation = db.get_item_inflation_report(from, to, None).unwrap();
+        assert_eq!(inflation.items.len(), 2);
+        assert_eq!(inflation.items[0].item_id, eggs.id);
+        assert!((inflation.items[0].change_percent - 50.0).abs() < 1e-9);
+        assert!(inflation.items[0].annualized_percent.is_some());
+        // Basket: 8.00 -> 10.50
+        assert!((inflation.basket_change_percent.unwrap() - 31.25).abs() < 1e-9);
+
+        let report = db.get_split_type_report(from, to, 5).unwrap();
+        let by_type = |t: SplitType| {
+            report
+                .split_types
+                .iter()
+                .find(|s| s.split_type == t)
+                .unwrap()
+        };
+        assert_eq!(report.split_types[0].split_type, SplitType::Item);
+        assert_eq!(by_type(SplitType::Item).split_count, 7);
+        assert_eq!(by_type(SplitType::Item).transaction_count, 3);
+        assert_eq!(by_type(SplitType::Discount).amount, -2.0);
+        assert_eq!(by_type(SplitType::Tax).top_merchants[0].merchant, "Safeway");
+        assert_eq!(by_type(SplitType::Tip).amount, 2.0);
+    }
+
+    #[test]
+    fn test_warranties_from_receipts() {
+        use chrono::NaiveDate;
+
+        let db = Database::in_memory().unwrap();
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+
+        {
+            let conn = db.conn().unwrap();
+            conn.execute(
+                "INSERT INTO accounts (name, bank) VALUES ('Test', 'chase')",
+                [],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO transactions (id, account_id, date, description, amount, merchant_normalized, import_hash) VALUES (1, 1, '2024-03-01', 'BEST BUY 00123', -1250.0, 'Best Buy', 'hash1')",
+                [],
+            )
+            .unwrap();
+        }
+        let split_id = db
+            .create_split(&NewTransactionSplit {
+                transaction_id: 1,
+                amount: 1199.99,
+                description: Some("MacBook Air 13".to_string()),
+                split_type: SplitType::Item,
+                entity_id: None,
+                purchaser_id: None,
+            })
+            .unwrap();
+        let receipt_id = db
+            .create_receipt_full(&NewReceipt {
+                transaction_id: Some(1),
+                image_path: None,
+                image_data: None,
+                status: ReceiptStatus::Matched,
+                role: ReceiptRole::Primary,
+                receipt_date: Some(date("2024-02-29")),
+                receipt_total: Some(1250.0),
+                receipt_merchant: Some("Best Buy #123".to_string()),
+                content_hash: None,
+            })
+            .unwrap();
+        let document_id = db
+            .create_receipt_full(&NewReceipt {
+                transaction_id: Some(1),
+                image_path: None,
+                image_data: None,
+                status: ReceiptStatus::Matched,
+                role: ReceiptRole::Supplementary,
+                receipt_date: None,
+                receipt_total: None,
+                receipt_merchant: None,
+                content_hash: None,
+            })
+            .unwrap();
+
+        // Item and price come from the split, merchant and date from the receipt
+        let laptop = db
+            .create_warranty(&NewWarranty {
+                receipt_id: Some(receipt_id),
+                split_id: Some(split_id),
+                document_receipt_id: Some(document_id),
+                return_days: Some(15),
+                warranty_months: Some(12),
+                serial_number: Some("C02XK1".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(laptop.item, "MacBook Air 13");
+        assert_eq!(laptop.price, Some(1199.99));
+        assert_eq!(laptop.merchant.as_deref(), Some("Best Buy #123"));
+        assert_eq!(laptop.transaction_id, Some(1));
+        assert_eq!(laptop.purchase_date, date("2024-02-29"));
+        assert_eq!(laptop.return_by, Some(date("2024-03-15")));
+        assert_eq!(laptop.warranty_expires, Some(date("2025-02-28")));
+
+        // A transaction alone fills in the merchant and date
+        let cable = db
+            .create_warranty(&NewWarranty {
+                item: Some("USB-C cable".to_string()),
+                transaction_id: Some(1),
+                return_days: Some(30),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(cable.merchant.as_deref(), Some("Best Buy"));
+        assert_eq!(cable.purchase_date, date("2024-03-01"));
+
+        let invalid = |new: NewWarranty| {
+            assert!(matches!(
+                db.create_warranty(&new),
+                Err(crate::Error::InvalidData(_))
+            ));
+        };
+        invalid(NewWarranty {
+            item: Some("Toaster".to_string()),
+            purchase_date: Some(date("2024-03-01")),
+            ..Default::default()
+        });
+        invalid(NewWarranty {
+            item: Some("Toaster".to_string()),
+            return_days: Some(30),
+            ..Default::default()
+        });
+        invalid(NewWarranty {
+            item: Some("Toaster".to_string()),
+            purchase_date: Some(date("2024-03-01")),
+            return_by: Some(date("2024-02-01")),
+            ..Default::default()
+        });
+        assert!(matches!(
+            db.create_warranty(&NewWarranty {
+                split_id: Some(9999),
+                return_days: Some(30),
+                ..Default::default()
+            }),
+            Err(crate::Error::NotFound(_))
+        ));
+
+        // Search matches item, merchant and serial number
+        assert_eq!(db.list_warranties(Some("macbook"), None).unwrap().len(), 1);
+        assert_eq!(db.list_warranties(Some("c02x"), None).unwrap().len(), 1);
+        assert_eq!(db.list_warranties(Some("best buy"), None).unwrap().len(), 2);
+        assert_eq!(
+            db.list_warranties(None, Some(document_id)).unwrap()[0].id,
+            laptop.id
+        );
+
+        let report = db.get_warranty_report(None, date("2024-03-20")).unwrap();
+        assert_eq!(report.returnable_count, 1);
+        assert_eq!(report.covered_count, 1);
+        assert_eq!(report.expired_count, 0);
+        assert_eq!(report.items[0].warranty.id, cable.id);
+        assert_eq!(report.items[0].return_days_left, Some(11));
+        assert_eq!(report.items[1].status, WarrantyStatus::Covered);
+        assert_eq!(report.items[1].warranty_days_left, Some(345));
+        assert!((report.covered_value - 1199.99).abs() < 1e-9);
+
+        let report = db
+            .get_warranty_report(Some("macbook"), date("2025-03-01"))
+            .unwrap();
+        assert!(report.items.is_empty());
+        assert_eq!(report.expired_count, 1);
+
+        // Deleting the receipt keeps the warranty
+        {
+            let conn = db.conn().unwrap();
+            conn.execute("DELETE FROM receipts WHERE id = ?", params![receipt_id])
+                .unwrap();
+        }
+        let laptop = db.get_warranty(laptop.id).unwrap().unwrap();
+        assert!(laptop.receipt_id.is_none());
+        assert_eq!(laptop.split_id, Some(split_id));
+
+        assert!(db.delete_warranty(cable.id).unwrap());
+        assert!(!db.delete_warranty(cable.id).unwrap());
+        db.soft_reset().unwrap();
+        assert!(db.list_warranties(None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_warranty_reminders() {
+        use chrono::NaiveDate;
+
+        let db = Database::in_memory().unwrap();
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+
+        let tv = db
+            .create_warranty(&NewWarranty {
+                item: Some("OLED TV".to_string()),
+                merchant: Some("Costco".to_string()),
+                purchase_date: Some(date("2024-05-01")),
+                return_by: Some(date("2024-07-30")),
+                warranty_expires: Some(date("2026-05-01")),
+                ..Default::default()
+            })
+            .unwrap();
+
+        // Nothing is due yet
+        assert_eq!(db.raise_warranty_reminders(date("2024-07-01")).unwrap(), 0);
+
+        // One reminder in the week before the return window closes
+        assert_eq!(db.raise_warranty_reminders(date("2024-07-25")).unwrap(), 1);
+        assert_eq!(db.raise_warranty_reminders(date("2024-07-26")).unwrap(), 0);
+        let tv = db.get_warranty(tv.id).unwrap().unwrap();
+        let alert = db.get_alert(tv.return_alert_id.unwrap()).unwrap();
+        assert_eq!(alert.alert_type, AlertType::ReturnWindowClosing);
+        assert!(alert
+            .message
+            .unwrap()
+            .contains("OLED TV from Costco closes in 5 days"));
+        assert_eq!(alert.warranty.unwrap().id, tv.id);
+
+        // The reminder is dismissed once the window closes
+        db.raise_warranty_reminders(date("2024-07-31")).unwrap();
+        assert!(db.get_alert(tv.return_alert_id.unwrap()).unwrap().dismissed);
+
+        // Warranty reminder a month out
+        assert_eq!(db.raise_warranty_reminders(date("2026-04-10")).unwrap(), 1);
+        let tv = db.get_warranty(tv.id).unwrap().unwrap();
+        let alert = db.get_alert(tv.warranty_alert_id.unwrap()).unwrap();
+        assert_eq!(alert.alert_type, AlertType::WarrantyExpiring);
+        assert!(!alert.dismissed);
+
+        // Extending the warranty retires the reminder so a new one can be raised
+        let tv = db
+            .update_warranty(
+                tv.id,
+                &WarrantyUpdate {
+                    warranty_expires: Some(date("2028-05-01")),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert!(tv.warranty_alert_id.is_none());
+        assert!(db.get_alert(alert.id).unwrap().dismissed);
+        assert_eq!(db.raise_warranty_reminders(date("2026-04-11")).unwrap(), 0);
+
+        assert!(matches!(
+            db.update_warranty(
+                tv.id,
+                &WarrantyUpdate {
+                    return_by: Some(date("2024-01-01")),
+                    ..Default::default()
+                },
+            ),
+            Err(crate::Error::InvalidData(_))
+        ));
+    }
+
     #[test]
     fn test_subscription_cancel_and_savings() {
         use chrono::NaiveDate;
@@ -829,6 +1172,105 @@ mod tests {
         assert!(savings.total_savings > 0.0); // Depends on current date
     }
 
+    #[test]
+    fn test_record_cancellation_and_confirmed_savings() {
+        use crate::models::{CancellationMethod, CancellationStatus, NewSubscriptionCancellation};
+        use chrono::{Duration, Utc};
+
+        let db = Database::in_memory().unwrap();
+        let today = Utc::now().date_naive();
+        let sub_id = db
+            .upsert_subscription(
+                "Netflix",
+                None,
+                Some(15.00),
+                Some(crate::models::Frequency::Monthly),
+                Some(today - Duration::days(400)),
+                Some(today - Duration::days(100)),
+            )
+            .unwrap();
+
+        // Missing subscription and negative refunds are rejected
+        assert!(matches!(
+            db.record_cancellation(999, &NewSubscriptionCancellation::default()),
+            Err(crate::error::Error::NotFound(_))
+        ));
+        assert!(matches!(
+            db.record_cancellation(
+                sub_id,
+                &NewSubscriptionCancellation {
+                    expected_refund: Some(-1.0),
+                    ..Default::default()
+                }
+            ),
+            Err(crate::error::Error::InvalidData(_))
+        ));
+
+        // Re-recording replaces the pending cancellation
+        db.record_cancellation(sub_id, &NewSubscriptionCancellation::default())
+            .unwrap();
+        let cancellation = db
+            .record_cancellation(
+                sub_id,
+                &NewSubscriptionCancellation {
+                    cancelled_on: Some(today - Duration::days(95)),
+                    method: Some(CancellationMethod::Website),
+                    confirmation_number: Some("  NF-123  ".to_string()),
+                    expected_final_charge: Some(today - Duration::days(92)),
+                    expected_refund: Some(7.50),
+                    notes: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(db.list_cancellations(None).unwrap().len(), 1);
+        assert_eq!(cancellation.merchant, "Netflix");
+        assert_eq!(cancellation.confirmation_number.as_deref(), Some("NF-123"));
+        assert_eq!(cancellation.status, CancellationStatus::Pending);
+        assert_eq!(
+            db.get_subscription(sub_id).unwrap().unwrap().status,
+            crate::models::SubscriptionStatus::Cancelled
+        );
+
+        // Unverified: savings count from the final charge, not yet confirmed
+        let savings = db.get_savings_report().unwrap();
+        assert_eq!(savings.cancelled_count, 1);
+        assert_eq!(savings.confirmed_count, 0);
+        let info = &savings.cancelled[0];
+        assert_eq!(info.final_charge, (today - Duration::days(92)).to_string());
+        assert_eq!(info.months_counted, 3);
+        assert_eq!(info.verification, Some(CancellationStatus::Pending));
+
+        // Verified with the refund received
+        let tx_id = {
+            let conn = db.conn().unwrap();
+            conn.execute(
+                "INSERT INTO accounts (name, bank) VALUES ('Checking', 'chase')",
+                [],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO transactions (account_id, date, description, amount, import_hash) VALUES (1, ?, 'NETFLIX REFUND', 7.50, 'refund')",
+                [(today - Duration::days(90)).to_string()],
+            )
+            .unwrap();
+            conn.last_insert_rowid()
+        };
+        db.record_cancellation_refund(cancellation.id, tx_id, 7.50)
+            .unwrap();
+        db.confirm_cancellation(cancellation.id).unwrap();
+
+        let savings = db.get_savings_report().unwrap();
+        assert_eq!(savings.confirmed_count, 1);
+        assert_eq!(savings.cancelled[0].refund, 7.50);
+        assert!((savings.confirmed_savings - (15.00 * 3.0 + 7.50)).abs() < 0.001);
+        assert_eq!(
+            db.list_cancellations(Some(CancellationStatus::Confirmed))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
     #[test]
     fn test_find_subscription_by_merchant_or_id() {
         let db = Database::in_memory().unwrap();
@@ -2413,6 +2855,297 @@ mod tests {
         assert!(!candidates.is_empty());
         assert!(candidates[0].score > 0.5);
     }
+
+    #[test]
+    fn test_detection_settings_scopes() {
+        use crate::db::DetectionScope;
+        use crate::detect::DetectionOverrides;
+
+        let db = Database::in_memory().unwrap();
+        let utilities = db.create_tag("Utilities", None, None, None, None).unwrap();
+        let power = db
+            .create_tag("Power", Some(utilities), None, None, None)
+            .unwrap();
+
+        let global = DetectionOverrides {
+            zombie_min_months: Some(6),
+            ..Default::default()
+        };
+        let tag = DetectionOverrides {
+            price_increase_percent: Some(10.0),
+            ..Default::default()
+        };
+        db.set_detection_overrides(&DetectionScope::Global, &global, "a@example.com")
+            .unwrap();
+        db.set_detection_overrides(&DetectionScope::Tag(utilities), &tag, "a@example.com")
+            .unwrap();
+        db.set_detection_overrides(
+            &DetectionScope::Merchant("Netflix".to_string()),
+            &tag,
+            "a@example.com",
+        )
+        .unwrap();
+
+        // Saving again replaces rather than duplicates
+        db.set_detection_overrides(&DetectionScope::Global, &global, "a@example.com")
+            .unwrap();
+
+        let settings = db.get_detection_settings().unwrap();
+        assert_eq!(settings.global, global);
+        assert_eq!(settings.config().zombie_min_months, 6);
+        assert_eq!(settings.tags.len(), 1);
+        assert_eq!(settings.tags[0].tag, "Utilities");
+        assert_eq!(settings.merchants.len(), 1);
+        assert_eq!(
+            db.get_detection_overrides(&DetectionScope::Merchant("NETFLIX".to_string()))
+                .unwrap(),
+            tag
+        );
+
+        // Invalid values and unknown tags are rejected
+        let bad = DetectionOverrides {
+            zombie_min_months: Some(0),
+            ..Default::default()
+        };
+        assert!(db
+            .set_detection_overrides(&DetectionScope::Global, &bad, "a@example.com")
+            .is_err());
+        assert!(db
+            .set_detection_overrides(&DetectionScope::Tag(9999), &tag, "a@example.com")
+            .is_err());
+
+        // A merchant tagged with a child tag inherits the parent's overrides
+        let account_id = db.upsert_account("Checking", Bank::Chase, None).unwrap();
+        let tx_id = db
+            .insert_transaction(
+                account_id,
+                &NewTransaction {
+                    date: chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+                    description: "POWER CO 1234".to_string(),
+                    amount: -120.00,
+                    category: None,
+                    import_hash: "hash_power".to_string(),
+                    original_data: None,
+                    import_format: None,
+                    card_member: None,
+                    payment_method: None,
+                },
+            )
+            .unwrap()
+            .unwrap();
+        db.update_merchant_normalized(tx_id, "Power Co").unwrap();
+        db.add_transaction_tag(tx_id, power, TagSource::Manual, None)
+            .unwrap();
+        let mut lineage = db.get_merchant_tag_lineage("power co").unwrap();
+        lineage.sort();
+        assert_eq!(lineage, vec![utilities, power]);
+        let config = db
+            .get_detection_settings()
+            .unwrap()
+            .config_for(Some("Power Co"), &lineage);
+        assert_eq!(config.price_increase_percent, 10.0);
+
+        // Deleting the tag removes its overrides; empty overrides delete the row
+        db.delete_tag(utilities, true).unwrap();
+        assert!(db.get_detection_settings().unwrap().tags.is_empty());
+        db.set_detection_overrides(
+            &DetectionScope::Global,
+            &DetectionOverrides::default(),
+            "a@example.com",
+        )
+        .unwrap();
+        assert!(db.get_detection_settings().unwrap().global.is_empty());
+        assert!(!db
+            .delete_detection_overrides(&DetectionScope::Global)
+            .unwrap());
+    }
+
+    fn insert_test_tx(
+        db: &Database,
+        account_id: i64,
+        date: &str,
+        description: &str,
+        amount: f64,
+    ) -> i64 {
+        db.insert_transaction(
+            account_id,
+            &NewTransaction {
+                date: chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+                description: description.to_string(),
+                amount,
+                category: None,
+                import_hash: format!("{}-{}-{}", date, description, amount),
+                original_data: None,
+                import_format: None,
+                card_member: None,
+                payment_method: None,
+            },
+        )
+        .unwrap()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_refund_matching() {
+        let db = Database::in_memory().unwrap();
+        db.seed_root_tags().unwrap();
+        let account_id = db.upsert_account("Card", Bank::Amex, None).unwrap();
+
+        // Full refund of one purchase
+        let shoes = insert_test_tx(&db, account_id, "2024-01-10", "NIKE.COM 12345", -120.00);
+        let shoes_refund = insert_test_tx(&db, account_id, "2024-02-03", "NIKE.COM REFUND", 120.00);
+
+        // Two partial refunds against one purchase
+        let order = insert_test_tx(&db, account_id, "2024-01-20", "AMAZON.COM*1A2B3C", -100.00);
+        let partial1 = insert_test_tx(&db, account_id, "2024-01-25", "AMAZON REFUND", 30.00);
+        let partial2 = insert_test_tx(&db, account_id, "2024-01-28", "AMAZON REFUND", 30.00);
+        // More than what's left of the purchase: left unmatched
+        let too_much = insert_test_tx(&db, account_id, "2024-01-29", "AMAZON REFUND", 50.00);
+
+        // One returned item from a split purchase
+        let groceries = insert_test_tx(&db, account_id, "2024-01-05", "TARGET 00012", -80.00);
+        let item = db
+            .create_split(&NewTransactionSplit {
+                transaction_id: groceries,
+                amount: 25.00,
+                description: Some("Lamp".to_string()),
+                split_type: SplitType::Item,
+                entity_id: None,
+                purchaser_id: None,
+            })
+            .unwrap();
+        let lamp_refund = insert_test_tx(&db, account_id, "2024-01-15", "TARGET RETURN", 25.00);
+
+        // Outside the window, and a different merchant
+        insert_test_tx(&db, account_id, "2023-06-01", "BESTBUY 555", -40.00);
+        let late = insert_test_tx(&db, account_id, "2024-01-15", "BESTBUY REFUND", 40.00);
+        let paycheck = insert_test_tx(&db, account_id, "2024-01-31", "ACME PAYROLL", 2000.00);
+
+        let (matched, checked) = db.auto_match_refunds().unwrap();
+        assert_eq!(checked, 7);
+        assert_eq!(matched, 4);
+
+        let m = db.get_refund_match(shoes_refund).unwrap().unwrap();
+        assert_eq!(m.original_transaction_id, shoes);
+        assert_eq!(m.source, RefundMatchSource::Auto);
+        assert_eq!(db.get_refunds_for_transaction(order).unwrap().len(), 2);
+        assert_eq!(db.get_refunded_amount(order).unwrap(), 60.0);
+        assert!(db.get_refund_match(partial1).unwrap().is_some());
+        assert!(db.get_refund_match(partial2).unwrap().is_some());
+        assert!(db.get_refund_match(too_much).unwrap().is_none());
+        let lamp = db.get_refund_match(lamp_refund).unwrap().unwrap();
+        assert_eq!(lamp.original_transaction_id, groceries);
+        assert_eq!(lamp.split_id, Some(item));
+        assert!(db.get_refund_match(late).unwrap().is_none());
+        assert!(db.get_refund_match(paycheck).unwrap().is_none());
+
+        // Manual links are checked against what's left of the purchase
+        assert!(db.link_refund(too_much, order, None, None).is_err());
+        let manual = db.link_refund(too_much, order, None, Some(40.0)).unwrap();
+        assert_eq!(manual.source, RefundMatchSource::Manual);
+        assert_eq!(db.get_refunded_amount(order).unwrap(), 100.0);
+        assert!(db.link_refund(paycheck, shoes_refund, None, None).is_err());
+
+        // Unlinked refunds stay unlinked
+        assert!(db.unlink_refund(shoes_refund).unwrap());
+        assert!(!db.unlink_refund(shoes_refund).unwrap());
+        let (matched, _) = db.auto_match_refunds().unwrap();
+        assert_eq!(matched, 0);
+        assert!(db.get_refund_match(shoes_refund).unwrap().is_none());
+
+        let listed = db.list_refund_matches(10, 0).unwrap();
+        assert_eq!(listed.len(), 4);
+        assert!(listed
+            .iter()
+            .any(|m| m.split_description.as_deref() == Some("Lamp")));
+    }
+
+    #[test]
+    fn test_refund_matching_ignores_processor_prefixes() {
+        let db = Database::in_memory().unwrap();
+        db.seed_root_tags().unwrap();
+        let account_id = db.upsert_account("Card", Bank::Amex, None).unwrap();
+
+        let coffee = insert_test_tx(&db, account_id, "2024-01-10", "SQ *BLUE BOTTLE", -18.00);
+        // A Square refund that doesn't name the shop isn't any Square purchase
+        let unnamed = insert_test_tx(&db, account_id, "2024-01-12", "SQ *REFUND 0042", 18.00);
+        let coffee_refund = insert_test_tx(
+            &db,
+            account_id,
+            "2024-01-14",
+            "SQ *BLUE BOTTLE REFUND",
+            18.00,
+        );
+
+        let (matched, _) = db.auto_match_refunds().unwrap();
+        assert_eq!(matched, 1);
+        assert!(db.get_refund_match(unnamed).unwrap().is_none());
+        let m = db.get_refund_match(coffee_refund).unwrap().unwrap();
+        assert_eq!(m.original_transaction_id, coffee);
+    }
+
+    #[test]
+    fn test_spending_reports_net_refunds() {
+        use chrono::NaiveDate;
+
+        let db = Database::in_memory().unwrap();
+        db.seed_root_tags().unwrap();
+        let account_id = db.upsert_account("Card", Bank::Chase, None).unwrap();
+        let shopping = db.get_tag_by_path("Shopping").unwrap().unwrap();
+
+        // December purchase, refunded in January
+        let coat = insert_test_tx(&db, account_id, "2023-12-20", "REI 123", -200.00);
+        db.add_transaction_tag(coat, shopping.id, TagSource::Manual, None)
+            .unwrap();
+        let refund = insert_test_tx(&db, account_id, "2024-01-05", "REI REFUND", 80.00);
+        db.link_refund(refund, coat, None, None).unwrap();
+
+        let dec_from = NaiveDate::from_ymd_opt(2023, 12, 1).unwrap();
+        let dec_to = NaiveDate::from_ymd_opt(2023, 12, 31).unwrap();
+        let gross = db
+            .get_spending_summary(dec_from, dec_to, None, false, None, None, false)
+            .unwrap();
+        assert_eq!(gross.total, 200.0);
+        let net = db
+            .get_spending_summary(dec_from, dec_to, None, false, None, None, true)
+            .unwrap();
+        assert_eq!(net.total, 120.0);
+        let net_shopping = net.categories.iter().find(|c| c.tag == "Shopping").unwrap();
+        assert_eq!(net_shopping.amount, 120.0);
+        let filtered = db
+            .get_spending_summary(dec_from, dec_to, Some("Shopping"), true, None, None, true)
+            .unwrap();
+        assert_eq!(filtered.categories[0].amount, 120.0);
+
+        // The January refund doesn't reduce January spending
+        let jan = db
+            .get_spending_summary(
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+                None,
+                false,
+                None,
+                None,
+                true,
+            )
+            .unwrap();
+        assert_eq!(jan.total, 0.0);
+
+        let trends = db
+            .get_spending_trends(
+                dec_from,
+                NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+                Granularity::Monthly,
+                Some("Shopping"),
+                None,
+                None,
+                true,
+            )
+            .unwrap();
+        assert_eq!(trends.data.len(), 1);
+        assert_eq!(trends.data[0].period, "2023-12");
+        assert_eq!(trends.data[0].amount, 120.0);
+    }
 }
 
 /// Security-focused tests for input validation and injection prevention
@@ -4029,4 +4762,1159 @@ mod security_tests {
         let cleared = db.clear_merchant_normalized_for_transactions(&[]).unwrap();
         assert_eq!(cleared, 0);
     }
+
+    // ========== Explore Session Tests ==========
+
+    fn explore_turn(query: &str, response: &str) -> NewExploreTurn {
+        NewExploreTurn {
+            query: query.to_string(),
+            response: response.to_string(),
+            tool_calls: vec![crate::ai::ToolCallRecord {
+                name: "get_alerts".to_string(),
+                input: serde_json::json!({}),
+                success: true,
+                output: Some("[]".to_string()),
+            }],
+            iterations: 2,
+            model: Some("qwen3".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_explore_session_roundtrip() {
+        let db = Database::in_memory().unwrap();
+
+        let session = db
+            .create_explore_session("exp_1", "Gas spending", Some("me@example.com"))
+            .unwrap();
+        assert_eq!(session.title, "Gas spending");
+        assert_eq!(session.turn_count, 0);
+        assert!(session.model.is_none());
+
+        db.add_explore_turn("exp_1", &explore_turn("How much on gas?", "$120.00"))
+            .unwrap();
+        db.add_explore_turn("exp_1", &explore_turn("And last year?", "$1,400.00"))
+            .unwrap();
+
+        let session = db.get_explore_session("exp_1").unwrap().unwrap();
+        assert_eq!(session.turn_count, 2);
+        assert_eq!(session.model.as_deref(), Some("qwen3"));
+
+        let turns = db.get_explore_turns("exp_1").unwrap();
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].query, "How much on gas?");
+        assert_eq!(turns[1].response, "$1,400.00");
+        assert_eq!(turns[0].tool_calls[0].name, "get_alerts");
+        assert!(!turns[0].summarized);
+
+        assert!(db.rename_explore_session("exp_1", "Fuel").unwrap());
+        assert!(!db.rename_explore_session("exp_missing", "Fuel").unwrap());
+        assert_eq!(
+            db.get_explore_session("exp_1").unwrap().unwrap().title,
+            "Fuel"
+        );
+
+        assert!(db.delete_explore_session("exp_1").unwrap());
+        assert!(db.get_explore_session("exp_1").unwrap().is_none());
+        assert!(db.get_explore_turns("exp_1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_list_explore_sessions_search() {
+        let db = Database::in_memory().unwrap();
+
+        db.create_explore_session("exp_a", "Dining out", None)
+            .unwrap();
+        db.create_explore_session("exp_b", "Subscriptions", None)
+            .unwrap();
+        db.add_explore_turn("exp_b", &explore_turn("Is Netflix still active?", "Yes"))
+            .unwrap();
+
+        assert_eq!(
+            db.list_explore_sessions(None, None, 50, 0).unwrap().len(),
+            2
+        );
+        assert_eq!(
+            db.list_explore_sessions(None, Some("  "), 50, 0)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(db.list_explore_sessions(None, None, 1, 0).unwrap().len(), 1);
+
+        // Title match
+        let found = db
+            .list_explore_sessions(None, Some("dining"), 50, 0)
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "exp_a");
+
+        // Conversation text match
+        let found = db
+            .list_explore_sessions(None, Some("NETFLIX"), 50, 0)
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "exp_b");
+
+        assert!(db
+            .list_explore_sessions(None, Some("mortgage"), 50, 0)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_list_explore_sessions_search_is_literal() {
+        let db = Database::in_memory().unwrap();
+
+        db.create_explore_session("exp_a", "tax_2024 deductions", None)
+            .unwrap();
+        db.create_explore_session("exp_b", "tax 2024 refund", None)
+            .unwrap();
+        db.create_explore_session("exp_c", "Saved 100% of bonus", None)
+            .unwrap();
+        db.create_explore_session("exp_d", "1000 dollar budget", None)
+            .unwrap();
+
+        // `_` and `%` match themselves, not any character
+        let found = db.list_explore_sessions(None, Some("tax_"), 50, 0).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "exp_a");
+
+        let found = db.list_explore_sessions(None, Some("100%"), 50, 0).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "exp_c");
+    }
+
+    #[test]
+    fn test_list_explore_sessions_by_creator() {
+        let db = Database::in_memory().unwrap();
+
+        db.create_explore_session("exp_a", "Groceries", Some("a@example.com"))
+            .unwrap();
+        db.create_explore_session("exp_b", "Gifts", Some("b@example.com"))
+            .unwrap();
+
+        let found = db
+            .list_explore_sessions(Some("a@example.com"), None, 50, 0)
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, "exp_a");
+        assert!(db
+            .list_explore_sessions(Some("a@example.com"), Some("gifts"), 50, 0)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            db.list_explore_sessions(None, None, 50, 0).unwrap().len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_summarize_explore_turns() {
+        let db = Database::in_memory().unwrap();
+        db.create_explore_session("exp_1", "Long chat", None)
+            .unwrap();
+
+        let mut ids = Vec::new();
+        for i in 0..3 {
+            ids.push(
+                db.add_explore_turn("exp_1", &explore_turn(&format!("Q{}", i), "A"))
+                    .unwrap(),
+            );
+        }
+
+        let marked = db
+            .summarize_explore_turns("exp_1", "The user asked about Q0 and Q1.", ids[1])
+            .unwrap();
+        assert_eq!(marked, 2);
+
+        let session = db.get_explore_session("exp_1").unwrap().unwrap();
+        assert_eq!(
+            session.summary.as_deref(),
+            Some("The user asked about Q0 and Q1.")
+        );
+        let turns = db.get_explore_turns("exp_1").unwrap();
+        assert!(turns[0].summarized && turns[1].summarized);
+        assert!(!turns[2].summarized);
+
+        // Already summarized turns are not counted again
+        let marked = db
+            .summarize_explore_turns("exp_1", "Updated", ids[1])
+            .unwrap();
+        assert_eq!(marked, 0);
+    }
+
+    // ========== Saved Question Tests ==========
+
+    fn saved_question(name: &str, interval_hours: i64) -> NewSavedQuestion {
+        NewSavedQuestion {
+            name: name.to_string(),
+            query: format!("{}?", name),
+            model: None,
+            interval_hours,
+            created_by: Some("me@example.com".to_string()),
+        }
+    }
+
+    fn digest_for(question_id: i64, success: bool, response: &str) -> NewExploreDigest {
+        NewExploreDigest {
+            saved_question_id: question_id,
+            success,
+            response: response.to_string(),
+            error_message: (!success).then(|| "boom".to_string()),
+            tool_calls: Vec::new(),
+            iterations: 1,
+            model: Some("qwen3".to_string()),
+            latency_ms: 25,
+            change_score: None,
+            changed: false,
+        }
+    }
+
+    #[test]
+    fn test_saved_question_crud() {
+        let db = Database::in_memory().unwrap();
+
+        let id = db
+            .create_saved_question(&saved_question("Dining", 24))
+            .unwrap();
+        let q = db.get_saved_question(id).unwrap().unwrap();
+        assert_eq!(q.name, "Dining");
+        assert!(q.enabled);
+        assert!(q.next_run_at.is_none());
+
+        db.update_saved_question(
+            id,
+            Some("Dining out"),
+            None,
+            Some(Some("llama3")),
+            None,
+            Some(false),
+        )
+        .unwrap();
+        let q = db.get_saved_question(id).unwrap().unwrap();
+        assert_eq!(q.name, "Dining out");
+        assert_eq!(q.model.as_deref(), Some("llama3"));
+        assert!(!q.enabled);
+
+        db.update_saved_question(id, None, None, Some(None), None, None)
+            .unwrap();
+        assert!(db.get_saved_question(id).unwrap().unwrap().model.is_none());
+
+        assert!(db
+            .update_saved_question(9999, Some("x"), None, None, None, None)
+            .is_err());
+
+        assert_eq!(db.list_saved_questions(None).unwrap().len(), 1);
+        assert_eq!(
+            db.list_saved_questions(Some("me@example.com"))
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(db
+            .list_saved_questions(Some("other@example.com"))
+            .unwrap()
+            .is_empty());
+        assert!(db.delete_saved_question(id).unwrap());
+        assert!(!db.delete_saved_question(id).unwrap());
+    }
+
+    #[test]
+    fn test_saved_question_scheduling() {
+        let db = Database::in_memory().unwrap();
+        let daily = db
+            .create_saved_question(&saved_question("Daily", 24))
+            .unwrap();
+        let disabled = db
+            .create_saved_question(&saved_question("Disabled", 24))
+            .unwrap();
+        db.update_saved_question(disabled, None, None, None, None, Some(false))
+            .unwrap();
+
+        // New questions are due immediately; disabled ones never
+        let due = db.get_due_saved_questions(chrono::Utc::now()).unwrap();
+        assert_eq!(due.iter().map(|q| q.id).collect::<Vec<_>>(), vec![daily]);
+
+        // Recording a digest (even a failed one) pushes the next run out
+        db.record_explore_digest(&digest_for(daily, false, ""))
+            .unwrap();
+        assert!(db
+            .get_due_saved_questions(chrono::Utc::now())
+            .unwrap()
+            .is_empty());
+        let q = db.get_saved_question(daily).unwrap().unwrap();
+        assert!(q.last_run_at.is_some());
+        let next = q.next_run_at.unwrap();
+        assert!(next > chrono::Utc::now() + chrono::Duration::hours(23));
+        assert_eq!(
+            db.get_due_saved_questions(next + chrono::Duration::minutes(1))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_explore_digests_history() {
+        let db = Database::in_memory().unwrap();
+        let id = db
+            .create_saved_question(&saved_question("Dining", 24))
+            .unwrap();
+
+        assert!(db.get_last_successful_digest(id).unwrap().is_none());
+        assert!(db.get_recent_digests(5).unwrap().is_empty());
+
+        let first = db
+            .record_explore_digest(&digest_for(id, true, "Dining: $320.00"))
+            .unwrap();
+        db.record_explore_digest(&digest_for(id, false, ""))
+            .unwrap();
+
+        // Failed runs are kept in history but never become the baseline
+        let digests = db.list_explore_digests(id, 10).unwrap();
+        assert_eq!(digests.len(), 2);
+        assert!(!digests[0].success);
+        assert_eq!(digests[0].error_message.as_deref(), Some("boom"));
+        let last = db.get_last_successful_digest(id).unwrap().unwrap();
+        assert_eq!(last.id, first);
+
+        let recent = db.get_recent_digests(5).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].digest_id, first);
+        assert_eq!(recent[0].name, "Dining");
+        assert_eq!(db.get_dashboard_stats().unwrap().recent_digests.len(), 1);
+
+        // Deleting the question removes its digests
+        db.delete_saved_question(id).unwrap();
+        assert!(db.list_explore_digests(id, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_household_users_and_owner_guard() {
+        let db = Database::in_memory().unwrap();
+        assert_eq!(db.count_users().unwrap(), 0);
+
+        let owner = db
+            .create_user("Owner@Example.com", Some("Owner"), UserRole::Owner)
+            .unwrap();
+        let viewer = db
+            .create_user("kid@example.com", None, UserRole::Viewer)
+            .unwrap();
+
+        // Emails are unique regardless of case
+        assert!(db
+            .create_user("owner@example.com", None, UserRole::Editor)
+            .is_err());
+        assert_eq!(
+            db.get_user_by_email("OWNER@example.com")
+                .unwrap()
+                .unwrap()
+                .id,
+            owner
+        );
+
+        let users = db.list_users().unwrap();
+        assert_eq!(users.len(), 2);
+        assert_eq!(users[0].role, UserRole::Owner);
+
+        // The last owner can't be demoted or removed
+        assert!(db.update_user(owner, None, Some(UserRole::Editor)).is_err());
+        assert!(db.delete_user(owner).is_err());
+
+        db.update_user(viewer, Some(Some("Kid")), Some(UserRole::Editor))
+            .unwrap();
+        let updated = db.get_user(viewer).unwrap().unwrap();
+        assert_eq!(updated.role, UserRole::Editor);
+        assert_eq!(updated.display_name.as_deref(), Some("Kid"));
+
+        let (key, secret) = db
+            .create_api_key(
+                "kid-key",
+                ApiKeyScope::ReadOnly,
+                None,
+                Some("Kid@example.com"),
+            )
+            .unwrap();
+        assert!(db.delete_user(viewer).unwrap());
+        assert!(!db.delete_user(viewer).unwrap());
+
+        // Their API keys stop working
+        assert!(db.authenticate_api_key(&secret).unwrap().is_none());
+        assert!(db
+            .get_api_key(key.id)
+            .unwrap()
+            .unwrap()
+            .revoked_at
+            .is_some());
+    }
+
+    #[test]
+    fn test_account_visibility() {
+        let db = Database::in_memory().unwrap();
+        let owner_id = db
+            .create_user("owner@example.com", None, UserRole::Owner)
+            .unwrap();
+        let a_id = db
+            .create_user("a@example.com", None, UserRole::Editor)
+            .unwrap();
+        let b_id = db
+            .create_user("b@example.com", None, UserRole::Viewer)
+            .unwrap();
+        let shared = db.upsert_account("Joint", Bank::Chase, None).unwrap();
+        let private = db.upsert_account("A's Card", Bank::Amex, None).unwrap();
+
+        db.set_account_visibility(private, &[a_id]).unwrap();
+        assert_eq!(db.get_account_visibility(private).unwrap(), vec![a_id]);
+        assert!(db.get_account_visibility(shared).unwrap().is_empty());
+
+        let owner = db.get_user(owner_id).unwrap().unwrap();
+        let a = db.get_user(a_id).unwrap().unwrap();
+        let b = db.get_user(b_id).unwrap().unwrap();
+        assert!(db.hidden_account_ids(&owner).unwrap().is_empty());
+        assert!(db.hidden_account_ids(&a).unwrap().is_empty());
+        assert_eq!(db.hidden_account_ids(&b).unwrap(), vec![private]);
+
+        // Removing a user drops their grants (an account with none left is shared)
+        db.delete_user(a_id).unwrap();
+        assert!(db.get_account_visibility(private).unwrap().is_empty());
+
+        // Sharing again clears the restriction
+        db.set_account_visibility(private, &[b_id]).unwrap();
+        db.set_account_visibility(private, &[]).unwrap();
+        assert!(db.hidden_account_ids(&b).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_auth_sessions() {
+        use crate::models::NewAuthSession;
+
+        let db = Database::in_memory().unwrap();
+        let user_id = db
+            .create_user("owner@example.com", None, UserRole::Owner)
+            .unwrap();
+        let new_session = |token: &str, hours: i64| NewAuthSession {
+            token: token.to_string(),
+            user_id,
+            csrf_token: "csrf".to_string(),
+            auth_method: "password".to_string(),
+            ip_address: Some("127.0.0.1".to_string()),
+            user_agent: None,
+            expires_at: chrono::Utc::now() + chrono::Duration::hours(hours),
+        };
+
+        db.create_auth_session(&new_session("token-a", 1)).unwrap();
+        db.create_auth_session(&new_session("token-b", 1)).unwrap();
+        db.create_auth_session(&new_session("expired", -1)).unwrap();
+
+        let session = db.get_auth_session("token-a").unwrap().unwrap();
+        assert_eq!(session.user_id, user_id);
+        assert_eq!(session.csrf_token, "csrf");
+        assert!(db.get_auth_session("expired").unwrap().is_none());
+        assert!(db.get_auth_session("unknown").unwrap().is_none());
+        assert_eq!(db.list_auth_sessions(user_id).unwrap().len(), 2);
+        assert_eq!(db.prune_expired_auth_sessions().unwrap(), 1);
+
+        // Ending other sessions keeps the current one
+        assert_eq!(
+            db.delete_user_auth_sessions(user_id, Some("token-a"))
+                .unwrap(),
+            1
+        );
+        assert!(db.get_auth_session("token-a").unwrap().is_some());
+        assert!(db.get_auth_session("token-b").unwrap().is_none());
+
+        assert!(db.delete_auth_session("token-a").unwrap());
+        assert!(!db.delete_auth_session("token-a").unwrap());
+    }
+
+    #[test]
+    fn test_reset_user_credentials() {
+        let db = Database::in_memory().unwrap();
+        let user_id = db
+            .create_user("owner@example.com", None, UserRole::Owner)
+            .unwrap();
+
+        db.set_user_password_hash(user_id, Some("$argon2id$hash"))
+            .unwrap();
+        db.set_user_totp(user_id, Some("SECRET"), true).unwrap();
+        db.add_webauthn_credential(user_id, "cred-1", &[1, 2, 3], 0, "Laptop")
+            .unwrap();
+
+        let user = db.get_user(user_id).unwrap().unwrap();
+        assert!(user.has_password);
+        assert!(user.totp_enabled);
+        let credential = db.get_webauthn_credential("cred-1").unwrap().unwrap();
+        assert_eq!(credential.public_key, vec![1, 2, 3]);
+
+        db.update_webauthn_sign_count(credential.id, 5).unwrap();
+        let credential = db.get_webauthn_credential("cred-1").unwrap().unwrap();
+        assert_eq!(credential.sign_count, 5);
+        assert!(credential.last_used_at.is_some());
+
+        db.reset_user_credentials(user_id).unwrap();
+        let user = db.get_user(user_id).unwrap().unwrap();
+        assert!(!user.has_password);
+        assert!(!user.totp_enabled);
+        assert!(db.get_user_totp(user_id).unwrap().is_none());
+        assert!(db.list_webauthn_credentials(user_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_totp_steps_are_single_use() {
+        let db = Database::in_memory().unwrap();
+        let user_id = db
+            .create_user("owner@example.com", None, UserRole::Owner)
+            .unwrap();
+        db.set_user_totp(user_id, Some("SECRET"), true).unwrap();
+
+        assert!(db.accept_totp_step(user_id, 100).unwrap());
+        assert!(!db.accept_totp_step(user_id, 100).unwrap());
+        // A code from an earlier step in the skew window is stale too
+        assert!(!db.accept_totp_step(user_id, 99).unwrap());
+        assert!(db.accept_totp_step(user_id, 101).unwrap());
+
+        // A new secret starts over
+        db.set_user_totp(user_id, Some("OTHER"), false).unwrap();
+        assert!(db.accept_totp_step(user_id, 50).unwrap());
+    }
+
+    #[test]
+    fn test_audit_log_hash_chain() {
+        let db = Database::in_memory().unwrap();
+        assert!(db.verify_audit_log().unwrap().valid);
+
+        for i in 1..=3 {
+            db.log_audit(
+                "a@example.com",
+                "update",
+                Some("transaction"),
+                Some(i),
+                None,
+            )
+            .unwrap();
+        }
+        let entries = db.list_audit_log(10).unwrap();
+        assert_eq!(entries.len(), 3);
+        // Newest first, each linked to the one before
+        assert_eq!(entries[2].prev_hash, crate::db::AUDIT_GENESIS_HASH);
+        assert_eq!(entries[1].prev_hash, entries[2].entry_hash);
+        assert_eq!(entries[0].prev_hash, entries[1].entry_hash);
+
+        let result = db.verify_audit_log().unwrap();
+        assert!(result.valid);
+        assert_eq!(result.entries_checked, 3);
+        assert_eq!(
+            result.head_hash.as_deref(),
+            Some(entries[0].entry_hash.as_str())
+        );
+
+        // Editing an entry breaks its own hash
+        let conn = db.conn().unwrap();
+        conn.execute(
+            "UPDATE audit_log SET user_email = 'b@example.com' WHERE id = 2",
+            [],
+        )
+        .unwrap();
+        let result = db.verify_audit_log().unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.first_invalid_id, Some(2));
+        assert_eq!(result.entries_checked, 1);
+
+        // Deleting an entry breaks the next entry's link
+        conn.execute("DELETE FROM audit_log WHERE id = 2", [])
+            .unwrap();
+        let result = db.verify_audit_log().unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.first_invalid_id, Some(3));
+    }
+
+    #[test]
+    fn test_audit_log_filter_and_archive() {
+        let db = Database::in_memory().unwrap();
+        db.log_audit(
+            "a@example.com",
+            "update",
+            Some("transaction"),
+            Some(1),
+            None,
+        )
+        .unwrap();
+        db.log_audit("b@example.com", "delete", Some("tag"), Some(7), None)
+            .unwrap();
+        db.log_audit("a@example.com", "login", Some("user"), Some(1), None)
+            .unwrap();
+
+        let by_user = AuditFilter {
+            user_email: Some("A@example.com".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(db.query_audit_log(&by_user).unwrap().len(), 2);
+        assert_eq!(db.count_audit_log(&by_user).unwrap(), 2);
+
+        let by_entity = AuditFilter {
+            entity_type: Some("tag".to_string()),
+            entity_id: Some(7),
+            ..Default::default()
+        };
+        let entries = db.query_audit_log(&by_entity).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, "delete");
+
+        let future = AuditFilter {
+            from: Some(chrono::Utc::now().date_naive() + chrono::Duration::days(1)),
+            ..Default::default()
+        };
+        assert!(db.query_audit_log(&future).unwrap().is_empty());
+
+        // Nothing is old enough yet
+        let past = chrono::Utc::now() - chrono::Duration::days(30);
+        assert_eq!(db.archive_audit_log(past).unwrap(), 0);
+
+        let future = chrono::Utc::now() + chrono::Duration::minutes(1);
+        assert_eq!(db.archive_audit_log(future).unwrap(), 3);
+        assert!(db.list_audit_log(10).unwrap().is_empty());
+        let all = AuditFilter {
+            include_archived: true,
+            ..Default::default()
+        };
+        assert_eq!(db.query_audit_log(&all).unwrap().len(), 3);
+
+        // New entries keep extending the chain after archiving
+        let id = db
+            .log_audit("a@example.com", "logout", None, None, None)
+            .unwrap();
+        assert_eq!(id, 4);
+        let result = db.verify_audit_log().unwrap();
+        assert!(result.valid);
+        assert_eq!(result.entries_checked, 4);
+        assert_eq!(result.archived_entries, 3);
+    }
+
+    #[test]
+    fn test_audit_archive_keeps_chain_prefix_with_out_of_order_timestamps() {
+        let db = Database::in_memory().unwrap();
+        for action in ["create", "update", "delete"] {
+            db.log_audit("a@example.com", action, Some("tag"), Some(1), None)
+                .unwrap();
+        }
+        // The clock jumped: entries 1 and 3 look old, entry 2 doesn't
+        db.conn()
+            .unwrap()
+            .execute(
+                "UPDATE audit_log SET timestamp = '2000-01-01 00:00:00' WHERE id IN (1, 3)",
+                [],
+            )
+            .unwrap();
+
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(30);
+        assert_eq!(db.archive_audit_log(cutoff).unwrap(), 3);
+        assert!(db.list_audit_log(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_soft_reset_keeps_audit_log() {
+        let db = Database::in_memory().unwrap();
+        db.log_audit("a@example.com", "update", None, None, None)
+            .unwrap();
+        db.soft_reset().unwrap();
+        assert_eq!(db.list_audit_log(10).unwrap().len(), 1);
+        assert!(db.verify_audit_log().unwrap().valid);
+    }
+
+    #[test]
+    fn test_api_keys() {
+        let db = Database::in_memory().unwrap();
+        let (key, secret) = db
+            .create_api_key("importer", ApiKeyScope::ImportOnly, None, Some("cli"))
+            .unwrap();
+        assert!(secret.starts_with("hone_"));
+        assert!(secret.starts_with(&key.prefix));
+        assert_eq!(key.identity(), "api-key:importer");
+        assert!(key.last_used_at.is_none());
+
+        // Names are unique; the secret is only stored hashed
+        assert!(db
+            .create_api_key("importer", ApiKeyScope::Admin, None, None)
+            .is_err());
+        assert!(db
+            .create_api_key(" ", ApiKeyScope::Admin, None, None)
+            .is_err());
+
+        let found = db.authenticate_api_key(&secret).unwrap().unwrap();
+        assert_eq!(found.id, key.id);
+        assert_eq!(found.scope, ApiKeyScope::ImportOnly);
+        assert!(db
+            .get_api_key(key.id)
+            .unwrap()
+            .unwrap()
+            .last_used_at
+            .is_some());
+        assert!(db.authenticate_api_key("hone_wrong").unwrap().is_none());
+        assert!(db.authenticate_api_key(&key.prefix).unwrap().is_none());
+
+        // Expired keys don't authenticate
+        let (_, expired) = db
+            .create_api_key(
+                "old",
+                ApiKeyScope::Mcp,
+                Some(Utc::now() - chrono::Duration::days(1)),
+                None,
+            )
+            .unwrap();
+        assert!(db.authenticate_api_key(&expired).unwrap().is_none());
+        assert!(!db.has_active_api_key_scope(ApiKeyScope::Mcp).unwrap());
+
+        // Revoked keys are kept but hidden by default
+        assert!(db.revoke_api_key(key.id).unwrap());
+        assert!(!db.revoke_api_key(key.id).unwrap());
+        assert!(db.authenticate_api_key(&secret).unwrap().is_none());
+        assert_eq!(db.list_api_keys(false).unwrap().len(), 1);
+        assert_eq!(db.list_api_keys(true).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_metrics_snapshot() {
+        let db = Database::in_memory().unwrap();
+        let empty = db.metrics_snapshot().unwrap();
+        assert!(empty.ai_calls.is_empty());
+        assert!(empty.db_size_bytes > 0);
+        assert!(empty.pool_max_size > 0);
+
+        db.create_alert(AlertType::Zombie, None, Some("a")).unwrap();
+        db.create_alert(AlertType::Zombie, None, Some("b")).unwrap();
+        let dismissed = db.create_alert(AlertType::Duplicate, None, None).unwrap();
+        db.dismiss_alert(dismissed).unwrap();
+
+        for (latency_ms, success) in [(50, true), (800, true), (90_000, false)] {
+            db.record_ollama_metric(&NewOllamaMetric {
+                operation: OllamaOperation::NormalizeMerchant,
+                model: "m".to_string(),
+                latency_ms,
+                success,
+                error_message: None,
+                confidence: None,
+                transaction_id: None,
+                input_text: None,
+                result_text: None,
+                metadata: None,
+            })
+            .unwrap();
+        }
+
+        let snapshot = db.metrics_snapshot().unwrap();
+        assert_eq!(snapshot.active_alerts, vec![("zombie".to_string(), 2)]);
+        assert_eq!(snapshot.ai_calls.len(), 1);
+        let calls = &snapshot.ai_calls[0];
+        assert_eq!((calls.calls, calls.errors), (3, 1));
+        assert_eq!(calls.latency_sum_ms, 90_850);
+        // Buckets are cumulative: 0.1s holds 1 call, 1s holds 2, 120s holds all 3
+        assert_eq!(calls.latency_buckets[0], 1);
+        assert_eq!(calls.latency_buckets[3], 2);
+        assert_eq!(*calls.latency_buckets.last().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_notification_deliveries_are_unique_per_channel_and_source() {
+        let db = Database::in_memory().unwrap();
+        let alert_id = db.create_alert(AlertType::Zombie, None, None).unwrap();
+        let delivery = NewNotificationDelivery {
+            channel: "hook".to_string(),
+            source_type: "alert".to_string(),
+            source_id: Some(alert_id),
+            event: "alert:zombie".to_string(),
+            severity: "warning".to_string(),
+            title: "Zombie".to_string(),
+            body: String::new(),
+            digest: false,
+            urgent: false,
+            next_attempt_at: chrono::Utc::now(),
+        };
+        assert!(db
+            .create_notification_delivery(&delivery)
+            .unwrap()
+            .is_some());
+        assert!(db
+            .create_notification_delivery(&delivery)
+            .unwrap()
+            .is_none());
+        let other_channel = NewNotificationDelivery {
+            channel: "mail".to_string(),
+            ..delivery.clone()
+        };
+        assert!(db
+            .create_notification_delivery(&other_channel)
+            .unwrap()
+            .is_some());
+
+        let sources = db.alert_notification_sources(0).unwrap();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].event, "alert:zombie");
+
+        // Soft reset clears alert deliveries and rewinds the alert cursor
+        db.set_notification_cursor("alert_cursor", alert_id)
+            .unwrap();
+        db.soft_reset().unwrap();
+        assert!(db
+            .list_notification_deliveries(None, 10)
+            .unwrap()
+            .is_empty());
+        assert_eq!(db.get_notification_cursor("alert_cursor").unwrap(), Some(0));
+    }
+
+    #[test]
+    fn test_job_claim_respects_lanes_and_order() {
+        let db = Database::in_memory().unwrap();
+        let backup = db
+            .create_job(&NewJob::new(JobType::Backup, serde_json::json!({})))
+            .unwrap();
+        let detection = db
+            .create_job(&NewJob::new(
+                JobType::Detection,
+                serde_json::json!({"kind": "all"}),
+            ))
+            .unwrap();
+        let insights = db
+            .create_job(&NewJob::new(JobType::InsightRefresh, serde_json::json!({})))
+            .unwrap();
+
+        let now = chrono::Utc::now() + chrono::Duration::seconds(1);
+        let ai_types: Vec<JobType> = JobType::all()
+            .iter()
+            .copied()
+            .filter(|t| t.is_ai_heavy())
+            .collect();
+
+        let claimed = db.claim_next_job(&ai_types, now).unwrap().unwrap();
+        assert_eq!(claimed.id, detection);
+        assert_eq!(claimed.status, JobStatus::Running);
+        assert_eq!(claimed.attempts, 1);
+        assert!(claimed.started_at.is_some());
+        assert_eq!(claimed.max_attempts, 2);
+
+        let claimed = db.claim_next_job(&ai_types, now).unwrap().unwrap();
+        assert_eq!(claimed.id, insights);
+        assert!(db.claim_next_job(&ai_types, now).unwrap().is_none());
+
+        let claimed = db.claim_next_job(&[JobType::Backup], now).unwrap().unwrap();
+        assert_eq!(claimed.id, backup);
+
+        db.update_job_progress(detection, "zombies", 2, 5).unwrap();
+        db.complete_job(detection, &serde_json::json!({"zombies_detected": 1}))
+            .unwrap();
+        let job = db.get_job(detection).unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Completed);
+        assert_eq!(job.progress_phase.as_deref(), Some("zombies"));
+        assert_eq!((job.progress_current, job.progress_total), (2, 5));
+        assert_eq!(job.result.unwrap()["zombies_detected"], 1);
+        assert!(job.finished_at.is_some());
+
+        let running = db.list_jobs(Some(JobStatus::Running), None, 10).unwrap();
+        assert_eq!(running.len(), 2);
+        let backups = db.list_jobs(None, Some(JobType::Backup), 10).unwrap();
+        assert_eq!(backups.len(), 1);
+    }
+
+    #[test]
+    fn test_job_retry_backoff_and_give_up() {
+        let db = Database::in_memory().unwrap();
+        let id = db
+            .create_job(&NewJob::new(JobType::InsightRefresh, serde_json::json!({})))
+            .unwrap();
+        let now = chrono::Utc::now() + chrono::Duration::seconds(1);
+
+        db.claim_next_job(&[JobType::InsightRefresh], now).unwrap();
+        let retry_at = now + chrono::Duration::minutes(5);
+        db.fail_job(id, "backend down", Some(retry_at)).unwrap();
+        let job = db.get_job(id).unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(job.error.as_deref(), Some("backend down"));
+
+        // Not due until the backoff has passed
+        assert!(db
+            .claim_next_job(&[JobType::InsightRefresh], now)
+            .unwrap()
+            .is_none());
+        let job = db
+            .claim_next_job(&[JobType::InsightRefresh], retry_at)
+            .unwrap()
+            .unwrap();
+        assert_eq!(job.attempts, 2);
+
+        db.fail_job(id, "still down", None).unwrap();
+        let job = db.get_job(id).unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Failed);
+        assert!(job.finished_at.is_some());
+
+        // A manual retry starts over
+        assert!(db.retry_job(id).unwrap());
+        let job = db.get_job(id).unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(job.attempts, 0);
+        assert!(job.error.is_none());
+        assert!(!db.retry_job(id).unwrap());
+    }
+
+    #[test]
+    fn test_job_cancellation() {
+        let db = Database::in_memory().unwrap();
+        let queued = db
+            .create_job(&NewJob::new(JobType::Backup, serde_json::json!({})))
+            .unwrap();
+        assert!(db.request_job_cancel(queued).unwrap());
+        assert_eq!(
+            db.get_job(queued).unwrap().unwrap().status,
+            JobStatus::Cancelled
+        );
+        assert!(!db.request_job_cancel(queued).unwrap());
+
+        let running = db
+            .create_job(&NewJob::new(JobType::Backup, serde_json::json!({})))
+            .unwrap();
+        let now = chrono::Utc::now() + chrono::Duration::seconds(1);
+        db.claim_next_job(&[JobType::Backup], now).unwrap();
+        assert!(!db.is_job_cancel_requested(running).unwrap());
+        assert!(db.request_job_cancel(running).unwrap());
+        // Running jobs are only flagged; the worker marks them cancelled
+        let job = db.get_job(running).unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Running);
+        assert!(db.is_job_cancel_requested(running).unwrap());
+        db.mark_job_cancelled(running).unwrap();
+        assert_eq!(
+            db.get_job(running).unwrap().unwrap().status,
+            JobStatus::Cancelled
+        );
+    }
+
+    #[test]
+    fn test_prune_finished_jobs() {
+        let db = Database::in_memory().unwrap();
+        let new = || NewJob::new(JobType::Backup, serde_json::json!({}));
+        let completed = db.create_job(&new()).unwrap();
+        let failed = db.create_job(&new()).unwrap();
+        let scheduled = db.create_job(&new()).unwrap();
+        let queued = db.create_job(&new()).unwrap();
+        db.complete_job(completed, &serde_json::json!({})).unwrap();
+        db.fail_job(failed, "boom", None).unwrap();
+        db.complete_job(scheduled, &serde_json::json!({})).unwrap();
+        db.set_schedule_next_run("nightly", "@daily", None).unwrap();
+        db.record_schedule_run("nightly", chrono::Utc::now(), Some(scheduled), None)
+            .unwrap();
+
+        // Recently finished jobs are kept
+        let now = chrono::Utc::now();
+        assert_eq!(
+            db.prune_finished_jobs(now - chrono::Duration::days(1))
+                .unwrap(),
+            0
+        );
+
+        // Unfinished jobs and a schedule's last job survive
+        assert_eq!(
+            db.prune_finished_jobs(now + chrono::Duration::days(1))
+                .unwrap(),
+            2
+        );
+        assert!(db.get_job(completed).unwrap().is_none());
+        assert!(db.get_job(failed).unwrap().is_none());
+        assert!(db.get_job(scheduled).unwrap().is_some());
+        assert!(db.get_job(queued).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_recover_interrupted_jobs_resumes_imports() {
+        let db = Database::in_memory().unwrap();
+        let account_id = db.upsert_account("Checking", Bank::Chase, None).unwrap();
+        let session_id = db
+            .create_import_session(&NewImportSession {
+                account_id,
+                filename: None,
+                file_size_bytes: None,
+                bank: Bank::Chase,
+                user_email: None,
+                ollama_model: None,
+            })
+            .unwrap();
+        db.update_import_progress(session_id, "tagging", 3, 10)
+            .unwrap();
+
+        let resumable = db
+            .create_job(
+                &NewJob::new(
+                    JobType::ImportProcessing,
+                    serde_json::json!({"session_id": session_id, "imported_count": 10}),
+                )
+                .for_entity("import_session", session_id),
+            )
+            .unwrap();
+        let exhausted = db
+            .create_job(&NewJob {
+                max_attempts: Some(1),
+                ..NewJob::new(JobType::Detection, serde_json::json!({}))
+            })
+            .unwrap();
+        let now = chrono::Utc::now() + chrono::Duration::seconds(1);
+        db.claim_next_job(&[JobType::ImportProcessing], now)
+            .unwrap();
+        db.claim_next_job(&[JobType::Detection], now).unwrap();
+
+        // Running import jobs report the session's progress
+        let job = db.get_job(resumable).unwrap().unwrap();
+        assert_eq!(job.progress_phase.as_deref(), Some("tagging"));
+        assert_eq!((job.progress_current, job.progress_total), (3, 10));
+
+        assert_eq!(db.recover_interrupted_jobs().unwrap(), 1);
+        let job = db.get_job(resumable).unwrap().unwrap();
+        assert_eq!(job.status, JobStatus::Queued);
+        assert_eq!(
+            db.get_job(exhausted).unwrap().unwrap().status,
+            JobStatus::Failed
+        );
+
+        // The import will be resumed, so it isn't marked failed
+        assert_eq!(db.recover_stuck_imports().unwrap(), 0);
+        let session = db.get_import_session(session_id).unwrap().unwrap();
+        assert_eq!(session.session.status, ImportStatus::Processing);
+
+        // Cancelling the import's jobs releases it to normal recovery
+        assert_eq!(
+            db.request_entity_jobs_cancel("import_session", session_id)
+                .unwrap(),
+            1
+        );
+        assert_eq!(db.recover_stuck_imports().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_schedule_state_keeps_history_across_reschedules() {
+        let db = Database::in_memory().unwrap();
+        assert!(db.get_schedule_state("nightly").unwrap().is_none());
+
+        let next = chrono::Utc::now() + chrono::Duration::hours(2);
+        let next = parse_datetime(&next.format("%Y-%m-%d %H:%M:%S").to_string());
+        db.set_schedule_next_run("nightly", "0 3 * * *", Some(next))
+            .unwrap();
+        db.record_schedule_run("nightly", next, None, Some("queue full"))
+            .unwrap();
+        db.set_schedule_next_run("nightly", "0 4 * * *", None)
+            .unwrap();
+
+        let state = db.get_schedule_state("nightly").unwrap().unwrap();
+        assert_eq!(state.cron, "0 4 * * *");
+        assert_eq!(state.next_run_at, None);
+        assert_eq!(state.last_run_at, Some(next));
+        assert_eq!(state.last_error.as_deref(), Some("queue full"));
+        assert_eq!(db.list_schedule_states().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_prune_ollama_metrics() {
+        let db = Database::in_memory().unwrap();
+        for _ in 0..3 {
+            db.record_ollama_metric(&NewOllamaMetric {
+                operation: OllamaOperation::NormalizeMerchant,
+                model: "m".to_string(),
+                latency_ms: 10,
+                success: true,
+                error_message: None,
+                confidence: None,
+                transaction_id: None,
+                input_text: None,
+                result_text: None,
+                metadata: None,
+            })
+            .unwrap();
+        }
+        db.conn()
+            .unwrap()
+            .execute(
+                "UPDATE ollama_metrics SET started_at = datetime('now', '-100 days') WHERE id <= 2",
+                [],
+            )
+            .unwrap();
+
+        assert_eq!(db.prune_ollama_metrics(90).unwrap(), 2);
+        assert_eq!(db.prune_ollama_metrics(90).unwrap(), 0);
+        assert_eq!(db.get_recent_ollama_calls(10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_import_idempotency_keys() {
+        let db = Database::in_memory().unwrap();
+        let account_id = db.upsert_account("Checking", Bank::Chase, None).unwrap();
+        let session_id = db
+            .create_import_session(&NewImportSession {
+                account_id,
+                filename: None,
+                file_size_bytes: None,
+                bank: Bank::Chase,
+                user_email: None,
+                ollama_model: None,
+            })
+            .unwrap();
+
+        assert!(db
+            .reserve_import_idempotency_key("k1", "a@example.com", "hash-a")
+            .unwrap()
+            .is_none());
+        // In progress: reserved with no response yet
+        let pending = db
+            .reserve_import_idempotency_key("k1", "a@example.com", "hash-a")
+            .unwrap()
+            .unwrap();
+        assert!(pending.response.is_none());
+
+        db.complete_import_idempotency_key("k1", "a@example.com", session_id, "{\"ok\":true}")
+            .unwrap();
+        let done = db
+            .reserve_import_idempotency_key("k1", "a@example.com", "hash-b")
+            .unwrap()
+            .unwrap();
+        assert_eq!(done.request_hash, "hash-a");
+        assert_eq!(done.import_session_id, Some(session_id));
+        assert_eq!(done.response.as_deref(), Some("{\"ok\":true}"));
+
+        // Another caller's key with the same name is separate
+        assert!(db
+            .reserve_import_idempotency_key("k1", "b@example.com", "hash-a")
+            .unwrap()
+            .is_none());
+        db.complete_import_idempotency_key("k1", "b@example.com", session_id, "{\"b\":1}")
+            .unwrap();
+        let done = db
+            .reserve_import_idempotency_key("k1", "a@example.com", "hash-a")
+            .unwrap()
+            .unwrap();
+        assert_eq!(done.created_by, "a@example.com");
+        assert_eq!(done.response.as_deref(), Some("{\"ok\":true}"));
+
+        // Completed keys aren't released; failed reservations are
+        db.release_import_idempotency_key("k1", "a@example.com")
+            .unwrap();
+        assert!(db
+            .reserve_import_idempotency_key("k1", "a@example.com", "hash-a")
+            .unwrap()
+            .is_some());
+        db.reserve_import_idempotency_key("k2", "a@example.com", "hash-c")
+            .unwrap();
+        db.release_import_idempotency_key("k2", "a@example.com")
+            .unwrap();
+        assert!(db
+            .reserve_import_idempotency_key("k2", "a@example.com", "hash-c")
+            .unwrap()
+            .is_none());
+
+        // Old keys expire
+        db.conn()
+            .unwrap()
+            .execute(
+                "UPDATE import_idempotency_keys SET created_at = datetime('now', '-31 days') WHERE key = 'k1'",
+                [],
+            )
+            .unwrap();
+        assert!(db
+            .reserve_import_idempotency_key("k1", "a@example.com", "hash-z")
+            .unwrap()
+            .is_none());
+    }
 }
@@ -15,6 +15,8 @@ use chrono::NaiveDate;
 #[derive(Default)]
 pub struct TransactionFilter<'query> {
     pub account_id: Option<i64>,
+    /// Accounts to leave out (filled in from the database handle's hidden accounts)
+    pub exclude_account_ids: Option<&'query [i64]>,
     pub entity_id: Option<i64>,
     pub card_member: Option<&'query str>,
     pub search: Option<&'query str>,
@@ -52,6 +54,12 @@ impl<'query> TransactionFilter<'query> {
         self
     }
 
+    /// Exclude transactions from these accounts
+    pub(crate) fn exclude_account_ids(mut self, ids: Option<&'query [i64]>) -> Self {
+        self.exclude_account_ids = ids;
+        self
+    }
+
     /// Set entity_id filter (filters by account owner)
     pub fn entity_id(mut self, id: Option<i64>) -> Self {
         self.entity_id = id;
@@ -157,6 +165,17 @@ impl<'query> TransactionFilter<'query> {
             where_params.push(Box::new(aid));
         }
 
+        // Excluded accounts
+        if let Some(ids) = self.exclude_account_ids {
+            if !ids.is_empty() {
+                let placeholders: Vec<&str> = ids.iter().map(|_| "?").collect();
+                conditions.push(format!("t.account_id NOT IN ({})", placeholders.join(", ")));
+                for id in ids {
+                    where_params.push(Box::new(*id));
+                }
+            }
+        }
+
         // Entity filter (account owner)
         if let Some(eid) = self.entity_id {
             conditions.push("a.entity_id = ?".to_string());
@@ -189,7 +189,10 @@ impl Database {
         };
 
         // Always filter out archived transactions (this method doesn't expose include_archived)
-        conditions.push("t.archived = 0".to_string());
+        conditions.push(format!(
+            "t.archived = 0 {}",
+            self.account_scope_sql("t.account_id")
+        ));
 
         let where_clause = if conditions.is_empty() {
             String::new()
@@ -306,9 +309,6 @@ impl Database {
         limit: i64,
         offset: i64,
     ) -> Result<Vec<Transaction>> {
-        let conn = self.conn()?;
-
-        // Build filter using the builder
         let filter = TransactionFilter::new()
             .account_id(account_id)
             .entity_id(entity_id)
@@ -319,7 +319,21 @@ impl Database {
             .date_range(date_range)
             .sort_field(sort_field)
             .sort_order(sort_order)
-            .include_archived(include_archived)
+            .include_archived(include_archived);
+
+        self.search_transactions_filtered(filter, limit, offset)
+    }
+
+    /// Search transactions with a prebuilt filter (for filters the positional API lacks)
+    pub fn search_transactions_filtered(
+        &self,
+        filter: TransactionFilter<'_>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Transaction>> {
+        let conn = self.conn()?;
+        let filter = filter
+            .exclude_account_ids(Some(self.hidden_accounts()))
             .build();
 
         // Build SELECT query
@@ -406,7 +420,10 @@ impl Database {
         }
 
         // Always exclude archived transactions for count
-        conditions.push("t.archived = 0".to_string());
+        conditions.push(format!(
+            "t.archived = 0 {}",
+            self.account_scope_sql("t.account_id")
+        ));
 
         // Tag filtering with hierarchy support
         let tag_cte = if let Some(ids) = tag_ids {
@@ -480,9 +497,7 @@ impl Database {
         untagged: bool,
         date_range: Option<(NaiveDate, NaiveDate)>,
     ) -> Result<i64> {
-        let conn = self.conn()?;
-
-        // Build filter using the builder (count always excludes archived)
+        // Count always excludes archived
         let filter = TransactionFilter::new()
             .account_id(account_id)
             .entity_id(entity_id)
@@ -491,7 +506,16 @@ impl Database {
             .tag_ids(tag_ids)
             .untagged(untagged)
             .date_range(date_range)
-            .include_archived(false)
+            .include_archived(false);
+
+        self.count_transactions_filtered(filter)
+    }
+
+    /// Count transactions matching a prebuilt filter
+    pub fn count_transactions_filtered(&self, filter: TransactionFilter<'_>) -> Result<i64> {
+        let conn = self.conn()?;
+        let filter = filter
+            .exclude_account_ids(Some(self.hidden_accounts()))
             .build();
 
         // Build COUNT query
@@ -548,12 +572,13 @@ impl Database {
     /// Get a single transaction by ID
     pub fn get_transaction(&self, id: i64) -> Result<Option<Transaction>> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT id, account_id, date, description, amount, category, merchant_normalized,
                     import_hash, purchase_location_id, vendor_location_id, trip_id,
                     source, expected_amount, archived, original_data, import_format, card_member, payment_method, created_at
-             FROM transactions WHERE id = ?",
-        )?;
+             FROM transactions WHERE id = ? {}",
+            self.account_scope_sql("account_id")
+        ))?;
 
         let transaction = stmt
             .query_row(params![id], |row| Self::row_to_transaction(row))
@@ -618,7 +643,10 @@ impl Database {
     pub fn count_archived_transactions(&self) -> Result<i64> {
         let conn = self.conn()?;
         let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM transactions WHERE archived = 1",
+            &format!(
+                "SELECT COUNT(*) FROM transactions WHERE archived = 1 {}",
+                self.account_scope_sql("account_id")
+            ),
             [],
             |row| row.get(0),
         )?;
@@ -628,17 +656,18 @@ impl Database {
     /// List archived transactions
     pub fn list_archived_transactions(&self, limit: i64, offset: i64) -> Result<Vec<Transaction>> {
         let conn = self.conn()?;
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             r#"
             SELECT id, account_id, date, description, amount, category, merchant_normalized,
                    import_hash, purchase_location_id, vendor_location_id, trip_id,
                    source, expected_amount, archived, original_data, import_format, card_member, payment_method, created_at
             FROM transactions
-            WHERE archived = 1
+            WHERE archived = 1 {}
             ORDER BY date DESC, id DESC
             LIMIT ? OFFSET ?
             "#,
-        )?;
+            self.account_scope_sql("account_id")
+        ))?;
 
         let transactions = stmt
             .query_map(params![limit, offset], |row| Self::row_to_transaction(row))?
@@ -0,0 +1,161 @@
+//! Free-trial and intro-price forecasts
+//!
+//! Each forecast comes with a `trial_ending` alert; resolving the forecast
+//! (the full-price charge posted, or the trial lapsed) dismisses the alert.
+
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use super::{parse_datetime, Database};
+use crate::error::Result;
+use crate::models::{AlertType, NewTrialForecast, TrialForecast, TrialSignal, TrialStatus};
+
+const TRIAL_COLUMNS: &str = "id, merchant, account_id, transaction_id, signal, trial_amount, started_on, expected_conversion, expected_amount, status, alert_id, converted_transaction_id, created_at";
+
+fn trial_from_row(row: &Row) -> rusqlite::Result<TrialForecast> {
+    let signal: String = row.get(4)?;
+    let started_on: String = row.get(6)?;
+    let expected_conversion: String = row.get(7)?;
+    let status: String = row.get(9)?;
+    let created_at: String = row.get(12)?;
+
+    Ok(TrialForecast {
+        id: row.get(0)?,
+        merchant: row.get(1)?,
+        account_id: row.get(2)?,
+        transaction_id: row.get(3)?,
+        signal: signal.parse().unwrap_or(TrialSignal::Authorization),
+        trial_amount: row.get(5)?,
+        started_on: NaiveDate::parse_from_str(&started_on, "%Y-%m-%d").unwrap_or_default(),
+        expected_conversion: NaiveDate::parse_from_str(&expected_conversion, "%Y-%m-%d")
+            .unwrap_or_default(),
+        expected_amount: row.get(8)?,
+        status: status.parse().unwrap_or(TrialStatus::Pending),
+        alert_id: row.get(10)?,
+        converted_transaction_id: row.get(11)?,
+        created_at: parse_datetime(&created_at),
+    })
+}
+
+/// Trial forecast behind a `trial_ending` alert
+pub(super) fn alert_trial(conn: &Connection, alert_id: i64) -> Result<Option<TrialForecast>> {
+    let trial = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM trial_forecasts WHERE alert_id = ?",
+                TRIAL_COLUMNS
+            ),
+            params![alert_id],
+            trial_from_row,
+        )
+        .optional()?;
+    Ok(trial)
+}
+
+impl Database {
+    /// Record a likely trial and raise its `trial_ending` alert
+    ///
+    /// Returns `None` if a trial was already recorded for this merchant on
+    /// this account (each merchant gets one trial per account).
+    pub fn create_trial_forecast(
+        &self,
+        trial: &NewTrialForecast,
+        message: &str,
+    ) -> Result<Option<TrialForecast>> {
+        {
+            let conn = self.conn()?;
+            let exists: bool = conn.query_row(
+                r#"
+                SELECT EXISTS(
+                    SELECT 1 FROM trial_forecasts
+                    WHERE transaction_id = ? OR (account_id = ? AND merchant = ?)
+                )
+                "#,
+                params![trial.transaction_id, trial.account_id, trial.merchant],
+                |row| row.get(0),
+            )?;
+            if exists {
+                return Ok(None);
+            }
+        }
+
+        let alert_id = self.create_alert(AlertType::TrialEnding, None, Some(message))?;
+
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO trial_forecasts
+                (merchant, account_id, transaction_id, signal, trial_amount, started_on,
+                 expected_conversion, expected_amount, alert_id)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                trial.merchant,
+                trial.account_id,
+                trial.transaction_id,
+                trial.signal.as_str(),
+                trial.trial_amount,
+                trial.started_on.to_string(),
+                trial.expected_conversion.to_string(),
+                trial.expected_amount,
+                alert_id,
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        drop(conn);
+
+        self.get_trial_forecast(id)
+    }
+
+    /// Get a trial forecast by ID
+    pub fn get_trial_forecast(&self, id: i64) -> Result<Option<TrialForecast>> {
+        let conn = self.conn()?;
+        let trial = conn
+            .query_row(
+                &format!("SELECT {} FROM trial_forecasts WHERE id = ?", TRIAL_COLUMNS),
+                params![id],
+                trial_from_row,
+            )
+            .optional()?;
+        Ok(trial)
+    }
+
+    /// List trial forecasts, soonest conversion first
+    pub fn list_trial_forecasts(&self, status: Option<TrialStatus>) -> Result<Vec<TrialForecast>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT {} FROM trial_forecasts
+            WHERE (?1 IS NULL OR status = ?1)
+            ORDER BY expected_conversion, id
+            "#,
+            TRIAL_COLUMNS
+        ))?;
+        let trials = stmt
+            .query_map(params![status.map(|s| s.as_str())], trial_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(trials)
+    }
+
+    /// Close out a trial and dismiss its alert
+    pub fn resolve_trial_forecast(
+        &self,
+        id: i64,
+        status: TrialStatus,
+        converted_transaction_id: Option<i64>,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "UPDATE trial_forecasts SET status = ?, converted_transaction_id = ? WHERE id = ?",
+            params![status.as_str(), converted_transaction_id, id],
+        )?;
+        conn.execute(
+            r#"
+            UPDATE alerts SET dismissed = TRUE
+            WHERE id = (SELECT alert_id FROM trial_forecasts WHERE id = ?)
+            "#,
+            params![id],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,249 @@
+//! Household users, roles and per-account visibility
+
+use rusqlite::{params, OptionalExtension};
+
+use super::{parse_datetime, Database};
+use crate::error::{Error, Result};
+use crate::models::{User, UserRole};
+
+const USER_COLUMNS: &str = "id, email, display_name, role, created_at, last_seen_at, password_hash IS NOT NULL, totp_enabled";
+
+impl Database {
+    /// Add a household user
+    pub fn create_user(
+        &self,
+        email: &str,
+        display_name: Option<&str>,
+        role: UserRole,
+    ) -> Result<i64> {
+        let email = email.trim();
+        if email.is_empty() {
+            return Err(Error::InvalidData("User email is required".to_string()));
+        }
+        if self.get_user_by_email(email)?.is_some() {
+            return Err(Error::InvalidData(format!("User {} already exists", email)));
+        }
+
+        let conn = self.conn()?;
+        conn.execute(
+            "INSERT INTO users (email, display_name, role) VALUES (?, ?, ?)",
+            params![email, display_name, role.as_str()],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Get a user by ID
+    pub fn get_user(&self, id: i64) -> Result<Option<User>> {
+        let conn = self.conn()?;
+        let sql = format!("SELECT {} FROM users WHERE id = ?", USER_COLUMNS);
+        let user = conn
+            .query_row(&sql, params![id], Self::row_to_user)
+            .optional()?;
+        Ok(user)
+    }
+
+    /// Get a user by email (case-insensitive)
+    pub fn get_user_by_email(&self, email: &str) -> Result<Option<User>> {
+        let conn = self.conn()?;
+        let sql = format!("SELECT {} FROM users WHERE email = ?", USER_COLUMNS);
+        let user = conn
+            .query_row(&sql, params![email.trim()], Self::row_to_user)
+            .optional()?;
+        Ok(user)
+    }
+
+    /// List all users (owners first)
+    pub fn list_users(&self) -> Result<Vec<User>> {
+        let conn = self.conn()?;
+        let sql = format!(
+            r#"
+            SELECT {} FROM users
+            ORDER BY CASE role WHEN 'owner' THEN 0 WHEN 'editor' THEN 1 ELSE 2 END,
+                     email COLLATE NOCASE
+            "#,
+            USER_COLUMNS
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([], Self::row_to_user)?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Number of users (zero means the instance has not been set up for a household yet)
+    pub fn count_users(&self) -> Result<i64> {
+        let conn = self.conn()?;
+        let count = conn.query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))?;
+        Ok(count)
+    }
+
+    /// Update a user's display name and/or role (None = leave unchanged)
+    ///
+    /// Refuses to demote the last owner so the household can't lock itself out.
+    pub fn update_user(
+        &self,
+        id: i64,
+        display_name: Option<Option<&str>>,
+        role: Option<UserRole>,
+    ) -> Result<()> {
+        let user = self
+            .get_user(id)?
+            .ok_or_else(|| Error::NotFound(format!("User {}", id)))?;
+
+        if let Some(role) = role {
+            if user.role == UserRole::Owner && role != UserRole::Owner && self.count_owners()? <= 1
+            {
+                return Err(Error::InvalidData(
+                    "Cannot demote the last owner".to_string(),
+                ));
+            }
+        }
+
+        let conn = self.conn()?;
+        if let Some(display_name) = display_name {
+            conn.execute(
+                "UPDATE users SET display_name = ? WHERE id = ?",
+                params![display_name, id],
+            )?;
+        }
+        if let Some(role) = role {
+            conn.execute(
+                "UPDATE users SET role = ? WHERE id = ?",
+                params![role.as_str(), id],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Remove a user with their account visibility grants, sessions and passkeys
+    ///
+    /// API keys the user created are revoked (kept for the audit trail).
+    pub fn delete_user(&self, id: i64) -> Result<bool> {
+        let Some(user) = self.get_user(id)? else {
+            return Ok(false);
+        };
+        if user.role == UserRole::Owner && self.count_owners()? <= 1 {
+            return Err(Error::InvalidData(
+                "Cannot remove the last owner".to_string(),
+            ));
+        }
+
+        let conn = self.conn()?;
+        conn.execute(
+            "DELETE FROM account_visibility WHERE user_id = ?",
+            params![id],
+        )?;
+        conn.execute("DELETE FROM auth_sessions WHERE user_id = ?", params![id])?;
+        conn.execute(
+            "DELETE FROM webauthn_credentials WHERE user_id = ?",
+            params![id],
+        )?;
+        conn.execute(
+            "UPDATE api_keys SET revoked_at = CURRENT_TIMESTAMP WHERE created_by = ? COLLATE NOCASE AND revoked_at IS NULL",
+            params![user.email],
+        )?;
+        let deleted = conn.execute("DELETE FROM users WHERE id = ?", params![id])?;
+        Ok(deleted > 0)
+    }
+
+    /// Record that a user made a request
+    pub fn touch_user(&self, id: i64) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "UPDATE users SET last_seen_at = CURRENT_TIMESTAMP WHERE id = ?",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    fn count_owners(&self) -> Result<i64> {
+        let conn = self.conn()?;
+        let count = conn.query_row(
+            "SELECT COUNT(*) FROM users WHERE role = 'owner'",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
+    /// Restrict an account to the given users (empty = shared with the household)
+    pub fn set_account_visibility(&self, account_id: i64, user_ids: &[i64]) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute("BEGIN TRANSACTION", [])?;
+
+        let result = (|| {
+            conn.execute(
+                "DELETE FROM account_visibility WHERE account_id = ?",
+                params![account_id],
+            )?;
+            for user_id in user_ids {
+                conn.execute(
+                    "INSERT OR IGNORE INTO account_visibility (account_id, user_id) VALUES (?, ?)",
+                    params![account_id, user_id],
+                )?;
+            }
+            Ok(())
+        })();
+
+        match result {
+            Ok(()) => {
+                conn.execute("COMMIT", [])?;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = conn.execute("ROLLBACK", []);
+                Err(e)
+            }
+        }
+    }
+
+    /// Users an account is restricted to (empty = shared with the household)
+    pub fn get_account_visibility(&self, account_id: i64) -> Result<Vec<i64>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT user_id FROM account_visibility WHERE account_id = ? ORDER BY user_id",
+        )?;
+        let rows = stmt.query_map(params![account_id], |row| row.get(0))?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Accounts restricted to other users that this user may not see
+    ///
+    /// Owners see every account.
+    pub fn hidden_account_ids(&self, user: &User) -> Result<Vec<i64>> {
+        if user.role == UserRole::Owner {
+            return Ok(Vec::new());
+        }
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT DISTINCT account_id FROM account_visibility
+            WHERE account_id NOT IN (
+                SELECT account_id FROM account_visibility WHERE user_id = ?
+            )
+            ORDER BY account_id
+            "#,
+        )?;
+        let rows = stmt.query_map(params![user.id], |row| row.get(0))?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// A handle that only reads what this user may see (see `with_hidden_accounts`)
+    pub fn for_user(&self, user: &User) -> Result<Database> {
+        Ok(self.with_hidden_accounts(self.hidden_account_ids(user)?))
+    }
+
+    fn row_to_user(row: &rusqlite::Row) -> rusqlite::Result<User> {
+        let role: String = row.get(3)?;
+        let created_at: String = row.get(4)?;
+        let last_seen_at: Option<String> = row.get(5)?;
+        Ok(User {
+            id: row.get(0)?,
+            email: row.get(1)?,
+            display_name: row.get(2)?,
+            role: role.parse().unwrap_or(UserRole::Viewer),
+            created_at: parse_datetime(&created_at),
+            last_seen_at: last_seen_at.map(|s| parse_datetime(&s)),
+            has_password: row.get(6)?,
+            totp_enabled: row.get(7)?,
+        })
+    }
+}
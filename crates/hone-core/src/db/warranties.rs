@@ -0,0 +1,533 @@
+//! Warranty and return-window tracking
+//!
+//! Each warranty can raise a `return_window_closing` alert in the week
+//! before its return window closes and a `warranty_expiring` alert in the
+//! month before its warranty ends. Reminders whose window has closed are
+//! dismissed.
+
+use chrono::{Duration, Months, NaiveDate};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use super::{parse_date, parse_datetime, Database};
+use crate::error::{Error, Result};
+use crate::models::{
+    AlertType, NewWarranty, Warranty, WarrantyReport, WarrantyReportItem, WarrantyStatus,
+    WarrantyUpdate,
+};
+
+/// Days before the return-by date to raise a reminder
+pub const RETURN_REMINDER_DAYS: i64 = 7;
+
+/// Days before the warranty ends to raise a reminder
+pub const WARRANTY_REMINDER_DAYS: i64 = 30;
+
+const WARRANTY_COLUMNS: &str = "id, item, merchant, purchase_date, price, return_by, warranty_expires, serial_number, receipt_id, split_id, transaction_id, document_receipt_id, notes, return_alert_id, warranty_alert_id, created_at";
+
+fn warranty_from_row(row: &Row) -> rusqlite::Result<Warranty> {
+    let purchase_date: String = row.get(3)?;
+    let created_at: String = row.get(15)?;
+
+    Ok(Warranty {
+        id: row.get(0)?,
+        item: row.get(1)?,
+        merchant: row.get(2)?,
+        purchase_date: NaiveDate::parse_from_str(&purchase_date, "%Y-%m-%d").unwrap_or_default(),
+        price: row.get(4)?,
+        return_by: parse_date(row.get(5)?),
+        warranty_expires: parse_date(row.get(6)?),
+        serial_number: row.get(7)?,
+        receipt_id: row.get(8)?,
+        split_id: row.get(9)?,
+        transaction_id: row.get(10)?,
+        document_receipt_id: row.get(11)?,
+        notes: row.get(12)?,
+        return_alert_id: row.get(13)?,
+        warranty_alert_id: row.get(14)?,
+        created_at: parse_datetime(&created_at),
+    })
+}
+
+/// Warranty behind a `return_window_closing` or `warranty_expiring` alert
+pub(super) fn alert_warranty(conn: &Connection, alert_id: i64) -> Result<Option<Warranty>> {
+    let warranty = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM warranties WHERE return_alert_id = ?1 OR warranty_alert_id = ?1",
+                WARRANTY_COLUMNS
+            ),
+            params![alert_id],
+            warranty_from_row,
+        )
+        .optional()?;
+    Ok(warranty)
+}
+
+/// Check the windows close on or after the purchase date
+fn validate_windows(warranty: &Warranty) -> Result<()> {
+    if warranty.item.trim().is_empty() {
+        return Err(Error::InvalidData("item is required".into()));
+    }
+    if warranty.return_by.is_none() && warranty.warranty_expires.is_none() {
+        return Err(Error::InvalidData(
+            "Set a return window or warranty end date".into(),
+        ));
+    }
+    for (name, date) in [
+        ("return_by", warranty.return_by),
+        ("warranty_expires", warranty.warranty_expires),
+    ] {
+        if date.is_some_and(|d| d < warranty.purchase_date) {
+            return Err(Error::InvalidData(format!(
+                "{} is before the purchase date",
+                name
+            )));
+        }
+    }
+    if warranty.price.is_some_and(|p| p < 0.0) {
+        return Err(Error::InvalidData("price can't be negative".into()));
+    }
+    Ok(())
+}
+
+/// Item name with its merchant, for alert messages
+fn describe(warranty: &Warranty) -> String {
+    match &warranty.merchant {
+        Some(merchant) => format!("{} from {}", warranty.item, merchant),
+        None => warranty.item.clone(),
+    }
+}
+
+fn days_phrase(days: i64) -> String {
+    match days {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        n => format!("in {} days", n),
+    }
+}
+
+impl Database {
+    /// Record a warranty or return window for a purchase
+    ///
+    /// Fields left blank are filled from the split, receipt or transaction
+    /// it's tied to.
+    pub fn create_warranty(&self, new: &NewWarranty) -> Result<Warranty> {
+        let mut item = new.item.clone().filter(|s| !s.trim().is_empty());
+        let mut merchant = new.merchant.clone().filter(|s| !s.trim().is_empty());
+        let mut purchase_date = new.purchase_date;
+        let mut price = new.price;
+        let mut transaction_id = new.transaction_id;
+
+        if let Some(split_id) = new.split_id {
+            let split = self
+                .get_split_by_id(split_id)?
+                .ok_or_else(|| Error::NotFound(format!("Split {} not found", split_id)))?;
+            if transaction_id.is_some_and(|id| id != split.transaction_id) {
+                return Err(Error::InvalidData(
+                    "Split belongs to a different transaction".into(),
+                ));
+            }
+            transaction_id = Some(split.transaction_id);
+            item = item.or(split.description);
+            price = price.or(Some(split.amount.abs()));
+        }
+
+        if let Some(receipt_id) = new.receipt_id {
+            let receipt = self
+                .get_receipt(receipt_id)?
+                .ok_or_else(|| Error::NotFound(format!("Receipt {} not found", receipt_id)))?;
+            if let (Some(tx), Some(receipt_tx)) = (transaction_id, receipt.transaction_id) {
+                if tx != receipt_tx {
+                    return Err(Error::InvalidData(
+                        "Receipt belongs to a different transaction".into(),
+                    ));
+                }
+            }
+            transaction_id = transaction_id.or(receipt.transaction_id);
+            merchant = merchant.or(receipt.receipt_merchant);
+            purchase_date = purchase_date.or(receipt.receipt_date);
+        }
+
+        if let Some(tx_id) = transaction_id {
+            let tx = self
+                .get_transaction(tx_id)?
+                .ok_or_else(|| Error::NotFound(format!("Transaction {} not found", tx_id)))?;
+            merchant = merchant.or(tx.merchant_normalized).or(Some(tx.description));
+            purchase_date = purchase_date.or(Some(tx.date));
+        }
+
+        if let Some(document_id) = new.document_receipt_id {
+            self.get_receipt(document_id)?
+                .ok_or_else(|| Error::NotFound(format!("Receipt {} not found", document_id)))?;
+        }
+
+        let purchase_date = purchase_date.ok_or_else(|| {
+            Error::InvalidData(
+                "purchase_date is required when no receipt or transaction is given".into(),
+            )
+        })?;
+        let return_by = match (new.return_by, new.return_days) {
+            (Some(date), _) => Some(date),
+            (None, Some(days)) if days >= 0 => Some(purchase_date + Duration::days(days)),
+            (None, Some(_)) => {
+                return Err(Error::InvalidData("return_days can't be negative".into()))
+            }
+            (None, None) => None,
+        };
+        let warranty_expires = new.warranty_expires.or_else(|| {
+            new.warranty_months
+                .and_then(|m| purchase_date.checked_add_months(Months::new(m)))
+        });
+
+        let warranty = Warranty {
+            id: 0,
+            item: item.unwrap_or_default().trim().to_string(),
+            merchant,
+            purchase_date,
+            price,
+            return_by,
+            warranty_expires,
+            serial_number: new.serial_number.clone().filter(|s| !s.trim().is_empty()),
+            receipt_id: new.receipt_id,
+            split_id: new.split_id,
+            transaction_id,
+            document_receipt_id: new.document_receipt_id,
+            notes: new.notes.clone().filter(|s| !s.trim().is_empty()),
+            return_alert_id: None,
+            warranty_alert_id: None,
+            created_at: chrono::Utc::now(),
+        };
+        validate_windows(&warranty)?;
+
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            INSERT INTO warranties
+                (item, merchant, purchase_date, price, return_by, warranty_expires, serial_number,
+                 receipt_id, split_id, transaction_id, document_receipt_id, notes)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                warranty.item,
+                warranty.merchant,
+                warranty.purchase_date.to_string(),
+                warranty.price,
+                warranty.return_by.map(|d| d.to_string()),
+                warranty.warranty_expires.map(|d| d.to_string()),
+                warranty.serial_number,
+                warranty.receipt_id,
+                warranty.split_id,
+                warranty.transaction_id,
+                warranty.document_receipt_id,
+                warranty.notes,
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        drop(conn);
+
+        self.get_warranty(id)?
+            .ok_or_else(|| Error::NotFound(format!("Warranty {} not found", id)))
+    }
+
+    /// Get a warranty by ID
+    pub fn get_warranty(&self, id: i64) -> Result<Option<Warranty>> {
+        let conn = self.conn()?;
+        let warranty = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM warranties WHERE id = ? {}",
+                    WARRANTY_COLUMNS,
+                    self.transaction_scope_sql("transaction_id")
+                ),
+                params![id],
+                warranty_from_row,
+            )
+            .optional()?;
+        Ok(warranty)
+    }
+
+    /// List warranties, newest purchase first
+    ///
+    /// `search` matches the item, merchant, serial number or notes.
+    pub fn list_warranties(
+        &self,
+        search: Option<&str>,
+        receipt_id: Option<i64>,
+    ) -> Result<Vec<Warranty>> {
+        let pattern = search
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| format!("%{}%", s.to_lowercase()));
+
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT {} FROM warranties
+            WHERE (?1 IS NULL
+                   OR LOWER(item) LIKE ?1
+                   OR LOWER(COALESCE(merchant, '')) LIKE ?1
+                   OR LOWER(COALESCE(serial_number, '')) LIKE ?1
+                   OR LOWER(COALESCE(notes, '')) LIKE ?1)
+              AND (?2 IS NULL OR receipt_id = ?2 OR document_receipt_id = ?2)
+              {}
+            ORDER BY purchase_date DESC, id DESC
+            "#,
+            WARRANTY_COLUMNS,
+            self.transaction_scope_sql("transaction_id")
+        ))?;
+        let warranties = stmt
+            .query_map(params![pattern, receipt_id], warranty_from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(warranties)
+    }
+
+    /// Update a warranty
+    ///
+    /// Moving a window dismisses its reminder so a new one is raised for the
+    /// new date.
+    pub fn update_warranty(&self, id: i64, update: &WarrantyUpdate) -> Result<Warranty> {
+        let current = self
+            .get_warranty(id)?
+            .ok_or_else(|| Error::NotFound(format!("Warranty {} not found", id)))?;
+
+        if let Some(document_id) = update.document_receipt_id {
+            self.get_receipt(document_id)?
+                .ok_or_else(|| Error::NotFound(format!("Receipt {} not found", document_id)))?;
+        }
+
+        let mut warranty = current.clone();
+        if let Some(item) = &update.item {
+            warranty.item = item.trim().to_string();
+        }
+        if let Some(merchant) = &update.merchant {
+            warranty.merchant = Some(merchant.clone()).filter(|s| !s.trim().is_empty());
+        }
+        if let Some(date) = update.purchase_date {
+            warranty.purchase_date = date;
+        }
+        if let Some(price) = update.price {
+            warranty.price = Some(price);
+        }
+        if let Some(date) = update.return_by {
+            warranty.return_by = Some(date);
+        }
+        if let Some(date) = update.warranty_expires {
+            warranty.warranty_expires = Some(date);
+        }
+        if let Some(serial) = &update.serial_number {
+            warranty.serial_number = Some(serial.clone()).filter(|s| !s.trim().is_empty());
+        }
+        if let Some(document_id) = update.document_receipt_id {
+            warranty.document_receipt_id = Some(document_id);
+        }
+        if let Some(notes) = &update.notes {
+            warranty.notes = Some(notes.clone()).filter(|s| !s.trim().is_empty());
+        }
+        validate_windows(&warranty)?;
+
+        let mut stale_alerts = Vec::new();
+        if warranty.return_by != current.return_by {
+            stale_alerts.extend(warranty.return_alert_id.take());
+        }
+        if warranty.warranty_expires != current.warranty_expires {
+            stale_alerts.extend(warranty.warranty_alert_id.take());
+        }
+
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            UPDATE warranties
+            SET item = ?, merchant = ?, purchase_date = ?, price = ?, return_by = ?,
+                warranty_expires = ?, serial_number = ?, document_receipt_id = ?, notes = ?,
+                return_alert_id = ?, warranty_alert_id = ?
+            WHERE id = ?
+            "#,
+            params![
+                warranty.item,
+                warranty.merchant,
+                warranty.purchase_date.to_string(),
+                warranty.price,
+                warranty.return_by.map(|d| d.to_string()),
+                warranty.warranty_expires.map(|d| d.to_string()),
+                warranty.serial_number,
+                warranty.document_receipt_id,
+                warranty.notes,
+                warranty.return_alert_id,
+                warranty.warranty_alert_id,
+                id,
+            ],
+        )?;
+        for alert_id in stale_alerts {
+            conn.execute(
+                "UPDATE alerts SET dismissed = TRUE WHERE id = ?",
+                params![alert_id],
+            )?;
+        }
+        drop(conn);
+
+        self.get_warranty(id)?
+            .ok_or_else(|| Error::NotFound(format!("Warranty {} not found", id)))
+    }
+
+    /// Delete a warranty and dismiss its reminders
+    ///
+    /// Returns false if there was no such warranty.
+    pub fn delete_warranty(&self, id: i64) -> Result<bool> {
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            UPDATE alerts SET dismissed = TRUE
+            WHERE id IN (
+                SELECT return_alert_id FROM warranties WHERE id = ?1
+                UNION SELECT warranty_alert_id FROM warranties WHERE id = ?1
+            )
+            "#,
+            params![id],
+        )?;
+        let deleted = conn.execute("DELETE FROM warranties WHERE id = ?", params![id])?;
+        Ok(deleted > 0)
+    }
+
+    /// What we own under warranty, or can still return, on `as_of`
+    ///
+    /// Items whose windows have all closed are only counted.
+    pub fn get_warranty_report(
+        &self,
+        search: Option<&str>,
+        as_of: NaiveDate,
+    ) -> Result<WarrantyReport> {
+        let mut report = WarrantyReport {
+            as_of,
+            query: search
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from),
+            items: Vec::new(),
+            returnable_count: 0,
+            covered_count: 0,
+            expired_count: 0,
+            covered_value: 0.0,
+        };
+
+        for warranty in self.list_warranties(search, None)? {
+            let status = warranty.status(as_of);
+            match status {
+                WarrantyStatus::Returnable => report.returnable_count += 1,
+                WarrantyStatus::Covered => report.covered_count += 1,
+                WarrantyStatus::Expired => {
+                    report.expired_count += 1;
+                    continue;
+                }
+            }
+            report.covered_value += warranty.price.unwrap_or(0.0);
+            report.items.push(WarrantyReportItem {
+                status,
+                return_days_left: warranty
+                    .return_by
+                    .filter(|d| *d >= as_of)
+                    .map(|d| (d - as_of).num_days()),
+                warranty_days_left: warranty
+                    .warranty_expires
+                    .filter(|d| *d >= as_of)
+                    .map(|d| (d - as_of).num_days()),
+                warranty,
+            });
+        }
+
+        report.items.sort_by_key(|item| {
+            (
+                item.return_days_left
+                    .or(item.warranty_days_left)
+                    .unwrap_or(i64::MAX),
+                item.warranty.id,
+            )
+        });
+        Ok(report)
+    }
+
+    /// Raise reminders for windows closing soon, and dismiss closed ones
+    ///
+    /// Each window gets one reminder, [`RETURN_REMINDER_DAYS`] before the
+    /// return-by date or [`WARRANTY_REMINDER_DAYS`] before the warranty ends.
+    /// Returns the number of reminders raised.
+    pub fn raise_warranty_reminders(&self, today: NaiveDate) -> Result<usize> {
+        let due: Vec<(Warranty, AlertType)> = {
+            let conn = self.conn()?;
+            let mut stmt = conn.prepare(&format!(
+                r#"
+                SELECT {0}, 'return_window_closing' FROM warranties
+                WHERE return_alert_id IS NULL AND return_by BETWEEN ?1 AND ?2
+                UNION ALL
+                SELECT {0}, 'warranty_expiring' FROM warranties
+                WHERE warranty_alert_id IS NULL AND warranty_expires BETWEEN ?1 AND ?3
+                "#,
+                WARRANTY_COLUMNS
+            ))?;
+            let rows = stmt
+                .query_map(
+                    params![
+                        today.to_string(),
+                        (today + Duration::days(RETURN_REMINDER_DAYS)).to_string(),
+                        (today + Duration::days(WARRANTY_REMINDER_DAYS)).to_string(),
+                    ],
+                    |row| {
+                        let alert_type: String = row.get(16)?;
+                        Ok((
+                            warranty_from_row(row)?,
+                            alert_type.parse().unwrap_or(AlertType::WarrantyExpiring),
+                        ))
+                    },
+                )?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            rows
+        };
+
+        let mut raised = 0;
+        for (warranty, alert_type) in due {
+            let (column, message) = if alert_type == AlertType::ReturnWindowClosing {
+                let return_by = warranty.return_by.unwrap_or(today);
+                (
+                    "return_alert_id",
+                    format!(
+                        "Return window for {} closes {} ({})",
+                        describe(&warranty),
+                        days_phrase((return_by - today).num_days()),
+                        return_by
+                    ),
+                )
+            } else {
+                let expires = warranty.warranty_expires.unwrap_or(today);
+                (
+                    "warranty_alert_id",
+                    format!(
+                        "Warranty on {} ends {} ({})",
+                        describe(&warranty),
+                        days_phrase((expires - today).num_days()),
+                        expires
+                    ),
+                )
+            };
+
+            let alert_id = self.create_alert(alert_type, None, Some(&message))?;
+            let conn = self.conn()?;
+            conn.execute(
+                &format!("UPDATE warranties SET {} = ? WHERE id = ?", column),
+                params![alert_id, warranty.id],
+            )?;
+            raised += 1;
+        }
+
+        let conn = self.conn()?;
+        conn.execute(
+            r#"
+            UPDATE alerts SET dismissed = TRUE
+            WHERE dismissed = FALSE AND id IN (
+                SELECT return_alert_id FROM warranties WHERE return_by < ?1
+                UNION SELECT warranty_alert_id FROM warranties WHERE warranty_expires < ?1
+            )
+            "#,
+            params![today.to_string()],
+        )?;
+
+        Ok(raised)
+    }
+}
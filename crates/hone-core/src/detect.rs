@@ -4,18 +4,26 @@
 //! - Zombie subscriptions: recurring charges you might have forgotten
 //! - Price increases: services that quietly raised prices
 //! - Duplicate services: multiple subscriptions in the same category
+//! - Free trials and intro prices: charges about to convert to full price
+//! - Transaction anomalies: unusual amounts, large first charges at new
+//!   merchants, unusual foreign or online activity, and duplicate charges
+//! - Return windows and warranties closing soon (reminders for tracked purchases)
 
 use chrono::{Datelike, Duration, NaiveDate, Utc};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use tracing::{debug, info, warn};
 
 use crate::ai::orchestrator::AIOrchestrator;
 use crate::ai::{AIBackend, AIClient, DuplicateAnalysis, ServiceFeature};
 use crate::db::Database;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::models::{
-    AlertType, FeedbackTargetType, Frequency, SpendingAnomalyData, SpendingChangeExplanation,
-    SubscriptionStatus, Transaction,
+    AlertType, AnomalyFeedback, CancellationStatus, FeedbackTargetType, Frequency,
+    NewTransactionAnomaly, NewTrialForecast, PaymentMethod, SpendingAnomalyData,
+    SpendingChangeExplanation, Subscription, SubscriptionStatus, Transaction, TrialSignal,
+    TrialStatus,
 };
 use crate::prompts::{PromptId, PromptLibrary};
 use crate::tools;
@@ -25,7 +33,11 @@ use crate::tools;
 pub type ProgressCallback = Box<dyn Fn(&str, i64, i64) + Send + Sync>;
 
 /// Detection configuration
-#[derive(Debug, Clone)]
+///
+/// Built from the defaults plus the user's saved settings (see
+/// [`DetectionSettings`]); unknown or missing fields take their defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DetectionConfig {
     /// Minimum months of recurring charges to flag as potential zombie
     pub zombie_min_months: i64,
@@ -61,6 +73,16 @@ pub struct DetectionConfig {
     pub acknowledgment_stale_days: i64,
     /// Threshold for tip discrepancy detection (absolute dollars)
     pub tip_discrepancy_threshold: f64,
+
+    // Transaction anomaly thresholds (raised further by dismissals)
+    /// Flag a charge this many times the merchant's median charge
+    pub unusual_amount_multiplier: f64,
+    /// Flag a first charge at a merchant at or above this amount (dollars)
+    pub new_merchant_threshold: f64,
+    /// Minimum foreign or card-not-present charge to flag (dollars)
+    pub unusual_activity_min_amount: f64,
+    /// Minimum same-day repeated charge to flag as a duplicate (dollars)
+    pub duplicate_charge_min_amount: f64,
 }
 
 impl Default for DetectionConfig {
@@ -82,7 +104,326 @@ impl Default for DetectionConfig {
             // Re-acknowledgment defaults
             acknowledgment_stale_days: 90, // Re-check after 90 days (~quarterly)
             tip_discrepancy_threshold: 0.50, // Flag if diff > $0.50
+            // Transaction anomaly defaults
+            unusual_amount_multiplier: 3.0, // 3x the merchant's median charge
+            new_merchant_threshold: 250.0,  // First charge of $250+
+            unusual_activity_min_amount: 50.0,
+            duplicate_charge_min_amount: 5.0,
+        }
+    }
+}
+
+/// Saved changes to detection thresholds (unset fields keep the inherited value)
+///
+/// Used for household-wide settings and for per-merchant and per-tag
+/// overrides; field meanings match [`DetectionConfig`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DetectionOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zombie_min_months: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_increase_percent: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price_increase_absolute: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancellation_grace_days_monthly: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smart_amount_variance: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smart_interval_consistency: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smart_min_transactions: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ollama_confidence_threshold: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spending_increase_threshold: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spending_decrease_threshold: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spending_anomaly_min_baseline: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledgment_stale_days: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tip_discrepancy_threshold: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unusual_amount_multiplier: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_merchant_threshold: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unusual_activity_min_amount: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_charge_min_amount: Option<f64>,
+}
+
+impl DetectionOverrides {
+    /// Names of the settings that can be overridden
+    pub const KEYS: &'static [&'static str] = &[
+        "zombie_min_months",
+        "price_increase_percent",
+        "price_increase_absolute",
+        "cancellation_grace_days_monthly",
+        "smart_amount_variance",
+        "smart_interval_consistency",
+        "smart_min_transactions",
+        "ollama_confidence_threshold",
+        "spending_increase_threshold",
+        "spending_decrease_threshold",
+        "spending_anomaly_min_baseline",
+        "acknowledgment_stale_days",
+        "tip_discrepancy_threshold",
+        "unusual_amount_multiplier",
+        "new_merchant_threshold",
+        "unusual_activity_min_amount",
+        "duplicate_charge_min_amount",
+    ];
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply these overrides on top of a config
+    pub fn apply(&self, config: &DetectionConfig) -> DetectionConfig {
+        DetectionConfig {
+            zombie_min_months: self.zombie_min_months.unwrap_or(config.zombie_min_months),
+            price_increase_percent: self
+                .price_increase_percent
+                .unwrap_or(config.price_increase_percent),
+            price_increase_absolute: self
+                .price_increase_absolute
+                .unwrap_or(config.price_increase_absolute),
+            cancellation_grace_days_monthly: self
+                .cancellation_grace_days_monthly
+                .unwrap_or(config.cancellation_grace_days_monthly),
+            smart_amount_variance: self
+                .smart_amount_variance
+                .unwrap_or(config.smart_amount_variance),
+            smart_interval_consistency: self
+                .smart_interval_consistency
+                .unwrap_or(config.smart_interval_consistency),
+            smart_min_transactions: self
+                .smart_min_transactions
+                .unwrap_or(config.smart_min_transactions),
+            ollama_confidence_threshold: self
+                .ollama_confidence_threshold
+                .unwrap_or(config.ollama_confidence_threshold),
+            spending_increase_threshold: self
+                .spending_increase_threshold
+                .unwrap_or(config.spending_increase_threshold),
+            spending_decrease_threshold: self
+                .spending_decrease_threshold
+                .unwrap_or(config.spending_decrease_threshold),
+            spending_anomaly_min_baseline: self
+                .spending_anomaly_min_baseline
+                .unwrap_or(config.spending_anomaly_min_baseline),
+            acknowledgment_stale_days: self
+                .acknowledgment_stale_days
+                .unwrap_or(config.acknowledgment_stale_days),
+            tip_discrepancy_threshold: self
+                .tip_discrepancy_threshold
+                .unwrap_or(config.tip_discrepancy_threshold),
+            unusual_amount_multiplier: self
+                .unusual_amount_multiplier
+                .unwrap_or(config.unusual_amount_multiplier),
+            new_merchant_threshold: self
+                .new_merchant_threshold
+                .unwrap_or(config.new_merchant_threshold),
+            unusual_activity_min_amount: self
+                .unusual_activity_min_amount
+                .unwrap_or(config.unusual_activity_min_amount),
+            duplicate_charge_min_amount: self
+                .duplicate_charge_min_amount
+                .unwrap_or(config.duplicate_charge_min_amount),
+        }
+    }
+
+    /// Set one value by name (e.g. from `hone config set`)
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        Self::check_key(key)?;
+        let number: f64 = value.trim().parse().map_err(|_| {
+            Error::InvalidData(format!("{} must be a number, got '{}'", key, value))
+        })?;
+        let mut map = self.to_map();
+        map.insert(key.to_string(), serde_json::json!(number));
+        *self = Self::from_map(map)?;
+        self.validate()
+    }
+
+    /// Clear one value by name; returns false if it wasn't set
+    pub fn unset(&mut self, key: &str) -> Result<bool> {
+        Self::check_key(key)?;
+        let mut map = self.to_map();
+        let removed = map.remove(key).is_some();
+        *self = Self::from_map(map)?;
+        Ok(removed)
+    }
+
+    fn check_key(key: &str) -> Result<()> {
+        if Self::KEYS.contains(&key) {
+            Ok(())
+        } else {
+            Err(Error::InvalidData(format!(
+                "Unknown detection setting: {} (expected one of: {})",
+                key,
+                Self::KEYS.join(", ")
+            )))
+        }
+    }
+
+    fn to_map(&self) -> serde_json::Map<String, serde_json::Value> {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        }
+    }
+
+    fn from_map(map: serde_json::Map<String, serde_json::Value>) -> Result<Self> {
+        // Whole numbers arrive as floats from `set`; integer fields need integers
+        let map = map
+            .into_iter()
+            .map(|(k, v)| match v.as_f64() {
+                Some(n) if n.fract() == 0.0 && n.abs() < i64::MAX as f64 => {
+                    (k, serde_json::json!(n as i64))
+                }
+                _ => (k, v),
+            })
+            .collect();
+        serde_json::from_value(serde_json::Value::Object(map))
+            .map_err(|e| Error::InvalidData(format!("Invalid detection setting: {}", e)))
+    }
+
+    /// Reject values that would break detection
+    pub fn validate(&self) -> Result<()> {
+        let fail =
+            |key: &str, rule: &str| Err(Error::InvalidData(format!("{} must be {}", key, rule)));
+        if self.zombie_min_months.is_some_and(|v| v < 1) {
+            return fail("zombie_min_months", "at least 1");
+        }
+        if self.smart_min_transactions.is_some_and(|v| v < 2) {
+            return fail("smart_min_transactions", "at least 2");
+        }
+        if self
+            .unusual_amount_multiplier
+            .is_some_and(|v| !v.is_finite() || v < 1.0)
+        {
+            return fail("unusual_amount_multiplier", "at least 1");
+        }
+        for (key, value) in [
+            (
+                "cancellation_grace_days_monthly",
+                self.cancellation_grace_days_monthly,
+            ),
+            ("acknowledgment_stale_days", self.acknowledgment_stale_days),
+        ] {
+            if value.is_some_and(|v| v < 0) {
+                return fail(key, "zero or more");
+            }
+        }
+        for (key, value) in [
+            ("smart_amount_variance", self.smart_amount_variance),
+            (
+                "smart_interval_consistency",
+                self.smart_interval_consistency,
+            ),
+            (
+                "ollama_confidence_threshold",
+                self.ollama_confidence_threshold,
+            ),
+        ] {
+            if value.is_some_and(|v| !(0.0..=1.0).contains(&v)) {
+                return fail(key, "between 0 and 1");
+            }
+        }
+        for (key, value) in [
+            ("price_increase_percent", self.price_increase_percent),
+            ("price_increase_absolute", self.price_increase_absolute),
+            (
+                "spending_increase_threshold",
+                self.spending_increase_threshold,
+            ),
+            (
+                "spending_decrease_threshold",
+                self.spending_decrease_threshold,
+            ),
+            (
+                "spending_anomaly_min_baseline",
+                self.spending_anomaly_min_baseline,
+            ),
+            ("tip_discrepancy_threshold", self.tip_discrepancy_threshold),
+            ("new_merchant_threshold", self.new_merchant_threshold),
+            (
+                "unusual_activity_min_amount",
+                self.unusual_activity_min_amount,
+            ),
+            (
+                "duplicate_charge_min_amount",
+                self.duplicate_charge_min_amount,
+            ),
+        ] {
+            if value.is_some_and(|v| !v.is_finite() || v < 0.0) {
+                return fail(key, "a non-negative number");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Detection overrides for one merchant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MerchantDetectionOverride {
+    /// Normalized merchant name (matched case-insensitively)
+    pub merchant: String,
+    pub overrides: DetectionOverrides,
+}
+
+/// Detection overrides for a tag and everything under it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagDetectionOverride {
+    pub tag_id: i64,
+    /// Tag path for display (e.g. "Utilities" or "Shopping.Groceries")
+    pub tag: String,
+    pub overrides: DetectionOverrides,
+}
+
+/// Saved detection settings: household-wide plus per-tag and per-merchant
+///
+/// Resolution order is defaults, then `global`, then matching tag
+/// overrides, then the merchant override (most specific wins).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DetectionSettings {
+    pub global: DetectionOverrides,
+    pub merchants: Vec<MerchantDetectionOverride>,
+    pub tags: Vec<TagDetectionOverride>,
+}
+
+impl DetectionSettings {
+    /// Household-wide config (defaults plus global settings)
+    pub fn config(&self) -> DetectionConfig {
+        self.global.apply(&DetectionConfig::default())
+    }
+
+    /// Whether any per-merchant or per-tag overrides exist
+    pub fn has_overrides(&self) -> bool {
+        !self.merchants.is_empty() || !self.tags.is_empty()
+    }
+
+    /// Config for a merchant whose transactions carry `tag_ids`
+    /// (including ancestor tags)
+    pub fn config_for(&self, merchant: Option<&str>, tag_ids: &[i64]) -> DetectionConfig {
+        let mut config = self.config();
+        for tag in self.tags.iter().filter(|t| tag_ids.contains(&t.tag_id)) {
+            config = tag.overrides.apply(&config);
+        }
+        if let Some(merchant) = merchant {
+            if let Some(m) = self
+                .merchants
+                .iter()
+                .find(|m| m.merchant.eq_ignore_ascii_case(merchant))
+            {
+                config = m.overrides.apply(&config);
+            }
         }
+        config
     }
 }
 
@@ -97,21 +438,49 @@ pub struct DetectionResults {
     pub resumes_detected: usize,
     pub spending_anomalies_detected: usize,
     pub tip_discrepancies_detected: usize,
+    pub trials_detected: usize,
+    /// Trials whose full-price charge posted (their alerts are resolved)
+    pub trials_converted: usize,
+    /// Tracked cancellations verified by a clean billing period
+    pub cancellations_confirmed: usize,
+    /// Tracked cancellations followed by another charge
+    pub charges_after_cancel: usize,
+    /// Unusual amounts, new merchants, unusual activity and duplicate charges
+    pub transaction_anomalies_detected: usize,
+    /// Return windows and warranties closing soon
+    pub warranty_reminders: usize,
+}
+
+/// Saved detection settings, or the defaults if they can't be read
+fn load_settings(db: &Database) -> DetectionSettings {
+    db.get_detection_settings().unwrap_or_else(|e| {
+        warn!("Failed to load detection settings, using defaults: {}", e);
+        DetectionSettings::default()
+    })
 }
 
 /// Main detector that runs all algorithms
 pub struct WasteDetector<'a> {
     db: &'a Database,
     config: DetectionConfig,
+    /// Saved settings, for per-merchant and per-tag overrides
+    settings: DetectionSettings,
     ai: Option<&'a AIClient>,
     orchestrator: Option<&'a AIOrchestrator>,
 }
 
 impl<'a> WasteDetector<'a> {
+    /// Create a detector using the saved detection settings
+    ///
+    /// All constructors without a `config` argument load the saved settings
+    /// (including per-merchant and per-tag overrides). An explicit config
+    /// replaces them entirely.
     pub fn new(db: &'a Database) -> Self {
+        let settings = load_settings(db);
         Self {
             db,
-            config: DetectionConfig::default(),
+            config: settings.config(),
+            settings,
             ai: None,
             orchestrator: None,
         }
@@ -121,15 +490,18 @@ impl<'a> WasteDetector<'a> {
         Self {
             db,
             config,
+            settings: DetectionSettings::default(),
             ai: None,
             orchestrator: None,
         }
     }
 
     pub fn with_ai(db: &'a Database, ai: &'a AIClient) -> Self {
+        let settings = load_settings(db);
         Self {
             db,
-            config: DetectionConfig::default(),
+            config: settings.config(),
+            settings,
             ai: Some(ai),
             orchestrator: None,
         }
@@ -139,6 +511,7 @@ impl<'a> WasteDetector<'a> {
         Self {
             db,
             config,
+            settings: DetectionSettings::default(),
             ai: Some(ai),
             orchestrator: None,
         }
@@ -149,9 +522,11 @@ impl<'a> WasteDetector<'a> {
     /// When the orchestrator is set, the detector will use tool-calling
     /// for richer spending analysis (the AI can query transactions, merchants, etc.)
     pub fn with_orchestrator(db: &'a Database, orchestrator: &'a AIOrchestrator) -> Self {
+        let settings = load_settings(db);
         Self {
             db,
-            config: DetectionConfig::default(),
+            config: settings.config(),
+            settings,
             ai: None,
             orchestrator: Some(orchestrator),
         }
@@ -163,9 +538,11 @@ impl<'a> WasteDetector<'a> {
         ai: &'a AIClient,
         orchestrator: &'a AIOrchestrator,
     ) -> Self {
+        let settings = load_settings(db);
         Self {
             db,
-            config: DetectionConfig::default(),
+            config: settings.config(),
+            settings,
             ai: Some(ai),
             orchestrator: Some(orchestrator),
         }
@@ -181,11 +558,44 @@ impl<'a> WasteDetector<'a> {
         Self {
             db,
             config,
+            settings: DetectionSettings::default(),
             ai: Some(ai),
             orchestrator: Some(orchestrator),
         }
     }
 
+    /// Household-wide config in effect
+    pub fn config(&self) -> &DetectionConfig {
+        &self.config
+    }
+
+    /// Config for one merchant, with its tag and merchant overrides applied
+    fn config_for_merchant(&self, merchant: &str) -> Cow<'_, DetectionConfig> {
+        if !self.settings.has_overrides() {
+            return Cow::Borrowed(&self.config);
+        }
+        let tag_ids = if self.settings.tags.is_empty() {
+            Vec::new()
+        } else {
+            self.db
+                .get_merchant_tag_lineage(merchant)
+                .unwrap_or_else(|e| {
+                    warn!("Failed to load tags for {}: {}", merchant, e);
+                    Vec::new()
+                })
+        };
+        Cow::Owned(self.settings.config_for(Some(merchant), &tag_ids))
+    }
+
+    /// Config for a spending category, with its tag override applied
+    fn config_for_tag(&self, tag_id: i64) -> Cow<'_, DetectionConfig> {
+        if self.settings.tags.iter().any(|t| t.tag_id == tag_id) {
+            Cow::Owned(self.settings.config_for(None, &[tag_id]))
+        } else {
+            Cow::Borrowed(&self.config)
+        }
+    }
+
     /// Run all detection algorithms
     pub async fn detect_all(&self) -> Result<DetectionResults> {
         self.detect_all_with_progress(None).await
@@ -200,6 +610,7 @@ impl<'a> WasteDetector<'a> {
         progress: Option<&ProgressCallback>,
     ) -> Result<DetectionResults> {
         let subscriptions_found = self.identify_subscriptions_with_progress(progress).await?;
+        let (cancellations_confirmed, charges_after_cancel) = self.verify_cancellations()?;
         let auto_cancelled = self.detect_cancelled()?;
         let resumes_detected = self.detect_resumed()?;
         let zombies_detected = self.detect_zombies()?;
@@ -209,10 +620,14 @@ impl<'a> WasteDetector<'a> {
             .detect_spending_anomalies_with_progress(progress)
             .await?;
         let tip_discrepancies_detected = self.detect_tip_discrepancies()?;
+        let transaction_anomalies_detected = self.detect_transaction_anomalies()?;
+        let trials_converted = self.resolve_trials()?;
+        let trials_detected = self.detect_trials()?;
+        let warranty_reminders = self.db.raise_warranty_reminders(Utc::now().date_naive())?;
 
         info!(
-            "Detection complete: {} subscriptions, {} auto-cancelled, {} resumed, {} zombies, {} price increases, {} duplicates, {} spending anomalies, {} tip discrepancies",
-            subscriptions_found, auto_cancelled, resumes_detected, zombies_detected, price_increases_detected, duplicates_detected, spending_anomalies_detected, tip_discrepancies_detected
+            "Detection complete: {} subscriptions, {} cancellations confirmed, {} charged after cancelling, {} auto-cancelled, {} resumed, {} zombies, {} price increases, {} duplicates, {} spending anomalies, {} tip discrepancies, {} unusual transactions, {} trials ({} converted), {} warranty reminders",
+            subscriptions_found, cancellations_confirmed, charges_after_cancel, auto_cancelled, resumes_detected, zombies_detected, price_increases_detected, duplicates_detected, spending_anomalies_detected, tip_discrepancies_detected, transaction_anomalies_detected, trials_detected, trials_converted, warranty_reminders
         );
 
         Ok(DetectionResults {
@@ -224,6 +639,12 @@ impl<'a> WasteDetector<'a> {
             resumes_detected,
             spending_anomalies_detected,
             tip_discrepancies_detected,
+            trials_detected,
+            trials_converted,
+            cancellations_confirmed,
+            charges_after_cancel,
+            transaction_anomalies_detected,
+            warranty_reminders,
         })
     }
 
@@ -254,6 +675,26 @@ impl<'a> WasteDetector<'a> {
         })
     }
 
+    /// Save a detected subscription along with its learned grace window
+    fn save_detected_subscription(
+        &self,
+        merchant: &str,
+        account_id: i64,
+        info: &SubscriptionInfo,
+    ) -> Result<i64> {
+        let id = self.db.upsert_subscription(
+            merchant,
+            Some(account_id),
+            Some(info.amount),
+            Some(info.frequency),
+            Some(info.first_seen),
+            Some(info.last_seen),
+        )?;
+        self.db
+            .set_subscription_grace(id, info.grace_days, info.samples)?;
+        Ok(id)
+    }
+
     /// Identify recurring charges and create/update subscriptions
     async fn identify_subscriptions(&self) -> Result<usize> {
         self.identify_subscriptions_with_progress(None).await
@@ -357,14 +798,7 @@ impl<'a> WasteDetector<'a> {
                 // Cached as subscription - use strict pattern detection
                 // (user confirmed or previously detected)
                 if let Some(sub_info) = detect_subscription_pattern(&txs) {
-                    self.db.upsert_subscription(
-                        &merchant,
-                        Some(account_id),
-                        Some(sub_info.amount),
-                        Some(sub_info.frequency),
-                        Some(sub_info.first_seen),
-                        Some(sub_info.last_seen),
-                    )?;
+                    self.save_detected_subscription(&merchant, account_id, &sub_info)?;
                     count += 1;
                     debug!(
                         "Found subscription (cached): {} (account {}) @ ${:.2}/{:?}",
@@ -375,6 +809,7 @@ impl<'a> WasteDetector<'a> {
             }
 
             // Layer 2: If Ollama available and not cached, classify merchant
+            let config = self.config_for_merchant(&merchant);
             let mut use_relaxed_detection = false;
             if let Some(ollama) = self.ai {
                 // Update progress before Ollama call
@@ -405,7 +840,7 @@ impl<'a> WasteDetector<'a> {
                         }
 
                         // Use relaxed detection if Ollama confidence is high enough
-                        if classification.confidence >= self.config.ollama_confidence_threshold {
+                        if classification.confidence >= config.ollama_confidence_threshold {
                             use_relaxed_detection = true;
                             debug!(
                                 "Using smart detection for {} - Ollama confidence: {:.2} ({})",
@@ -422,20 +857,13 @@ impl<'a> WasteDetector<'a> {
 
             // Layer 3: Pattern-based detection (relaxed or strict)
             let sub_info = if use_relaxed_detection {
-                detect_subscription_pattern_relaxed(&txs, &self.config)
+                detect_subscription_pattern_relaxed(&txs, &config)
             } else {
                 detect_subscription_pattern(&txs)
             };
 
             if let Some(sub_info) = sub_info {
-                self.db.upsert_subscription(
-                    &merchant,
-                    Some(account_id),
-                    Some(sub_info.amount),
-                    Some(sub_info.frequency),
-                    Some(sub_info.first_seen),
-                    Some(sub_info.last_seen),
-                )?;
+                self.save_detected_subscription(&merchant, account_id, &sub_info)?;
                 count += 1;
                 let detection_type = if use_relaxed_detection {
                     "smart"
@@ -462,7 +890,6 @@ impl<'a> WasteDetector<'a> {
         let mut count = 0;
         let now = Utc::now();
         let today = now.date_naive();
-        let threshold = today - Duration::days(self.config.zombie_min_months * 30);
 
         for sub in subscriptions {
             // Skip if not active (excluded, cancelled, or already zombie)
@@ -470,13 +897,16 @@ impl<'a> WasteDetector<'a> {
                 continue;
             }
 
+            let config = self.config_for_merchant(&sub.merchant);
+            let threshold = today - Duration::days(config.zombie_min_months * 30);
+
             // Check if acknowledgment is stale (if configured and acknowledged)
             let is_stale_acknowledgment =
-                if sub.user_acknowledged && self.config.acknowledgment_stale_days > 0 {
+                if sub.user_acknowledged && config.acknowledgment_stale_days > 0 {
                     match sub.acknowledged_at {
                         Some(ack_time) => {
                             let stale_threshold =
-                                now - chrono::Duration::days(self.config.acknowledgment_stale_days);
+                                now - chrono::Duration::days(config.acknowledgment_stale_days);
                             ack_time < stale_threshold
                         }
                         // If acknowledged but no timestamp, treat as fresh (legacy data)
@@ -583,8 +1013,9 @@ impl<'a> WasteDetector<'a> {
             let increase = current_amount - old_amount;
             let increase_percent = (increase / old_amount) * 100.0;
 
-            if increase > self.config.price_increase_absolute
-                || increase_percent > self.config.price_increase_percent
+            let config = self.config_for_merchant(&sub.merchant);
+            if increase > config.price_increase_absolute
+                || increase_percent > config.price_increase_percent
             {
                 let message = format!(
                     "{} increased from ${:.2} to ${:.2} (+{:.1}%)",
@@ -742,17 +1173,13 @@ impl<'a> WasteDetector<'a> {
                 continue;
             }
 
-            let (last_seen, frequency) = match (sub.last_seen, sub.frequency) {
-                (Some(ls), Some(freq)) => (ls, freq),
+            let last_seen = match (sub.last_seen, sub.frequency) {
+                (Some(ls), Some(_)) => ls,
                 _ => continue,
             };
 
             // Calculate expected next charge date with grace period
-            let (interval_days, grace_days) = match frequency {
-                Frequency::Weekly => (7, 3),
-                Frequency::Monthly => (30, self.config.cancellation_grace_days_monthly),
-                Frequency::Yearly => (365, 30),
-            };
+            let (interval_days, grace_days) = self.billing_period(&sub);
 
             let expected_by = last_seen + Duration::days(interval_days + grace_days);
 
@@ -771,6 +1198,123 @@ impl<'a> WasteDetector<'a> {
         Ok(count)
     }
 
+    /// Days between charges and the grace period allowed after a missed charge
+    ///
+    /// A grace window learned from the subscription's own charge history
+    /// widens the default when its charges drift more than usual.
+    fn billing_period(&self, sub: &Subscription) -> (i64, i64) {
+        let frequency = sub.frequency.unwrap_or(Frequency::Monthly);
+        let default_grace = match frequency {
+            Frequency::Monthly => {
+                self.config_for_merchant(&sub.merchant)
+                    .cancellation_grace_days_monthly
+            }
+            other => other.default_grace_days(),
+        };
+        let grace = default_grace.max(sub.grace_days.unwrap_or(0));
+        (frequency.interval_days(), grace)
+    }
+
+    /// Verify tracked cancellations against later charges
+    ///
+    /// A charge after the expected final charge reactivates the subscription
+    /// and raises a `charged_after_cancel` alert. Otherwise the cancellation
+    /// is confirmed once a full billing period (plus grace) has passed since
+    /// the final charge. A credit from the merchant after cancelling is
+    /// recorded as the expected refund.
+    ///
+    /// Returns (confirmed, charged after cancelling).
+    fn verify_cancellations(&self) -> Result<(usize, usize)> {
+        let pending = self
+            .db
+            .list_cancellations(Some(CancellationStatus::Pending))?;
+        if pending.is_empty() {
+            return Ok((0, 0));
+        }
+
+        let transactions = self.db.list_transactions(None, 10000, 0)?;
+        let today = Utc::now().date_naive();
+        let mut confirmed = 0;
+        let mut charged = 0;
+
+        for cancellation in pending {
+            let sub = match self.db.get_subscription(cancellation.subscription_id)? {
+                Some(sub) => sub,
+                None => continue,
+            };
+            let final_charge = cancellation
+                .expected_final_charge
+                .unwrap_or(cancellation.cancelled_on);
+            let merchant_txs: Vec<&Transaction> = transactions
+                .iter()
+                .filter(|tx| {
+                    sub.account_id.is_none_or(|id| id == tx.account_id)
+                        && transaction_merchant(tx) == sub.merchant
+                })
+                .collect();
+
+            if cancellation.expected_refund.is_some()
+                && cancellation.refund_transaction_id.is_none()
+            {
+                if let Some(credit) = merchant_txs
+                    .iter()
+                    .filter(|tx| tx.amount > 0.0 && tx.date >= cancellation.cancelled_on)
+                    .min_by_key(|tx| tx.date)
+                {
+                    self.db.record_cancellation_refund(
+                        cancellation.id,
+                        credit.id,
+                        credit.amount,
+                    )?;
+                    debug!(
+                        "Refund received for cancelled {}: ${:.2}",
+                        sub.merchant, credit.amount
+                    );
+                }
+            }
+
+            let late_charge = merchant_txs
+                .iter()
+                .filter(|tx| tx.amount < 0.0 && tx.date > final_charge)
+                .min_by_key(|tx| tx.date);
+
+            if let Some(tx) = late_charge {
+                let amount = tx.amount.abs();
+                self.db.mark_cancellation_charged(cancellation.id, tx.id)?;
+                self.db.reactivate_subscription(sub.id, tx.date, amount)?;
+
+                let mut message = format!(
+                    "{} charged ${:.2} on {} after you cancelled on {}",
+                    sub.merchant,
+                    amount,
+                    tx.date.format("%B %d, %Y"),
+                    cancellation.cancelled_on.format("%B %d, %Y")
+                );
+                if let Some(number) = &cancellation.confirmation_number {
+                    message.push_str(&format!(" (confirmation {})", number));
+                }
+                self.db.create_alert(
+                    AlertType::ChargedAfterCancel,
+                    Some(sub.id),
+                    Some(&message),
+                )?;
+
+                debug!("Charge after cancellation: {}", sub.merchant);
+                charged += 1;
+                continue;
+            }
+
+            let (interval_days, grace_days) = self.billing_period(&sub);
+            if today > final_charge + Duration::days(interval_days + grace_days) {
+                self.db.confirm_cancellation(cancellation.id)?;
+                debug!("Cancellation confirmed: {}", sub.merchant);
+                confirmed += 1;
+            }
+        }
+
+        Ok((confirmed, charged))
+    }
+
     /// Detect resumed subscriptions
     ///
     /// A subscription is considered resumed when:
@@ -788,11 +1332,22 @@ impl<'a> WasteDetector<'a> {
             }
 
             // Get the cancellation date (when we last saw a charge)
-            let last_seen = match sub.last_seen {
+            let mut last_seen = match sub.last_seen {
                 Some(ls) => ls,
                 None => continue,
             };
 
+            // Tracked cancellations are checked by verify_cancellations until
+            // confirmed; after that the expected final charge isn't a resume
+            if let Some(cancellation) = self.db.get_subscription_cancellation(sub.id)? {
+                if cancellation.status == CancellationStatus::Pending {
+                    continue;
+                }
+                if let Some(final_charge) = cancellation.expected_final_charge {
+                    last_seen = last_seen.max(final_charge);
+                }
+            }
+
             // Find transactions for this merchant after last_seen
             let new_txs: Vec<&Transaction> = transactions
                 .iter()
@@ -849,10 +1404,11 @@ impl<'a> WasteDetector<'a> {
             let actual = tx.amount.abs();
             let diff = actual - expected;
 
-            if diff > self.config.tip_discrepancy_threshold {
+            let merchant = tx.merchant_normalized.as_deref().unwrap_or(&tx.description);
+            if diff > self.config_for_merchant(merchant).tip_discrepancy_threshold {
                 let message = format!(
                     "{} charge of ${:.2} is higher than receipt total of ${:.2} (potential tip: ${:.2})",
-                    tx.merchant_normalized.as_deref().unwrap_or(&tx.description),
+                    merchant,
                     actual,
                     expected,
                     diff
@@ -867,112 +1423,600 @@ impl<'a> WasteDetector<'a> {
         Ok(count)
     }
 
-    /// Detect spending anomalies
+    /// Flag individual charges that stand out from their history
     ///
-    /// Compares current month spending by category against a 3-month rolling baseline.
-    /// Creates alerts when spending increases by >30% or decreases by >40%.
-    async fn detect_spending_anomalies_with_progress(
-        &self,
-        progress: Option<&ProgressCallback>,
-    ) -> Result<usize> {
-        let today = Utc::now().date_naive();
-
-        // Calculate current month period
-        let current_month_start = today.with_day(1).expect("Day 1 always valid");
-
-        // Calculate 3-month baseline period (the 3 months before current month)
-        let baseline_end = current_month_start - Duration::days(1);
-        let baseline_start = baseline_end - Duration::days(90);
+    /// Checks expenses from the last `ANOMALY_LOOKBACK_DAYS` against the
+    /// charges before them:
+    /// - unusual amount: several times the merchant's median charge
+    /// - new merchant: a large first charge, once there's enough history to
+    ///   know the merchant is new
+    /// - unusual activity: a foreign or card-not-present charge on an account
+    ///   where those are rare
+    /// - duplicate charge: the same amount from the same merchant on the same
+    ///   day and account
+    ///
+    /// Thresholds are raised by dismissals of earlier alerts (see
+    /// [`AnomalyTuning`]). Each charge is flagged at most once per reason.
+    fn detect_transaction_anomalies(&self) -> Result<usize> {
+        let transactions = self.db.list_transactions(None, 10000, 0)?;
+        let since = Utc::now().date_naive() - Duration::days(ANOMALY_LOOKBACK_DAYS);
+        let tuning = AnomalyTuning::new(&self.db.get_anomaly_feedback()?);
+        let countries = self.db.get_purchase_countries()?;
 
-        // Get spending by category for current month
-        // (tag_filter=None, expand=false, entity_id=None, card_member=None)
-        let current =
-            self.db
-                .get_spending_summary(current_month_start, today, None, false, None, None)?;
+        let mut first_date_by_account: HashMap<i64, NaiveDate> = HashMap::new();
+        for tx in &transactions {
+            let first = first_date_by_account
+                .entry(tx.account_id)
+                .or_insert(tx.date);
+            *first = (*first).min(tx.date);
+        }
 
-        // Get spending by category for baseline period
-        let baseline =
-            self.db
-                .get_spending_summary(baseline_start, baseline_end, None, false, None, None)?;
+        let mut expenses: Vec<&Transaction> = transactions
+            .iter()
+            .filter(|tx| tx.amount < 0.0 && !tx.archived)
+            .collect();
+        expenses.sort_by_key(|tx| (tx.date, tx.id));
+
+        // Each account's home country is where most of its located charges are
+        let mut country_counts: HashMap<i64, HashMap<&str, usize>> = HashMap::new();
+        for tx in &expenses {
+            if let Some(country) = countries.get(&tx.id) {
+                *country_counts
+                    .entry(tx.account_id)
+                    .or_default()
+                    .entry(country.as_str())
+                    .or_default() += 1;
+            }
+        }
+        let home_country: HashMap<i64, &str> = country_counts
+            .into_iter()
+            .filter_map(|(account_id, counts)| {
+                counts
+                    .into_iter()
+                    .max_by_key(|(country, n)| (*n, std::cmp::Reverse(*country)))
+                    .map(|(country, _)| (account_id, country))
+            })
+            .collect();
 
+        // History seen so far, walking the charges in date order
+        let mut merchant_amounts: HashMap<String, Vec<f64>> = HashMap::new();
+        let mut activity: HashMap<i64, AccountActivity> = HashMap::new();
+        let mut same_day: HashMap<(i64, String, NaiveDate, i64), i64> = HashMap::new();
         let mut count = 0;
 
-        // First pass: identify categories with anomalies (for progress tracking)
-        let anomaly_categories: Vec<_> = current
-            .categories
-            .iter()
-            .filter(|current_cat| {
-                let Some(baseline_cat) = baseline
-                    .categories
-                    .iter()
-                    .find(|c| c.tag_id == current_cat.tag_id)
-                else {
-                    return false;
+        for tx in expenses {
+            let merchant = transaction_merchant(tx);
+            let amount = tx.amount.abs();
+            let foreign_country = countries
+                .get(&tx.id)
+                .filter(|c| home_country.get(&tx.account_id) != Some(&c.as_str()));
+            let card_not_present = tx.payment_method == Some(PaymentMethod::Online);
+            let day_key = (
+                tx.account_id,
+                merchant.clone(),
+                tx.date,
+                (amount * 100.0).round() as i64,
+            );
+
+            if tx.date >= since {
+                let config = self.config_for_merchant(&merchant);
+                let history = merchant_amounts.get(&merchant);
+                let account = activity.get(&tx.account_id).copied().unwrap_or_default();
+                let date = tx.date.format("%B %d, %Y");
+                let anomaly = |anomaly_type, threshold| NewTransactionAnomaly {
+                    transaction_id: tx.id,
+                    related_transaction_id: None,
+                    anomaly_type,
+                    account_id: tx.account_id,
+                    merchant: merchant.clone(),
+                    amount,
+                    transaction_date: tx.date,
+                    baseline_amount: None,
+                    threshold,
+                    detail: None,
                 };
-                let baseline_monthly_avg = baseline_cat.amount.abs() / 3.0;
-                if baseline_monthly_avg < self.config.spending_anomaly_min_baseline {
-                    return false;
+                let mut found = Vec::new();
+
+                if let Some(amounts) = history.filter(|a| a.len() >= ANOMALY_MIN_MERCHANT_HISTORY) {
+                    let usual = median(amounts);
+                    let threshold = usual
+                        * config.unusual_amount_multiplier
+                        * tuning.scale(AlertType::UnusualAmount, &merchant);
+                    if amount >= threshold && amount - usual >= UNUSUAL_AMOUNT_MIN_EXCESS {
+                        found.push((
+                            NewTransactionAnomaly {
+                                baseline_amount: Some(usual),
+                                ..anomaly(AlertType::UnusualAmount, threshold)
+                            },
+                            format!(
+                                "{} charged ${:.2} on {}, {:.1}x your usual ${:.2}",
+                                merchant,
+                                amount,
+                                date,
+                                amount / usual,
+                                usual
+                            ),
+                        ));
+                    }
                 }
-                let current_amount = current_cat.amount.abs();
-                let percent_change = if baseline_monthly_avg > 0.0 {
-                    ((current_amount - baseline_monthly_avg) / baseline_monthly_avg) * 100.0
-                } else {
-                    return false;
-                };
-                let is_increase = percent_change > self.config.spending_increase_threshold;
-                let is_decrease = percent_change < -self.config.spending_decrease_threshold;
-                is_increase || is_decrease
-            })
-            .collect();
-
-        let total_anomalies = anomaly_categories.len() as i64;
-        let mut processed_anomalies = 0i64;
 
-        // Compare each category
-        for current_cat in &current.categories {
-            // Find matching baseline category
-            let baseline_cat = baseline
-                .categories
-                .iter()
-                .find(|c| c.tag_id == current_cat.tag_id);
+                let established = first_date_by_account
+                    .get(&tx.account_id)
+                    .is_some_and(|first| {
+                        tx.date - *first >= Duration::days(NEW_MERCHANT_MIN_HISTORY_DAYS)
+                    });
+                if history.is_none() && established {
+                    let threshold = config.new_merchant_threshold
+                        * tuning.scale(AlertType::NewMerchant, &merchant);
+                    if amount >= threshold {
+                        found.push((
+                            anomaly(AlertType::NewMerchant, threshold),
+                            format!("First charge from {}: ${:.2} on {}", merchant, amount, date),
+                        ));
+                    }
+                }
 
-            let Some(baseline_cat) = baseline_cat else {
-                // No baseline for this category (new this month) - skip
-                continue;
-            };
+                let mut unusual = Vec::new();
+                if let Some(country) = foreign_country {
+                    if account.rarely(account.foreign, account.located) {
+                        unusual.push(country.clone());
+                    }
+                }
+                if card_not_present && account.rarely(account.online, account.with_method) {
+                    unusual.push("card_not_present".to_string());
+                }
+                if !unusual.is_empty() {
+                    let threshold = config.unusual_activity_min_amount
+                        * tuning.scale(AlertType::UnusualActivity, &merchant);
+                    if amount >= threshold {
+                        let reasons = unusual
+                            .iter()
+                            .map(|u| match u.as_str() {
+                                "card_not_present" => "card not present".to_string(),
+                                country => format!("in {}", country),
+                            })
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        found.push((
+                            NewTransactionAnomaly {
+                                detail: Some(unusual.join(",")),
+                                ..anomaly(AlertType::UnusualActivity, threshold)
+                            },
+                            format!(
+                                "{} charged ${:.2} on {} ({}), which is unusual for this account",
+                                merchant, amount, date, reasons
+                            ),
+                        ));
+                    }
+                }
 
-            // Calculate average monthly baseline (divide by 3 months)
-            let baseline_monthly_avg = baseline_cat.amount.abs() / 3.0;
+                if let Some(&earlier_id) = same_day.get(&day_key) {
+                    let threshold = config.duplicate_charge_min_amount
+                        * tuning.scale(AlertType::DuplicateCharge, &merchant);
+                    if amount >= threshold {
+                        found.push((
+                            NewTransactionAnomaly {
+                                related_transaction_id: Some(earlier_id),
+                                ..anomaly(AlertType::DuplicateCharge, threshold)
+                            },
+                            format!("{} charged ${:.2} twice on {}", merchant, amount, date),
+                        ));
+                    }
+                }
 
-            // Skip if baseline is too small
-            if baseline_monthly_avg < self.config.spending_anomaly_min_baseline {
-                continue;
+                for (anomaly, message) in found {
+                    if self
+                        .db
+                        .create_transaction_anomaly(&anomaly, &message)?
+                        .is_some()
+                    {
+                        debug!(
+                            "Transaction anomaly ({}): {}",
+                            anomaly.anomaly_type.as_str(),
+                            message
+                        );
+                        count += 1;
+                    }
+                }
             }
 
-            let current_amount = current_cat.amount.abs();
+            merchant_amounts.entry(merchant).or_default().push(amount);
+            let account = activity.entry(tx.account_id).or_default();
+            if countries.contains_key(&tx.id) {
+                account.located += 1;
+                if foreign_country.is_some() {
+                    account.foreign += 1;
+                }
+            }
+            if tx.payment_method.is_some() {
+                account.with_method += 1;
+                if card_not_present {
+                    account.online += 1;
+                }
+            }
+            same_day.entry(day_key).or_insert(tx.id);
+        }
 
-            // Calculate percent change from baseline
-            let percent_change = if baseline_monthly_avg > 0.0 {
-                ((current_amount - baseline_monthly_avg) / baseline_monthly_avg) * 100.0
-            } else {
-                continue; // Can't calculate change from zero
-            };
+        Ok(count)
+    }
 
-            // Check if change exceeds thresholds
-            let is_increase = percent_change > self.config.spending_increase_threshold;
-            let is_decrease = percent_change < -self.config.spending_decrease_threshold;
+    /// Detect likely free trials and intro prices before they convert
+    ///
+    /// Looks at the first charge from each merchant on each account. It's
+    /// treated as a trial when no full-price charge has followed and it is:
+    /// a $0 authorization, a $1 authorization with a subscription signal,
+    /// well below what similar subscriptions cost, or somewhat below that
+    /// norm for a merchant known for trials.
+    fn detect_trials(&self) -> Result<usize> {
+        let transactions = self.db.list_transactions(None, 10000, 0)?;
+        let today = Utc::now().date_naive();
 
-            if !is_increase && !is_decrease {
-                continue; // Change not significant enough
+        let mut by_account_merchant: HashMap<(i64, String), Vec<&Transaction>> = HashMap::new();
+        for tx in &transactions {
+            if tx.amount > 0.0 {
+                continue; // Skip income/credits, keep $0 authorizations
             }
+            by_account_merchant
+                .entry((tx.account_id, transaction_merchant(tx)))
+                .or_default()
+                .push(tx);
+        }
 
-            // Update progress before Ollama call
-            processed_anomalies += 1;
-            if let Some(cb) = progress {
-                if self.ai.is_some() {
-                    cb("analyzing_spending", processed_anomalies, total_anomalies);
-                }
-            }
+        let mut prices_by_tag: Option<HashMap<i64, Vec<(String, f64)>>> = None;
+        let mut subscription_tags: Option<HashSet<i64>> = None;
+        let mut count = 0;
+
+        for ((account_id, merchant), txs) in by_account_merchant {
+            let first = match txs.iter().min_by_key(|tx| (tx.date, tx.id)) {
+                Some(tx) => *tx,
+                None => continue,
+            };
+            let last_date = txs.iter().map(|tx| tx.date).max().unwrap_or(first.date);
+            let trial_amount = first.amount.abs();
+
+            // A full-price charge already posted, so this is a regular merchant
+            if txs
+                .iter()
+                .any(|tx| is_full_price_charge(tx.amount, trial_amount))
+            {
+                continue;
+            }
+
+            let known_trial_days = known_trial_days(&merchant);
+            // $1 is also an ordinary one-off amount, so it needs a sign the
+            // merchant sells subscriptions
+            let is_authorization = is_zero_authorization(trial_amount)
+                || (is_dollar_authorization(trial_amount)
+                    && (known_trial_days.is_some()
+                        || self.is_subscription_charge(
+                            first.id,
+                            &merchant,
+                            &mut subscription_tags,
+                        )?));
+
+            // Skip trials that would have converted (or lapsed) long ago
+            let expected_conversion = if is_authorization {
+                first.date + Duration::days(known_trial_days.unwrap_or(DEFAULT_TRIAL_DAYS))
+            } else {
+                last_date + Duration::days(INTRO_PERIOD_DAYS)
+            };
+            if expected_conversion + Duration::days(TRIAL_LAPSE_GRACE_DAYS) < today {
+                continue;
+            }
+
+            // Merchants the user (or Ollama) marked as retail don't have trials
+            if let Ok(Some(false)) = self.db.get_merchant_subscription_cache(&merchant) {
+                continue;
+            }
+
+            let prices = match &prices_by_tag {
+                Some(prices) => prices,
+                None => prices_by_tag.insert(self.subscription_prices_by_tag()?),
+            };
+            let norm = self.category_norm(first.id, &merchant, prices)?;
+
+            let signal = if is_authorization {
+                TrialSignal::Authorization
+            } else if norm.is_some_and(|n| trial_amount < n * INTRO_PRICE_RATIO) {
+                TrialSignal::IntroPrice
+            } else if known_trial_days.is_some()
+                && norm.is_some_and(|n| trial_amount < n * KNOWN_MERCHANT_INTRO_RATIO)
+            {
+                TrialSignal::KnownMerchant
+            } else {
+                continue;
+            };
+
+            let mut message = match signal {
+                TrialSignal::Authorization => format!(
+                    "{} looks like a free trial (${:.2} authorization on {})",
+                    merchant,
+                    trial_amount,
+                    first.date.format("%B %d, %Y")
+                ),
+                _ => format!(
+                    "{} looks like an intro price (${:.2} on {})",
+                    merchant,
+                    trial_amount,
+                    first.date.format("%B %d, %Y")
+                ),
+            };
+            message.push_str(&format!(
+                "; full price expected around {}",
+                expected_conversion.format("%B %d, %Y")
+            ));
+            if let Some(amount) = norm {
+                message.push_str(&format!(" (about ${:.2})", amount));
+            }
+
+            let created = self.db.create_trial_forecast(
+                &NewTrialForecast {
+                    merchant: merchant.clone(),
+                    account_id,
+                    transaction_id: first.id,
+                    signal,
+                    trial_amount,
+                    started_on: first.date,
+                    expected_conversion,
+                    expected_amount: norm,
+                },
+                &message,
+            )?;
+            if created.is_some() {
+                debug!("Detected trial ({}): {}", signal.as_str(), merchant);
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Resolve pending trials whose outcome is known
+    ///
+    /// A trial converts when a charge above the trial amount posts from the
+    /// same merchant on the same account, and lapses when none has posted
+    /// `TRIAL_LAPSE_GRACE_DAYS` after the expected conversion. Either way its
+    /// alert is dismissed. Returns the number of trials that converted.
+    fn resolve_trials(&self) -> Result<usize> {
+        let pending = self.db.list_trial_forecasts(Some(TrialStatus::Pending))?;
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let transactions = self.db.list_transactions(None, 10000, 0)?;
+        let today = Utc::now().date_naive();
+        let mut converted = 0;
+
+        for trial in pending {
+            let conversion = transactions
+                .iter()
+                .filter(|tx| {
+                    tx.id != trial.transaction_id
+                        && tx.account_id == trial.account_id
+                        && tx.date >= trial.started_on
+                        && is_full_price_charge(tx.amount, trial.trial_amount)
+                        && transaction_merchant(tx) == trial.merchant
+                })
+                .min_by_key(|tx| tx.date);
+
+            if let Some(tx) = conversion {
+                self.db
+                    .resolve_trial_forecast(trial.id, TrialStatus::Converted, Some(tx.id))?;
+                debug!(
+                    "Trial converted: {} charged ${:.2} on {}",
+                    trial.merchant,
+                    tx.amount.abs(),
+                    tx.date
+                );
+                converted += 1;
+            } else if today > trial.expected_conversion + Duration::days(TRIAL_LAPSE_GRACE_DAYS) {
+                self.db
+                    .resolve_trial_forecast(trial.id, TrialStatus::Lapsed, None)?;
+                debug!("Trial lapsed without a charge: {}", trial.merchant);
+            }
+        }
+
+        Ok(converted)
+    }
+
+    /// Monthly prices of active subscriptions, keyed by each tag in their lineage
+    fn subscription_prices_by_tag(&self) -> Result<HashMap<i64, Vec<(String, f64)>>> {
+        let mut prices: HashMap<i64, Vec<(String, f64)>> = HashMap::new();
+        for sub in self.db.list_subscriptions(None)? {
+            if sub.status != SubscriptionStatus::Active {
+                continue;
+            }
+            let (amount, frequency) = match (sub.amount, sub.frequency) {
+                (Some(a), Some(f)) => (a.abs(), f),
+                _ => continue,
+            };
+            let monthly = frequency.monthly_amount(amount);
+            for tag_id in self.db.get_merchant_tag_lineage(&sub.merchant)? {
+                prices
+                    .entry(tag_id)
+                    .or_default()
+                    .push((sub.merchant.clone(), monthly));
+            }
+        }
+        Ok(prices)
+    }
+
+    /// Whether the merchant is cached as a subscription or the charge is
+    /// tagged under Subscriptions
+    fn is_subscription_charge(
+        &self,
+        transaction_id: i64,
+        merchant: &str,
+        subscription_tags: &mut Option<HashSet<i64>>,
+    ) -> Result<bool> {
+        if let Ok(Some(true)) = self.db.get_merchant_subscription_cache(merchant) {
+            return Ok(true);
+        }
+        let tags = match subscription_tags {
+            Some(tags) => tags,
+            None => {
+                let mut tags: HashSet<i64> = self
+                    .db
+                    .get_subscription_categories()?
+                    .into_iter()
+                    .map(|tag| tag.id)
+                    .collect();
+                if let Some(root) = self.db.get_tag_by_path("Subscriptions")? {
+                    tags.insert(root.id);
+                }
+                subscription_tags.insert(tags)
+            }
+        };
+        Ok(self
+            .db
+            .get_transaction_tags(transaction_id)?
+            .iter()
+            .any(|tag| tags.contains(&tag.tag_id)))
+    }
+
+    /// Median monthly price of other subscriptions in a transaction's category
+    fn category_norm(
+        &self,
+        transaction_id: i64,
+        merchant: &str,
+        prices_by_tag: &HashMap<i64, Vec<(String, f64)>>,
+    ) -> Result<Option<f64>> {
+        for tag in self.db.get_transaction_tags(transaction_id)? {
+            let prices: Vec<f64> = prices_by_tag
+                .get(&tag.tag_id)
+                .into_iter()
+                .flatten()
+                .filter(|(m, _)| !m.eq_ignore_ascii_case(merchant))
+                .map(|(_, price)| *price)
+                .collect();
+            if prices.len() < MIN_NORM_SUBSCRIPTIONS {
+                continue;
+            }
+            return Ok(Some(median(&prices)));
+        }
+        Ok(None)
+    }
+
+    /// Detect spending anomalies
+    ///
+    /// Compares current month spending by category against a 3-month rolling baseline.
+    /// Creates alerts when spending increases by >30% or decreases by >40%.
+    async fn detect_spending_anomalies_with_progress(
+        &self,
+        progress: Option<&ProgressCallback>,
+    ) -> Result<usize> {
+        let today = Utc::now().date_naive();
+
+        // Calculate current month period
+        let current_month_start = today.with_day(1).expect("Day 1 always valid");
+
+        // Calculate 3-month baseline period (the 3 months before current month)
+        let baseline_end = current_month_start - Duration::days(1);
+        let baseline_start = baseline_end - Duration::days(90);
+
+        // Get spending by category for current month
+        // (tag_filter=None, expand=false, entity_id=None, card_member=None)
+        let current = self.db.get_spending_summary(
+            current_month_start,
+            today,
+            None,
+            false,
+            None,
+            None,
+            false,
+        )?;
+
+        // Get spending by category for baseline period
+        let baseline = self.db.get_spending_summary(
+            baseline_start,
+            baseline_end,
+            None,
+            false,
+            None,
+            None,
+            false,
+        )?;
+
+        let mut count = 0;
+
+        // First pass: identify categories with anomalies (for progress tracking)
+        let anomaly_categories: Vec<_> = current
+            .categories
+            .iter()
+            .filter(|current_cat| {
+                let Some(baseline_cat) = baseline
+                    .categories
+                    .iter()
+                    .find(|c| c.tag_id == current_cat.tag_id)
+                else {
+                    return false;
+                };
+                let config = self.config_for_tag(current_cat.tag_id);
+                let baseline_monthly_avg = baseline_cat.amount.abs() / 3.0;
+                if baseline_monthly_avg < config.spending_anomaly_min_baseline {
+                    return false;
+                }
+                let current_amount = current_cat.amount.abs();
+                let percent_change = if baseline_monthly_avg > 0.0 {
+                    ((current_amount - baseline_monthly_avg) / baseline_monthly_avg) * 100.0
+                } else {
+                    return false;
+                };
+                let is_increase = percent_change > config.spending_increase_threshold;
+                let is_decrease = percent_change < -config.spending_decrease_threshold;
+                is_increase || is_decrease
+            })
+            .collect();
+
+        let total_anomalies = anomaly_categories.len() as i64;
+        let mut processed_anomalies = 0i64;
+
+        // Compare each category
+        for current_cat in &current.categories {
+            // Find matching baseline category
+            let baseline_cat = baseline
+                .categories
+                .iter()
+                .find(|c| c.tag_id == current_cat.tag_id);
+
+            let Some(baseline_cat) = baseline_cat else {
+                // No baseline for this category (new this month) - skip
+                continue;
+            };
+
+            // Calculate average monthly baseline (divide by 3 months)
+            let baseline_monthly_avg = baseline_cat.amount.abs() / 3.0;
+            let config = self.config_for_tag(current_cat.tag_id);
+
+            // Skip if baseline is too small
+            if baseline_monthly_avg < config.spending_anomaly_min_baseline {
+                continue;
+            }
+
+            let current_amount = current_cat.amount.abs();
+
+            // Calculate percent change from baseline
+            let percent_change = if baseline_monthly_avg > 0.0 {
+                ((current_amount - baseline_monthly_avg) / baseline_monthly_avg) * 100.0
+            } else {
+                continue; // Can't calculate change from zero
+            };
+
+            // Check if change exceeds thresholds
+            let is_increase = percent_change > config.spending_increase_threshold;
+            let is_decrease = percent_change < -config.spending_decrease_threshold;
+
+            if !is_increase && !is_decrease {
+                continue; // Change not significant enough
+            }
+
+            // Update progress before Ollama call
+            processed_anomalies += 1;
+            if let Some(cb) = progress {
+                if self.ai.is_some() {
+                    cb("analyzing_spending", processed_anomalies, total_anomalies);
+                }
+            }
 
             debug!(
                 "Spending anomaly detected: {} {} by {:.1}% (${:.2} -> ${:.2})",
@@ -1346,12 +2390,231 @@ fn parse_orchestrator_explanation(response: &str) -> (String, Vec<String>) {
     (summary, reasons)
 }
 
+/// Days until a free trial converts, when the merchant's trial length is unknown
+const DEFAULT_TRIAL_DAYS: i64 = 30;
+/// Days until an intro price steps up after the last intro charge
+const INTRO_PERIOD_DAYS: i64 = 30;
+/// Days past the expected conversion before a trial with no charge is considered lapsed
+const TRIAL_LAPSE_GRACE_DAYS: i64 = 14;
+/// A first charge below this fraction of the category norm looks like an intro price
+const INTRO_PRICE_RATIO: f64 = 0.5;
+/// Lower bar for merchants known for trials
+const KNOWN_MERCHANT_INTRO_RATIO: f64 = 0.8;
+/// Subscriptions needed in a category before its norm is trusted
+const MIN_NORM_SUBSCRIPTIONS: usize = 2;
+
+/// Merchants known for free trials, with their usual trial length in days
+const KNOWN_TRIAL_MERCHANTS: &[(&str, i64)] = &[
+    ("amazon prime", 30),
+    ("apple tv", 7),
+    ("audible", 30),
+    ("disney", 7),
+    ("duolingo", 14),
+    ("headspace", 14),
+    ("hellofresh", 7),
+    ("hulu", 30),
+    ("kindle unlimited", 30),
+    ("max", 7),
+    ("paramount", 7),
+    ("peacock", 7),
+    ("siriusxm", 90),
+    ("spotify", 30),
+    ("youtube premium", 30),
+];
+
+/// Usual trial length for a merchant known for free trials
+fn known_trial_days(merchant: &str) -> Option<i64> {
+    let words = merchant
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    KNOWN_TRIAL_MERCHANTS
+        .iter()
+        .find(|(name, _)| words == *name || words.starts_with(&format!("{} ", name)))
+        .map(|(_, days)| *days)
+}
+
+/// A $0 card authorization
+fn is_zero_authorization(amount: f64) -> bool {
+    amount.abs() < 0.01
+}
+
+/// A $1 charge, the other usual trial authorization amount
+fn is_dollar_authorization(amount: f64) -> bool {
+    (amount.abs() - 1.0).abs() < 0.01
+}
+
+/// An expense clearly above the trial amount (and above an authorization)
+fn is_full_price_charge(amount: f64, trial_amount: f64) -> bool {
+    amount < 0.0 && amount.abs() > (trial_amount + 0.01).max(1.01)
+}
+
+/// Merchant a transaction is grouped under for detection
+fn transaction_merchant(tx: &Transaction) -> String {
+    tx.merchant_normalized
+        .clone()
+        .unwrap_or_else(|| normalize_merchant(&tx.description))
+}
+
+/// How far back transaction anomalies are checked (days)
+const ANOMALY_LOOKBACK_DAYS: i64 = 30;
+/// Prior charges needed before a merchant has a usual amount
+const ANOMALY_MIN_MERCHANT_HISTORY: usize = 3;
+/// An unusual amount must also be this much above the usual (dollars)
+const UNUSUAL_AMOUNT_MIN_EXCESS: f64 = 20.0;
+/// History an account needs before a merchant counts as new (days)
+const NEW_MERCHANT_MIN_HISTORY_DAYS: i64 = 90;
+/// Prior charges an account needs before foreign or online activity is unusual
+const ACTIVITY_MIN_HISTORY: usize = 10;
+/// Foreign or online charges are unusual below this share of an account's charges
+const ACTIVITY_RARE_SHARE: f64 = 0.1;
+/// Threshold increase per net dismissal of an anomaly type
+const ANOMALY_DISMISSAL_STEP: f64 = 0.25;
+/// Net dismissals of a type that count (caps the increase at 3x)
+const ANOMALY_MAX_DISMISSALS: i64 = 8;
+/// Threshold increase per net dismissal at the same merchant
+const ANOMALY_MERCHANT_DISMISSAL_STEP: f64 = 1.0;
+/// Net dismissals at a merchant that count
+const ANOMALY_MAX_MERCHANT_DISMISSALS: i64 = 3;
+
+/// An account's charges so far, for spotting unusual activity
+#[derive(Debug, Default, Clone, Copy)]
+struct AccountActivity {
+    /// Charges with a known purchase country
+    located: usize,
+    /// Located charges outside the account's home country
+    foreign: usize,
+    /// Charges with a known payment method
+    with_method: usize,
+    /// Card-not-present (online) charges
+    online: usize,
+}
+
+impl AccountActivity {
+    /// Whether `matching` of `total` charges is rare enough to flag another
+    fn rarely(&self, matching: usize, total: usize) -> bool {
+        total >= ACTIVITY_MIN_HISTORY && (matching as f64) < total as f64 * ACTIVITY_RARE_SHARE
+    }
+}
+
+/// Threshold scaling learned from feedback on anomaly alerts
+///
+/// Each net dismissal of an anomaly type (dismissals and "not helpful"
+/// ratings minus "helpful" ratings) raises that type's thresholds by
+/// `ANOMALY_DISMISSAL_STEP`, and each net dismissal at a merchant raises
+/// them for that merchant by a further `ANOMALY_MERCHANT_DISMISSAL_STEP`.
+#[derive(Debug, Default)]
+struct AnomalyTuning {
+    net_dismissals: HashMap<AlertType, i64>,
+    merchant_dismissals: HashMap<(AlertType, String), i64>,
+}
+
+impl AnomalyTuning {
+    fn new(feedback: &[AnomalyFeedback]) -> Self {
+        let mut tuning = Self::default();
+        for f in feedback {
+            let net = f.dismissed - f.helpful;
+            *tuning.net_dismissals.entry(f.anomaly_type).or_default() += net;
+            *tuning
+                .merchant_dismissals
+                .entry((f.anomaly_type, f.merchant.clone()))
+                .or_default() += net;
+        }
+        tuning
+    }
+
+    /// Multiplier for an anomaly type's threshold at a merchant
+    fn scale(&self, anomaly_type: AlertType, merchant: &str) -> f64 {
+        let global = self
+            .net_dismissals
+            .get(&anomaly_type)
+            .copied()
+            .unwrap_or(0)
+            .clamp(0, ANOMALY_MAX_DISMISSALS);
+        let local = self
+            .merchant_dismissals
+            .get(&(anomaly_type, merchant.to_string()))
+            .copied()
+            .unwrap_or(0)
+            .clamp(0, ANOMALY_MAX_MERCHANT_DISMISSALS);
+        1.0 + global as f64 * ANOMALY_DISMISSAL_STEP
+            + local as f64 * ANOMALY_MERCHANT_DISMISSAL_STEP
+    }
+}
+
 /// Info about a detected subscription pattern
 struct SubscriptionInfo {
     amount: f64,
     frequency: Frequency,
     first_seen: NaiveDate,
     last_seen: NaiveDate,
+    /// Grace window learned from how far the charges drifted
+    grace_days: i64,
+    /// Intervals the pattern was learned from
+    samples: usize,
+}
+
+/// Named billing cadences with their strict and relaxed tolerances (days)
+const CADENCES: [(Frequency, f64, f64); 6] = [
+    (Frequency::Weekly, 3.0, 3.0),
+    (Frequency::Biweekly, 3.0, 4.0),
+    (Frequency::Monthly, 7.0, 10.0),
+    (Frequency::Quarterly, 10.0, 15.0),
+    (Frequency::Semiannual, 15.0, 20.0),
+    (Frequency::Yearly, 30.0, 45.0),
+];
+
+/// Shortest cadence that isn't a named one; anything more frequent is
+/// errands, not a subscription
+const MIN_CUSTOM_INTERVAL_DAYS: f64 = 10.0;
+
+/// Longest interval between charges of a subscription
+const MAX_INTERVAL_DAYS: f64 = 400.0;
+
+/// Days a charge may post after its billing date (processing, weekends)
+const POSTING_DELAY_DAYS: i64 = 2;
+
+/// Match the typical interval between charges to a cadence
+///
+/// Returns the frequency, its expected interval and the tolerance around it.
+/// Intervals that fit no named cadence become `EveryDays` with a tolerance of
+/// 10% (15% relaxed).
+fn classify_interval(typical: f64, relaxed: bool) -> Option<(Frequency, f64, f64)> {
+    let named = CADENCES
+        .iter()
+        .map(|&(frequency, strict, loose)| {
+            let tolerance = if relaxed { loose } else { strict };
+            (frequency, frequency.interval_days() as f64, tolerance)
+        })
+        .filter(|(_, expected, tolerance)| (typical - expected).abs() <= *tolerance)
+        .min_by(|a, b| (typical - a.1).abs().total_cmp(&(typical - b.1).abs()));
+    if named.is_some() {
+        return named;
+    }
+
+    if !(MIN_CUSTOM_INTERVAL_DAYS..=MAX_INTERVAL_DAYS).contains(&typical) {
+        return None;
+    }
+    let days = typical.round();
+    let share = if relaxed { 0.15 } else { 0.10 };
+    Some((
+        Frequency::EveryDays(days as u32),
+        days,
+        (days * share).max(2.0),
+    ))
+}
+
+/// Grace window learned from a charge history: the furthest an on-cadence
+/// charge drifted from its expected date, plus posting delay
+fn learned_grace_days(intervals: &[i64], expected: f64, tolerance: f64) -> i64 {
+    let drift = intervals
+        .iter()
+        .map(|&interval| (interval as f64 - expected).abs())
+        .filter(|drift| *drift <= tolerance)
+        .fold(0.0, f64::max);
+    drift.ceil() as i64 + POSTING_DELAY_DAYS
 }
 
 /// Check if transactions have similar raw descriptions (likely same merchant).
@@ -1413,7 +2676,8 @@ fn descriptions_are_similar(transactions: &[&Transaction]) -> bool {
 /// A subscription is characterized by:
 /// 1. Similar raw descriptions (same merchant, not just same normalized name)
 /// 2. Consistent amounts (within 5% of median, allowing for small price changes)
-/// 3. Regular intervals that match a known cadence (weekly, monthly, yearly)
+/// 3. Regular intervals that match a cadence (weekly, biweekly, monthly,
+///    quarterly, semiannual, yearly, or any fixed number of days)
 /// 4. At least 3 transactions to establish a pattern
 fn detect_subscription_pattern(transactions: &[&Transaction]) -> Option<SubscriptionInfo> {
     // Need at least 3 transactions to establish a reliable pattern
@@ -1465,18 +2729,10 @@ fn detect_subscription_pattern(transactions: &[&Transaction]) -> Option<Subscrip
         return None;
     }
 
-    // Determine the expected frequency based on average interval
-    let avg_interval = intervals.iter().sum::<i64>() as f64 / intervals.len() as f64;
-
-    let (frequency, expected_interval, tolerance) = if avg_interval < 10.0 {
-        (Frequency::Weekly, 7.0, 3.0) // Weekly: expect ~7 days, allow ±3 days
-    } else if avg_interval < 45.0 {
-        (Frequency::Monthly, 30.0, 7.0) // Monthly: expect ~30 days, allow ±7 days
-    } else if avg_interval < 400.0 {
-        (Frequency::Yearly, 365.0, 30.0) // Yearly: expect ~365 days, allow ±30 days
-    } else {
-        return None; // Interval too long to be a subscription
-    };
+    // Determine the expected frequency from the median interval, so one
+    // skipped or doubled charge doesn't shift the cadence
+    let typical_interval = median(&intervals.iter().map(|&i| i as f64).collect::<Vec<_>>());
+    let (frequency, expected_interval, tolerance) = classify_interval(typical_interval, false)?;
 
     // Verify that most intervals are consistent with the expected frequency
     // At least 70% of intervals should fall within tolerance of the expected interval
@@ -1502,6 +2758,8 @@ fn detect_subscription_pattern(transactions: &[&Transaction]) -> Option<Subscrip
         frequency,
         first_seen,
         last_seen,
+        grace_days: learned_grace_days(&intervals, expected_interval, tolerance),
+        samples: intervals.len(),
     })
 }
 
@@ -1565,18 +2823,10 @@ fn detect_subscription_pattern_relaxed(
         return None;
     }
 
-    // Determine the expected frequency based on average interval
-    let avg_interval = intervals.iter().sum::<i64>() as f64 / intervals.len() as f64;
-
-    let (frequency, expected_interval, tolerance) = if avg_interval < 10.0 {
-        (Frequency::Weekly, 7.0, 3.0)
-    } else if avg_interval < 45.0 {
-        (Frequency::Monthly, 30.0, 10.0) // Slightly more tolerance for monthly
-    } else if avg_interval < 400.0 {
-        (Frequency::Yearly, 365.0, 45.0) // More tolerance for yearly
-    } else {
-        return None;
-    };
+    // Determine the expected frequency from the median interval, with
+    // relaxed tolerances around each cadence
+    let typical_interval = median(&intervals.iter().map(|&i| i as f64).collect::<Vec<_>>());
+    let (frequency, expected_interval, tolerance) = classify_interval(typical_interval, true)?;
 
     // Use relaxed interval consistency threshold (e.g., 50% vs strict's 70%)
     let consistent_interval_count = intervals
@@ -1599,6 +2849,8 @@ fn detect_subscription_pattern_relaxed(
         frequency,
         first_seen,
         last_seen,
+        grace_days: learned_grace_days(&intervals, expected_interval, tolerance),
+        samples: intervals.len(),
     })
 }
 
@@ -2119,6 +3371,118 @@ mod tests {
         );
     }
 
+    /// Charges of one merchant on the given days after a start 400 days ago
+    fn cadence_test_txs(description: &str, amount: f64, day_offsets: &[i64]) -> Vec<Transaction> {
+        use crate::models::TransactionSource;
+
+        let base_date = Utc::now().date_naive() - Duration::days(400);
+        day_offsets
+            .iter()
+            .enumerate()
+            .map(|(i, offset)| Transaction {
+                id: i as i64 + 1,
+                account_id: 1,
+                date: base_date + Duration::days(*offset),
+                description: description.to_string(),
+                amount,
+                category: None,
+                import_hash: format!("cadence_{}", i),
+                merchant_normalized: None,
+                archived: false,
+                purchase_location_id: None,
+                vendor_location_id: None,
+                trip_id: None,
+                source: TransactionSource::Import,
+                expected_amount: None,
+                original_data: None,
+                import_format: None,
+                card_member: None,
+                payment_method: None,
+                created_at: Utc::now(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_subscription_pattern_cadences() {
+        let cases: [(&str, &[i64], Option<Frequency>); 8] = [
+            (
+                "DOG WALKER",
+                &[0, 14, 28, 42, 56],
+                Some(Frequency::Biweekly),
+            ),
+            ("STATE FARM", &[0, 90, 182, 274], Some(Frequency::Quarterly)),
+            (
+                "DMV REGISTRATION",
+                &[0, 181, 365],
+                Some(Frequency::Semiannual),
+            ),
+            ("AMAZON PRIME", &[0, 365], None), // Too few charges
+            (
+                "PEST CONTROL",
+                &[0, 45, 90, 136],
+                Some(Frequency::EveryDays(45)),
+            ),
+            // One skipped month doesn't change the cadence
+            ("GYM", &[0, 31, 61, 92, 153], Some(Frequency::Monthly)),
+            ("COSTCO MEMBERSHIP", &[0, 365, 731], Some(Frequency::Yearly)),
+            // Regular amount, irregular timing: errands, not a subscription
+            ("CAR WASH", &[0, 20, 65, 80], None),
+        ];
+
+        for (description, offsets, expected) in cases {
+            let transactions = cadence_test_txs(description, -49.99, offsets);
+            let refs: Vec<&Transaction> = transactions.iter().collect();
+            let detected = detect_subscription_pattern(&refs).map(|info| info.frequency);
+            assert_eq!(detected, expected, "{}", description);
+        }
+    }
+
+    #[test]
+    fn test_learned_grace_days() {
+        // Charges that land on the day only get the posting delay
+        assert_eq!(learned_grace_days(&[30, 30, 30], 30.0, 7.0), 2);
+        // The furthest on-cadence drift widens the window
+        assert_eq!(learned_grace_days(&[28, 35, 30], 30.0, 7.0), 7);
+        // A skipped month is off-cadence and doesn't count as drift
+        assert_eq!(learned_grace_days(&[30, 61, 31], 30.0, 7.0), 3);
+
+        let transactions = cadence_test_txs("STATE FARM", -310.0, &[0, 85, 182, 274]);
+        let refs: Vec<&Transaction> = transactions.iter().collect();
+        let info = detect_subscription_pattern(&refs).unwrap();
+        assert_eq!(info.frequency, Frequency::Quarterly);
+        assert_eq!(info.grace_days, 8);
+        assert_eq!(info.samples, 3);
+    }
+
+    #[tokio::test]
+    async fn test_learned_grace_widens_cancellation_window() {
+        let db = Database::in_memory().unwrap();
+        let account_id = db
+            .upsert_account("Test Account", crate::models::Bank::Chase, None)
+            .unwrap();
+
+        // A monthly charge that drifts up to 7 days, last seen 38 days ago:
+        // past the default 30 + 7 days, inside the learned 30 + 9
+        for days_ago in [128, 98, 61, 38] {
+            insert_trial_test_tx(&db, account_id, "CITY WATER", -64.00, days_ago);
+        }
+
+        let detector = WasteDetector::new(&db);
+        detector.detect_all().await.unwrap();
+        let sub = db.list_subscriptions(None).unwrap().pop().unwrap();
+        assert_eq!(sub.frequency, Some(Frequency::Monthly));
+        assert_eq!(sub.grace_days, Some(9));
+
+        db.acknowledge_subscription(sub.id).unwrap();
+        let results = detector.detect_all().await.unwrap();
+        assert_eq!(results.auto_cancelled, 0);
+        assert_eq!(
+            db.get_subscription(sub.id).unwrap().unwrap().status,
+            SubscriptionStatus::Active
+        );
+    }
+
     #[test]
     fn test_detection_config_smart_defaults() {
         let config = DetectionConfig::default();
@@ -2181,10 +3545,483 @@ mod tests {
             .iter()
             .find(|a| a.alert_type == AlertType::TipDiscrepancy)
             .expect("Should have created a tip discrepancy alert");
-        
+
         assert!(tip_alert.message.as_ref().unwrap().contains("RESTO BAR"));
         assert!(tip_alert.message.as_ref().unwrap().contains("$55.00"));
         assert!(tip_alert.message.as_ref().unwrap().contains("$45.00"));
         assert!(tip_alert.message.as_ref().unwrap().contains("potential tip: $10.00"));
     }
+
+    #[test]
+    fn test_detection_overrides_set_and_unset() {
+        let mut overrides = DetectionOverrides::default();
+        overrides.set("price_increase_percent", "10").unwrap();
+        overrides.set("zombie_min_months", "6").unwrap();
+        assert_eq!(overrides.price_increase_percent, Some(10.0));
+        assert_eq!(overrides.zombie_min_months, Some(6));
+
+        // Unknown keys, bad numbers and out-of-range values are rejected
+        assert!(overrides.set("no_such_setting", "1").is_err());
+        assert!(overrides.set("zombie_min_months", "soon").is_err());
+        assert!(overrides.set("ollama_confidence_threshold", "1.5").is_err());
+        assert!(overrides.set("zombie_min_months", "2.5").is_err());
+
+        assert!(overrides.unset("zombie_min_months").unwrap());
+        assert!(!overrides.unset("zombie_min_months").unwrap());
+        assert!(overrides.unset("no_such_setting").is_err());
+
+        let config = overrides.apply(&DetectionConfig::default());
+        assert_eq!(config.price_increase_percent, 10.0);
+        assert_eq!(config.zombie_min_months, 3);
+    }
+
+    #[test]
+    fn test_detection_settings_precedence() {
+        let settings = DetectionSettings {
+            global: DetectionOverrides {
+                price_increase_percent: Some(8.0),
+                zombie_min_months: Some(4),
+                ..Default::default()
+            },
+            tags: vec![TagDetectionOverride {
+                tag_id: 7,
+                tag: "Utilities".to_string(),
+                overrides: DetectionOverrides {
+                    price_increase_percent: Some(10.0),
+                    price_increase_absolute: Some(50.0),
+                    ..Default::default()
+                },
+            }],
+            merchants: vec![MerchantDetectionOverride {
+                merchant: "Power Co".to_string(),
+                overrides: DetectionOverrides {
+                    price_increase_percent: Some(20.0),
+                    ..Default::default()
+                },
+            }],
+        };
+
+        let household = settings.config_for(Some("Netflix"), &[]);
+        assert_eq!(household.price_increase_percent, 8.0);
+        assert_eq!(household.price_increase_absolute, 1.0);
+        assert_eq!(household.zombie_min_months, 4);
+
+        let utility = settings.config_for(Some("Water Co"), &[7]);
+        assert_eq!(utility.price_increase_percent, 10.0);
+        assert_eq!(utility.price_increase_absolute, 50.0);
+        assert_eq!(utility.zombie_min_months, 4);
+
+        // Merchant beats tag, matched case-insensitively
+        let power = settings.config_for(Some("POWER CO"), &[7]);
+        assert_eq!(power.price_increase_percent, 20.0);
+        assert_eq!(power.price_increase_absolute, 50.0);
+    }
+
+    #[tokio::test]
+    async fn test_merchant_override_applies_to_detection() {
+        use crate::db::DetectionScope;
+        use chrono::Duration;
+
+        let db = Database::in_memory().unwrap();
+
+        // Last seen 45 days ago: past the default 30 + 7 day grace window
+        let last_seen = Utc::now().date_naive() - Duration::days(45);
+        let sub_id = db
+            .upsert_subscription(
+                "SLOW BILLER",
+                None,
+                Some(9.99),
+                Some(Frequency::Monthly),
+                Some(last_seen - Duration::days(90)),
+                Some(last_seen),
+            )
+            .unwrap();
+        db.acknowledge_subscription(sub_id).unwrap();
+
+        // This merchant bills late, so give it a longer grace period
+        let overrides = DetectionOverrides {
+            cancellation_grace_days_monthly: Some(30),
+            ..Default::default()
+        };
+        db.set_detection_overrides(
+            &DetectionScope::Merchant("slow biller".to_string()),
+            &overrides,
+            "test",
+        )
+        .unwrap();
+
+        let results = WasteDetector::new(&db).detect_all().await.unwrap();
+        assert_eq!(results.auto_cancelled, 0);
+
+        // Without the override the subscription is treated as cancelled
+        db.delete_detection_overrides(&DetectionScope::Merchant("SLOW BILLER".to_string()))
+            .unwrap();
+        let results = WasteDetector::new(&db).detect_all().await.unwrap();
+        assert_eq!(results.auto_cancelled, 1);
+    }
+
+    fn insert_trial_test_tx(
+        db: &Database,
+        account_id: i64,
+        description: &str,
+        amount: f64,
+        days_ago: i64,
+    ) -> i64 {
+        db.insert_transaction(
+            account_id,
+            &crate::models::NewTransaction {
+                date: Utc::now().date_naive() - Duration::days(days_ago),
+                description: description.to_string(),
+                amount,
+                category: None,
+                import_hash: format!("trial_test_{}_{}_{}", description, amount, days_ago),
+                original_data: None,
+                import_format: None,
+                card_member: None,
+                payment_method: None,
+            },
+        )
+        .unwrap()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_detect_trial_authorization_and_conversion() {
+        let db = Database::in_memory().unwrap();
+        let account_id = db
+            .upsert_account("Test Account", crate::models::Bank::Chase, None)
+            .unwrap();
+
+        // $1 authorization from a merchant known for 30-day trials
+        let auth_id = insert_trial_test_tx(&db, account_id, "AUDIBLE", -1.00, 5);
+
+        let results = WasteDetector::new(&db).detect_all().await.unwrap();
+        assert_eq!(results.trials_detected, 1);
+
+        let trials = db.list_trial_forecasts(None).unwrap();
+        assert_eq!(trials.len(), 1);
+        let trial = &trials[0];
+        assert_eq!(trial.transaction_id, auth_id);
+        assert_eq!(trial.signal, TrialSignal::Authorization);
+        assert_eq!(
+            trial.expected_conversion,
+            Utc::now().date_naive() + Duration::days(25)
+        );
+
+        let alerts = db.list_alerts(false).unwrap();
+        let alert = alerts
+            .iter()
+            .find(|a| a.alert_type == AlertType::TrialEnding)
+            .expect("Should have created a trial alert");
+        assert_eq!(alert.trial.as_ref().map(|t| t.id), Some(trial.id));
+
+        // Running again doesn't raise a second alert
+        let results = WasteDetector::new(&db).detect_all().await.unwrap();
+        assert_eq!(results.trials_detected, 0);
+
+        // The full-price charge posts: the trial converts and its alert resolves
+        let charge_id = insert_trial_test_tx(&db, account_id, "AUDIBLE", -14.95, 0);
+        let results = WasteDetector::new(&db).detect_all().await.unwrap();
+        assert_eq!(results.trials_converted, 1);
+
+        let trial = db.get_trial_forecast(trial.id).unwrap().unwrap();
+        assert_eq!(trial.status, TrialStatus::Converted);
+        assert_eq!(trial.converted_transaction_id, Some(charge_id));
+        assert!(db.get_alert(alert.id).unwrap().dismissed);
+    }
+
+    #[tokio::test]
+    async fn test_lone_dollar_charge_needs_subscription_signal() {
+        let db = Database::in_memory().unwrap();
+        let account_id = db
+            .upsert_account("Test Account", crate::models::Bank::Chase, None)
+            .unwrap();
+        db.seed_root_tags().unwrap();
+
+        // A one-off $1 charge from an ordinary merchant isn't a trial
+        insert_trial_test_tx(&db, account_id, "CITY PARKING METER", -1.00, 3);
+        let results = WasteDetector::new(&db).detect_all().await.unwrap();
+        assert_eq!(results.trials_detected, 0);
+        assert!(db.list_trial_forecasts(None).unwrap().is_empty());
+
+        // Tagged as a subscription, a $1 charge is
+        let streaming = db
+            .get_tag_by_path("Subscriptions.Streaming")
+            .unwrap()
+            .unwrap();
+        let tx_id = insert_trial_test_tx(&db, account_id, "NEWSTREAM TV", -1.00, 3);
+        db.add_transaction_tag(tx_id, streaming.id, crate::models::TagSource::Manual, None)
+            .unwrap();
+        let results = WasteDetector::new(&db).detect_all().await.unwrap();
+        assert_eq!(results.trials_detected, 1);
+        let trials = db.list_trial_forecasts(None).unwrap();
+        assert_eq!(trials.len(), 1);
+        assert_eq!(trials[0].transaction_id, tx_id);
+        assert_eq!(trials[0].signal, TrialSignal::Authorization);
+    }
+
+    #[tokio::test]
+    async fn test_detect_intro_price_below_category_norm() {
+        let db = Database::in_memory().unwrap();
+        let account_id = db
+            .upsert_account("Test Account", crate::models::Bank::Chase, None)
+            .unwrap();
+        db.seed_root_tags().unwrap();
+        let streaming = db
+            .get_tag_by_path("Subscriptions.Streaming")
+            .unwrap()
+            .unwrap();
+
+        // Two streaming subscriptions set the category norm
+        for (merchant, price) in [("NETFLIX", 15.49), ("HULU", 17.99)] {
+            let tx_id = insert_trial_test_tx(&db, account_id, merchant, -price, 10);
+            db.add_transaction_tag(tx_id, streaming.id, crate::models::TagSource::Manual, None)
+                .unwrap();
+            db.upsert_subscription(
+                merchant,
+                Some(account_id),
+                Some(price),
+                Some(Frequency::Monthly),
+                Some(Utc::now().date_naive() - Duration::days(40)),
+                Some(Utc::now().date_naive() - Duration::days(10)),
+            )
+            .unwrap();
+        }
+
+        // A new streaming service at a fraction of that price
+        let intro_id = insert_trial_test_tx(&db, account_id, "CRUNCHYROLL", -3.99, 3);
+        db.add_transaction_tag(
+            intro_id,
+            streaming.id,
+            crate::models::TagSource::Manual,
+            None,
+        )
+        .unwrap();
+
+        let results = WasteDetector::new(&db).detect_all().await.unwrap();
+        assert_eq!(results.trials_detected, 1);
+
+        let trials = db.list_trial_forecasts(Some(TrialStatus::Pending)).unwrap();
+        assert_eq!(trials.len(), 1);
+        assert_eq!(trials[0].transaction_id, intro_id);
+        assert_eq!(trials[0].signal, TrialSignal::IntroPrice);
+        let expected = trials[0].expected_amount.unwrap();
+        assert!((expected - 16.74).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_verify_cancellations() {
+        let db = Database::in_memory().unwrap();
+        let account_id = db
+            .upsert_account("Test Account", crate::models::Bank::Chase, None)
+            .unwrap();
+        let today = Utc::now().date_naive();
+
+        let mut sub_ids = Vec::new();
+        for merchant in ["SPOTIFY", "PELOTON"] {
+            sub_ids.push(
+                db.upsert_subscription(
+                    &normalize_merchant(merchant),
+                    Some(account_id),
+                    Some(12.99),
+                    Some(Frequency::Monthly),
+                    Some(today - Duration::days(200)),
+                    Some(today - Duration::days(60)),
+                )
+                .unwrap(),
+            );
+        }
+
+        // Spotify: cancelled with a prorated refund, no charges since
+        let clean = db
+            .record_cancellation(
+                sub_ids[0],
+                &crate::models::NewSubscriptionCancellation {
+                    cancelled_on: Some(today - Duration::days(70)),
+                    expected_final_charge: Some(today - Duration::days(60)),
+                    expected_refund: Some(4.00),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let refund_id = insert_trial_test_tx(&db, account_id, "SPOTIFY", 4.00, 55);
+
+        // Peloton: cancelled, but charged again after the final charge
+        let late = db
+            .record_cancellation(
+                sub_ids[1],
+                &crate::models::NewSubscriptionCancellation {
+                    cancelled_on: Some(today - Duration::days(20)),
+                    expected_final_charge: Some(today - Duration::days(15)),
+                    confirmation_number: Some("PX-981".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let late_id = insert_trial_test_tx(&db, account_id, "PELOTON", -12.99, 2);
+
+        let results = WasteDetector::new(&db).detect_all().await.unwrap();
+        assert_eq!(results.cancellations_confirmed, 1);
+        assert_eq!(results.charges_after_cancel, 1);
+
+        let clean = db.get_cancellation(clean.id).unwrap().unwrap();
+        assert_eq!(clean.status, CancellationStatus::Confirmed);
+        assert_eq!(clean.refund_transaction_id, Some(refund_id));
+        assert_eq!(clean.refund_amount, Some(4.00));
+
+        let late = db.get_cancellation(late.id).unwrap().unwrap();
+        assert_eq!(late.status, CancellationStatus::ChargedAfter);
+        assert_eq!(late.late_charge_transaction_id, Some(late_id));
+        let sub = db.get_subscription(sub_ids[1]).unwrap().unwrap();
+        assert_eq!(sub.status, SubscriptionStatus::Active);
+
+        let alerts = db.list_alerts(false).unwrap();
+        let alert = alerts
+            .iter()
+            .find(|a| a.alert_type == AlertType::ChargedAfterCancel)
+            .expect("Should have alerted on the late charge");
+        assert!(alert.message.as_deref().unwrap().contains("PX-981"));
+        // The tracked cancellation supersedes the generic resume alert
+        assert!(!alerts.iter().any(|a| a.alert_type == AlertType::Resume));
+
+        // Verified cancellations aren't checked again
+        let results = WasteDetector::new(&db).detect_all().await.unwrap();
+        assert_eq!(results.cancellations_confirmed, 0);
+        assert_eq!(results.charges_after_cancel, 0);
+    }
+
+    #[test]
+    fn test_known_trial_days() {
+        assert_eq!(known_trial_days("Audible"), Some(30));
+        assert_eq!(known_trial_days("DISNEY PLUS"), Some(7));
+        assert_eq!(known_trial_days("Max"), Some(7));
+        // Whole words only
+        assert_eq!(known_trial_days("Maxwell Coffee"), None);
+        assert_eq!(known_trial_days("Corner Store"), None);
+    }
+
+    fn insert_anomaly_test_tx(
+        db: &Database,
+        account_id: i64,
+        description: &str,
+        amount: f64,
+        days_ago: i64,
+        payment_method: Option<PaymentMethod>,
+    ) -> i64 {
+        let n = db.list_transactions(None, 10000, 0).unwrap().len();
+        db.insert_transaction(
+            account_id,
+            &crate::models::NewTransaction {
+                date: Utc::now().date_naive() - Duration::days(days_ago),
+                description: description.to_string(),
+                amount,
+                category: None,
+                import_hash: format!("anomaly_test_{}", n),
+                original_data: None,
+                import_format: None,
+                card_member: None,
+                payment_method,
+            },
+        )
+        .unwrap()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_detect_transaction_anomalies() {
+        let db = Database::in_memory().unwrap();
+        let account_id = db
+            .upsert_account("Test Account", crate::models::Bank::Chase, None)
+            .unwrap();
+
+        // Ten in-person grocery runs establish the account's habits
+        for days_ago in (100..=190).step_by(10) {
+            insert_anomaly_test_tx(
+                &db,
+                account_id,
+                "GROCERY MART",
+                -60.0,
+                days_ago,
+                Some(PaymentMethod::PhysicalCard),
+            );
+        }
+        for (amount, days_ago) in [(-40.0, 200), (-45.0, 150), (-50.0, 100)] {
+            insert_anomaly_test_tx(&db, account_id, "HARDWARE STORE", amount, days_ago, None);
+        }
+
+        let unusual = insert_anomaly_test_tx(&db, account_id, "HARDWARE STORE", -300.0, 5, None);
+        let new_merchant =
+            insert_anomaly_test_tx(&db, account_id, "BIG TV OUTLET", -900.0, 3, None);
+        insert_anomaly_test_tx(&db, account_id, "NEW CAFE", -12.0, 4, None);
+        let first = insert_anomaly_test_tx(&db, account_id, "PIZZA PLACE", -25.0, 2, None);
+        let second = insert_anomaly_test_tx(&db, account_id, "PIZZA PLACE", -25.0, 2, None);
+        let online = insert_anomaly_test_tx(
+            &db,
+            account_id,
+            "WEBSHOP",
+            -80.0,
+            1,
+            Some(PaymentMethod::Online),
+        );
+
+        let detector = WasteDetector::new(&db);
+        assert_eq!(detector.detect_transaction_anomalies().unwrap(), 4);
+        // Charges are only flagged once
+        assert_eq!(detector.detect_transaction_anomalies().unwrap(), 0);
+
+        let anomalies: Vec<_> = db
+            .list_alerts(false)
+            .unwrap()
+            .into_iter()
+            .filter_map(|a| a.transaction_anomaly)
+            .collect();
+        let find = |t: AlertType| anomalies.iter().find(|a| a.anomaly_type == t).unwrap();
+
+        let amount = find(AlertType::UnusualAmount);
+        assert_eq!(amount.transaction_id, unusual);
+        assert_eq!(amount.baseline_amount, Some(45.0));
+        assert_eq!(find(AlertType::NewMerchant).transaction_id, new_merchant);
+        let duplicate = find(AlertType::DuplicateCharge);
+        assert_eq!(duplicate.transaction_id, second);
+        assert_eq!(duplicate.related_transaction_id, Some(first));
+        let activity = find(AlertType::UnusualActivity);
+        assert_eq!(activity.transaction_id, online);
+        assert_eq!(activity.detail.as_deref(), Some("card_not_present"));
+    }
+
+    #[tokio::test]
+    async fn test_anomaly_dismissals_raise_thresholds() {
+        let db = Database::in_memory().unwrap();
+        let account_id = db
+            .upsert_account("Test Account", crate::models::Bank::Chase, None)
+            .unwrap();
+        let detector = WasteDetector::new(&db);
+
+        insert_anomaly_test_tx(&db, account_id, "COFFEE BAR", -6.0, 10, None);
+        insert_anomaly_test_tx(&db, account_id, "COFFEE BAR", -6.0, 10, None);
+        assert_eq!(detector.detect_transaction_anomalies().unwrap(), 1);
+
+        let alert_id = db.list_alerts(false).unwrap()[0].id;
+        db.dismiss_alert(alert_id).unwrap();
+        assert!(db.record_anomaly_dismissal(alert_id).unwrap());
+        assert!(!db.record_anomaly_dismissal(alert_id).unwrap());
+
+        // One dismissal: the duplicate minimum is $6.25, and $11.25 at the coffee bar
+        insert_anomaly_test_tx(&db, account_id, "COFFEE BAR", -7.0, 3, None);
+        insert_anomaly_test_tx(&db, account_id, "COFFEE BAR", -7.0, 3, None);
+        insert_anomaly_test_tx(&db, account_id, "BAKERY", -7.0, 3, None);
+        insert_anomaly_test_tx(&db, account_id, "BAKERY", -7.0, 3, None);
+        assert_eq!(detector.detect_transaction_anomalies().unwrap(), 1);
+        let flagged = db.list_alerts(false).unwrap()[0]
+            .transaction_anomaly
+            .clone()
+            .unwrap();
+        assert_eq!(flagged.merchant, normalize_merchant("BAKERY"));
+        assert_eq!(flagged.threshold, 6.25);
+
+        // Restoring the alert withdraws its feedback
+        db.restore_alert(alert_id).unwrap();
+        assert_eq!(db.revert_alert_dismissals(alert_id).unwrap(), 1);
+        assert_eq!(detector.detect_transaction_anomalies().unwrap(), 1);
+    }
 }
@@ -48,6 +48,21 @@ pub enum Error {
 
     #[error("Training error: {0}")]
     Training(String),
+
+    #[error("Notification error: {0}")]
+    Notification(String),
+
+    #[error("Schedule error: {0}")]
+    Schedule(String),
+
+    #[error("Watch error: {0}")]
+    Watch(String),
+
+    #[error("Mail error: {0}")]
+    Mail(String),
+
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
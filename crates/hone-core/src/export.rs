@@ -3,11 +3,12 @@
 //! Supports:
 //! - Transaction CSV export with filtering (date range, tags)
 //! - Full JSON backup export/import with all database tables
+//! - Audit log CSV export with filtering
 
 use chrono::{NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::db::Database;
+use crate::db::{AuditFilter, Database};
 use crate::error::Result;
 
 /// Export format options
@@ -321,6 +322,33 @@ impl Database {
         Ok(csv)
     }
 
+    /// Export audit log entries to CSV format (oldest first, hashes included)
+    pub fn export_audit_log_csv(&self, filter: &AuditFilter) -> Result<String> {
+        let mut entries = self.query_audit_log(filter)?;
+        entries.reverse();
+
+        let mut csv = String::from(
+            "id,timestamp,user_email,action,entity_type,entity_id,details,prev_hash,entry_hash\n",
+        );
+
+        for entry in entries {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{}\n",
+                entry.id,
+                entry.timestamp,
+                escape_csv_field(&entry.user_email),
+                escape_csv_field(&entry.action),
+                escape_csv_field(entry.entity_type.as_deref().unwrap_or("")),
+                entry.entity_id.map(|id| id.to_string()).unwrap_or_default(),
+                escape_csv_field(entry.details.as_deref().unwrap_or("")),
+                entry.prev_hash,
+                entry.entry_hash
+            ));
+        }
+
+        Ok(csv)
+    }
+
     /// Export transactions with filtering
     pub fn export_transactions(
         &self,
@@ -343,6 +371,8 @@ impl Database {
             "#,
         );
 
+        sql.push_str(&self.account_scope_sql("t.account_id"));
+
         let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![];
 
         if let Some(from) = &opts.from {
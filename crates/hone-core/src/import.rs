@@ -1,4 +1,4 @@
-//! CSV import parsers for various bank formats
+//! CSV and OFX import parsers for various bank formats
 
 use chrono::NaiveDate;
 use csv::{ReaderBuilder, StringRecord};
@@ -8,7 +8,7 @@ use std::io::Read;
 use tracing::debug;
 
 use crate::error::{Error, Result};
-use crate::models::{Bank, NewTransaction, PaymentMethod};
+use crate::models::{Bank, ImportRow, NewTransaction, PaymentMethod};
 
 /// Convert a CSV record to a JSON object using headers as keys
 fn record_to_json(headers: &StringRecord, record: &StringRecord) -> String {
@@ -520,6 +520,167 @@ fn extract_merchant_from_extended_details(details: &str) -> Option<String> {
     }
 }
 
+/// Whether file contents look like an OFX/QFX statement
+pub fn is_ofx(content: &str) -> bool {
+    let head: String = content
+        .chars()
+        .take(1024)
+        .collect::<String>()
+        .to_ascii_uppercase();
+    head.contains("OFXHEADER") || head.contains("<OFX>")
+}
+
+/// Detect the bank of an OFX statement from its `<FI><ORG>` element
+pub fn detect_ofx_bank(content: &str) -> Option<Bank> {
+    let org = ofx_field(content, "ORG")?.to_lowercase();
+    let compact: String = org.chars().filter(|c| c.is_alphanumeric()).collect();
+    if compact.contains("chase") {
+        Some(Bank::Chase)
+    } else if compact.contains("bankofamerica") || compact == "bofa" {
+        Some(Bank::Bofa)
+    } else if compact.contains("amex") || compact.contains("americanexpress") {
+        Some(Bank::Amex)
+    } else if compact.contains("capitalone") {
+        Some(Bank::CapitalOne)
+    } else {
+        None
+    }
+}
+
+/// Parse an OFX/QFX statement (SGML 1.x or XML 2.x) into transactions
+///
+/// Amounts keep OFX signs (negative = expense). The bank's `FITID` is part
+/// of the dedup hash, so re-downloading an overlapping statement is safe.
+pub fn parse_ofx(content: &str) -> Result<Vec<NewTransaction>> {
+    // ASCII-only uppercasing keeps byte offsets valid for slicing `content`
+    let upper = content.to_ascii_uppercase();
+    let mut transactions = Vec::new();
+    let mut rest = 0;
+
+    while let Some(start) = upper[rest..].find("<STMTTRN>") {
+        let block_start = rest + start + "<STMTTRN>".len();
+        let block_end = upper[block_start..]
+            .find("</STMTTRN>")
+            .map(|i| block_start + i)
+            .unwrap_or(upper.len());
+        let block = &content[block_start..block_end];
+        rest = block_end;
+
+        let date_str = ofx_field(block, "DTPOSTED")
+            .ok_or_else(|| Error::Import("OFX transaction missing DTPOSTED".into()))?;
+        let date = date_str
+            .get(..8)
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y%m%d").ok())
+            .ok_or_else(|| Error::Import(format!("Unable to parse OFX date: {}", date_str)))?;
+        let amount = parse_amount(
+            &ofx_field(block, "TRNAMT")
+                .ok_or_else(|| Error::Import("OFX transaction missing TRNAMT".into()))?,
+        )?;
+        let name = ofx_field(block, "NAME");
+        let memo = ofx_field(block, "MEMO");
+        let description = name
+            .clone()
+            .or_else(|| memo.clone())
+            .ok_or_else(|| Error::Import("OFX transaction missing NAME".into()))?;
+        let fitid = ofx_field(block, "FITID");
+
+        let import_hash = generate_hash_with_ref(&date, &description, amount, fitid.as_deref());
+        let original_data = json!({
+            "TRNTYPE": ofx_field(block, "TRNTYPE"),
+            "DTPOSTED": date_str,
+            "TRNAMT": amount,
+            "FITID": fitid,
+            "NAME": name,
+            "MEMO": memo,
+        })
+        .to_string();
+
+        transactions.push(NewTransaction {
+            date,
+            description,
+            amount,
+            category: None,
+            import_hash,
+            original_data: Some(original_data),
+            import_format: Some("ofx".to_string()),
+            card_member: None,
+            payment_method: None,
+        });
+    }
+
+    debug!("Parsed {} OFX transactions", transactions.len());
+    Ok(transactions)
+}
+
+/// Value of the first `<TAG>value` element (closing tags are optional in OFX 1.x)
+fn ofx_field(content: &str, tag: &str) -> Option<String> {
+    let open = format!("<{}>", tag);
+    let start = content.to_ascii_uppercase().find(&open)? + open.len();
+    let value = &content[start..];
+    let end = value.find('<').unwrap_or(value.len());
+    let value = value[..end].trim();
+    (!value.is_empty()).then(|| decode_ofx_entities(value))
+}
+
+fn decode_ofx_entities(s: &str) -> String {
+    s.replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&apos;", "'")
+        .replace("&quot;", "\"")
+}
+
+/// Convert a JSON import API row into a transaction
+///
+/// `external_id` is part of the dedup hash, so re-sent rows are skipped as
+/// duplicates while distinct same-day rows with identical amounts aren't.
+pub fn parse_import_row(row: &ImportRow) -> Result<NewTransaction> {
+    // ISO timestamps ("2024-01-15T08:30:00Z") keep only their date
+    let date = row
+        .date
+        .trim()
+        .get(..10)
+        .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .map_or_else(|| parse_date(&row.date), Ok)?;
+    let description = row.description.trim();
+    if description.is_empty() {
+        return Err(Error::Import("Missing description".into()));
+    }
+    if !row.amount.is_finite() {
+        return Err(Error::Import(format!("Invalid amount: {}", row.amount)));
+    }
+    let external_id = row
+        .external_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|id| !id.is_empty());
+
+    Ok(NewTransaction {
+        date,
+        description: description.to_string(),
+        amount: row.amount,
+        category: row
+            .category
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(String::from),
+        import_hash: generate_hash_with_ref(&date, description, row.amount, external_id),
+        original_data: serde_json::to_string(row).ok(),
+        import_format: Some("json".to_string()),
+        card_member: None,
+        payment_method: None,
+    })
+}
+
+/// Fingerprint of a JSON import request, to detect idempotency key reuse
+pub fn import_request_hash(account_id: i64, rows: &[ImportRow]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(account_id.to_be_bytes());
+    hasher.update(serde_json::to_string(rows).unwrap_or_default().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
 /// Parse a date string in various common formats
 fn parse_date(s: &str) -> Result<NaiveDate> {
     let s = s.trim();
@@ -751,4 +912,95 @@ CA",ADOBE ACROPRO SUBS,"500 ADOBE LN","SAN JOSE, CA",95110,UNITED STATES,1234567
         assert_eq!(transactions[0].description, "SP BATTERYSTORE");
         assert_eq!(transactions[0].amount, -11.99);
     }
+
+    const OFX_SGML: &str = "OFXHEADER:100
+DATA:OFXSGML
+VERSION:102
+
+<OFX>
+<SIGNONMSGSRSV1><SONRS><FI><ORG>Chase Bank<FID>10898</FI></SONRS></SIGNONMSGSRSV1>
+<CREDITCARDMSGSRSV1><CCSTMTTRNRS><CCSTMTRS><BANKTRANLIST>
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20240115120000[-5:EST]
+<TRNAMT>-15.99
+<FITID>2024011501
+<NAME>NETFLIX.COM
+<MEMO>Streaming
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>20240116
+<TRNAMT>25.00
+<FITID>2024011602
+<NAME>RETURN: AT&amp;T
+</STMTTRN>
+</BANKTRANLIST></CCSTMTRS></CCSTMTTRNRS></CREDITCARDMSGSRSV1>
+</OFX>";
+
+    #[test]
+    fn test_parse_ofx_sgml() {
+        assert!(is_ofx(OFX_SGML));
+        assert!(!is_ofx("Date,Description,Amount"));
+        assert_eq!(detect_ofx_bank(OFX_SGML), Some(Bank::Chase));
+
+        let transactions = parse_ofx(OFX_SGML).unwrap();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].description, "NETFLIX.COM");
+        assert_eq!(transactions[0].amount, -15.99);
+        assert_eq!(
+            transactions[0].date,
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()
+        );
+        assert_eq!(transactions[0].import_format.as_deref(), Some("ofx"));
+        assert_eq!(transactions[1].description, "RETURN: AT&T");
+        assert_eq!(transactions[1].amount, 25.00);
+        assert_ne!(transactions[0].import_hash, transactions[1].import_hash);
+    }
+
+    #[test]
+    fn test_parse_ofx_xml_fitid_in_hash() {
+        let xml = r#"<?xml version="1.0"?><OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS><BANKTRANLIST>
+<STMTTRN><DTPOSTED>20240201</DTPOSTED><TRNAMT>-4.50</TRNAMT><FITID>A1</FITID><NAME>COFFEE</NAME></STMTTRN>
+<STMTTRN><DTPOSTED>20240201</DTPOSTED><TRNAMT>-4.50</TRNAMT><FITID>A2</FITID><NAME>COFFEE</NAME></STMTTRN>
+</BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>"#;
+        assert!(is_ofx(xml));
+        assert_eq!(detect_ofx_bank(xml), None);
+
+        let transactions = parse_ofx(xml).unwrap();
+        assert_eq!(transactions.len(), 2);
+        // Same purchase twice on one day stays two transactions
+        assert_ne!(transactions[0].import_hash, transactions[1].import_hash);
+
+        assert!(parse_ofx("<OFX><STMTTRN><TRNAMT>1.00</STMTTRN></OFX>").is_err());
+    }
+
+    #[test]
+    fn test_parse_import_row() {
+        let row = |date: &str, description: &str, amount: f64, id: Option<&str>| ImportRow {
+            date: date.to_string(),
+            description: description.to_string(),
+            amount,
+            external_id: id.map(String::from),
+            category: Some(" Groceries ".to_string()),
+        };
+
+        let tx =
+            parse_import_row(&row("2024-03-05T08:30:00Z", " KROGER ", -42.1, Some("a1"))).unwrap();
+        assert_eq!(tx.date, NaiveDate::from_ymd_opt(2024, 3, 5).unwrap());
+        assert_eq!(tx.description, "KROGER");
+        assert_eq!(tx.category.as_deref(), Some("Groceries"));
+        assert_eq!(tx.import_format.as_deref(), Some("json"));
+
+        // Same details, different external IDs: distinct transactions
+        let other = parse_import_row(&row("2024-03-05", "KROGER", -42.1, Some("a2"))).unwrap();
+        assert_ne!(tx.import_hash, other.import_hash);
+        let resent = parse_import_row(&row("2024-03-05", "KROGER", -42.1, Some("a1"))).unwrap();
+        assert_eq!(tx.import_hash, resent.import_hash);
+
+        assert!(parse_import_row(&row("03/05/2024", "KROGER", -1.0, None)).is_ok());
+        assert!(parse_import_row(&row("yesterday", "KROGER", -1.0, None)).is_err());
+        assert!(parse_import_row(&row("2024-03-05", "  ", -1.0, None)).is_err());
+        assert!(parse_import_row(&row("2024-03-05", "KROGER", f64::NAN, None)).is_err());
+    }
 }
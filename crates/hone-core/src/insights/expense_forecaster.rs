@@ -2,19 +2,24 @@
 //!
 //! Predicts upcoming expenses based on:
 //! - Active subscriptions with expected charge dates
+//! - Free trials and intro prices expected to convert to full price
 //! - Rolling averages for variable categories
 
 use async_trait::async_trait;
 use chrono::{Duration, NaiveDate, Utc};
 
 use crate::error::Result;
-use crate::models::{Frequency, SubscriptionStatus};
+use crate::models::{Frequency, SubscriptionStatus, TrialSignal, TrialStatus};
 
 use super::engine::{AnalysisContext, Insight};
 use super::types::{
     ExpenseForecasterData, Finding, ForecastItem, ForecastItemType, InsightType, Severity,
 };
 
+/// Subscriptions billed at least this many days apart are large expenses
+/// worth early notice (quarterly insurance, semiannual registration, ...)
+const LARGE_EXPENSE_MIN_INTERVAL_DAYS: i64 = 60;
+
 /// Insight that forecasts upcoming expenses
 pub struct ExpenseForecasterInsight {
     /// Number of days to forecast (default 30)
@@ -33,18 +38,19 @@ impl ExpenseForecasterInsight {
     }
 
     /// Calculate expected next charge date for a subscription
+    ///
+    /// Cadences billed on a day of the month (monthly, quarterly, ...) step
+    /// by calendar months; the rest step by days.
     fn next_charge_date(last_seen: NaiveDate, frequency: Frequency, today: NaiveDate) -> NaiveDate {
-        let interval_days = match frequency {
-            Frequency::Weekly => 7,
-            Frequency::Monthly => 30,
-            Frequency::Yearly => 365,
-        };
-
-        let mut next = last_seen + Duration::days(interval_days);
+        let mut next = frequency.next_date(last_seen);
 
         // Advance until we're in the future
         while next <= today {
-            next = next + Duration::days(interval_days);
+            let after = frequency.next_date(next);
+            if after == next {
+                break; // Out of calendar range
+            }
+            next = after;
         }
 
         next
@@ -102,8 +108,8 @@ impl Insight for ExpenseForecasterInsight {
                     amount,
                     due_date: Some(next_charge.format("%Y-%m-%d").to_string()),
                     basis: Some(format!(
-                        "Recurring {:?} since {}",
-                        frequency,
+                        "Recurring {} since {}",
+                        frequency.label(),
                         sub.first_seen
                             .map(|d| d.format("%b %Y").to_string())
                             .unwrap_or_else(|| "unknown".to_string())
@@ -115,15 +121,53 @@ impl Insight for ExpenseForecasterInsight {
             }
         }
 
-        // 2. Add estimates for variable categories based on historical averages
+        // 2. Add free trials and intro prices converting within the window
+        for trial in ctx.db.list_trial_forecasts(Some(TrialStatus::Pending))? {
+            if trial.expected_conversion > forecast_end {
+                continue;
+            }
+
+            // Overdue conversions are still expected any day now
+            let due = trial.expected_conversion.max(today);
+            let kind = match trial.signal {
+                TrialSignal::Authorization => "Free trial",
+                _ => "Intro price",
+            };
+            let amount = trial.expected_amount.unwrap_or(0.0);
+            let basis = match trial.expected_amount {
+                Some(_) => format!("{} since {}", kind, trial.started_on.format("%b %d")),
+                None => format!(
+                    "{} since {} (full price unknown)",
+                    kind,
+                    trial.started_on.format("%b %d")
+                ),
+            };
+
+            total_expected += amount;
+            items.push(ForecastItem {
+                item_type: ForecastItemType::Trial,
+                name: format!("{} (trial ends)", trial.merchant),
+                amount,
+                due_date: Some(due.format("%Y-%m-%d").to_string()),
+                basis: Some(basis),
+            });
+        }
+
+        // 3. Add estimates for variable categories based on historical averages
         // Get 4-week spending averages for key categories
         let baseline_start = today - Duration::days(28 * 3); // 3 months of data
         let baseline_end = today;
 
         // Get spending summary for baseline period
-        let spending =
-            ctx.db
-                .get_spending_summary(baseline_start, baseline_end, None, false, None, None)?;
+        let spending = ctx.db.get_spending_summary(
+            baseline_start,
+            baseline_end,
+            None,
+            false,
+            None,
+            None,
+            false,
+        )?;
 
         // Variable expense categories to estimate
         let variable_categories = ["Groceries", "Dining", "Gas", "Transport"];
@@ -148,8 +192,9 @@ impl Insight for ExpenseForecasterInsight {
             }
         }
 
-        // 3. Check for large one-time expenses based on patterns
-        // Look for any large upcoming expenses (like quarterly insurance)
+        // 4. Check for large one-time expenses based on patterns
+        // Look for any large upcoming expenses (like quarterly insurance or
+        // semiannual registration), billed every two months or less often
         for sub in ctx.db.list_subscriptions(None)? {
             if sub.status != SubscriptionStatus::Active {
                 continue;
@@ -160,8 +205,8 @@ impl Insight for ExpenseForecasterInsight {
                 _ => continue,
             };
 
-            // Flag large yearly subscriptions as notable
-            if frequency == Frequency::Yearly && amount > 100.0 {
+            // Flag large infrequent subscriptions as notable
+            if frequency.interval_days() >= LARGE_EXPENSE_MIN_INTERVAL_DAYS && amount > 100.0 {
                 let next_charge = Self::next_charge_date(last_seen, frequency, today);
 
                 // Check if within 60 days (give more notice for large expenses)
@@ -169,10 +214,14 @@ impl Insight for ExpenseForecasterInsight {
                 if next_charge <= extended_window && next_charge > forecast_end {
                     let item = ForecastItem {
                         item_type: ForecastItemType::LargeExpense,
-                        name: format!("{} (annual)", sub.merchant),
+                        name: format!("{} ({})", sub.merchant, frequency.label()),
                         amount,
                         due_date: Some(next_charge.format("%Y-%m-%d").to_string()),
-                        basis: Some("Annual charge".to_string()),
+                        basis: Some(if frequency == Frequency::Yearly {
+                            "Annual charge".to_string()
+                        } else {
+                            format!("Charged {}", frequency.label())
+                        }),
                     };
 
                     // Don't add to total (outside forecast window), but include as heads-up
@@ -282,6 +331,18 @@ mod tests {
         assert_eq!(next, NaiveDate::from_ymd_opt(2026, 1, 17).unwrap());
     }
 
+    #[test]
+    fn test_next_charge_date_quarterly() {
+        let today = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        let last_seen = NaiveDate::from_ymd_opt(2025, 7, 20).unwrap();
+
+        let next =
+            ExpenseForecasterInsight::next_charge_date(last_seen, Frequency::Quarterly, today);
+
+        // Oct 20 has passed; the next quarter bills Jan 20
+        assert_eq!(next, NaiveDate::from_ymd_opt(2026, 1, 20).unwrap());
+    }
+
     #[tokio::test]
     async fn test_expense_forecaster_with_subscriptions() {
         let db = Database::in_memory().unwrap();
@@ -320,4 +381,61 @@ mod tests {
         assert!(netflix.is_some());
         assert!((netflix.unwrap().amount - 22.99).abs() < 0.01);
     }
+
+    #[tokio::test]
+    async fn test_expense_forecaster_includes_trials() {
+        let db = Database::in_memory().unwrap();
+        let account_id = db
+            .upsert_account("Test Account", Bank::Chase, None)
+            .unwrap();
+
+        let today = Utc::now().date_naive();
+        let tx_id = db
+            .insert_transaction(
+                account_id,
+                &crate::models::NewTransaction {
+                    date: today - Duration::days(20),
+                    description: "PEACOCK".to_string(),
+                    amount: -1.00,
+                    category: None,
+                    import_hash: "forecast_trial_hash".to_string(),
+                    original_data: None,
+                    import_format: None,
+                    card_member: None,
+                    payment_method: None,
+                },
+            )
+            .unwrap()
+            .unwrap();
+        db.create_trial_forecast(
+            &crate::models::NewTrialForecast {
+                merchant: "PEACOCK".to_string(),
+                account_id,
+                transaction_id: tx_id,
+                signal: TrialSignal::Authorization,
+                trial_amount: 1.00,
+                started_on: today - Duration::days(20),
+                expected_conversion: today + Duration::days(10),
+                expected_amount: Some(13.99),
+            },
+            "PEACOCK looks like a free trial",
+        )
+        .unwrap()
+        .unwrap();
+
+        let insight = ExpenseForecasterInsight::new();
+        let ctx = AnalysisContext::current_month(&db, None);
+        let findings = insight.analyze(&ctx).await.unwrap();
+        assert_eq!(findings.len(), 1);
+
+        let data: ExpenseForecasterData = serde_json::from_value(findings[0].data.clone()).unwrap();
+        let trial = data
+            .items
+            .iter()
+            .find(|i| matches!(i.item_type, ForecastItemType::Trial))
+            .expect("Should forecast the trial conversion");
+        assert_eq!(trial.name, "PEACOCK (trial ends)");
+        assert!((trial.amount - 13.99).abs() < 0.01);
+        assert!((data.total_expected - 13.99).abs() < 0.01);
+    }
 }
@@ -0,0 +1,243 @@
+//! Explore Digest Insight
+//!
+//! Saved explore questions run on a schedule and store each answer as a
+//! digest. This module compares a new digest against the previous successful
+//! one and, when the answer moved meaningfully, builds a finding for it.
+//!
+//! "Meaningfully" is judged without another model call:
+//! - the wording differs substantially (word-set Jaccard distance), or
+//! - a dollar amount in the answer moved by at least 10% (and at least $1)
+
+use std::collections::HashSet;
+
+use crate::models::{ExploreDigest, SavedQuestion};
+
+use super::types::{AmountChange, ExploreDigestData, Finding, InsightType, Severity};
+
+/// Word-set distance at or above which an answer counts as changed
+const TEXT_CHANGE_THRESHOLD: f64 = 0.5;
+
+/// Relative change in a dollar amount that counts as meaningful
+const AMOUNT_CHANGE_THRESHOLD: f64 = 0.10;
+
+/// Ignore amount changes smaller than this many dollars
+const MIN_AMOUNT_CHANGE: f64 = 1.0;
+
+/// Result of comparing two digest responses
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestComparison {
+    /// Word-set distance between the responses (0.0 = same words, 1.0 = disjoint)
+    pub change_score: f64,
+    /// Dollar amounts that moved meaningfully, paired by order of appearance
+    pub amount_changes: Vec<AmountChange>,
+    /// Whether the difference is worth surfacing as an insight
+    pub changed: bool,
+}
+
+/// Compare a new response against the previous one
+pub fn compare_digests(previous: &str, current: &str) -> DigestComparison {
+    let change_score = word_distance(previous, current);
+
+    let amount_changes: Vec<AmountChange> = extract_amounts(previous)
+        .into_iter()
+        .zip(extract_amounts(current))
+        .filter(|(prev, curr)| {
+            let delta = (curr - prev).abs();
+            delta >= MIN_AMOUNT_CHANGE && delta / prev.abs().max(1.0) >= AMOUNT_CHANGE_THRESHOLD
+        })
+        .map(|(previous, current)| AmountChange { previous, current })
+        .collect();
+
+    let changed = change_score >= TEXT_CHANGE_THRESHOLD || !amount_changes.is_empty();
+
+    DigestComparison {
+        change_score,
+        amount_changes,
+        changed,
+    }
+}
+
+/// Build the insight finding for a digest that changed meaningfully
+pub fn digest_finding(
+    question: &SavedQuestion,
+    previous: &ExploreDigest,
+    current: &ExploreDigest,
+    comparison: &DigestComparison,
+) -> Finding {
+    let summary = match comparison.amount_changes.first() {
+        Some(change) => format!(
+            "\"{}\" moved from ${:.2} to ${:.2} since the last run",
+            question.query, change.previous, change.current
+        ),
+        None => format!(
+            "The answer to \"{}\" differs from the last run",
+            question.query
+        ),
+    };
+
+    let data = ExploreDigestData {
+        saved_question_id: question.id,
+        question_name: question.name.clone(),
+        query: question.query.clone(),
+        digest_id: current.id,
+        previous_digest_id: previous.id,
+        change_score: comparison.change_score,
+        amount_changes: comparison.amount_changes.clone(),
+        previous_response: previous.response.clone(),
+        current_response: current.response.clone(),
+    };
+
+    Finding::new(
+        InsightType::ExploreDigest,
+        format!("digest:{}:{}", question.id, current.id),
+        Severity::Attention,
+        format!("{} changed", question.name),
+        summary,
+    )
+    .with_detail(current.response.clone())
+    .with_data(serde_json::to_value(data).unwrap_or_default())
+}
+
+/// Lowercased words (letters/digits) in a response
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Jaccard distance between the word sets of two responses
+fn word_distance(a: &str, b: &str) -> f64 {
+    let a = words(a);
+    let b = words(b);
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    let intersection = a.intersection(&b).count();
+    1.0 - intersection as f64 / union as f64
+}
+
+/// Dollar amounts ("$1,234.56", "-$12") in order of appearance
+fn extract_amounts(text: &str) -> Vec<f64> {
+    let mut amounts = Vec::new();
+    let mut rest = text;
+    while let Some(pos) = rest.find('$') {
+        let negative = rest[..pos].ends_with('-');
+        let after = rest[pos + 1..].trim_start();
+        let number: String = after
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == ',' || *c == '.')
+            .collect();
+        let cleaned = number.trim_end_matches('.').replace(',', "");
+        if let Ok(value) = cleaned.parse::<f64>() {
+            amounts.push(if negative { -value } else { value });
+        }
+        rest = &rest[pos + 1..];
+    }
+    amounts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_extract_amounts() {
+        assert_eq!(
+            extract_amounts("You spent $1,234.56 on dining, down from $980. Refund: -$12"),
+            vec![1234.56, 980.0, -12.0]
+        );
+        assert!(extract_amounts("No dollars here").is_empty());
+    }
+
+    #[test]
+    fn test_identical_answers_unchanged() {
+        let cmp = compare_digests(
+            "Dining this month: $320.00 across 14 transactions.",
+            "Dining this month: $320.00 across 14 transactions.",
+        );
+        assert_eq!(cmp.change_score, 0.0);
+        assert!(cmp.amount_changes.is_empty());
+        assert!(!cmp.changed);
+    }
+
+    #[test]
+    fn test_small_amount_drift_unchanged() {
+        let cmp = compare_digests(
+            "Dining this month: $320.00 across 14 transactions.",
+            "Dining this month: $330.00 across 15 transactions.",
+        );
+        assert!(cmp.amount_changes.is_empty());
+        assert!(!cmp.changed);
+    }
+
+    #[test]
+    fn test_amount_jump_changed() {
+        let cmp = compare_digests(
+            "Dining this month: $320.00 across 14 transactions.",
+            "Dining this month: $480.00 across 20 transactions.",
+        );
+        assert!(cmp.changed);
+        assert_eq!(
+            cmp.amount_changes,
+            vec![AmountChange {
+                previous: 320.0,
+                current: 480.0
+            }]
+        );
+    }
+
+    #[test]
+    fn test_reworded_answer_changed() {
+        let cmp = compare_digests(
+            "No zombie subscriptions right now.",
+            "Netflix and Hulu overlap; consider cancelling one to save money.",
+        );
+        assert!(cmp.change_score >= TEXT_CHANGE_THRESHOLD);
+        assert!(cmp.changed);
+    }
+
+    #[test]
+    fn test_digest_finding() {
+        let now = Utc::now();
+        let question = SavedQuestion {
+            id: 3,
+            name: "Dining check".to_string(),
+            query: "What changed in dining this month?".to_string(),
+            model: None,
+            interval_hours: 24,
+            enabled: true,
+            last_run_at: None,
+            next_run_at: None,
+            created_by: None,
+            created_at: now,
+        };
+        let digest = |id: i64, response: &str| ExploreDigest {
+            id,
+            saved_question_id: 3,
+            success: true,
+            response: response.to_string(),
+            error_message: None,
+            tool_calls: Vec::new(),
+            iterations: 1,
+            model: None,
+            latency_ms: 10,
+            change_score: None,
+            changed: false,
+            insight_id: None,
+            created_at: now,
+        };
+        let previous = digest(7, "Dining: $320.00");
+        let current = digest(8, "Dining: $480.00");
+        let cmp = compare_digests(&previous.response, &current.response);
+
+        let finding = digest_finding(&question, &previous, &current, &cmp);
+        assert_eq!(finding.insight_type, InsightType::ExploreDigest);
+        assert_eq!(finding.key, "digest:3:8");
+        assert_eq!(finding.title, "Dining check changed");
+        assert!(finding.summary.contains("$320.00 to $480.00"));
+        assert_eq!(finding.data["previous_digest_id"], 7);
+    }
+}
@@ -10,6 +10,7 @@
 //! - **Spending Explainer** - Explains spending changes vs baseline
 //! - **Expense Forecaster** - Predicts upcoming expenses
 //! - **Savings Opportunity** - Identifies ways to reduce spending
+//! - **Explore Digest** - Flags saved explore questions whose answers changed
 //!
 //! ## Usage
 //!
@@ -23,16 +24,18 @@
 
 pub mod engine;
 pub mod expense_forecaster;
+pub mod explore_digest;
 pub mod savings_opportunity;
 pub mod spending_explainer;
 pub mod types;
 
 pub use engine::{AnalysisContext, Insight, InsightEngine};
 pub use expense_forecaster::ExpenseForecasterInsight;
+pub use explore_digest::{compare_digests, digest_finding, DigestComparison};
 pub use savings_opportunity::SavingsOpportunityInsight;
 pub use spending_explainer::SpendingExplainerInsight;
 pub use types::{
-    ExpenseForecasterData, Finding, ForecastItem, ForecastItemType, InsightFinding, InsightStatus,
-    InsightType, MerchantContribution, SavingsOpportunityData, SavingsOpportunityType, Severity,
-    SpendingExplainerData,
+    AmountChange, ExpenseForecasterData, ExploreDigestData, Finding, ForecastItem,
+    ForecastItemType, InsightFinding, InsightStatus, InsightType, MerchantContribution,
+    SavingsOpportunityData, SavingsOpportunityType, Severity, SpendingExplainerData,
 };
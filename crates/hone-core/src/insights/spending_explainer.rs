@@ -83,14 +83,26 @@ impl Insight for SpendingExplainerInsight {
         let baseline_start = baseline_end - Duration::days(90);
 
         // Get spending by category for current month
-        let current =
-            ctx.db
-                .get_spending_summary(current_month_start, today, None, false, None, None)?;
+        let current = ctx.db.get_spending_summary(
+            current_month_start,
+            today,
+            None,
+            false,
+            None,
+            None,
+            false,
+        )?;
 
         // Get spending by category for baseline period
-        let baseline =
-            ctx.db
-                .get_spending_summary(baseline_start, baseline_end, None, false, None, None)?;
+        let baseline = ctx.db.get_spending_summary(
+            baseline_start,
+            baseline_end,
+            None,
+            false,
+            None,
+            None,
+            false,
+        )?;
 
         // Collect categories with notable changes
         let mut changes: Vec<(String, i64, f64, f64, f64)> = Vec::new(); // (tag, tag_id, current, baseline, percent_change)
@@ -414,7 +426,10 @@ mod tests {
 
                 let txs = db.list_transactions(None, 100, 0).unwrap();
                 // Find the specifically inserted transaction to avoid tagging the wrong one
-                let newly_inserted = txs.iter().find(|t| t.import_hash == format!("baseline_{}_{}", month, i)).unwrap();
+                let newly_inserted = txs
+                    .iter()
+                    .find(|t| t.import_hash == format!("baseline_{}_{}", month, i))
+                    .unwrap();
                 db.add_transaction_tag(newly_inserted.id, dining_tag.id, TagSource::Manual, None)
                     .unwrap();
             }
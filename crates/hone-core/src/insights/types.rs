@@ -15,6 +15,8 @@ pub enum InsightType {
     ExpenseForecaster,
     /// Identifies potential savings opportunities
     SavingsOpportunity,
+    /// A scheduled explore question whose answer changed meaningfully
+    ExploreDigest,
 }
 
 impl InsightType {
@@ -23,6 +25,7 @@ impl InsightType {
             InsightType::SpendingExplainer => "spending_explainer",
             InsightType::ExpenseForecaster => "expense_forecaster",
             InsightType::SavingsOpportunity => "savings_opportunity",
+            InsightType::ExploreDigest => "explore_digest",
         }
     }
 }
@@ -41,6 +44,7 @@ impl FromStr for InsightType {
             "spending_explainer" => Ok(InsightType::SpendingExplainer),
             "expense_forecaster" => Ok(InsightType::ExpenseForecaster),
             "savings_opportunity" => Ok(InsightType::SavingsOpportunity),
+            "explore_digest" => Ok(InsightType::ExploreDigest),
             _ => Err(format!("Unknown insight type: {}", s)),
         }
     }
@@ -267,6 +271,8 @@ pub enum ForecastItemType {
     Subscription,
     Estimate,
     LargeExpense,
+    /// A free trial or intro price converting to full price
+    Trial,
 }
 
 /// Data for savings opportunity insight
@@ -292,6 +298,28 @@ pub enum SavingsOpportunityType {
     AnnualSwitch,
 }
 
+/// Data for explore digest insight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExploreDigestData {
+    pub saved_question_id: i64,
+    pub question_name: String,
+    pub query: String,
+    pub digest_id: i64,
+    pub previous_digest_id: i64,
+    /// Text difference between the two answers (0.0 = identical, 1.0 = disjoint)
+    pub change_score: f64,
+    /// Dollar amounts that moved between runs, in order of appearance
+    pub amount_changes: Vec<AmountChange>,
+    pub previous_response: String,
+    pub current_response: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmountChange {
+    pub previous: f64,
+    pub current: f64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -10,6 +10,11 @@
 //! - Context assembler for LLM prompt context
 //! - Tag assignment engine for automatic categorization
 //! - Backup system with pluggable destinations
+//! - Built-in authentication primitives (passwords, TOTP)
+//! - Outbound notifications for alerts and insights
+//! - Cron-scheduled recurring tasks
+//! - Import directory watcher for hands-free statement ingestion
+//! - Email receipt ingestion from Maildir, mbox and IMAP mailboxes
 
 pub mod ai;
 pub mod backup;
@@ -20,14 +25,21 @@ pub mod error;
 pub mod export;
 pub mod import;
 pub mod insights;
+pub mod local_auth;
+pub mod mail;
 pub mod model_router;
 pub mod models;
+pub mod net;
+pub mod notify;
 pub mod ollama;
 pub mod prompts;
+pub mod schedule;
 pub mod tags;
 pub mod tools;
+pub mod trainer;
 pub mod training;
 pub mod training_pipeline;
+pub mod watch;
 
 /// Test utilities including mock Ollama server
 #[cfg(any(test, feature = "test-utils"))]
@@ -43,12 +55,15 @@ pub use backup::{
     BackupDestination, BackupInfo, BackupResult, LocalDestination, PruneResult, RetentionPolicy,
 };
 pub use context::{BaselineStats, Context, ContextAssembler, ContextType};
-pub use db::{AuditEntry, Database};
+pub use db::{AuditEntry, AuditFilter, AuditVerification, Database};
 pub use error::{Error, Result};
 pub use export::{ExportFormat, FullBackup, ImportStats, TransactionExportOptions};
 pub use model_router::{ModelRouter, RouterConfig, TaskConfig, TaskType};
 pub use prompts::{Prompt, PromptId, PromptInfo, PromptLibrary};
 pub use tags::{BackfillResult, TagAssigner, TagAssignment};
+pub use trainer::{
+    FakeTrainer, LlamaCppTrainer, MlxTrainer, Trainer, TrainerBackend, TrainingArtifacts,
+};
 pub use training::{TrainingDataGenerator, TrainingExample, TrainingExportStats, TrainingTask};
 pub use training_pipeline::{
     ExperimentStatus, PipelineConfig, TrainingExperiment, TrainingPipeline,
@@ -0,0 +1,233 @@
+//! Built-in authentication primitives
+//!
+//! Password hashing (Argon2id), TOTP codes (RFC 6238) and random tokens for
+//! `hone serve` deployments that don't sit behind Cloudflare Access. Sessions,
+//! passkeys and rate limiting live in `hone-server`; this module is the part
+//! the CLI needs too (setting passwords, resetting TOTP).
+
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+use crate::error::{Error, Result};
+
+/// Minimum password length for local accounts
+pub const MIN_PASSWORD_LENGTH: usize = 10;
+
+/// TOTP time step in seconds
+const TOTP_STEP_SECS: i64 = 30;
+
+/// TOTP code digits
+const TOTP_DIGITS: u32 = 6;
+
+/// Accepted clock drift, in steps, on either side of the current one
+const TOTP_SKEW_STEPS: i64 = 1;
+
+/// Fill a buffer from the OS random number generator
+pub fn random_bytes(buf: &mut [u8]) -> Result<()> {
+    getrandom::getrandom(buf)
+        .map_err(|e| Error::InvalidData(format!("Random number generator failed: {}", e)))
+}
+
+/// Generate a random hex token with `bytes` bytes of entropy (session IDs, CSRF tokens)
+pub fn random_token(bytes: usize) -> Result<String> {
+    let mut buf = vec![0u8; bytes];
+    random_bytes(&mut buf)?;
+    Ok(hex::encode(buf))
+}
+
+/// Hash a password with Argon2id and a random salt (PHC string format)
+pub fn hash_password(password: &str) -> Result<String> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(Error::InvalidData(format!(
+            "Password must be at least {} characters",
+            MIN_PASSWORD_LENGTH
+        )));
+    }
+
+    let mut salt = [0u8; 16];
+    random_bytes(&mut salt)?;
+    let salt = SaltString::encode_b64(&salt)
+        .map_err(|e| Error::InvalidData(format!("Failed to create salt: {}", e)))?;
+
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| Error::InvalidData(format!("Failed to hash password: {}", e)))?;
+    Ok(hash.to_string())
+}
+
+/// Check a password against a stored PHC hash
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    match PasswordHash::new(hash) {
+        Ok(parsed) => Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Generate a new base32 TOTP secret (160 bits, as recommended by RFC 4226)
+pub fn generate_totp_secret() -> Result<String> {
+    let mut secret = [0u8; 20];
+    random_bytes(&mut secret)?;
+    Ok(base32_encode(&secret))
+}
+
+/// `otpauth://` URL for authenticator apps (rendered as a QR code by the UI)
+pub fn totp_uri(secret: &str, account: &str) -> String {
+    format!(
+        "otpauth://totp/Hone:{}?secret={}&issuer=Hone&digits={}&period={}",
+        account.replace(' ', "%20"),
+        secret,
+        TOTP_DIGITS,
+        TOTP_STEP_SECS
+    )
+}
+
+/// TOTP code for a secret at a Unix timestamp
+pub fn totp_code(secret: &str, unix_time: i64) -> Result<String> {
+    let key = base32_decode(secret)
+        .ok_or_else(|| Error::InvalidData("Invalid TOTP secret".to_string()))?;
+    Ok(hotp(&key, (unix_time / TOTP_STEP_SECS) as u64))
+}
+
+/// Check a TOTP code, allowing one step of clock drift either way
+///
+/// Returns the time step the code matched, so callers can refuse a code
+/// that was already used (see `Database::accept_totp_step`).
+pub fn verify_totp(secret: &str, code: &str, unix_time: i64) -> Option<i64> {
+    let code = code.trim().replace(' ', "");
+    if code.len() != TOTP_DIGITS as usize {
+        return None;
+    }
+    let key = base32_decode(secret)?;
+    let step = unix_time / TOTP_STEP_SECS;
+    (-TOTP_SKEW_STEPS..=TOTP_SKEW_STEPS)
+        .map(|skew| step + skew)
+        .find(|&counter| counter >= 0 && hotp(&key, counter as u64) == code)
+}
+
+/// HOTP (RFC 4226) with HMAC-SHA1
+fn hotp(key: &[u8], counter: u64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    )
+}
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// RFC 4648 base32 without padding (the format authenticator apps expect)
+fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+/// Decode base32 (case-insensitive, ignores padding and spaces)
+fn base32_decode(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(s.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in s.chars().filter(|c| *c != '=' && !c.is_whitespace()) {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a as char == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    if out.is_empty() {
+        None
+    } else {
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password_hash_roundtrip() {
+        let hash = hash_password("correct horse battery").unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(verify_password("correct horse battery", &hash));
+        assert!(!verify_password("wrong password!!", &hash));
+        assert!(!verify_password("correct horse battery", "not-a-hash"));
+
+        // Salts are random
+        assert_ne!(hash, hash_password("correct horse battery").unwrap());
+    }
+
+    #[test]
+    fn test_password_minimum_length() {
+        assert!(hash_password("short").is_err());
+    }
+
+    #[test]
+    fn test_totp_rfc6238_vectors() {
+        // RFC 6238 Appendix B, SHA1 secret "12345678901234567890" (6-digit truncation)
+        let secret = base32_encode(b"12345678901234567890");
+        assert_eq!(secret, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(totp_code(&secret, 59).unwrap(), "287082");
+        assert_eq!(totp_code(&secret, 1111111109).unwrap(), "081804");
+        assert_eq!(totp_code(&secret, 1234567890).unwrap(), "005924");
+        assert_eq!(totp_code(&secret, 2000000000).unwrap(), "279037");
+    }
+
+    #[test]
+    fn test_verify_totp_allows_one_step_of_drift() {
+        let secret = generate_totp_secret().unwrap();
+        let now = 1_700_000_000;
+        let code = totp_code(&secret, now).unwrap();
+
+        let step = now / TOTP_STEP_SECS;
+        assert_eq!(verify_totp(&secret, &code, now), Some(step));
+        assert_eq!(verify_totp(&secret, &code, now + 30), Some(step));
+        assert_eq!(verify_totp(&secret, &code, now - 30), Some(step));
+        assert_eq!(verify_totp(&secret, &code, now + 90), None);
+        assert_eq!(verify_totp(&secret, "12345", now), None);
+        assert_eq!(verify_totp("!!!", &code, now), None);
+    }
+
+    #[test]
+    fn test_base32_roundtrip() {
+        let data = [0u8, 1, 2, 250, 251, 252, 253];
+        assert_eq!(base32_decode(&base32_encode(&data)).unwrap(), data);
+        assert_eq!(
+            base32_decode("gezd gnbv").unwrap(),
+            base32_decode("GEZDGNBV").unwrap()
+        );
+    }
+}
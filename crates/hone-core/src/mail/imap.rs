@@ -0,0 +1,230 @@
+//! IMAP mailbox reader
+//!
+//! A minimal read-only IMAP client: optional STARTTLS or implicit TLS,
+//! LOGIN, EXAMINE (so nothing is marked read), UID SEARCH SINCE and
+//! UID FETCH BODY.PEEK[] one message at a time.
+
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::net::tls::Stream;
+
+/// Timeout for connecting and for each read
+const IMAP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Largest literal (message) we'll buffer; matches common provider
+/// attachment limits
+const MAX_LITERAL_BYTES: usize = 25 * 1024 * 1024;
+
+/// Connection security
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImapTls {
+    /// Plain text (local servers and bridges only)
+    None,
+    /// Upgrade with STARTTLS (port 143)
+    Starttls,
+    /// Implicit TLS (port 993)
+    #[default]
+    Tls,
+}
+
+impl ImapTls {
+    pub fn default_port(&self) -> u16 {
+        match self {
+            ImapTls::None | ImapTls::Starttls => 143,
+            ImapTls::Tls => 993,
+        }
+    }
+}
+
+/// One logged-in IMAP session
+pub struct ImapSession {
+    stream: Stream,
+    buf: Vec<u8>,
+    next_tag: u32,
+}
+
+impl ImapSession {
+    /// Connect and log in
+    pub async fn connect(
+        host: &str,
+        port: u16,
+        tls: ImapTls,
+        username: &str,
+        password: &str,
+    ) -> Result<Self> {
+        let stream =
+            Stream::connect(host, port, tls == ImapTls::Tls, IMAP_TIMEOUT, Error::Mail).await?;
+        let mut session = Self {
+            stream,
+            buf: Vec::new(),
+            next_tag: 1,
+        };
+
+        let greeting = session.read_line().await?;
+        if !greeting.starts_with("* OK") && !greeting.starts_with("* PREAUTH") {
+            return Err(Error::Mail(format!(
+                "Unexpected IMAP greeting: {}",
+                greeting
+            )));
+        }
+
+        if tls == ImapTls::Starttls {
+            session.command("STARTTLS").await?;
+            session.stream = session
+                .stream
+                .starttls(host, IMAP_TIMEOUT, Error::Mail)
+                .await?;
+            session.buf.clear();
+        }
+
+        session
+            .command(&format!("LOGIN {} {}", quote(username), quote(password)))
+            .await
+            .map_err(|e| Error::Mail(format!("IMAP login failed: {}", e)))?;
+        Ok(session)
+    }
+
+    /// Open a folder read-only
+    pub async fn examine(&mut self, folder: &str) -> Result<()> {
+        self.command(&format!("EXAMINE {}", quote(folder))).await?;
+        Ok(())
+    }
+
+    /// UIDs of messages received on or after a date
+    pub async fn search_since(&mut self, since: NaiveDate) -> Result<Vec<u32>> {
+        let lines = self
+            .command(&format!("UID SEARCH SINCE {}", since.format("%d-%b-%Y")))
+            .await?;
+        let mut uids: Vec<u32> = lines
+            .iter()
+            .filter_map(|line| line.strip_prefix("* SEARCH"))
+            .flat_map(|rest| rest.split_whitespace().filter_map(|n| n.parse().ok()))
+            .collect();
+        uids.sort_unstable();
+        Ok(uids)
+    }
+
+    /// Raw RFC 5322 message by UID, without setting \Seen
+    pub async fn fetch(&mut self, uid: u32) -> Result<Vec<u8>> {
+        let tag = self.send(&format!("UID FETCH {} BODY.PEEK[]", uid)).await?;
+        let mut message = None;
+        loop {
+            let line = self.read_line().await?;
+            if let Some(status) = line.strip_prefix(&format!("{} ", tag)) {
+                check_status(status)?;
+                break;
+            }
+            // `* 1 FETCH (UID 7 BODY[] {1234}` is followed by 1234 raw bytes
+            if let Some(len) = literal_len(&line) {
+                let data = self.read_exact(len).await?;
+                if line.contains("BODY[]") && message.is_none() {
+                    message = Some(data);
+                }
+            }
+        }
+        message.ok_or_else(|| Error::Mail(format!("IMAP message {} not found", uid)))
+    }
+
+    /// Log out; errors don't matter once we're done
+    pub async fn logout(mut self) {
+        let _ = self.command("LOGOUT").await;
+    }
+
+    async fn send(&mut self, command: &str) -> Result<String> {
+        let tag = format!("h{}", self.next_tag);
+        self.next_tag += 1;
+        self.write(format!("{} {}\r\n", tag, command).as_bytes())
+            .await?;
+        Ok(tag)
+    }
+
+    /// Send a command and collect its untagged responses
+    async fn command(&mut self, command: &str) -> Result<Vec<String>> {
+        let tag = self.send(command).await?;
+        let mut untagged = Vec::new();
+        loop {
+            let line = self.read_line().await?;
+            if let Some(status) = line.strip_prefix(&format!("{} ", tag)) {
+                check_status(status)?;
+                return Ok(untagged);
+            }
+            if let Some(len) = literal_len(&line) {
+                self.read_exact(len).await?;
+            }
+            untagged.push(line);
+        }
+    }
+
+    async fn fill(&mut self) -> Result<()> {
+        let mut chunk = [0u8; 8192];
+        let n = self
+            .stream
+            .read(&mut chunk, IMAP_TIMEOUT, Error::Mail)
+            .await?;
+        if n == 0 {
+            return Err(Error::Mail("IMAP server closed the connection".to_string()));
+        }
+        self.buf.extend_from_slice(&chunk[..n]);
+        Ok(())
+    }
+
+    async fn read_line(&mut self) -> Result<String> {
+        loop {
+            if let Some(pos) = self.buf.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buf.drain(..=pos).collect();
+                return Ok(String::from_utf8_lossy(&line).trim_end().to_string());
+            }
+            self.fill().await?;
+        }
+    }
+
+    async fn read_exact(&mut self, len: usize) -> Result<Vec<u8>> {
+        // The length comes from the server; don't let it size our buffer
+        if len > MAX_LITERAL_BYTES {
+            return Err(Error::Mail(format!(
+                "IMAP literal of {} bytes is over the {} byte limit",
+                len, MAX_LITERAL_BYTES
+            )));
+        }
+        while self.buf.len() < len {
+            self.fill().await?;
+        }
+        Ok(self.buf.drain(..len).collect())
+    }
+
+    async fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.stream.write_all(data).await?;
+        Ok(())
+    }
+}
+
+/// `OK ...` passes; `NO`/`BAD` become errors
+fn check_status(status: &str) -> Result<()> {
+    if status.starts_with("OK") {
+        Ok(())
+    } else {
+        Err(Error::Mail(format!("IMAP error: {}", status)))
+    }
+}
+
+/// Length of a `{n}` literal ending a response line
+fn literal_len(line: &str) -> Option<usize> {
+    let open = line.strip_suffix('}')?.rfind('{')?;
+    line[open + 1..line.len() - 1].parse().ok()
+}
+
+/// IMAP quoted string
+fn quote(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace(['\r', '\n'], "")
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+    )
+}
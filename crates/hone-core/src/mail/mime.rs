@@ -0,0 +1,618 @@
+//! Minimal MIME parsing for receipt emails
+//!
+//! Handles what order confirmations actually use: folded headers, RFC 2047
+//! encoded words, nested multiparts, forwarded `message/rfc822` parts,
+//! base64 and quoted-printable bodies, and UTF-8 or Latin-1 text.
+
+use std::collections::HashMap;
+
+use base64::Engine;
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::ai::ReceiptFormat;
+
+/// Receipt text longer than this is cut before it goes to the model
+pub(crate) const MAX_TEXT_CHARS: usize = 12_000;
+
+/// A parsed email
+#[derive(Debug, Clone)]
+pub struct MailMessage {
+    pub message_id: Option<String>,
+    pub from: Option<String>,
+    pub subject: Option<String>,
+    pub date: Option<NaiveDate>,
+    /// Leaf parts in message order
+    pub parts: Vec<MailPart>,
+}
+
+/// One leaf MIME part with its body decoded
+#[derive(Debug, Clone)]
+pub struct MailPart {
+    /// Lowercase MIME type, e.g. `text/html`
+    pub content_type: String,
+    pub charset: Option<String>,
+    pub filename: Option<String>,
+    /// `Content-Disposition: attachment`
+    pub attachment: bool,
+    /// Referenced from the HTML body (`Content-ID`), e.g. a logo
+    pub embedded: bool,
+    pub body: Vec<u8>,
+}
+
+impl MailPart {
+    /// Body as text, decoded from its charset
+    pub fn text(&self) -> String {
+        decode_charset(&self.body, self.charset.as_deref())
+    }
+}
+
+/// What kind of receipt a document holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentKind {
+    Image,
+    Pdf,
+    Html,
+    Text,
+}
+
+impl DocumentKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentKind::Image => "image",
+            DocumentKind::Pdf => "pdf",
+            DocumentKind::Html => "html",
+            DocumentKind::Text => "text",
+        }
+    }
+
+    /// Format for the receipt parsing pipeline
+    pub fn format(&self) -> ReceiptFormat {
+        match self {
+            DocumentKind::Image => ReceiptFormat::Image,
+            DocumentKind::Pdf => ReceiptFormat::Pdf,
+            DocumentKind::Html => ReceiptFormat::Html,
+            DocumentKind::Text => ReceiptFormat::Text,
+        }
+    }
+}
+
+/// A candidate receipt found in an email
+#[derive(Debug, Clone)]
+pub struct ReceiptDocument {
+    pub kind: DocumentKind,
+    pub filename: Option<String>,
+    /// File extension to store it under
+    pub extension: String,
+    /// Bytes to store (and hash for dedup)
+    pub data: Vec<u8>,
+    /// Readable text of HTML and plain-text bodies
+    pub text: Option<String>,
+}
+
+impl MailMessage {
+    /// Parse a raw RFC 5322 message
+    pub fn parse(raw: &[u8]) -> Self {
+        let (headers, body) = split_entity(raw);
+        let mut parts = Vec::new();
+        collect_parts(&headers, body, &mut parts, 0);
+
+        let from = headers.get("from").map(|v| decode_words(v));
+        let subject = headers.get("subject").map(|v| decode_words(v));
+        let message_id = headers
+            .get("message-id")
+            .map(|v| v.trim().trim_start_matches('<').trim_end_matches('>'))
+            .filter(|v| !v.is_empty())
+            .map(str::to_string);
+        let date = headers
+            .get("date")
+            .and_then(|v| chrono::DateTime::parse_from_rfc2822(v.trim()).ok())
+            .map(|d| d.date_naive());
+
+        Self {
+            message_id,
+            from,
+            subject,
+            date,
+            parts,
+        }
+    }
+
+    /// Sender address, lowercased (`Shop <orders@shop.com>` → `orders@shop.com`)
+    pub fn from_address(&self) -> Option<String> {
+        let from = self.from.as_deref()?;
+        let address = match (from.rfind('<'), from.rfind('>')) {
+            (Some(start), Some(end)) if start < end => &from[start + 1..end],
+            _ => from,
+        };
+        Some(address.trim().to_lowercase())
+    }
+
+    /// Sender display name (`"Shop" <orders@shop.com>` → `Shop`)
+    pub fn from_name(&self) -> Option<String> {
+        let from = self.from.as_deref()?;
+        let name = from[..from.find('<')?].trim().trim_matches('"').trim();
+        (!name.is_empty()).then(|| name.to_string())
+    }
+
+    /// Receipts in this message
+    ///
+    /// PDF and image attachments win; an email that has none is treated as
+    /// the receipt itself (HTML body, else plain text). Images embedded in
+    /// the HTML (logos, banners) are never receipts.
+    pub fn receipt_documents(&self) -> Vec<ReceiptDocument> {
+        let attachments: Vec<ReceiptDocument> = self
+            .parts
+            .iter()
+            .filter_map(|part| {
+                let extension = part
+                    .filename
+                    .as_deref()
+                    .and_then(|f| f.rsplit_once('.'))
+                    .map(|(_, ext)| ext.to_lowercase());
+                let kind = if part.content_type == "application/pdf"
+                    || extension.as_deref() == Some("pdf")
+                {
+                    DocumentKind::Pdf
+                } else if part.content_type.starts_with("image/") && !part.embedded {
+                    DocumentKind::Image
+                } else {
+                    return None;
+                };
+                if part.body.is_empty() {
+                    return None;
+                }
+                let extension = match kind {
+                    DocumentKind::Pdf => "pdf".to_string(),
+                    _ => extension
+                        .filter(|e| {
+                            ["jpg", "jpeg", "png", "gif", "webp", "heic"].contains(&e.as_str())
+                        })
+                        .unwrap_or_else(|| {
+                            match part.content_type.as_str() {
+                                "image/png" => "png",
+                                "image/gif" => "gif",
+                                "image/webp" => "webp",
+                                "image/heic" => "heic",
+                                _ => "jpg",
+                            }
+                            .to_string()
+                        }),
+                };
+                Some(ReceiptDocument {
+                    kind,
+                    filename: part.filename.clone(),
+                    extension,
+                    data: part.body.clone(),
+                    text: None,
+                })
+            })
+            .collect();
+        if !attachments.is_empty() {
+            return attachments;
+        }
+
+        let body = |content_type: &str| {
+            self.parts
+                .iter()
+                .find(|p| p.content_type == content_type && !p.attachment)
+        };
+        if let Some(part) = body("text/html") {
+            let html = part.text();
+            let text = html_to_text(&html);
+            if !text.is_empty() {
+                return vec![ReceiptDocument {
+                    kind: DocumentKind::Html,
+                    filename: None,
+                    extension: "html".to_string(),
+                    data: html.into_bytes(),
+                    text: Some(truncate(text)),
+                }];
+            }
+        }
+        if let Some(part) = body("text/plain") {
+            let text = part.text().trim().to_string();
+            if !text.is_empty() {
+                return vec![ReceiptDocument {
+                    kind: DocumentKind::Text,
+                    filename: None,
+                    extension: "txt".to_string(),
+                    data: text.clone().into_bytes(),
+                    text: Some(truncate(text)),
+                }];
+            }
+        }
+        Vec::new()
+    }
+}
+
+/// Cut text to `MAX_TEXT_CHARS` (also used for receipt files)
+pub(crate) fn truncate(text: String) -> String {
+    match text.char_indices().nth(MAX_TEXT_CHARS) {
+        Some((end, _)) => text[..end].to_string(),
+        None => text,
+    }
+}
+
+/// Split an entity into unfolded headers (lowercase names) and its body
+fn split_entity(raw: &[u8]) -> (HashMap<String, String>, &[u8]) {
+    let (head, body) = match find(raw, b"\r\n\r\n") {
+        Some(pos) => (&raw[..pos], &raw[pos + 4..]),
+        None => match find(raw, b"\n\n") {
+            Some(pos) => (&raw[..pos], &raw[pos + 2..]),
+            None => (raw, &raw[raw.len()..]),
+        },
+    };
+
+    let mut headers = HashMap::new();
+    let mut current: Option<(String, String)> = None;
+    for line in String::from_utf8_lossy(head).lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = current.as_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        if let Some((name, value)) = current.take() {
+            headers.entry(name).or_insert(value);
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            current = Some((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+    if let Some((name, value)) = current {
+        headers.entry(name).or_insert(value);
+    }
+    (headers, body)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Walk an entity, pushing its leaf parts
+fn collect_parts(
+    headers: &HashMap<String, String>,
+    body: &[u8],
+    parts: &mut Vec<MailPart>,
+    depth: usize,
+) {
+    // Guard against pathological nesting
+    if depth > 10 {
+        return;
+    }
+    let (content_type, params) = headers
+        .get("content-type")
+        .map(|v| parse_header_params(v))
+        .unwrap_or_else(|| ("text/plain".to_string(), HashMap::new()));
+
+    if content_type.starts_with("multipart/") {
+        if let Some(boundary) = params.get("boundary") {
+            for section in split_multipart(body, boundary) {
+                let (part_headers, part_body) = split_entity(section);
+                collect_parts(&part_headers, part_body, parts, depth + 1);
+            }
+        }
+        return;
+    }
+
+    let encoding = headers
+        .get("content-transfer-encoding")
+        .map(|v| v.trim().to_lowercase())
+        .unwrap_or_default();
+    let decoded = match encoding.as_str() {
+        "base64" => decode_base64(body),
+        "quoted-printable" => decode_quoted_printable(body),
+        _ => body.to_vec(),
+    };
+
+    if content_type == "message/rfc822" {
+        // Forwarded receipt: its parts are as good as ours
+        let (inner_headers, inner_body) = split_entity(&decoded);
+        collect_parts(&inner_headers, inner_body, parts, depth + 1);
+        return;
+    }
+
+    let (disposition, disposition_params) = headers
+        .get("content-disposition")
+        .map(|v| parse_header_params(v))
+        .unwrap_or_default();
+    let filename = disposition_params
+        .get("filename")
+        .or_else(|| params.get("name"))
+        .map(|f| decode_words(f));
+
+    parts.push(MailPart {
+        content_type,
+        charset: params.get("charset").cloned(),
+        filename,
+        attachment: disposition == "attachment",
+        embedded: headers.contains_key("content-id") && disposition != "attachment",
+        body: decoded,
+    });
+}
+
+/// Sections between `--boundary` delimiters (preamble and epilogue dropped)
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut sections = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut pos = 0;
+
+    while pos < body.len() {
+        let end = body[pos..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map(|i| pos + i + 1)
+            .unwrap_or(body.len());
+        let line = &body[pos..end];
+        let trimmed = trim_line_end(line);
+        if trimmed.starts_with(delimiter.as_bytes()) {
+            let rest = &trimmed[delimiter.len()..];
+            if rest.is_empty() || rest == b"--" || rest.iter().all(|b| b.is_ascii_whitespace()) {
+                if let Some(s) = start {
+                    // The line break before a delimiter belongs to it
+                    sections.push(trim_line_end(&body[s..pos]));
+                }
+                if rest == b"--" {
+                    return sections;
+                }
+                start = Some(end);
+            }
+        }
+        pos = end;
+    }
+    if let Some(s) = start {
+        sections.push(&body[s..]);
+    }
+    sections
+}
+
+fn trim_line_end(line: &[u8]) -> &[u8] {
+    let mut end = line.len();
+    if end > 0 && line[end - 1] == b'\n' {
+        end -= 1;
+    }
+    if end > 0 && line[end - 1] == b'\r' {
+        end -= 1;
+    }
+    &line[..end]
+}
+
+/// `type/subtype; key=value; key="quoted"` → lowercase value and parameters
+///
+/// RFC 2231 extended values (`filename*=utf-8''na%C3%AFve.pdf`) are decoded;
+/// continuations (`filename*0=`) are not.
+fn parse_header_params(value: &str) -> (String, HashMap<String, String>) {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in value.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            ';' if !quoted => pieces.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    pieces.push(current);
+
+    let mut pieces = pieces.into_iter();
+    let main = pieces.next().unwrap_or_default().trim().to_lowercase();
+    let mut params = HashMap::new();
+    for piece in pieces {
+        let Some((key, value)) = piece.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let value = value.trim().trim_matches('"');
+        match key.strip_suffix('*') {
+            Some(key) => {
+                let mut fields = value.splitn(3, '\'');
+                let charset = fields.next().unwrap_or_default().to_string();
+                let encoded = fields.nth(1).unwrap_or(value);
+                let bytes = percent_decode(encoded);
+                params.insert(key.to_string(), decode_charset(&bytes, Some(&charset)));
+            }
+            None => {
+                params.insert(key, value.to_string());
+            }
+        }
+    }
+    (main, params)
+}
+
+fn percent_decode(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes
+                .get(i + 1..i + 3)
+                .and_then(|h| std::str::from_utf8(h).ok())
+                .and_then(|h| u8::from_str_radix(h, 16).ok());
+            if let Some(byte) = hex {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    out
+}
+
+fn decode_base64(body: &[u8]) -> Vec<u8> {
+    let cleaned: Vec<u8> = body
+        .iter()
+        .copied()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    base64::engine::general_purpose::STANDARD
+        .decode(&cleaned)
+        .or_else(|_| base64::engine::general_purpose::STANDARD_NO_PAD.decode(&cleaned))
+        .unwrap_or_default()
+}
+
+fn decode_quoted_printable(body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len());
+    let mut i = 0;
+    while i < body.len() {
+        if body[i] != b'=' {
+            out.push(body[i]);
+            i += 1;
+            continue;
+        }
+        // Soft line break
+        if body[i + 1..].starts_with(b"\r\n") {
+            i += 3;
+            continue;
+        }
+        if body[i + 1..].starts_with(b"\n") {
+            i += 2;
+            continue;
+        }
+        let hex = body
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(b'=');
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Decode text from its charset (UTF-8, or Latin-1 for the 8-bit charsets)
+fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    let charset = charset.unwrap_or("utf-8").to_lowercase();
+    let latin1 = charset.starts_with("iso-8859")
+        || charset.starts_with("windows-125")
+        || charset == "latin1"
+        || charset == "cp1252";
+    if latin1 && std::str::from_utf8(bytes).is_err() {
+        bytes.iter().map(|&b| b as char).collect()
+    } else {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+/// Decode RFC 2047 encoded words (`=?UTF-8?B?...?=`) in a header value
+pub fn decode_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut last_was_word = false;
+
+    while let Some(start) = rest.find("=?") {
+        let decoded = parse_encoded_word(&rest[start..]);
+        let Some((text, len)) = decoded else {
+            out.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            last_was_word = false;
+            continue;
+        };
+        let between = &rest[..start];
+        // Whitespace between adjacent encoded words is dropped
+        if !(last_was_word && between.trim().is_empty()) {
+            out.push_str(between);
+        }
+        out.push_str(&text);
+        rest = &rest[start + len..];
+        last_was_word = true;
+    }
+    out.push_str(rest);
+    out.trim().to_string()
+}
+
+/// One encoded word at the start of `s` → (text, length consumed)
+fn parse_encoded_word(s: &str) -> Option<(String, usize)> {
+    let inner = s.strip_prefix("=?")?;
+    let (charset, inner) = inner.split_once('?')?;
+    let (encoding, inner) = inner.split_once('?')?;
+    let end = inner.find("?=")?;
+    let encoded = &inner[..end];
+    let bytes = match encoding.to_ascii_uppercase().as_str() {
+        "B" => base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .ok()?,
+        "Q" => decode_quoted_printable(encoded.replace('_', " ").as_bytes()),
+        _ => return None,
+    };
+    let len = 2 + charset.len() + 1 + encoding.len() + 1 + end + 2;
+    Some((decode_charset(&bytes, Some(charset)), len))
+}
+
+/// Readable text from an HTML email: one line per block, table cells
+/// separated by spaces, scripts and styles dropped
+pub fn html_to_text(html: &str) -> String {
+    let hidden = Regex::new(r"(?is)<(script|style|head|title)\b.*?</(script|style|head|title)\s*>")
+        .expect("valid regex");
+    let comments = Regex::new(r"(?s)<!--.*?-->").expect("valid regex");
+    let breaks =
+        Regex::new(r"(?i)<br\s*/?>|</(p|div|tr|li|h[1-6]|table|section)\s*>").expect("valid regex");
+    let cells = Regex::new(r"(?i)</(td|th)\s*>").expect("valid regex");
+    let tags = Regex::new(r"(?s)<[^>]*>").expect("valid regex");
+
+    let text = hidden.replace_all(html, " ");
+    let text = comments.replace_all(&text, " ");
+    let text = breaks.replace_all(&text, "\n");
+    let text = cells.replace_all(&text, " ");
+    let text = tags.replace_all(&text, "");
+    let text = decode_entities(&text);
+
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+    lines.join("\n")
+}
+
+fn decode_entities(text: &str) -> String {
+    let entity = Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").expect("valid regex");
+    entity
+        .replace_all(text, |caps: &regex::Captures| {
+            let name = &caps[1];
+            let decoded = if let Some(hex) = name.strip_prefix("#x").or(name.strip_prefix("#X")) {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            } else if let Some(dec) = name.strip_prefix('#') {
+                dec.parse().ok().and_then(char::from_u32)
+            } else {
+                match name {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some(' '),
+                    "euro" => Some('€'),
+                    "pound" => Some('£'),
+                    "copy" => Some('©'),
+                    "reg" => Some('®'),
+                    "ndash" => Some('–'),
+                    "mdash" => Some('—'),
+                    _ => None,
+                }
+            };
+            decoded
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
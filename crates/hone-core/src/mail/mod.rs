@@ -0,0 +1,581 @@
+//! Email receipt ingestion
+//!
+//! Reads order confirmations from local Maildir folders, mbox files or IMAP
+//! accounts and files each receipt the same way an upload does: the file is
+//! saved in the receipts folder, deduplicated by content hash, parsed by the
+//! AI backend and left pending for `auto_match_receipts`.
+//!
+//! PDF and image attachments are the receipt when an email has them;
+//! otherwise the HTML (or plain-text) body is. Every document goes through
+//! `ai::parse_receipt_document`, so bodies and PDF text layers are parsed as
+//! text. Bodies that don't look like a receipt are skipped. Mailboxes are only read: each
+//! message is remembered by Message-ID so later scans skip it.
+//!
+//! Mailboxes are configured in `~/.local/share/hone/config/mail.toml`
+//! (override with `HONE_MAIL_CONFIG`):
+//!
+//! ```toml
+//! receipts_dir = "/home/me/hone/receipts"   # default: ./receipts
+//! senders = ["*@amazon.com", "receipts@*"]  # default: every sender
+//! since_days = 30                           # IMAP search window
+//!
+//! [[mailboxes]]
+//! name = "receipts-folder"
+//! type = "maildir"
+//! path = "/home/me/Mail/Receipts"
+//!
+//! [[mailboxes]]
+//! name = "gmail"
+//! type = "imap"
+//! host = "imap.gmail.com"
+//! username = "me@gmail.com"
+//! password = "app-password"
+//! folder = "Receipts"
+//! ```
+
+mod imap;
+mod mime;
+
+pub use imap::{ImapSession, ImapTls};
+pub(crate) use mime::truncate;
+pub use mime::{decode_words, html_to_text, DocumentKind, MailMessage, MailPart, ReceiptDocument};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::ai::{parse_receipt_document, AIClient, ParsedReceipt};
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::models::{NewReceipt, ReceiptRole, ReceiptStatus};
+use crate::watch::glob_match;
+
+/// Audit actor for receipts filed from email
+pub const MAIL_ACTOR: &str = "mail";
+
+fn default_since_days() -> u32 {
+    30
+}
+
+fn default_folder() -> String {
+    "INBOX".to_string()
+}
+
+/// A mailbox to read receipts from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailboxConfig {
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: MailboxKind,
+}
+
+/// Mailbox-specific settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MailboxKind {
+    /// Maildir folder (messages in `new/` and `cur/`)
+    Maildir { path: PathBuf },
+    /// mbox file
+    Mbox { path: PathBuf },
+    /// IMAP account
+    Imap {
+        host: String,
+        #[serde(default)]
+        port: Option<u16>,
+        #[serde(default)]
+        tls: ImapTls,
+        username: String,
+        password: String,
+        #[serde(default = "default_folder")]
+        folder: String,
+    },
+}
+
+/// Email ingestion configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailConfig {
+    /// Where receipt files are saved (default: `receipts`)
+    #[serde(default)]
+    pub receipts_dir: Option<PathBuf>,
+    /// Sender patterns (`*` and `?` wildcards); empty accepts every sender
+    #[serde(default)]
+    pub senders: Vec<String>,
+    /// Only search IMAP messages received this many days back
+    #[serde(default = "default_since_days")]
+    pub since_days: u32,
+    #[serde(default)]
+    pub mailboxes: Vec<MailboxConfig>,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            receipts_dir: None,
+            senders: Vec::new(),
+            since_days: default_since_days(),
+            mailboxes: Vec::new(),
+        }
+    }
+}
+
+impl MailConfig {
+    /// Parse and validate a TOML config
+    pub fn parse(content: &str) -> Result<Self> {
+        let config: MailConfig = toml::from_str(content)
+            .map_err(|e| Error::Mail(format!("Invalid mail config: {}", e)))?;
+        let mut names = std::collections::HashSet::new();
+        for mailbox in &config.mailboxes {
+            if mailbox.name.trim().is_empty() {
+                return Err(Error::Mail("Mailbox name cannot be empty".to_string()));
+            }
+            if !names.insert(mailbox.name.as_str()) {
+                return Err(Error::Mail(format!(
+                    "Duplicate mailbox name: {}",
+                    mailbox.name
+                )));
+            }
+        }
+        if config.senders.iter().any(|s| s.trim().is_empty()) {
+            return Err(Error::Mail("Empty sender pattern".to_string()));
+        }
+        Ok(config)
+    }
+
+    /// Load from a path, or `HONE_MAIL_CONFIG`, or the default location
+    ///
+    /// Returns None when no config file exists.
+    pub fn load(path: Option<&Path>) -> Result<Option<Self>> {
+        let path = match path {
+            Some(p) => Some(p.to_path_buf()),
+            None => std::env::var("HONE_MAIL_CONFIG")
+                .ok()
+                .map(PathBuf::from)
+                .or_else(default_config_path),
+        };
+        let Some(path) = path.filter(|p| p.exists()) else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(&path)
+            .map_err(|e| Error::Mail(format!("Failed to read {}: {}", path.display(), e)))?;
+        Self::parse(&content).map(Some)
+    }
+
+    /// Add a mailbox (e.g. a Maildir or mbox from the command line)
+    pub fn with_mailbox(mut self, name: impl Into<String>, kind: MailboxKind) -> Self {
+        self.mailboxes.push(MailboxConfig {
+            name: name.into(),
+            kind,
+        });
+        self
+    }
+
+    /// Whether a sender address passes the `senders` filter
+    pub fn accepts_sender(&self, address: Option<&str>) -> bool {
+        if self.senders.is_empty() {
+            return true;
+        }
+        address.is_some_and(|a| self.senders.iter().any(|p| glob_match(p, a)))
+    }
+}
+
+/// Default config path
+pub fn default_config_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("hone").join("config").join("mail.toml"))
+}
+
+/// What happened to one receipt document
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum DocumentOutcome {
+    Created {
+        receipt_id: i64,
+        path: PathBuf,
+        /// Whether the AI backend extracted merchant, date and total
+        parsed: bool,
+    },
+    /// Same file already filed as this receipt
+    Duplicate { receipt_id: i64 },
+    /// Email body that isn't a receipt
+    NotReceipt,
+}
+
+/// A receipt document from one email
+#[derive(Debug, Clone, Serialize)]
+pub struct IngestedDocument {
+    pub kind: DocumentKind,
+    pub filename: Option<String>,
+    #[serde(flatten)]
+    pub outcome: DocumentOutcome,
+}
+
+/// An email handled by a scan
+#[derive(Debug, Clone, Serialize)]
+pub struct IngestedMessage {
+    pub mailbox: String,
+    pub from: Option<String>,
+    pub subject: Option<String>,
+    pub documents: Vec<IngestedDocument>,
+}
+
+/// A mailbox that couldn't be read
+#[derive(Debug, Clone, Serialize)]
+pub struct MailboxError {
+    pub mailbox: String,
+    pub error: String,
+}
+
+/// Result of scanning every configured mailbox
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MailScan {
+    pub messages: Vec<IngestedMessage>,
+    pub errors: Vec<MailboxError>,
+    /// Pending receipts auto-matched to transactions after the scan
+    pub matched: usize,
+    /// Pending receipts the matcher looked at
+    pub checked: usize,
+}
+
+impl MailScan {
+    /// Receipts created by this scan
+    pub fn created(&self) -> usize {
+        self.messages
+            .iter()
+            .flat_map(|m| &m.documents)
+            .filter(|d| matches!(d.outcome, DocumentOutcome::Created { .. }))
+            .count()
+    }
+}
+
+/// Files receipts found in mailboxes
+pub struct MailIngester {
+    db: Database,
+    config: MailConfig,
+    ai: Option<AIClient>,
+    receipts_dir: PathBuf,
+}
+
+impl MailIngester {
+    /// Create an ingester; without an AI backend receipts are filed unparsed
+    pub fn new(db: Database, config: MailConfig, ai: Option<AIClient>) -> Self {
+        let receipts_dir = config
+            .receipts_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("receipts"));
+        Self {
+            db,
+            config,
+            ai,
+            receipts_dir,
+        }
+    }
+
+    pub fn config(&self) -> &MailConfig {
+        &self.config
+    }
+
+    /// Read every mailbox, then auto-match the pending receipts
+    ///
+    /// A mailbox that can't be read is reported and the others still run.
+    pub async fn scan(&self, today: NaiveDate) -> Result<MailScan> {
+        let mut scan = MailScan::default();
+        for mailbox in &self.config.mailboxes {
+            match self.scan_mailbox(mailbox, today).await {
+                Ok(messages) => scan.messages.extend(messages),
+                Err(e) => {
+                    warn!("Failed to read mailbox {}: {}", mailbox.name, e);
+                    scan.errors.push(MailboxError {
+                        mailbox: mailbox.name.clone(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+        let (matched, checked) = self.db.auto_match_receipts()?;
+        scan.matched = matched;
+        scan.checked = checked;
+        Ok(scan)
+    }
+
+    /// Ingest the messages of one mailbox that haven't been seen yet
+    pub async fn scan_mailbox(
+        &self,
+        mailbox: &MailboxConfig,
+        today: NaiveDate,
+    ) -> Result<Vec<IngestedMessage>> {
+        let mut handled = Vec::new();
+        match &mailbox.kind {
+            MailboxKind::Maildir { path } => {
+                for raw in read_maildir(path)? {
+                    handled.extend(self.ingest_message(&mailbox.name, &raw).await?);
+                }
+            }
+            MailboxKind::Mbox { path } => {
+                for raw in read_mbox(path)? {
+                    handled.extend(self.ingest_message(&mailbox.name, &raw).await?);
+                }
+            }
+            MailboxKind::Imap {
+                host,
+                port,
+                tls,
+                username,
+                password,
+                folder,
+            } => {
+                let port = port.unwrap_or_else(|| tls.default_port());
+                let mut session =
+                    ImapSession::connect(host, port, *tls, username, password).await?;
+                session.examine(folder).await?;
+                let since = today - chrono::Duration::days(self.config.since_days as i64);
+                for uid in session.search_since(since).await? {
+                    let raw = session.fetch(uid).await?;
+                    handled.extend(self.ingest_message(&mailbox.name, &raw).await?);
+                }
+                session.logout().await;
+            }
+        }
+        Ok(handled)
+    }
+
+    /// File the receipts in one raw message
+    ///
+    /// Returns None for messages already scanned or from senders outside the
+    /// `senders` filter.
+    pub async fn ingest_message(
+        &self,
+        mailbox: &str,
+        raw: &[u8],
+    ) -> Result<Option<IngestedMessage>> {
+        let message = MailMessage::parse(raw);
+        let key = message
+            .message_id
+            .clone()
+            .unwrap_or_else(|| hex::encode(Sha256::digest(raw)));
+        if self.db.is_mail_message_ingested(&key)? {
+            return Ok(None);
+        }
+        if !self
+            .config
+            .accepts_sender(message.from_address().as_deref())
+        {
+            return Ok(None);
+        }
+
+        let mut documents = Vec::new();
+        for document in message.receipt_documents() {
+            let outcome = self.file_document(mailbox, &message, &document).await?;
+            documents.push(IngestedDocument {
+                kind: document.kind,
+                filename: document.filename.clone(),
+                outcome,
+            });
+        }
+
+        let created = documents
+            .iter()
+            .filter(|d| matches!(d.outcome, DocumentOutcome::Created { .. }))
+            .count();
+        self.db
+            .record_mail_message(&key, mailbox, message.subject.as_deref(), created)?;
+        Ok(Some(IngestedMessage {
+            mailbox: mailbox.to_string(),
+            from: message.from.clone(),
+            subject: message.subject.clone(),
+            documents,
+        }))
+    }
+
+    /// Save, parse and record one receipt document
+    async fn file_document(
+        &self,
+        mailbox: &str,
+        message: &MailMessage,
+        document: &ReceiptDocument,
+    ) -> Result<DocumentOutcome> {
+        let text = document.text.as_deref();
+        if text.is_some_and(|t| !looks_like_receipt(t)) {
+            return Ok(DocumentOutcome::NotReceipt);
+        }
+
+        let content_hash = hex::encode(Sha256::digest(&document.data));
+        if let Some(existing) = self.db.get_receipt_by_hash(&content_hash)? {
+            return Ok(DocumentOutcome::Duplicate {
+                receipt_id: existing.id,
+            });
+        }
+
+        let parsed = match &self.ai {
+            Some(ai) => {
+                match parse_receipt_document(
+                    ai,
+                    &self.db,
+                    &document.data,
+                    document.kind.format(),
+                    None,
+                )
+                .await
+                {
+                    Ok(parse) => Some(parse.receipt),
+                    Err(e) => {
+                        warn!("Failed to parse emailed receipt: {}", e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+        if text.is_some() && parsed.as_ref().is_some_and(is_empty_receipt) {
+            return Ok(DocumentOutcome::NotReceipt);
+        }
+
+        fs::create_dir_all(&self.receipts_dir)?;
+        let filename = format!(
+            "receipt_email_{}_{}.{}",
+            chrono::Utc::now().format("%Y%m%d_%H%M%S"),
+            &content_hash[..12],
+            document.extension
+        );
+        let path = self.receipts_dir.join(filename);
+        fs::write(&path, &document.data)?;
+        let path_str = path.to_string_lossy().to_string();
+
+        // Fall back to the email's own date and sender for matching
+        let receipt_date = parsed
+            .as_ref()
+            .and_then(|p| p.date.as_deref())
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .or(message.date);
+        let receipt_merchant = parsed
+            .as_ref()
+            .and_then(|p| p.merchant.clone())
+            .or_else(|| message.from_name());
+        let new_receipt = NewReceipt {
+            transaction_id: None,
+            image_path: Some(path_str.clone()),
+            image_data: None,
+            status: ReceiptStatus::Pending,
+            role: ReceiptRole::Primary,
+            receipt_date,
+            receipt_total: parsed.as_ref().and_then(|p| p.total),
+            receipt_merchant,
+            content_hash: Some(content_hash),
+        };
+        let receipt_id = self.db.create_receipt_full(&new_receipt)?;
+        if let Some(ref p) = parsed {
+            self.db
+                .update_receipt_parsed(receipt_id, &serde_json::to_string(p)?)?;
+        }
+
+        self.db.log_audit(
+            MAIL_ACTOR,
+            "upload",
+            Some("pending_receipt"),
+            Some(receipt_id),
+            Some(&format!(
+                "mailbox={}, kind={}, path={}, parsed={}",
+                mailbox,
+                document.kind.as_str(),
+                path_str,
+                parsed.is_some()
+            )),
+        )?;
+        info!(
+            "Filed {} receipt #{} from {}",
+            document.kind.as_str(),
+            receipt_id,
+            message.subject.as_deref().unwrap_or("(no subject)")
+        );
+
+        Ok(DocumentOutcome::Created {
+            receipt_id,
+            path,
+            parsed: parsed.is_some(),
+        })
+    }
+}
+
+/// Cheap check before spending a model call: an amount and a receipt word
+fn looks_like_receipt(text: &str) -> bool {
+    let amount = Regex::new(r"\d+[.,]\d{2}\b").expect("valid regex");
+    let lower = text.to_lowercase();
+    amount.is_match(text)
+        && ["total", "receipt", "order", "invoice", "paid", "payment"]
+            .iter()
+            .any(|word| lower.contains(word))
+}
+
+/// The model found nothing receipt-like
+fn is_empty_receipt(parsed: &ParsedReceipt) -> bool {
+    parsed.items.is_empty() && parsed.total.is_none()
+}
+
+/// Raw messages in a Maildir (`new/` then `cur/`, by filename)
+fn read_maildir(path: &Path) -> Result<Vec<Vec<u8>>> {
+    if !path.join("cur").is_dir() && !path.join("new").is_dir() {
+        return Err(Error::Mail(format!("Not a Maildir: {}", path.display())));
+    }
+    let mut messages = Vec::new();
+    for sub in ["new", "cur"] {
+        let dir = path.join(sub);
+        if !dir.is_dir() {
+            continue;
+        }
+        let mut files: Vec<PathBuf> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|p| {
+                p.is_file()
+                    && !p
+                        .file_name()
+                        .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+            })
+            .collect();
+        files.sort();
+        for file in files {
+            messages.push(fs::read(file)?);
+        }
+    }
+    Ok(messages)
+}
+
+/// Raw messages in an mbox file (mboxrd `>From ` quoting undone)
+fn read_mbox(path: &Path) -> Result<Vec<Vec<u8>>> {
+    let data = fs::read(path)
+        .map_err(|e| Error::Mail(format!("Failed to read {}: {}", path.display(), e)))?;
+    let mut messages = Vec::new();
+    let mut current: Option<Vec<u8>> = None;
+    let mut previous_blank = true;
+
+    for line in data.split_inclusive(|&b| b == b'\n') {
+        if line.starts_with(b"From ") && previous_blank {
+            if let Some(message) = current.take() {
+                messages.push(message);
+            }
+            current = Some(Vec::new());
+            previous_blank = false;
+            continue;
+        }
+        previous_blank = line == b"\n" || line == b"\r\n";
+        let Some(message) = current.as_mut() else {
+            continue;
+        };
+        let quoted = line.iter().take_while(|&&b| b == b'>').count();
+        if quoted > 0 && line[quoted..].starts_with(b"From ") {
+            message.extend_from_slice(&line[1..]);
+        } else {
+            message.extend_from_slice(line);
+        }
+    }
+    if let Some(message) = current {
+        messages.push(message);
+    }
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests;
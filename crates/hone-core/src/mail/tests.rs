@@ -0,0 +1,507 @@
+//! Email receipt ingestion tests
+
+use base64::Engine;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+
+use super::*;
+use crate::models::{Bank, NewTransaction};
+
+const HTML_RECEIPT: &str = "From: \"Mock Store\" <orders@mockstore.com>\r
+To: me@example.com\r
+Subject: =?UTF-8?Q?Your_order_=E2=80=94_thanks!?=\r
+Date: Mon, 15 Jan 2024 10:30:00 -0500\r
+Message-ID: <order-1001@mockstore.com>\r
+MIME-Version: 1.0\r
+Content-Type: multipart/alternative; boundary=\"alt\"\r
+\r
+--alt\r
+Content-Type: text/plain; charset=utf-8\r
+\r
+Order 1001. Total: $10.80\r
+--alt\r
+Content-Type: text/html; charset=utf-8\r
+Content-Transfer-Encoding: quoted-printable\r
+\r
+<html><head><style>td { color: red; }</style></head><body>\r
+<p>Order&nbsp;#1001</p><table><tr><td>Item 1</td><td>$10.00</td></tr>=\r
+<tr><td>Tax</td><td>$0.80</td></tr><tr><td>Total</td><td>$10.80</td></tr></table>\r
+</body></html>\r
+--alt--\r
+";
+
+const NEWSLETTER: &str = "From: Mock Store <news@mockstore.com>\r
+Subject: Big sale this weekend\r
+Message-ID: <news-1@mockstore.com>\r
+Content-Type: text/html\r
+\r
+<p>Everything must go. Visit us soon!</p>\r
+";
+
+fn attachment_email(message_id: &str, image: &[u8], pdf: &[u8]) -> String {
+    let b64 = |data: &[u8]| base64::engine::general_purpose::STANDARD.encode(data);
+    format!(
+        "From: receipts@hardware.example\r
+Subject: Receipt attached\r
+Message-ID: <{}>\r
+Content-Type: multipart/mixed; boundary=\"mix\"\r
+\r
+preamble\r
+--mix\r
+Content-Type: multipart/related; boundary=\"rel\"\r
+\r
+--rel\r
+Content-Type: text/html\r
+\r
+<p>Your receipt is attached. <img src=\"cid:logo\"></p>\r
+--rel\r
+Content-Type: image/png\r
+Content-ID: <logo>\r
+Content-Transfer-Encoding: base64\r
+\r
+{}\r
+--rel--\r
+--mix\r
+Content-Type: image/jpeg; name=\"photo.jpg\"\r
+Content-Disposition: attachment; filename=\"photo.jpg\"\r
+Content-Transfer-Encoding: base64\r
+\r
+{}\r
+--mix\r
+Content-Type: application/octet-stream\r
+Content-Disposition: attachment; filename*=utf-8''re%C3%A7u.pdf\r
+Content-Transfer-Encoding: base64\r
+\r
+{}\r
+--mix--\r
+",
+        message_id,
+        b64(b"logo-bytes"),
+        b64(image),
+        b64(pdf)
+    )
+}
+
+fn ingester(db: &Database, dir: &TempDir, config: &str) -> MailIngester {
+    let mut config = MailConfig::parse(config).unwrap();
+    config.receipts_dir = Some(dir.path().join("receipts"));
+    MailIngester::new(db.clone(), config, Some(AIClient::mock()))
+}
+
+fn maildir(dir: &TempDir, messages: &[(&str, &str)]) -> PathBuf {
+    let path = dir.path().join("Maildir");
+    for sub in ["new", "cur", "tmp"] {
+        fs::create_dir_all(path.join(sub)).unwrap();
+    }
+    for (name, content) in messages {
+        fs::write(path.join(name), content).unwrap();
+    }
+    path
+}
+
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, 20).unwrap()
+}
+
+#[test]
+fn test_parse_headers_and_html_body() {
+    let message = MailMessage::parse(HTML_RECEIPT.as_bytes());
+    assert_eq!(
+        message.message_id.as_deref(),
+        Some("order-1001@mockstore.com")
+    );
+    assert_eq!(message.subject.as_deref(), Some("Your order — thanks!"));
+    assert_eq!(
+        message.from_address().as_deref(),
+        Some("orders@mockstore.com")
+    );
+    assert_eq!(message.from_name().as_deref(), Some("Mock Store"));
+    assert_eq!(message.date, NaiveDate::from_ymd_opt(2024, 1, 15));
+    assert_eq!(message.parts.len(), 2);
+
+    let documents = message.receipt_documents();
+    assert_eq!(documents.len(), 1);
+    assert_eq!(documents[0].kind, DocumentKind::Html);
+    let text = documents[0].text.as_deref().unwrap();
+    assert!(text.contains("Order #1001"));
+    assert!(text.contains("Item 1 $10.00"));
+    assert!(text.contains("Total $10.80"));
+    assert!(!text.contains("color"));
+}
+
+#[test]
+fn test_attachments_win_over_body() {
+    let raw = attachment_email("a@x", b"jpeg-bytes", b"%PDF-1.4 fake");
+    let message = MailMessage::parse(raw.as_bytes());
+    let documents = message.receipt_documents();
+
+    // The embedded logo is skipped; the photo and the PDF are receipts
+    assert_eq!(documents.len(), 2);
+    assert_eq!(documents[0].kind, DocumentKind::Image);
+    assert_eq!(documents[0].extension, "jpg");
+    assert_eq!(documents[0].data, b"jpeg-bytes");
+    assert_eq!(documents[1].kind, DocumentKind::Pdf);
+    assert_eq!(documents[1].filename.as_deref(), Some("reçu.pdf"));
+    assert_eq!(documents[1].data, b"%PDF-1.4 fake");
+}
+
+#[test]
+fn test_decode_words_and_html() {
+    assert_eq!(decode_words("=?utf-8?B?Q2Fmw6k=?= receipt"), "Café receipt");
+    assert_eq!(
+        decode_words("=?ISO-8859-1?Q?Caf=E9?= =?ISO-8859-1?Q?_au_lait?="),
+        "Café au lait"
+    );
+    assert_eq!(decode_words("Plain subject"), "Plain subject");
+    assert_eq!(
+        html_to_text("<div>A &amp; B</div><script>x()</script><br>&#36;5.00 &#x2014;"),
+        "A & B\n$5.00 —"
+    );
+}
+
+#[test]
+fn test_config_parse() {
+    let config = MailConfig::parse(
+        r#"
+        senders = ["*@mockstore.com"]
+
+        [[mailboxes]]
+        name = "local"
+        type = "maildir"
+        path = "/tmp/Maildir"
+
+        [[mailboxes]]
+        name = "gmail"
+        type = "imap"
+        host = "imap.example.com"
+        username = "me"
+        password = "secret"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.since_days, 30);
+    assert_eq!(config.mailboxes.len(), 2);
+    match &config.mailboxes[1].kind {
+        MailboxKind::Imap { tls, folder, .. } => {
+            assert_eq!(*tls, ImapTls::Tls);
+            assert_eq!(folder, "INBOX");
+        }
+        other => panic!("expected imap, got {:?}", other),
+    }
+    assert!(config.accepts_sender(Some("orders@mockstore.com")));
+    assert!(!config.accepts_sender(Some("news@other.com")));
+    assert!(!config.accepts_sender(None));
+
+    let duplicate = "[[mailboxes]]\nname = \"a\"\ntype = \"mbox\"\npath = \"x\"\n\n\
+                     [[mailboxes]]\nname = \"a\"\ntype = \"mbox\"\npath = \"y\"";
+    assert!(MailConfig::parse(duplicate).is_err());
+    assert!(MailConfig::parse("[[mailboxes]]\nname = \"a\"\ntype = \"pop3\"").is_err());
+    assert!(MailConfig::parse("senders = [\"\"]").is_err());
+}
+
+#[tokio::test]
+async fn test_maildir_ingestion_files_and_matches_receipts() {
+    let db = Database::in_memory().unwrap();
+    let account_id = db.upsert_account("Checking", Bank::Chase, None).unwrap();
+    let tx_id = db
+        .insert_transaction(
+            account_id,
+            &NewTransaction {
+                date: NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+                description: "MOCK STORE #12".to_string(),
+                amount: -10.80,
+                category: None,
+                import_hash: "mock_store".to_string(),
+                original_data: None,
+                import_format: None,
+                card_member: None,
+                payment_method: None,
+            },
+        )
+        .unwrap()
+        .unwrap();
+
+    let dir = TempDir::new().unwrap();
+    let attachments = attachment_email("b@x", b"jpeg-bytes", b"%PDF-1.4 fake");
+    let path = maildir(
+        &dir,
+        &[
+            ("new/1.eml", HTML_RECEIPT),
+            ("cur/2.eml:2,S", NEWSLETTER),
+            ("cur/3.eml:2,S", attachments.as_str()),
+        ],
+    );
+    let mut ingester = ingester(&db, &dir, "");
+    ingester.config = ingester
+        .config
+        .clone()
+        .with_mailbox("local", MailboxKind::Maildir { path });
+
+    let scan = ingester.scan(today()).await.unwrap();
+    assert!(scan.errors.is_empty());
+    assert_eq!(scan.messages.len(), 3);
+    assert_eq!(scan.created(), 3);
+
+    let newsletter = &scan.messages[1];
+    assert_eq!(newsletter.subject.as_deref(), Some("Big sale this weekend"));
+    assert!(matches!(
+        newsletter.documents[0].outcome,
+        DocumentOutcome::NotReceipt
+    ));
+
+    // The HTML receipt parsed as text. The photo attachment parses to the
+    // same receipt and, being newer, is matched to the charge first
+    let DocumentOutcome::Created {
+        receipt_id,
+        ref path,
+        parsed,
+    } = scan.messages[0].documents[0].outcome
+    else {
+        panic!("expected a created receipt");
+    };
+    assert!(parsed);
+    assert!(path.extension().is_some_and(|e| e == "html"));
+    assert!(path.exists());
+    let receipt = db.get_receipt(receipt_id).unwrap().unwrap();
+    assert_eq!(receipt.receipt_total, Some(10.80));
+    let DocumentOutcome::Created {
+        receipt_id: photo_id,
+        ..
+    } = scan.messages[2].documents[0].outcome
+    else {
+        panic!("expected a created receipt");
+    };
+    assert_eq!(scan.matched, 1);
+    assert_eq!(receipt.status, ReceiptStatus::Pending);
+    let linked = db.get_receipts_for_transaction(tx_id).unwrap();
+    assert_eq!(linked.len(), 1);
+    assert_eq!(linked[0].id, photo_id);
+    assert_eq!(linked[0].status, ReceiptStatus::Matched);
+
+    // A PDF that can be neither read nor rasterized is filed unparsed,
+    // dated and named from the email
+    let DocumentOutcome::Created {
+        receipt_id, parsed, ..
+    } = scan.messages[2].documents[1].outcome
+    else {
+        panic!("expected a created receipt");
+    };
+    assert!(!parsed);
+    let pdf = db.get_receipt(receipt_id).unwrap().unwrap();
+    assert_eq!(pdf.status, ReceiptStatus::Pending);
+    assert!(pdf.receipt_total.is_none());
+
+    // A second scan skips everything already seen
+    let again = ingester.scan(today()).await.unwrap();
+    assert!(again.messages.is_empty());
+}
+
+#[tokio::test]
+async fn test_mbox_dedups_by_content_and_filters_senders() {
+    let db = Database::in_memory().unwrap();
+    let dir = TempDir::new().unwrap();
+    let first = attachment_email("c@x", b"same-photo", b"%PDF-1.4 one");
+    let resent = attachment_email("d@x", b"same-photo", b"%PDF-1.4 two");
+    let mbox = format!(
+        "From sender Mon Jan 15 10:00:00 2024\n{}\n\
+         From sender Mon Jan 15 11:00:00 2024\n{}\n\
+         From news Mon Jan 15 12:00:00 2024\n{}\n>From the desk of the editor\n",
+        first, resent, HTML_RECEIPT
+    );
+    let path = dir.path().join("receipts.mbox");
+    fs::write(&path, mbox).unwrap();
+
+    let ingester = ingester(&db, &dir, "senders = [\"*@hardware.example\"]");
+    let mailbox = MailboxConfig {
+        name: "archive".to_string(),
+        kind: MailboxKind::Mbox { path },
+    };
+    let messages = ingester.scan_mailbox(&mailbox, today()).await.unwrap();
+
+    // The mockstore message is outside the sender filter
+    assert_eq!(messages.len(), 2);
+    assert!(matches!(
+        messages[0].documents[0].outcome,
+        DocumentOutcome::Created { .. }
+    ));
+    assert!(matches!(
+        messages[1].documents[0].outcome,
+        DocumentOutcome::Duplicate { .. }
+    ));
+    assert!(matches!(
+        messages[1].documents[1].outcome,
+        DocumentOutcome::Created { .. }
+    ));
+}
+
+#[test]
+fn test_read_mbox_unquotes_from_lines() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("box");
+    fs::write(
+        &path,
+        "From a@b Mon Jan 15 10:00:00 2024\nSubject: one\n\n>From here\nbody\n\n\
+         From c@d Mon Jan 15 11:00:00 2024\nSubject: two\n\nbody\nFrom inside text\n",
+    )
+    .unwrap();
+    let messages = read_mbox(&path).unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(
+        String::from_utf8_lossy(&messages[0]),
+        "Subject: one\n\nFrom here\nbody\n\n"
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&messages[1]),
+        "Subject: two\n\nbody\nFrom inside text\n"
+    );
+    assert!(read_maildir(dir.path()).is_err());
+}
+
+/// Serve one mailbox over plain-text IMAP and record the commands received
+async fn imap_stand_in(messages: Vec<String>) -> (u16, tokio::task::JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        let (read, mut write) = socket.into_split();
+        let mut lines = BufReader::new(read).lines();
+        let mut received = Vec::new();
+        write.write_all(b"* OK IMAP4rev1 ready\r\n").await.unwrap();
+
+        while let Some(line) = lines.next_line().await.unwrap() {
+            let (tag, command) = line.split_once(' ').unwrap();
+            received.push(command.to_string());
+            let upper = command.to_uppercase();
+            let reply = if upper.starts_with("LOGIN") {
+                if command.contains("\"wrong\"") {
+                    format!("{} NO [AUTHENTICATIONFAILED] Invalid credentials\r\n", tag)
+                } else {
+                    format!("{} OK LOGIN completed\r\n", tag)
+                }
+            } else if upper.starts_with("EXAMINE") {
+                format!(
+                    "* {} EXISTS\r\n* FLAGS (\\Seen)\r\n{} OK [READ-ONLY] EXAMINE completed\r\n",
+                    messages.len(),
+                    tag
+                )
+            } else if upper.starts_with("UID SEARCH") {
+                let uids: Vec<String> =
+                    (1..=messages.len()).map(|n| (n * 10).to_string()).collect();
+                format!(
+                    "* SEARCH {}\r\n{} OK SEARCH completed\r\n",
+                    uids.join(" "),
+                    tag
+                )
+            } else if upper.starts_with("UID FETCH") {
+                let uid: usize = command.split_whitespace().nth(2).unwrap().parse().unwrap();
+                let body = &messages[uid / 10 - 1];
+                format!(
+                    "* {} FETCH (UID {} BODY[] {{{}}}\r\n{})\r\n{} OK FETCH completed\r\n",
+                    uid / 10,
+                    uid,
+                    body.len(),
+                    body,
+                    tag
+                )
+            } else if upper.starts_with("LOGOUT") {
+                write
+                    .write_all(format!("* BYE\r\n{} OK LOGOUT completed\r\n", tag).as_bytes())
+                    .await
+                    .unwrap();
+                break;
+            } else {
+                format!("{} BAD Unknown command\r\n", tag)
+            };
+            write.write_all(reply.as_bytes()).await.unwrap();
+        }
+        received
+    });
+    (port, handle)
+}
+
+fn imap_mailbox(port: u16, password: &str) -> MailboxConfig {
+    MailboxConfig {
+        name: "imap".to_string(),
+        kind: MailboxKind::Imap {
+            host: "127.0.0.1".to_string(),
+            port: Some(port),
+            tls: ImapTls::None,
+            username: "me@example.com".to_string(),
+            password: password.to_string(),
+            folder: "Receipts".to_string(),
+        },
+    }
+}
+
+#[tokio::test]
+async fn test_imap_ingestion_reads_without_marking() {
+    let db = Database::in_memory().unwrap();
+    let dir = TempDir::new().unwrap();
+    let attachments = attachment_email("e@x", b"imap-photo", b"%PDF-1.4 imap");
+    let (port, server) = imap_stand_in(vec![HTML_RECEIPT.to_string(), attachments]).await;
+
+    let ingester = ingester(&db, &dir, "since_days = 7");
+    let messages = ingester
+        .scan_mailbox(&imap_mailbox(port, "secret"), today())
+        .await
+        .unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].documents[0].kind, DocumentKind::Html);
+    assert_eq!(messages[1].documents.len(), 2);
+
+    let received = server.await.unwrap();
+    assert_eq!(
+        received,
+        vec![
+            "LOGIN \"me@example.com\" \"secret\"",
+            "EXAMINE \"Receipts\"",
+            "UID SEARCH SINCE 13-Jan-2024",
+            "UID FETCH 10 BODY.PEEK[]",
+            "UID FETCH 20 BODY.PEEK[]",
+            "LOGOUT",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_imap_login_failure_is_reported_per_mailbox() {
+    let db = Database::in_memory().unwrap();
+    let dir = TempDir::new().unwrap();
+    let (port, _server) = imap_stand_in(Vec::new()).await;
+
+    let mut ingester = ingester(&db, &dir, "");
+    ingester.config.mailboxes.push(imap_mailbox(port, "wrong"));
+    let scan = ingester.scan(today()).await.unwrap();
+    assert!(scan.messages.is_empty());
+    assert_eq!(scan.errors.len(), 1);
+    assert!(scan.errors[0].error.contains("login failed"));
+}
+
+#[tokio::test]
+async fn test_imap_rejects_oversized_literal() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+        let (read, mut write) = socket.into_split();
+        let mut lines = BufReader::new(read).lines();
+        write.write_all(b"* OK IMAP4rev1 ready\r\n").await.unwrap();
+        while let Some(line) = lines.next_line().await.unwrap() {
+            let (tag, command) = line.split_once(' ').unwrap();
+            let reply = if command.starts_with("UID FETCH") {
+                // Announce a 4 GB message and never send it
+                "* 1 FETCH (UID 10 BODY[] {4000000000}\r\n".to_string()
+            } else {
+                format!("{} OK done\r\n", tag)
+            };
+            write.write_all(reply.as_bytes()).await.unwrap();
+        }
+    });
+
+    let mut session = ImapSession::connect("127.0.0.1", port, ImapTls::None, "me", "secret")
+        .await
+        .unwrap();
+    let err = session.fetch(10).await.unwrap_err().to_string();
+    assert!(err.contains("over the"), "{}", err);
+}
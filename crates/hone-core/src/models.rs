@@ -222,6 +222,8 @@ pub struct Subscription {
     pub account_id: Option<i64>,
     pub amount: Option<f64>,
     pub frequency: Option<Frequency>,
+    /// Grace window learned from how far this subscription's charges drift
+    pub grace_days: Option<i64>,
     pub first_seen: Option<NaiveDate>,
     pub last_seen: Option<NaiveDate>,
     pub status: SubscriptionStatus,
@@ -232,24 +234,156 @@ pub struct Subscription {
 }
 
 /// Subscription billing frequency
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+///
+/// Stored and serialized as text: the named cadences by name, any other
+/// fixed cadence as `every_<n>_days`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub enum Frequency {
     Weekly,
+    Biweekly,
     Monthly,
+    Quarterly,
+    Semiannual,
     Yearly,
+    /// A fixed cadence that isn't one of the named ones
+    EveryDays(u32),
 }
 
 impl Frequency {
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> std::borrow::Cow<'static, str> {
         match self {
-            Self::Weekly => "weekly",
-            Self::Monthly => "monthly",
-            Self::Yearly => "yearly",
+            Self::Weekly => "weekly".into(),
+            Self::Biweekly => "biweekly".into(),
+            Self::Monthly => "monthly".into(),
+            Self::Quarterly => "quarterly".into(),
+            Self::Semiannual => "semiannual".into(),
+            Self::Yearly => "yearly".into(),
+            Self::EveryDays(days) => format!("every_{}_days", days).into(),
+        }
+    }
+
+    /// Human-readable cadence ("monthly", "every 2 weeks", "every 45 days")
+    pub fn label(&self) -> std::borrow::Cow<'static, str> {
+        match self {
+            Self::Weekly => "weekly".into(),
+            Self::Biweekly => "every 2 weeks".into(),
+            Self::Monthly => "monthly".into(),
+            Self::Quarterly => "quarterly".into(),
+            Self::Semiannual => "every 6 months".into(),
+            Self::Yearly => "annual".into(),
+            Self::EveryDays(days) => format!("every {} days", days).into(),
+        }
+    }
+
+    /// Typical days between charges
+    pub fn interval_days(&self) -> i64 {
+        match self {
+            Self::Weekly => 7,
+            Self::Biweekly => 14,
+            Self::Monthly => 30,
+            Self::Quarterly => 91,
+            Self::Semiannual => 182,
+            Self::Yearly => 365,
+            Self::EveryDays(days) => i64::from(*days),
+        }
+    }
+
+    /// Calendar months between charges, for cadences billed on a day of the month
+    pub fn interval_months(&self) -> Option<u32> {
+        match self {
+            Self::Monthly => Some(1),
+            Self::Quarterly => Some(3),
+            Self::Semiannual => Some(6),
+            Self::Yearly => Some(12),
+            Self::Weekly | Self::Biweekly | Self::EveryDays(_) => None,
+        }
+    }
+
+    /// Default days to wait past a missed charge before treating it as stopped
+    pub fn default_grace_days(&self) -> i64 {
+        match self {
+            Self::Weekly | Self::Biweekly => 3,
+            Self::Monthly => 7,
+            Self::Quarterly => 14,
+            Self::Semiannual => 21,
+            Self::Yearly => 30,
+            Self::EveryDays(days) => (i64::from(*days) / 7).max(3),
+        }
+    }
+
+    /// Monthly equivalent of a charge at this frequency
+    pub fn monthly_amount(&self, amount: f64) -> f64 {
+        match self {
+            Self::Weekly => amount * 4.33, // Approximate weeks per month
+            Self::Biweekly => amount * 26.0 / 12.0,
+            Self::Monthly => amount,
+            Self::Quarterly => amount / 3.0,
+            Self::Semiannual => amount / 6.0,
+            Self::Yearly => amount / 12.0,
+            Self::EveryDays(days) => amount * 30.44 / f64::from((*days).max(1)),
+        }
+    }
+
+    /// The charge after one on `date`
+    pub fn next_date(&self, date: NaiveDate) -> NaiveDate {
+        match self.interval_months() {
+            Some(months) => date
+                .checked_add_months(chrono::Months::new(months))
+                .unwrap_or(date),
+            None => date + chrono::Duration::days(self.interval_days()),
+        }
+    }
+}
+
+impl std::fmt::Display for Frequency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for Frequency {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let normalized = s.trim().to_lowercase().replace(['-', ' '], "_");
+        match normalized.as_str() {
+            "weekly" => Ok(Self::Weekly),
+            "biweekly" | "fortnightly" => Ok(Self::Biweekly),
+            "monthly" => Ok(Self::Monthly),
+            "quarterly" => Ok(Self::Quarterly),
+            "semiannual" | "semiannually" | "semi_annual" => Ok(Self::Semiannual),
+            "yearly" | "annual" | "annually" => Ok(Self::Yearly),
+            other => other
+                .strip_prefix("every_")
+                .and_then(|rest| rest.strip_suffix("_days"))
+                .and_then(|days| days.parse::<u32>().ok())
+                .filter(|days| *days > 0)
+                .map(Self::EveryDays)
+                .ok_or_else(|| {
+                    format!(
+                        "Unknown frequency: {} (valid: weekly, biweekly, monthly, quarterly, semiannual, yearly, every_<n>_days)",
+                        s
+                    )
+                }),
         }
     }
 }
 
+impl TryFrom<String> for Frequency {
+    type Error = String;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Frequency> for String {
+    fn from(frequency: Frequency) -> Self {
+        frequency.as_str().into_owned()
+    }
+}
+
 /// Subscription status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -296,13 +430,22 @@ pub struct Alert {
     /// Spending anomaly data (for spending_anomaly alerts)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub spending_anomaly: Option<SpendingAnomalyData>,
+    /// Trial forecast (for trial_ending alerts)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trial: Option<TrialForecast>,
+    /// The flagged charge (for per-transaction anomaly alerts)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_anomaly: Option<TransactionAnomaly>,
+    /// The purchase (for return_window_closing and warranty_expiring alerts)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warranty: Option<Warranty>,
     // Joined data for display
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subscription: Option<Subscription>,
 }
 
 /// Types of waste detection alerts
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum AlertType {
     /// A recurring charge that might be forgotten
@@ -317,6 +460,22 @@ pub enum AlertType {
     SpendingAnomaly,
     /// Transaction amount is higher than receipt total (e.g. unrecorded tip)
     TipDiscrepancy,
+    /// A free trial or intro price that is about to convert to full price
+    TrialEnding,
+    /// A cancelled subscription charged after its expected final charge
+    ChargedAfterCancel,
+    /// A charge several times the merchant's usual amount
+    UnusualAmount,
+    /// A large first-ever charge at a merchant
+    NewMerchant,
+    /// A foreign or card-not-present charge on an account that rarely has them
+    UnusualActivity,
+    /// The same amount charged twice by a merchant on the same day
+    DuplicateCharge,
+    /// A purchase's return window closes soon
+    ReturnWindowClosing,
+    /// A purchase's warranty ends soon
+    WarrantyExpiring,
 }
 
 impl AlertType {
@@ -328,6 +487,14 @@ impl AlertType {
             Self::Resume => "resume",
             Self::SpendingAnomaly => "spending_anomaly",
             Self::TipDiscrepancy => "tip_discrepancy",
+            Self::TrialEnding => "trial_ending",
+            Self::ChargedAfterCancel => "charged_after_cancel",
+            Self::UnusualAmount => "unusual_amount",
+            Self::NewMerchant => "new_merchant",
+            Self::UnusualActivity => "unusual_activity",
+            Self::DuplicateCharge => "duplicate_charge",
+            Self::ReturnWindowClosing => "return_window_closing",
+            Self::WarrantyExpiring => "warranty_expiring",
         }
     }
 
@@ -339,6 +506,14 @@ impl AlertType {
             Self::Resume => "Subscription Resumed",
             Self::SpendingAnomaly => "Spending Change",
             Self::TipDiscrepancy => "Tip Discrepancy",
+            Self::TrialEnding => "Trial Ending",
+            Self::ChargedAfterCancel => "Charged After Cancelling",
+            Self::UnusualAmount => "Unusual Amount",
+            Self::NewMerchant => "New Merchant",
+            Self::UnusualActivity => "Unusual Activity",
+            Self::DuplicateCharge => "Duplicate Charge",
+            Self::ReturnWindowClosing => "Return Window Closing",
+            Self::WarrantyExpiring => "Warranty Expiring",
         }
     }
 
@@ -350,6 +525,55 @@ impl AlertType {
             Self::Resume => "A subscription you cancelled has started charging again",
             Self::SpendingAnomaly => "Your spending in this category changed significantly",
             Self::TipDiscrepancy => "This transaction is higher than your receipt total",
+            Self::TrialEnding => "A free trial or intro price is about to convert to full price",
+            Self::ChargedAfterCancel => {
+                "A subscription you cancelled charged after its final billing date"
+            }
+            Self::UnusualAmount => "This charge is much higher than you usually pay here",
+            Self::NewMerchant => "A large first charge from a merchant you haven't used before",
+            Self::UnusualActivity => {
+                "A foreign or online charge on an account that rarely has them"
+            }
+            Self::DuplicateCharge => "This merchant charged the same amount twice in one day",
+            Self::ReturnWindowClosing => "The last day to return this purchase is coming up",
+            Self::WarrantyExpiring => "The warranty on something you own is about to end",
+        }
+    }
+
+    /// Alerts raised for a single transaction (tuned by dismissal feedback)
+    pub fn is_transaction_anomaly(&self) -> bool {
+        matches!(
+            self,
+            Self::UnusualAmount | Self::NewMerchant | Self::UnusualActivity | Self::DuplicateCharge
+        )
+    }
+
+    /// Reminders raised before a purchase's return window or warranty closes
+    pub fn is_warranty_reminder(&self) -> bool {
+        matches!(self, Self::ReturnWindowClosing | Self::WarrantyExpiring)
+    }
+}
+
+impl std::str::FromStr for AlertType {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "zombie" => Ok(Self::Zombie),
+            "price_increase" => Ok(Self::PriceIncrease),
+            "duplicate" => Ok(Self::Duplicate),
+            "resume" => Ok(Self::Resume),
+            "spending_anomaly" => Ok(Self::SpendingAnomaly),
+            "tip_discrepancy" => Ok(Self::TipDiscrepancy),
+            "trial_ending" => Ok(Self::TrialEnding),
+            "charged_after_cancel" => Ok(Self::ChargedAfterCancel),
+            "unusual_amount" => Ok(Self::UnusualAmount),
+            "new_merchant" => Ok(Self::NewMerchant),
+            "unusual_activity" => Ok(Self::UnusualActivity),
+            "duplicate_charge" => Ok(Self::DuplicateCharge),
+            "return_window_closing" => Ok(Self::ReturnWindowClosing),
+            "warranty_expiring" => Ok(Self::WarrantyExpiring),
+            _ => Err(format!("Unknown alert type: {}", s)),
         }
     }
 }
@@ -396,6 +620,8 @@ pub struct DashboardStats {
     pub recent_imports: Vec<RecentImport>,
     /// Transactions without any tags
     pub untagged_transactions: i64,
+    /// Latest successful run of each enabled saved explore question
+    pub recent_digests: Vec<RecentDigest>,
 }
 
 /// Info about a recent import
@@ -1222,9 +1448,15 @@ pub struct CancelledSubscriptionInfo {
     pub merchant: String,
     pub monthly_amount: f64,
     pub cancelled_at: String,
+    /// Savings start after this charge (the cancellation date if untracked)
+    pub final_charge: String,
     pub months_counted: i64,
     pub months_remaining: i64,
+    /// Refund received for the cancellation
+    pub refund: f64,
     pub savings: f64,
+    /// Verification status of the tracked cancellation (`None` if untracked)
+    pub verification: Option<CancellationStatus>,
 }
 
 /// Savings report
@@ -1233,6 +1465,9 @@ pub struct SavingsReport {
     pub total_savings: f64,
     pub total_monthly_saved: f64,
     pub cancelled_count: i64,
+    /// Savings from cancellations verified by a clean billing period
+    pub confirmed_savings: f64,
+    pub confirmed_count: i64,
     pub cancelled: Vec<CancelledSubscriptionInfo>,
 }
 
@@ -1257,6 +1492,8 @@ pub enum OllamaOperation {
     ExplainSpendingChange,
     /// Agentic explore query (conversational finance assistant)
     ExploreQuery,
+    /// Summarize older explore turns into a rolling session summary
+    ExploreSummary,
 }
 
 impl OllamaOperation {
@@ -1272,6 +1509,7 @@ impl OllamaOperation {
             Self::AnalyzeDuplicates => "analyze_duplicates",
             Self::ExplainSpendingChange => "explain_spending_change",
             Self::ExploreQuery => "explore_query",
+            Self::ExploreSummary => "explore_summary",
         }
     }
 }
@@ -1291,6 +1529,7 @@ impl std::str::FromStr for OllamaOperation {
             "analyze_duplicates" => Ok(Self::AnalyzeDuplicates),
             "explain_spending_change" => Ok(Self::ExplainSpendingChange),
             "explore_query" => Ok(Self::ExploreQuery),
+            "explore_summary" => Ok(Self::ExploreSummary),
             _ => Err(format!("Unknown Ollama operation: {}", s)),
         }
     }
@@ -1579,6 +1818,59 @@ pub struct SkippedTransaction {
     pub created_at: DateTime<Utc>,
 }
 
+/// A transaction pushed through the JSON import API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportRow {
+    /// Posting date (`2024-01-15`, an ISO timestamp, or a CSV date format)
+    pub date: String,
+    pub description: String,
+    /// Negative for purchases, positive for refunds and credits
+    pub amount: f64,
+    /// The source system's ID (keeps identical same-day rows apart)
+    #[serde(default)]
+    pub external_id: Option<String>,
+    /// Source category, used as a tagging hint like a bank category
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+/// What happened to one row of a JSON import
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportRowStatus {
+    Imported,
+    /// Already imported (by this or an earlier import)
+    Duplicate,
+    /// Rejected before import (see `error`)
+    Invalid,
+}
+
+/// Per-row result of a JSON import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportRowResult {
+    /// Position in the request's `transactions` array
+    pub index: usize,
+    pub external_id: Option<String>,
+    pub status: ImportRowStatus,
+    /// New transaction, or the existing one for duplicates
+    pub transaction_id: Option<i64>,
+    pub error: Option<String>,
+}
+
+/// A JSON import idempotency key
+#[derive(Debug, Clone)]
+pub struct ImportIdempotencyKey {
+    pub key: String,
+    /// Caller that sent the key
+    pub created_by: String,
+    /// Fingerprint of the request first sent with this key
+    pub request_hash: String,
+    pub import_session_id: Option<i64>,
+    /// Stored JSON response (None while the first request is still running)
+    pub response: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
 // ========== User Feedback Models ==========
 
 /// Type of feedback provided by the user
@@ -1961,126 +2253,1409 @@ pub struct MerchantDifference {
     pub run_b_merchant: Option<String>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// A persisted explore mode conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExploreSession {
+    /// Opaque session ID (e.g., "exp_1a2b3c...")
+    pub id: String,
+    pub title: String,
+    /// Model used for the most recent query
+    pub model: Option<String>,
+    /// Rolling summary of older turns that no longer go to the model verbatim
+    pub summary: Option<String>,
+    /// User who created the session
+    pub created_by: Option<String>,
+    /// Number of query/response turns in the session
+    pub turn_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
 
-    #[test]
-    fn test_tag_source_as_str() {
-        assert_eq!(TagSource::Manual.as_str(), "manual");
-        assert_eq!(TagSource::Pattern.as_str(), "pattern");
-        assert_eq!(TagSource::Ollama.as_str(), "ollama");
-        assert_eq!(TagSource::Rule.as_str(), "rule");
-    }
+/// One query/response exchange within an explore session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExploreTurn {
+    pub id: i64,
+    pub session_id: String,
+    pub query: String,
+    pub response: String,
+    /// Tool calls the orchestrator made while answering
+    pub tool_calls: Vec<crate::ai::ToolCallRecord>,
+    pub iterations: i64,
+    pub model: Option<String>,
+    /// Whether this turn has been folded into the session summary
+    pub summarized: bool,
+    pub created_at: DateTime<Utc>,
+}
 
-    #[test]
-    fn test_tag_source_from_str() {
-        assert_eq!("manual".parse::<TagSource>().unwrap(), TagSource::Manual);
-        assert_eq!("PATTERN".parse::<TagSource>().unwrap(), TagSource::Pattern);
-        assert_eq!("Ollama".parse::<TagSource>().unwrap(), TagSource::Ollama);
-        assert_eq!("rule".parse::<TagSource>().unwrap(), TagSource::Rule);
-        assert!("invalid".parse::<TagSource>().is_err());
-    }
+/// New explore turn for insertion
+#[derive(Debug, Clone)]
+pub struct NewExploreTurn {
+    pub query: String,
+    pub response: String,
+    pub tool_calls: Vec<crate::ai::ToolCallRecord>,
+    pub iterations: i64,
+    pub model: Option<String>,
+}
 
-    #[test]
-    fn test_tag_source_serde() {
-        let source = TagSource::Ollama;
-        let json = serde_json::to_string(&source).unwrap();
-        assert_eq!(json, r#""ollama""#);
+/// A saved explore question that runs on a schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedQuestion {
+    pub id: i64,
+    pub name: String,
+    /// The question sent to the explore agent
+    pub query: String,
+    /// Model override (uses the orchestrator default if None)
+    pub model: Option<String>,
+    /// Hours between scheduled runs
+    pub interval_hours: i64,
+    pub enabled: bool,
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// When the scheduler should run this next (None = as soon as possible)
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
 
-        let parsed: TagSource = serde_json::from_str(r#""pattern""#).unwrap();
-        assert_eq!(parsed, TagSource::Pattern);
-    }
+/// New saved question for creation
+#[derive(Debug, Clone)]
+pub struct NewSavedQuestion {
+    pub name: String,
+    pub query: String,
+    pub model: Option<String>,
+    pub interval_hours: i64,
+    pub created_by: Option<String>,
+}
 
-    #[test]
-    fn test_pattern_type_as_str() {
-        assert_eq!(PatternType::Contains.as_str(), "contains");
-        assert_eq!(PatternType::Regex.as_str(), "regex");
-        assert_eq!(PatternType::Exact.as_str(), "exact");
-    }
+/// The stored result of one saved question run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExploreDigest {
+    pub id: i64,
+    pub saved_question_id: i64,
+    pub success: bool,
+    /// Assistant response (empty if the run failed)
+    pub response: String,
+    pub error_message: Option<String>,
+    /// Tool calls the orchestrator made during the run
+    pub tool_calls: Vec<crate::ai::ToolCallRecord>,
+    pub iterations: i64,
+    pub model: Option<String>,
+    pub latency_ms: i64,
+    /// How different this run is from the previous successful one (0.0 - 1.0)
+    pub change_score: Option<f64>,
+    /// Whether the change was significant enough to raise an insight
+    pub changed: bool,
+    /// Insight finding raised for this run, if any
+    pub insight_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
 
-    #[test]
-    fn test_pattern_type_from_str() {
-        assert_eq!(
-            "contains".parse::<PatternType>().unwrap(),
-            PatternType::Contains
-        );
-        assert_eq!("REGEX".parse::<PatternType>().unwrap(), PatternType::Regex);
-        assert_eq!("Exact".parse::<PatternType>().unwrap(), PatternType::Exact);
-        assert!("invalid".parse::<PatternType>().is_err());
-    }
+/// New digest for insertion
+#[derive(Debug, Clone)]
+pub struct NewExploreDigest {
+    pub saved_question_id: i64,
+    pub success: bool,
+    pub response: String,
+    pub error_message: Option<String>,
+    pub tool_calls: Vec<crate::ai::ToolCallRecord>,
+    pub iterations: i64,
+    pub model: Option<String>,
+    pub latency_ms: i64,
+    pub change_score: Option<f64>,
+    pub changed: bool,
+}
 
-    #[test]
-    fn test_pattern_type_serde() {
-        let pt = PatternType::Regex;
-        let json = serde_json::to_string(&pt).unwrap();
-        assert_eq!(json, r#""regex""#);
+/// Latest digest for a saved question (for the dashboard)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentDigest {
+    pub saved_question_id: i64,
+    pub name: String,
+    pub digest_id: i64,
+    pub response: String,
+    pub changed: bool,
+    pub tool_call_count: i64,
+    pub created_at: DateTime<Utc>,
+}
 
-        let parsed: PatternType = serde_json::from_str(r#""contains""#).unwrap();
-        assert_eq!(parsed, PatternType::Contains);
-    }
+// ============================================================================
+// Household User Models
+// ============================================================================
 
-    #[test]
-    fn test_tag_serde() {
-        let tag = Tag {
-            id: 1,
-            name: "Groceries".to_string(),
-            parent_id: None,
-            color: Some("#10b981".to_string()),
-            icon: Some("shopping-cart".to_string()),
-            auto_patterns: Some("SAFEWAY|TRADER JOE".to_string()),
-            created_at: Utc::now(),
-        };
+/// Role of a household member (ordered: viewer < editor < owner)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserRole {
+    /// Read-only access to accounts, transactions and reports
+    Viewer,
+    /// Can import, tag, split and otherwise edit data
+    Editor,
+    /// Full access, including users, backups, restores and resets
+    Owner,
+}
 
-        let json = serde_json::to_string(&tag).unwrap();
-        assert!(json.contains("Groceries"));
-        assert!(json.contains("#10b981"));
-        assert!(json.contains("SAFEWAY|TRADER JOE"));
+impl UserRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Viewer => "viewer",
+            Self::Editor => "editor",
+            Self::Owner => "owner",
+        }
+    }
 
-        let parsed: Tag = serde_json::from_str(&json).unwrap();
-        assert_eq!(parsed.name, "Groceries");
-        assert_eq!(parsed.color, Some("#10b981".to_string()));
+    /// Whether this role grants at least the access of `required`
+    pub fn allows(&self, required: UserRole) -> bool {
+        *self >= required
     }
+}
 
-    #[test]
-    fn test_tag_with_path_serde() {
-        let tag = Tag {
-            id: 2,
-            name: "Streaming".to_string(),
-            parent_id: Some(1),
-            color: None,
-            icon: None,
-            auto_patterns: Some("NETFLIX|HULU|DISNEY".to_string()),
-            created_at: Utc::now(),
-        };
+impl std::fmt::Display for UserRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
 
-        let tag_with_path = TagWithPath {
-            tag,
-            path: "Entertainment.Streaming".to_string(),
-            depth: 1,
-            children: vec![],
-        };
+impl std::str::FromStr for UserRole {
+    type Err = String;
 
-        let json = serde_json::to_string(&tag_with_path).unwrap();
-        assert!(json.contains("Entertainment.Streaming"));
-        assert!(json.contains("\"depth\":1"));
-        // children should be omitted when empty
-        assert!(!json.contains("children"));
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "viewer" => Ok(Self::Viewer),
+            "editor" => Ok(Self::Editor),
+            "owner" => Ok(Self::Owner),
+            _ => Err(format!(
+                "Unknown role: {} (expected owner, editor or viewer)",
+                s
+            )),
+        }
     }
+}
 
-    #[test]
-    fn test_transaction_tag_serde() {
-        let tt = TransactionTag {
-            transaction_id: 100,
-            tag_id: 5,
-            source: TagSource::Ollama,
-            confidence: Some(0.85),
-            created_at: Utc::now(),
-        };
+/// A household member
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: i64,
+    /// Login identity (Cloudflare Access email or the email an API key maps to)
+    pub email: String,
+    pub display_name: Option<String>,
+    pub role: UserRole,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: Option<DateTime<Utc>>,
+    /// Whether a built-in auth password is set
+    pub has_password: bool,
+    /// Whether TOTP is required at password login
+    pub totp_enabled: bool,
+}
 
-        let json = serde_json::to_string(&tt).unwrap();
-        assert!(json.contains("\"source\":\"ollama\""));
-        assert!(json.contains("0.85"));
+/// A built-in auth session (cookie-backed)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthSession {
+    pub id: i64,
+    pub user_id: i64,
+    /// Double-submit token required on state-changing requests
+    #[serde(skip_serializing)]
+    pub csrf_token: String,
+    /// How the session was established (password, password+totp, passkey)
+    pub auth_method: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A session to create at login
+#[derive(Debug, Clone)]
+pub struct NewAuthSession {
+    /// Cookie token (only its hash is stored)
+    pub token: String,
+    pub user_id: i64,
+    pub csrf_token: String,
+    pub auth_method: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A registered WebAuthn passkey
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebAuthnCredential {
+    pub id: i64,
+    pub user_id: i64,
+    /// Credential ID (base64url)
+    pub credential_id: String,
+    /// SEC1-encoded P-256 public key
+    #[serde(skip)]
+    pub public_key: Vec<u8>,
+    pub sign_count: u32,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// What a database-managed API key may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    /// Reads only (viewer access)
+    ReadOnly,
+    /// Imports only (CSV and webhook imports, listing accounts)
+    ImportOnly,
+    /// The MCP server only, no REST access
+    Mcp,
+    /// Full access (owner)
+    Admin,
+}
+
+impl ApiKeyScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ReadOnly => "read_only",
+            Self::ImportOnly => "import_only",
+            Self::Mcp => "mcp",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// Role REST requests made with this scope run as (None = no REST access)
+    pub fn role(&self) -> Option<UserRole> {
+        match self {
+            Self::ReadOnly => Some(UserRole::Viewer),
+            Self::ImportOnly => Some(UserRole::Editor),
+            Self::Mcp => None,
+            Self::Admin => Some(UserRole::Owner),
+        }
+    }
+}
+
+impl std::fmt::Display for ApiKeyScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for ApiKeyScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "read_only" | "readonly" => Ok(Self::ReadOnly),
+            "import_only" | "import" => Ok(Self::ImportOnly),
+            "mcp" => Ok(Self::Mcp),
+            "admin" => Ok(Self::Admin),
+            _ => Err(format!(
+                "Unknown API key scope: {} (expected read-only, import-only, mcp or admin)",
+                s
+            )),
+        }
+    }
+}
+
+/// A database-managed API key (the secret itself is never stored)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: i64,
+    /// Unique name, recorded in the audit log as `api-key:<name>`
+    pub name: String,
+    /// First characters of the secret, to tell keys apart
+    pub prefix: String,
+    pub scope: ApiKeyScope,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    /// Audit log identity for requests made with this key
+    pub fn identity(&self) -> String {
+        format!("api-key:{}", self.name)
+    }
+}
+
+/// Upper bounds (seconds) of the AI call latency histogram buckets
+pub const AI_LATENCY_BUCKETS: [f64; 10] = [0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+
+/// AI call totals for one operation and model (from `ollama_metrics`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiCallMetrics {
+    pub operation: String,
+    pub model: String,
+    pub calls: i64,
+    pub errors: i64,
+    pub latency_sum_ms: i64,
+    /// Cumulative counts per `AI_LATENCY_BUCKETS` bound
+    pub latency_buckets: Vec<i64>,
+}
+
+/// Point-in-time database figures for metrics export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    pub ai_calls: Vec<AiCallMetrics>,
+    /// Import sessions by status
+    pub import_sessions: Vec<(String, i64)>,
+    /// Undismissed alerts by type
+    pub active_alerts: Vec<(String, i64)>,
+    /// Active insights by type
+    pub active_insights: Vec<(String, i64)>,
+    pub db_size_bytes: i64,
+    pub pool_connections: u32,
+    pub pool_idle_connections: u32,
+    pub pool_max_size: u32,
+}
+
+/// Delivery state of a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Waiting to be sent (first attempt, retry, quiet hours or digest)
+    Pending,
+    Sent,
+    /// Gave up after the maximum number of attempts
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Sent => "sent",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl std::fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for DeliveryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "sent" => Ok(Self::Sent),
+            "failed" => Ok(Self::Failed),
+            _ => Err(format!("Unknown delivery status: {}", s)),
+        }
+    }
+}
+
+/// A new alert or insight that may need notifying
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationSource {
+    /// "alert" or "insight"
+    pub source_type: String,
+    pub source_id: i64,
+    /// Routing key, e.g. "alert:zombie" or "insight:savings_opportunity"
+    pub event: String,
+    /// Insight severity (alerts count as "warning")
+    pub severity: String,
+    pub title: String,
+    pub body: String,
+}
+
+/// A notification queued for one channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewNotificationDelivery {
+    pub channel: String,
+    pub source_type: String,
+    pub source_id: Option<i64>,
+    pub event: String,
+    pub severity: String,
+    pub title: String,
+    pub body: String,
+    /// Batch into the daily digest instead of sending right away
+    pub digest: bool,
+    /// Send even during quiet hours
+    pub urgent: bool,
+    pub next_attempt_at: DateTime<Utc>,
+}
+
+/// A tracked notification delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationDelivery {
+    pub id: i64,
+    pub channel: String,
+    pub source_type: String,
+    pub source_id: Option<i64>,
+    pub event: String,
+    pub severity: String,
+    pub title: String,
+    pub body: String,
+    pub digest: bool,
+    pub urgent: bool,
+    pub status: DeliveryStatus,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+/// Kind of background job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobType {
+    /// AI processing after a CSV import (tagging, normalization, detection)
+    ImportProcessing,
+    /// Re-run AI processing for an existing import session
+    Reprocess,
+    /// Waste detection across all transactions
+    Detection,
+    /// Regenerate insight findings
+    InsightRefresh,
+    /// Create a local backup and prune old ones
+    Backup,
+    /// Write a spending report for a period to the reports directory
+    Report,
+    /// Delete old AI call metrics
+    PruneMetrics,
+    /// Fine-tune models for tasks with enough new training data
+    Training,
+    /// Archive audit log entries past the retention period
+    AuditArchive,
+    /// Run saved explore questions that have come due
+    ExploreDigests,
+}
+
+impl JobType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ImportProcessing => "import_processing",
+            Self::Reprocess => "reprocess",
+            Self::Detection => "detection",
+            Self::InsightRefresh => "insight_refresh",
+            Self::Backup => "backup",
+            Self::Report => "report",
+            Self::PruneMetrics => "prune_metrics",
+            Self::Training => "training",
+            Self::AuditArchive => "audit_archive",
+            Self::ExploreDigests => "explore_digests",
+        }
+    }
+
+    /// Jobs only owners may queue, run, cancel or retry
+    pub fn requires_owner(&self) -> bool {
+        matches!(self, Self::Backup | Self::Training | Self::AuditArchive)
+    }
+
+    /// Jobs that call AI backends or train models; only one of these runs at a time
+    pub fn is_ai_heavy(&self) -> bool {
+        !matches!(
+            self,
+            Self::Backup | Self::Report | Self::PruneMetrics | Self::AuditArchive
+        )
+    }
+
+    /// Default number of attempts before a job is marked failed
+    pub fn default_max_attempts(&self) -> i64 {
+        match self {
+            Self::Backup | Self::Report | Self::PruneMetrics | Self::AuditArchive => 3,
+            // Both run again at their next check anyway
+            Self::Training | Self::ExploreDigests => 1,
+            _ => 2,
+        }
+    }
+
+    pub fn all() -> &'static [JobType] {
+        &[
+            Self::ImportProcessing,
+            Self::Reprocess,
+            Self::Detection,
+            Self::InsightRefresh,
+            Self::Backup,
+            Self::Report,
+            Self::PruneMetrics,
+            Self::Training,
+            Self::AuditArchive,
+            Self::ExploreDigests,
+        ]
+    }
+}
+
+impl std::str::FromStr for JobType {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::all()
+            .iter()
+            .find(|t| t.as_str() == s)
+            .copied()
+            .ok_or_else(|| format!("Unknown job type: {}", s))
+    }
+}
+
+impl std::fmt::Display for JobType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Lifecycle state of a background job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    /// Waiting for a worker (new, or scheduled for a retry)
+    Queued,
+    Running,
+    Completed,
+    /// Gave up after the last attempt
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+impl std::str::FromStr for JobStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(Self::Queued),
+            "running" => Ok(Self::Running),
+            "completed" => Ok(Self::Completed),
+            "failed" => Ok(Self::Failed),
+            "cancelled" => Ok(Self::Cancelled),
+            _ => Err(format!("Unknown job status: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A job to enqueue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewJob {
+    pub job_type: JobType,
+    /// Type-specific parameters
+    pub params: serde_json::Value,
+    /// Record the job works on (e.g. "import_session", 12)
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i64>,
+    /// Defaults to `JobType::default_max_attempts`
+    pub max_attempts: Option<i64>,
+    pub created_by: Option<String>,
+}
+
+impl NewJob {
+    pub fn new(job_type: JobType, params: serde_json::Value) -> Self {
+        Self {
+            job_type,
+            params,
+            entity_type: None,
+            entity_id: None,
+            max_attempts: None,
+            created_by: None,
+        }
+    }
+
+    pub fn for_entity(mut self, entity_type: &str, entity_id: i64) -> Self {
+        self.entity_type = Some(entity_type.to_string());
+        self.entity_id = Some(entity_id);
+        self
+    }
+
+    pub fn created_by(mut self, actor: &str) -> Self {
+        self.created_by = Some(actor.to_string());
+        self
+    }
+}
+
+/// A persisted background job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: i64,
+    pub job_type: JobType,
+    pub status: JobStatus,
+    pub params: serde_json::Value,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i64>,
+    /// Current phase and position within it (import-linked jobs report the
+    /// import session's progress)
+    pub progress_phase: Option<String>,
+    pub progress_current: i64,
+    pub progress_total: i64,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub attempts: i64,
+    pub max_attempts: i64,
+    pub cancel_requested: bool,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Earliest time a queued job may start (retry backoff)
+    pub run_after: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Bookkeeping for one scheduled task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleState {
+    pub name: String,
+    /// Cron expression the next run was computed from
+    pub cron: String,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Job queued by the last run
+    pub last_job_id: Option<i64>,
+    /// Why the last run couldn't be queued
+    pub last_error: Option<String>,
+}
+
+// ========== Refund Matching Models ==========
+
+/// How a refund was linked to its original purchase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RefundMatchSource {
+    /// Found by the refund matcher
+    Auto,
+    /// Linked by a user
+    Manual,
+}
+
+impl RefundMatchSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Manual => "manual",
+        }
+    }
+}
+
+impl std::str::FromStr for RefundMatchSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "manual" => Ok(Self::Manual),
+            _ => Err(format!("Unknown refund match source: {}", s)),
+        }
+    }
+}
+
+/// A refund (credit) linked to the purchase it reverses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefundMatch {
+    pub id: i64,
+    pub refund_transaction_id: i64,
+    pub original_transaction_id: i64,
+    /// Split item that was returned, if the refund covers a single item
+    pub split_id: Option<i64>,
+    /// Portion of the original purchase refunded (positive dollars)
+    pub amount: f64,
+    pub source: RefundMatchSource,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A refund match with both transactions, for listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefundMatchWithTransactions {
+    #[serde(flatten)]
+    pub refund_match: RefundMatch,
+    pub refund: Transaction,
+    pub original: Transaction,
+    /// Description of the returned split item
+    pub split_description: Option<String>,
+}
+
+/// A purchase that a refund may belong to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefundCandidate {
+    pub transaction: Transaction,
+    /// Split item whose amount matches the refund
+    pub split: Option<TransactionSplit>,
+    /// Amount of the purchase not yet refunded
+    pub remaining: f64,
+    /// Match score (0.0-1.0, higher is better)
+    pub score: f64,
+}
+
+// ========== Trial Models ==========
+
+/// Why a charge looks like a free trial or intro price
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrialSignal {
+    /// A $0 or $1 card authorization
+    Authorization,
+    /// A first charge well below what similar subscriptions cost
+    IntroPrice,
+    /// A merchant known for free trials
+    KnownMerchant,
+}
+
+impl TrialSignal {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Authorization => "authorization",
+            Self::IntroPrice => "intro_price",
+            Self::KnownMerchant => "known_merchant",
+        }
+    }
+}
+
+impl std::str::FromStr for TrialSignal {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "authorization" => Ok(Self::Authorization),
+            "intro_price" => Ok(Self::IntroPrice),
+            "known_merchant" => Ok(Self::KnownMerchant),
+            _ => Err(format!("Unknown trial signal: {}", s)),
+        }
+    }
+}
+
+/// Where a detected trial stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrialStatus {
+    /// Waiting for the full-price charge
+    Pending,
+    /// The full-price charge posted
+    Converted,
+    /// No full-price charge came (the trial was likely cancelled)
+    Lapsed,
+}
+
+impl TrialStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Converted => "converted",
+            Self::Lapsed => "lapsed",
+        }
+    }
+}
+
+impl std::str::FromStr for TrialStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(Self::Pending),
+            "converted" => Ok(Self::Converted),
+            "lapsed" => Ok(Self::Lapsed),
+            _ => Err(format!("Unknown trial status: {}", s)),
+        }
+    }
+}
+
+/// A likely free trial or intro price, with its forecast conversion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrialForecast {
+    pub id: i64,
+    pub merchant: String,
+    pub account_id: i64,
+    /// The trial authorization or intro-price charge
+    pub transaction_id: i64,
+    pub signal: TrialSignal,
+    /// Amount of the trial charge (positive dollars)
+    pub trial_amount: f64,
+    pub started_on: NaiveDate,
+    /// When the full-price charge is expected
+    pub expected_conversion: NaiveDate,
+    /// Expected full price, if it can be estimated
+    pub expected_amount: Option<f64>,
+    pub status: TrialStatus,
+    pub alert_id: Option<i64>,
+    /// The full-price charge, once it posts
+    pub converted_transaction_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A trial to record (see [`TrialForecast`])
+#[derive(Debug, Clone)]
+pub struct NewTrialForecast {
+    pub merchant: String,
+    pub account_id: i64,
+    pub transaction_id: i64,
+    pub signal: TrialSignal,
+    pub trial_amount: f64,
+    pub started_on: NaiveDate,
+    pub expected_conversion: NaiveDate,
+    pub expected_amount: Option<f64>,
+}
+
+// ========== Transaction Anomaly Models ==========
+
+/// A single charge flagged as unusual, with the threshold it crossed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionAnomaly {
+    pub id: i64,
+    pub alert_id: i64,
+    pub transaction_id: i64,
+    /// The earlier copy of a duplicate charge
+    pub related_transaction_id: Option<i64>,
+    /// One of the per-transaction alert types (see [`AlertType::is_transaction_anomaly`])
+    pub anomaly_type: AlertType,
+    pub account_id: i64,
+    pub merchant: String,
+    /// Amount of the charge (positive dollars)
+    pub amount: f64,
+    pub transaction_date: NaiveDate,
+    /// Merchant's usual charge (for unusual_amount)
+    pub baseline_amount: Option<f64>,
+    /// Threshold the charge crossed, after tuning from feedback
+    pub threshold: f64,
+    /// What was unusual: a country code, or "card_not_present"
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An anomaly to record (see [`TransactionAnomaly`])
+#[derive(Debug, Clone)]
+pub struct NewTransactionAnomaly {
+    pub transaction_id: i64,
+    pub related_transaction_id: Option<i64>,
+    pub anomaly_type: AlertType,
+    pub account_id: i64,
+    pub merchant: String,
+    pub amount: f64,
+    pub transaction_date: NaiveDate,
+    pub baseline_amount: Option<f64>,
+    pub threshold: f64,
+    pub detail: Option<String>,
+}
+
+/// Active feedback on one merchant's anomaly alerts of one type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyFeedback {
+    pub anomaly_type: AlertType,
+    pub merchant: String,
+    /// Dismissed or rated not helpful
+    pub dismissed: i64,
+    /// Rated helpful
+    pub helpful: i64,
+}
+
+// ========== Cancellation Tracking Models ==========
+
+/// How a subscription was cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CancellationMethod {
+    Website,
+    App,
+    Phone,
+    Email,
+    Chat,
+    Mail,
+    InPerson,
+    /// Blocked or disputed through the bank
+    Bank,
+    Other,
+}
+
+impl CancellationMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Website => "website",
+            Self::App => "app",
+            Self::Phone => "phone",
+            Self::Email => "email",
+            Self::Chat => "chat",
+            Self::Mail => "mail",
+            Self::InPerson => "in_person",
+            Self::Bank => "bank",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl std::str::FromStr for CancellationMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "website" | "web" => Ok(Self::Website),
+            "app" => Ok(Self::App),
+            "phone" => Ok(Self::Phone),
+            "email" => Ok(Self::Email),
+            "chat" => Ok(Self::Chat),
+            "mail" => Ok(Self::Mail),
+            "in_person" => Ok(Self::InPerson),
+            "bank" => Ok(Self::Bank),
+            "other" => Ok(Self::Other),
+            _ => Err(format!("Unknown cancellation method: {}", s)),
+        }
+    }
+}
+
+/// Verification status of a tracked cancellation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CancellationStatus {
+    /// Waiting for the billing period after the final charge to pass
+    Pending,
+    /// The billing period passed with no further charges
+    Confirmed,
+    /// The merchant charged after the expected final charge
+    ChargedAfter,
+}
+
+impl CancellationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Confirmed => "confirmed",
+            Self::ChargedAfter => "charged_after",
+        }
+    }
+}
+
+impl std::str::FromStr for CancellationStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(Self::Pending),
+            "confirmed" => Ok(Self::Confirmed),
+            "charged_after" => Ok(Self::ChargedAfter),
+            _ => Err(format!("Unknown cancellation status: {}", s)),
+        }
+    }
+}
+
+/// A tracked subscription cancellation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionCancellation {
+    pub id: i64,
+    pub subscription_id: i64,
+    pub merchant: String,
+    pub cancelled_on: NaiveDate,
+    pub method: Option<CancellationMethod>,
+    pub confirmation_number: Option<String>,
+    /// Last charge expected before the cancellation takes effect
+    pub expected_final_charge: Option<NaiveDate>,
+    /// Refund the merchant promised (positive dollars)
+    pub expected_refund: Option<f64>,
+    /// Credit from the merchant received after cancelling
+    pub refund_transaction_id: Option<i64>,
+    pub refund_amount: Option<f64>,
+    pub status: CancellationStatus,
+    /// The charge that posted after the expected final charge
+    pub late_charge_transaction_id: Option<i64>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Cancellation details to record (see [`SubscriptionCancellation`])
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NewSubscriptionCancellation {
+    /// Defaults to today
+    pub cancelled_on: Option<NaiveDate>,
+    pub method: Option<CancellationMethod>,
+    pub confirmation_number: Option<String>,
+    pub expected_final_charge: Option<NaiveDate>,
+    pub expected_refund: Option<f64>,
+    pub notes: Option<String>,
+}
+
+// ========== Item Catalog Models ==========
+
+/// A product in the item catalog
+///
+/// Receipt line items from the same merchant whose names normalize to the
+/// same key (case, punctuation, SKUs, sizes and common abbreviations
+/// ignored) share one catalog item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogItem {
+    pub id: i64,
+    pub merchant: String,
+    /// Display name (the first description seen, or a merged item's name)
+    pub name: String,
+    pub normalized_name: String,
+    pub purchase_count: i64,
+    pub first_seen: Option<NaiveDate>,
+    pub last_seen: Option<NaiveDate>,
+    pub last_price: Option<f64>,
+    pub avg_price: Option<f64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One purchase of a catalog item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemPricePoint {
+    pub date: NaiveDate,
+    pub price: f64,
+    pub transaction_id: i64,
+    pub split_id: i64,
+    /// Line item description as it appeared on the receipt
+    pub description: String,
+}
+
+/// Price history of a catalog item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemPriceHistory {
+    pub item: CatalogItem,
+    pub points: Vec<ItemPricePoint>,
+    pub first_price: Option<f64>,
+    pub latest_price: Option<f64>,
+    pub min_price: Option<f64>,
+    pub max_price: Option<f64>,
+    /// Change from the first to the latest price
+    pub change_percent: Option<f64>,
+}
+
+/// Price change of one item over a report period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemInflation {
+    pub item_id: i64,
+    pub merchant: String,
+    pub name: String,
+    pub purchase_count: i64,
+    pub start_date: NaiveDate,
+    pub start_price: f64,
+    pub end_date: NaiveDate,
+    pub end_price: f64,
+    pub change_percent: f64,
+    /// Change scaled to a year (`None` for spans under 30 days)
+    pub annualized_percent: Option<f64>,
+}
+
+/// Item-level inflation report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemInflationReport {
+    pub period: ReportPeriod,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchant: Option<String>,
+    /// Change in the cost of buying each tracked item once at its first and
+    /// latest price
+    pub basket_change_percent: Option<f64>,
+    pub items: Vec<ItemInflation>,
+}
+
+/// Totals for one split type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitTypeSummary {
+    pub split_type: SplitType,
+    /// Signed total (discounts and rewards are negative)
+    pub amount: f64,
+    pub split_count: i64,
+    pub transaction_count: i64,
+    pub avg_amount: f64,
+    /// Merchants with the largest totals of this type
+    pub top_merchants: Vec<MerchantSummary>,
+}
+
+/// Report of split totals by type (items, tax, tips, fees, discounts, rewards)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitTypeReport {
+    pub period: ReportPeriod,
+    pub split_types: Vec<SplitTypeSummary>,
+}
+
+// ========== Warranty Models ==========
+
+/// Return window and warranty for a purchased item
+///
+/// Usually tied to the receipt and split item it was bought on; a
+/// supplementary receipt can hold the warranty document itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Warranty {
+    pub id: i64,
+    pub item: String,
+    pub merchant: Option<String>,
+    pub purchase_date: NaiveDate,
+    /// Price paid (positive dollars)
+    pub price: Option<f64>,
+    /// Last day the item can be returned
+    pub return_by: Option<NaiveDate>,
+    /// Last day of warranty coverage
+    pub warranty_expires: Option<NaiveDate>,
+    pub serial_number: Option<String>,
+    /// Receipt the item was bought on
+    pub receipt_id: Option<i64>,
+    /// Split item for the purchase
+    pub split_id: Option<i64>,
+    pub transaction_id: Option<i64>,
+    /// Supplementary receipt holding the warranty document
+    pub document_receipt_id: Option<i64>,
+    pub notes: Option<String>,
+    /// `return_window_closing` reminder, once raised
+    pub return_alert_id: Option<i64>,
+    /// `warranty_expiring` reminder, once raised
+    pub warranty_alert_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A warranty to record (see [`Warranty`])
+///
+/// Blank fields are filled from the split (item, price), the receipt
+/// (merchant, purchase date) or the transaction. Windows can be given as
+/// dates or as lengths from the purchase date.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NewWarranty {
+    pub item: Option<String>,
+    pub merchant: Option<String>,
+    pub purchase_date: Option<NaiveDate>,
+    pub price: Option<f64>,
+    pub return_by: Option<NaiveDate>,
+    /// Return window length, if `return_by` isn't given
+    pub return_days: Option<i64>,
+    pub warranty_expires: Option<NaiveDate>,
+    /// Warranty length, if `warranty_expires` isn't given
+    pub warranty_months: Option<u32>,
+    pub serial_number: Option<String>,
+    pub receipt_id: Option<i64>,
+    pub split_id: Option<i64>,
+    pub transaction_id: Option<i64>,
+    pub document_receipt_id: Option<i64>,
+    pub notes: Option<String>,
+}
+
+/// Changes to a warranty (`None` leaves a field as is)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WarrantyUpdate {
+    pub item: Option<String>,
+    pub merchant: Option<String>,
+    pub purchase_date: Option<NaiveDate>,
+    pub price: Option<f64>,
+    pub return_by: Option<NaiveDate>,
+    pub warranty_expires: Option<NaiveDate>,
+    pub serial_number: Option<String>,
+    pub document_receipt_id: Option<i64>,
+    pub notes: Option<String>,
+}
+
+/// Where an item stands on a given day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WarrantyStatus {
+    /// The return window is still open
+    Returnable,
+    /// Past the return window, still under warranty
+    Covered,
+    /// Neither window is open
+    Expired,
+}
+
+impl WarrantyStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Returnable => "returnable",
+            Self::Covered => "covered",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+impl std::str::FromStr for WarrantyStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "returnable" => Ok(Self::Returnable),
+            "covered" => Ok(Self::Covered),
+            "expired" => Ok(Self::Expired),
+            _ => Err(format!("Unknown warranty status: {}", s)),
+        }
+    }
+}
+
+impl Warranty {
+    /// Status on `as_of`
+    pub fn status(&self, as_of: NaiveDate) -> WarrantyStatus {
+        if self.return_by.is_some_and(|d| d >= as_of) {
+            WarrantyStatus::Returnable
+        } else if self.warranty_expires.is_some_and(|d| d >= as_of) {
+            WarrantyStatus::Covered
+        } else {
+            WarrantyStatus::Expired
+        }
+    }
+}
+
+/// One item in the warranty report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarrantyReportItem {
+    pub warranty: Warranty,
+    pub status: WarrantyStatus,
+    /// Days until the return window closes (while it's open)
+    pub return_days_left: Option<i64>,
+    /// Days until the warranty ends (while it's active)
+    pub warranty_days_left: Option<i64>,
+}
+
+/// What we own under warranty (or can still return) on a given day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarrantyReport {
+    pub as_of: NaiveDate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Covered items, soonest deadline first
+    pub items: Vec<WarrantyReportItem>,
+    pub returnable_count: i64,
+    pub covered_count: i64,
+    /// Matching items whose windows have all closed (not listed)
+    pub expired_count: i64,
+    /// Total price of the listed items
+    pub covered_value: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key_scope_parse() {
+        assert_eq!(
+            "read-only".parse::<ApiKeyScope>().unwrap(),
+            ApiKeyScope::ReadOnly
+        );
+        assert_eq!(
+            "import_only".parse::<ApiKeyScope>().unwrap(),
+            ApiKeyScope::ImportOnly
+        );
+        assert_eq!("MCP".parse::<ApiKeyScope>().unwrap(), ApiKeyScope::Mcp);
+        assert_eq!("admin".parse::<ApiKeyScope>().unwrap(), ApiKeyScope::Admin);
+        assert!("root".parse::<ApiKeyScope>().is_err());
+        assert_eq!(ApiKeyScope::Mcp.role(), None);
+        assert_eq!(ApiKeyScope::ReadOnly.role(), Some(UserRole::Viewer));
+    }
+
+    #[test]
+    fn test_tag_source_as_str() {
+        assert_eq!(TagSource::Manual.as_str(), "manual");
+        assert_eq!(TagSource::Pattern.as_str(), "pattern");
+        assert_eq!(TagSource::Ollama.as_str(), "ollama");
+        assert_eq!(TagSource::Rule.as_str(), "rule");
+    }
+
+    #[test]
+    fn test_tag_source_from_str() {
+        assert_eq!("manual".parse::<TagSource>().unwrap(), TagSource::Manual);
+        assert_eq!("PATTERN".parse::<TagSource>().unwrap(), TagSource::Pattern);
+        assert_eq!("Ollama".parse::<TagSource>().unwrap(), TagSource::Ollama);
+        assert_eq!("rule".parse::<TagSource>().unwrap(), TagSource::Rule);
+        assert!("invalid".parse::<TagSource>().is_err());
+    }
+
+    #[test]
+    fn test_tag_source_serde() {
+        let source = TagSource::Ollama;
+        let json = serde_json::to_string(&source).unwrap();
+        assert_eq!(json, r#""ollama""#);
+
+        let parsed: TagSource = serde_json::from_str(r#""pattern""#).unwrap();
+        assert_eq!(parsed, TagSource::Pattern);
+    }
+
+    #[test]
+    fn test_pattern_type_as_str() {
+        assert_eq!(PatternType::Contains.as_str(), "contains");
+        assert_eq!(PatternType::Regex.as_str(), "regex");
+        assert_eq!(PatternType::Exact.as_str(), "exact");
+    }
+
+    #[test]
+    fn test_pattern_type_from_str() {
+        assert_eq!(
+            "contains".parse::<PatternType>().unwrap(),
+            PatternType::Contains
+        );
+        assert_eq!("REGEX".parse::<PatternType>().unwrap(), PatternType::Regex);
+        assert_eq!("Exact".parse::<PatternType>().unwrap(), PatternType::Exact);
+        assert!("invalid".parse::<PatternType>().is_err());
+    }
+
+    #[test]
+    fn test_pattern_type_serde() {
+        let pt = PatternType::Regex;
+        let json = serde_json::to_string(&pt).unwrap();
+        assert_eq!(json, r#""regex""#);
+
+        let parsed: PatternType = serde_json::from_str(r#""contains""#).unwrap();
+        assert_eq!(parsed, PatternType::Contains);
+    }
+
+    #[test]
+    fn test_tag_serde() {
+        let tag = Tag {
+            id: 1,
+            name: "Groceries".to_string(),
+            parent_id: None,
+            color: Some("#10b981".to_string()),
+            icon: Some("shopping-cart".to_string()),
+            auto_patterns: Some("SAFEWAY|TRADER JOE".to_string()),
+            created_at: Utc::now(),
+        };
+
+        let json = serde_json::to_string(&tag).unwrap();
+        assert!(json.contains("Groceries"));
+        assert!(json.contains("#10b981"));
+        assert!(json.contains("SAFEWAY|TRADER JOE"));
+
+        let parsed: Tag = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.name, "Groceries");
+        assert_eq!(parsed.color, Some("#10b981".to_string()));
+    }
+
+    #[test]
+    fn test_tag_with_path_serde() {
+        let tag = Tag {
+            id: 2,
+            name: "Streaming".to_string(),
+            parent_id: Some(1),
+            color: None,
+            icon: None,
+            auto_patterns: Some("NETFLIX|HULU|DISNEY".to_string()),
+            created_at: Utc::now(),
+        };
+
+        let tag_with_path = TagWithPath {
+            tag,
+            path: "Entertainment.Streaming".to_string(),
+            depth: 1,
+            children: vec![],
+        };
+
+        let json = serde_json::to_string(&tag_with_path).unwrap();
+        assert!(json.contains("Entertainment.Streaming"));
+        assert!(json.contains("\"depth\":1"));
+        // children should be omitted when empty
+        assert!(!json.contains("children"));
+    }
+
+    #[test]
+    fn test_transaction_tag_serde() {
+        let tt = TransactionTag {
+            transaction_id: 100,
+            tag_id: 5,
+            source: TagSource::Ollama,
+            confidence: Some(0.85),
+            created_at: Utc::now(),
+        };
+
+        let json = serde_json::to_string(&tt).unwrap();
+        assert!(json.contains("\"source\":\"ollama\""));
+        assert!(json.contains("0.85"));
 
         let parsed: TransactionTag = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.transaction_id, 100);
@@ -2135,4 +3710,89 @@ mod tests {
         assert_eq!(parsed.transactions_moved, 42);
         assert_eq!(parsed.children_affected, 3);
     }
+
+    #[test]
+    fn test_user_role_ordering() {
+        assert!(UserRole::Owner.allows(UserRole::Editor));
+        assert!(UserRole::Editor.allows(UserRole::Viewer));
+        assert!(UserRole::Viewer.allows(UserRole::Viewer));
+        assert!(!UserRole::Viewer.allows(UserRole::Editor));
+        assert!(!UserRole::Editor.allows(UserRole::Owner));
+        assert_eq!(
+            UserRole::Owner.min(UserRole::Viewer),
+            UserRole::Viewer,
+            "a key capped at viewer stays a viewer"
+        );
+    }
+
+    #[test]
+    fn test_user_role_parse() {
+        assert_eq!("Owner".parse::<UserRole>().unwrap(), UserRole::Owner);
+        assert_eq!(" editor ".parse::<UserRole>().unwrap(), UserRole::Editor);
+        assert!("admin".parse::<UserRole>().is_err());
+        assert_eq!(
+            serde_json::to_string(&UserRole::Viewer).unwrap(),
+            "\"viewer\""
+        );
+    }
+
+    #[test]
+    fn test_frequency_parse_and_serde() {
+        assert_eq!(
+            "Quarterly".parse::<Frequency>().unwrap(),
+            Frequency::Quarterly
+        );
+        assert_eq!(
+            "semi-annual".parse::<Frequency>().unwrap(),
+            Frequency::Semiannual
+        );
+        assert_eq!(
+            "fortnightly".parse::<Frequency>().unwrap(),
+            Frequency::Biweekly
+        );
+        assert_eq!(
+            "every 45 days".parse::<Frequency>().unwrap(),
+            Frequency::EveryDays(45)
+        );
+        assert!("every_0_days".parse::<Frequency>().is_err());
+        assert!("hourly".parse::<Frequency>().is_err());
+
+        assert_eq!(
+            serde_json::to_string(&Frequency::Monthly).unwrap(),
+            "\"monthly\""
+        );
+        assert_eq!(
+            serde_json::to_string(&Frequency::EveryDays(45)).unwrap(),
+            "\"every_45_days\""
+        );
+        let parsed: Frequency = serde_json::from_str("\"every_45_days\"").unwrap();
+        assert_eq!(parsed, Frequency::EveryDays(45));
+        assert!(serde_json::from_str::<Frequency>("\"sometimes\"").is_err());
+    }
+
+    #[test]
+    fn test_frequency_schedule() {
+        let jan_31 = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+        assert_eq!(
+            Frequency::Monthly.next_date(jan_31),
+            NaiveDate::from_ymd_opt(2025, 2, 28).unwrap()
+        );
+        assert_eq!(
+            Frequency::Quarterly.next_date(jan_31),
+            NaiveDate::from_ymd_opt(2025, 4, 30).unwrap()
+        );
+        assert_eq!(
+            Frequency::Biweekly.next_date(jan_31),
+            NaiveDate::from_ymd_opt(2025, 2, 14).unwrap()
+        );
+        assert_eq!(
+            Frequency::EveryDays(45).next_date(jan_31),
+            NaiveDate::from_ymd_opt(2025, 3, 17).unwrap()
+        );
+
+        assert_eq!(Frequency::Quarterly.monthly_amount(300.0), 100.0);
+        assert_eq!(Frequency::Semiannual.monthly_amount(120.0), 20.0);
+        assert!((Frequency::Biweekly.monthly_amount(12.0) - 26.0).abs() < 1e-9);
+        assert!((Frequency::EveryDays(60).monthly_amount(60.0) - 30.44).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,5 @@
+//! Low-level networking shared by the built-in protocol clients
+//!
+//! - `tls` - plain/TLS TCP streams for the IMAP reader and SMTP channel
+
+pub mod tls;
@@ -0,0 +1,124 @@
+//! Plain or TLS client connections
+//!
+//! Used by the IMAP reader and the SMTP channel for implicit TLS and
+//! STARTTLS upgrades. Server certificates are checked against the
+//! bundled webpki roots. Connecting, handshakes and reads take a timeout so
+//! a stalled server can't hang the caller.
+
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+use crate::error::{Error, Result};
+
+/// Plain or TLS connection
+pub enum Stream {
+    Plain(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+impl Stream {
+    /// Open a TCP connection, over TLS from the start when `tls` is set
+    ///
+    /// The connection and handshake together must finish within `timeout`.
+    pub async fn connect(
+        host: &str,
+        port: u16,
+        tls: bool,
+        timeout: Duration,
+        error: fn(String) -> Error,
+    ) -> Result<Self> {
+        let connect = async {
+            let tcp = TcpStream::connect((host, port)).await?;
+            if tls {
+                Self::tls(host, tcp, error).await
+            } else {
+                Ok(Stream::Plain(tcp))
+            }
+        };
+        tokio::time::timeout(timeout, connect)
+            .await
+            .map_err(|_| error(format!("Timed out connecting to {}:{}", host, port)))?
+    }
+
+    /// Connect over TLS
+    ///
+    /// `error` builds the caller's error variant (e.g. `Error::Mail`).
+    pub async fn tls(host: &str, tcp: TcpStream, error: fn(String) -> Error) -> Result<Self> {
+        let server_name = ServerName::try_from(host.to_string())
+            .map_err(|e| error(format!("Invalid host {}: {}", host, e)))?;
+        let tls = connector(error)?
+            .connect(server_name, tcp)
+            .await
+            .map_err(|e| error(format!("TLS handshake failed: {}", e)))?;
+        Ok(Stream::Tls(Box::new(tls)))
+    }
+
+    /// Upgrade a plain connection after STARTTLS
+    pub async fn starttls(
+        self,
+        host: &str,
+        timeout: Duration,
+        error: fn(String) -> Error,
+    ) -> Result<Self> {
+        match self {
+            Stream::Plain(tcp) => tokio::time::timeout(timeout, Self::tls(host, tcp, error))
+                .await
+                .map_err(|_| error("Timed out during the TLS handshake".to_string()))?,
+            Stream::Tls(_) => Err(error("STARTTLS on an encrypted connection".to_string())),
+        }
+    }
+
+    /// Read what's available, waiting at most `timeout` for it
+    pub async fn read(
+        &mut self,
+        buf: &mut [u8],
+        timeout: Duration,
+        error: fn(String) -> Error,
+    ) -> Result<usize> {
+        let read = async {
+            match self {
+                Stream::Plain(s) => s.read(buf).await,
+                Stream::Tls(s) => s.read(buf).await,
+            }
+        };
+        let n = tokio::time::timeout(timeout, read)
+            .await
+            .map_err(|_| error("Timed out waiting for the server".to_string()))??;
+        Ok(n)
+    }
+
+    /// Write everything and flush
+    pub async fn write_all(&mut self, data: &[u8]) -> io::Result<()> {
+        match self {
+            Stream::Plain(s) => {
+                s.write_all(data).await?;
+                s.flush().await
+            }
+            Stream::Tls(s) => {
+                s.write_all(data).await?;
+                s.flush().await
+            }
+        }
+    }
+}
+
+fn connector(error: fn(String) -> Error) -> Result<TlsConnector> {
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let config = ClientConfig::builder_with_provider(Arc::new(
+        tokio_rustls::rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .map_err(|e| error(format!("TLS setup failed: {}", e)))?
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+    Ok(TlsConnector::from(Arc::new(config)))
+}
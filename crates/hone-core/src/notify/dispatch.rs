@@ -0,0 +1,334 @@
+//! Notification dispatcher
+//!
+//! Each run:
+//! 1. Queues new alerts and insights for every matching route/channel
+//! 2. Sends due immediate deliveries (holding non-urgent ones during quiet hours)
+//! 3. Sends due digest deliveries as one batched message per channel
+//!
+//! Failed sends are retried with exponential backoff (2, 4, 8... minutes)
+//! until `max_attempts`, then marked failed.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone, Utc};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use super::{Notification, NotificationChannel, NotifyConfig};
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::models::{NewNotificationDelivery, NotificationDelivery, NotificationSource};
+
+const ALERT_CURSOR: &str = "alert_cursor";
+const INSIGHT_CURSOR: &str = "insight_cursor";
+
+/// Outcome of one dispatcher run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NotifyRunSummary {
+    /// Deliveries queued from new alerts/insights
+    pub queued: usize,
+    /// Deliveries sent
+    pub sent: usize,
+    /// Failed sends (retried later unless out of attempts)
+    pub failed: usize,
+    /// Deliveries held back by quiet hours
+    pub deferred: usize,
+}
+
+/// Routes alerts and insights to channels and tracks delivery
+pub struct Notifier {
+    db: Database,
+    config: NotifyConfig,
+    channels: HashMap<String, Box<dyn NotificationChannel>>,
+}
+
+impl Notifier {
+    pub fn new(db: Database, config: NotifyConfig) -> Self {
+        let channels = config
+            .channels
+            .iter()
+            .map(|c| (c.name.clone(), c.build()))
+            .collect();
+        Self {
+            db,
+            config,
+            channels,
+        }
+    }
+
+    /// Replace (or add) a channel backend
+    pub fn set_channel(&mut self, channel: Box<dyn NotificationChannel>) {
+        self.channels.insert(channel.name().to_string(), channel);
+    }
+
+    pub fn config(&self) -> &NotifyConfig {
+        &self.config
+    }
+
+    /// Send a test message straight to a channel (recorded as a delivery)
+    pub async fn send_test(&self, channel_name: &str) -> Result<NotificationDelivery> {
+        let channel = self
+            .channels
+            .get(channel_name)
+            .ok_or_else(|| Error::NotFound(format!("Notification channel: {}", channel_name)))?;
+        let notification = Notification {
+            event: "test".to_string(),
+            title: "Hone test notification".to_string(),
+            body: format!("Notifications to '{}' are working.", channel_name),
+            severity: "info".to_string(),
+        };
+        let id = self
+            .db
+            .create_notification_delivery(&NewNotificationDelivery {
+                channel: channel_name.to_string(),
+                source_type: "test".to_string(),
+                source_id: None,
+                event: notification.event.clone(),
+                severity: notification.severity.clone(),
+                title: notification.title.clone(),
+                body: notification.body.clone(),
+                digest: false,
+                urgent: true,
+                next_attempt_at: Utc::now(),
+            })?
+            .ok_or_else(|| Error::Notification("Failed to record test delivery".to_string()))?;
+
+        match channel.send(&notification).await {
+            Ok(()) => self.db.mark_notification_sent(id)?,
+            Err(e) => self.db.mark_notification_failed(id, &e.to_string(), None)?,
+        }
+        self.db
+            .get_notification_delivery(id)?
+            .ok_or_else(|| Error::NotFound(format!("Notification delivery {}", id)))
+    }
+
+    /// Queue new events and send everything that is due
+    pub async fn run_once(&self, now: DateTime<Utc>) -> Result<NotifyRunSummary> {
+        let mut summary = NotifyRunSummary {
+            queued: self.enqueue(now)?,
+            ..Default::default()
+        };
+
+        let quiet = self.in_quiet_hours(now);
+        let (digests, immediate): (Vec<_>, Vec<_>) = self
+            .db
+            .due_notification_deliveries(now)?
+            .into_iter()
+            .partition(|d| d.digest);
+
+        for delivery in immediate {
+            if quiet && !delivery.urgent {
+                summary.deferred += 1;
+                continue;
+            }
+            let notification = Notification {
+                event: delivery.event.clone(),
+                title: delivery.title.clone(),
+                body: delivery.body.clone(),
+                severity: delivery.severity.clone(),
+            };
+            match self.send(&delivery.channel, &notification).await {
+                Ok(()) => {
+                    self.db.mark_notification_sent(delivery.id)?;
+                    summary.sent += 1;
+                }
+                Err(e) => {
+                    self.record_failure(&delivery, &e.to_string(), now)?;
+                    summary.failed += 1;
+                }
+            }
+        }
+
+        let mut batches: BTreeMap<String, Vec<NotificationDelivery>> = BTreeMap::new();
+        for delivery in digests {
+            if quiet && !delivery.urgent {
+                summary.deferred += 1;
+                continue;
+            }
+            batches
+                .entry(delivery.channel.clone())
+                .or_default()
+                .push(delivery);
+        }
+        for (channel, deliveries) in batches {
+            let notification = digest_notification(&deliveries);
+            match self.send(&channel, &notification).await {
+                Ok(()) => {
+                    for delivery in &deliveries {
+                        self.db.mark_notification_sent(delivery.id)?;
+                    }
+                    summary.sent += deliveries.len();
+                }
+                Err(e) => {
+                    for delivery in &deliveries {
+                        self.record_failure(delivery, &e.to_string(), now)?;
+                    }
+                    summary.failed += deliveries.len();
+                }
+            }
+        }
+
+        if summary.queued + summary.sent + summary.failed > 0 {
+            info!(
+                "Notifications: {} queued, {} sent, {} failed",
+                summary.queued, summary.sent, summary.failed
+            );
+        }
+        Ok(summary)
+    }
+
+    async fn send(&self, channel: &str, notification: &Notification) -> Result<()> {
+        let backend = self
+            .channels
+            .get(channel)
+            .ok_or_else(|| Error::Notification(format!("Unknown channel: {}", channel)))?;
+        backend.send(notification).await
+    }
+
+    fn record_failure(
+        &self,
+        delivery: &NotificationDelivery,
+        error: &str,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let attempts = delivery.attempts + 1;
+        let retry_at = (attempts < i64::from(self.config.max_attempts))
+            .then(|| now + Duration::minutes(1 << attempts.min(10)));
+        warn!(
+            "Notification {} to {} failed (attempt {}): {}",
+            delivery.id, delivery.channel, attempts, error
+        );
+        self.db
+            .mark_notification_failed(delivery.id, error, retry_at)
+    }
+
+    /// Queue deliveries for alerts and insights created since the last run
+    fn enqueue(&self, now: DateTime<Utc>) -> Result<usize> {
+        let alert_cursor = self.db.get_notification_cursor(ALERT_CURSOR)?;
+        let insight_cursor = self.db.get_notification_cursor(INSIGHT_CURSOR)?;
+
+        // First run: start from the current state instead of replaying history
+        let (Some(alert_cursor), Some(insight_cursor)) = (alert_cursor, insight_cursor) else {
+            let (max_alert, max_insight) = self.db.max_notification_source_ids()?;
+            self.db.set_notification_cursor(ALERT_CURSOR, max_alert)?;
+            self.db
+                .set_notification_cursor(INSIGHT_CURSOR, max_insight)?;
+            return Ok(0);
+        };
+
+        let mut queued = 0;
+        for (cursor_key, sources) in [
+            (
+                ALERT_CURSOR,
+                self.db.alert_notification_sources(alert_cursor)?,
+            ),
+            (
+                INSIGHT_CURSOR,
+                self.db.insight_notification_sources(insight_cursor)?,
+            ),
+        ] {
+            let Some(last) = sources.last().map(|s| s.source_id) else {
+                continue;
+            };
+            for source in &sources {
+                queued += self.route(source, now)?;
+            }
+            self.db.set_notification_cursor(cursor_key, last)?;
+        }
+        Ok(queued)
+    }
+
+    fn route(&self, source: &NotificationSource, now: DateTime<Utc>) -> Result<usize> {
+        let mut queued = 0;
+        for route in &self.config.routes {
+            if !route.matches(&source.event, &source.severity) {
+                continue;
+            }
+            let next_attempt_at = if route.digest {
+                next_local_time(now, self.digest_time())
+            } else if !route.bypass_quiet_hours && self.in_quiet_hours(now) {
+                self.quiet_hours_end(now)
+            } else {
+                now
+            };
+            for channel in &route.channels {
+                let created = self
+                    .db
+                    .create_notification_delivery(&NewNotificationDelivery {
+                        channel: channel.clone(),
+                        source_type: source.source_type.clone(),
+                        source_id: Some(source.source_id),
+                        event: source.event.clone(),
+                        severity: source.severity.clone(),
+                        title: source.title.clone(),
+                        body: source.body.clone(),
+                        digest: route.digest,
+                        urgent: route.bypass_quiet_hours,
+                        next_attempt_at,
+                    })?;
+                if created.is_some() {
+                    queued += 1;
+                }
+            }
+        }
+        Ok(queued)
+    }
+
+    fn digest_time(&self) -> NaiveTime {
+        NaiveTime::from_hms_opt(self.config.digest_hour, 0, 0).unwrap_or(NaiveTime::MIN)
+    }
+
+    /// Whether `now` falls inside the configured quiet hours (local time)
+    pub fn in_quiet_hours(&self, now: DateTime<Utc>) -> bool {
+        self.config
+            .quiet_hours
+            .as_ref()
+            .is_some_and(|q| q.contains(now.with_timezone(&Local).time()))
+    }
+
+    fn quiet_hours_end(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self
+            .config
+            .quiet_hours
+            .as_ref()
+            .and_then(|q| q.bounds().ok())
+        {
+            Some((_, end)) => next_local_time(now, end),
+            None => now,
+        }
+    }
+}
+
+/// Next occurrence of a local wall-clock time after `now`
+fn next_local_time(now: DateTime<Utc>, time: NaiveTime) -> DateTime<Utc> {
+    let local = now.with_timezone(&Local).naive_local();
+    let mut candidate = local.date().and_time(time);
+    if candidate <= local {
+        candidate += Duration::days(1);
+    }
+    Local
+        .from_local_datetime(&candidate)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or(now + Duration::days(1))
+}
+
+/// Combine digest deliveries into one message
+fn digest_notification(deliveries: &[NotificationDelivery]) -> Notification {
+    let severity = deliveries
+        .iter()
+        .filter_map(|d| d.severity.parse::<crate::insights::Severity>().ok())
+        .max_by_key(|s| s.priority())
+        .map_or("info", |s| s.as_str());
+    let body = deliveries
+        .iter()
+        .map(|d| format!("- {}: {}", d.title, d.body))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Notification {
+        event: "digest".to_string(),
+        title: format!("Hone digest: {} new notifications", deliveries.len()),
+        body,
+        severity: severity.to_string(),
+    }
+}
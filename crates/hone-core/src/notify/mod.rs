@@ -0,0 +1,379 @@
+//! Outbound notifications for alerts and insights
+//!
+//! New alerts and insight findings are routed to one or more channels:
+//!
+//! - `webhook` - JSON POST signed with HMAC-SHA256
+//! - `smtp` - plain-text email
+//! - `ntfy` / `gotify` - push notification servers
+//! - `matrix` - Matrix webhook bridge (hookshot-style `{text, html}` payload)
+//!
+//! # Architecture
+//!
+//! - `NotificationChannel` trait defines the interface for delivery backends
+//! - `NotifyConfig` holds channels, routing rules, quiet hours and digest settings
+//! - `Notifier` queues deliveries in the database and sends them with retries
+//!
+//! Configuration lives in `~/.local/share/hone/config/notifications.toml`
+//! (override with `HONE_NOTIFY_CONFIG`):
+//!
+//! ```toml
+//! digest_hour = 8
+//!
+//! [quiet_hours]
+//! start = "22:00"
+//! end = "07:00"
+//!
+//! [[channels]]
+//! name = "phone"
+//! type = "ntfy"
+//! url = "https://ntfy.sh/my-hone-topic"
+//!
+//! [[routes]]
+//! events = ["alert:zombie", "alert:price_increase"]
+//! channels = ["phone"]
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::insights::Severity;
+
+mod dispatch;
+mod push;
+mod smtp;
+mod webhook;
+
+pub use dispatch::{Notifier, NotifyRunSummary};
+pub use push::{GotifyChannel, MatrixChannel, NtfyChannel};
+pub use smtp::{SmtpChannel, SmtpTls};
+pub use webhook::{sign_payload, WebhookChannel};
+
+/// A message ready to be sent to a channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    /// Event name (e.g. `alert:zombie`, `insight:spending_explainer`, `digest`)
+    pub event: String,
+    /// Short title
+    pub title: String,
+    /// Plain-text body
+    pub body: String,
+    /// Severity (`info`, `attention`, `warning`, `alert`)
+    pub severity: String,
+}
+
+/// Interface for notification delivery backends
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    /// Channel name from the config
+    fn name(&self) -> &str;
+
+    /// Deliver a notification
+    async fn send(&self, notification: &Notification) -> Result<()>;
+}
+
+/// A configured channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelConfig {
+    /// Name used by routes
+    pub name: String,
+    /// Backend settings
+    #[serde(flatten)]
+    pub kind: ChannelKind,
+}
+
+/// Backend-specific channel settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChannelKind {
+    /// Generic JSON webhook
+    Webhook {
+        url: String,
+        /// HMAC-SHA256 signing secret
+        #[serde(default)]
+        secret: Option<String>,
+    },
+    /// SMTP email
+    Smtp {
+        host: String,
+        #[serde(default)]
+        port: Option<u16>,
+        #[serde(default)]
+        tls: SmtpTls,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password: Option<String>,
+        from: String,
+        to: Vec<String>,
+    },
+    /// ntfy topic URL
+    Ntfy {
+        url: String,
+        #[serde(default)]
+        token: Option<String>,
+        #[serde(default)]
+        priority: Option<u8>,
+    },
+    /// Gotify server
+    Gotify {
+        url: String,
+        token: String,
+        #[serde(default)]
+        priority: Option<u8>,
+    },
+    /// Matrix webhook bridge URL
+    Matrix { url: String },
+}
+
+impl ChannelConfig {
+    /// Backend type name
+    pub fn kind_name(&self) -> &'static str {
+        match self.kind {
+            ChannelKind::Webhook { .. } => "webhook",
+            ChannelKind::Smtp { .. } => "smtp",
+            ChannelKind::Ntfy { .. } => "ntfy",
+            ChannelKind::Gotify { .. } => "gotify",
+            ChannelKind::Matrix { .. } => "matrix",
+        }
+    }
+
+    /// Create the channel backend
+    pub fn build(&self) -> Box<dyn NotificationChannel> {
+        let name = self.name.clone();
+        match &self.kind {
+            ChannelKind::Webhook { url, secret } => {
+                Box::new(WebhookChannel::new(name, url.clone(), secret.clone()))
+            }
+            ChannelKind::Smtp {
+                host,
+                port,
+                tls,
+                username,
+                password,
+                from,
+                to,
+            } => Box::new(SmtpChannel {
+                name,
+                host: host.clone(),
+                port: port.unwrap_or_else(|| tls.default_port()),
+                tls: *tls,
+                credentials: username.clone().zip(password.clone()),
+                from: from.clone(),
+                to: to.clone(),
+            }),
+            ChannelKind::Ntfy {
+                url,
+                token,
+                priority,
+            } => Box::new(NtfyChannel::new(
+                name,
+                url.clone(),
+                token.clone(),
+                *priority,
+            )),
+            ChannelKind::Gotify {
+                url,
+                token,
+                priority,
+            } => Box::new(GotifyChannel::new(
+                name,
+                url.clone(),
+                token.clone(),
+                *priority,
+            )),
+            ChannelKind::Matrix { url } => Box::new(MatrixChannel::new(name, url.clone())),
+        }
+    }
+}
+
+/// Routing rule: which events go to which channels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteConfig {
+    /// Event patterns: `alert:zombie`, `alert:*`, `insight:*`, `*`
+    pub events: Vec<String>,
+    /// Channel names to deliver to
+    pub channels: Vec<String>,
+    /// Batch into the daily digest instead of sending immediately
+    #[serde(default)]
+    pub digest: bool,
+    /// Only route events at or above this severity
+    #[serde(default)]
+    pub min_severity: Option<Severity>,
+    /// Send even during quiet hours
+    #[serde(default)]
+    pub bypass_quiet_hours: bool,
+}
+
+impl RouteConfig {
+    /// Whether this route applies to an event with the given severity
+    pub fn matches(&self, event: &str, severity: &str) -> bool {
+        let severity_ok = match (self.min_severity, severity.parse::<Severity>()) {
+            (Some(min), Ok(sev)) => sev.priority() >= min.priority(),
+            (Some(_), Err(_)) => false,
+            (None, _) => true,
+        };
+        severity_ok && self.events.iter().any(|p| event_matches(p, event))
+    }
+}
+
+/// Match an event name against a route pattern (`*` and `prefix:*` wildcards)
+pub fn event_matches(pattern: &str, event: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => event.starts_with(prefix),
+        None => pattern == event,
+    }
+}
+
+/// Quiet hours in local time (`HH:MM`); may wrap past midnight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+impl QuietHours {
+    fn parse_time(value: &str) -> Result<NaiveTime> {
+        NaiveTime::parse_from_str(value, "%H:%M")
+            .map_err(|_| Error::Notification(format!("Invalid quiet hours time: {}", value)))
+    }
+
+    /// Start and end times
+    pub fn bounds(&self) -> Result<(NaiveTime, NaiveTime)> {
+        Ok((Self::parse_time(&self.start)?, Self::parse_time(&self.end)?))
+    }
+
+    /// Whether a local time falls inside quiet hours
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let Ok((start, end)) = self.bounds() else {
+            return false;
+        };
+        if start <= end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+fn default_digest_hour() -> u32 {
+    8
+}
+
+fn default_max_attempts() -> u32 {
+    5
+}
+
+/// Notification configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifyConfig {
+    #[serde(default)]
+    pub channels: Vec<ChannelConfig>,
+    #[serde(default)]
+    pub routes: Vec<RouteConfig>,
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+    /// Local hour (0-23) when digests are sent
+    #[serde(default = "default_digest_hour")]
+    pub digest_hour: u32,
+    /// Attempts before a delivery is marked failed
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+}
+
+impl NotifyConfig {
+    /// Parse and validate a TOML config
+    pub fn parse(content: &str) -> Result<Self> {
+        let config: NotifyConfig = toml::from_str(content)
+            .map_err(|e| Error::Notification(format!("Invalid notification config: {}", e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load from a path, or `HONE_NOTIFY_CONFIG`, or the default location
+    ///
+    /// Returns None when no config file exists (notifications disabled).
+    pub fn load(path: Option<&Path>) -> Result<Option<Self>> {
+        let path = match path {
+            Some(p) => Some(p.to_path_buf()),
+            None => std::env::var("HONE_NOTIFY_CONFIG")
+                .ok()
+                .map(PathBuf::from)
+                .or_else(default_config_path),
+        };
+        let Some(path) = path.filter(|p| p.exists()) else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(&path).map_err(|e| {
+            Error::Notification(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        Self::parse(&content).map(Some)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.digest_hour > 23 {
+            return Err(Error::Notification(
+                "digest_hour must be between 0 and 23".to_string(),
+            ));
+        }
+        if self.max_attempts == 0 {
+            return Err(Error::Notification(
+                "max_attempts must be at least 1".to_string(),
+            ));
+        }
+        if let Some(quiet) = &self.quiet_hours {
+            quiet.bounds()?;
+        }
+        for route in &self.routes {
+            for channel in &route.channels {
+                if self.channel(channel).is_none() {
+                    return Err(Error::Notification(format!(
+                        "Route references unknown channel: {}",
+                        channel
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Find a channel by name
+    pub fn channel(&self, name: &str) -> Option<&ChannelConfig> {
+        self.channels.iter().find(|c| c.name == name)
+    }
+}
+
+/// Default config path
+pub fn default_config_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("hone").join("config").join("notifications.toml"))
+}
+
+/// Shared HTTP client for webhook-style channels
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Turn a non-success HTTP response into an error
+async fn check_response(channel: &str, response: reqwest::Response) -> Result<()> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(Error::Notification(format!(
+        "{} returned {}: {}",
+        channel,
+        status,
+        body.chars().take(200).collect::<String>()
+    )))
+}
+
+#[cfg(test)]
+mod tests;
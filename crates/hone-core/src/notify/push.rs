@@ -0,0 +1,160 @@
+//! Push-style channels: ntfy, Gotify and Matrix webhook bridges
+
+use async_trait::async_trait;
+
+use super::{check_response, http_client, Notification, NotificationChannel};
+use crate::error::{Error, Result};
+
+/// Map a severity to a 1-5 push priority
+fn severity_priority(severity: &str) -> u8 {
+    match severity {
+        "alert" => 5,
+        "warning" => 4,
+        "attention" => 3,
+        _ => 2,
+    }
+}
+
+/// ntfy backend (`url` is the full topic URL)
+pub struct NtfyChannel {
+    name: String,
+    url: String,
+    token: Option<String>,
+    priority: Option<u8>,
+    client: reqwest::Client,
+}
+
+impl NtfyChannel {
+    pub fn new(name: String, url: String, token: Option<String>, priority: Option<u8>) -> Self {
+        Self {
+            name,
+            url,
+            token,
+            priority,
+            client: http_client(),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for NtfyChannel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let priority = self
+            .priority
+            .unwrap_or_else(|| severity_priority(&notification.severity));
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("Title", &notification.title)
+            .header("Priority", priority.to_string())
+            .header("Tags", notification.event.replace(':', ","));
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request
+            .body(notification.body.clone())
+            .send()
+            .await
+            .map_err(|e| Error::Notification(format!("{}: {}", self.name, e)))?;
+        check_response(&self.name, response).await
+    }
+}
+
+/// Gotify backend (`url` is the server base URL, `token` an application token)
+pub struct GotifyChannel {
+    name: String,
+    url: String,
+    token: String,
+    priority: Option<u8>,
+    client: reqwest::Client,
+}
+
+impl GotifyChannel {
+    pub fn new(name: String, url: String, token: String, priority: Option<u8>) -> Self {
+        Self {
+            name,
+            url,
+            token,
+            priority,
+            client: http_client(),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for GotifyChannel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        // Gotify priorities run 0-10
+        let priority = self
+            .priority
+            .unwrap_or_else(|| severity_priority(&notification.severity) * 2);
+        let response = self
+            .client
+            .post(format!("{}/message", self.url.trim_end_matches('/')))
+            .header("X-Gotify-Key", &self.token)
+            .json(&serde_json::json!({
+                "title": notification.title,
+                "message": notification.body,
+                "priority": priority,
+            }))
+            .send()
+            .await
+            .map_err(|e| Error::Notification(format!("{}: {}", self.name, e)))?;
+        check_response(&self.name, response).await
+    }
+}
+
+/// Matrix webhook bridge backend (e.g. matrix-hookshot generic webhooks)
+pub struct MatrixChannel {
+    name: String,
+    url: String,
+    client: reqwest::Client,
+}
+
+impl MatrixChannel {
+    pub fn new(name: String, url: String) -> Self {
+        Self {
+            name,
+            url,
+            client: http_client(),
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[async_trait]
+impl NotificationChannel for MatrixChannel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let text = format!("{}\n{}", notification.title, notification.body);
+        let html = format!(
+            "<strong>{}</strong><br>{}",
+            escape_html(&notification.title),
+            escape_html(&notification.body).replace('\n', "<br>")
+        );
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&serde_json::json!({ "text": text, "html": html }))
+            .send()
+            .await
+            .map_err(|e| Error::Notification(format!("{}: {}", self.name, e)))?;
+        check_response(&self.name, response).await
+    }
+}
@@ -0,0 +1,233 @@
+//! SMTP email channel
+//!
+//! A minimal SMTP client: EHLO, optional STARTTLS or implicit TLS,
+//! AUTH PLAIN, and a single plain-text message per notification.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use super::{Notification, NotificationChannel};
+use crate::error::{Error, Result};
+use crate::net::tls::Stream;
+
+/// Overall timeout for one SMTP conversation
+const SMTP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Timeout for connecting and for each reply
+const SMTP_STEP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Connection security
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpTls {
+    /// Plain text (local relays only)
+    None,
+    /// Upgrade with STARTTLS (port 587)
+    #[default]
+    Starttls,
+    /// Implicit TLS (port 465)
+    Tls,
+}
+
+impl SmtpTls {
+    pub fn default_port(&self) -> u16 {
+        match self {
+            SmtpTls::None => 25,
+            SmtpTls::Starttls => 587,
+            SmtpTls::Tls => 465,
+        }
+    }
+}
+
+/// SMTP delivery backend
+pub struct SmtpChannel {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    pub tls: SmtpTls,
+    /// Username and password for AUTH PLAIN
+    pub credentials: Option<(String, String)>,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+/// One SMTP session
+struct Session {
+    stream: Stream,
+    buf: Vec<u8>,
+}
+
+impl Session {
+    /// Read a (possibly multi-line) reply and check its code
+    async fn expect(&mut self, codes: &[u16]) -> Result<String> {
+        let mut reply = String::new();
+        loop {
+            let line = self.read_line().await?;
+            reply.push_str(&line);
+            reply.push('\n');
+            // "250-..." continues, "250 ..." ends
+            if line.len() < 4 || line.as_bytes()[3] != b'-' {
+                let code: u16 = line.get(..3).and_then(|c| c.parse().ok()).unwrap_or(0);
+                if codes.contains(&code) {
+                    return Ok(reply);
+                }
+                return Err(Error::Notification(format!(
+                    "SMTP error: {}",
+                    reply.trim_end()
+                )));
+            }
+        }
+    }
+
+    async fn read_line(&mut self) -> Result<String> {
+        loop {
+            if let Some(pos) = self.buf.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buf.drain(..=pos).collect();
+                return Ok(String::from_utf8_lossy(&line).trim_end().to_string());
+            }
+            let mut chunk = [0u8; 1024];
+            let n = self
+                .stream
+                .read(&mut chunk, SMTP_STEP_TIMEOUT, Error::Notification)
+                .await?;
+            if n == 0 {
+                return Err(Error::Notification(
+                    "SMTP server closed the connection".to_string(),
+                ));
+            }
+            self.buf.extend_from_slice(&chunk[..n]);
+        }
+    }
+
+    async fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.stream.write_all(data).await?;
+        Ok(())
+    }
+
+    async fn command(&mut self, line: &str, codes: &[u16]) -> Result<String> {
+        self.write(format!("{}\r\n", line).as_bytes()).await?;
+        self.expect(codes).await
+    }
+}
+
+/// Strip CR/LF so values can't inject headers
+fn header_value(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+/// Bare address for the SMTP envelope (`Hone <hone@example.com>` -> `hone@example.com`)
+fn envelope_address(value: &str) -> String {
+    let value = header_value(value);
+    match value.rfind('<').zip(value.rfind('>')) {
+        Some((start, end)) if start < end => value[start + 1..end].trim().to_string(),
+        _ => value.trim().to_string(),
+    }
+}
+
+/// RFC 2047 encode a header if it isn't plain ASCII
+fn encode_header(value: &str) -> String {
+    let value = header_value(value);
+    if value.is_ascii() {
+        value
+    } else {
+        format!(
+            "=?UTF-8?B?{}?=",
+            base64::engine::general_purpose::STANDARD.encode(value)
+        )
+    }
+}
+
+impl SmtpChannel {
+    /// Build the RFC 5322 message (with SMTP dot-stuffing applied)
+    fn message(&self, notification: &Notification) -> String {
+        let mut message = format!(
+            "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\nX-Hone-Event: {}\r\n\r\n",
+            header_value(&self.from),
+            header_value(&self.to.join(", ")),
+            encode_header(&notification.title),
+            chrono::Utc::now().to_rfc2822(),
+            header_value(&notification.event),
+        );
+        for line in notification.body.lines() {
+            if line.starts_with('.') {
+                message.push('.');
+            }
+            message.push_str(line);
+            message.push_str("\r\n");
+        }
+        message.push_str(".\r\n");
+        message
+    }
+
+    async fn deliver(&self, notification: &Notification) -> Result<()> {
+        let stream = Stream::connect(
+            &self.host,
+            self.port,
+            self.tls == SmtpTls::Tls,
+            SMTP_STEP_TIMEOUT,
+            Error::Notification,
+        )
+        .await?;
+        let mut session = Session {
+            stream,
+            buf: Vec::new(),
+        };
+
+        session.expect(&[220]).await?;
+        session.command("EHLO hone", &[250]).await?;
+
+        if self.tls == SmtpTls::Starttls {
+            session.command("STARTTLS", &[220]).await?;
+            session = Session {
+                stream: session
+                    .stream
+                    .starttls(&self.host, SMTP_STEP_TIMEOUT, Error::Notification)
+                    .await?,
+                buf: Vec::new(),
+            };
+            session.command("EHLO hone", &[250]).await?;
+        }
+
+        if let Some((username, password)) = &self.credentials {
+            let token = base64::engine::general_purpose::STANDARD
+                .encode(format!("\0{}\0{}", username, password));
+            session
+                .command(&format!("AUTH PLAIN {}", token), &[235])
+                .await?;
+        }
+
+        session
+            .command(
+                &format!("MAIL FROM:<{}>", envelope_address(&self.from)),
+                &[250],
+            )
+            .await?;
+        for to in &self.to {
+            session
+                .command(&format!("RCPT TO:<{}>", envelope_address(to)), &[250, 251])
+                .await?;
+        }
+        session.command("DATA", &[354]).await?;
+        session.write(self.message(notification).as_bytes()).await?;
+        session.expect(&[250]).await?;
+        // The message is accepted; a failed QUIT doesn't matter
+        let _ = session.command("QUIT", &[221]).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for SmtpChannel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        tokio::time::timeout(SMTP_TIMEOUT, self.deliver(notification))
+            .await
+            .map_err(|_| Error::Notification(format!("{}: SMTP timed out", self.name)))?
+    }
+}
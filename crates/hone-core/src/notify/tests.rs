@@ -0,0 +1,487 @@
+//! Notification tests (channels run against local stand-in servers)
+
+use super::*;
+
+#[test]
+fn test_event_matches() {
+    assert!(event_matches("*", "alert:zombie"));
+    assert!(event_matches("alert:*", "alert:zombie"));
+    assert!(event_matches("alert:zombie", "alert:zombie"));
+    assert!(!event_matches("alert:zombie", "alert:resume"));
+    assert!(!event_matches("insight:*", "alert:zombie"));
+}
+
+#[test]
+fn test_route_min_severity() {
+    let route = RouteConfig {
+        events: vec!["insight:*".to_string()],
+        channels: vec![],
+        digest: false,
+        min_severity: Some(Severity::Warning),
+        bypass_quiet_hours: false,
+    };
+    assert!(route.matches("insight:savings_opportunity", "alert"));
+    assert!(route.matches("insight:savings_opportunity", "warning"));
+    assert!(!route.matches("insight:savings_opportunity", "info"));
+}
+
+#[test]
+fn test_quiet_hours_wrap_midnight() {
+    let quiet = QuietHours {
+        start: "22:00".to_string(),
+        end: "07:00".to_string(),
+    };
+    let t = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+    assert!(quiet.contains(t(23, 30)));
+    assert!(quiet.contains(t(6, 59)));
+    assert!(!quiet.contains(t(7, 0)));
+    assert!(!quiet.contains(t(12, 0)));
+}
+
+#[test]
+fn test_parse_config() {
+    let config = NotifyConfig::parse(
+        r#"
+        [quiet_hours]
+        start = "22:00"
+        end = "07:00"
+
+        [[channels]]
+        name = "hook"
+        type = "webhook"
+        url = "http://localhost/hook"
+        secret = "s3cret"
+
+        [[channels]]
+        name = "mail"
+        type = "smtp"
+        host = "mail.example.com"
+        tls = "starttls"
+        from = "hone@example.com"
+        to = ["me@example.com"]
+
+        [[routes]]
+        events = ["alert:*"]
+        channels = ["hook", "mail"]
+        digest = true
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.channels.len(), 2);
+    assert_eq!(config.channel("mail").unwrap().kind_name(), "smtp");
+    assert_eq!(config.digest_hour, 8);
+    assert_eq!(config.max_attempts, 5);
+    assert!(config.routes[0].digest);
+}
+
+#[test]
+fn test_parse_config_rejects_unknown_channel() {
+    let err = NotifyConfig::parse(
+        r#"
+        [[routes]]
+        events = ["*"]
+        channels = ["missing"]
+        "#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("unknown channel"));
+}
+
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+
+use crate::db::Database;
+use crate::insights::{Finding, InsightType};
+use crate::models::{AlertType, DeliveryStatus};
+use crate::test_utils::{MockNotificationServer, MockSmtpServer};
+
+fn notification() -> Notification {
+    Notification {
+        event: "alert:zombie".to_string(),
+        title: "Zombie subscription: Netflix".to_string(),
+        body: "No activity in 90 days".to_string(),
+        severity: "warning".to_string(),
+    }
+}
+
+fn webhook_config(url: &str, routes: &str) -> NotifyConfig {
+    NotifyConfig::parse(&format!(
+        r#"
+        [[channels]]
+        name = "hook"
+        type = "webhook"
+        url = "{}/hook"
+        secret = "s3cret"
+
+        {}
+        "#,
+        url, routes
+    ))
+    .unwrap()
+}
+
+/// A local time on a fixed date, as UTC
+fn local_time(hour: u32, minute: u32) -> DateTime<Utc> {
+    Local
+        .with_ymd_and_hms(2026, 3, 10, hour, minute, 0)
+        .earliest()
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+#[tokio::test]
+async fn test_webhook_channel_signs_payload() {
+    let server = MockNotificationServer::start().await;
+    let channel = WebhookChannel::new(
+        "hook".to_string(),
+        format!("{}/hook", server.url()),
+        Some("s3cret".to_string()),
+    );
+    channel.send(&notification()).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    assert_eq!(request.path, "/hook");
+    let timestamp: i64 = request.headers["x-hone-timestamp"].parse().unwrap();
+    assert_eq!(
+        request.headers["x-hone-signature"],
+        sign_payload("s3cret", timestamp, request.body.as_bytes())
+    );
+    let body: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+    assert_eq!(body["event"], "alert:zombie");
+    assert_eq!(body["title"], "Zombie subscription: Netflix");
+}
+
+#[tokio::test]
+async fn test_webhook_channel_reports_http_errors() {
+    let server = MockNotificationServer::start().await;
+    server.fail_next(1);
+    let channel = WebhookChannel::new("hook".to_string(), server.url(), None);
+    let err = channel.send(&notification()).await.unwrap_err();
+    assert!(err.to_string().contains("500"));
+}
+
+#[tokio::test]
+async fn test_push_channels() {
+    let server = MockNotificationServer::start().await;
+
+    NtfyChannel::new(
+        "ntfy".to_string(),
+        format!("{}/hone", server.url()),
+        Some("tk_123".to_string()),
+        None,
+    )
+    .send(&notification())
+    .await
+    .unwrap();
+    GotifyChannel::new(
+        "gotify".to_string(),
+        format!("{}/", server.url()),
+        "app-token".to_string(),
+        None,
+    )
+    .send(&notification())
+    .await
+    .unwrap();
+    MatrixChannel::new(
+        "matrix".to_string(),
+        format!("{}/webhook/abc", server.url()),
+    )
+    .send(&notification())
+    .await
+    .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+
+    let ntfy = &requests[0];
+    assert_eq!(ntfy.path, "/hone");
+    assert_eq!(ntfy.headers["title"], "Zombie subscription: Netflix");
+    assert_eq!(ntfy.headers["priority"], "4");
+    assert_eq!(ntfy.headers["authorization"], "Bearer tk_123");
+    assert_eq!(ntfy.body, "No activity in 90 days");
+
+    let gotify = &requests[1];
+    assert_eq!(gotify.path, "/message");
+    assert_eq!(gotify.headers["x-gotify-key"], "app-token");
+    let body: serde_json::Value = serde_json::from_str(&gotify.body).unwrap();
+    assert_eq!(body["message"], "No activity in 90 days");
+    assert_eq!(body["priority"], 8);
+
+    let matrix = &requests[2];
+    assert_eq!(matrix.path, "/webhook/abc");
+    let body: serde_json::Value = serde_json::from_str(&matrix.body).unwrap();
+    assert!(body["text"].as_str().unwrap().contains("Netflix"));
+    assert!(body["html"].as_str().unwrap().starts_with("<strong>"));
+}
+
+#[tokio::test]
+async fn test_smtp_channel() {
+    let server = MockSmtpServer::start().await;
+    let channel = SmtpChannel {
+        name: "mail".to_string(),
+        host: "127.0.0.1".to_string(),
+        port: server.port(),
+        tls: SmtpTls::None,
+        credentials: Some(("hone".to_string(), "secret".to_string())),
+        from: "Hone <hone@example.com>".to_string(),
+        to: vec!["me@example.com".to_string()],
+    };
+    let mut message = notification();
+    message.body = "line one\n.leading dot".to_string();
+    channel.send(&message).await.unwrap();
+
+    // The envelope takes the bare address; the header keeps the display name
+    assert_eq!(
+        server.envelope(),
+        vec!["MAIL FROM:<hone@example.com>", "RCPT TO:<me@example.com>"]
+    );
+    let messages = server.messages();
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].contains("From: Hone <hone@example.com>"));
+    assert!(messages[0].contains("Subject: Zombie subscription: Netflix"));
+    assert!(messages[0].contains("To: me@example.com"));
+    // Dot-stuffed on the wire
+    assert!(messages[0].contains("\n..leading dot\n"));
+}
+
+#[tokio::test]
+async fn test_notifier_first_run_skips_backlog() {
+    let server = MockNotificationServer::start().await;
+    let db = Database::in_memory().unwrap();
+    db.create_alert(AlertType::Zombie, None, Some("old alert"))
+        .unwrap();
+
+    let notifier = Notifier::new(
+        db.clone(),
+        webhook_config(
+            &server.url(),
+            "[[routes]]\nevents = [\"*\"]\nchannels = [\"hook\"]",
+        ),
+    );
+    let summary = notifier.run_once(Utc::now()).await.unwrap();
+    assert_eq!(summary.queued, 0);
+    assert!(server.requests().is_empty());
+
+    db.create_alert(AlertType::PriceIncrease, None, Some("Netflix went up"))
+        .unwrap();
+    let summary = notifier.run_once(Utc::now()).await.unwrap();
+    assert_eq!(summary.queued, 1);
+    assert_eq!(summary.sent, 1);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(body["event"], "alert:price_increase");
+    assert_eq!(body["body"], "Netflix went up");
+
+    // Already routed: nothing new on the next run
+    let summary = notifier.run_once(Utc::now()).await.unwrap();
+    assert_eq!(summary.queued + summary.sent, 0);
+}
+
+#[tokio::test]
+async fn test_notifier_routes_by_event_and_severity() {
+    let server = MockNotificationServer::start().await;
+    let db = Database::in_memory().unwrap();
+    let notifier = Notifier::new(
+        db.clone(),
+        webhook_config(
+            &server.url(),
+            r#"
+            [[routes]]
+            events = ["alert:zombie"]
+            channels = ["hook"]
+
+            [[routes]]
+            events = ["insight:*"]
+            channels = ["hook"]
+            min_severity = "warning"
+            "#,
+        ),
+    );
+    notifier.run_once(Utc::now()).await.unwrap();
+
+    db.create_alert(AlertType::Zombie, None, None).unwrap();
+    db.create_alert(AlertType::Duplicate, None, None).unwrap();
+    db.upsert_insight_finding(&Finding::new(
+        InsightType::SavingsOpportunity,
+        "savings:1",
+        Severity::Info,
+        "Minor saving",
+        "Not worth a ping",
+    ))
+    .unwrap();
+    db.upsert_insight_finding(&Finding::new(
+        InsightType::SavingsOpportunity,
+        "savings:2",
+        Severity::Warning,
+        "Big saving",
+        "Cancel the gym",
+    ))
+    .unwrap();
+
+    let summary = notifier.run_once(Utc::now()).await.unwrap();
+    assert_eq!(summary.queued, 2);
+    assert_eq!(summary.sent, 2);
+
+    let events: Vec<String> = server
+        .requests()
+        .iter()
+        .map(|r| {
+            let body: serde_json::Value = serde_json::from_str(&r.body).unwrap();
+            body["event"].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(events, vec!["alert:zombie", "insight:savings_opportunity"]);
+}
+
+#[tokio::test]
+async fn test_notifier_retries_with_backoff() {
+    let server = MockNotificationServer::start().await;
+    let db = Database::in_memory().unwrap();
+    let mut config = webhook_config(
+        &server.url(),
+        "[[routes]]\nevents = [\"*\"]\nchannels = [\"hook\"]",
+    );
+    config.max_attempts = 2;
+    let notifier = Notifier::new(db.clone(), config);
+    let now = Utc::now();
+    notifier.run_once(now).await.unwrap();
+
+    db.create_alert(AlertType::Zombie, None, None).unwrap();
+    db.create_alert(AlertType::Resume, None, None).unwrap();
+    server.fail_next(3);
+
+    let summary = notifier.run_once(now).await.unwrap();
+    assert_eq!(summary.failed, 2);
+    let pending = db
+        .list_notification_deliveries(Some(DeliveryStatus::Pending), 10)
+        .unwrap();
+    assert_eq!(pending.len(), 2);
+    assert!(pending.iter().all(|d| d.attempts == 1));
+    assert!(pending[0].last_error.as_deref().unwrap().contains("500"));
+
+    // Not due yet (backoff is 2 minutes after the first failure)
+    let summary = notifier.run_once(now + Duration::minutes(1)).await.unwrap();
+    assert_eq!(summary.sent + summary.failed, 0);
+
+    // One more failure exhausts max_attempts; the other succeeds
+    let summary = notifier.run_once(now + Duration::minutes(3)).await.unwrap();
+    assert_eq!(summary.failed, 1);
+    assert_eq!(summary.sent, 1);
+
+    let failed = db
+        .list_notification_deliveries(Some(DeliveryStatus::Failed), 10)
+        .unwrap();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].attempts, 2);
+    assert!(failed[0].next_attempt_at.is_none());
+
+    // Manual retry re-queues it
+    assert!(db.retry_notification_delivery(failed[0].id).unwrap());
+    let summary = notifier
+        .run_once(Utc::now() + Duration::seconds(1))
+        .await
+        .unwrap();
+    assert_eq!(summary.sent, 1);
+}
+
+#[tokio::test]
+async fn test_notifier_digest_batches_per_channel() {
+    let server = MockNotificationServer::start().await;
+    let db = Database::in_memory().unwrap();
+    let mut config = webhook_config(
+        &server.url(),
+        "[[routes]]\nevents = [\"alert:*\"]\nchannels = [\"hook\"]\ndigest = true",
+    );
+    config.digest_hour = 8;
+    let notifier = Notifier::new(db.clone(), config);
+
+    let evening = local_time(18, 0);
+    notifier.run_once(evening).await.unwrap();
+    db.create_alert(AlertType::Zombie, None, Some("Netflix unused"))
+        .unwrap();
+    db.create_alert(AlertType::PriceIncrease, None, Some("Spotify up $2"))
+        .unwrap();
+
+    let summary = notifier.run_once(evening).await.unwrap();
+    assert_eq!(summary.queued, 2);
+    assert_eq!(summary.sent, 0);
+
+    // Next morning at the digest hour: one message for both
+    let summary = notifier
+        .run_once(evening + Duration::hours(14))
+        .await
+        .unwrap();
+    assert_eq!(summary.sent, 2);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert_eq!(body["event"], "digest");
+    assert!(body["title"].as_str().unwrap().contains("2 new"));
+    assert!(body["body"].as_str().unwrap().contains("Netflix unused"));
+    assert!(body["body"].as_str().unwrap().contains("Spotify up $2"));
+}
+
+#[tokio::test]
+async fn test_notifier_quiet_hours() {
+    let server = MockNotificationServer::start().await;
+    let db = Database::in_memory().unwrap();
+    let config = webhook_config(
+        &server.url(),
+        r#"
+        [quiet_hours]
+        start = "22:00"
+        end = "07:00"
+
+        [[routes]]
+        events = ["alert:zombie"]
+        channels = ["hook"]
+
+        [[routes]]
+        events = ["alert:duplicate"]
+        channels = ["hook"]
+        bypass_quiet_hours = true
+        "#,
+    );
+    let notifier = Notifier::new(db.clone(), config);
+
+    let late = local_time(23, 0);
+    assert!(notifier.in_quiet_hours(late));
+    notifier.run_once(late).await.unwrap();
+    db.create_alert(AlertType::Zombie, None, None).unwrap();
+    db.create_alert(AlertType::Duplicate, None, None).unwrap();
+
+    // Only the urgent route goes out during quiet hours
+    let summary = notifier.run_once(late).await.unwrap();
+    assert_eq!(summary.queued, 2);
+    assert_eq!(summary.sent, 1);
+
+    let summary = notifier.run_once(late + Duration::hours(2)).await.unwrap();
+    assert_eq!(summary.sent, 0);
+
+    // Held delivery goes out once quiet hours end
+    let summary = notifier.run_once(late + Duration::hours(8)).await.unwrap();
+    assert_eq!(summary.sent, 1);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_notifier_send_test_records_delivery() {
+    let server = MockNotificationServer::start().await;
+    let db = Database::in_memory().unwrap();
+    let notifier = Notifier::new(db.clone(), webhook_config(&server.url(), ""));
+
+    let delivery = notifier.send_test("hook").await.unwrap();
+    assert_eq!(delivery.status, DeliveryStatus::Sent);
+    assert_eq!(delivery.source_type, "test");
+    assert_eq!(server.requests().len(), 1);
+
+    server.fail_next(1);
+    let delivery = notifier.send_test("hook").await.unwrap();
+    assert_eq!(delivery.status, DeliveryStatus::Failed);
+    assert!(delivery.last_error.is_some());
+
+    assert!(notifier.send_test("missing").await.is_err());
+}
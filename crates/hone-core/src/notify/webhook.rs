@@ -0,0 +1,75 @@
+//! Generic JSON webhook channel
+//!
+//! Posts the notification as JSON. When a secret is configured, the request
+//! carries `X-Hone-Timestamp` and `X-Hone-Signature: sha256=<hex>`, where the
+//! signature is HMAC-SHA256 over `<timestamp>.<body>`.
+
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use super::{check_response, http_client, Notification, NotificationChannel};
+use crate::error::{Error, Result};
+
+/// Webhook delivery backend
+pub struct WebhookChannel {
+    name: String,
+    url: String,
+    secret: Option<String>,
+    client: reqwest::Client,
+}
+
+impl WebhookChannel {
+    pub fn new(name: String, url: String, secret: Option<String>) -> Self {
+        Self {
+            name,
+            url,
+            secret,
+            client: http_client(),
+        }
+    }
+}
+
+/// Compute the `sha256=<hex>` signature for a webhook body
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[async_trait]
+impl NotificationChannel for WebhookChannel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let timestamp = chrono::Utc::now().timestamp();
+        let body = serde_json::to_vec(&serde_json::json!({
+            "event": notification.event,
+            "title": notification.title,
+            "body": notification.body,
+            "severity": notification.severity,
+            "timestamp": timestamp,
+        }))?;
+
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .header("X-Hone-Timestamp", timestamp.to_string());
+        if let Some(secret) = &self.secret {
+            request = request.header("X-Hone-Signature", sign_payload(secret, timestamp, &body));
+        }
+
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| Error::Notification(format!("{}: {}", self.name, e)))?;
+        check_response(&self.name, response).await
+    }
+}
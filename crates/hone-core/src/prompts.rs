@@ -22,6 +22,7 @@ mod defaults {
     pub const NORMALIZE_MERCHANT_WITH_CONTEXT: &str =
         include_str!("../../../prompts/normalize_merchant_with_context.md");
     pub const PARSE_RECEIPT: &str = include_str!("../../../prompts/parse_receipt.md");
+    pub const PARSE_RECEIPT_TEXT: &str = include_str!("../../../prompts/parse_receipt_text.md");
     pub const SUGGEST_ENTITY: &str = include_str!("../../../prompts/suggest_entity.md");
     pub const CLASSIFY_SUBSCRIPTION: &str =
         include_str!("../../../prompts/classify_subscription.md");
@@ -35,6 +36,7 @@ mod defaults {
     pub const DUPLICATE_ANALYSIS_AGENT: &str =
         include_str!("../../../prompts/duplicate_analysis_agent.md");
     pub const EXPLORE_AGENT: &str = include_str!("../../../prompts/explore_agent.md");
+    pub const EXPLORE_SUMMARY: &str = include_str!("../../../prompts/explore_summary.md");
 }
 
 /// Known prompt IDs
@@ -44,6 +46,8 @@ pub enum PromptId {
     NormalizeMerchant,
     NormalizeMerchantWithContext,
     ParseReceipt,
+    /// Text receipts (HTML emails, PDFs with a text layer)
+    ParseReceiptText,
     SuggestEntity,
     ClassifySubscription,
     SuggestSplit,
@@ -56,6 +60,8 @@ pub enum PromptId {
     DuplicateAnalysisAgent,
     /// Agentic prompt for explore mode conversational queries
     ExploreAgent,
+    /// Summarizes older explore turns so long conversations stay within context
+    ExploreSummary,
 }
 
 impl PromptId {
@@ -66,6 +72,7 @@ impl PromptId {
             Self::NormalizeMerchant => "normalize_merchant",
             Self::NormalizeMerchantWithContext => "normalize_merchant_with_context",
             Self::ParseReceipt => "parse_receipt",
+            Self::ParseReceiptText => "parse_receipt_text",
             Self::SuggestEntity => "suggest_entity",
             Self::ClassifySubscription => "classify_subscription",
             Self::SuggestSplit => "suggest_split",
@@ -75,6 +82,7 @@ impl PromptId {
             Self::SpendingAnalysisAgent => "spending_analysis_agent",
             Self::DuplicateAnalysisAgent => "duplicate_analysis_agent",
             Self::ExploreAgent => "explore_agent",
+            Self::ExploreSummary => "explore_summary",
         }
     }
 
@@ -85,6 +93,7 @@ impl PromptId {
             Self::NormalizeMerchant,
             Self::NormalizeMerchantWithContext,
             Self::ParseReceipt,
+            Self::ParseReceiptText,
             Self::SuggestEntity,
             Self::ClassifySubscription,
             Self::SuggestSplit,
@@ -94,6 +103,7 @@ impl PromptId {
             Self::SpendingAnalysisAgent,
             Self::DuplicateAnalysisAgent,
             Self::ExploreAgent,
+            Self::ExploreSummary,
         ]
     }
 
@@ -104,6 +114,7 @@ impl PromptId {
             Self::NormalizeMerchant => defaults::NORMALIZE_MERCHANT,
             Self::NormalizeMerchantWithContext => defaults::NORMALIZE_MERCHANT_WITH_CONTEXT,
             Self::ParseReceipt => defaults::PARSE_RECEIPT,
+            Self::ParseReceiptText => defaults::PARSE_RECEIPT_TEXT,
             Self::SuggestEntity => defaults::SUGGEST_ENTITY,
             Self::ClassifySubscription => defaults::CLASSIFY_SUBSCRIPTION,
             Self::SuggestSplit => defaults::SUGGEST_SPLIT,
@@ -113,6 +124,7 @@ impl PromptId {
             Self::SpendingAnalysisAgent => defaults::SPENDING_ANALYSIS_AGENT,
             Self::DuplicateAnalysisAgent => defaults::DUPLICATE_ANALYSIS_AGENT,
             Self::ExploreAgent => defaults::EXPLORE_AGENT,
+            Self::ExploreSummary => defaults::EXPLORE_SUMMARY,
         }
     }
 }
@@ -512,7 +524,7 @@ Hello {{name}}, your value is {{value}}."#;
     #[test]
     fn test_prompt_id_all() {
         let all = PromptId::all();
-        assert_eq!(all.len(), 13);
+        assert_eq!(all.len(), 15);
     }
 
     #[test]
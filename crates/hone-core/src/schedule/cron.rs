@@ -0,0 +1,291 @@
+//! Cron expression parsing and evaluation
+//!
+//! Standard five-field expressions (`minute hour day-of-month month
+//! day-of-week`) with `*`, lists, ranges, steps, and month/day names, plus
+//! the `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly` shortcuts.
+//! Like cron, when both day-of-month and day-of-week are restricted a day
+//! matches if either does. Times are evaluated in local time.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{
+    DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc,
+};
+
+use crate::error::{Error, Result};
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const DAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// How far ahead to search before deciding an expression never fires (e.g. Feb 30)
+const SEARCH_YEARS: i32 = 5;
+
+/// A parsed cron expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    source: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Day-of-month field was `*`
+    any_day_of_month: bool,
+    /// Day-of-week field was `*`
+    any_day_of_week: bool,
+}
+
+impl CronExpr {
+    /// Parse a cron expression
+    pub fn parse(expr: &str) -> Result<Self> {
+        let source = expr.trim().to_string();
+        let expanded = match source.to_lowercase().as_str() {
+            "@hourly" => "0 * * * *".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            other if other.starts_with('@') => {
+                return Err(invalid(&source, "unknown shortcut"));
+            }
+            _ => source.clone(),
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(invalid(&source, "expected 5 fields"));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7, &DAY_NAMES)
+            .map_err(|e| invalid(&source, &format!("day of week: {}", e)))?;
+        // 7 is Sunday too
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59, &[])
+                .map_err(|e| invalid(&source, &format!("minute: {}", e)))?,
+            hours: parse_field(fields[1], 0, 23, &[])
+                .map_err(|e| invalid(&source, &format!("hour: {}", e)))?,
+            days_of_month: parse_field(fields[2], 1, 31, &[])
+                .map_err(|e| invalid(&source, &format!("day of month: {}", e)))?,
+            months: parse_field(fields[3], 1, 12, &MONTH_NAMES)
+                .map_err(|e| invalid(&source, &format!("month: {}", e)))?,
+            days_of_week,
+            any_day_of_month: fields[2] == "*",
+            any_day_of_week: fields[4] == "*",
+            source,
+        })
+    }
+
+    /// Build the expression for a fixed interval in hours
+    ///
+    /// Used for `HONE_BACKUP_SCHEDULE`: intervals are aligned to midnight, so
+    /// `24` runs daily at 00:00 and `168` weekly on Sunday. Intervals cron
+    /// can't express (`5`, `36`, `100`, over 30 days) are rejected rather
+    /// than rounded; see `nearest_interval_hours`.
+    ///
+    /// Multi-day intervals other than 168 count from the 1st of each month
+    /// (`0 0 */3 * *` runs on the 1st, 4th, ... 28th, 31st), so the gap across
+    /// a month end can be shorter than the interval.
+    pub fn from_interval_hours(hours: u64) -> Result<Self> {
+        let expr = match hours {
+            0 => {
+                return Err(Error::Schedule(
+                    "Interval must be at least 1 hour".to_string(),
+                ))
+            }
+            1 => "0 * * * *".to_string(),
+            h if h < 24 && 24 % h == 0 => format!("0 */{} * * *", h),
+            24 => "0 0 * * *".to_string(),
+            168 => "0 0 * * 0".to_string(),
+            h if h % 24 == 0 && h / 24 < 31 => format!("0 0 */{} * *", h / 24),
+            h => {
+                return Err(Error::Schedule(format!(
+                    "A {}-hour interval can't be expressed as a cron schedule; \
+                     use an interval that divides 24, a whole number of days up to 30, \
+                     or 168, or add a backup task with a cron expression to schedules.toml",
+                    h
+                )))
+            }
+        };
+        Self::parse(&expr)
+    }
+
+    /// The closest interval `from_interval_hours` accepts, preferring the
+    /// shorter one on a tie
+    pub fn nearest_interval_hours(hours: u64) -> u64 {
+        [1u64, 2, 3, 4, 6, 8, 12]
+            .into_iter()
+            .chain((1..=30).map(|days| days * 24))
+            .min_by_key(|&h| (h.abs_diff(hours), h))
+            .unwrap_or(24)
+    }
+
+    /// Build the expression for a fixed interval in minutes
+    ///
+    /// Like `from_interval_hours`, intervals must repeat evenly: divisors of
+    /// 60, or whole hours.
+    pub fn from_interval_minutes(minutes: u64) -> Result<Self> {
+        match minutes {
+            0 => Err(Error::Schedule(
+                "Interval must be at least 1 minute".to_string(),
+            )),
+            1 => Self::parse("* * * * *"),
+            m if m < 60 && 60 % m == 0 => Self::parse(&format!("*/{} * * * *", m)),
+            m if m % 60 == 0 => Self::from_interval_hours(m / 60),
+            m => Err(Error::Schedule(format!(
+                "A {}-minute interval can't be expressed as a cron schedule; \
+                 use an interval that divides 60 or a whole number of hours",
+                m
+            ))),
+        }
+    }
+
+    /// The expression as written
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether a local time (to the minute) matches
+    pub fn matches(&self, time: &NaiveDateTime) -> bool {
+        has(self.minutes, time.minute())
+            && has(self.hours, time.hour())
+            && has(self.months, time.month())
+            && self.matches_day(time.date())
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let dom = has(self.days_of_month, date.day());
+        let dow = has(self.days_of_week, date.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => dow,
+            (false, true) => dom,
+            (false, false) => dom || dow,
+        }
+    }
+
+    /// Next matching time strictly after `after`
+    ///
+    /// Returns None if the expression can never match (e.g. `0 0 31 2 *`).
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.with_timezone(&Local).naive_local();
+        let mut t = start.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(366 * i64::from(SEARCH_YEARS));
+
+        while t <= limit {
+            if !has(self.months, t.month()) {
+                // First minute of the next month
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.matches_day(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !has(self.hours, t.hour()) {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !has(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+                continue;
+            }
+            // Skip local times that don't exist (DST gaps)
+            if let Some(local) = Local.from_local_datetime(&t).earliest() {
+                return Some(local.with_timezone(&Utc));
+            }
+            t += Duration::minutes(1);
+        }
+        None
+    }
+}
+
+impl FromStr for CronExpr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for CronExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+fn invalid(expr: &str, reason: &str) -> Error {
+    Error::Schedule(format!("Invalid cron expression '{}': {}", expr, reason))
+}
+
+fn has(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+/// Parse one field into a bitset of allowed values
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+) -> std::result::Result<u64, String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("invalid step '{}'", step))?;
+                if step == 0 {
+                    return Err("step must be at least 1".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse_value(a, min, names)?, parse_value(b, min, names)?)
+        } else {
+            let value = parse_value(range, min, names)?;
+            // "5/15" means starting at 5, every 15
+            (value, if step > 1 { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            return Err(format!("'{}' is outside {}-{}", part, min, max));
+        }
+        let mut value = start;
+        while value <= end {
+            bits |= 1 << value;
+            value += step;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_value(value: &str, min: u32, names: &[&str]) -> std::result::Result<u32, String> {
+    if let Ok(n) = value.parse() {
+        return Ok(n);
+    }
+    let lower = value.to_lowercase();
+    names
+        .iter()
+        .position(|name| *name == lower)
+        // Month names start at 1, day names at 0
+        .map(|i| i as u32 + min)
+        .ok_or_else(|| format!("invalid value '{}'", value))
+}
@@ -0,0 +1,466 @@
+//! Cron-scheduled recurring tasks
+//!
+//! Each configured task queues a background job when its cron expression
+//! comes due:
+//!
+//! - `backup` - create a backup and prune old ones (`retention`)
+//! - `detection` - run waste detection (`kind`: all, zombies, increases, duplicates)
+//! - `insight_refresh` - regenerate insight findings
+//! - `report` - write a spending report (`period`: last-month, this-month, last-30-days)
+//! - `prune_metrics` - delete old AI call metrics (`keep_days`)
+//! - `training` - fine-tune models with enough new data (`tasks`, `trainer`)
+//! - `audit_archive` - archive old audit log entries (`retention_days`)
+//! - `explore_digests` - run saved explore questions that are due
+//!
+//! The server adds `audit_archive` and `explore_digests` tasks itself from
+//! `HONE_AUDIT_RETENTION_DAYS` and `HONE_DIGEST_CHECK_MINUTES` unless
+//! `schedules.toml` already has one.
+//!
+//! Configuration lives in `~/.local/share/hone/config/schedules.toml`
+//! (override with `HONE_SCHEDULE_CONFIG`):
+//!
+//! ```toml
+//! [[tasks]]
+//! name = "nightly-detection"
+//! task = "detection"
+//! cron = "0 3 * * *"
+//!
+//! [[tasks]]
+//! name = "monthly-report"
+//! task = "report"
+//! cron = "0 6 1 * *"
+//! params = { period = "last-month" }
+//! ```
+//!
+//! Next due times are stored in the database, so a run missed while the
+//! server was down happens once at startup. A task whose previous job is
+//! still queued or running is skipped until its next due time.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::models::{JobStatus, JobType, NewJob};
+use crate::training::TrainingTask;
+
+mod cron;
+mod report;
+
+pub use cron::CronExpr;
+pub use report::{default_reports_dir, write_spending_report, SpendingReportFile};
+
+/// Report periods accepted by `report` tasks
+pub const REPORT_PERIODS: &[&str] = &["last-month", "this-month", "last-30-days"];
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// One scheduled task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskConfig {
+    /// Unique name (used in the API and to track runs)
+    pub name: String,
+    /// Job to queue
+    pub task: JobType,
+    /// Five-field cron expression or shortcut (`@daily`), in local time
+    pub cron: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Job parameters
+    #[serde(default)]
+    pub params: serde_json::Value,
+}
+
+impl TaskConfig {
+    /// Parameters for the queued job (never null)
+    pub fn job_params(&self) -> serde_json::Value {
+        match &self.params {
+            serde_json::Value::Null => serde_json::json!({}),
+            params => params.clone(),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let fail = |reason: String| Err(Error::Schedule(format!("{}: {}", self.name, reason)));
+        CronExpr::parse(&self.cron)?;
+
+        let params = self.job_params();
+        if !params.is_object() {
+            return fail("params must be a table".to_string());
+        }
+        let str_param = |key: &str| params.get(key).and_then(|v| v.as_str());
+        match self.task {
+            JobType::ImportProcessing | JobType::Reprocess => {
+                return fail(format!("{} jobs can't be scheduled", self.task));
+            }
+            JobType::Detection => {
+                if let Some(kind) = str_param("kind") {
+                    if !["all", "zombies", "increases", "duplicates"].contains(&kind) {
+                        return fail(format!("unknown detection kind '{}'", kind));
+                    }
+                }
+            }
+            JobType::Report => {
+                if let Some(period) = str_param("period") {
+                    if !REPORT_PERIODS.contains(&period) {
+                        return fail(format!(
+                            "unknown report period '{}' (valid: {})",
+                            period,
+                            REPORT_PERIODS.join(", ")
+                        ));
+                    }
+                }
+            }
+            JobType::PruneMetrics => {
+                if let Some(days) = params.get("keep_days") {
+                    if days.as_i64().is_none_or(|d| d < 1) {
+                        return fail("keep_days must be at least 1".to_string());
+                    }
+                }
+            }
+            JobType::Backup => {
+                if let Some(retention) = params.get("retention") {
+                    if retention.as_u64().is_none_or(|r| r < 1) {
+                        return fail("retention must be at least 1".to_string());
+                    }
+                }
+            }
+            JobType::Training => {
+                if let Some(tasks) = params.get("tasks") {
+                    let names = tasks
+                        .as_array()
+                        .ok_or_else(|| {
+                            Error::Schedule(format!("{}: tasks must be a list", self.name))
+                        })?
+                        .iter()
+                        .map(|t| t.as_str().unwrap_or_default());
+                    for name in names {
+                        if TrainingTask::from_str(name).is_none() {
+                            return fail(format!("unknown training task '{}'", name));
+                        }
+                    }
+                }
+            }
+            JobType::AuditArchive => {
+                if params
+                    .get("retention_days")
+                    .and_then(|d| d.as_i64())
+                    .is_none_or(|d| d < 1)
+                {
+                    return fail("retention_days must be at least 1".to_string());
+                }
+            }
+            JobType::InsightRefresh | JobType::ExploreDigests => {}
+        }
+        Ok(())
+    }
+}
+
+/// Scheduled task configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    #[serde(default)]
+    pub tasks: Vec<TaskConfig>,
+}
+
+impl ScheduleConfig {
+    /// Parse and validate a TOML config
+    pub fn parse(content: &str) -> Result<Self> {
+        let config: ScheduleConfig = toml::from_str(content)
+            .map_err(|e| Error::Schedule(format!("Invalid schedule config: {}", e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load from a path, or `HONE_SCHEDULE_CONFIG`, or the default location
+    ///
+    /// Returns None when no config file exists.
+    pub fn load(path: Option<&Path>) -> Result<Option<Self>> {
+        let path = match path {
+            Some(p) => Some(p.to_path_buf()),
+            None => std::env::var("HONE_SCHEDULE_CONFIG")
+                .ok()
+                .map(PathBuf::from)
+                .or_else(default_config_path),
+        };
+        let Some(path) = path.filter(|p| p.exists()) else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(&path)
+            .map_err(|e| Error::Schedule(format!("Failed to read {}: {}", path.display(), e)))?;
+        Self::parse(&content).map(Some)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for task in &self.tasks {
+            if task.name.trim().is_empty() {
+                return Err(Error::Schedule("Task name can't be empty".to_string()));
+            }
+            if !names.insert(task.name.as_str()) {
+                return Err(Error::Schedule(format!(
+                    "Duplicate task name: {}",
+                    task.name
+                )));
+            }
+            task.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Find a task by name
+    pub fn task(&self, name: &str) -> Option<&TaskConfig> {
+        self.tasks.iter().find(|t| t.name == name)
+    }
+
+    /// Whether a task of this type is configured
+    fn has_task(&self, task: JobType) -> bool {
+        self.tasks.iter().any(|t| t.task == task)
+    }
+
+    /// Add a built-in task unless `schedules.toml` already has one of its type
+    fn add_builtin(
+        &mut self,
+        source: &str,
+        name: &str,
+        task: JobType,
+        cron: CronExpr,
+        params: serde_json::Value,
+    ) {
+        if self.has_task(task) {
+            warn!(
+                "{} task not added: schedules.toml already has a {} task",
+                source, task
+            );
+            return;
+        }
+        self.tasks.push(TaskConfig {
+            name: name.to_string(),
+            task,
+            cron: cron.to_string(),
+            enabled: true,
+            params,
+        });
+    }
+
+    /// Add a backup task for `HONE_BACKUP_SCHEDULE` unless one is configured
+    ///
+    /// Intervals cron can't express fall back to the nearest one it can, with
+    /// a warning, so an interval the old ticker accepted still starts the
+    /// server.
+    pub fn add_backup_interval(&mut self, interval_hours: u64, retention: usize) -> Result<()> {
+        if self.has_task(JobType::Backup) {
+            warn!("HONE_BACKUP_SCHEDULE ignored: schedules.toml already has a backup task");
+            return Ok(());
+        }
+        let cron = match CronExpr::from_interval_hours(interval_hours) {
+            Ok(cron) => cron,
+            Err(e) => {
+                let nearest = CronExpr::nearest_interval_hours(interval_hours);
+                warn!(
+                    "HONE_BACKUP_SCHEDULE: {}; backing up every {} hours instead",
+                    e, nearest
+                );
+                CronExpr::from_interval_hours(nearest)?
+            }
+        };
+        self.add_builtin(
+            "HONE_BACKUP_SCHEDULE",
+            "backup",
+            JobType::Backup,
+            cron,
+            serde_json::json!({ "retention": retention }),
+        );
+        Ok(())
+    }
+
+    /// Add a daily audit archive task for `HONE_AUDIT_RETENTION_DAYS`
+    pub fn add_audit_retention(&mut self, retention_days: i64) -> Result<()> {
+        self.add_builtin(
+            "HONE_AUDIT_RETENTION_DAYS",
+            "audit-retention",
+            JobType::AuditArchive,
+            CronExpr::parse("@daily")?,
+            serde_json::json!({ "retention_days": retention_days }),
+        );
+        Ok(())
+    }
+
+    /// Add a task checking for due saved questions (`HONE_DIGEST_CHECK_MINUTES`)
+    pub fn add_explore_digests(&mut self, check_interval_minutes: u64) -> Result<()> {
+        let cron = CronExpr::from_interval_minutes(check_interval_minutes)?;
+        self.add_builtin(
+            "HONE_DIGEST_CHECK_MINUTES",
+            "explore-digests",
+            JobType::ExploreDigests,
+            cron,
+            serde_json::json!({}),
+        );
+        Ok(())
+    }
+}
+
+/// Default config path
+pub fn default_config_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("hone").join("config").join("schedules.toml"))
+}
+
+/// A task with its next and last run
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleStatus {
+    pub name: String,
+    pub task: JobType,
+    pub cron: String,
+    pub enabled: bool,
+    pub params: serde_json::Value,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_job_id: Option<i64>,
+    /// Status of the last queued job
+    pub last_status: Option<JobStatus>,
+    /// Why the last run failed to queue, or why its job failed
+    pub last_error: Option<String>,
+}
+
+/// Queues jobs for scheduled tasks when they come due
+pub struct Scheduler {
+    db: Database,
+    tasks: Vec<(TaskConfig, CronExpr)>,
+}
+
+impl Scheduler {
+    pub fn new(db: Database, config: &ScheduleConfig) -> Result<Self> {
+        let tasks = config
+            .tasks
+            .iter()
+            .map(|t| Ok((t.clone(), CronExpr::parse(&t.cron)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { db, tasks })
+    }
+
+    /// Queue jobs for every task that is due; returns the queued job IDs
+    ///
+    /// Tasks seen for the first time (or whose cron changed) are only
+    /// scheduled, not run. A due task whose previous job is still queued or
+    /// running is skipped, so a stalled worker doesn't build up a backlog.
+    pub fn run_once(&self, now: DateTime<Utc>) -> Result<Vec<i64>> {
+        let mut queued = Vec::new();
+        for (task, cron) in self.tasks.iter().filter(|(t, _)| t.enabled) {
+            let state = self.db.get_schedule_state(&task.name)?;
+            let due = match &state {
+                Some(state) if state.cron == task.cron => {
+                    state.next_run_at.is_some_and(|next| next <= now)
+                }
+                _ => false,
+            };
+            if !due {
+                if state.as_ref().is_none_or(|s| s.cron != task.cron) {
+                    self.db
+                        .set_schedule_next_run(&task.name, &task.cron, cron.next_after(now))?;
+                }
+                continue;
+            }
+
+            if let Some(active) = self.active_job(task)? {
+                info!(
+                    "Scheduled task '{}' skipped: job {} is still queued or running",
+                    task.name, active
+                );
+            } else if let Some(id) = self.queue(task, "scheduler", now)? {
+                queued.push(id);
+            }
+            self.db
+                .set_schedule_next_run(&task.name, &task.cron, cron.next_after(now))?;
+        }
+        Ok(queued)
+    }
+
+    /// Queue a task immediately (its regular schedule is unchanged)
+    ///
+    /// Returns the task's job that is still queued or running instead of
+    /// queueing another.
+    pub fn run_now(&self, name: &str, actor: &str) -> Result<i64> {
+        let (task, cron) = self
+            .tasks
+            .iter()
+            .find(|(t, _)| t.name == name)
+            .ok_or_else(|| Error::NotFound(format!("Scheduled task: {}", name)))?;
+        let now = Utc::now();
+        if self.db.get_schedule_state(name)?.is_none() {
+            self.db
+                .set_schedule_next_run(name, &task.cron, cron.next_after(now))?;
+        }
+        if let Some(active) = self.active_job(task)? {
+            return Ok(active);
+        }
+        self.queue(task, actor, now)?
+            .ok_or_else(|| Error::Schedule(format!("Failed to queue scheduled task: {}", name)))
+    }
+
+    /// A queued or running job of the task's type with the same parameters
+    fn active_job(&self, task: &TaskConfig) -> Result<Option<i64>> {
+        self.db.find_active_job(task.task, &task.job_params())
+    }
+
+    /// Queue the task's job and record the run
+    fn queue(&self, task: &TaskConfig, actor: &str, now: DateTime<Utc>) -> Result<Option<i64>> {
+        let job = NewJob::new(task.task, task.job_params()).created_by(actor);
+        match self.db.create_job(&job) {
+            Ok(id) => {
+                info!("Scheduled task '{}' queued job {}", task.name, id);
+                self.db
+                    .record_schedule_run(&task.name, now, Some(id), None)?;
+                Ok(Some(id))
+            }
+            Err(e) => {
+                warn!("Scheduled task '{}' failed to queue: {}", task.name, e);
+                self.db
+                    .record_schedule_run(&task.name, now, None, Some(&e.to_string()))?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Every configured task with its next and last run
+    pub fn statuses(&self) -> Result<Vec<ScheduleStatus>> {
+        self.tasks
+            .iter()
+            .map(|(task, cron)| {
+                let state = self.db.get_schedule_state(&task.name)?;
+                let next_run_at = match &state {
+                    Some(s) if s.cron == task.cron => s.next_run_at,
+                    _ => cron.next_after(Utc::now()),
+                };
+                let last_job = match state.as_ref().and_then(|s| s.last_job_id) {
+                    Some(id) => self.db.get_job(id)?,
+                    None => None,
+                };
+                Ok(ScheduleStatus {
+                    name: task.name.clone(),
+                    task: task.task,
+                    cron: task.cron.clone(),
+                    enabled: task.enabled,
+                    params: task.job_params(),
+                    next_run_at: next_run_at.filter(|_| task.enabled),
+                    last_run_at: state.as_ref().and_then(|s| s.last_run_at),
+                    last_job_id: state.as_ref().and_then(|s| s.last_job_id),
+                    last_status: last_job.as_ref().map(|j| j.status),
+                    last_error: state
+                        .as_ref()
+                        .and_then(|s| s.last_error.clone())
+                        .or_else(|| last_job.and_then(|j| j.error)),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests;
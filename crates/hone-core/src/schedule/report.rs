@@ -0,0 +1,60 @@
+//! Spending report files written by scheduled `report` tasks
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::db::Database;
+use crate::error::Result;
+use crate::models::{MerchantsReport, SavingsReport, SpendingSummary, SubscriptionSummaryReport};
+use crate::tools::resolve_period;
+
+/// Contents of a report file
+#[derive(Debug, Serialize)]
+pub struct SpendingReportFile {
+    pub period: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub generated_at: DateTime<Utc>,
+    pub spending: SpendingSummary,
+    pub top_merchants: MerchantsReport,
+    pub subscriptions: SubscriptionSummaryReport,
+    pub savings: SavingsReport,
+}
+
+/// Reports directory: `HONE_REPORTS_DIR` or `~/.local/share/hone/reports`
+pub fn default_reports_dir() -> PathBuf {
+    std::env::var("HONE_REPORTS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("hone")
+                .join("reports")
+        })
+}
+
+/// Write a JSON spending report for a period; returns the file path
+///
+/// Files are named by date range (`spending-2026-01-01_2026-01-31.json`),
+/// so re-running a period replaces its report.
+pub fn write_spending_report(db: &Database, period: &str, dir: &Path) -> Result<PathBuf> {
+    let (from, to) = resolve_period(period)?;
+    let report = SpendingReportFile {
+        period: period.to_string(),
+        from,
+        to,
+        generated_at: Utc::now(),
+        spending: db.get_spending_summary(from, to, None, false, None, None, false)?,
+        top_merchants: db.get_top_merchants(from, to, 10, None, None, None)?,
+        subscriptions: db.get_subscription_summary()?,
+        savings: db.get_savings_report()?,
+    };
+
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("spending-{}_{}.json", from, to));
+    fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+    Ok(path)
+}
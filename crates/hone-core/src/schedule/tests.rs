@@ -0,0 +1,420 @@
+//! Schedule tests
+
+use chrono::{Duration, Local, NaiveDate, TimeZone, Timelike};
+
+use super::*;
+use crate::models::JobStatus;
+
+fn local(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+    Local
+        .from_local_datetime(
+            &NaiveDate::from_ymd_opt(y, m, d)
+                .unwrap()
+                .and_hms_opt(h, min, 0)
+                .unwrap(),
+        )
+        .earliest()
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+#[test]
+fn test_cron_parse_fields() {
+    assert!(CronExpr::parse("0 3 * * *").is_ok());
+    assert!(CronExpr::parse("*/15 9-17 * * mon-fri").is_ok());
+    assert!(CronExpr::parse("0 0 1,15 jan,jul *").is_ok());
+    assert!(CronExpr::parse("@weekly").is_ok());
+
+    assert!(CronExpr::parse("0 3 * *").is_err());
+    assert!(CronExpr::parse("60 3 * * *").is_err());
+    assert!(CronExpr::parse("0 3 * * */0").is_err());
+    assert!(CronExpr::parse("0 3 * * funday").is_err());
+    assert!(CronExpr::parse("@sometimes").is_err());
+}
+
+#[test]
+fn test_cron_matches() {
+    let expr = CronExpr::parse("30 2 * * 1-5").unwrap();
+    // 2026-03-02 is a Monday
+    let monday = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+    assert!(expr.matches(&monday.and_hms_opt(2, 30, 0).unwrap()));
+    assert!(!expr.matches(&monday.and_hms_opt(2, 31, 0).unwrap()));
+    let sunday = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+    assert!(!expr.matches(&sunday.and_hms_opt(2, 30, 0).unwrap()));
+
+    // 7 is Sunday as well
+    let expr = CronExpr::parse("0 0 * * 7").unwrap();
+    assert!(expr.matches(&sunday.and_hms_opt(0, 0, 0).unwrap()));
+
+    // Day-of-month OR day-of-week when both are restricted
+    let expr = CronExpr::parse("0 0 15 * mon").unwrap();
+    assert!(expr.matches(&monday.and_hms_opt(0, 0, 0).unwrap()));
+    let fifteenth = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+    assert!(expr.matches(&fifteenth.and_hms_opt(0, 0, 0).unwrap()));
+}
+
+#[test]
+fn test_cron_next_after() {
+    let daily = CronExpr::parse("0 3 * * *").unwrap();
+    assert_eq!(
+        daily.next_after(local(2026, 3, 2, 1, 0)),
+        Some(local(2026, 3, 2, 3, 0))
+    );
+    // Strictly after
+    assert_eq!(
+        daily.next_after(local(2026, 3, 2, 3, 0)),
+        Some(local(2026, 3, 3, 3, 0))
+    );
+
+    let monthly = CronExpr::parse("0 6 1 * *").unwrap();
+    assert_eq!(
+        monthly.next_after(local(2026, 12, 15, 0, 0)),
+        Some(local(2027, 1, 1, 6, 0))
+    );
+
+    // Leap day only
+    let leap = CronExpr::parse("0 0 29 2 *").unwrap();
+    assert_eq!(
+        leap.next_after(local(2026, 3, 1, 0, 0)),
+        Some(local(2028, 2, 29, 0, 0))
+    );
+
+    assert_eq!(
+        CronExpr::parse("0 0 31 2 *")
+            .unwrap()
+            .next_after(Utc::now()),
+        None
+    );
+}
+
+#[test]
+fn test_cron_from_interval_hours() {
+    assert_eq!(
+        CronExpr::from_interval_hours(1).unwrap().as_str(),
+        "0 * * * *"
+    );
+    assert_eq!(
+        CronExpr::from_interval_hours(6).unwrap().as_str(),
+        "0 */6 * * *"
+    );
+    assert_eq!(
+        CronExpr::from_interval_hours(24).unwrap().as_str(),
+        "0 0 * * *"
+    );
+    assert_eq!(
+        CronExpr::from_interval_hours(48).unwrap().as_str(),
+        "0 0 */2 * *"
+    );
+    assert_eq!(
+        CronExpr::from_interval_hours(168).unwrap().as_str(),
+        "0 0 * * 0"
+    );
+    assert!(CronExpr::from_interval_hours(0).is_err());
+
+    // Intervals cron can't repeat evenly are rejected, not rounded
+    assert!(CronExpr::from_interval_hours(5).is_err());
+    assert!(CronExpr::from_interval_hours(36).is_err());
+    assert!(CronExpr::from_interval_hours(100).is_err());
+    assert!(CronExpr::from_interval_hours(744).is_err());
+}
+
+#[test]
+fn test_cron_nearest_interval_hours() {
+    assert_eq!(CronExpr::nearest_interval_hours(0), 1);
+    assert_eq!(CronExpr::nearest_interval_hours(6), 6);
+    assert_eq!(CronExpr::nearest_interval_hours(168), 168);
+    // Ties go to the shorter interval
+    assert_eq!(CronExpr::nearest_interval_hours(5), 4);
+    assert_eq!(CronExpr::nearest_interval_hours(36), 24);
+    assert_eq!(CronExpr::nearest_interval_hours(100), 96);
+    assert_eq!(CronExpr::nearest_interval_hours(10_000), 720);
+}
+
+#[test]
+fn test_cron_from_interval_minutes() {
+    assert_eq!(
+        CronExpr::from_interval_minutes(1).unwrap().as_str(),
+        "* * * * *"
+    );
+    assert_eq!(
+        CronExpr::from_interval_minutes(15).unwrap().as_str(),
+        "*/15 * * * *"
+    );
+    assert_eq!(
+        CronExpr::from_interval_minutes(120).unwrap().as_str(),
+        "0 */2 * * *"
+    );
+    assert!(CronExpr::from_interval_minutes(0).is_err());
+    assert!(CronExpr::from_interval_minutes(7).is_err());
+    assert!(CronExpr::from_interval_minutes(90).is_err());
+}
+
+#[test]
+fn test_config_parse_and_validate() {
+    let config = ScheduleConfig::parse(
+        r#"
+        [[tasks]]
+        name = "nightly-detection"
+        task = "detection"
+        cron = "0 3 * * *"
+
+        [[tasks]]
+        name = "prune"
+        task = "prune_metrics"
+        cron = "@weekly"
+        enabled = false
+        params = { keep_days = 30 }
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.tasks.len(), 2);
+    assert!(config.tasks[0].enabled);
+    assert_eq!(config.tasks[0].job_params(), serde_json::json!({}));
+    assert!(!config.task("prune").unwrap().enabled);
+
+    let invalid = [
+        // Duplicate names
+        "[[tasks]]\nname = \"a\"\ntask = \"backup\"\ncron = \"@daily\"\n[[tasks]]\nname = \"a\"\ntask = \"backup\"\ncron = \"@daily\"",
+        // Bad cron
+        "[[tasks]]\nname = \"a\"\ntask = \"backup\"\ncron = \"every day\"",
+        // Import jobs can't be scheduled
+        "[[tasks]]\nname = \"a\"\ntask = \"import_processing\"\ncron = \"@daily\"",
+        // Bad params
+        "[[tasks]]\nname = \"a\"\ntask = \"report\"\ncron = \"@daily\"\nparams = { period = \"fortnight\" }",
+        "[[tasks]]\nname = \"a\"\ntask = \"training\"\ncron = \"@daily\"\nparams = { tasks = [\"astrology\"] }",
+        "[[tasks]]\nname = \"a\"\ntask = \"audit_archive\"\ncron = \"@daily\"",
+    ];
+    for content in invalid {
+        assert!(ScheduleConfig::parse(content).is_err(), "{}", content);
+    }
+}
+
+#[test]
+fn test_add_backup_interval() {
+    let mut config = ScheduleConfig::default();
+    config.add_backup_interval(24, 5).unwrap();
+    let backup = config.task("backup").unwrap();
+    assert_eq!(backup.task, JobType::Backup);
+    assert_eq!(backup.params["retention"], 5);
+
+    // An explicit backup task wins
+    config.add_backup_interval(6, 3).unwrap();
+    assert_eq!(config.tasks.len(), 1);
+    assert_eq!(config.tasks[0].params["retention"], 5);
+}
+
+#[test]
+fn test_add_backup_interval_falls_back_to_nearest() {
+    for (hours, cron) in [
+        (5, "0 */4 * * *"),
+        (36, "0 0 * * *"),
+        (1000, "0 0 */30 * *"),
+    ] {
+        let mut config = ScheduleConfig::default();
+        config.add_backup_interval(hours, 7).unwrap();
+        assert_eq!(config.task("backup").unwrap().cron, cron, "{}", hours);
+    }
+}
+
+#[test]
+fn test_add_builtin_tasks() {
+    let mut config = ScheduleConfig::parse(
+        r#"
+        [[tasks]]
+        name = "custom-digests"
+        task = "explore_digests"
+        cron = "0 * * * *"
+        "#,
+    )
+    .unwrap();
+    config.add_audit_retention(365).unwrap();
+    config.add_explore_digests(15).unwrap();
+
+    let audit = config.task("audit-retention").unwrap();
+    assert_eq!(audit.task, JobType::AuditArchive);
+    assert_eq!(audit.cron, "@daily");
+    assert_eq!(audit.params["retention_days"], 365);
+
+    // The configured digest task wins
+    assert!(config.task("explore-digests").is_none());
+    assert_eq!(config.tasks.len(), 2);
+    config.validate().unwrap();
+}
+
+#[test]
+fn test_scheduler_runs_due_tasks_once() {
+    let db = Database::in_memory().unwrap();
+    let config = ScheduleConfig::parse(
+        "[[tasks]]\nname = \"nightly\"\ntask = \"detection\"\ncron = \"0 3 * * *\"\nparams = { kind = \"zombies\" }",
+    )
+    .unwrap();
+    let scheduler = Scheduler::new(db.clone(), &config).unwrap();
+
+    // First sight only schedules
+    let start = local(2026, 3, 2, 1, 0);
+    assert!(scheduler.run_once(start).unwrap().is_empty());
+    let state = db.get_schedule_state("nightly").unwrap().unwrap();
+    assert_eq!(state.next_run_at, Some(local(2026, 3, 2, 3, 0)));
+
+    assert!(scheduler
+        .run_once(start + Duration::hours(1))
+        .unwrap()
+        .is_empty());
+
+    // Missed runs catch up once
+    let late = local(2026, 3, 4, 12, 0);
+    let queued = scheduler.run_once(late).unwrap();
+    assert_eq!(queued.len(), 1);
+    assert!(scheduler.run_once(late).unwrap().is_empty());
+
+    let job = db.get_job(queued[0]).unwrap().unwrap();
+    assert_eq!(job.job_type, JobType::Detection);
+    assert_eq!(job.params["kind"], "zombies");
+    assert_eq!(job.created_by.as_deref(), Some("scheduler"));
+
+    let status = &scheduler.statuses().unwrap()[0];
+    assert_eq!(status.last_job_id, Some(queued[0]));
+    assert_eq!(status.last_status, Some(JobStatus::Queued));
+    assert_eq!(status.last_run_at, Some(late));
+    assert_eq!(status.next_run_at.unwrap().with_timezone(&Local).hour(), 3);
+    assert!(status.next_run_at.unwrap() > late);
+
+    // Run now returns the job that is still queued
+    let next = status.next_run_at;
+    assert_eq!(
+        scheduler.run_now("nightly", "owner@example.com").unwrap(),
+        queued[0]
+    );
+
+    // Run now leaves the schedule alone
+    db.complete_job(queued[0], &serde_json::json!({})).unwrap();
+    let id = scheduler.run_now("nightly", "owner@example.com").unwrap();
+    assert_ne!(id, queued[0]);
+    assert_eq!(
+        db.get_schedule_state("nightly")
+            .unwrap()
+            .unwrap()
+            .next_run_at,
+        next
+    );
+    assert!(scheduler.run_now("missing", "owner@example.com").is_err());
+}
+
+#[test]
+fn test_scheduler_skips_tasks_with_an_active_job() {
+    let db = Database::in_memory().unwrap();
+    let config = ScheduleConfig::parse(
+        r#"
+        [[tasks]]
+        name = "hourly"
+        task = "insight_refresh"
+        cron = "0 * * * *"
+
+        [[tasks]]
+        name = "monthly"
+        task = "report"
+        cron = "0 * * * *"
+        params = { period = "last-month" }
+
+        [[tasks]]
+        name = "rolling"
+        task = "report"
+        cron = "0 * * * *"
+        params = { period = "last-30-days" }
+        "#,
+    )
+    .unwrap();
+    let scheduler = Scheduler::new(db.clone(), &config).unwrap();
+    let start = local(2026, 3, 2, 1, 30);
+    scheduler.run_once(start).unwrap();
+
+    // Tasks of the same type with different params both queue
+    let first = scheduler.run_once(start + Duration::hours(1)).unwrap();
+    assert_eq!(first.len(), 3);
+
+    // Nothing has run yet, so the next hour queues nothing new
+    assert!(scheduler
+        .run_once(start + Duration::hours(2))
+        .unwrap()
+        .is_empty());
+    assert_eq!(db.list_jobs(None, None, 10).unwrap().len(), 3);
+
+    // Still skipped while running; queued again once finished
+    let running = db
+        .claim_next_job(&[JobType::InsightRefresh], Utc::now())
+        .unwrap()
+        .unwrap();
+    assert!(scheduler
+        .run_once(start + Duration::hours(3))
+        .unwrap()
+        .is_empty());
+    db.complete_job(running.id, &serde_json::json!({})).unwrap();
+    let next = scheduler.run_once(start + Duration::hours(4)).unwrap();
+    assert_eq!(next.len(), 1);
+    assert_eq!(
+        db.get_job(next[0]).unwrap().unwrap().job_type,
+        JobType::InsightRefresh
+    );
+}
+
+#[test]
+fn test_scheduler_reschedules_on_cron_change() {
+    let db = Database::in_memory().unwrap();
+    let start = local(2026, 3, 2, 1, 0);
+    let config = ScheduleConfig::parse(
+        "[[tasks]]\nname = \"t\"\ntask = \"insight_refresh\"\ncron = \"0 3 * * *\"",
+    )
+    .unwrap();
+    Scheduler::new(db.clone(), &config)
+        .unwrap()
+        .run_once(start)
+        .unwrap();
+
+    let config = ScheduleConfig::parse(
+        "[[tasks]]\nname = \"t\"\ntask = \"insight_refresh\"\ncron = \"0 2 * * *\"",
+    )
+    .unwrap();
+    let scheduler = Scheduler::new(db.clone(), &config).unwrap();
+    // The old next run is stale, so nothing runs until the new time
+    assert!(scheduler
+        .run_once(local(2026, 3, 2, 4, 0))
+        .unwrap()
+        .is_empty());
+    let state = db.get_schedule_state("t").unwrap().unwrap();
+    assert_eq!(state.cron, "0 2 * * *");
+    assert_eq!(state.next_run_at, Some(local(2026, 3, 3, 2, 0)));
+}
+
+#[test]
+fn test_disabled_tasks_never_run() {
+    let db = Database::in_memory().unwrap();
+    let config = ScheduleConfig::parse(
+        "[[tasks]]\nname = \"t\"\ntask = \"backup\"\ncron = \"* * * * *\"\nenabled = false",
+    )
+    .unwrap();
+    let scheduler = Scheduler::new(db.clone(), &config).unwrap();
+    let now = Utc::now();
+    assert!(scheduler.run_once(now).unwrap().is_empty());
+    assert!(scheduler
+        .run_once(now + Duration::hours(1))
+        .unwrap()
+        .is_empty());
+    assert!(db.list_jobs(None, None, 10).unwrap().is_empty());
+    assert_eq!(scheduler.statuses().unwrap()[0].next_run_at, None);
+}
+
+#[test]
+fn test_write_spending_report() {
+    let db = Database::in_memory().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_spending_report(&db, "last-month", dir.path()).unwrap();
+    assert!(path.starts_with(dir.path()));
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(report["period"], "last-month");
+    assert!(report["spending"].is_object());
+    assert!(report["savings"].is_object());
+
+    assert!(write_spending_report(&db, "someday", dir.path()).is_err());
+}
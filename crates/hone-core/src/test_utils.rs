@@ -1,10 +1,13 @@
 //! Test utilities for hone-core
 //!
 //! This module provides testing infrastructure including a mock Ollama server
-//! that can be used for development and integration tests.
+//! that can be used for development and integration tests, plus stand-in
+//! webhook and SMTP servers for notification channels.
 
 use axum::{
     extract::Json,
+    http::header,
+    response::{IntoResponse, Response},
     routing::{get, post},
     Router,
 };
@@ -23,7 +26,8 @@ impl MockOllamaServer {
     pub async fn start() -> Self {
         let app = Router::new()
             .route("/api/tags", get(handle_tags))
-            .route("/api/generate", post(handle_generate));
+            .route("/api/generate", post(handle_generate))
+            .route("/v1/messages", post(handle_messages));
 
         let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
         let addr = listener.local_addr().unwrap();
@@ -121,6 +125,109 @@ async fn handle_generate(Json(request): Json<GenerateRequest>) -> Json<GenerateR
     })
 }
 
+/// Anthropic-compatible messages endpoint (used by the orchestrator)
+///
+/// When tools are offered and the last message is plain user text, the mock asks
+/// for a single `get_alerts` call; once tool results come back it answers in text.
+/// Honors `stream: true` by replying with server-sent events.
+async fn handle_messages(Json(request): Json<serde_json::Value>) -> Response {
+    let model = request["model"].as_str().unwrap_or("mock").to_string();
+    let has_tools = request["tools"].as_array().is_some_and(|t| !t.is_empty());
+    let last_is_text = request["messages"]
+        .as_array()
+        .and_then(|m| m.last())
+        .is_some_and(|m| m["content"].is_string());
+
+    let (content, stop_reason) = if has_tools && last_is_text {
+        (
+            vec![
+                serde_json::json!({"type": "text", "text": "Checking your alerts."}),
+                serde_json::json!({
+                    "type": "tool_use",
+                    "id": "toolu_mock_1",
+                    "name": "get_alerts",
+                    "input": {}
+                }),
+            ],
+            "tool_use",
+        )
+    } else {
+        (
+            vec![serde_json::json!({"type": "text", "text": "You have no active alerts."})],
+            "end_turn",
+        )
+    };
+
+    if !request["stream"].as_bool().unwrap_or(false) {
+        return Json(serde_json::json!({
+            "id": "msg_mock",
+            "type": "message",
+            "role": "assistant",
+            "content": content,
+            "model": model,
+            "stop_reason": stop_reason,
+            "stop_sequence": null,
+        }))
+        .into_response();
+    }
+
+    let mut events = vec![serde_json::json!({
+        "type": "message_start",
+        "message": {"id": "msg_mock", "model": model}
+    })];
+    for (index, block) in content.iter().enumerate() {
+        if block["type"] == "text" {
+            events.push(serde_json::json!({
+                "type": "content_block_start",
+                "index": index,
+                "content_block": {"type": "text", "text": ""}
+            }));
+            // Split text into word-sized deltas to exercise incremental delivery
+            for word in block["text"]
+                .as_str()
+                .unwrap_or_default()
+                .split_inclusive(' ')
+            {
+                events.push(serde_json::json!({
+                    "type": "content_block_delta",
+                    "index": index,
+                    "delta": {"type": "text_delta", "text": word}
+                }));
+            }
+        } else {
+            events.push(serde_json::json!({
+                "type": "content_block_start",
+                "index": index,
+                "content_block": {"type": "tool_use", "id": block["id"], "name": block["name"], "input": {}}
+            }));
+            events.push(serde_json::json!({
+                "type": "content_block_delta",
+                "index": index,
+                "delta": {"type": "input_json_delta", "partial_json": block["input"].to_string()}
+            }));
+        }
+        events.push(serde_json::json!({"type": "content_block_stop", "index": index}));
+    }
+    events.push(serde_json::json!({
+        "type": "message_delta",
+        "delta": {"stop_reason": stop_reason, "stop_sequence": null}
+    }));
+    events.push(serde_json::json!({"type": "message_stop"}));
+
+    let body: String = events
+        .iter()
+        .map(|e| {
+            format!(
+                "event: {}\ndata: {}\n\n",
+                e["type"].as_str().unwrap_or(""),
+                e
+            )
+        })
+        .collect();
+
+    ([(header::CONTENT_TYPE, "text/event-stream")], body).into_response()
+}
+
 /// Handle normalize merchant request
 fn handle_normalize_mock(prompt: &str) -> String {
     let merchant = extract_merchant_from_prompt_normalize(prompt);
@@ -399,6 +506,188 @@ struct MerchantClassificationResponse {
     category: String,
 }
 
+/// A request captured by [`MockNotificationServer`]
+#[derive(Debug, Clone)]
+pub struct CapturedRequest {
+    pub path: String,
+    pub headers: std::collections::HashMap<String, String>,
+    pub body: String,
+}
+
+#[derive(Default)]
+struct NotificationServerState {
+    requests: Vec<CapturedRequest>,
+    failures_remaining: usize,
+}
+
+/// Stand-in for webhook/ntfy/Gotify/Matrix endpoints
+///
+/// Accepts any POST and records it. Can be told to fail the next N requests
+/// with a 500 to exercise retries.
+pub struct MockNotificationServer {
+    addr: SocketAddr,
+    state: std::sync::Arc<std::sync::Mutex<NotificationServerState>>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+}
+
+impl MockNotificationServer {
+    /// Start the mock server on an available port
+    pub async fn start() -> Self {
+        let state = std::sync::Arc::new(std::sync::Mutex::new(NotificationServerState::default()));
+        let handler_state = state.clone();
+        let app = Router::new().fallback(
+            move |uri: axum::http::Uri, headers: axum::http::HeaderMap, body: String| {
+                let state = handler_state.clone();
+                async move {
+                    let mut state = state.lock().unwrap();
+                    state.requests.push(CapturedRequest {
+                        path: uri.path().to_string(),
+                        headers: headers
+                            .iter()
+                            .map(|(k, v)| {
+                                (k.as_str().to_string(), v.to_str().unwrap_or("").to_string())
+                            })
+                            .collect(),
+                        body,
+                    });
+                    if state.failures_remaining > 0 {
+                        state.failures_remaining -= 1;
+                        (axum::http::StatusCode::INTERNAL_SERVER_ERROR, "unavailable")
+                    } else {
+                        (axum::http::StatusCode::OK, "ok")
+                    }
+                }
+            },
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            axum::serve(listener, app)
+                .with_graceful_shutdown(async {
+                    shutdown_rx.await.ok();
+                })
+                .await
+                .unwrap();
+        });
+
+        Self {
+            addr,
+            state,
+            shutdown_tx: Some(shutdown_tx),
+        }
+    }
+
+    /// Get the base URL for this mock server
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Respond with 500 to the next `count` requests
+    pub fn fail_next(&self, count: usize) {
+        self.state.lock().unwrap().failures_remaining = count;
+    }
+
+    /// Requests received so far
+    pub fn requests(&self) -> Vec<CapturedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+impl Drop for MockNotificationServer {
+    fn drop(&mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+/// Stand-in SMTP server (plain text, no TLS)
+///
+/// Speaks just enough SMTP for the notification channel and records the
+/// envelope commands and DATA section of each message.
+pub struct MockSmtpServer {
+    addr: SocketAddr,
+    envelope: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    messages: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+impl MockSmtpServer {
+    /// Start the mock server on an available port
+    pub async fn start() -> Self {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let envelope = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let messages = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (commands, accepted) = (envelope.clone(), messages.clone());
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let envelope = commands.clone();
+                let messages = accepted.clone();
+                tokio::spawn(async move {
+                    let (reader, mut writer) = stream.into_split();
+                    let mut lines = BufReader::new(reader).lines();
+                    writer.write_all(b"220 mock ESMTP\r\n").await.ok();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        let command = line.to_ascii_uppercase();
+                        let reply: &[u8] = if command.starts_with("EHLO") {
+                            b"250-mock\r\n250 AUTH PLAIN\r\n"
+                        } else if command.starts_with("AUTH") {
+                            b"235 ok\r\n"
+                        } else if command.starts_with("MAIL") || command.starts_with("RCPT") {
+                            envelope.lock().unwrap().push(line);
+                            b"250 ok\r\n"
+                        } else if command.starts_with("DATA") {
+                            writer.write_all(b"354 go ahead\r\n").await.ok();
+                            let mut data = String::new();
+                            while let Ok(Some(line)) = lines.next_line().await {
+                                if line == "." {
+                                    break;
+                                }
+                                data.push_str(&line);
+                                data.push('\n');
+                            }
+                            messages.lock().unwrap().push(data);
+                            b"250 queued\r\n"
+                        } else if command.starts_with("QUIT") {
+                            writer.write_all(b"221 bye\r\n").await.ok();
+                            break;
+                        } else {
+                            b"250 ok\r\n"
+                        };
+                        writer.write_all(reply).await.ok();
+                    }
+                });
+            }
+        });
+
+        Self {
+            addr,
+            envelope,
+            messages,
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    /// MAIL FROM / RCPT TO commands received so far
+    pub fn envelope(&self) -> Vec<String> {
+        self.envelope.lock().unwrap().clone()
+    }
+
+    /// Messages received so far (headers and body)
+    pub fn messages(&self) -> Vec<String> {
+        self.messages.lock().unwrap().clone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -313,6 +313,7 @@ pub fn get_spending_summary(
         false, // expand
         None,  // entity_id
         None,  // card_member
+        false, // net_refunds
     )?;
 
     let categories: Vec<CategorySpending> = summary
@@ -371,7 +372,7 @@ pub fn get_subscriptions(
     db: &Database,
     params: SubscriptionsParams,
 ) -> Result<SubscriptionsResult> {
-    use crate::models::SubscriptionStatus;
+    use crate::models::{Frequency, SubscriptionStatus};
 
     let status_filter = params.status.as_deref().unwrap_or("active");
     let include_excluded = params.include_excluded.unwrap_or(false);
@@ -428,12 +429,9 @@ pub fn get_subscriptions(
         .iter()
         .filter(|s| s.status == "active")
         .map(|s| {
-            match s.frequency.to_lowercase().as_str() {
-                "weekly" => s.amount * 4.33, // Approximate weeks per month
-                "yearly" => s.amount / 12.0,
-                "quarterly" => s.amount / 3.0,
-                _ => s.amount, // Assume monthly
-            }
+            s.frequency
+                .parse::<Frequency>()
+                .map_or(s.amount, |f| f.monthly_amount(s.amount)) // Assume monthly
         })
         .sum();
 
@@ -573,6 +571,7 @@ pub fn compare_spending(
         false,
         None,
         None,
+        false,
     )?;
 
     let baseline_summary = db.get_spending_summary(
@@ -582,6 +581,7 @@ pub fn compare_spending(
         false,
         None,
         None,
+        false,
     )?;
 
     // Build comparison by category
@@ -829,6 +829,170 @@ pub fn get_account_summary(
     })
 }
 
+// =============================================================================
+// get_item_prices
+// =============================================================================
+
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct ItemPricesParams {
+    /// Time period (default: last-12-months)
+    #[schemars(description = "Time period for item price changes")]
+    pub period: Option<String>,
+
+    /// Filter to one merchant
+    #[schemars(description = "Optional: only items bought at this merchant")]
+    pub merchant: Option<String>,
+
+    /// Filter items by name
+    #[schemars(description = "Optional: only items whose name contains this text (e.g. 'milk')")]
+    pub item: Option<String>,
+
+    /// Maximum number of items to return (default: 20)
+    #[schemars(description = "Number of items to return (default 20, max 100)")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ItemPriceSummary {
+    pub item_id: i64,
+    pub merchant: String,
+    pub name: String,
+    pub purchase_count: i64,
+    pub first_price: f64,
+    pub latest_price: f64,
+    pub change_percent: f64,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SplitTypeTotal {
+    pub split_type: String,
+    pub amount: f64,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ItemPricesResult {
+    pub period: String,
+    /// Price change of the tracked items as a basket
+    pub basket_change_percent: Option<f64>,
+    pub items: Vec<ItemPriceSummary>,
+    /// Receipt totals by line type (item, tax, tip, fee, discount, rewards)
+    pub split_types: Vec<SplitTypeTotal>,
+}
+
+pub fn get_item_prices(db: &Database, params: ItemPricesParams) -> Result<ItemPricesResult> {
+    let period_name = params.period.as_deref().unwrap_or("last-12-months");
+    let (from_date, to_date) = resolve_period(period_name)?;
+    let limit = params.limit.unwrap_or(20).min(100);
+
+    let inflation = db.get_item_inflation_report(from_date, to_date, params.merchant.as_deref())?;
+    let item_filter = params
+        .item
+        .as_deref()
+        .map(|i| i.trim().to_lowercase())
+        .filter(|i| !i.is_empty());
+
+    let items: Vec<ItemPriceSummary> = inflation
+        .items
+        .into_iter()
+        .filter(|i| {
+            item_filter
+                .as_ref()
+                .is_none_or(|f| i.name.to_lowercase().contains(f))
+        })
+        .take(limit)
+        .map(|i| ItemPriceSummary {
+            item_id: i.item_id,
+            merchant: i.merchant,
+            name: i.name,
+            purchase_count: i.purchase_count,
+            first_price: i.start_price,
+            latest_price: i.end_price,
+            change_percent: i.change_percent,
+        })
+        .collect();
+
+    let split_types = db
+        .get_split_type_report(from_date, to_date, 0)?
+        .split_types
+        .into_iter()
+        .map(|s| SplitTypeTotal {
+            split_type: s.split_type.as_str().to_string(),
+            amount: s.amount,
+            count: s.split_count,
+        })
+        .collect();
+
+    Ok(ItemPricesResult {
+        period: period_name.to_string(),
+        basket_change_percent: inflation.basket_change_percent,
+        items,
+        split_types,
+    })
+}
+
+// =============================================================================
+// get_warranties
+// =============================================================================
+
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct WarrantiesParams {
+    /// Filter by item, merchant, serial number or notes
+    #[schemars(
+        description = "Optional: only items matching this text (e.g. 'laptop', 'Best Buy')"
+    )]
+    pub query: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct WarrantySummary {
+    pub item: String,
+    pub merchant: Option<String>,
+    pub purchase_date: String,
+    pub price: Option<f64>,
+    /// returnable, covered
+    pub status: String,
+    pub return_by: Option<String>,
+    pub warranty_expires: Option<String>,
+    pub serial_number: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct WarrantiesResult {
+    pub as_of: String,
+    /// Items under warranty or still returnable, soonest deadline first
+    pub items: Vec<WarrantySummary>,
+    pub covered_value: f64,
+    /// Matching items whose windows have closed
+    pub expired_count: i64,
+}
+
+pub fn get_warranties(db: &Database, params: WarrantiesParams) -> Result<WarrantiesResult> {
+    let report = db.get_warranty_report(params.query.as_deref(), Utc::now().date_naive())?;
+
+    let items = report
+        .items
+        .into_iter()
+        .map(|i| WarrantySummary {
+            item: i.warranty.item,
+            merchant: i.warranty.merchant,
+            purchase_date: i.warranty.purchase_date.to_string(),
+            price: i.warranty.price,
+            status: i.status.as_str().to_string(),
+            return_by: i.warranty.return_by.map(|d| d.to_string()),
+            warranty_expires: i.warranty.warranty_expires.map(|d| d.to_string()),
+            serial_number: i.warranty.serial_number,
+        })
+        .collect();
+
+    Ok(WarrantiesResult {
+        as_of: report.as_of.to_string(),
+        items,
+        covered_value: report.covered_value,
+        expired_count: report.expired_count,
+    })
+}
+
 // =============================================================================
 // Tool Definitions for Anthropic Format
 // =============================================================================
@@ -874,6 +1038,18 @@ pub fn hone_tools() -> Vec<Tool> {
             "Get overview of all accounts with transaction counts.",
             schemars::schema_for!(AccountSummaryParams).into(),
         ),
+        Tool::new(
+            "get_item_prices",
+            "Get receipt item price changes (per-item inflation) and totals by line type \
+             (tax, tip, fees, discounts, rewards).",
+            schemars::schema_for!(ItemPricesParams).into(),
+        ),
+        Tool::new(
+            "get_warranties",
+            "Get items still under warranty or within their return window, with deadlines \
+             and serial numbers.",
+            schemars::schema_for!(WarrantiesParams).into(),
+        ),
     ]
 }
 
@@ -1125,7 +1301,7 @@ mod tests {
     #[test]
     fn test_hone_tools_count() {
         let tools = hone_tools();
-        assert_eq!(tools.len(), 7);
+        assert_eq!(tools.len(), 9);
     }
 
     #[test]
@@ -1416,6 +1592,25 @@ mod tests {
         assert_eq!(result.merchants.len(), 0);
     }
 
+    #[test]
+    fn test_get_item_prices_empty_db() {
+        let db = create_test_db();
+        let result = get_item_prices(&db, ItemPricesParams::default()).unwrap();
+        assert_eq!(result.period, "last-12-months");
+        assert!(result.items.is_empty());
+        assert!(result.split_types.is_empty());
+        assert!(result.basket_change_percent.is_none());
+    }
+
+    #[test]
+    fn test_get_warranties_empty_db() {
+        let db = create_test_db();
+        let result = get_warranties(&db, WarrantiesParams::default()).unwrap();
+        assert!(result.items.is_empty());
+        assert_eq!(result.covered_value, 0.0);
+        assert_eq!(result.expired_count, 0);
+    }
+
     #[test]
     fn test_get_merchants_with_data() {
         let db = create_test_db();
@@ -0,0 +1,464 @@
+//! Pluggable fine-tuning backends
+//!
+//! The training pipeline prepares data and records results; the actual LoRA
+//! fine-tuning run is delegated to a `Trainer` implementation.
+//!
+//! # Backends
+//!
+//! - `MlxTrainer`: `mlx_lm.lora` (Apple Silicon only)
+//! - `LlamaCppTrainer`: llama.cpp `llama-finetune` (CPU, works on Linux)
+//! - `FakeTrainer`: writes placeholder artifacts without running anything (tests)
+//!
+//! # Configuration
+//!
+//! Environment variables:
+//! - `HONE_TRAINER`: Backend to use (mlx, llama_cpp). Default: mlx on macOS, llama_cpp elsewhere
+//! - `HONE_LLAMA_FINETUNE`: llama.cpp finetune binary (default: llama-finetune)
+//! - `HONE_LLAMA_THREADS`: CPU threads for llama.cpp (default: available parallelism)
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::training::ChatTrainingExample;
+use crate::training_pipeline::TrainingExperiment;
+
+/// Marker llama.cpp uses to split the plain-text training file into samples
+pub const LLAMA_SAMPLE_START: &str = "<SFT>";
+
+/// Inputs for a single fine-tuning run
+#[derive(Debug, Clone)]
+pub struct TrainingJob<'a> {
+    pub experiment: &'a TrainingExperiment,
+    /// Chat-format JSONL produced by `prepare_training_data`
+    pub training_data: &'a Path,
+    /// Experiment artifacts directory (adapters and logs are written here)
+    pub output_dir: &'a Path,
+}
+
+/// Files produced by a successful fine-tuning run
+#[derive(Debug, Clone, Serialize)]
+pub struct TrainingArtifacts {
+    /// LoRA adapter (file or directory) usable as an Ollama `ADAPTER`
+    pub adapter_path: PathBuf,
+    /// Trainer stdout/stderr captured for later inspection
+    pub log_path: Option<PathBuf>,
+}
+
+/// A fine-tuning backend
+pub trait Trainer: Send + Sync {
+    /// Short backend name recorded on the experiment (e.g. "llama_cpp")
+    fn name(&self) -> &'static str;
+
+    /// Whether the backend can fine-tune on image inputs
+    fn supports_vision(&self) -> bool {
+        false
+    }
+
+    /// Run fine-tuning and return the produced artifacts
+    fn train(&self, job: &TrainingJob<'_>) -> Result<TrainingArtifacts>;
+}
+
+/// Concrete trainer enum selected from configuration
+#[derive(Debug, Clone)]
+pub enum TrainerBackend {
+    Mlx(MlxTrainer),
+    LlamaCpp(LlamaCppTrainer),
+    Fake(FakeTrainer),
+}
+
+impl TrainerBackend {
+    /// Create a trainer from `HONE_TRAINER` (platform default when unset)
+    pub fn from_env() -> Self {
+        match std::env::var("HONE_TRAINER") {
+            Ok(name) => Self::from_name(&name).unwrap_or_else(|| {
+                tracing::warn!(trainer = %name, "Unknown HONE_TRAINER, using platform default");
+                Self::platform_default()
+            }),
+            Err(_) => Self::platform_default(),
+        }
+    }
+
+    /// Create a trainer by name (mlx, llama_cpp, fake)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace('-', "_").as_str() {
+            "mlx" => Some(TrainerBackend::Mlx(MlxTrainer::default())),
+            "llama_cpp" | "llamacpp" | "llama" => {
+                Some(TrainerBackend::LlamaCpp(LlamaCppTrainer::from_env()))
+            }
+            "fake" => Some(TrainerBackend::Fake(FakeTrainer::default())),
+            _ => None,
+        }
+    }
+
+    /// MLX on macOS, llama.cpp on CPU everywhere else
+    pub fn platform_default() -> Self {
+        if cfg!(target_os = "macos") {
+            TrainerBackend::Mlx(MlxTrainer::default())
+        } else {
+            TrainerBackend::LlamaCpp(LlamaCppTrainer::from_env())
+        }
+    }
+}
+
+impl Trainer for TrainerBackend {
+    fn name(&self) -> &'static str {
+        match self {
+            TrainerBackend::Mlx(t) => t.name(),
+            TrainerBackend::LlamaCpp(t) => t.name(),
+            TrainerBackend::Fake(t) => t.name(),
+        }
+    }
+
+    fn supports_vision(&self) -> bool {
+        match self {
+            TrainerBackend::Mlx(t) => t.supports_vision(),
+            TrainerBackend::LlamaCpp(t) => t.supports_vision(),
+            TrainerBackend::Fake(t) => t.supports_vision(),
+        }
+    }
+
+    fn train(&self, job: &TrainingJob<'_>) -> Result<TrainingArtifacts> {
+        match self {
+            TrainerBackend::Mlx(t) => t.train(job),
+            TrainerBackend::LlamaCpp(t) => t.train(job),
+            TrainerBackend::Fake(t) => t.train(job),
+        }
+    }
+}
+
+/// MLX LoRA fine-tuning (requires `pip install mlx-lm` on Apple Silicon)
+#[derive(Debug, Clone)]
+pub struct MlxTrainer {
+    pub iters: u32,
+    pub batch_size: u32,
+    pub learning_rate: String,
+}
+
+impl Default for MlxTrainer {
+    fn default() -> Self {
+        Self {
+            iters: 100,
+            batch_size: 4,
+            learning_rate: "1e-5".to_string(),
+        }
+    }
+}
+
+impl Trainer for MlxTrainer {
+    fn name(&self) -> &'static str {
+        "mlx"
+    }
+
+    fn train(&self, job: &TrainingJob<'_>) -> Result<TrainingArtifacts> {
+        let adapter_path = job.output_dir.join("adapters");
+
+        let output = Command::new("mlx_lm.lora")
+            .args([
+                "--model",
+                &job.experiment.base_model,
+                "--train",
+                "--data",
+                job.training_data.to_string_lossy().as_ref(),
+                "--adapter-path",
+                adapter_path.to_string_lossy().as_ref(),
+                "--iters",
+                &self.iters.to_string(),
+                "--batch-size",
+                &self.batch_size.to_string(),
+                "--learning-rate",
+                &self.learning_rate,
+            ])
+            .output()
+            .map_err(|e| {
+                Error::Training(format!("Failed to run MLX: {}. Is mlx-lm installed?", e))
+            })?;
+
+        let log_path = write_log(job.output_dir, &output)?;
+        if !output.status.success() {
+            return Err(Error::Training(format!(
+                "MLX training failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        Ok(TrainingArtifacts {
+            adapter_path,
+            log_path: Some(log_path),
+        })
+    }
+}
+
+/// CPU LoRA fine-tuning with llama.cpp's `llama-finetune`
+///
+/// llama.cpp trains on plain text, so the chat JSONL is flattened into a
+/// text file with one `<SFT>`-prefixed sample per example. The base model
+/// must be a GGUF file; Ollama model names are resolved to their blob via
+/// `ollama show --modelfile`.
+#[derive(Debug, Clone)]
+pub struct LlamaCppTrainer {
+    /// Path or name of the finetune binary
+    pub binary: String,
+    pub threads: usize,
+    pub epochs: u32,
+    pub context_size: u32,
+    pub batch_size: u32,
+}
+
+impl Default for LlamaCppTrainer {
+    fn default() -> Self {
+        Self {
+            binary: "llama-finetune".to_string(),
+            threads: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(4),
+            epochs: 2,
+            context_size: 512,
+            batch_size: 4,
+        }
+    }
+}
+
+impl LlamaCppTrainer {
+    /// Defaults overridden by `HONE_LLAMA_FINETUNE` / `HONE_LLAMA_THREADS`
+    pub fn from_env() -> Self {
+        let mut trainer = Self::default();
+        if let Ok(binary) = std::env::var("HONE_LLAMA_FINETUNE") {
+            if !binary.trim().is_empty() {
+                trainer.binary = binary;
+            }
+        }
+        if let Some(threads) = std::env::var("HONE_LLAMA_THREADS")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|n| *n > 0)
+        {
+            trainer.threads = threads;
+        }
+        trainer
+    }
+
+    /// Resolve the base model to a GGUF file path
+    fn resolve_base_model(&self, base_model: &str) -> Result<PathBuf> {
+        let path = Path::new(base_model);
+        if path.is_file() {
+            return Ok(path.to_path_buf());
+        }
+
+        let output = Command::new("ollama")
+            .args(["show", "--modelfile", base_model])
+            .output()
+            .map_err(|e| Error::Training(format!("Failed to run ollama: {}", e)))?;
+        if !output.status.success() {
+            return Err(Error::Training(format!(
+                "Could not resolve base model '{}': {}",
+                base_model,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        parse_modelfile_from(&String::from_utf8_lossy(&output.stdout))
+            .filter(|p| p.is_file())
+            .ok_or_else(|| {
+                Error::Training(format!(
+                    "Base model '{}' has no local GGUF file; pass a .gguf path as the base model",
+                    base_model
+                ))
+            })
+    }
+}
+
+impl Trainer for LlamaCppTrainer {
+    fn name(&self) -> &'static str {
+        "llama_cpp"
+    }
+
+    fn train(&self, job: &TrainingJob<'_>) -> Result<TrainingArtifacts> {
+        let base_gguf = self.resolve_base_model(&job.experiment.base_model)?;
+
+        let text_path = job.output_dir.join("training_data.txt");
+        let jsonl = fs::read_to_string(job.training_data)?;
+        fs::write(&text_path, chat_jsonl_to_text(&jsonl)?)?;
+
+        let adapter_path = job.output_dir.join("adapter.gguf");
+        let output = Command::new(&self.binary)
+            .args([
+                "--model-base",
+                base_gguf.to_string_lossy().as_ref(),
+                "--train-data",
+                text_path.to_string_lossy().as_ref(),
+                "--lora-out",
+                adapter_path.to_string_lossy().as_ref(),
+                "--threads",
+                &self.threads.to_string(),
+                "--epochs",
+                &self.epochs.to_string(),
+                "--ctx",
+                &self.context_size.to_string(),
+                "--batch",
+                &self.batch_size.to_string(),
+                "--sample-start",
+                LLAMA_SAMPLE_START,
+                "--include-sample-start",
+            ])
+            .output()
+            .map_err(|e| {
+                Error::Training(format!(
+                    "Failed to run {}: {}. Is llama.cpp installed?",
+                    self.binary, e
+                ))
+            })?;
+
+        let log_path = write_log(job.output_dir, &output)?;
+        if !output.status.success() {
+            return Err(Error::Training(format!(
+                "llama.cpp training failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        if !adapter_path.exists() {
+            return Err(Error::Training(format!(
+                "llama.cpp finished without writing {}",
+                adapter_path.display()
+            )));
+        }
+
+        Ok(TrainingArtifacts {
+            adapter_path,
+            log_path: Some(log_path),
+        })
+    }
+}
+
+/// Trainer that writes placeholder artifacts (for tests)
+#[derive(Debug, Clone, Default)]
+pub struct FakeTrainer {
+    /// Fail with this message instead of producing artifacts
+    pub fail_with: Option<String>,
+}
+
+impl FakeTrainer {
+    /// A fake trainer whose runs always fail
+    pub fn failing(message: &str) -> Self {
+        Self {
+            fail_with: Some(message.to_string()),
+        }
+    }
+}
+
+impl Trainer for FakeTrainer {
+    fn name(&self) -> &'static str {
+        "fake"
+    }
+
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
+    fn train(&self, job: &TrainingJob<'_>) -> Result<TrainingArtifacts> {
+        if let Some(message) = &self.fail_with {
+            return Err(Error::Training(message.clone()));
+        }
+
+        let examples = fs::read_to_string(job.training_data)?.lines().count();
+        let adapter_path = job.output_dir.join("adapter.gguf");
+        fs::write(&adapter_path, b"fake adapter")?;
+        let log_path = job.output_dir.join("train.log");
+        fs::write(
+            &log_path,
+            format!(
+                "fake training: {} examples on {}\n",
+                examples, job.experiment.base_model
+            ),
+        )?;
+
+        Ok(TrainingArtifacts {
+            adapter_path,
+            log_path: Some(log_path),
+        })
+    }
+}
+
+/// Flatten chat-format JSONL into llama.cpp plain-text samples
+pub fn chat_jsonl_to_text(jsonl: &str) -> Result<String> {
+    let mut text = String::new();
+    for line in jsonl.lines().filter(|l| !l.trim().is_empty()) {
+        let example: ChatTrainingExample = serde_json::from_str(line)?;
+        text.push_str(LLAMA_SAMPLE_START);
+        for message in &example.messages {
+            text.push_str(&format!("### {}\n{}\n", message.role, message.content));
+        }
+    }
+    Ok(text)
+}
+
+/// The `FROM` path in `ollama show --modelfile` output
+fn parse_modelfile_from(modelfile: &str) -> Option<PathBuf> {
+    modelfile
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("FROM "))
+        .map(str::trim)
+        .find(|from| from.starts_with('/'))
+        .map(PathBuf::from)
+}
+
+/// Save trainer output next to the other artifacts
+fn write_log(output_dir: &Path, output: &std::process::Output) -> Result<PathBuf> {
+    let log_path = output_dir.join("train.log");
+    let mut log = output.stdout.clone();
+    log.extend_from_slice(&output.stderr);
+    fs::write(&log_path, log)?;
+    Ok(log_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trainer_from_name() {
+        assert_eq!(TrainerBackend::from_name("mlx").unwrap().name(), "mlx");
+        assert_eq!(
+            TrainerBackend::from_name("llama-cpp").unwrap().name(),
+            "llama_cpp"
+        );
+        assert_eq!(
+            TrainerBackend::from_name("LLAMA_CPP").unwrap().name(),
+            "llama_cpp"
+        );
+        assert_eq!(TrainerBackend::from_name("fake").unwrap().name(), "fake");
+        assert!(TrainerBackend::from_name("cuda").is_none());
+    }
+
+    #[test]
+    fn test_chat_jsonl_to_text() {
+        let jsonl = concat!(
+            r#"{"messages":[{"role":"system","content":"Classify."},{"role":"user","content":"NETFLIX.COM"},{"role":"assistant","content":"Entertainment"}]}"#,
+            "\n\n",
+            r#"{"messages":[{"role":"user","content":"KROGER #123"},{"role":"assistant","content":"Groceries"}]}"#,
+            "\n"
+        );
+        let text = chat_jsonl_to_text(jsonl).unwrap();
+        assert_eq!(text.matches(LLAMA_SAMPLE_START).count(), 2);
+        assert!(text.starts_with("<SFT>### system\nClassify.\n### user\nNETFLIX.COM\n"));
+        assert!(text.ends_with("### assistant\nGroceries\n"));
+    }
+
+    #[test]
+    fn test_chat_jsonl_to_text_invalid() {
+        assert!(chat_jsonl_to_text("not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_modelfile_from() {
+        let modelfile = "# Modelfile generated by \"ollama show\"\n# FROM gemma3:latest\nFROM /home/hone/.ollama/models/blobs/sha256-abc\nTEMPLATE \"\"\"{{ .Prompt }}\"\"\"\n";
+        assert_eq!(
+            parse_modelfile_from(modelfile),
+            Some(PathBuf::from("/home/hone/.ollama/models/blobs/sha256-abc"))
+        );
+        assert_eq!(parse_modelfile_from("FROM gemma3\n"), None);
+    }
+}
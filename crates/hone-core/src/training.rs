@@ -2,6 +2,11 @@
 //!
 //! This module exports training data from user corrections and Ollama interactions
 //! in formats suitable for fine-tuning LLMs (JSONL chat format for Ollama/llama.cpp).
+//!
+//! The label-style tasks (merchant/subscription classification, normalization) use
+//! short task-specific system prompts. The JSON tasks (entity/split suggestion,
+//! receipt matching and parsing) render the same prompt templates used at inference
+//! time, and the assistant message is the JSON the model is expected to return.
 
 use std::collections::HashMap;
 use std::io::Write;
@@ -9,8 +14,10 @@ use std::io::Write;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 
+use crate::ai::{EntitySuggestion, ParsedReceipt, ReceiptMatchEvaluation, SplitRecommendation};
 use crate::db::Database;
 use crate::error::Result;
+use crate::prompts::{PromptId, PromptLibrary};
 
 /// Supported training tasks
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -22,6 +29,14 @@ pub enum TrainingTask {
     NormalizeMerchant,
     /// Merchant → subscription/retail classification
     ClassifySubscription,
+    /// Purchase → entity it was for (from user split assignments)
+    SuggestEntity,
+    /// Merchant → whether transactions should be split (from user splits)
+    SuggestSplit,
+    /// Receipt + transaction → same purchase or not (from manual link/unlink)
+    EvaluateReceiptMatch,
+    /// Receipt image → structured line items (from user-edited parsed data)
+    ParseReceipt,
 }
 
 impl TrainingTask {
@@ -30,6 +45,10 @@ impl TrainingTask {
             TrainingTask::ClassifyMerchant => "classify_merchant",
             TrainingTask::NormalizeMerchant => "normalize_merchant",
             TrainingTask::ClassifySubscription => "classify_subscription",
+            TrainingTask::SuggestEntity => "suggest_entity",
+            TrainingTask::SuggestSplit => "suggest_split",
+            TrainingTask::EvaluateReceiptMatch => "evaluate_receipt_match",
+            TrainingTask::ParseReceipt => "parse_receipt",
         }
     }
 
@@ -38,6 +57,10 @@ impl TrainingTask {
             "classify_merchant" => Some(TrainingTask::ClassifyMerchant),
             "normalize_merchant" => Some(TrainingTask::NormalizeMerchant),
             "classify_subscription" => Some(TrainingTask::ClassifySubscription),
+            "suggest_entity" => Some(TrainingTask::SuggestEntity),
+            "suggest_split" => Some(TrainingTask::SuggestSplit),
+            "evaluate_receipt_match" => Some(TrainingTask::EvaluateReceiptMatch),
+            "parse_receipt" => Some(TrainingTask::ParseReceipt),
             _ => None,
         }
     }
@@ -47,8 +70,30 @@ impl TrainingTask {
             TrainingTask::ClassifyMerchant,
             TrainingTask::NormalizeMerchant,
             TrainingTask::ClassifySubscription,
+            TrainingTask::SuggestEntity,
+            TrainingTask::SuggestSplit,
+            TrainingTask::EvaluateReceiptMatch,
+            TrainingTask::ParseReceipt,
         ]
     }
+
+    /// Prompt template used at inference time (for tasks that render one)
+    pub fn prompt_id(&self) -> Option<PromptId> {
+        match self {
+            TrainingTask::ClassifyMerchant
+            | TrainingTask::NormalizeMerchant
+            | TrainingTask::ClassifySubscription => None,
+            TrainingTask::SuggestEntity => Some(PromptId::SuggestEntity),
+            TrainingTask::SuggestSplit => Some(PromptId::SuggestSplit),
+            TrainingTask::EvaluateReceiptMatch => Some(PromptId::EvaluateReceiptMatch),
+            TrainingTask::ParseReceipt => Some(PromptId::ParseReceipt),
+        }
+    }
+
+    /// Whether examples include an image (vision fine-tuning)
+    pub fn is_vision(&self) -> bool {
+        matches!(self, TrainingTask::ParseReceipt)
+    }
 }
 
 impl std::fmt::Display for TrainingTask {
@@ -85,6 +130,9 @@ pub struct TrainingExample {
 pub struct ChatMessage {
     pub role: String,
     pub content: String,
+    /// Image file paths attached to the message (vision tasks only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
 }
 
 /// Training example in chat format (ready for export)
@@ -103,6 +151,19 @@ pub struct TrainingExportStats {
     pub unique_inputs: usize,
 }
 
+/// A manual receipt match decision with the fields the match prompt needs
+#[derive(Debug, Clone)]
+pub struct ReceiptMatchTrainingRow {
+    pub receipt_merchant: Option<String>,
+    pub receipt_date: Option<String>,
+    pub receipt_total: Option<f64>,
+    pub transaction_description: String,
+    pub transaction_merchant_normalized: Option<String>,
+    pub transaction_date: String,
+    pub transaction_amount: f64,
+    pub is_match: bool,
+}
+
 /// Training data generator
 pub struct TrainingDataGenerator<'a> {
     db: &'a Database,
@@ -119,6 +180,10 @@ impl<'a> TrainingDataGenerator<'a> {
             TrainingTask::ClassifyMerchant => self.generate_classify_merchant(),
             TrainingTask::NormalizeMerchant => self.generate_normalize_merchant(),
             TrainingTask::ClassifySubscription => self.generate_classify_subscription(),
+            TrainingTask::SuggestEntity => self.generate_suggest_entity(),
+            TrainingTask::SuggestSplit => self.generate_suggest_split(),
+            TrainingTask::EvaluateReceiptMatch => self.generate_evaluate_receipt_match(),
+            TrainingTask::ParseReceipt => self.generate_parse_receipt(),
         }
     }
 
@@ -218,6 +283,201 @@ impl<'a> TrainingDataGenerator<'a> {
         Ok(examples)
     }
 
+    /// Generate entity suggestion training data
+    ///
+    /// Every split the user assigned to an entity becomes an example. The input is
+    /// the rendered `suggest_entity` prompt (merchant, category, current entities).
+    fn generate_suggest_entity(&self) -> Result<Vec<TrainingExample>> {
+        let entities = self.db.list_entities(false)?;
+        if entities.is_empty() {
+            return Ok(Vec::new());
+        }
+        let entity_list = entities
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut library = PromptLibrary::embedded_only();
+        let template = library.get(PromptId::SuggestEntity)?.clone();
+
+        let mut examples = Vec::new();
+        for (merchant, category, entity) in self.db.get_entity_assignment_training_data()? {
+            let mut vars = HashMap::new();
+            vars.insert("merchant", merchant.as_str());
+            vars.insert("category", category.as_str());
+            vars.insert("entities", entity_list.as_str());
+
+            let output = EntitySuggestion {
+                entity: Some(entity),
+                confidence: 1.0,
+                reason: "Assigned by user".to_string(),
+            };
+            examples.push(TrainingExample {
+                input: template.render_user(&vars),
+                output: serde_json::to_string(&output)?,
+                source: "user_correction".to_string(),
+                confidence: 1.0,
+                created_at: None,
+            });
+        }
+
+        Ok(Self::deduplicate_examples(examples))
+    }
+
+    /// Generate split suggestion training data
+    ///
+    /// Positive examples come from transactions the user split into several items
+    /// (categories taken from the split tags). Negative examples come from
+    /// user-acknowledged subscriptions, which are single-category charges.
+    fn generate_suggest_split(&self) -> Result<Vec<TrainingExample>> {
+        let mut library = PromptLibrary::embedded_only();
+        let template = library.get(PromptId::SuggestSplit)?.clone();
+        let render = |merchant: &str| {
+            let mut vars = HashMap::new();
+            vars.insert("merchant", merchant);
+            template.render_user(&vars)
+        };
+
+        let mut examples = Vec::new();
+
+        for (merchant, categories) in self.db.get_split_training_data()? {
+            let output = SplitRecommendation {
+                should_split: true,
+                reason: "User split this merchant's purchases across categories".to_string(),
+                typical_categories: categories,
+            };
+            examples.push(TrainingExample {
+                input: render(&merchant),
+                output: serde_json::to_string(&output)?,
+                source: "user_correction".to_string(),
+                confidence: 1.0,
+                created_at: None,
+            });
+        }
+
+        for merchant in self.db.get_unsplit_subscription_merchants()? {
+            let output = SplitRecommendation {
+                should_split: false,
+                reason: "Recurring single-category charge".to_string(),
+                typical_categories: Vec::new(),
+            };
+            examples.push(TrainingExample {
+                input: render(&merchant),
+                output: serde_json::to_string(&output)?,
+                source: "user_acknowledged".to_string(),
+                confidence: 0.8,
+                created_at: None,
+            });
+        }
+
+        Ok(Self::deduplicate_examples(examples))
+    }
+
+    /// Generate receipt match evaluation training data
+    ///
+    /// Uses the latest manual link (match) or unlink (not a match) decision for
+    /// each receipt/transaction pair.
+    fn generate_evaluate_receipt_match(&self) -> Result<Vec<TrainingExample>> {
+        let mut library = PromptLibrary::embedded_only();
+        let template = library.get(PromptId::EvaluateReceiptMatch)?.clone();
+
+        let mut examples = Vec::new();
+        for row in self.db.get_receipt_match_training_data()? {
+            // Same formatting as the inference-time prompt
+            let receipt_total = row
+                .receipt_total
+                .map(|t| format!("${:.2}", t))
+                .unwrap_or_else(|| "unknown".to_string());
+            let transaction_amount = format!("${:.2}", row.transaction_amount.abs());
+
+            let mut vars = HashMap::new();
+            vars.insert(
+                "receipt_merchant",
+                row.receipt_merchant.as_deref().unwrap_or("unknown"),
+            );
+            vars.insert(
+                "receipt_date",
+                row.receipt_date.as_deref().unwrap_or("unknown"),
+            );
+            vars.insert("receipt_total", receipt_total.as_str());
+            vars.insert(
+                "transaction_description",
+                row.transaction_description.as_str(),
+            );
+            vars.insert(
+                "transaction_merchant_normalized",
+                row.transaction_merchant_normalized
+                    .as_deref()
+                    .unwrap_or("unknown"),
+            );
+            vars.insert("transaction_date", row.transaction_date.as_str());
+            vars.insert("transaction_amount", transaction_amount.as_str());
+
+            let amount_explanation = match row.receipt_total {
+                Some(total)
+                    if row.is_match && (row.transaction_amount.abs() - total).abs() >= 0.01 =>
+                {
+                    Some(format!(
+                        "Transaction differs from receipt by ${:.2}",
+                        row.transaction_amount.abs() - total
+                    ))
+                }
+                _ => None,
+            };
+            let output = ReceiptMatchEvaluation {
+                is_match: row.is_match,
+                confidence: 1.0,
+                reason: if row.is_match {
+                    "Linked by user".to_string()
+                } else {
+                    "Unlinked by user".to_string()
+                },
+                amount_explanation,
+            };
+
+            examples.push(TrainingExample {
+                input: template.render_user(&vars),
+                output: serde_json::to_string(&output)?,
+                source: "user_correction".to_string(),
+                confidence: 1.0,
+                created_at: None,
+            });
+        }
+
+        Ok(Self::deduplicate_examples(examples))
+    }
+
+    /// Generate receipt parsing training data
+    ///
+    /// The input is the receipt image path; the output is the user-corrected
+    /// parsed receipt. Receipts stored without an image path are skipped.
+    fn generate_parse_receipt(&self) -> Result<Vec<TrainingExample>> {
+        let mut examples = Vec::new();
+
+        for (image_path, corrected_json) in self.db.get_receipt_parse_training_data()? {
+            // The parse_receipt prompt is the vision one; PDF, HTML and text
+            // receipts go through parse_receipt_text instead
+            let extension = image_path.rsplit('.').next().unwrap_or("").to_lowercase();
+            if matches!(extension.as_str(), "pdf" | "html" | "htm" | "txt") {
+                continue;
+            }
+            // Normalize through the parsed type so the output matches the prompt schema
+            let Ok(parsed) = serde_json::from_str::<ParsedReceipt>(&corrected_json) else {
+                continue;
+            };
+            examples.push(TrainingExample {
+                input: image_path,
+                output: serde_json::to_string(&parsed)?,
+                source: "user_correction".to_string(),
+                confidence: 1.0,
+                created_at: None,
+            });
+        }
+
+        Ok(Self::deduplicate_examples(examples))
+    }
+
     /// Deduplicate examples by input, keeping highest confidence
     fn deduplicate_examples(examples: Vec<TrainingExample>) -> Vec<TrainingExample> {
         let mut by_input: HashMap<String, TrainingExample> = HashMap::new();
@@ -258,22 +518,7 @@ impl<'a> TrainingDataGenerator<'a> {
                 ollama_confirmed += 1;
             }
 
-            let chat_example = ChatTrainingExample {
-                messages: vec![
-                    ChatMessage {
-                        role: "system".to_string(),
-                        content: system_prompt.clone(),
-                    },
-                    ChatMessage {
-                        role: "user".to_string(),
-                        content: example.input.clone(),
-                    },
-                    ChatMessage {
-                        role: "assistant".to_string(),
-                        content: example.output.clone(),
-                    },
-                ],
-            };
+            let chat_example = self.chat_example(task, &system_prompt, example);
 
             let json = serde_json::to_string(&chat_example)?;
             writeln!(writer, "{}", json)?;
@@ -288,8 +533,63 @@ impl<'a> TrainingDataGenerator<'a> {
         })
     }
 
+    /// Build the chat example for a task
+    ///
+    /// Vision tasks send the inference prompt as the user message with the image
+    /// attached; all other tasks use the example input as the user message.
+    fn chat_example(
+        &self,
+        task: TrainingTask,
+        system_prompt: &str,
+        example: &TrainingExample,
+    ) -> ChatTrainingExample {
+        let (content, images) = if task.is_vision() {
+            let content = task
+                .prompt_id()
+                .and_then(|id| {
+                    let mut library = PromptLibrary::embedded_only();
+                    library.get(id).ok().map(|p| p.render_user(&HashMap::new()))
+                })
+                .unwrap_or_default();
+            (content, vec![example.input.clone()])
+        } else {
+            (example.input.clone(), Vec::new())
+        };
+
+        ChatTrainingExample {
+            messages: vec![
+                ChatMessage {
+                    role: "system".to_string(),
+                    content: system_prompt.to_string(),
+                    images: Vec::new(),
+                },
+                ChatMessage {
+                    role: "user".to_string(),
+                    content,
+                    images,
+                },
+                ChatMessage {
+                    role: "assistant".to_string(),
+                    content: example.output.clone(),
+                    images: Vec::new(),
+                },
+            ],
+        }
+    }
+
     /// Get the system prompt for a task
-    fn get_system_prompt(&self, task: TrainingTask) -> String {
+    pub fn get_system_prompt(&self, task: TrainingTask) -> String {
+        if let Some(id) = task.prompt_id() {
+            let mut library = PromptLibrary::embedded_only();
+            if let Some(system) = library
+                .get(id)
+                .ok()
+                .and_then(|p| p.system_section().map(str::to_string))
+            {
+                return system;
+            }
+        }
+
         match task {
             TrainingTask::ClassifyMerchant => {
                 "You are a financial transaction classifier. Given a merchant description from a bank statement, output the spending category. Categories: Income, Housing, Utilities, Groceries, Dining, Transport, Healthcare, Shopping, Entertainment, Subscriptions, Travel, Personal, Education, Pets, Gifts, Financial, Other. Output only the category name.".to_string()
@@ -300,6 +600,10 @@ impl<'a> TrainingDataGenerator<'a> {
             TrainingTask::ClassifySubscription => {
                 "You are a subscription classifier. Given a merchant name, determine if it is a subscription service or a retail store. Output either SUBSCRIPTION or RETAIL.".to_string()
             }
+            TrainingTask::SuggestEntity
+            | TrainingTask::SuggestSplit
+            | TrainingTask::EvaluateReceiptMatch
+            | TrainingTask::ParseReceipt => "Return JSON only.".to_string(),
         }
     }
 }
@@ -440,6 +744,179 @@ impl Database {
 
         Ok(results)
     }
+
+    /// Get user entity assignments on splits for training
+    ///
+    /// Returns (transaction description, category, entity name), newest first.
+    /// The category is the split's tag, falling back to the transaction's tag.
+    pub fn get_entity_assignment_training_data(&self) -> Result<Vec<(String, String, String)>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT t.description,
+                   COALESCE(
+                       (SELECT tg.name FROM split_tags st
+                        INNER JOIN tags tg ON st.tag_id = tg.id
+                        WHERE st.split_id = s.id LIMIT 1),
+                       (SELECT tg.name FROM transaction_tags tt
+                        INNER JOIN tags tg ON tt.tag_id = tg.id
+                        WHERE tt.transaction_id = t.id LIMIT 1),
+                       'Other'
+                   ),
+                   e.name
+            FROM transaction_splits s
+            INNER JOIN transactions t ON s.transaction_id = t.id
+            INNER JOIN entities e ON s.entity_id = e.id
+            WHERE e.archived = 0
+            ORDER BY s.created_at DESC, s.id DESC
+            "#,
+        )?;
+
+        let results = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(results)
+    }
+
+    /// Get transactions the user split into multiple items, for training
+    ///
+    /// Returns (transaction description, distinct split tag names), newest first.
+    pub fn get_split_training_data(&self) -> Result<Vec<(String, Vec<String>)>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT t.id, t.description
+            FROM transactions t
+            WHERE (SELECT COUNT(*) FROM transaction_splits s
+                   WHERE s.transaction_id = t.id AND s.split_type = 'item') >= 2
+            ORDER BY t.date DESC, t.id DESC
+            "#,
+        )?;
+        let transactions = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut tag_stmt = conn.prepare(
+            r#"
+            SELECT DISTINCT tg.name
+            FROM transaction_splits s
+            INNER JOIN split_tags st ON st.split_id = s.id
+            INNER JOIN tags tg ON st.tag_id = tg.id
+            WHERE s.transaction_id = ?
+            ORDER BY tg.name
+            "#,
+        )?;
+
+        let mut results = Vec::with_capacity(transactions.len());
+        for (transaction_id, description) in transactions {
+            let categories = tag_stmt
+                .query_map(rusqlite::params![transaction_id], |row| {
+                    row.get::<_, String>(0)
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            results.push((description, categories));
+        }
+
+        Ok(results)
+    }
+
+    /// Get acknowledged subscription merchants that have never been split
+    pub fn get_unsplit_subscription_merchants(&self) -> Result<Vec<String>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT DISTINCT sub.merchant
+            FROM subscriptions sub
+            WHERE sub.user_acknowledged = 1
+              AND NOT EXISTS (
+                  SELECT 1 FROM transactions t
+                  INNER JOIN transaction_splits s ON s.transaction_id = t.id
+                  WHERE t.merchant_normalized = sub.merchant
+              )
+            ORDER BY sub.merchant
+            "#,
+        )?;
+
+        let results = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(results)
+    }
+
+    /// Get the latest manual match decision per receipt/transaction pair for training
+    pub fn get_receipt_match_training_data(&self) -> Result<Vec<ReceiptMatchTrainingRow>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT r.receipt_merchant, r.receipt_date, r.receipt_total,
+                   t.description, t.merchant_normalized, t.date, t.amount, f.is_match
+            FROM receipt_match_feedback f
+            INNER JOIN receipts r ON f.receipt_id = r.id
+            INNER JOIN transactions t ON f.transaction_id = t.id
+            WHERE f.id = (
+                SELECT MAX(f2.id) FROM receipt_match_feedback f2
+                WHERE f2.receipt_id = f.receipt_id AND f2.transaction_id = f.transaction_id
+            )
+            ORDER BY f.id DESC
+            "#,
+        )?;
+
+        let results = stmt
+            .query_map([], |row| {
+                Ok(ReceiptMatchTrainingRow {
+                    receipt_merchant: row.get(0)?,
+                    receipt_date: row.get(1)?,
+                    receipt_total: row.get(2)?,
+                    transaction_description: row.get(3)?,
+                    transaction_merchant_normalized: row.get(4)?,
+                    transaction_date: row.get(5)?,
+                    transaction_amount: row.get(6)?,
+                    is_match: row.get(7)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(results)
+    }
+
+    /// Get user-corrected receipt parses for training
+    ///
+    /// Returns (image path, corrected parsed JSON), newest first.
+    pub fn get_receipt_parse_training_data(&self) -> Result<Vec<(String, String)>> {
+        let conn = self.conn()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT r.image_path, c.corrected_json
+            FROM receipt_parse_corrections c
+            INNER JOIN receipts r ON c.receipt_id = r.id
+            WHERE r.image_path IS NOT NULL
+            ORDER BY c.corrected_at DESC
+            "#,
+        )?;
+
+        let results = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(results)
+    }
 }
 
 #[cfg(test)]
@@ -495,10 +972,14 @@ mod tests {
     #[test]
     fn test_training_task_all() {
         let all = TrainingTask::all();
-        assert_eq!(all.len(), 3);
+        assert_eq!(all.len(), 7);
         assert!(all.contains(&TrainingTask::ClassifyMerchant));
         assert!(all.contains(&TrainingTask::NormalizeMerchant));
         assert!(all.contains(&TrainingTask::ClassifySubscription));
+        assert!(all.contains(&TrainingTask::SuggestEntity));
+        assert!(all.contains(&TrainingTask::SuggestSplit));
+        assert!(all.contains(&TrainingTask::EvaluateReceiptMatch));
+        assert!(all.contains(&TrainingTask::ParseReceipt));
     }
 
     #[test]
@@ -579,14 +1060,17 @@ mod tests {
                 ChatMessage {
                     role: "system".to_string(),
                     content: "You are a classifier.".to_string(),
+                    images: Vec::new(),
                 },
                 ChatMessage {
                     role: "user".to_string(),
                     content: "NETFLIX".to_string(),
+                    images: Vec::new(),
                 },
                 ChatMessage {
                     role: "assistant".to_string(),
                     content: "Subscriptions".to_string(),
+                    images: Vec::new(),
                 },
             ],
         };
@@ -739,8 +1223,229 @@ mod tests {
         let msg = ChatMessage {
             role: "user".to_string(),
             content: "test content".to_string(),
+            images: Vec::new(),
         };
         assert_eq!(msg.role, "user");
         assert_eq!(msg.content, "test content");
     }
+
+    fn insert_transaction(db: &Database, description: &str, amount: f64, hash: &str) -> i64 {
+        use crate::models::{Bank, NewTransaction};
+        let account_id = db.upsert_account("Test", Bank::Chase, None).unwrap();
+        db.insert_transaction(
+            account_id,
+            &NewTransaction {
+                date: chrono::NaiveDate::from_ymd_opt(2024, 3, 10).unwrap(),
+                description: description.to_string(),
+                amount,
+                category: None,
+                import_hash: hash.to_string(),
+                original_data: None,
+                import_format: None,
+                card_member: None,
+                payment_method: None,
+            },
+        )
+        .unwrap()
+        .unwrap()
+    }
+
+    fn split(db: &Database, transaction_id: i64, amount: f64, entity_id: Option<i64>) -> i64 {
+        use crate::models::{NewTransactionSplit, SplitType};
+        db.create_split(&NewTransactionSplit {
+            transaction_id,
+            amount,
+            description: None,
+            split_type: SplitType::Item,
+            entity_id,
+            purchaser_id: None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_generate_new_tasks_empty() {
+        let db = create_test_db();
+        let generator = TrainingDataGenerator::new(&db);
+        for task in [
+            TrainingTask::SuggestEntity,
+            TrainingTask::SuggestSplit,
+            TrainingTask::EvaluateReceiptMatch,
+            TrainingTask::ParseReceipt,
+        ] {
+            assert!(generator.generate(task).unwrap().is_empty(), "{}", task);
+        }
+    }
+
+    #[test]
+    fn test_generate_suggest_entity() {
+        use crate::models::{EntityType, NewEntity, TagSource};
+        let db = create_test_db();
+        db.seed_root_tags().unwrap();
+        let rex = db
+            .create_entity(&NewEntity {
+                name: "Rex".to_string(),
+                entity_type: EntityType::Pet,
+                icon: None,
+                color: None,
+            })
+            .unwrap();
+        let tx = insert_transaction(&db, "PETCO ANIMAL SUPPLIES", -75.0, "entity-1");
+        let split_id = split(&db, tx, 75.0, Some(rex));
+        let pets = db.get_tag_by_path("Pets").unwrap().unwrap();
+        db.add_split_tag(split_id, pets.id, TagSource::Manual, None)
+            .unwrap();
+        // Unassigned splits are not examples
+        split(&db, tx, 10.0, None);
+
+        let generator = TrainingDataGenerator::new(&db);
+        let examples = generator.generate(TrainingTask::SuggestEntity).unwrap();
+        assert_eq!(examples.len(), 1);
+        assert!(examples[0]
+            .input
+            .contains("Merchant: \"PETCO ANIMAL SUPPLIES\""));
+        assert!(examples[0].input.contains("Category: \"Pets\""));
+        assert!(examples[0].input.contains("[Rex]"));
+
+        let output: EntitySuggestion = serde_json::from_str(&examples[0].output).unwrap();
+        assert_eq!(output.entity.as_deref(), Some("Rex"));
+    }
+
+    #[test]
+    fn test_generate_suggest_split() {
+        use crate::models::TagSource;
+        let db = create_test_db();
+        db.seed_root_tags().unwrap();
+        let tx = insert_transaction(&db, "TARGET 00012345", -120.0, "split-1");
+        let groceries = db.get_tag_by_path("Groceries").unwrap().unwrap();
+        let shopping = db.get_tag_by_path("Shopping").unwrap().unwrap();
+        let a = split(&db, tx, 70.0, None);
+        let b = split(&db, tx, 50.0, None);
+        db.add_split_tag(a, groceries.id, TagSource::Manual, None)
+            .unwrap();
+        db.add_split_tag(b, shopping.id, TagSource::Manual, None)
+            .unwrap();
+
+        // A single split is not a multi-category purchase
+        let single = insert_transaction(&db, "SHELL OIL", -40.0, "split-2");
+        split(&db, single, 40.0, None);
+
+        // Acknowledged subscriptions are negative examples
+        let sub_id = db
+            .upsert_subscription("Netflix", None, Some(15.99), None, None, None)
+            .unwrap();
+        db.acknowledge_subscription(sub_id).unwrap();
+
+        let generator = TrainingDataGenerator::new(&db);
+        let mut examples = generator.generate(TrainingTask::SuggestSplit).unwrap();
+        examples.sort_by(|a, b| a.input.cmp(&b.input));
+        assert_eq!(examples.len(), 2);
+
+        let netflix: SplitRecommendation = serde_json::from_str(&examples[0].output).unwrap();
+        assert!(examples[0].input.contains("\"Netflix\""));
+        assert!(!netflix.should_split);
+        assert_eq!(examples[0].source, "user_acknowledged");
+
+        let target: SplitRecommendation = serde_json::from_str(&examples[1].output).unwrap();
+        assert!(examples[1].input.contains("TARGET 00012345"));
+        assert!(target.should_split);
+        assert_eq!(target.typical_categories, vec!["Groceries", "Shopping"]);
+    }
+
+    #[test]
+    fn test_generate_evaluate_receipt_match() {
+        use crate::models::{NewReceipt, ReceiptRole, ReceiptStatus};
+        let db = create_test_db();
+        let tx = insert_transaction(&db, "OLIVE GARDEN 1234", -54.60, "match-1");
+        let other = insert_transaction(&db, "MCDONALD'S 5678", -8.99, "match-2");
+        let receipt_id = db
+            .create_receipt_full(&NewReceipt {
+                transaction_id: None,
+                image_path: None,
+                image_data: None,
+                status: ReceiptStatus::Pending,
+                role: ReceiptRole::Primary,
+                receipt_date: chrono::NaiveDate::from_ymd_opt(2024, 3, 10),
+                receipt_total: Some(45.50),
+                receipt_merchant: Some("Olive Garden".to_string()),
+                content_hash: None,
+            })
+            .unwrap();
+
+        db.record_receipt_match_feedback(receipt_id, other, true, None)
+            .unwrap();
+        // The latest decision for a pair wins
+        db.record_receipt_match_feedback(receipt_id, other, false, None)
+            .unwrap();
+        db.record_receipt_match_feedback(receipt_id, tx, true, None)
+            .unwrap();
+
+        let generator = TrainingDataGenerator::new(&db);
+        let examples = generator
+            .generate(TrainingTask::EvaluateReceiptMatch)
+            .unwrap();
+        assert_eq!(examples.len(), 2);
+
+        for example in &examples {
+            let output: ReceiptMatchEvaluation = serde_json::from_str(&example.output).unwrap();
+            assert!(example.input.contains("Total: $45.50"));
+            if example.input.contains("Description: OLIVE GARDEN 1234") {
+                assert!(output.is_match);
+                assert!(example.input.contains("Amount: $54.60"));
+                assert!(output.amount_explanation.unwrap().contains("$9.10"));
+            } else {
+                assert!(!output.is_match);
+                assert!(output.amount_explanation.is_none());
+            }
+        }
+    }
+
+    #[test]
+    fn test_export_parse_receipt_attaches_image() {
+        let db = create_test_db();
+        let tx = insert_transaction(&db, "TARGET 00012345", -48.50, "parse-1");
+        let receipt_id = db.create_receipt(tx, Some("/receipts/target.jpg")).unwrap();
+        db.update_receipt_parsed(receipt_id, r#"{"merchant":"Targt","items":[]}"#)
+            .unwrap();
+        db.correct_receipt_parsed_data(
+            receipt_id,
+            r#"{"merchant":"Target","date":"2024-03-10","items":[{"description":"Milk","amount":4.5,"split_type":"item"}],"total":48.5}"#,
+            Some("Target"),
+            chrono::NaiveDate::from_ymd_opt(2024, 3, 10),
+            Some(48.5),
+            Some("user@example.com"),
+        )
+        .unwrap();
+
+        let generator = TrainingDataGenerator::new(&db);
+        let mut output = Vec::new();
+        let stats = generator
+            .export_jsonl(TrainingTask::ParseReceipt, &mut output)
+            .unwrap();
+        assert_eq!(stats.total_examples, 1);
+        assert_eq!(stats.user_corrections, 1);
+
+        let line = String::from_utf8(output).unwrap();
+        let example: ChatTrainingExample = serde_json::from_str(line.trim()).unwrap();
+        assert!(example.messages[0].content.contains("receipt image"));
+        assert_eq!(example.messages[1].images, vec!["/receipts/target.jpg"]);
+        assert!(example.messages[1].content.contains("Return format"));
+        let parsed: ParsedReceipt = serde_json::from_str(&example.messages[2].content).unwrap();
+        assert_eq!(parsed.merchant.as_deref(), Some("Target"));
+        assert_eq!(parsed.items.len(), 1);
+        // Non-vision messages don't serialize an images field
+        assert!(!line.contains(r#""images":[]"#));
+    }
+
+    #[test]
+    fn test_system_prompt_from_template() {
+        let db = create_test_db();
+        let generator = TrainingDataGenerator::new(&db);
+        assert!(generator
+            .get_system_prompt(TrainingTask::SuggestSplit)
+            .contains("multiple categories"));
+        assert!(generator
+            .get_system_prompt(TrainingTask::EvaluateReceiptMatch)
+            .contains("same purchase"));
+    }
 }
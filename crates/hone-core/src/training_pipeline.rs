@@ -2,7 +2,7 @@
 //!
 //! This module provides infrastructure for:
 //! - Training experiment versioning (branches)
-//! - Automated fine-tuning via MLX or Ollama
+//! - Automated fine-tuning via pluggable trainer backends (MLX, llama.cpp)
 //! - Model comparison and promotion
 //! - Scheduled training runs
 
@@ -11,10 +11,12 @@ use std::path::PathBuf;
 use std::process::Command;
 
 use chrono::{DateTime, Utc};
+use rusqlite::OptionalExtension;
 use serde::{Deserialize, Serialize};
 
-use crate::db::Database;
+use crate::db::{parse_datetime, Database};
 use crate::error::Result;
+use crate::trainer::{MlxTrainer, Trainer, TrainingJob};
 use crate::training::{TrainingDataGenerator, TrainingExportStats, TrainingTask};
 
 /// Status of a training experiment
@@ -88,6 +90,12 @@ pub struct TrainingExperiment {
     pub training_data_path: Option<String>,
     /// Adapter/LoRA file path
     pub adapter_path: Option<String>,
+    /// Trainer backend that produced the adapter (mlx, llama_cpp)
+    pub trainer: Option<String>,
+    /// Trainer output log path
+    pub log_path: Option<String>,
+    /// Ollama Modelfile path (written when the model is created)
+    pub modelfile_path: Option<String>,
     /// Evaluation metrics (JSON)
     pub metrics: Option<String>,
     /// Notes or description
@@ -126,6 +134,23 @@ pub struct ComparisonResult {
     pub improvement: f64,
 }
 
+/// Data growth needed before a scheduled run retrains a task
+pub const RETRAIN_GROWTH: f64 = 1.2;
+
+/// What a scheduled training run did for one task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledTrainingOutcome {
+    pub task: String,
+    /// Training examples available
+    pub examples: usize,
+    /// Experiment created for this run
+    pub experiment_id: Option<i64>,
+    /// Why the task wasn't trained
+    pub skipped: Option<String>,
+    /// Why training failed
+    pub error: Option<String>,
+}
+
 /// Configuration for the training pipeline
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PipelineConfig {
@@ -225,10 +250,7 @@ impl<'a> TrainingPipeline<'a> {
         })?;
 
         // Create artifacts directory
-        let exp_dir = self
-            .config
-            .artifacts_dir
-            .join(format!("exp-{}", experiment_id));
+        let exp_dir = self.experiment_dir(experiment_id);
         fs::create_dir_all(&exp_dir)?;
 
         let data_path = exp_dir.join("training_data.jsonl");
@@ -244,30 +266,26 @@ impl<'a> TrainingPipeline<'a> {
         Ok((data_path, stats))
     }
 
-    /// Generate a Modelfile for Ollama fine-tuning
+    /// Generate a Modelfile that layers the trained adapter on the base model
     pub fn generate_modelfile(&self, experiment_id: i64) -> Result<PathBuf> {
-        let experiment = self
-            .db
-            .get_training_experiment(experiment_id)?
-            .ok_or_else(|| {
-                crate::error::Error::Training(format!("Experiment {} not found", experiment_id))
-            })?;
+        let experiment = self.get_experiment(experiment_id)?;
 
-        let exp_dir = self
-            .config
-            .artifacts_dir
-            .join(format!("exp-{}", experiment_id));
-        fs::create_dir_all(&exp_dir)?;
+        let adapter_path = experiment.adapter_path.ok_or_else(|| {
+            crate::error::Error::Training(format!(
+                "Experiment {} has no adapter; run training first",
+                experiment_id
+            ))
+        })?;
 
+        let exp_dir = self.experiment_dir(experiment_id);
+        fs::create_dir_all(&exp_dir)?;
         let modelfile_path = exp_dir.join("Modelfile");
-        let training_data_path = experiment.training_data_path.ok_or_else(|| {
-            crate::error::Error::Training("Training data not prepared".to_string())
-        })?;
 
         let modelfile_content = format!(
             r#"# Hone fine-tuned model for {}
 # Experiment: {} (branch: {})
 # Base model: {}
+# Trainer: {}
 
 FROM {}
 ADAPTER {}
@@ -279,96 +297,93 @@ SYSTEM """{}"""
             experiment_id,
             experiment.branch,
             experiment.base_model,
+            experiment.trainer.as_deref().unwrap_or("unknown"),
             experiment.base_model,
-            training_data_path,
+            adapter_path,
             self.get_system_prompt(&experiment.task),
         );
 
         fs::write(&modelfile_path, modelfile_content)?;
+        self.db.update_experiment_modelfile(
+            experiment_id,
+            modelfile_path.to_string_lossy().as_ref(),
+        )?;
 
         Ok(modelfile_path)
     }
 
-    /// Run MLX fine-tuning (for Mac Studio)
-    pub fn run_mlx_finetuning(&self, experiment_id: i64) -> Result<()> {
-        let experiment = self
-            .db
-            .get_training_experiment(experiment_id)?
-            .ok_or_else(|| {
-                crate::error::Error::Training(format!("Experiment {} not found", experiment_id))
-            })?;
+    /// Run fine-tuning with the given trainer backend
+    ///
+    /// Training data must already be prepared. On success the adapter, log and
+    /// trainer name are recorded on the experiment and its status is `completed`.
+    pub fn run_finetuning(
+        &self,
+        experiment_id: i64,
+        trainer: &dyn Trainer,
+    ) -> Result<TrainingExperiment> {
+        let experiment = self.get_experiment(experiment_id)?;
+
+        let training_data = experiment.training_data_path.clone().ok_or_else(|| {
+            crate::error::Error::Training("Training data not prepared".to_string())
+        })?;
+
+        let task = TrainingTask::from_str(&experiment.task).ok_or_else(|| {
+            crate::error::Error::Training(format!("Unknown task: {}", experiment.task))
+        })?;
+        if task.is_vision() && !trainer.supports_vision() {
+            return Err(crate::error::Error::Training(format!(
+                "The {} trainer cannot fine-tune vision task {}",
+                trainer.name(),
+                experiment.task
+            )));
+        }
+
+        let exp_dir = self.experiment_dir(experiment_id);
+        fs::create_dir_all(&exp_dir)?;
 
         // Mark as training
         self.db
             .update_experiment_status(experiment_id, ExperimentStatus::Training)?;
 
-        let exp_dir = self
-            .config
-            .artifacts_dir
-            .join(format!("exp-{}", experiment_id));
-        let adapter_path = exp_dir.join("adapters");
-
-        // Prepare MLX command
-        // This assumes mlx-lm is installed: pip install mlx-lm
-        let training_data = experiment.training_data_path.ok_or_else(|| {
-            crate::error::Error::Training("Training data not prepared".to_string())
-        })?;
-
-        let output = Command::new("mlx_lm.lora")
-            .args([
-                "--model",
-                &experiment.base_model,
-                "--train",
-                "--data",
-                &training_data,
-                "--adapter-path",
-                adapter_path.to_string_lossy().as_ref(),
-                "--iters",
-                "100", // Adjust based on data size
-                "--batch-size",
-                "4",
-                "--learning-rate",
-                "1e-5",
-            ])
-            .output();
+        let job = TrainingJob {
+            experiment: &experiment,
+            training_data: std::path::Path::new(&training_data),
+            output_dir: &exp_dir,
+        };
 
-        match output {
-            Ok(result) if result.status.success() => {
-                self.db.update_experiment_adapter(
+        match trainer.train(&job) {
+            Ok(artifacts) => {
+                self.db.update_experiment_artifacts(
                     experiment_id,
-                    adapter_path.to_string_lossy().as_ref(),
+                    trainer.name(),
+                    artifacts.adapter_path.to_string_lossy().as_ref(),
+                    artifacts
+                        .log_path
+                        .as_ref()
+                        .map(|p| p.to_string_lossy().to_string())
+                        .as_deref(),
                 )?;
                 self.db
                     .update_experiment_status(experiment_id, ExperimentStatus::Completed)?;
-                Ok(())
-            }
-            Ok(result) => {
-                self.db
-                    .update_experiment_status(experiment_id, ExperimentStatus::Failed)?;
-                Err(crate::error::Error::Training(format!(
-                    "MLX training failed: {}",
-                    String::from_utf8_lossy(&result.stderr)
-                )))
+                self.get_experiment(experiment_id)
             }
             Err(e) => {
                 self.db
                     .update_experiment_status(experiment_id, ExperimentStatus::Failed)?;
-                Err(crate::error::Error::Training(format!(
-                    "Failed to run MLX: {}. Is mlx-lm installed?",
-                    e
-                )))
+                Err(e)
             }
         }
     }
 
+    /// Run MLX fine-tuning (for Mac Studio)
+    pub fn run_mlx_finetuning(&self, experiment_id: i64) -> Result<()> {
+        self.run_finetuning(experiment_id, &MlxTrainer::default())?;
+        Ok(())
+    }
+
     /// Create Ollama model from fine-tuned adapter
     pub fn create_ollama_model(&self, experiment_id: i64) -> Result<String> {
-        let experiment = self
-            .db
-            .get_training_experiment(experiment_id)?
-            .ok_or_else(|| {
-                crate::error::Error::Training(format!("Experiment {} not found", experiment_id))
-            })?;
+        let experiment = self.get_experiment(experiment_id)?;
 
         let modelfile_path = self.generate_modelfile(experiment_id)?;
 
@@ -463,6 +478,96 @@ SYSTEM """{}"""
         )
     }
 
+    /// Train each task whose data has grown enough since its last trained model
+    ///
+    /// A task is skipped when it has fewer than `min_training_examples`
+    /// examples, or less than [`RETRAIN_GROWTH`] times the examples of its
+    /// latest completed or promoted experiment. New experiments branch from
+    /// the promoted one; promotion stays manual.
+    pub fn run_scheduled_training(
+        &self,
+        tasks: &[TrainingTask],
+        trainer: &dyn Trainer,
+    ) -> Result<Vec<ScheduledTrainingOutcome>> {
+        let branch = format!("scheduled-{}", Utc::now().format("%Y%m%d"));
+        let generator = TrainingDataGenerator::new(self.db);
+        let mut outcomes = Vec::new();
+
+        for &task in tasks {
+            let mut outcome = ScheduledTrainingOutcome {
+                task: task.as_str().to_string(),
+                examples: 0,
+                experiment_id: None,
+                skipped: None,
+                error: None,
+            };
+            outcome.examples = generator.generate(task)?.len();
+
+            let trained = self
+                .db
+                .list_training_experiments(Some(task.as_str()), None)?
+                .into_iter()
+                .find(|e| {
+                    matches!(
+                        e.status,
+                        ExperimentStatus::Completed | ExperimentStatus::Promoted
+                    )
+                });
+            if task.is_vision() && !trainer.supports_vision() {
+                outcome.skipped = Some(format!(
+                    "{} trainer can't fine-tune vision tasks",
+                    trainer.name()
+                ));
+            } else if outcome.examples < self.config.min_training_examples {
+                outcome.skipped = Some(format!(
+                    "{} examples (minimum: {})",
+                    outcome.examples, self.config.min_training_examples
+                ));
+            } else if let Some(trained) = trained
+                .filter(|e| (outcome.examples as f64) < e.training_examples as f64 * RETRAIN_GROWTH)
+            {
+                outcome.skipped = Some(format!(
+                    "{} examples, {} when experiment {} was trained",
+                    outcome.examples, trained.training_examples, trained.id
+                ));
+            } else {
+                let parent = self.db.get_promoted_experiment(task.as_str())?;
+                let result = self
+                    .create_experiment(
+                        task,
+                        &branch,
+                        parent.as_ref().map(|p| p.base_model.as_str()),
+                        parent.as_ref().map(|p| p.id),
+                        Some("Scheduled training run"),
+                    )
+                    .and_then(|experiment| {
+                        outcome.experiment_id = Some(experiment.id);
+                        self.prepare_training_data(experiment.id)?;
+                        self.run_finetuning(experiment.id, trainer)
+                    });
+                if let Err(e) = result {
+                    outcome.error = Some(e.to_string());
+                }
+            }
+            outcomes.push(outcome);
+        }
+        Ok(outcomes)
+    }
+
+    fn get_experiment(&self, experiment_id: i64) -> Result<TrainingExperiment> {
+        self.db
+            .get_training_experiment(experiment_id)?
+            .ok_or_else(|| {
+                crate::error::Error::Training(format!("Experiment {} not found", experiment_id))
+            })
+    }
+
+    fn experiment_dir(&self, experiment_id: i64) -> PathBuf {
+        self.config
+            .artifacts_dir
+            .join(format!("exp-{}", experiment_id))
+    }
+
     fn get_system_prompt(&self, task: &str) -> String {
         match task {
             "classify_merchant" => {
@@ -474,11 +579,49 @@ SYSTEM """{}"""
             "classify_subscription" => {
                 "You are a subscription classifier. Output SUBSCRIPTION or RETAIL.".to_string()
             }
-            _ => "You are a helpful assistant.".to_string(),
+            other => match TrainingTask::from_str(other) {
+                // Template-driven tasks share the generator's system prompt
+                Some(task) => TrainingDataGenerator::new(self.db).get_system_prompt(task),
+                None => "You are a helpful assistant.".to_string(),
+            },
         }
     }
 }
 
+const EXPERIMENT_COLUMNS: &str = r#"
+    id, branch, task, base_model, model_name, status,
+    parent_id, training_examples, training_data_path,
+    adapter_path, trainer, log_path, modelfile_path,
+    metrics, notes, created_at, started_at, completed_at
+"#;
+
+fn row_to_experiment(row: &rusqlite::Row) -> rusqlite::Result<TrainingExperiment> {
+    let status_str: String = row.get(5)?;
+    let created_at: String = row.get(15)?;
+    let started_at: Option<String> = row.get(16)?;
+    let completed_at: Option<String> = row.get(17)?;
+    Ok(TrainingExperiment {
+        id: row.get(0)?,
+        branch: row.get(1)?,
+        task: row.get(2)?,
+        base_model: row.get(3)?,
+        model_name: row.get(4)?,
+        status: ExperimentStatus::from_str(&status_str).unwrap_or(ExperimentStatus::Pending),
+        parent_id: row.get(6)?,
+        training_examples: row.get(7)?,
+        training_data_path: row.get(8)?,
+        adapter_path: row.get(9)?,
+        trainer: row.get(10)?,
+        log_path: row.get(11)?,
+        modelfile_path: row.get(12)?,
+        metrics: row.get(13)?,
+        notes: row.get(14)?,
+        created_at: parse_datetime(&created_at),
+        started_at: started_at.map(|s| parse_datetime(&s)),
+        completed_at: completed_at.map(|s| parse_datetime(&s)),
+    })
+}
+
 // Database methods for training experiments
 impl Database {
     /// Create a new training experiment
@@ -522,51 +665,13 @@ impl Database {
     pub fn get_training_experiment(&self, id: i64) -> Result<Option<TrainingExperiment>> {
         let conn = self.conn()?;
 
+        let sql = format!(
+            "SELECT {} FROM training_experiments WHERE id = ?",
+            EXPERIMENT_COLUMNS
+        );
         let experiment = conn
-            .query_row(
-                r#"
-                SELECT id, branch, task, base_model, model_name, status,
-                       parent_id, training_examples, training_data_path,
-                       adapter_path, metrics, notes, created_at, started_at, completed_at
-                FROM training_experiments WHERE id = ?
-                "#,
-                rusqlite::params![id],
-                |row| {
-                    let status_str: String = row.get(5)?;
-                    let created_at: String = row.get(12)?;
-                    let started_at: Option<String> = row.get(13)?;
-                    let completed_at: Option<String> = row.get(14)?;
-                    Ok(TrainingExperiment {
-                        id: row.get(0)?,
-                        branch: row.get(1)?,
-                        task: row.get(2)?,
-                        base_model: row.get(3)?,
-                        model_name: row.get(4)?,
-                        status: ExperimentStatus::from_str(&status_str)
-                            .unwrap_or(ExperimentStatus::Pending),
-                        parent_id: row.get(6)?,
-                        training_examples: row.get(7)?,
-                        training_data_path: row.get(8)?,
-                        adapter_path: row.get(9)?,
-                        metrics: row.get(10)?,
-                        notes: row.get(11)?,
-                        created_at: DateTime::parse_from_rfc3339(&created_at)
-                            .map(|dt| dt.with_timezone(&Utc))
-                            .unwrap_or_else(|_| Utc::now()),
-                        started_at: started_at.and_then(|s| {
-                            DateTime::parse_from_rfc3339(&s)
-                                .ok()
-                                .map(|dt| dt.with_timezone(&Utc))
-                        }),
-                        completed_at: completed_at.and_then(|s| {
-                            DateTime::parse_from_rfc3339(&s)
-                                .ok()
-                                .map(|dt| dt.with_timezone(&Utc))
-                        }),
-                    })
-                },
-            )
-            .ok();
+            .query_row(&sql, rusqlite::params![id], row_to_experiment)
+            .optional()?;
 
         Ok(experiment)
     }
@@ -579,14 +684,10 @@ impl Database {
     ) -> Result<Vec<TrainingExperiment>> {
         let conn = self.conn()?;
 
-        let mut sql = r#"
-            SELECT id, branch, task, base_model, model_name, status,
-                   parent_id, training_examples, training_data_path,
-                   adapter_path, metrics, notes, created_at, started_at, completed_at
-            FROM training_experiments
-            WHERE 1=1
-        "#
-        .to_string();
+        let mut sql = format!(
+            "SELECT {} FROM training_experiments WHERE 1=1",
+            EXPERIMENT_COLUMNS
+        );
 
         let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
@@ -600,46 +701,13 @@ impl Database {
             params.push(Box::new(b.to_string()));
         }
 
-        sql.push_str(" ORDER BY created_at DESC");
+        sql.push_str(" ORDER BY created_at DESC, id DESC");
 
         let mut stmt = conn.prepare(&sql)?;
         let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
 
         let experiments = stmt
-            .query_map(params_refs.as_slice(), |row| {
-                let status_str: String = row.get(5)?;
-                let created_at: String = row.get(12)?;
-                let started_at: Option<String> = row.get(13)?;
-                let completed_at: Option<String> = row.get(14)?;
-                Ok(TrainingExperiment {
-                    id: row.get(0)?,
-                    branch: row.get(1)?,
-                    task: row.get(2)?,
-                    base_model: row.get(3)?,
-                    model_name: row.get(4)?,
-                    status: ExperimentStatus::from_str(&status_str)
-                        .unwrap_or(ExperimentStatus::Pending),
-                    parent_id: row.get(6)?,
-                    training_examples: row.get(7)?,
-                    training_data_path: row.get(8)?,
-                    adapter_path: row.get(9)?,
-                    metrics: row.get(10)?,
-                    notes: row.get(11)?,
-                    created_at: DateTime::parse_from_rfc3339(&created_at)
-                        .map(|dt| dt.with_timezone(&Utc))
-                        .unwrap_or_else(|_| Utc::now()),
-                    started_at: started_at.and_then(|s| {
-                        DateTime::parse_from_rfc3339(&s)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&Utc))
-                    }),
-                    completed_at: completed_at.and_then(|s| {
-                        DateTime::parse_from_rfc3339(&s)
-                            .ok()
-                            .map(|dt| dt.with_timezone(&Utc))
-                    }),
-                })
-            })?
+            .query_map(params_refs.as_slice(), row_to_experiment)?
             .collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(experiments)
@@ -649,52 +717,13 @@ impl Database {
     pub fn get_promoted_experiment(&self, task: &str) -> Result<Option<TrainingExperiment>> {
         let conn = self.conn()?;
 
+        let sql = format!(
+            "SELECT {} FROM training_experiments WHERE task = ? AND status = 'promoted'",
+            EXPERIMENT_COLUMNS
+        );
         let experiment = conn
-            .query_row(
-                r#"
-                SELECT id, branch, task, base_model, model_name, status,
-                       parent_id, training_examples, training_data_path,
-                       adapter_path, metrics, notes, created_at, started_at, completed_at
-                FROM training_experiments
-                WHERE task = ? AND status = 'promoted'
-                "#,
-                rusqlite::params![task],
-                |row| {
-                    let status_str: String = row.get(5)?;
-                    let created_at: String = row.get(12)?;
-                    let started_at: Option<String> = row.get(13)?;
-                    let completed_at: Option<String> = row.get(14)?;
-                    Ok(TrainingExperiment {
-                        id: row.get(0)?,
-                        branch: row.get(1)?,
-                        task: row.get(2)?,
-                        base_model: row.get(3)?,
-                        model_name: row.get(4)?,
-                        status: ExperimentStatus::from_str(&status_str)
-                            .unwrap_or(ExperimentStatus::Pending),
-                        parent_id: row.get(6)?,
-                        training_examples: row.get(7)?,
-                        training_data_path: row.get(8)?,
-                        adapter_path: row.get(9)?,
-                        metrics: row.get(10)?,
-                        notes: row.get(11)?,
-                        created_at: DateTime::parse_from_rfc3339(&created_at)
-                            .map(|dt| dt.with_timezone(&Utc))
-                            .unwrap_or_else(|_| Utc::now()),
-                        started_at: started_at.and_then(|s| {
-                            DateTime::parse_from_rfc3339(&s)
-                                .ok()
-                                .map(|dt| dt.with_timezone(&Utc))
-                        }),
-                        completed_at: completed_at.and_then(|s| {
-                            DateTime::parse_from_rfc3339(&s)
-                                .ok()
-                                .map(|dt| dt.with_timezone(&Utc))
-                        }),
-                    })
-                },
-            )
-            .ok();
+            .query_row(&sql, rusqlite::params![task], row_to_experiment)
+            .optional()?;
 
         Ok(experiment)
     }
@@ -744,6 +773,32 @@ impl Database {
         Ok(())
     }
 
+    /// Record the artifacts of a finished training run
+    pub fn update_experiment_artifacts(
+        &self,
+        id: i64,
+        trainer: &str,
+        adapter_path: &str,
+        log_path: Option<&str>,
+    ) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "UPDATE training_experiments SET trainer = ?, adapter_path = ?, log_path = ? WHERE id = ?",
+            rusqlite::params![trainer, adapter_path, log_path, id],
+        )?;
+        Ok(())
+    }
+
+    /// Update experiment Modelfile path
+    pub fn update_experiment_modelfile(&self, id: i64, path: &str) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute(
+            "UPDATE training_experiments SET modelfile_path = ? WHERE id = ?",
+            rusqlite::params![path, id],
+        )?;
+        Ok(())
+    }
+
     /// Update experiment metrics
     pub fn update_experiment_metrics(&self, id: i64, metrics: &str) -> Result<()> {
         let conn = self.conn()?;
@@ -758,6 +813,7 @@ impl Database {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::trainer::FakeTrainer;
 
     fn create_test_db() -> Database {
         Database::in_memory().unwrap()
@@ -969,6 +1025,9 @@ mod tests {
             training_examples: 100,
             training_data_path: None,
             adapter_path: None,
+            trainer: None,
+            log_path: None,
+            modelfile_path: None,
             metrics: None,
             notes: Some("Test experiment".to_string()),
             created_at: Utc::now(),
@@ -1042,6 +1101,9 @@ mod tests {
         let subscription_prompt = pipeline.get_system_prompt("classify_subscription");
         assert!(subscription_prompt.contains("subscription"));
 
+        let entity_prompt = pipeline.get_system_prompt("suggest_entity");
+        assert!(entity_prompt.contains("entity"));
+
         let unknown_prompt = pipeline.get_system_prompt("unknown_task");
         assert!(unknown_prompt.contains("helpful assistant"));
     }
@@ -1201,6 +1263,9 @@ mod tests {
             training_examples: 100,
             training_data_path: Some("/path/to/data".to_string()),
             adapter_path: Some("/path/to/adapter".to_string()),
+            trainer: Some("llama_cpp".to_string()),
+            log_path: None,
+            modelfile_path: None,
             metrics: Some(r#"{"accuracy": 0.9}"#.to_string()),
             notes: None,
             created_at: Utc::now(),
@@ -1269,4 +1334,236 @@ mod tests {
         assert_eq!(cloned.default_base_model, config.default_base_model);
         assert_eq!(cloned.min_training_examples, config.min_training_examples);
     }
+
+    #[test]
+    fn test_experiment_lifecycle_for_receipt_match() {
+        use crate::models::{Bank, NewReceipt, NewTransaction, ReceiptRole, ReceiptStatus};
+
+        let db = create_test_db();
+        let account_id = db.upsert_account("Test", Bank::Chase, None).unwrap();
+        let tx = db
+            .insert_transaction(
+                account_id,
+                &NewTransaction {
+                    date: chrono::NaiveDate::from_ymd_opt(2024, 1, 10).unwrap(),
+                    description: "TARGET 00012345".to_string(),
+                    amount: -127.43,
+                    category: None,
+                    import_hash: "pipeline-receipt-1".to_string(),
+                    original_data: None,
+                    import_format: None,
+                    card_member: None,
+                    payment_method: None,
+                },
+            )
+            .unwrap()
+            .unwrap();
+        let receipt_id = db
+            .create_receipt_full(&NewReceipt {
+                transaction_id: None,
+                image_path: None,
+                image_data: None,
+                status: ReceiptStatus::Pending,
+                role: ReceiptRole::Primary,
+                receipt_date: chrono::NaiveDate::from_ymd_opt(2024, 1, 10),
+                receipt_total: Some(127.43),
+                receipt_merchant: Some("Target".to_string()),
+                content_hash: None,
+            })
+            .unwrap();
+        db.record_receipt_match_feedback(receipt_id, tx, true, None)
+            .unwrap();
+
+        let artifacts_dir =
+            std::env::temp_dir().join(format!("hone_pipeline_test_{}", std::process::id()));
+        let pipeline = TrainingPipeline::with_config(
+            &db,
+            PipelineConfig {
+                artifacts_dir: artifacts_dir.clone(),
+                default_base_model: "gemma3".to_string(),
+                min_training_examples: 1,
+                test_split: 0.1,
+            },
+        );
+
+        let experiment = pipeline
+            .create_experiment(TrainingTask::EvaluateReceiptMatch, "main", None, None, None)
+            .unwrap();
+        assert_eq!(experiment.task, "evaluate_receipt_match");
+        assert_eq!(experiment.model_name, "hone-evaluate-receipt-match-main");
+        assert_eq!(experiment.training_examples, 1);
+
+        let (data_path, stats) = pipeline.prepare_training_data(experiment.id).unwrap();
+        assert_eq!(stats.total_examples, 1);
+        let data = fs::read_to_string(&data_path).unwrap();
+        assert!(data.contains("TARGET 00012345"));
+
+        // No adapter yet, so there is nothing to build a model from
+        assert!(pipeline.generate_modelfile(experiment.id).is_err());
+
+        let trained = pipeline
+            .run_finetuning(experiment.id, &FakeTrainer::default())
+            .unwrap();
+        assert_eq!(trained.status, ExperimentStatus::Completed);
+        assert_eq!(trained.trainer.as_deref(), Some("fake"));
+        let adapter_path = trained.adapter_path.clone().unwrap();
+        assert!(std::path::Path::new(&adapter_path).exists());
+        assert!(std::path::Path::new(trained.log_path.as_deref().unwrap()).exists());
+        assert!(trained.started_at.is_some());
+        assert!(trained.completed_at.is_some());
+
+        let modelfile_path = pipeline.generate_modelfile(experiment.id).unwrap();
+        let modelfile = fs::read_to_string(&modelfile_path).unwrap();
+        assert!(modelfile.contains("same purchase"));
+        assert!(modelfile.contains(&format!("ADAPTER {}", adapter_path)));
+        assert!(modelfile.contains("# Trainer: fake"));
+        assert_eq!(
+            db.get_training_experiment(experiment.id)
+                .unwrap()
+                .unwrap()
+                .modelfile_path,
+            Some(modelfile_path.to_string_lossy().to_string())
+        );
+
+        let _ = fs::remove_dir_all(&artifacts_dir);
+    }
+
+    fn pipeline_with_examples<'a>(
+        db: &'a Database,
+        artifacts_dir: &std::path::Path,
+    ) -> TrainingPipeline<'a> {
+        db.cache_merchant_name("NETFLIX.COM 866", "Netflix", "user", 1.0)
+            .unwrap();
+        TrainingPipeline::with_config(
+            db,
+            PipelineConfig {
+                artifacts_dir: artifacts_dir.to_path_buf(),
+                default_base_model: "gemma3".to_string(),
+                min_training_examples: 1,
+                test_split: 0.1,
+            },
+        )
+    }
+
+    #[test]
+    fn test_run_finetuning_requires_training_data() {
+        let db = create_test_db();
+        let artifacts_dir =
+            std::env::temp_dir().join(format!("hone_trainer_nodata_{}", std::process::id()));
+        let pipeline = pipeline_with_examples(&db, &artifacts_dir);
+
+        let experiment = pipeline
+            .create_experiment(TrainingTask::NormalizeMerchant, "main", None, None, None)
+            .unwrap();
+        let err = pipeline
+            .run_finetuning(experiment.id, &FakeTrainer::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("not prepared"));
+        // Status is untouched when training never started
+        let experiment = db.get_training_experiment(experiment.id).unwrap().unwrap();
+        assert_eq!(experiment.status, ExperimentStatus::Pending);
+
+        let _ = fs::remove_dir_all(&artifacts_dir);
+    }
+
+    #[test]
+    fn test_run_finetuning_failure_marks_failed() {
+        let db = create_test_db();
+        let artifacts_dir =
+            std::env::temp_dir().join(format!("hone_trainer_fail_{}", std::process::id()));
+        let pipeline = pipeline_with_examples(&db, &artifacts_dir);
+
+        let experiment = pipeline
+            .create_experiment(TrainingTask::NormalizeMerchant, "main", None, None, None)
+            .unwrap();
+        pipeline.prepare_training_data(experiment.id).unwrap();
+
+        let err = pipeline
+            .run_finetuning(experiment.id, &FakeTrainer::failing("out of memory"))
+            .unwrap_err();
+        assert!(err.to_string().contains("out of memory"));
+
+        let experiment = db.get_training_experiment(experiment.id).unwrap().unwrap();
+        assert_eq!(experiment.status, ExperimentStatus::Failed);
+        assert!(experiment.adapter_path.is_none());
+        assert!(experiment.trainer.is_none());
+
+        let _ = fs::remove_dir_all(&artifacts_dir);
+    }
+
+    #[test]
+    fn test_run_finetuning_rejects_vision_task_without_support() {
+        let db = create_test_db();
+        let artifacts_dir =
+            std::env::temp_dir().join(format!("hone_trainer_vision_{}", std::process::id()));
+        let pipeline = pipeline_with_examples(&db, &artifacts_dir);
+
+        let experiment = db
+            .create_training_experiment(
+                "main",
+                TrainingTask::ParseReceipt.as_str(),
+                "gemma3",
+                "hone-parse-receipt-main",
+                None,
+                1,
+                None,
+            )
+            .unwrap();
+        db.update_experiment_training_data(experiment.id, "/tmp/unused.jsonl")
+            .unwrap();
+
+        let err = pipeline
+            .run_finetuning(experiment.id, &crate::trainer::LlamaCppTrainer::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("vision"));
+
+        let _ = fs::remove_dir_all(&artifacts_dir);
+    }
+
+    #[test]
+    fn test_run_scheduled_training_skips_without_new_data() {
+        let db = create_test_db();
+        let artifacts_dir =
+            std::env::temp_dir().join(format!("hone_trainer_scheduled_{}", std::process::id()));
+        let pipeline = pipeline_with_examples(&db, &artifacts_dir);
+        let tasks = [TrainingTask::NormalizeMerchant, TrainingTask::SuggestSplit];
+
+        let outcomes = pipeline
+            .run_scheduled_training(&tasks, &FakeTrainer::default())
+            .unwrap();
+        assert_eq!(outcomes.len(), 2);
+        let trained = outcomes[0].experiment_id.unwrap();
+        assert!(outcomes[0].error.is_none());
+        let experiment = db.get_training_experiment(trained).unwrap().unwrap();
+        assert_eq!(experiment.status, ExperimentStatus::Completed);
+        assert!(experiment.branch.starts_with("scheduled-"));
+        // No split examples at all
+        assert!(outcomes[1].experiment_id.is_none());
+        assert!(outcomes[1].skipped.as_deref().unwrap().contains("minimum"));
+
+        // Same data again: nothing to retrain
+        let outcomes = pipeline
+            .run_scheduled_training(&tasks[..1], &FakeTrainer::default())
+            .unwrap();
+        assert!(outcomes[0].experiment_id.is_none());
+        assert!(outcomes[0].skipped.is_some());
+
+        // Enough new examples retrains from the promoted model
+        pipeline.promote_experiment(trained).unwrap();
+        db.cache_merchant_name("SPOTIFY USA", "Spotify", "user", 1.0)
+            .unwrap();
+        let outcomes = pipeline
+            .run_scheduled_training(&tasks[..1], &FakeTrainer::default())
+            .unwrap();
+        let retrained = outcomes[0].experiment_id.unwrap();
+        assert_eq!(
+            db.get_training_experiment(retrained)
+                .unwrap()
+                .unwrap()
+                .parent_id,
+            Some(trained)
+        );
+
+        let _ = fs::remove_dir_all(&artifacts_dir);
+    }
 }
@@ -0,0 +1,443 @@
+//! Import directory watcher
+//!
+//! Scans a folder (e.g. a synced downloads folder) for new CSV and OFX/QFX
+//! statements and imports each through the normal import-session pipeline:
+//! transactions are inserted under a session and AI processing is queued as
+//! an `import_processing` job. Imported files move to an archive folder;
+//! files that can't be imported move to a quarantine folder next to a
+//! `<file>.error.txt` explaining why.
+//!
+//! Files are mapped to accounts by filename pattern in
+//! `~/.local/share/hone/config/watch.toml` (override with `HONE_WATCH_CONFIG`):
+//!
+//! ```toml
+//! dir = "/home/me/Statements"   # or pass it on the command line
+//!
+//! [[accounts]]
+//! pattern = "chase-*.csv"
+//! account = "Chase Sapphire"
+//!
+//! [[accounts]]
+//! pattern = "*checking*.ofx"
+//! account = "Credit Union Checking"
+//! bank = "bofa"                  # when the format can't be detected
+//! ```
+//!
+//! Unmapped files use the detected bank's default account
+//! (`CHASE Account`, same as `hone import`).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::import::{detect_bank_format, detect_ofx_bank, is_ofx, parse_csv, parse_ofx};
+use crate::models::{Bank, NewImportSession};
+
+/// Audit actor and session user for watcher imports
+pub const WATCHER_ACTOR: &str = "watcher";
+
+/// File extensions the watcher imports
+const EXTENSIONS: &[&str] = &["csv", "ofx", "qfx"];
+
+fn default_settle_secs() -> u64 {
+    10
+}
+
+fn default_poll_secs() -> u64 {
+    30
+}
+
+/// Maps statement filenames to an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountMapping {
+    /// Filename pattern (`*` and `?` wildcards, case-insensitive)
+    pub pattern: String,
+    /// Account name (created if it doesn't exist)
+    pub account: String,
+    /// Bank format, when it can't be detected from the file
+    #[serde(default)]
+    pub bank: Option<Bank>,
+}
+
+/// Import watcher configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchConfig {
+    /// Folder to watch (a command-line directory takes precedence)
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+    /// Where imported files go (default: `<dir>/archive`)
+    #[serde(default)]
+    pub archive_dir: Option<PathBuf>,
+    /// Where failed files go (default: `<dir>/quarantine`)
+    #[serde(default)]
+    pub quarantine_dir: Option<PathBuf>,
+    /// Skip files modified more recently than this (still syncing/downloading)
+    #[serde(default = "default_settle_secs")]
+    pub settle_secs: u64,
+    /// How often to scan the folder
+    #[serde(default = "default_poll_secs")]
+    pub poll_secs: u64,
+    #[serde(default)]
+    pub accounts: Vec<AccountMapping>,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            archive_dir: None,
+            quarantine_dir: None,
+            settle_secs: default_settle_secs(),
+            poll_secs: default_poll_secs(),
+            accounts: Vec::new(),
+        }
+    }
+}
+
+impl WatchConfig {
+    /// Parse and validate a TOML config
+    pub fn parse(content: &str) -> Result<Self> {
+        let config: WatchConfig = toml::from_str(content)
+            .map_err(|e| Error::Watch(format!("Invalid watch config: {}", e)))?;
+        if config.poll_secs == 0 {
+            return Err(Error::Watch("poll_secs must be at least 1".to_string()));
+        }
+        if let Some(mapping) = config.accounts.iter().find(|m| m.pattern.trim().is_empty()) {
+            return Err(Error::Watch(format!(
+                "Empty filename pattern for account {}",
+                mapping.account
+            )));
+        }
+        Ok(config)
+    }
+
+    /// Load from a path, or `HONE_WATCH_CONFIG`, or the default location
+    ///
+    /// Returns None when no config file exists.
+    pub fn load(path: Option<&Path>) -> Result<Option<Self>> {
+        let path = match path {
+            Some(p) => Some(p.to_path_buf()),
+            None => std::env::var("HONE_WATCH_CONFIG")
+                .ok()
+                .map(PathBuf::from)
+                .or_else(default_config_path),
+        };
+        let Some(path) = path.filter(|p| p.exists()) else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(&path)
+            .map_err(|e| Error::Watch(format!("Failed to read {}: {}", path.display(), e)))?;
+        Self::parse(&content).map(Some)
+    }
+
+    /// Use a different folder (e.g. from the command line)
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+
+    /// First account mapping whose pattern matches a filename
+    pub fn mapping_for(&self, filename: &str) -> Option<&AccountMapping> {
+        self.accounts
+            .iter()
+            .find(|m| glob_match(&m.pattern, filename))
+    }
+}
+
+/// Default config path
+pub fn default_config_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("hone").join("config").join("watch.toml"))
+}
+
+/// What happened to one file
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum WatchOutcome {
+    Imported {
+        session_id: i64,
+        account: String,
+        bank: Bank,
+        imported: usize,
+        skipped: usize,
+        /// Import processing job (None when nothing new was imported)
+        job_id: Option<i64>,
+        /// None if the file couldn't be archived; it stays in the folder and
+        /// the next scan finds only duplicates
+        archived_to: Option<PathBuf>,
+    },
+    Quarantined {
+        error: String,
+        quarantined_to: PathBuf,
+    },
+}
+
+/// A file handled by a scan
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchedFile {
+    pub filename: String,
+    #[serde(flatten)]
+    pub outcome: WatchOutcome,
+}
+
+/// Imports statements dropped into a folder
+pub struct ImportWatcher {
+    db: Database,
+    config: WatchConfig,
+    dir: PathBuf,
+    archive_dir: PathBuf,
+    quarantine_dir: PathBuf,
+}
+
+impl ImportWatcher {
+    /// Create a watcher; the config must name a folder that exists
+    pub fn new(db: Database, config: WatchConfig) -> Result<Self> {
+        let dir = config
+            .dir
+            .clone()
+            .ok_or_else(|| Error::Watch("No folder to watch".to_string()))?;
+        if !dir.is_dir() {
+            return Err(Error::Watch(format!("Not a directory: {}", dir.display())));
+        }
+        let archive_dir = config
+            .archive_dir
+            .clone()
+            .unwrap_or_else(|| dir.join("archive"));
+        let quarantine_dir = config
+            .quarantine_dir
+            .clone()
+            .unwrap_or_else(|| dir.join("quarantine"));
+        Ok(Self {
+            db,
+            config,
+            dir,
+            archive_dir,
+            quarantine_dir,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn config(&self) -> &WatchConfig {
+        &self.config
+    }
+
+    /// Import every settled statement in the folder
+    ///
+    /// Files modified within `settle_secs` of `now` are left for the next
+    /// scan. Subfolders (including the archive and quarantine) are ignored.
+    pub fn scan_once(&self, now: SystemTime) -> Result<Vec<WatchedFile>> {
+        let settle = Duration::from_secs(self.config.settle_secs);
+        let mut paths: Vec<PathBuf> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| is_statement(path))
+            .filter(|path| {
+                fs::metadata(path)
+                    .and_then(|m| m.modified())
+                    .map(|modified| now.duration_since(modified).unwrap_or_default() >= settle)
+                    .unwrap_or(false)
+            })
+            .collect();
+        paths.sort();
+
+        let mut handled = Vec::new();
+        for path in paths {
+            let filename = file_name(&path);
+            let outcome = match self.import_file(&path) {
+                Ok(imported) => imported,
+                Err(e) => {
+                    warn!("Quarantining {}: {}", filename, e);
+                    let quarantined_to = move_into(&path, &self.quarantine_dir)?;
+                    let note = quarantined_to
+                        .with_file_name(format!("{}.error.txt", file_name(&quarantined_to)));
+                    fs::write(&note, format!("{}\n", e))?;
+                    WatchOutcome::Quarantined {
+                        error: e.to_string(),
+                        quarantined_to,
+                    }
+                }
+            };
+            handled.push(WatchedFile { filename, outcome });
+        }
+        Ok(handled)
+    }
+
+    /// Import one file and archive it
+    fn import_file(&self, path: &Path) -> Result<WatchOutcome> {
+        let filename = file_name(path);
+        let data = fs::read(path)?;
+        let content = String::from_utf8_lossy(&data);
+        let content = content.trim_start_matches('\u{feff}');
+        let mapping = self.config.mapping_for(&filename);
+
+        let (bank, transactions) = if is_ofx(content) {
+            let bank = mapping
+                .and_then(|m| m.bank)
+                .or_else(|| detect_ofx_bank(content))
+                .ok_or_else(|| {
+                    Error::Import(format!(
+                        "Could not detect the bank of {}; add an [[accounts]] mapping with a bank",
+                        filename
+                    ))
+                })?;
+            (bank, parse_ofx(content)?)
+        } else {
+            let header = content.lines().next().unwrap_or_default();
+            let bank = mapping
+                .and_then(|m| m.bank)
+                .or_else(|| detect_bank_format(header))
+                .ok_or_else(|| {
+                    Error::Import(format!(
+                        "Could not detect the bank format of {}; add an [[accounts]] mapping with a bank",
+                        filename
+                    ))
+                })?;
+            (bank, parse_csv(content.as_bytes(), bank)?)
+        };
+        if transactions.is_empty() {
+            return Err(Error::Import(format!("No transactions in {}", filename)));
+        }
+
+        let account = mapping
+            .map(|m| m.account.clone())
+            .unwrap_or_else(|| format!("{} Account", bank.as_str().to_uppercase()));
+        let account_id = match self
+            .db
+            .list_accounts()?
+            .into_iter()
+            .find(|a| a.name == account)
+        {
+            Some(existing) => existing.id,
+            None => self.db.upsert_account(&account, bank, None)?,
+        };
+
+        let session = NewImportSession {
+            account_id,
+            filename: Some(filename.clone()),
+            file_size_bytes: Some(data.len() as i64),
+            bank,
+            user_email: Some(WATCHER_ACTOR.to_string()),
+            ollama_model: None,
+        };
+        let (session_id, imported, skipped) =
+            self.db.import_into_session(&session, &transactions)?;
+        self.db.log_audit(
+            WATCHER_ACTOR,
+            "import",
+            Some("transaction"),
+            None,
+            Some(&format!(
+                "session={}, account={}, file={}, imported={}, skipped={}",
+                session_id, account, filename, imported, skipped
+            )),
+        )?;
+        let job_id = self
+            .db
+            .queue_import_processing(session_id, imported, None, WATCHER_ACTOR)?;
+
+        info!(
+            "Imported {} into {} ({} new, {} duplicates)",
+            filename, account, imported, skipped
+        );
+        // The import is committed: a failed move mustn't quarantine the file
+        let archived_to = match move_into(path, &self.archive_dir) {
+            Ok(archived_to) => Some(archived_to),
+            Err(e) => {
+                warn!("Imported {} but could not archive it: {}", filename, e);
+                None
+            }
+        };
+        Ok(WatchOutcome::Imported {
+            session_id,
+            account,
+            bank,
+            imported,
+            skipped,
+            job_id,
+            archived_to,
+        })
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Regular, non-hidden file with a statement extension
+fn is_statement(path: &Path) -> bool {
+    let visible = !file_name(path).starts_with('.');
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    visible && path.is_file() && EXTENSIONS.contains(&extension.as_str())
+}
+
+/// Move a file into a folder, adding `-1`, `-2`... if the name is taken
+fn move_into(path: &Path, dir: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let name = file_name(path);
+    let mut target = dir.join(&name);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let mut n = 1;
+    while target.exists() {
+        target = dir.join(format!("{}-{}{}", stem, n, extension));
+        n += 1;
+    }
+    if fs::rename(path, &target).is_err() {
+        // Different filesystem: copy then remove
+        fs::copy(path, &target)?;
+        fs::remove_file(path)?;
+    }
+    Ok(target)
+}
+
+/// Case-insensitive filename match with `*` and `?` wildcards
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position after the last `*` and the name position it matched up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, n));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests;
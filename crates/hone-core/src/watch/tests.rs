@@ -0,0 +1,219 @@
+//! Import watcher tests
+
+use std::time::{Duration, SystemTime};
+
+use tempfile::TempDir;
+
+use super::*;
+use crate::models::{ImportStatus, JobType};
+
+const CHASE_CSV: &str = "Transaction Date,Post Date,Description,Category,Type,Amount,Memo
+01/15/2024,01/16/2024,NETFLIX.COM,Entertainment,Sale,-15.99,
+01/14/2024,01/15/2024,STARBUCKS,Food & Drink,Sale,-5.50,
+";
+
+fn later() -> SystemTime {
+    SystemTime::now() + Duration::from_secs(3600)
+}
+
+fn watcher(db: &Database, dir: &TempDir, config: &str) -> ImportWatcher {
+    let config = WatchConfig::parse(config).unwrap().with_dir(dir.path());
+    ImportWatcher::new(db.clone(), config).unwrap()
+}
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("chase-*.csv", "chase-2024-01.csv"));
+    assert!(glob_match("chase-*.csv", "Chase-2024-01.CSV"));
+    assert!(glob_match("*checking*", "my_checking_jan.ofx"));
+    assert!(glob_match("stmt-??.csv", "stmt-01.csv"));
+    assert!(glob_match("*", "anything"));
+    assert!(!glob_match("chase-*.csv", "amex-2024.csv"));
+    assert!(!glob_match("stmt-??.csv", "stmt-1.csv"));
+    assert!(!glob_match("*.csv", "file.csv.part"));
+}
+
+#[test]
+fn test_config_parse_and_mapping() {
+    let config = WatchConfig::parse(
+        r#"
+        settle_secs = 0
+
+        [[accounts]]
+        pattern = "chase-*.csv"
+        account = "Chase Sapphire"
+
+        [[accounts]]
+        pattern = "*.ofx"
+        account = "Credit Union"
+        bank = "bofa"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.poll_secs, 30);
+    assert_eq!(
+        config.mapping_for("chase-jan.csv").unwrap().account,
+        "Chase Sapphire"
+    );
+    assert_eq!(
+        config.mapping_for("jan.ofx").unwrap().bank,
+        Some(Bank::Bofa)
+    );
+    assert!(config.mapping_for("amex.csv").is_none());
+
+    assert!(WatchConfig::parse("poll_secs = 0").is_err());
+    assert!(WatchConfig::parse("[[accounts]]\npattern = \"\"\naccount = \"x\"").is_err());
+    assert!(
+        WatchConfig::parse("[[accounts]]\npattern = \"*\"\naccount = \"x\"\nbank = \"zzz\"")
+            .is_err()
+    );
+}
+
+#[test]
+fn test_scan_imports_and_archives() {
+    let db = Database::in_memory().unwrap();
+    let dir = TempDir::new().unwrap();
+    let watcher = watcher(
+        &db,
+        &dir,
+        "[[accounts]]\npattern = \"chase-*.csv\"\naccount = \"Chase Sapphire\"",
+    );
+    fs::write(dir.path().join("chase-jan.csv"), CHASE_CSV).unwrap();
+    fs::write(dir.path().join("notes.txt"), "not a statement").unwrap();
+
+    // Too fresh: still syncing
+    assert!(watcher.scan_once(SystemTime::now()).unwrap().is_empty());
+
+    let handled = watcher.scan_once(later()).unwrap();
+    assert_eq!(handled.len(), 1);
+    assert_eq!(handled[0].filename, "chase-jan.csv");
+    let WatchOutcome::Imported {
+        session_id,
+        account,
+        imported,
+        job_id,
+        archived_to,
+        ..
+    } = &handled[0].outcome
+    else {
+        panic!("expected import: {:?}", handled[0].outcome);
+    };
+    assert_eq!(account, "Chase Sapphire");
+    assert_eq!(*imported, 2);
+    let archived_to = archived_to.as_ref().unwrap();
+    assert!(archived_to.starts_with(dir.path().join("archive")));
+    assert!(archived_to.exists());
+    assert!(!dir.path().join("chase-jan.csv").exists());
+    assert!(dir.path().join("notes.txt").exists());
+
+    let session = db.get_import_session(*session_id).unwrap().unwrap();
+    assert_eq!(session.session.filename.as_deref(), Some("chase-jan.csv"));
+    assert_eq!(session.session.user_email.as_deref(), Some(WATCHER_ACTOR));
+    let job = db.get_job(job_id.unwrap()).unwrap().unwrap();
+    assert_eq!(job.job_type, JobType::ImportProcessing);
+    assert_eq!(job.entity_id, Some(*session_id));
+
+    // The same statement again: all duplicates, completed without a job,
+    // archived under a new name
+    fs::write(dir.path().join("chase-jan.csv"), CHASE_CSV).unwrap();
+    let handled = watcher.scan_once(later()).unwrap();
+    let WatchOutcome::Imported {
+        session_id,
+        imported,
+        skipped,
+        job_id,
+        archived_to,
+        ..
+    } = &handled[0].outcome
+    else {
+        panic!("expected import");
+    };
+    assert_eq!((*imported, *skipped), (0, 2));
+    assert!(job_id.is_none());
+    assert_eq!(file_name(archived_to.as_ref().unwrap()), "chase-jan-1.csv");
+    let session = db.get_import_session(*session_id).unwrap().unwrap();
+    assert_eq!(session.session.status, ImportStatus::Completed);
+}
+
+#[test]
+fn test_archive_failure_after_import_is_not_quarantined() {
+    let db = Database::in_memory().unwrap();
+    let dir = TempDir::new().unwrap();
+    let watcher = watcher(&db, &dir, "");
+    // A file where the archive folder should be makes the move fail
+    fs::write(dir.path().join("archive"), "").unwrap();
+    fs::write(dir.path().join("chase-jan.csv"), CHASE_CSV).unwrap();
+
+    let handled = watcher.scan_once(later()).unwrap();
+    let WatchOutcome::Imported {
+        imported,
+        archived_to,
+        ..
+    } = &handled[0].outcome
+    else {
+        panic!("expected import: {:?}", handled[0].outcome);
+    };
+    assert_eq!(*imported, 2);
+    assert!(archived_to.is_none());
+    assert!(dir.path().join("chase-jan.csv").exists());
+    assert!(!dir.path().join("quarantine").exists());
+}
+
+#[test]
+fn test_unrecognized_files_are_quarantined() {
+    let db = Database::in_memory().unwrap();
+    let dir = TempDir::new().unwrap();
+    let watcher = watcher(&db, &dir, "");
+    fs::write(dir.path().join("mystery.csv"), "Foo,Bar\n1,2\n").unwrap();
+
+    let handled = watcher.scan_once(later()).unwrap();
+    let WatchOutcome::Quarantined {
+        error,
+        quarantined_to,
+    } = &handled[0].outcome
+    else {
+        panic!("expected quarantine");
+    };
+    assert!(error.contains("bank format"));
+    assert!(quarantined_to.starts_with(dir.path().join("quarantine")));
+    let note = fs::read_to_string(dir.path().join("quarantine/mystery.csv.error.txt")).unwrap();
+    assert!(note.contains("bank format"));
+    assert!(db.list_accounts().unwrap().is_empty());
+}
+
+#[test]
+fn test_ofx_uses_mapped_bank_and_default_account() {
+    let db = Database::in_memory().unwrap();
+    let dir = TempDir::new().unwrap();
+    let watcher = watcher(
+        &db,
+        &dir,
+        "[[accounts]]\npattern = \"*.qfx\"\naccount = \"Credit Union\"\nbank = \"bofa\"",
+    );
+    let ofx = "OFXHEADER:100\n<OFX><BANKTRANLIST>\
+        <STMTTRN><DTPOSTED>20240201<TRNAMT>-4.50<FITID>A1<NAME>COFFEE</STMTTRN>\
+        </BANKTRANLIST></OFX>";
+    fs::write(dir.path().join("jan.qfx"), ofx).unwrap();
+    // Unmapped and undetectable
+    fs::write(dir.path().join("feb.ofx"), ofx).unwrap();
+
+    let handled = watcher.scan_once(later()).unwrap();
+    assert_eq!(handled.len(), 2);
+    assert!(matches!(
+        handled[0].outcome,
+        WatchOutcome::Quarantined { .. }
+    ));
+    let WatchOutcome::Imported { account, bank, .. } = &handled[1].outcome else {
+        panic!("expected import");
+    };
+    assert_eq!(account, "Credit Union");
+    assert_eq!(*bank, Bank::Bofa);
+}
+
+#[test]
+fn test_new_requires_existing_dir() {
+    let db = Database::in_memory().unwrap();
+    assert!(ImportWatcher::new(db.clone(), WatchConfig::default()).is_err());
+    let config = WatchConfig::default().with_dir("/definitely/not/here");
+    assert!(ImportWatcher::new(db, config).is_err());
+}
@@ -8,7 +8,7 @@ use axum::{
 };
 use serde::Deserialize;
 
-use crate::{get_user_email, AppError, AppState, SuccessResponse};
+use crate::{get_user_email, AppError, AppState, CurrentUser, SuccessResponse};
 use hone_core::models::{Account, Bank};
 
 /// Request body for creating an account
@@ -21,11 +21,12 @@ pub struct CreateAccountRequest {
 /// GET /api/accounts - List all accounts
 pub async fn list_accounts(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     request: Request,
 ) -> Result<Json<Vec<Account>>, AppError> {
     let user_email = get_user_email(request.headers());
 
-    let accounts = state.db.list_accounts()?;
+    let accounts: Vec<Account> = current_user.db(&state.db).list_accounts()?;
 
     // Audit log - read access
     state.db.log_audit(
@@ -95,13 +96,14 @@ pub struct UpdateAccountEntityRequest {
 /// GET /api/accounts/:id - Get a single account
 pub async fn get_account(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Path(id): Path<i64>,
     request: Request,
 ) -> Result<Json<Account>, AppError> {
     let user_email = get_user_email(request.headers());
 
-    let account = state
-        .db
+    let account = current_user
+        .db(&state.db)
         .get_account(id)?
         .ok_or_else(|| AppError::not_found(&format!("Account {} not found", id)))?;
 
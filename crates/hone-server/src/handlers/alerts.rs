@@ -9,7 +9,8 @@ use axum::{
 use chrono::{Datelike, Utc};
 use serde::Deserialize;
 
-use crate::{get_user_email, AppError, AppState, SuccessResponse};
+use super::saved_questions::question_creator;
+use crate::{get_user_email, AppError, AppState, CurrentUser, SuccessResponse};
 use hone_core::ai::AIBackend;
 use hone_core::models::{Alert, AlertType, DashboardStats, FeedbackTargetType};
 
@@ -23,12 +24,15 @@ pub struct AlertQuery {
 /// GET /api/alerts - List alerts
 pub async fn list_alerts(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Query(params): Query<AlertQuery>,
     request: Request,
 ) -> Result<Json<Vec<Alert>>, AppError> {
     let user_email = get_user_email(request.headers());
 
-    let alerts = state.db.list_alerts(params.include_dismissed)?;
+    let alerts = current_user
+        .db(&state.db)
+        .list_alerts(params.include_dismissed)?;
 
     // Audit log - read access
     state.db.log_audit(
@@ -47,6 +51,9 @@ pub async fn list_alerts(
 }
 
 /// POST /api/alerts/:id/dismiss - Dismiss an alert
+///
+/// Dismissing a transaction anomaly is recorded as feedback, which raises
+/// that anomaly's thresholds on later detection runs.
 pub async fn dismiss_alert(
     State(state): State<Arc<AppState>>,
     Path(id): Path<i64>,
@@ -55,6 +62,7 @@ pub async fn dismiss_alert(
     let user_email = get_user_email(request.headers());
 
     state.db.dismiss_alert(id)?;
+    state.db.record_anomaly_dismissal(id)?;
 
     // Audit log
     state
@@ -80,6 +88,7 @@ pub async fn dismiss_alert_exclude(
 
     // Dismiss the alert
     state.db.dismiss_alert(id)?;
+    state.db.record_anomaly_dismissal(id)?;
 
     // Exclude the subscription if it exists
     if let Some(subscription_id) = alert.subscription_id {
@@ -116,6 +125,7 @@ pub async fn restore_alert(
     let user_email = get_user_email(request.headers());
 
     state.db.restore_alert(id)?;
+    state.db.revert_alert_dismissals(id)?;
 
     // Audit log
     state
@@ -128,11 +138,24 @@ pub async fn restore_alert(
 /// GET /api/dashboard - Dashboard statistics
 pub async fn get_dashboard(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     request: Request,
 ) -> Result<Json<DashboardStats>, AppError> {
     let user_email = get_user_email(request.headers());
 
-    let stats = state.db.get_dashboard_stats()?;
+    let mut stats = current_user.db(&state.db).get_dashboard_stats()?;
+    // Members only see digests of the saved questions they created
+    if let Some(email) = question_creator(&current_user) {
+        let own: Vec<i64> = state
+            .db
+            .list_saved_questions(Some(email))?
+            .iter()
+            .map(|q| q.id)
+            .collect();
+        stats
+            .recent_digests
+            .retain(|d| own.contains(&d.saved_question_id));
+    }
 
     // Audit log - read access
     state
@@ -0,0 +1,114 @@
+//! API key management handlers (owner only)
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppError, AppState, CurrentUser};
+use hone_core::db::MAX_API_KEY_DAYS;
+use hone_core::models::{ApiKey, ApiKeyScope, UserRole};
+
+/// Query parameters for listing keys
+#[derive(Debug, Deserialize)]
+pub struct ListApiKeysQuery {
+    /// Include revoked keys
+    #[serde(default)]
+    pub include_revoked: bool,
+}
+
+/// Request body for creating a key
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scope: ApiKeyScope,
+    /// Expire the key after this many days (never expires when omitted, at most 3650)
+    #[serde(default)]
+    pub expires_in_days: Option<i64>,
+}
+
+/// A newly created key with its secret (only ever returned once)
+#[derive(Debug, Serialize)]
+pub struct CreatedApiKey {
+    pub key: ApiKey,
+    pub secret: String,
+}
+
+/// GET /api/keys - List API keys
+pub async fn list_api_keys(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Query(params): Query<ListApiKeysQuery>,
+) -> Result<Json<Vec<ApiKey>>, AppError> {
+    user.require(UserRole::Owner)?;
+    Ok(Json(state.db.list_api_keys(params.include_revoked)?))
+}
+
+/// POST /api/keys - Create an API key
+pub async fn create_api_key(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Json(req): Json<CreateApiKeyRequest>,
+) -> Result<Json<CreatedApiKey>, AppError> {
+    user.require(UserRole::Owner)?;
+
+    let expires_at = match req.expires_in_days {
+        Some(days) if !(1..=MAX_API_KEY_DAYS).contains(&days) => {
+            return Err(AppError::bad_request(&format!(
+                "expires_in_days must be between 1 and {}",
+                MAX_API_KEY_DAYS
+            )))
+        }
+        Some(days) => Some(Utc::now() + Duration::days(days)),
+        None => None,
+    };
+
+    let (key, secret) = state
+        .db
+        .create_api_key(&req.name, req.scope, expires_at, Some(&user.email))
+        .map_err(|e| match e {
+            hone_core::Error::InvalidData(msg) => AppError::bad_request(&msg),
+            other => other.into(),
+        })?;
+
+    state.db.log_audit(
+        &user.email,
+        "create",
+        Some("api_key"),
+        Some(key.id),
+        Some(&format!("name={}, scope={}", key.name, key.scope)),
+    )?;
+
+    Ok(Json(CreatedApiKey { key, secret }))
+}
+
+/// DELETE /api/keys/:id - Revoke an API key
+pub async fn revoke_api_key(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    user.require(UserRole::Owner)?;
+
+    let key = state
+        .db
+        .get_api_key(id)?
+        .ok_or_else(|| AppError::not_found("API key not found"))?;
+    if !state.db.revoke_api_key(id)? {
+        return Err(AppError::bad_request("API key is already revoked"));
+    }
+
+    state.db.log_audit(
+        &user.email,
+        "revoke",
+        Some("api_key"),
+        Some(id),
+        Some(&format!("name={}", key.name)),
+    )?;
+
+    Ok(Json(serde_json::json!({ "revoked": true })))
+}
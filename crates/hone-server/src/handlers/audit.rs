@@ -3,26 +3,111 @@
 use std::sync::Arc;
 
 use axum::{
+    body::Body,
     extract::{Query, Request, State},
+    http::{header, Response, StatusCode},
     Json,
 };
+use chrono::{Duration, NaiveDate, Utc};
 use serde::Deserialize;
+use tracing::{info, warn};
 
 use crate::{get_user_email, AppError, AppState, MAX_PAGE_LIMIT};
-use hone_core::AuditEntry;
+use hone_core::{AuditEntry, AuditFilter, AuditVerification};
 
 /// Query parameters for audit log
 #[derive(Debug, Deserialize)]
 pub struct AuditQuery {
     #[serde(default = "default_audit_limit")]
     pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+    /// Filter by user email
+    pub user: Option<String>,
+    /// Filter by action (e.g., "update", "login_failed")
+    pub action: Option<String>,
+    /// Filter by entity type (e.g., "transaction")
+    pub entity_type: Option<String>,
+    /// Filter by entity ID
+    pub entity_id: Option<i64>,
+    /// Start date (YYYY-MM-DD)
+    pub from: Option<String>,
+    /// End date (YYYY-MM-DD, inclusive)
+    pub to: Option<String>,
+    /// Include archived entries
+    #[serde(default)]
+    pub include_archived: bool,
 }
 
 fn default_audit_limit() -> i64 {
     100
 }
 
-/// GET /api/audit - List audit log entries
+/// Query parameters for audit log export
+#[derive(Debug, Deserialize)]
+pub struct AuditExportQuery {
+    /// Output format (default: csv)
+    #[serde(default = "default_export_format")]
+    pub format: String,
+    pub user: Option<String>,
+    pub action: Option<String>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i64>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    /// Include archived entries (default: true, exports are usually for the full record)
+    #[serde(default = "default_true")]
+    pub include_archived: bool,
+}
+
+fn default_export_format() -> String {
+    "csv".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Request body for POST /api/audit/archive
+#[derive(Debug, Deserialize)]
+pub struct ArchiveAuditRequest {
+    /// Archive entries older than this many days
+    pub older_than_days: i64,
+}
+
+fn parse_date(value: Option<&str>, name: &str) -> Result<Option<NaiveDate>, AppError> {
+    value
+        .filter(|s| !s.is_empty())
+        .map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d"))
+        .transpose()
+        .map_err(|_| {
+            AppError::bad_request(&format!("Invalid '{}' date format (use YYYY-MM-DD)", name))
+        })
+}
+
+fn build_filter(
+    user: Option<String>,
+    action: Option<String>,
+    entity_type: Option<String>,
+    entity_id: Option<i64>,
+    from: Option<&str>,
+    to: Option<&str>,
+    include_archived: bool,
+) -> Result<AuditFilter, AppError> {
+    let non_empty = |s: Option<String>| s.filter(|s| !s.trim().is_empty());
+    Ok(AuditFilter {
+        user_email: non_empty(user),
+        action: non_empty(action),
+        entity_type: non_empty(entity_type),
+        entity_id,
+        from: parse_date(from, "from")?,
+        to: parse_date(to, "to")?,
+        include_archived,
+        ..Default::default()
+    })
+}
+
+/// GET /api/audit - List audit log entries (newest first, filterable)
 pub async fn list_audit_log(
     State(state): State<Arc<AppState>>,
     Query(params): Query<AuditQuery>,
@@ -31,7 +116,20 @@ pub async fn list_audit_log(
     let user_email = get_user_email(request.headers());
     let limit = params.limit.max(1).min(MAX_PAGE_LIMIT);
 
-    let entries = state.db.list_audit_log(limit)?;
+    let filter = AuditFilter {
+        limit: Some(limit),
+        offset: params.offset.max(0),
+        ..build_filter(
+            params.user,
+            params.action,
+            params.entity_type,
+            params.entity_id,
+            params.from.as_deref(),
+            params.to.as_deref(),
+            params.include_archived,
+        )?
+    };
+    let entries = state.db.query_audit_log(&filter)?;
 
     // Audit log - viewing the audit log itself
     state.db.log_audit(
@@ -44,3 +142,112 @@ pub async fn list_audit_log(
 
     Ok(Json(entries))
 }
+
+/// GET /api/audit/export - Export audit log entries to CSV or JSON
+pub async fn export_audit_log(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Query(params): Query<AuditExportQuery>,
+) -> Result<Response<Body>, AppError> {
+    let user_email = get_user_email(&headers);
+    let filter = build_filter(
+        params.user,
+        params.action,
+        params.entity_type,
+        params.entity_id,
+        params.from.as_deref(),
+        params.to.as_deref(),
+        params.include_archived,
+    )?;
+
+    let (body, content_type, filename) = match params.format.as_str() {
+        "csv" => (
+            state.db.export_audit_log_csv(&filter)?,
+            "text/csv; charset=utf-8",
+            "audit_log.csv",
+        ),
+        "json" => {
+            let mut entries = state.db.query_audit_log(&filter)?;
+            entries.reverse();
+            let json = serde_json::to_string_pretty(&entries)
+                .map_err(|e| AppError::internal(&e.to_string()))?;
+            (json, "application/json", "audit_log.json")
+        }
+        _ => return Err(AppError::bad_request("Invalid format. Use 'csv' or 'json'")),
+    };
+
+    state.db.log_audit(
+        &user_email,
+        "export",
+        Some("audit_log"),
+        None,
+        Some(&format!("format={}", params.format)),
+    )?;
+    info!("Exported audit log as {}", params.format);
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename),
+        )
+        .body(Body::from(body))
+        .map_err(|e| AppError::internal(&e.to_string()))
+}
+
+/// GET /api/audit/verify - Verify the audit log hash chain
+pub async fn verify_audit_log(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+) -> Result<Json<AuditVerification>, AppError> {
+    let user_email = get_user_email(&headers);
+
+    // Verify before logging, so the result covers the log as it was found
+    let result = state.db.verify_audit_log()?;
+    if !result.valid {
+        warn!(error = ?result.error, "Audit log verification failed");
+    }
+
+    state.db.log_audit(
+        &user_email,
+        "verify",
+        Some("audit_log"),
+        None,
+        Some(&format!(
+            "valid={}, entries={}",
+            result.valid, result.entries_checked
+        )),
+    )?;
+
+    Ok(Json(result))
+}
+
+/// POST /api/audit/archive - Move old entries to the archive table
+pub async fn archive_audit_log(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Json(req): Json<ArchiveAuditRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user_email = get_user_email(&headers);
+    if req.older_than_days < 1 {
+        return Err(AppError::bad_request("older_than_days must be at least 1"));
+    }
+
+    let archived = state
+        .db
+        .archive_audit_log(Utc::now() - Duration::days(req.older_than_days))?;
+
+    state.db.log_audit(
+        &user_email,
+        "archive",
+        Some("audit_log"),
+        None,
+        Some(&format!(
+            "older_than_days={}, archived={}",
+            req.older_than_days, archived
+        )),
+    )?;
+
+    Ok(Json(serde_json::json!({ "archived": archived })))
+}
@@ -1,11 +1,21 @@
 //! Authentication-related handlers
 
-use axum::extract::Request;
+use std::net::SocketAddr;
+use std::sync::{Arc, OnceLock};
+
+use axum::extract::{ConnectInfo, Path, Request};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
 use axum::{extract::State, Json};
-use serde::Serialize;
-use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::{info, warn};
 
-use crate::{get_client_ip, get_user_email, AppState};
+use crate::local_auth::{self, ChallengePurpose, SESSION_COOKIE};
+use crate::webauthn::{self, b64url_decode, b64url_encode, RelyingParty};
+use crate::{get_client_ip, AppError, AppState, CurrentUser, SuccessResponse};
+use hone_core::local_auth::{hash_password, verify_password, verify_totp};
+use hone_core::models::{AuthSession, NewAuthSession, User, UserRole, WebAuthnCredential};
 
 /// Response for the /api/me endpoint
 #[derive(Serialize)]
@@ -14,61 +24,721 @@ pub struct MeResponse {
     pub user: String,
     /// How the user was authenticated
     pub auth_method: String,
+    /// Effective role for this session
+    pub role: UserRole,
+    /// Household user record (None in single-user mode)
+    pub household_user: Option<User>,
 }
 
 /// Get the currently authenticated user
 pub async fn get_me(
     State(state): State<Arc<AppState>>,
     connect_info: Option<axum::extract::ConnectInfo<std::net::SocketAddr>>,
+    current_user: CurrentUser,
     request: Request,
 ) -> Json<MeResponse> {
-    let headers = request.headers();
-    let header_user = get_user_email(headers);
-
-    // Get the real client IP (respects trusted proxies)
-    let client_ip = get_client_ip(
-        &request,
-        connect_info.as_ref(),
-        &state.config.trusted_proxies,
-    );
-
-    // Check if this is a trusted network request (no CF headers, but auth passed)
-    let is_trusted_network = header_user == "local-dev"
-        && state.config.require_auth
-        && !state.config.trusted_networks.is_empty()
-        && client_ip
-            .map(|ip| {
-                state
-                    .config
-                    .trusted_networks
-                    .iter()
-                    .any(|net| net.contains(&ip))
-            })
-            .unwrap_or(false);
-
-    let (user, auth_method) = if is_trusted_network {
-        // Format IP as the user identifier
-        let ip = client_ip.map(|ip| ip.to_string()).unwrap_or_default();
-        (ip, "trusted_network")
-    } else if header_user == "api-key" {
-        (header_user, "api_key")
-    } else if header_user == "local-dev" {
-        (header_user, "none")
-    } else if header_user.contains('@') {
-        // Check if we have JWT validation configured
-        let method =
-            if state.config.cf_jwt.team_name.is_some() && state.config.cf_jwt.audience.is_some() {
-                "cloudflare_jwt"
-            } else {
-                "cloudflare_header"
-            };
-        (header_user, method)
+    let user = if current_user.auth_method == "trusted_network" {
+        // Format the real client IP (respects trusted proxies) as the user identifier
+        get_client_ip(
+            &request,
+            connect_info.as_ref(),
+            &state.config.trusted_proxies,
+        )
+        .map(|ip| ip.to_string())
+        .unwrap_or_default()
     } else {
-        (header_user, "unknown")
+        current_user.email.clone()
     };
 
     Json(MeResponse {
         user,
+        auth_method: current_user.auth_method.to_string(),
+        role: current_user.role,
+        household_user: current_user.user,
+    })
+}
+
+// ========== Built-in authentication ==========
+
+/// Response for GET /api/auth/status (public)
+#[derive(Serialize)]
+pub struct AuthStatusResponse {
+    /// Password logins and session cookies are accepted
+    pub local_auth_enabled: bool,
+    /// Passkey login is configured
+    pub passkeys_enabled: bool,
+}
+
+/// Request body for POST /api/auth/login
+#[derive(Deserialize)]
+pub struct LoginRequest {
+    pub email: String,
+    pub password: String,
+    #[serde(default)]
+    pub totp_code: Option<String>,
+}
+
+/// Response for a successful login
+#[derive(Serialize)]
+pub struct LoginResponse {
+    pub user: User,
+    /// Send back in the `X-CSRF-Token` header on state-changing requests
+    pub csrf_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Request body for POST /api/auth/password
+#[derive(Deserialize)]
+pub struct ChangePasswordRequest {
+    #[serde(default)]
+    pub current_password: Option<String>,
+    pub new_password: String,
+}
+
+/// Request body for TOTP enable/disable
+#[derive(Deserialize)]
+pub struct TotpCodeRequest {
+    pub code: String,
+}
+
+/// Response for POST /api/auth/totp/setup
+#[derive(Serialize)]
+pub struct TotpSetupResponse {
+    /// Base32 secret for manual entry
+    pub secret: String,
+    /// `otpauth://` URI for QR codes
+    pub otpauth_uri: String,
+}
+
+/// Request body for POST /api/auth/passkeys/register/finish (binary fields base64url)
+#[derive(Deserialize)]
+pub struct PasskeyRegisterRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub client_data_json: String,
+    pub attestation_object: String,
+}
+
+/// Request body for POST /api/auth/passkeys/login/finish (binary fields base64url)
+#[derive(Deserialize)]
+pub struct PasskeyLoginRequest {
+    pub credential_id: String,
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+}
+
+/// Hash checked when the email is unknown, so response time doesn't reveal which emails exist
+fn dummy_password_hash() -> &'static str {
+    static HASH: OnceLock<String> = OnceLock::new();
+    HASH.get_or_init(|| hash_password("hone-timing-equalizer").unwrap_or_default())
+}
+
+/// Check a password off the async executor (Argon2 is deliberately slow)
+///
+/// With no stored hash, the dummy hash is checked instead and the result is
+/// always false.
+async fn verify_password_blocking(password: &str, hash: Option<String>) -> Result<bool, AppError> {
+    let password = password.to_string();
+    tokio::task::spawn_blocking(move || match hash {
+        Some(hash) => verify_password(&password, &hash),
+        None => {
+            verify_password(&password, dummy_password_hash());
+            false
+        }
+    })
+    .await
+    .map_err(|e| AppError::internal(&format!("Password check failed: {}", e)))
+}
+
+fn require_local_auth(state: &AppState) -> Result<(), AppError> {
+    if state.config.local_auth.enabled {
+        Ok(())
+    } else {
+        Err(AppError::not_found(
+            "Built-in authentication is not enabled",
+        ))
+    }
+}
+
+fn require_passkeys(state: &AppState) -> Result<&RelyingParty, AppError> {
+    require_local_auth(state)?;
+    state
+        .config
+        .local_auth
+        .relying_party
+        .as_ref()
+        .ok_or_else(|| {
+            AppError::not_found("Passkeys are not configured (set HONE_WEBAUTHN_ORIGIN)")
+        })
+}
+
+/// Built-in credentials belong to household user records
+fn require_household_user(current_user: &CurrentUser) -> Result<&User, AppError> {
+    current_user.user.as_ref().ok_or_else(|| {
+        AppError::bad_request(
+            "Built-in credentials require a household user (see `hone users add`)",
+        )
+    })
+}
+
+async fn read_json<T: DeserializeOwned>(request: Request) -> Result<T, AppError> {
+    let bytes = axum::body::to_bytes(request.into_body(), 1024 * 64)
+        .await
+        .map_err(|_| AppError::bad_request("Invalid request body"))?;
+    serde_json::from_slice(&bytes).map_err(|_| AppError::bad_request("Invalid JSON"))
+}
+
+fn decode_field(value: &str, field: &str) -> Result<Vec<u8>, AppError> {
+    b64url_decode(value).map_err(|_| AppError::bad_request(&format!("{} is not base64url", field)))
+}
+
+fn too_many_attempts(retry_after: u64) -> Response {
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, retry_after.to_string())],
+        Json(serde_json::json!({
+            "error": "Too many failed login attempts, try again later",
+            "retry_after": retry_after,
+        })),
+    )
+        .into_response()
+}
+
+/// Client IP and User-Agent for rate limiting and the session record
+fn client_details(
+    state: &AppState,
+    request: &Request,
+    connect_info: Option<&ConnectInfo<SocketAddr>>,
+) -> (Option<String>, Option<String>) {
+    let ip = get_client_ip(request, connect_info, &state.config.trusted_proxies)
+        .map(|ip| ip.to_string());
+    let user_agent = request
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|ua| ua.chars().take(256).collect());
+    (ip, user_agent)
+}
+
+/// Create a session and respond with its cookies
+fn start_session(
+    state: &AppState,
+    user: User,
+    auth_method: &str,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+) -> Result<Response, AppError> {
+    let config = &state.config.local_auth;
+    let token = hone_core::local_auth::random_token(32)?;
+    let csrf_token = hone_core::local_auth::random_token(32)?;
+    let expires_at = Utc::now() + Duration::hours(config.session_ttl_hours);
+
+    state.db.create_auth_session(&NewAuthSession {
+        token: token.clone(),
+        user_id: user.id,
+        csrf_token: csrf_token.clone(),
         auth_method: auth_method.to_string(),
+        ip_address: ip_address.clone(),
+        user_agent,
+        expires_at,
+    })?;
+    if let Err(e) = state.db.prune_expired_auth_sessions() {
+        warn!(error = %e, "Failed to prune expired sessions");
+    }
+
+    state.db.log_audit(
+        &user.email,
+        "login",
+        Some("user"),
+        Some(user.id),
+        Some(&format!(
+            "method={}, ip={}",
+            auth_method,
+            ip_address.as_deref().unwrap_or("unknown")
+        )),
+    )?;
+    info!(user = %user.email, method = auth_method, "Started session");
+
+    let [session_cookie, csrf_cookie] = local_auth::session_cookies(config, &token, &csrf_token);
+    let mut response = Json(LoginResponse {
+        user,
+        csrf_token,
+        expires_at,
     })
+    .into_response();
+    response
+        .headers_mut()
+        .append(header::SET_COOKIE, session_cookie);
+    response
+        .headers_mut()
+        .append(header::SET_COOKIE, csrf_cookie);
+    Ok(response)
+}
+
+/// GET /api/auth/status - Which built-in login methods are available (public)
+pub async fn get_auth_status(State(state): State<Arc<AppState>>) -> Json<AuthStatusResponse> {
+    let config = &state.config.local_auth;
+    Json(AuthStatusResponse {
+        local_auth_enabled: config.enabled,
+        passkeys_enabled: config.enabled && config.relying_party.is_some(),
+    })
+}
+
+/// POST /api/auth/login - Password (and TOTP) login (public, rate limited)
+pub async fn login(
+    State(state): State<Arc<AppState>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    request: Request,
+) -> Result<Response, AppError> {
+    require_local_auth(&state)?;
+    let (ip, user_agent) = client_details(&state, &request, connect_info.as_ref());
+    let req: LoginRequest = read_json(request).await?;
+
+    let email = req.email.trim().to_lowercase();
+    let ip_key = format!("ip:{}", ip.as_deref().unwrap_or("unknown"));
+    let email_key = format!("email:{}", email);
+    for key in [&ip_key, &email_key] {
+        if let Some(retry_after) = state.local_auth.retry_after(key) {
+            warn!(key = %key, "Login rate limited");
+            return Ok(too_many_attempts(retry_after));
+        }
+    }
+
+    let user = state.db.get_user_by_email(&email)?;
+    let password_hash = match &user {
+        Some(user) => state.db.get_user_password_hash(user.id)?,
+        None => None,
+    };
+    let password_ok = verify_password_blocking(&req.password, password_hash).await?;
+
+    let fail = |reason: &str| -> Result<Response, AppError> {
+        state.local_auth.record_failure(&ip_key);
+        state.local_auth.record_failure(&email_key);
+        state.db.log_audit(
+            &email,
+            "login_failed",
+            Some("user"),
+            user.as_ref().map(|u| u.id),
+            Some(&format!(
+                "reason={}, ip={}",
+                reason,
+                ip.as_deref().unwrap_or("unknown")
+            )),
+        )?;
+        Err(AppError::unauthorized("Invalid email, password or code"))
+    };
+
+    let Some(user) = user.clone().filter(|_| password_ok) else {
+        return fail("password");
+    };
+
+    if let Some((secret, true)) = state.db.get_user_totp(user.id)? {
+        let Some(code) = req.totp_code.as_deref().filter(|c| !c.trim().is_empty()) else {
+            // Not a failure: the client asks for the code and retries
+            return Ok((
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({
+                    "error": "TOTP code required",
+                    "totp_required": true,
+                })),
+            )
+                .into_response());
+        };
+        let Some(step) = verify_totp(&secret, code, Utc::now().timestamp()) else {
+            return fail("totp");
+        };
+        // A code is only good once, even within its step and skew window
+        if !state.db.accept_totp_step(user.id, step)? {
+            return fail("totp_replay");
+        }
+        state.local_auth.clear_failures(&email_key);
+        return start_session(&state, user, "password+totp", ip, user_agent);
+    }
+
+    state.local_auth.clear_failures(&email_key);
+    start_session(&state, user, "password", ip, user_agent)
+}
+
+/// POST /api/auth/logout - End the current session
+pub async fn logout(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    if let Some(token) = local_auth::get_cookie(&headers, SESSION_COOKIE) {
+        if state.db.delete_auth_session(&token)? {
+            state.db.log_audit(
+                &current_user.email,
+                "logout",
+                Some("user"),
+                current_user.user.as_ref().map(|u| u.id),
+                None,
+            )?;
+        }
+    }
+
+    let mut response = Json(SuccessResponse { success: true }).into_response();
+    for cookie in local_auth::clear_session_cookies(&state.config.local_auth) {
+        response.headers_mut().append(header::SET_COOKIE, cookie);
+    }
+    Ok(response)
+}
+
+/// POST /api/auth/password - Set or change your password (ends your other sessions)
+pub async fn change_password(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    headers: HeaderMap,
+    Json(req): Json<ChangePasswordRequest>,
+) -> Result<Json<SuccessResponse>, AppError> {
+    require_local_auth(&state)?;
+    let user = require_household_user(&current_user)?;
+
+    if let Some(existing) = state.db.get_user_password_hash(user.id)? {
+        let current = req.current_password.as_deref().unwrap_or_default();
+        if !verify_password_blocking(current, Some(existing)).await? {
+            return Err(AppError::forbidden("Current password is incorrect"));
+        }
+    }
+
+    let hash = hash_password(&req.new_password).map_err(|e| match e {
+        hone_core::Error::InvalidData(msg) => AppError::bad_request(&msg),
+        other => other.into(),
+    })?;
+    state.db.set_user_password_hash(user.id, Some(&hash))?;
+
+    let current_token = local_auth::get_cookie(&headers, SESSION_COOKIE);
+    let ended = state
+        .db
+        .delete_user_auth_sessions(user.id, current_token.as_deref())?;
+
+    state.db.log_audit(
+        &user.email,
+        "password_change",
+        Some("user"),
+        Some(user.id),
+        Some(&format!("other_sessions_ended={}", ended)),
+    )?;
+
+    Ok(Json(SuccessResponse { success: true }))
+}
+
+/// POST /api/auth/totp/setup - Generate a TOTP secret (confirm with /totp/enable)
+pub async fn setup_totp(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+) -> Result<Json<TotpSetupResponse>, AppError> {
+    require_local_auth(&state)?;
+    let user = require_household_user(&current_user)?;
+
+    if let Some((_, true)) = state.db.get_user_totp(user.id)? {
+        return Err(AppError::conflict("TOTP is already enabled"));
+    }
+
+    let secret = hone_core::local_auth::generate_totp_secret()?;
+    state.db.set_user_totp(user.id, Some(&secret), false)?;
+
+    Ok(Json(TotpSetupResponse {
+        otpauth_uri: hone_core::local_auth::totp_uri(&secret, &user.email),
+        secret,
+    }))
+}
+
+/// POST /api/auth/totp/enable - Confirm the pending secret with a code
+pub async fn enable_totp(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Json(req): Json<TotpCodeRequest>,
+) -> Result<Json<SuccessResponse>, AppError> {
+    require_local_auth(&state)?;
+    let user = require_household_user(&current_user)?;
+
+    let (secret, _) = state
+        .db
+        .get_user_totp(user.id)?
+        .ok_or_else(|| AppError::bad_request("Run TOTP setup first"))?;
+    let Some(step) = verify_totp(&secret, &req.code, Utc::now().timestamp()) else {
+        return Err(AppError::bad_request("Invalid TOTP code"));
+    };
+    state.db.set_user_totp(user.id, Some(&secret), true)?;
+    // The confirmation code can't be reused to log in
+    state.db.accept_totp_step(user.id, step)?;
+
+    state.db.log_audit(
+        &user.email,
+        "totp_enable",
+        Some("user"),
+        Some(user.id),
+        None,
+    )?;
+
+    Ok(Json(SuccessResponse { success: true }))
+}
+
+/// POST /api/auth/totp/disable - Turn off TOTP (requires a current code)
+pub async fn disable_totp(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Json(req): Json<TotpCodeRequest>,
+) -> Result<Json<SuccessResponse>, AppError> {
+    require_local_auth(&state)?;
+    let user = require_household_user(&current_user)?;
+
+    let Some((secret, true)) = state.db.get_user_totp(user.id)? else {
+        return Err(AppError::bad_request("TOTP is not enabled"));
+    };
+    // A code already used (e.g. to log in) can't be replayed to turn TOTP off
+    let Some(step) = verify_totp(&secret, &req.code, Utc::now().timestamp()) else {
+        return Err(AppError::bad_request("Invalid TOTP code"));
+    };
+    if !state.db.accept_totp_step(user.id, step)? {
+        return Err(AppError::bad_request("Invalid TOTP code"));
+    }
+    state.db.set_user_totp(user.id, None, false)?;
+
+    state.db.log_audit(
+        &user.email,
+        "totp_disable",
+        Some("user"),
+        Some(user.id),
+        None,
+    )?;
+
+    Ok(Json(SuccessResponse { success: true }))
+}
+
+/// GET /api/auth/sessions - Your active sessions
+pub async fn list_sessions(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+) -> Result<Json<Vec<AuthSession>>, AppError> {
+    let user = require_household_user(&current_user)?;
+    Ok(Json(state.db.list_auth_sessions(user.id)?))
+}
+
+/// DELETE /api/auth/sessions/:id - End one of your sessions
+pub async fn revoke_session(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Json<SuccessResponse>, AppError> {
+    let user = require_household_user(&current_user)?;
+    if !state.db.delete_user_auth_session(user.id, id)? {
+        return Err(AppError::not_found("Session not found"));
+    }
+    state.db.log_audit(
+        &user.email,
+        "session_revoke",
+        Some("user"),
+        Some(user.id),
+        Some(&format!("session_id={}", id)),
+    )?;
+    Ok(Json(SuccessResponse { success: true }))
+}
+
+/// GET /api/auth/passkeys - Your registered passkeys
+pub async fn list_passkeys(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+) -> Result<Json<Vec<WebAuthnCredential>>, AppError> {
+    let user = require_household_user(&current_user)?;
+    Ok(Json(state.db.list_webauthn_credentials(user.id)?))
+}
+
+/// POST /api/auth/passkeys/register/start - Options for `navigator.credentials.create`
+pub async fn start_passkey_registration(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let rp = require_passkeys(&state)?;
+    let user = require_household_user(&current_user)?;
+
+    let challenge = state
+        .local_auth
+        .issue_challenge(ChallengePurpose::Register { user_id: user.id })?;
+    let exclude: Vec<_> = state
+        .db
+        .list_webauthn_credentials(user.id)?
+        .into_iter()
+        .map(|c| serde_json::json!({ "type": "public-key", "id": c.credential_id }))
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "challenge": challenge,
+        "rp": { "id": rp.id, "name": "Hone" },
+        "user": {
+            "id": b64url_encode(&user.id.to_be_bytes()),
+            "name": user.email,
+            "displayName": user.display_name.as_deref().unwrap_or(&user.email),
+        },
+        "pubKeyCredParams": [{ "type": "public-key", "alg": webauthn::COSE_ALG_ES256 }],
+        "timeout": 300000,
+        "attestation": "none",
+        "authenticatorSelection": {
+            "residentKey": "preferred",
+            "userVerification": "required",
+        },
+        "excludeCredentials": exclude,
+    })))
+}
+
+/// POST /api/auth/passkeys/register/finish - Verify and store a new passkey
+pub async fn finish_passkey_registration(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Json(req): Json<PasskeyRegisterRequest>,
+) -> Result<Json<WebAuthnCredential>, AppError> {
+    let rp = require_passkeys(&state)?;
+    let user = require_household_user(&current_user)?;
+
+    let client_data = decode_field(&req.client_data_json, "client_data_json")?;
+    let attestation = decode_field(&req.attestation_object, "attestation_object")?;
+
+    let challenge = webauthn::client_data_challenge(&client_data)
+        .ok_or_else(|| AppError::bad_request("Invalid client data"))?;
+    match state.local_auth.take_challenge(&challenge) {
+        Some(ChallengePurpose::Register { user_id }) if user_id == user.id => {}
+        _ => return Err(AppError::bad_request("Unknown or expired challenge")),
+    }
+
+    let credential = webauthn::verify_registration(rp, &challenge, &client_data, &attestation)
+        .map_err(|e| AppError::bad_request(&format!("Passkey registration failed: {}", e)))?;
+    if state
+        .db
+        .get_webauthn_credential(&credential.credential_id)?
+        .is_some()
+    {
+        return Err(AppError::conflict("Passkey is already registered"));
+    }
+
+    let name = req
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .unwrap_or("Passkey");
+    let id = state.db.add_webauthn_credential(
+        user.id,
+        &credential.credential_id,
+        &credential.public_key,
+        credential.sign_count,
+        name,
+    )?;
+
+    state.db.log_audit(
+        &user.email,
+        "passkey_register",
+        Some("user"),
+        Some(user.id),
+        Some(&format!("passkey_id={}, name={}", id, name)),
+    )?;
+
+    let stored = state
+        .db
+        .list_webauthn_credentials(user.id)?
+        .into_iter()
+        .find(|c| c.id == id)
+        .ok_or_else(|| AppError::internal("Passkey not found after registration"))?;
+    Ok(Json(stored))
+}
+
+/// DELETE /api/auth/passkeys/:id - Remove one of your passkeys
+pub async fn delete_passkey(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Json<SuccessResponse>, AppError> {
+    let user = require_household_user(&current_user)?;
+    if !state.db.delete_webauthn_credential(user.id, id)? {
+        return Err(AppError::not_found("Passkey not found"));
+    }
+    state.db.log_audit(
+        &user.email,
+        "passkey_delete",
+        Some("user"),
+        Some(user.id),
+        Some(&format!("passkey_id={}", id)),
+    )?;
+    Ok(Json(SuccessResponse { success: true }))
+}
+
+/// POST /api/auth/passkeys/login/start - Options for `navigator.credentials.get` (public)
+pub async fn start_passkey_login(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let rp = require_passkeys(&state)?;
+    let challenge = state.local_auth.issue_challenge(ChallengePurpose::Login)?;
+
+    // Discoverable credentials: the authenticator offers the user's passkeys
+    Ok(Json(serde_json::json!({
+        "challenge": challenge,
+        "rpId": rp.id,
+        "timeout": 300000,
+        "userVerification": "required",
+    })))
+}
+
+/// POST /api/auth/passkeys/login/finish - Verify an assertion and start a session (public)
+pub async fn finish_passkey_login(
+    State(state): State<Arc<AppState>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    request: Request,
+) -> Result<Response, AppError> {
+    let rp = require_passkeys(&state)?.clone();
+    let (ip, user_agent) = client_details(&state, &request, connect_info.as_ref());
+    let req: PasskeyLoginRequest = read_json(request).await?;
+
+    let ip_key = format!("ip:{}", ip.as_deref().unwrap_or("unknown"));
+    if let Some(retry_after) = state.local_auth.retry_after(&ip_key) {
+        return Ok(too_many_attempts(retry_after));
+    }
+
+    let client_data = decode_field(&req.client_data_json, "client_data_json")?;
+    let authenticator_data = decode_field(&req.authenticator_data, "authenticator_data")?;
+    let signature = decode_field(&req.signature, "signature")?;
+
+    let challenge = webauthn::client_data_challenge(&client_data)
+        .ok_or_else(|| AppError::bad_request("Invalid client data"))?;
+    if state.local_auth.take_challenge(&challenge) != Some(ChallengePurpose::Login) {
+        return Err(AppError::bad_request("Unknown or expired challenge"));
+    }
+
+    let credential = state
+        .db
+        .get_webauthn_credential(req.credential_id.trim_end_matches('='))?;
+    let verified = credential.as_ref().map(|c| {
+        webauthn::verify_assertion(
+            &rp,
+            &challenge,
+            &client_data,
+            &authenticator_data,
+            &signature,
+            &c.public_key,
+            c.sign_count,
+        )
+    });
+
+    let (credential, sign_count) = match (credential, verified) {
+        (Some(c), Some(Ok(count))) => (c, count),
+        (_, verified) => {
+            let reason = match verified {
+                Some(Err(e)) => e,
+                _ => "unknown credential".to_string(),
+            };
+            warn!(reason = %reason, "Passkey login failed");
+            state.local_auth.record_failure(&ip_key);
+            return Err(AppError::unauthorized("Passkey login failed"));
+        }
+    };
+
+    state
+        .db
+        .update_webauthn_sign_count(credential.id, sign_count)?;
+    let user = state
+        .db
+        .get_user(credential.user_id)?
+        .ok_or_else(|| AppError::unauthorized("Passkey login failed"))?;
+
+    start_session(&state, user, "passkey", ip, user_agent)
 }
@@ -11,14 +11,15 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error, info, warn};
 
-use crate::{get_user_email, AppError, AppState, MAX_UPLOAD_SIZE};
+use crate::{get_user_email, AppError, AppState, CurrentUser, MAX_UPLOAD_SIZE};
 use hone_core::{
     ai::{AIBackend, AIClient, MerchantContext},
     db::{Database, TransactionInsertResult},
     detect::WasteDetector,
-    import::{detect_bank_format, parse_csv},
+    import::{detect_bank_format, import_request_hash, parse_csv, parse_import_row},
     models::{
-        ImportTaggingBreakdown, NewImportSession, NewOllamaMetric, OllamaOperation, Transaction,
+        ImportRow, ImportRowResult, ImportRowStatus, ImportTaggingBreakdown, Job, JobType,
+        NewImportSession, NewJob, NewOllamaMetric, OllamaOperation, Transaction,
     },
     tags::TagAssigner,
 };
@@ -34,22 +35,14 @@ fn default_kind() -> String {
     "all".to_string()
 }
 
-/// Detection response
-#[derive(Serialize)]
-pub struct DetectResponse {
-    pub subscriptions_found: usize,
-    pub zombies_detected: usize,
-    pub price_increases_detected: usize,
-    pub duplicates_detected: usize,
-    pub spending_anomalies_detected: usize,
-    pub tip_discrepancies_detected: usize,
-}
-
-/// POST /api/detect - Run waste detection
+/// POST /api/detect - Queue waste detection
+///
+/// Returns the queued job. Detection runs on the AI lane, so it waits for
+/// any other AI-heavy job to finish; its counts are in the job's result.
 pub async fn run_detection(
     State(state): State<Arc<AppState>>,
     request: Request,
-) -> Result<Json<DetectResponse>, AppError> {
+) -> Result<Json<Job>, AppError> {
     let user_email = get_user_email(request.headers());
 
     // Extract JSON body
@@ -59,50 +52,31 @@ pub async fn run_detection(
     let params: DetectRequest = serde_json::from_slice(&bytes).unwrap_or(DetectRequest {
         kind: default_kind(),
     });
-
-    // Build detector with best available AI capabilities
-    let detector = match (&state.orchestrator, &state.ai) {
-        (Some(ref orch), Some(ref ai)) => {
-            WasteDetector::with_ai_and_orchestrator(&state.db, ai, orch)
-        }
-        (Some(ref orch), None) => WasteDetector::with_orchestrator(&state.db, orch),
-        (None, Some(ref ai)) => WasteDetector::with_ai(&state.db, ai),
-        (None, None) => WasteDetector::new(&state.db),
+    // Unknown kinds run every detector
+    let kind = match params.kind.as_str() {
+        "zombies" | "increases" | "duplicates" => params.kind.as_str(),
+        _ => "all",
     };
 
-    let results = match params.kind.as_str() {
-        "zombies" => detector.detect_zombies_only().await?,
-        "increases" => detector.detect_increases_only().await?,
-        "duplicates" => detector.detect_duplicates_only().await?,
-        _ => detector.detect_all().await?,
-    };
+    let id = state.db.create_job(
+        &NewJob::new(JobType::Detection, serde_json::json!({ "kind": kind }))
+            .created_by(&user_email),
+    )?;
 
     // Audit log
     state.db.log_audit(
         &user_email,
         "detect",
-        None,
-        None,
-        Some(&format!(
-            "kind={}, subscriptions={}, zombies={}, increases={}, duplicates={}, anomalies={}, tips={}",
-            params.kind,
-            results.subscriptions_found,
-            results.zombies_detected,
-            results.price_increases_detected,
-            results.duplicates_detected,
-            results.spending_anomalies_detected,
-            results.tip_discrepancies_detected
-        )),
+        Some("job"),
+        Some(id),
+        Some(&format!("kind={}", kind)),
     )?;
 
-    Ok(Json(DetectResponse {
-        subscriptions_found: results.subscriptions_found,
-        zombies_detected: results.zombies_detected,
-        price_increases_detected: results.price_increases_detected,
-        duplicates_detected: results.duplicates_detected,
-        spending_anomalies_detected: results.spending_anomalies_detected,
-        tip_discrepancies_detected: results.tip_discrepancies_detected,
-    }))
+    let job = state
+        .db
+        .get_job(id)?
+        .ok_or_else(|| AppError::internal("Failed to retrieve queued job"))?;
+    Ok(Json(job))
 }
 
 /// Response for import endpoint
@@ -137,6 +111,7 @@ pub struct ImportResponse {
 /// - model: AI model to use (optional, uses server default if not specified)
 pub async fn import_csv(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     headers: axum::http::HeaderMap,
     mut multipart: Multipart,
 ) -> Result<Json<ImportResponse>, AppError> {
@@ -199,6 +174,7 @@ pub async fn import_csv(
     // Delegate to core import logic
     import_csv_core(
         &state,
+        &current_user,
         &headers,
         file_data,
         account_id,
@@ -220,6 +196,7 @@ pub async fn import_csv(
 /// * `model_override` - Optional model name to use instead of server default
 pub async fn import_csv_core(
     state: &AppState,
+    current_user: &CurrentUser,
     headers: &HeaderMap,
     file_data: Vec<u8>,
     account_id: i64,
@@ -232,11 +209,10 @@ pub async fn import_csv_core(
         Some(user_email.clone())
     };
 
-    // Get the account to determine bank format
-    let accounts = state.db.list_accounts()?;
-    let account = accounts
-        .iter()
-        .find(|a| a.id == account_id)
+    // Get the account to determine bank format (hidden accounts are not found)
+    let account = current_user
+        .db(&state.db)
+        .get_account(account_id)?
         .ok_or_else(|| AppError::not_found("Account not found"))?;
 
     let bank = account.bank;
@@ -275,52 +251,11 @@ pub async fn import_csv_core(
         user_email: user_email_opt.clone(),
         ollama_model: effective_model.clone(),
     };
-    let import_session_id = state.db.create_import_session(&new_session)?;
-    info!("Created import session {}", import_session_id);
 
     // Import transactions with session tracking (synchronous phase)
-    let mut imported = 0;
-    let mut skipped = 0;
-
-    for tx in &transactions {
-        match state
-            .db
-            .insert_transaction_with_session(account_id, tx, import_session_id)?
-        {
-            TransactionInsertResult::Inserted(_) => imported += 1,
-            TransactionInsertResult::Duplicate(existing_id) => {
-                skipped += 1;
-                // Record the skipped transaction for history
-                if let Err(e) = state.db.record_skipped_transaction(
-                    import_session_id,
-                    tx.date,
-                    &tx.description,
-                    tx.amount,
-                    &tx.import_hash,
-                    Some(existing_id),
-                ) {
-                    warn!("Failed to record skipped transaction: {}", e);
-                }
-            }
-        }
-    }
-
-    // Update session with initial import counts
-    if let Err(e) = state.db.update_import_session_results(
-        import_session_id,
-        imported as i64,
-        skipped as i64,
-        &ImportTaggingBreakdown::default(),
-        0,
-        0,
-        0,
-        0,
-        0,
-        0,
-        0,
-    ) {
-        warn!("Failed to update import session results: {}", e);
-    }
+    let (import_session_id, imported, skipped) =
+        state.db.import_into_session(&new_session, &transactions)?;
+    info!("Created import session {}", import_session_id);
 
     // Audit log for the sync phase
     state.db.log_audit(
@@ -338,45 +273,16 @@ pub async fn import_csv_core(
         )),
     )?;
 
-    // Spawn background task for AI processing if we imported transactions
-    if imported > 0 {
-        let db = state.db.clone();
-        let ollama = effective_ai;
-        let imported_count = imported as i64;
-        let model_override_owned = model_override.map(String::from);
-
-        tokio::spawn(async move {
-            // Create orchestrator inside task with cloned db (AIOrchestrator contains Database)
-            // Apply model override if specified
-            let orchestrator = match model_override_owned.as_deref() {
-                Some(model) => hone_core::ai::orchestrator::AIOrchestrator::from_env(db.clone())
-                    .map(|o| o.with_model(model)),
-                None => hone_core::ai::orchestrator::AIOrchestrator::from_env(db.clone()),
-            };
-
-            if let Err(e) = run_async_import_processing(
-                &db,
-                ollama.as_ref(),
-                orchestrator.as_ref(),
-                import_session_id,
-                imported_count,
-            )
-            .await
-            {
-                error!(
-                    "Background import processing failed for session {}: {}",
-                    import_session_id, e
-                );
-                if let Err(e2) = db.mark_import_failed(import_session_id, &e.to_string()) {
-                    error!("Failed to mark import as failed: {}", e2);
-                }
-            }
-        });
-    } else {
-        // No transactions imported, mark as completed immediately
-        if let Err(e) = state.db.mark_import_completed(import_session_id) {
-            warn!("Failed to mark import as completed: {}", e);
-        }
+    // Queue AI processing as a background job (completes the session if nothing was imported)
+    if let Err(e) =
+        state
+            .db
+            .queue_import_processing(import_session_id, imported, model_override, &user_email)
+    {
+        error!(
+            "Failed to queue import processing for session {}: {}",
+            import_session_id, e
+        );
     }
 
     // Return immediately with import counts (AI processing runs in background)
@@ -401,8 +307,8 @@ pub async fn import_csv_core(
 
 /// Run the async import processing (tagging, normalization, detection)
 ///
-/// This runs in a background task after the initial import returns.
-async fn run_async_import_processing(
+/// This runs as an `import_processing` background job after the initial import returns.
+pub(crate) async fn run_async_import_processing(
     db: &Database,
     ollama: Option<&AIClient>,
     orchestrator: Option<&hone_core::ai::orchestrator::AIOrchestrator>,
@@ -500,6 +406,15 @@ async fn run_async_import_processing(
             0
         }
     };
+
+    // Link refunds in this import to their original purchases
+    match db.auto_match_refunds() {
+        Ok((matched, _)) if matched > 0 => {
+            info!("Linked {} refunds to their original purchases", matched)
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to match refunds: {}", e),
+    }
     let matching_duration_ms = matching_start.elapsed().as_millis() as i64;
     if let Err(e) =
         db.update_import_phase_duration(session_id, "matching_receipts", matching_duration_ms)
@@ -601,6 +516,7 @@ pub struct ImportCsvJsonRequest {
 /// POST /api/import/json - Import transactions from CSV via JSON (for testing)
 pub async fn import_csv_json(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     headers: HeaderMap,
     Json(req): Json<ImportCsvJsonRequest>,
 ) -> Result<Json<ImportResponse>, AppError> {
@@ -622,6 +538,7 @@ pub async fn import_csv_json(
     // Delegate to core import logic with model override
     import_csv_core(
         &state,
+        &current_user,
         &headers,
         file_data,
         req.account_id,
@@ -630,6 +547,235 @@ pub async fn import_csv_json(
     .await
 }
 
+/// Maximum rows per JSON transaction import
+pub const MAX_IMPORT_ROWS: usize = 5000;
+
+/// Longest accepted idempotency key
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+/// Transactions pushed by scripts and automation tools
+#[derive(Debug, Deserialize)]
+pub struct ImportTransactionsRequest {
+    pub account_id: i64,
+    pub transactions: Vec<ImportRow>,
+    /// Retry-safe key (the `Idempotency-Key` header takes precedence)
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// Optional model override (uses server default if not specified)
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// Response for JSON transaction imports
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportTransactionsResponse {
+    pub import_session_id: i64,
+    pub account_id: i64,
+    pub account_name: String,
+    pub imported: usize,
+    pub skipped: usize,
+    pub invalid: usize,
+    /// Background tagging/detection job (None when nothing new was imported)
+    pub job_id: Option<i64>,
+    pub rows: Vec<ImportRowResult>,
+    /// True when this is a stored response for a repeated idempotency key
+    #[serde(default)]
+    pub replayed: bool,
+}
+
+/// POST /api/import/transactions - Import a JSON array of transactions
+///
+/// Machine-facing counterpart of `/api/import`: each row is validated on its
+/// own and reported as imported, duplicate or invalid. Imported rows go
+/// through the same background tagging, normalization and detection as file
+/// imports (follow `job_id` or the import session).
+///
+/// With an `Idempotency-Key` header (or `idempotency_key` field), a retried
+/// request returns the first response instead of importing again; reusing a
+/// key for a different request is a conflict.
+pub async fn import_transactions(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    headers: HeaderMap,
+    Json(req): Json<ImportTransactionsRequest>,
+) -> Result<Json<ImportTransactionsResponse>, AppError> {
+    let user_email = get_user_email(&headers);
+
+    if req.transactions.is_empty() {
+        return Err(AppError::bad_request("No transactions to import"));
+    }
+    if req.transactions.len() > MAX_IMPORT_ROWS {
+        return Err(AppError::bad_request(&format!(
+            "Too many transactions. Maximum is {} per request",
+            MAX_IMPORT_ROWS
+        )));
+    }
+    let account = current_user
+        .db(&state.db)
+        .get_account(req.account_id)?
+        .ok_or_else(|| AppError::not_found("Account not found"))?;
+
+    let idempotency_key = headers
+        .get("idempotency-key")
+        .and_then(|v| v.to_str().ok())
+        .map(String::from)
+        .or(req.idempotency_key.clone())
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty());
+    if let Some(key) = &idempotency_key {
+        if key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+            return Err(AppError::bad_request(&format!(
+                "Idempotency key is too long (max {} characters)",
+                MAX_IDEMPOTENCY_KEY_LEN
+            )));
+        }
+        let request_hash = import_request_hash(req.account_id, &req.transactions);
+        if let Some(existing) =
+            state
+                .db
+                .reserve_import_idempotency_key(key, &current_user.email, &request_hash)?
+        {
+            // Only the caller that sent a key may replay its response
+            if existing.created_by != current_user.email || existing.request_hash != request_hash {
+                return Err(AppError::conflict(
+                    "Idempotency key was already used for a different request",
+                ));
+            }
+            let stored = existing.response.ok_or_else(|| {
+                AppError::conflict("A request with this idempotency key is still in progress")
+            })?;
+            let mut response: ImportTransactionsResponse = serde_json::from_str(&stored)
+                .map_err(|_| AppError::internal("Stored import response is unreadable"))?;
+            response.replayed = true;
+            return Ok(Json(response));
+        }
+    }
+
+    let result = import_transaction_rows(&state, &user_email, &account, &req);
+    match (&idempotency_key, &result) {
+        (Some(key), Ok(response)) => {
+            let stored = serde_json::to_string(response)
+                .map_err(|e| AppError::internal(&format!("Failed to store response: {}", e)))?;
+            state.db.complete_import_idempotency_key(
+                key,
+                &current_user.email,
+                response.import_session_id,
+                &stored,
+            )?;
+        }
+        (Some(key), Err(_)) => {
+            if let Err(e) = state
+                .db
+                .release_import_idempotency_key(key, &current_user.email)
+            {
+                warn!("Failed to release idempotency key: {}", e);
+            }
+        }
+        (None, _) => {}
+    }
+    result.map(Json)
+}
+
+/// Validate, insert and queue processing for a JSON import
+fn import_transaction_rows(
+    state: &AppState,
+    user_email: &str,
+    account: &hone_core::models::Account,
+    req: &ImportTransactionsRequest,
+) -> Result<ImportTransactionsResponse, AppError> {
+    let mut rows: Vec<ImportRowResult> = Vec::with_capacity(req.transactions.len());
+    let mut valid = Vec::new();
+    for (index, row) in req.transactions.iter().enumerate() {
+        match parse_import_row(row) {
+            Ok(tx) => valid.push((index, tx)),
+            Err(e) => rows.push(ImportRowResult {
+                index,
+                external_id: row.external_id.clone(),
+                status: ImportRowStatus::Invalid,
+                transaction_id: None,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+    let invalid = rows.len();
+
+    let session = NewImportSession {
+        account_id: account.id,
+        filename: None,
+        file_size_bytes: None,
+        bank: account.bank,
+        user_email: (!user_email.is_empty()).then(|| user_email.to_string()),
+        ollama_model: match (state.ai.as_ref(), req.model.as_deref()) {
+            (Some(_), Some(model)) => Some(model.to_string()),
+            (Some(ai), None) => Some(ai.model().to_string()),
+            _ => None,
+        },
+    };
+    let transactions: Vec<_> = valid.iter().map(|(_, tx)| tx.clone()).collect();
+    let (import_session_id, results) =
+        state.db.import_rows_into_session(&session, &transactions)?;
+
+    let mut imported = 0;
+    for ((index, _), result) in valid.iter().zip(results) {
+        let (status, transaction_id) = match result {
+            TransactionInsertResult::Inserted(id) => {
+                imported += 1;
+                (ImportRowStatus::Imported, id)
+            }
+            TransactionInsertResult::Duplicate(id) => (ImportRowStatus::Duplicate, id),
+        };
+        rows.push(ImportRowResult {
+            index: *index,
+            external_id: req.transactions[*index].external_id.clone(),
+            status,
+            transaction_id: Some(transaction_id),
+            error: None,
+        });
+    }
+    rows.sort_by_key(|r| r.index);
+    let skipped = valid.len() - imported;
+
+    state.db.log_audit(
+        user_email,
+        "import",
+        Some("transaction"),
+        None,
+        Some(&format!(
+            "session={}, account={}, source=api, imported={}, skipped={}, invalid={}",
+            import_session_id, account.name, imported, skipped, invalid,
+        )),
+    )?;
+
+    // Queue AI processing as a background job (completes the session if nothing was imported)
+    let job_id = match state.db.queue_import_processing(
+        import_session_id,
+        imported,
+        req.model.as_deref(),
+        user_email,
+    ) {
+        Ok(job_id) => job_id,
+        Err(e) => {
+            error!(
+                "Failed to queue import processing for session {}: {}",
+                import_session_id, e
+            );
+            None
+        }
+    };
+
+    Ok(ImportTransactionsResponse {
+        import_session_id,
+        account_id: account.id,
+        account_name: account.name.clone(),
+        imported,
+        skipped,
+        invalid,
+        job_id,
+        rows,
+        replayed: false,
+    })
+}
+
 /// Strip common payment method prefixes from merchant descriptions
 /// This helps group transactions that are the same merchant but with different payment methods
 fn strip_payment_prefix(description: &str) -> &str {
@@ -1,149 +1,302 @@
 //! Explore mode handler - conversational financial queries
 //!
 //! Uses the AI orchestrator to execute agentic queries with tool calling.
-//! Supports multi-turn conversations via session management.
+//! Conversations are persisted in the database so they survive restarts and
+//! can be listed, searched, resumed, and exported to Markdown. Long
+//! conversations are compacted by summarizing older turns rather than
+//! dropping them. A server-sent events variant streams tool calls and
+//! assistant text while the orchestrator loop runs.
 
 use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use axum::{
-    extract::{Path, State},
-    http::HeaderMap,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, Response, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
     Json,
 };
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use tokio::sync::RwLock;
-use tracing::{debug, error};
+use tokio::sync::{mpsc, RwLock};
+use tokio_stream::{wrappers::UnboundedReceiverStream, Stream};
+use tracing::{debug, error, warn};
 
-use crate::{get_user_email, AppError, AppState};
-use hone_core::ai::{Message, ToolCallRecord};
-use hone_core::models::{NewOllamaMetric, OllamaOperation};
+use crate::{get_user_email, AppError, AppState, CurrentUser, MAX_PAGE_LIMIT};
+use hone_core::ai::{
+    AIOrchestrator, AnthropicCompatBackend, Message, OrchestratorEvent, OrchestratorEventCallback,
+    OrchestratorResult, ToolCallRecord,
+};
+use hone_core::db::Database;
+use hone_core::models::{
+    ExploreSession, ExploreTurn, NewExploreTurn, NewOllamaMetric, OllamaOperation, UserRole,
+};
 use hone_core::prompts::{PromptId, PromptLibrary};
 use hone_core::tools::hone_tools;
 
-/// Session timeout (30 minutes of inactivity)
-const SESSION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Title given to sessions created before their first query
+const DEFAULT_SESSION_TITLE: &str = "New conversation";
+
+/// Maximum length of a title derived from the first query
+const MAX_TITLE_CHARS: usize = 60;
+
+/// Maximum unsummarized turns sent to the model (each turn is a user/assistant pair)
+const MAX_CONTEXT_TURNS: usize = 10;
 
-/// Maximum messages to keep in history (to limit context size)
-const MAX_HISTORY_MESSAGES: usize = 20;
+/// Turns kept verbatim when older turns are folded into the session summary
+const KEEP_RECENT_TURNS: usize = 4;
 
-/// An explore session with conversation history
-#[derive(Debug, Clone)]
-pub struct ExploreSession {
-    /// Session creation time
-    pub created_at: Instant,
-    /// Last activity time
-    pub last_activity: Instant,
-    /// Conversation history (user/assistant pairs)
-    pub messages: Vec<Message>,
+/// Maximum characters of tool output included in Markdown exports
+const MAX_EXPORT_TOOL_OUTPUT: usize = 2000;
+
+/// Tracks in-flight explore runs; sessions themselves live in the database
+#[derive(Debug, Default)]
+pub struct ExploreSessionManager {
+    /// Cancellation flags for in-flight streaming queries, keyed by session ID
+    active_runs: RwLock<HashMap<String, Arc<AtomicBool>>>,
 }
 
-impl ExploreSession {
-    fn new() -> Self {
+impl ExploreSessionManager {
+    pub fn new() -> Self {
         Self {
-            created_at: Instant::now(),
-            last_activity: Instant::now(),
-            messages: Vec::new(),
+            active_runs: RwLock::new(HashMap::new()),
         }
     }
 
-    fn is_expired(&self) -> bool {
-        self.last_activity.elapsed() > SESSION_TIMEOUT
+    /// Register an in-flight run for a session and return its cancellation flag
+    ///
+    /// Starting a new run cancels any run still in flight for the same session.
+    pub async fn start_run(&self, session_id: &str) -> Arc<AtomicBool> {
+        let flag = Arc::new(AtomicBool::new(false));
+        let mut runs = self.active_runs.write().await;
+        if let Some(previous) = runs.insert(session_id.to_string(), flag.clone()) {
+            previous.store(true, Ordering::SeqCst);
+        }
+        flag
     }
 
-    fn touch(&mut self) {
-        self.last_activity = Instant::now();
+    /// Remove a finished run (only if it is still the session's current run)
+    pub async fn finish_run(&self, session_id: &str, flag: &Arc<AtomicBool>) {
+        let mut runs = self.active_runs.write().await;
+        if runs.get(session_id).is_some_and(|f| Arc::ptr_eq(f, flag)) {
+            runs.remove(session_id);
+        }
     }
 
-    fn add_messages(&mut self, new_messages: Vec<Message>) {
-        self.messages = new_messages;
-        // Trim if too long (keep most recent)
-        if self.messages.len() > MAX_HISTORY_MESSAGES {
-            let start = self.messages.len() - MAX_HISTORY_MESSAGES;
-            self.messages = self.messages[start..].to_vec();
+    /// Cancel the in-flight run for a session, returning whether one was running
+    pub async fn cancel_run(&self, session_id: &str) -> bool {
+        let runs = self.active_runs.read().await;
+        match runs.get(session_id) {
+            Some(flag) => {
+                flag.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
         }
-        self.touch();
     }
 }
 
-/// In-memory session manager
-#[derive(Debug, Default)]
-pub struct ExploreSessionManager {
-    sessions: RwLock<HashMap<String, ExploreSession>>,
+/// Generate a new explore session ID
+fn new_session_id() -> String {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let mut hasher = Sha256::new();
+    hasher.update(timestamp.to_le_bytes());
+    let hash = hasher.finalize();
+    format!("exp_{:x}", hash)[..20].to_string()
 }
 
-impl ExploreSessionManager {
-    pub fn new() -> Self {
-        Self {
-            sessions: RwLock::new(HashMap::new()),
-        }
+/// Derive a session title from the first query (first line, truncated)
+pub fn title_from_query(query: &str) -> String {
+    let line = query.lines().map(str::trim).find(|l| !l.is_empty());
+    let Some(line) = line else {
+        return DEFAULT_SESSION_TITLE.to_string();
+    };
+    if line.chars().count() <= MAX_TITLE_CHARS {
+        line.to_string()
+    } else {
+        let truncated: String = line.chars().take(MAX_TITLE_CHARS - 1).collect();
+        format!("{}…", truncated.trim_end())
+    }
+}
+
+/// Build the conversation history sent to the model for a session
+///
+/// The session summary (if any) stands in for summarized turns, followed by
+/// the most recent unsummarized turns as user/assistant text pairs.
+pub fn build_history(session: &ExploreSession, turns: &[ExploreTurn]) -> Vec<Message> {
+    let mut messages = Vec::new();
+
+    if let Some(summary) = session.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+        messages.push(Message::user(format!(
+            "Summary of our earlier conversation:\n{}",
+            summary
+        )));
+        messages.push(Message::assistant(
+            "Understood. I'll keep that context in mind.",
+        ));
     }
 
-    /// Create a new session and return its ID
-    pub async fn create_session(&self) -> String {
-        // Generate a unique session ID from timestamp + counter
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos();
-        let mut hasher = Sha256::new();
-        hasher.update(timestamp.to_le_bytes());
-        let hash = hasher.finalize();
-        let session_id = format!("exp_{:x}", hash)[..20].to_string();
-
-        let mut sessions = self.sessions.write().await;
-
-        // Clean up expired sessions while we're here
-        sessions.retain(|_, s| !s.is_expired());
-
-        sessions.insert(session_id.clone(), ExploreSession::new());
-        session_id
+    let active: Vec<&ExploreTurn> = turns.iter().filter(|t| !t.summarized).collect();
+    let start = active.len().saturating_sub(MAX_CONTEXT_TURNS);
+    for turn in &active[start..] {
+        messages.push(Message::user(turn.query.clone()));
+        messages.push(Message::assistant(turn.response.clone()));
     }
 
-    /// Get a session's messages (returns empty if not found or expired)
-    pub async fn get_messages(&self, session_id: &str) -> Vec<Message> {
-        let sessions = self.sessions.read().await;
-        sessions
-            .get(session_id)
-            .filter(|s| !s.is_expired())
-            .map(|s| s.messages.clone())
-            .unwrap_or_default()
+    messages
+}
+
+/// Fold older turns into the session summary once history grows too long
+///
+/// Returns `Ok(true)` if turns were summarized. If the model call fails the
+/// turns are left as-is; `build_history` still caps what goes to the model.
+pub async fn compact_explore_session(
+    db: &Database,
+    backend: &AnthropicCompatBackend,
+    session_id: &str,
+) -> hone_core::Result<bool> {
+    let Some(session) = db.get_explore_session(session_id)? else {
+        return Ok(false);
+    };
+    let turns = db.get_explore_turns(session_id)?;
+    let active: Vec<&ExploreTurn> = turns.iter().filter(|t| !t.summarized).collect();
+    if active.len() <= MAX_CONTEXT_TURNS {
+        return Ok(false);
     }
 
-    /// Update a session's messages
-    pub async fn update_session(&self, session_id: &str, messages: Vec<Message>) {
-        let mut sessions = self.sessions.write().await;
-        if let Some(session) = sessions.get_mut(session_id) {
-            session.add_messages(messages);
-        } else {
-            // Create session if it doesn't exist
-            let mut session = ExploreSession::new();
-            session.add_messages(messages);
-            sessions.insert(session_id.to_string(), session);
+    let to_summarize = &active[..active.len() - KEEP_RECENT_TURNS];
+    let Some(last) = to_summarize.last() else {
+        return Ok(false);
+    };
+
+    let mut transcript = String::new();
+    for turn in to_summarize {
+        let _ = writeln!(
+            transcript,
+            "User: {}\nAssistant: {}\n",
+            turn.query, turn.response
+        );
+    }
+
+    let mut prompt_lib = PromptLibrary::new();
+    let prompt = prompt_lib.get(PromptId::ExploreSummary)?;
+    let system = prompt.system_section().unwrap_or_default().to_string();
+    let mut vars = HashMap::new();
+    let previous = session.summary.clone().unwrap_or_default();
+    vars.insert("previous_summary", previous.as_str());
+    vars.insert("turns", transcript.as_str());
+    let user = prompt.render_user(&vars);
+
+    let start = Instant::now();
+    let result = backend
+        .messages(Some(&system), vec![Message::user(user.clone())], None)
+        .await;
+
+    let summary = result
+        .as_ref()
+        .ok()
+        .and_then(|r| r.text())
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+
+    let metric = NewOllamaMetric {
+        operation: OllamaOperation::ExploreSummary,
+        model: backend.model().to_string(),
+        latency_ms: start.elapsed().as_millis() as i64,
+        success: summary.is_some(),
+        error_message: match &result {
+            Ok(_) if summary.is_none() => Some("Empty summary".to_string()),
+            Ok(_) => None,
+            Err(e) => Some(e.to_string()),
+        },
+        confidence: None,
+        transaction_id: None,
+        input_text: Some(user),
+        result_text: summary.clone(),
+        metadata: None,
+    };
+    if let Err(e) = db.record_ollama_metric(&metric) {
+        error!("Failed to record explore summary metric: {}", e);
+    }
+
+    match summary {
+        Some(summary) => {
+            let marked = db.summarize_explore_turns(session_id, &summary, last.id)?;
+            debug!(session_id = %session_id, turns = marked, "Summarized older explore turns");
+            Ok(true)
+        }
+        None => {
+            if let Err(e) = result {
+                warn!(session_id = %session_id, "Explore summary failed: {}", e);
+            }
+            Ok(false)
         }
     }
+}
+
+/// Render a session and its turns (including tool calls) as Markdown
+pub fn session_to_markdown(session: &ExploreSession, turns: &[ExploreTurn]) -> String {
+    let mut md = String::new();
+    let _ = writeln!(md, "# {}\n", session.title);
+    let _ = writeln!(
+        md,
+        "- Created: {}",
+        session.created_at.format("%Y-%m-%d %H:%M UTC")
+    );
+    let _ = writeln!(
+        md,
+        "- Last activity: {}",
+        session.updated_at.format("%Y-%m-%d %H:%M UTC")
+    );
+    if let Some(model) = &session.model {
+        let _ = writeln!(md, "- Model: {}", model);
+    }
+    let _ = writeln!(md, "- Turns: {}", turns.len());
+
+    for (i, turn) in turns.iter().enumerate() {
+        let _ = writeln!(md, "\n---\n\n## Turn {}\n", i + 1);
+        let _ = writeln!(md, "**You:**\n\n{}\n", turn.query.trim());
+
+        if !turn.tool_calls.is_empty() {
+            let _ = writeln!(md, "**Tool calls:**\n");
+            for call in &turn.tool_calls {
+                let status = if call.success { "ok" } else { "failed" };
+                let input = serde_json::to_string(&call.input).unwrap_or_default();
+                let _ = writeln!(md, "- `{}` ({}) `{}`", call.name, status, input);
+                if let Some(output) = &call.output {
+                    let shown = if output.chars().count() > MAX_EXPORT_TOOL_OUTPUT {
+                        let head: String = output.chars().take(MAX_EXPORT_TOOL_OUTPUT).collect();
+                        format!("{}\n… (truncated)", head)
+                    } else {
+                        output.clone()
+                    };
+                    let _ = writeln!(md, "\n  ```\n  {}\n  ```", shown.replace('\n', "\n  "));
+                }
+            }
+            md.push('\n');
+        }
 
-    /// Delete a session
-    pub async fn delete_session(&self, session_id: &str) -> bool {
-        let mut sessions = self.sessions.write().await;
-        sessions.remove(session_id).is_some()
+        let _ = writeln!(md, "**Hone:**\n\n{}", turn.response.trim());
     }
 
-    /// Get session info
-    pub async fn get_session_info(&self, session_id: &str) -> Option<SessionInfo> {
-        let sessions = self.sessions.read().await;
-        sessions
-            .get(session_id)
-            .filter(|s| !s.is_expired())
-            .map(|s| SessionInfo {
-                session_id: session_id.to_string(),
-                message_count: s.messages.len(),
-                created_at_secs_ago: s.created_at.elapsed().as_secs(),
-                last_activity_secs_ago: s.last_activity.elapsed().as_secs(),
-            })
+    if let Some(summary) = session.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+        let _ = writeln!(
+            md,
+            "\n---\n\n## Summary of earlier turns\n\n{}",
+            summary.trim()
+        );
     }
+
+    md
 }
 
 /// Request to query the explore assistant
@@ -177,19 +330,91 @@ pub struct ExploreResponse {
 #[derive(Debug, Serialize)]
 pub struct SessionInfo {
     pub session_id: String,
+    pub title: String,
+    pub model: Option<String>,
+    /// Number of user/assistant messages (two per turn)
     pub message_count: usize,
+    pub turn_count: i64,
+    /// Whether older turns have been folded into a summary
+    pub has_summary: bool,
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
     pub created_at_secs_ago: u64,
     pub last_activity_secs_ago: u64,
 }
 
+impl From<ExploreSession> for SessionInfo {
+    fn from(session: ExploreSession) -> Self {
+        let now = Utc::now();
+        Self {
+            message_count: (session.turn_count * 2) as usize,
+            turn_count: session.turn_count,
+            has_summary: session.summary.as_deref().is_some_and(|s| !s.is_empty()),
+            created_at_secs_ago: (now - session.created_at).num_seconds().max(0) as u64,
+            last_activity_secs_ago: (now - session.updated_at).num_seconds().max(0) as u64,
+            session_id: session.id,
+            title: session.title,
+            model: session.model,
+            created_at: session.created_at,
+            updated_at: session.updated_at,
+        }
+    }
+}
+
+/// Session with its full turn history (for resuming a conversation)
+#[derive(Debug, Serialize)]
+pub struct SessionDetail {
+    #[serde(flatten)]
+    pub info: SessionInfo,
+    pub summary: Option<String>,
+    pub turns: Vec<ExploreTurn>,
+}
+
+/// Query parameters for listing sessions
+#[derive(Debug, Deserialize)]
+pub struct ExploreSessionsQuery {
+    /// Matches session titles and conversation text
+    pub search: Option<String>,
+    #[serde(default = "default_sessions_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_sessions_limit() -> i64 {
+    50
+}
+
+/// Request to rename a session
+#[derive(Debug, Deserialize)]
+pub struct RenameSessionRequest {
+    pub title: String,
+}
+
+/// Request to create a session
+#[derive(Debug, Default, Deserialize)]
+pub struct CreateSessionRequest {
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
 /// POST /api/explore/session - Create a new explore session
 pub async fn create_explore_session(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
+    payload: Option<Json<CreateSessionRequest>>,
 ) -> Result<Json<SessionInfo>, AppError> {
     let user_email = get_user_email(&headers);
+    let title = payload
+        .and_then(|Json(p)| p.title)
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| DEFAULT_SESSION_TITLE.to_string());
 
-    let session_id = state.explore_sessions.create_session().await;
+    let session_id = new_session_id();
+    let session = state
+        .db
+        .create_explore_session(&session_id, &title, Some(&user_email))?;
 
     debug!(session_id = %session_id, user = %user_email, "Created explore session");
 
@@ -202,23 +427,76 @@ pub async fn create_explore_session(
         Some(&session_id),
     )?;
 
-    Ok(Json(SessionInfo {
-        session_id,
-        message_count: 0,
-        created_at_secs_ago: 0,
-        last_activity_secs_ago: 0,
-    }))
+    Ok(Json(session.into()))
+}
+
+/// Creator whose sessions the caller may access (None for owners, who see every session)
+fn session_creator(current_user: &CurrentUser) -> Option<&str> {
+    (current_user.role != UserRole::Owner).then_some(current_user.email.as_str())
+}
+
+/// Look up a session, failing with 404 if it belongs to another member
+///
+/// Returns `None` for a session that doesn't exist.
+fn find_session(
+    state: &AppState,
+    current_user: &CurrentUser,
+    session_id: &str,
+) -> Result<Option<ExploreSession>, AppError> {
+    let Some(session) = state.db.get_explore_session(session_id)? else {
+        return Ok(None);
+    };
+    match session_creator(current_user) {
+        Some(email) if session.created_by.as_deref() != Some(email) => {
+            Err(AppError::not_found("Session not found"))
+        }
+        _ => Ok(Some(session)),
+    }
+}
+
+/// Load a session the caller may access (404 if missing or another member's)
+fn visible_session(
+    state: &AppState,
+    current_user: &CurrentUser,
+    session_id: &str,
+) -> Result<ExploreSession, AppError> {
+    find_session(state, current_user, session_id)?
+        .ok_or_else(|| AppError::not_found("Session not found"))
+}
+
+/// GET /api/explore/sessions - List saved sessions (newest activity first)
+///
+/// Members see the sessions they started; owners see every session.
+pub async fn list_explore_sessions(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Query(params): Query<ExploreSessionsQuery>,
+) -> Result<Json<Vec<SessionInfo>>, AppError> {
+    let limit = params.limit.clamp(1, MAX_PAGE_LIMIT);
+    let offset = params.offset.max(0);
+
+    let sessions = state.db.list_explore_sessions(
+        session_creator(&current_user),
+        params.search.as_deref(),
+        limit,
+        offset,
+    )?;
+
+    Ok(Json(sessions.into_iter().map(SessionInfo::from).collect()))
 }
 
 /// DELETE /api/explore/session/:id - Delete an explore session
 pub async fn delete_explore_session(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     headers: HeaderMap,
     Path(session_id): Path<String>,
 ) -> Result<Json<serde_json::Value>, AppError> {
     let user_email = get_user_email(&headers);
+    find_session(&state, &current_user, &session_id)?;
 
-    let deleted = state.explore_sessions.delete_session(&session_id).await;
+    state.explore_sessions.cancel_run(&session_id).await;
+    let deleted = state.db.delete_explore_session(&session_id)?;
 
     debug!(session_id = %session_id, deleted = deleted, "Deleted explore session");
 
@@ -234,29 +512,114 @@ pub async fn delete_explore_session(
     Ok(Json(serde_json::json!({ "deleted": deleted })))
 }
 
-/// GET /api/explore/session/:id - Get session info
+/// GET /api/explore/session/:id - Get a session with its conversation history
 pub async fn get_explore_session(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Path(session_id): Path<String>,
+) -> Result<Json<SessionDetail>, AppError> {
+    let session = visible_session(&state, &current_user, &session_id)?;
+    let turns = state.db.get_explore_turns(&session_id)?;
+
+    Ok(Json(SessionDetail {
+        summary: session.summary.clone(),
+        info: session.into(),
+        turns,
+    }))
+}
+
+/// PATCH /api/explore/session/:id - Rename a session
+pub async fn rename_explore_session(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    headers: HeaderMap,
     Path(session_id): Path<String>,
+    Json(payload): Json<RenameSessionRequest>,
 ) -> Result<Json<SessionInfo>, AppError> {
-    let info = state
-        .explore_sessions
-        .get_session_info(&session_id)
-        .await
-        .ok_or_else(|| AppError::not_found("Session not found or expired"))?;
+    let user_email = get_user_email(&headers);
+    let title = payload.title.trim();
+    if title.is_empty() {
+        return Err(AppError::bad_request("Title cannot be empty"));
+    }
+    visible_session(&state, &current_user, &session_id)?;
+
+    if !state.db.rename_explore_session(&session_id, title)? {
+        return Err(AppError::not_found("Session not found"));
+    }
+
+    state.db.log_audit(
+        &user_email,
+        "explore_session_rename",
+        Some("explore"),
+        None,
+        Some(&format!("{}: {}", session_id, title)),
+    )?;
 
-    Ok(Json(info))
+    let session = state
+        .db
+        .get_explore_session(&session_id)?
+        .ok_or_else(|| AppError::not_found("Session not found"))?;
+    Ok(Json(session.into()))
 }
 
-/// POST /api/explore/query - Query the explore assistant
-pub async fn query_explore(
+/// GET /api/explore/session/:id/export - Download a session as Markdown
+pub async fn export_explore_session(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     headers: HeaderMap,
-    Json(payload): Json<ExploreQuery>,
-) -> Result<Json<ExploreResponse>, AppError> {
-    let start = Instant::now();
+    Path(session_id): Path<String>,
+) -> Result<Response<Body>, AppError> {
     let user_email = get_user_email(&headers);
+    let session = visible_session(&state, &current_user, &session_id)?;
+    let turns = state.db.get_explore_turns(&session_id)?;
+
+    state.db.log_audit(
+        &user_email,
+        "explore_session_export",
+        Some("explore"),
+        None,
+        Some(&session_id),
+    )?;
+
+    let markdown = session_to_markdown(&session, &turns);
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/markdown; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.md\"", session_id),
+        )
+        .body(Body::from(markdown))
+        .map_err(|e| AppError::internal(&e.to_string()))
+}
+
+/// Load the system section of the explore agent prompt (honoring overrides)
+pub fn explore_system_prompt() -> hone_core::Result<String> {
+    let mut prompt_lib = PromptLibrary::new();
+    let prompt = prompt_lib.get(PromptId::ExploreAgent)?;
+    prompt.system_section().map(str::to_string).ok_or_else(|| {
+        hone_core::Error::InvalidData("Explore prompt missing system section".into())
+    })
+}
+
+/// Everything needed to run one explore query, resolved before execution starts
+struct PreparedQuery {
+    session_id: String,
+    prior_messages: Vec<Message>,
+    system_prompt: String,
+    /// Orchestrator for this run: the requested model, with tools reading
+    /// only the accounts the caller may see
+    orchestrator: AIOrchestrator,
+}
 
+/// Validate the orchestrator, resolve the session, and load the explore prompt
+async fn prepare_query(
+    state: &AppState,
+    current_user: &CurrentUser,
+    payload: &ExploreQuery,
+    user_email: &str,
+) -> Result<PreparedQuery, AppError> {
     // Check if orchestrator is configured
     let orchestrator = state.orchestrator.as_ref().ok_or_else(|| {
         AppError::bad_request(
@@ -264,14 +627,34 @@ pub async fn query_explore(
         )
     })?;
 
-    // Get or create session
-    let session_id = match &payload.session_id {
-        Some(id) => id.clone(),
-        None => state.explore_sessions.create_session().await,
+    // Resume the session (creating it if new or unknown), titled after its first query
+    let session = match &payload.session_id {
+        Some(id) => find_session(state, current_user, id)?,
+        None => None,
     };
+    let session = match session {
+        Some(session) => {
+            if session.turn_count == 0 && session.title == DEFAULT_SESSION_TITLE {
+                state
+                    .db
+                    .rename_explore_session(&session.id, &title_from_query(&payload.query))?;
+            }
+            session
+        }
+        None => {
+            let id = payload.session_id.clone().unwrap_or_else(new_session_id);
+            state.db.create_explore_session(
+                &id,
+                &title_from_query(&payload.query),
+                Some(user_email),
+            )?
+        }
+    };
+    let session_id = session.id.clone();
 
-    // Get existing conversation history
-    let prior_messages = state.explore_sessions.get_messages(&session_id).await;
+    // Get existing conversation history (summary + recent turns)
+    let turns = state.db.get_explore_turns(&session_id)?;
+    let prior_messages = build_history(&session, &turns);
 
     debug!(
         session_id = %session_id,
@@ -280,38 +663,35 @@ pub async fn query_explore(
     );
 
     // Load the explore prompt from the library
-    let mut prompt_lib = PromptLibrary::new();
-    let prompt = prompt_lib.get(PromptId::ExploreAgent).map_err(|e| {
+    let system_prompt = explore_system_prompt().map_err(|e| {
         error!("Failed to load explore prompt: {}", e);
         AppError::internal("Failed to load explore prompt")
     })?;
 
-    // Get the system prompt section
-    let system_prompt = prompt
-        .system_section()
-        .ok_or_else(|| AppError::internal("Explore prompt missing system section"))?;
-
-    // Get all available tools
-    let tools = hone_tools();
-
-    // Use model override if specified, otherwise use default
-    let effective_orchestrator;
-    let orchestrator_ref = if let Some(ref model) = payload.model {
-        effective_orchestrator = orchestrator.with_model(model);
-        &effective_orchestrator
-    } else {
-        orchestrator
+    // Tools only see the caller's accounts; use model override if specified
+    let orchestrator = orchestrator.with_db(current_user.db(&state.db));
+    let orchestrator = match &payload.model {
+        Some(model) => orchestrator.with_model(model),
+        None => orchestrator,
     };
 
-    // Execute the query through the orchestrator with full tracking
-    let model_name = orchestrator_ref.model().to_string();
-    let result = orchestrator_ref
-        .execute_with_tracking(system_prompt, &payload.query, &tools, prior_messages)
-        .await;
+    Ok(PreparedQuery {
+        session_id,
+        prior_messages,
+        system_prompt,
+        orchestrator,
+    })
+}
 
-    // Record metrics regardless of success/failure
-    let latency_ms = start.elapsed().as_millis() as i64;
-    let (success, error_message, response_text, metadata_json) = match &result {
+/// Record the explore query metric (with tool calls in metadata) regardless of outcome
+fn record_query_metric(
+    state: &AppState,
+    model_name: &str,
+    query: &str,
+    latency_ms: i64,
+    result: &hone_core::Result<OrchestratorResult>,
+) {
+    let (success, error_message, response_text, metadata_json) = match result {
         Ok(r) => {
             // Serialize tool calls and iterations for metrics storage
             let metadata = serde_json::json!({
@@ -324,62 +704,131 @@ pub async fn query_explore(
         Err(e) => (false, Some(e.to_string()), None, None),
     };
 
-    // Record the metric (with tool calls in metadata)
     let metric = NewOllamaMetric {
         operation: OllamaOperation::ExploreQuery,
-        model: model_name.clone(),
+        model: model_name.to_string(),
         latency_ms,
         success,
-        error_message: error_message.clone(),
+        error_message,
         confidence: None,
         transaction_id: None,
-        input_text: Some(payload.query.clone()),
-        result_text: response_text.clone(),
+        input_text: Some(query.to_string()),
+        result_text: response_text,
         metadata: metadata_json,
     };
 
     if let Err(e) = state.db.record_ollama_metric(&metric) {
         error!("Failed to record explore query metric: {}", e);
     }
+}
+
+/// Persist a completed query as a session turn
+fn save_turn(
+    state: &AppState,
+    session_id: &str,
+    query: &str,
+    model_name: &str,
+    result: &OrchestratorResult,
+) -> hone_core::Result<i64> {
+    state.db.add_explore_turn(
+        session_id,
+        &NewExploreTurn {
+            query: query.to_string(),
+            response: result.response.clone(),
+            tool_calls: result.tool_calls.clone(),
+            iterations: result.iterations as i64,
+            model: Some(model_name.to_string()),
+        },
+    )
+}
+
+/// Turn an orchestrator error into a helpful, user-facing message
+fn describe_query_error(err: &hone_core::Error, model_name: &str, host: &str) -> String {
+    let err_str = err.to_string();
+
+    if err_str.contains("does not support tools") {
+        format!(
+            "The model '{}' doesn't support tool calling. Please select a different model like llama3.1 or qwen3-coder.",
+            model_name
+        )
+    } else if err_str.contains("not found") {
+        format!(
+            "AI model '{}' not found. It may not be pulled on the Ollama server. Try: ollama pull {}",
+            model_name, model_name
+        )
+    } else if err_str.contains("connection refused") || err_str.contains("Connection refused") {
+        format!(
+            "Cannot connect to AI backend. Is Ollama running at {}?",
+            host
+        )
+    } else if err_str.contains("timeout") || err_str.contains("timed out") {
+        "AI query timed out. The model may be overloaded or the query too complex.".to_string()
+    } else {
+        format!("AI query failed: {}", err_str)
+    }
+}
+
+/// POST /api/explore/query - Query the explore assistant
+pub async fn query_explore(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    headers: HeaderMap,
+    Json(payload): Json<ExploreQuery>,
+) -> Result<Json<ExploreResponse>, AppError> {
+    let start = Instant::now();
+    let user_email = get_user_email(&headers);
+
+    let prepared = prepare_query(&state, &current_user, &payload, &user_email).await?;
+    let orchestrator_ref = &prepared.orchestrator;
+
+    // Get all available tools
+    let tools = hone_tools();
+
+    // Execute the query through the orchestrator with full tracking
+    let model_name = orchestrator_ref.model().to_string();
+    let result = orchestrator_ref
+        .execute_with_tracking(
+            &prepared.system_prompt,
+            &payload.query,
+            &tools,
+            prepared.prior_messages,
+        )
+        .await;
+
+    // Record metrics regardless of success/failure
+    let latency_ms = start.elapsed().as_millis() as i64;
+    record_query_metric(&state, &model_name, &payload.query, latency_ms, &result);
 
     // Now handle the result
     let orchestrator_result = result.map_err(|e| {
-        let err_str = e.to_string();
-        error!("AI query failed: {}", err_str);
-
-        // Provide a more helpful error message based on the error type
-        let user_message = if err_str.contains("does not support tools") {
-            format!(
-                "The model '{}' doesn't support tool calling. Please select a different model like llama3.1 or qwen3-coder.",
-                model_name
-            )
-        } else if err_str.contains("not found") {
-            format!(
-                "AI model '{}' not found. It may not be pulled on the Ollama server. Try: ollama pull {}",
-                model_name, model_name
-            )
-        } else if err_str.contains("connection refused")
-            || err_str.contains("Connection refused")
-        {
-            format!(
-                "Cannot connect to AI backend. Is Ollama running at {}?",
-                orchestrator_ref.backend().host()
-            )
-        } else if err_str.contains("timeout") || err_str.contains("timed out") {
-            "AI query timed out. The model may be overloaded or the query too complex."
-                .to_string()
-        } else {
-            format!("AI query failed: {}", err_str)
-        };
-
-        AppError::internal(&user_message)
+        error!("AI query failed: {}", e);
+        AppError::internal(&describe_query_error(
+            &e,
+            &model_name,
+            orchestrator_ref.backend().host(),
+        ))
     })?;
 
-    // Update session with new messages
-    state
-        .explore_sessions
-        .update_session(&session_id, orchestrator_result.messages)
-        .await;
+    let session_id = prepared.session_id;
+
+    // Persist the turn, then compact long histories in the background
+    save_turn(
+        &state,
+        &session_id,
+        &payload.query,
+        &model_name,
+        &orchestrator_result,
+    )?;
+    {
+        let db = state.db.clone();
+        let backend = orchestrator_ref.backend().clone();
+        let session_id = session_id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = compact_explore_session(&db, &backend, &session_id).await {
+                warn!(session_id = %session_id, "Failed to compact explore session: {}", e);
+            }
+        });
+    }
 
     // Audit log
     state.db.log_audit(
@@ -406,6 +855,170 @@ pub async fn query_explore(
     }))
 }
 
+/// Build an SSE event with a JSON payload
+fn sse_event(name: &str, data: &impl Serialize) -> Event {
+    Event::default()
+        .event(name)
+        .json_data(data)
+        .unwrap_or_else(|_| Event::default().event(name))
+}
+
+/// Map an orchestrator progress event to its SSE form
+fn orchestrator_sse_event(event: &OrchestratorEvent) -> Event {
+    let name = match event {
+        OrchestratorEvent::IterationStarted { .. } => "iteration_started",
+        OrchestratorEvent::TextDelta { .. } => "text_delta",
+        OrchestratorEvent::ToolCallStarted { .. } => "tool_call_started",
+        OrchestratorEvent::ToolCallFinished { .. } => "tool_call_finished",
+    };
+    sse_event(name, event)
+}
+
+/// POST /api/explore/query/stream - Query the explore assistant, streaming progress
+///
+/// Responds with server-sent events:
+/// - `session`: `{session_id, model}` once the run starts
+/// - `iteration_started`, `text_delta`, `tool_call_started`, `tool_call_finished`
+///   as the orchestrator works (tool events carry `ToolCallRecord` data)
+/// - `done`: the same body `/api/explore/query` returns
+/// - `error` or `cancelled` if the run does not complete
+///
+/// The run is cancelled when the client disconnects or calls
+/// `POST /api/explore/session/:id/cancel`.
+pub async fn query_explore_stream(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    headers: HeaderMap,
+    Json(payload): Json<ExploreQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let user_email = get_user_email(&headers);
+    let prepared = prepare_query(&state, &current_user, &payload, &user_email).await?;
+    let cancelled = state.explore_sessions.start_run(&prepared.session_id).await;
+
+    let (tx, rx) = mpsc::unbounded_channel::<Event>();
+
+    tokio::spawn(async move {
+        let start = Instant::now();
+        let orchestrator = &prepared.orchestrator;
+        let session_id = prepared.session_id;
+        let model_name = orchestrator.model().to_string();
+
+        let _ = tx.send(sse_event(
+            "session",
+            &serde_json::json!({ "session_id": session_id, "model": model_name }),
+        ));
+
+        // Forward orchestrator events; a closed channel means the client went away
+        let event_tx = tx.clone();
+        let disconnect_flag = cancelled.clone();
+        let on_event: OrchestratorEventCallback = Box::new(move |event| {
+            if event_tx.send(orchestrator_sse_event(&event)).is_err() {
+                disconnect_flag.store(true, Ordering::SeqCst);
+            }
+        });
+
+        let tools = hone_tools();
+        let result = orchestrator
+            .execute_streaming(
+                &prepared.system_prompt,
+                &payload.query,
+                &tools,
+                prepared.prior_messages,
+                &on_event,
+                &cancelled,
+            )
+            .await;
+
+        let latency_ms = start.elapsed().as_millis() as i64;
+        record_query_metric(&state, &model_name, &payload.query, latency_ms, &result);
+        state
+            .explore_sessions
+            .finish_run(&session_id, &cancelled)
+            .await;
+
+        match result {
+            Ok(orchestrator_result) => {
+                if let Err(e) = save_turn(
+                    &state,
+                    &session_id,
+                    &payload.query,
+                    &model_name,
+                    &orchestrator_result,
+                ) {
+                    error!("Failed to save explore turn: {}", e);
+                }
+
+                if let Err(e) = state.db.log_audit(
+                    &user_email,
+                    "explore_query",
+                    Some("explore"),
+                    None,
+                    Some(&payload.query),
+                ) {
+                    error!("Failed to audit explore query: {}", e);
+                }
+
+                let response = ExploreResponse {
+                    response: orchestrator_result.response,
+                    processing_time_ms: start.elapsed().as_millis() as u64,
+                    session_id: session_id.clone(),
+                    model: model_name,
+                    tool_calls: orchestrator_result.tool_calls,
+                    iterations: orchestrator_result.iterations,
+                };
+                let _ = tx.send(sse_event("done", &response));
+
+                // The client already has its answer; compaction can take its time
+                if let Err(e) =
+                    compact_explore_session(&state.db, orchestrator.backend(), &session_id).await
+                {
+                    warn!(session_id = %session_id, "Failed to compact explore session: {}", e);
+                }
+            }
+            Err(hone_core::Error::Cancelled(_)) => {
+                debug!(session_id = %session_id, "Explore query cancelled");
+                let _ = tx.send(sse_event(
+                    "cancelled",
+                    &serde_json::json!({ "session_id": session_id }),
+                ));
+            }
+            Err(e) => {
+                error!("AI query failed: {}", e);
+                let message = describe_query_error(&e, &model_name, orchestrator.backend().host());
+                let _ = tx.send(sse_event("error", &serde_json::json!({ "error": message })));
+            }
+        }
+    });
+
+    let stream = UnboundedReceiverStream::new(rx);
+    Ok(Sse::new(tokio_stream::StreamExt::map(stream, Ok)).keep_alive(KeepAlive::default()))
+}
+
+/// POST /api/explore/session/:id/cancel - Cancel an in-flight streaming query
+pub async fn cancel_explore_query(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user_email = get_user_email(&headers);
+    find_session(&state, &current_user, &session_id)?;
+
+    let cancelled = state.explore_sessions.cancel_run(&session_id).await;
+
+    debug!(session_id = %session_id, cancelled = cancelled, "Cancel explore query");
+
+    state.db.log_audit(
+        &user_email,
+        "explore_query_cancel",
+        Some("explore"),
+        None,
+        Some(&session_id),
+    )?;
+
+    Ok(Json(serde_json::json!({ "cancelled": cancelled })))
+}
+
 /// Response for listing available models
 #[derive(Debug, Serialize)]
 pub struct ModelsResponse {
@@ -12,7 +12,7 @@ use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
-use crate::{get_user_email, AppError, AppState};
+use crate::{get_user_email, AppError, AppState, CurrentUser};
 use hone_core::export::{FullBackup, ImportStats, TransactionExportOptions};
 
 /// Query parameters for transaction export
@@ -39,6 +39,7 @@ fn default_format() -> String {
 /// GET /api/export/transactions - Export transactions to CSV or JSON
 pub async fn export_transactions(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     headers: axum::http::HeaderMap,
     Query(params): Query<TransactionExportQuery>,
 ) -> Result<Response<Body>, AppError> {
@@ -85,7 +86,7 @@ pub async fn export_transactions(
 
     match params.format.as_str() {
         "csv" => {
-            let csv = state.db.export_transactions_csv(&opts)?;
+            let csv = current_user.db(&state.db).export_transactions_csv(&opts)?;
             let lines = csv.lines().count().saturating_sub(1);
             info!("Exported {} transactions to CSV", lines);
 
@@ -100,7 +101,7 @@ pub async fn export_transactions(
                 .map_err(|e| AppError::internal(&e.to_string()))
         }
         "json" => {
-            let transactions = state.db.export_transactions(&opts)?;
+            let transactions = current_user.db(&state.db).export_transactions(&opts)?;
             let json = serde_json::to_string_pretty(&transactions)
                 .map_err(|e| AppError::internal(&e.to_string()))?;
             info!("Exported {} transactions to JSON", transactions.len());
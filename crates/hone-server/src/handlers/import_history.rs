@@ -10,15 +10,15 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
-use crate::{get_user_email, AppError, AppState, MAX_PAGE_LIMIT};
+use crate::{get_user_email, AppError, AppState, CurrentUser, MAX_PAGE_LIMIT};
 use hone_core::{
     ai::{AIBackend, AIClient, MerchantContext},
     db::Database,
     detect::WasteDetector,
     models::{
-        ImportSessionWithAccount, ImportTaggingBreakdown, NewOllamaMetric, NewReprocessRun,
-        OllamaOperation, ReprocessComparison, ReprocessRunSummary, ReprocessRunWithComparison,
-        RunComparison, SkippedTransaction, Transaction,
+        ImportSessionWithAccount, ImportTaggingBreakdown, JobType, NewJob, NewOllamaMetric,
+        NewReprocessRun, OllamaOperation, ReprocessComparison, ReprocessRunSummary,
+        ReprocessRunWithComparison, RunComparison, SkippedTransaction, Transaction,
     },
     tags::TagAssigner,
 };
@@ -116,6 +116,7 @@ pub struct ImportTransactionsResponse {
 /// GET /api/imports/:id/transactions - Get transactions from an import session
 pub async fn get_import_session_transactions(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Path(id): Path<i64>,
     Query(params): Query<ImportTransactionsQuery>,
     request: Request,
@@ -129,10 +130,9 @@ pub async fn get_import_session_transactions(
         .get_import_session(id)?
         .ok_or_else(|| AppError::not_found("Import session not found"))?;
 
-    let transactions = state
-        .db
-        .get_import_session_transactions(id, limit, params.offset)?;
-    let total = state.db.count_import_session_transactions(id)?;
+    let db = current_user.db(&state.db);
+    let transactions = db.get_import_session_transactions(id, limit, params.offset)?;
+    let total = db.count_import_session_transactions(id)?;
 
     state.db.log_audit(
         &user_email,
@@ -151,6 +151,7 @@ pub async fn get_import_session_transactions(
 /// GET /api/imports/:id/skipped - Get skipped (duplicate) transactions from an import session
 pub async fn get_import_session_skipped(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Path(id): Path<i64>,
     request: Request,
 ) -> Result<Json<Vec<SkippedTransaction>>, AppError> {
@@ -162,7 +163,7 @@ pub async fn get_import_session_skipped(
         .get_import_session(id)?
         .ok_or_else(|| AppError::not_found("Import session not found"))?;
 
-    let skipped = state.db.get_skipped_transactions(id)?;
+    let skipped = current_user.db(&state.db).get_skipped_transactions(id)?;
 
     state.db.log_audit(
         &user_email,
@@ -209,8 +210,9 @@ pub async fn cancel_import_session(
         }));
     }
 
-    // Cancel the import
+    // Cancel the import and stop its background job
     let cancelled = state.db.cancel_import(id)?;
+    state.db.request_entity_jobs_cancel("import_session", id)?;
 
     state.db.log_audit(
         &user_email,
@@ -257,7 +259,7 @@ pub struct ReprocessRequest {
 
 /// POST /api/imports/:id/reprocess - Start async reprocess of an import session
 ///
-/// Captures a "before" snapshot, then spawns a background task to:
+/// Captures a "before" snapshot, then queues a `reprocess` background job to:
 /// - Clear auto-assigned tags and merchant normalizations
 /// - Re-run tagging, normalization, and detection
 /// - Capture an "after" snapshot for comparison
@@ -350,49 +352,31 @@ pub async fn reprocess_import_session(
         )),
     )?;
 
-    // 4. Spawn background task
-    let db = state.db.clone();
-    let ollama = effective_ai;
-    let imported_count = session.session.imported_count;
-    let skipped_count = session.session.skipped_count;
-    let receipts_matched = session.session.receipts_matched;
-    let model_override_owned = model_override;
-
-    tokio::spawn(async move {
-        // Create orchestrator inside task with cloned db (AIOrchestrator contains Database)
-        // Apply model override if specified
-        let orchestrator = match model_override_owned.as_deref() {
-            Some(model) => hone_core::ai::orchestrator::AIOrchestrator::from_env(db.clone())
-                .map(|o| o.with_model(model)),
-            None => hone_core::ai::orchestrator::AIOrchestrator::from_env(db.clone()),
-        };
-
-        if let Err(e) = run_async_reprocess(
-            &db,
-            ollama.as_ref(),
-            orchestrator.as_ref(),
-            id,
-            run_id,
-            total_transactions,
-            imported_count,
-            skipped_count,
-            receipts_matched,
-        )
-        .await
-        {
-            error!(
-                "Background reprocess failed for session {}, run {}: {}",
-                id, run_id, e
-            );
-            if let Err(e2) = db.mark_import_failed(id, &e.to_string()) {
-                error!("Failed to mark import as failed: {}", e2);
-            }
-            // Also mark the run as failed
-            if let Err(e2) = db.fail_reprocess_run(run_id) {
-                error!("Failed to mark reprocess run as failed: {}", e2);
-            }
-        }
-    });
+    // 4. Queue the reprocess as a background job
+    let job = NewJob::new(
+        JobType::Reprocess,
+        serde_json::json!({
+            "session_id": id,
+            "run_id": run_id,
+            "total_transactions": total_transactions,
+            "imported_count": session.session.imported_count,
+            "skipped_count": session.session.skipped_count,
+            "receipts_matched": session.session.receipts_matched,
+            "model": model_override,
+        }),
+    )
+    .for_entity("import_session", id)
+    .created_by(&user_email);
+
+    if let Err(e) = state.db.create_job(&job) {
+        error!(
+            "Failed to queue reprocess for session {}, run {}: {}",
+            id, run_id, e
+        );
+        state.db.mark_import_failed(id, &e.to_string())?;
+        state.db.fail_reprocess_run(run_id)?;
+        return Err(e.into());
+    }
 
     Ok(Json(ReprocessStartResponse {
         session_id: id,
@@ -403,7 +387,7 @@ pub async fn reprocess_import_session(
 }
 
 /// Run the async reprocess (clearing, tagging, normalization, detection)
-async fn run_async_reprocess(
+pub(crate) async fn run_async_reprocess(
     db: &Database,
     ollama: Option<&AIClient>,
     orchestrator: Option<&hone_core::ai::orchestrator::AIOrchestrator>,
@@ -502,6 +486,11 @@ async fn run_async_reprocess(
         warn!("Failed to update normalizing duration: {}", e);
     }
 
+    // Re-normalized merchants may let more refunds find their purchases
+    if let Err(e) = db.auto_match_refunds() {
+        warn!("Failed to match refunds: {}", e);
+    }
+
     // Phase 4: Detection
     let detecting_start = Instant::now();
     db.update_import_progress(session_id, "detecting", 0, 1)?;
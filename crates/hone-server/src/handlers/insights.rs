@@ -6,12 +6,11 @@ use axum::{
     extract::{Path, Query, Request, State},
     Json,
 };
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 
-use crate::{get_user_email, AppError, AppState, SuccessResponse};
-use hone_core::insights::{
-    AnalysisContext, InsightEngine, InsightFinding, InsightStatus, InsightType,
-};
+use crate::{get_user_email, AppError, AppState, CurrentUser, SuccessResponse};
+use hone_core::insights::{InsightFinding, InsightStatus, InsightType};
+use hone_core::models::{Job, JobType, NewJob};
 
 /// Query parameters for listing insights
 #[derive(Debug, Deserialize)]
@@ -43,23 +42,18 @@ pub struct InsightFeedbackRequest {
     pub feedback: String,
 }
 
-/// Response for insight refresh
-#[derive(Debug, Serialize)]
-pub struct RefreshResponse {
-    pub count: usize,
-}
-
 /// GET /api/insights - Get top N insights for dashboard
 ///
 /// Returns the most relevant active insights, sorted by severity and recency.
 pub async fn get_top_insights(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Query(params): Query<InsightQuery>,
     request: Request,
 ) -> Result<Json<Vec<InsightFinding>>, AppError> {
     let user_email = get_user_email(request.headers());
 
-    let insights = state.db.get_top_insights(params.limit)?;
+    let insights = current_user.db(&state.db).get_top_insights(params.limit)?;
 
     // Audit log - read access
     state.db.log_audit(
@@ -76,6 +70,7 @@ pub async fn get_top_insights(
 /// GET /api/insights/all - List all insights with optional filters
 pub async fn list_insights(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Query(params): Query<InsightQuery>,
     request: Request,
 ) -> Result<Json<Vec<InsightFinding>>, AppError> {
@@ -86,7 +81,7 @@ pub async fn list_insights(
         .as_ref()
         .and_then(|s| s.parse::<InsightStatus>().ok());
 
-    let mut insights = state.db.list_insight_findings(status)?;
+    let mut insights = current_user.db(&state.db).list_insight_findings(status)?;
 
     // Filter by insight type if specified
     if let Some(ref type_str) = params.insight_type {
@@ -115,13 +110,14 @@ pub async fn list_insights(
 /// GET /api/insights/:id - Get a specific insight
 pub async fn get_insight(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Path(id): Path<i64>,
     request: Request,
 ) -> Result<Json<InsightFinding>, AppError> {
     let user_email = get_user_email(request.headers());
 
-    let insight = state
-        .db
+    let insight = current_user
+        .db(&state.db)
         .get_insight_finding(id)?
         .ok_or_else(|| AppError::not_found("Insight not found"))?;
 
@@ -244,40 +240,40 @@ pub async fn set_insight_feedback(
     Ok(Json(SuccessResponse { success: true }))
 }
 
-/// POST /api/insights/refresh - Re-run insight analysis
+/// POST /api/insights/refresh - Queue insight analysis
 ///
-/// Runs all insight analyzers and persists the findings.
+/// Returns the queued job; the number of findings is in its result.
 pub async fn refresh_insights(
     State(state): State<Arc<AppState>>,
     request: Request,
-) -> Result<Json<RefreshResponse>, AppError> {
+) -> Result<Json<Job>, AppError> {
     let user_email = get_user_email(request.headers());
 
-    let engine = InsightEngine::new();
-    let ctx = AnalysisContext::current_month(&state.db, state.ai.as_ref());
-
-    let count = engine.run_and_persist(&ctx).await?;
+    let id = state.db.create_job(
+        &NewJob::new(JobType::InsightRefresh, serde_json::json!({})).created_by(&user_email),
+    )?;
 
     // Audit log
-    state.db.log_audit(
-        &user_email,
-        "refresh",
-        Some("insight"),
-        None,
-        Some(&format!("findings_count={}", count)),
-    )?;
+    state
+        .db
+        .log_audit(&user_email, "refresh", Some("insight"), None, None)?;
 
-    Ok(Json(RefreshResponse { count }))
+    let job = state
+        .db
+        .get_job(id)?
+        .ok_or_else(|| AppError::internal("Failed to retrieve queued job"))?;
+    Ok(Json(job))
 }
 
 /// GET /api/insights/count - Get count of active insights
 pub async fn count_insights(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     request: Request,
 ) -> Result<Json<i64>, AppError> {
     let user_email = get_user_email(request.headers());
 
-    let count = state.db.count_active_insights()?;
+    let count = current_user.db(&state.db).count_active_insights()?;
 
     // Audit log
     state
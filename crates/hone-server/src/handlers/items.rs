@@ -0,0 +1,79 @@
+//! Item catalog handlers
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+
+use crate::{get_user_email, AppError, AppState, CurrentUser};
+use hone_core::models::CatalogItem;
+
+/// Map catalog validation errors to client errors
+fn catalog_error(e: hone_core::Error) -> AppError {
+    match e {
+        hone_core::Error::InvalidData(msg) => AppError::bad_request(&msg),
+        hone_core::Error::NotFound(msg) => AppError::not_found(&msg),
+        other => other.into(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CatalogItemsQuery {
+    /// Only items bought at this merchant
+    pub merchant: Option<String>,
+    /// Match item names containing this text
+    pub q: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// GET /api/items - Catalog items with purchase stats, most purchased first
+pub async fn list_catalog_items(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Query(params): Query<CatalogItemsQuery>,
+) -> Result<Json<Vec<CatalogItem>>, AppError> {
+    let limit = params.limit.unwrap_or(100).clamp(1, 500);
+    let items = current_user.db(&state.db).list_catalog_items(
+        params.merchant.as_deref(),
+        params.q.as_deref(),
+        limit,
+    )?;
+    Ok(Json(items))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergeCatalogItemRequest {
+    /// Catalog item that absorbs this one
+    pub into_id: i64,
+}
+
+/// POST /api/items/:id/merge - Merge a catalog item into another
+///
+/// Later receipt lines that normalize to the merged item's name are linked
+/// to the target.
+pub async fn merge_catalog_item(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Path(id): Path<i64>,
+    Json(req): Json<MergeCatalogItemRequest>,
+) -> Result<Json<CatalogItem>, AppError> {
+    let user_email = get_user_email(&headers);
+
+    let item = state
+        .db
+        .merge_catalog_items(id, req.into_id)
+        .map_err(catalog_error)?;
+
+    state.db.log_audit(
+        &user_email,
+        "merge",
+        Some("catalog_item"),
+        Some(id),
+        Some(&format!("into_id={}", item.id)),
+    )?;
+
+    Ok(Json(item))
+}
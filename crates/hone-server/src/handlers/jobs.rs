@@ -0,0 +1,197 @@
+//! Background job handlers
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+
+use crate::jobs::release_cancelled_job;
+use crate::{AppError, AppState, CurrentUser};
+use hone_core::models::{Job, JobStatus, JobType, NewJob, UserRole};
+
+/// Query parameters for listing jobs
+#[derive(Debug, Deserialize)]
+pub struct ListJobsQuery {
+    pub status: Option<JobStatus>,
+    #[serde(rename = "type")]
+    pub job_type: Option<JobType>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+/// Request body for enqueueing a job
+#[derive(Debug, Deserialize)]
+pub struct CreateJobRequest {
+    #[serde(rename = "type")]
+    pub job_type: JobType,
+    #[serde(default)]
+    pub params: serde_json::Value,
+}
+
+/// GET /api/jobs - List recent jobs
+pub async fn list_jobs(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ListJobsQuery>,
+) -> Result<Json<Vec<Job>>, AppError> {
+    let limit = params.limit.clamp(1, 500);
+    Ok(Json(state.db.list_jobs(
+        params.status,
+        params.job_type,
+        limit,
+    )?))
+}
+
+/// GET /api/jobs/:id - Get a job with its progress
+pub async fn get_job(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<Json<Job>, AppError> {
+    let job = state
+        .db
+        .get_job(id)?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
+    Ok(Json(job))
+}
+
+/// POST /api/jobs - Queue a detection, insight refresh or backup job
+///
+/// Import processing and reprocess jobs are queued by the import endpoints.
+pub async fn create_job(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Json(req): Json<CreateJobRequest>,
+) -> Result<Json<Job>, AppError> {
+    if req.job_type.requires_owner() {
+        user.require(UserRole::Owner)?;
+    }
+    let params = match req.job_type {
+        JobType::Detection => {
+            let kind = req
+                .params
+                .get("kind")
+                .and_then(|k| k.as_str())
+                .unwrap_or("all");
+            if !["all", "zombies", "increases", "duplicates"].contains(&kind) {
+                return Err(AppError::bad_request(
+                    "kind must be all, zombies, increases or duplicates",
+                ));
+            }
+            serde_json::json!({ "kind": kind })
+        }
+        JobType::InsightRefresh => serde_json::json!({}),
+        JobType::Backup => {
+            let retention = req
+                .params
+                .get("retention")
+                .and_then(|r| r.as_u64())
+                .unwrap_or(7);
+            if retention == 0 {
+                return Err(AppError::bad_request("retention must be at least 1"));
+            }
+            serde_json::json!({ "retention": retention })
+        }
+        JobType::ImportProcessing | JobType::Reprocess => {
+            return Err(AppError::bad_request(
+                "Import jobs are started from /api/import and /api/imports/:id/reprocess",
+            ));
+        }
+        JobType::Report
+        | JobType::PruneMetrics
+        | JobType::Training
+        | JobType::AuditArchive
+        | JobType::ExploreDigests => {
+            return Err(AppError::bad_request(
+                "Scheduled tasks are run from /api/schedules/:name/run",
+            ));
+        }
+    };
+
+    let id = state
+        .db
+        .create_job(&NewJob::new(req.job_type, params).created_by(&user.email))?;
+
+    state.db.log_audit(
+        &user.email,
+        "create",
+        Some("job"),
+        Some(id),
+        Some(&format!("type={}", req.job_type)),
+    )?;
+
+    let job = state
+        .db
+        .get_job(id)?
+        .ok_or_else(|| AppError::internal("Failed to retrieve created job"))?;
+    Ok(Json(job))
+}
+
+/// POST /api/jobs/:id/cancel - Cancel a queued or running job
+///
+/// Queued jobs are cancelled immediately; running jobs stop within a few seconds.
+pub async fn cancel_job(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Json<Job>, AppError> {
+    let job = state
+        .db
+        .get_job(id)?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
+    if job.job_type.requires_owner() {
+        user.require(UserRole::Owner)?;
+    }
+    if !state.db.request_job_cancel(id)? {
+        return Err(AppError::bad_request("Job has already finished"));
+    }
+    // A queued job never started, so nothing else will release its import
+    if job.status == JobStatus::Queued {
+        release_cancelled_job(&state.db, &job);
+    }
+
+    state
+        .db
+        .log_audit(&user.email, "cancel", Some("job"), Some(id), None)?;
+
+    let job = state
+        .db
+        .get_job(id)?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
+    Ok(Json(job))
+}
+
+/// POST /api/jobs/:id/retry - Queue a failed or cancelled job again
+pub async fn retry_job(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Json<Job>, AppError> {
+    let job = state
+        .db
+        .get_job(id)?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
+    if job.job_type.requires_owner() {
+        user.require(UserRole::Owner)?;
+    }
+    if !state.db.retry_job(id)? {
+        return Err(AppError::bad_request(
+            "Only failed or cancelled jobs can be retried",
+        ));
+    }
+
+    state
+        .db
+        .log_audit(&user.email, "retry", Some("job"), Some(id), None)?;
+
+    let job = state
+        .db
+        .get_job(id)?
+        .ok_or_else(|| AppError::not_found("Job not found"))?;
+    Ok(Json(job))
+}
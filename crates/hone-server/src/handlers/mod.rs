@@ -4,6 +4,7 @@
 
 pub mod accounts;
 pub mod alerts;
+pub mod api_keys;
 pub mod audit;
 pub mod auth;
 pub mod backup;
@@ -14,11 +15,18 @@ pub mod export;
 pub mod feedback;
 pub mod import_history;
 pub mod insights;
+pub mod items;
+pub mod jobs;
 pub mod locations;
 pub mod mileage;
+pub mod notifications;
 pub mod ollama;
 pub mod receipts;
+pub mod refunds;
 pub mod reports;
+pub mod saved_questions;
+pub mod schedules;
+pub mod settings;
 pub mod splits;
 pub mod subscriptions;
 pub mod suggestions;
@@ -26,10 +34,13 @@ pub mod tags;
 pub mod training;
 pub mod transactions;
 pub mod trips;
+pub mod users;
+pub mod warranties;
 
 // Re-export all handlers for use in router
 pub use accounts::*;
 pub use alerts::*;
+pub use api_keys::*;
 pub use audit::*;
 pub use auth::*;
 pub use backup::*;
@@ -40,11 +51,18 @@ pub use export::*;
 pub use feedback::*;
 pub use import_history::*;
 pub use insights::*;
+pub use items::*;
+pub use jobs::*;
 pub use locations::*;
 pub use mileage::*;
+pub use notifications::*;
 pub use ollama::*;
 pub use receipts::*;
+pub use refunds::*;
 pub use reports::*;
+pub use saved_questions::*;
+pub use schedules::*;
+pub use settings::*;
 pub use splits::*;
 pub use subscriptions::*;
 pub use suggestions::*;
@@ -52,3 +70,5 @@ pub use tags::*;
 pub use training::*;
 pub use transactions::*;
 pub use trips::*;
+pub use users::*;
+pub use warranties::*;
@@ -0,0 +1,161 @@
+//! Notification channel and delivery handlers (owner only)
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppError, AppState, CurrentUser};
+use hone_core::models::{DeliveryStatus, NotificationDelivery, UserRole};
+use hone_core::notify::{Notifier, QuietHours, RouteConfig};
+
+/// A configured channel (without URLs or secrets)
+#[derive(Debug, Serialize)]
+pub struct NotificationChannelInfo {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// Notification configuration summary
+#[derive(Debug, Serialize)]
+pub struct NotificationConfigResponse {
+    /// Whether a notifications config was loaded
+    pub configured: bool,
+    pub channels: Vec<NotificationChannelInfo>,
+    pub routes: Vec<RouteConfig>,
+    pub quiet_hours: Option<QuietHours>,
+    pub digest_hour: Option<u32>,
+}
+
+/// Query parameters for listing deliveries
+#[derive(Debug, Deserialize)]
+pub struct ListDeliveriesQuery {
+    pub status: Option<DeliveryStatus>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+/// Request body for a test notification
+#[derive(Debug, Deserialize)]
+pub struct TestNotificationRequest {
+    pub channel: String,
+}
+
+/// GET /api/notifications/channels - Show configured channels and routes
+pub async fn list_notification_channels(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+) -> Result<Json<NotificationConfigResponse>, AppError> {
+    user.require(UserRole::Owner)?;
+
+    let response = match &state.config.notifications {
+        Some(config) => NotificationConfigResponse {
+            configured: true,
+            channels: config
+                .channels
+                .iter()
+                .map(|c| NotificationChannelInfo {
+                    name: c.name.clone(),
+                    kind: c.kind_name().to_string(),
+                })
+                .collect(),
+            routes: config.routes.clone(),
+            quiet_hours: config.quiet_hours.clone(),
+            digest_hour: Some(config.digest_hour),
+        },
+        None => NotificationConfigResponse {
+            configured: false,
+            channels: vec![],
+            routes: vec![],
+            quiet_hours: None,
+            digest_hour: None,
+        },
+    };
+    Ok(Json(response))
+}
+
+/// GET /api/notifications/deliveries - List recent deliveries
+pub async fn list_notification_deliveries(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Query(params): Query<ListDeliveriesQuery>,
+) -> Result<Json<Vec<NotificationDelivery>>, AppError> {
+    user.require(UserRole::Owner)?;
+    let limit = params.limit.clamp(1, 500);
+    Ok(Json(
+        state
+            .db
+            .list_notification_deliveries(params.status, limit)?,
+    ))
+}
+
+/// POST /api/notifications/test - Send a test message to a channel
+pub async fn send_test_notification(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Json(req): Json<TestNotificationRequest>,
+) -> Result<Json<NotificationDelivery>, AppError> {
+    user.require(UserRole::Owner)?;
+
+    let config = state
+        .config
+        .notifications
+        .clone()
+        .ok_or_else(|| AppError::bad_request("Notifications are not configured"))?;
+    if config.channel(&req.channel).is_none() {
+        return Err(AppError::not_found("Notification channel not found"));
+    }
+
+    let delivery = Notifier::new(state.db.clone(), config)
+        .send_test(&req.channel)
+        .await?;
+
+    state.db.log_audit(
+        &user.email,
+        "test",
+        Some("notification"),
+        Some(delivery.id),
+        Some(&format!(
+            "channel={}, status={}",
+            req.channel, delivery.status
+        )),
+    )?;
+
+    Ok(Json(delivery))
+}
+
+/// POST /api/notifications/deliveries/:id/retry - Re-queue a failed delivery
+pub async fn retry_notification_delivery(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Json<NotificationDelivery>, AppError> {
+    user.require(UserRole::Owner)?;
+
+    if state.db.get_notification_delivery(id)?.is_none() {
+        return Err(AppError::not_found("Notification delivery not found"));
+    }
+    if !state.db.retry_notification_delivery(id)? {
+        return Err(AppError::bad_request(
+            "Only failed deliveries can be retried",
+        ));
+    }
+
+    state
+        .db
+        .log_audit(&user.email, "retry", Some("notification"), Some(id), None)?;
+
+    let delivery = state
+        .db
+        .get_notification_delivery(id)?
+        .ok_or_else(|| AppError::not_found("Notification delivery not found"))?;
+    Ok(Json(delivery))
+}
@@ -10,8 +10,10 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tracing::warn;
 
-use crate::{get_user_email, AppError, AppState, SuccessResponse, MAX_UPLOAD_SIZE};
-use hone_core::ai::{AIBackend, AIClient, ParsedReceipt};
+use crate::{get_user_email, AppError, AppState, CurrentUser, SuccessResponse, MAX_UPLOAD_SIZE};
+use hone_core::ai::{
+    parse_receipt_document, AIBackend, AIClient, ParsedReceipt, ReceiptFormat, ReceiptParsePath,
+};
 use hone_core::models::{
     NewOllamaMetric, NewReceipt, OllamaOperation, Receipt, ReceiptMatchCandidate, ReceiptStatus,
 };
@@ -19,12 +21,15 @@ use hone_core::models::{
 /// GET /api/transactions/:id/receipts - Get receipts for a transaction
 pub async fn get_transaction_receipts(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Path(transaction_id): Path<i64>,
     request: Request,
 ) -> Result<Json<Vec<Receipt>>, AppError> {
     let user_email = get_user_email(request.headers());
 
-    let receipts = state.db.get_receipts_for_transaction(transaction_id)?;
+    let receipts = current_user
+        .db(&state.db)
+        .get_receipts_for_transaction(transaction_id)?;
 
     state.db.log_audit(
         &user_email,
@@ -40,13 +45,14 @@ pub async fn get_transaction_receipts(
 /// GET /api/receipts/:id - Get a specific receipt
 pub async fn get_receipt(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Path(id): Path<i64>,
     request: Request,
 ) -> Result<Json<Receipt>, AppError> {
     let user_email = get_user_email(request.headers());
 
-    let receipt = state
-        .db
+    let receipt = current_user
+        .db(&state.db)
         .get_receipt(id)?
         .ok_or_else(|| AppError::not_found("Receipt not found"))?;
 
@@ -64,7 +70,7 @@ pub struct ReceiptUploadResponse {
     pub image_path: String,
 }
 
-/// POST /api/transactions/:id/receipts - Upload a receipt image
+/// POST /api/transactions/:id/receipts - Upload a receipt (image, PDF or HTML)
 pub async fn upload_receipt(
     State(state): State<Arc<AppState>>,
     Path(transaction_id): Path<i64>,
@@ -84,7 +90,7 @@ pub async fn upload_receipt(
         .map_err(|_| AppError::bad_request("Invalid request body or file too large (max 10MB)"))?;
 
     if bytes.is_empty() {
-        return Err(AppError::bad_request("No image data provided"));
+        return Err(AppError::bad_request("No receipt data provided"));
     }
 
     // Create receipts directory if it doesn't exist
@@ -95,9 +101,10 @@ pub async fn upload_receipt(
         })?;
     }
 
-    // Generate unique filename
+    // Generate unique filename (extension from the content: image, PDF or HTML)
     let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-    let filename = format!("receipt_{}_{}.jpg", transaction_id, timestamp);
+    let extension = ReceiptFormat::detect(&bytes).extension(&bytes);
+    let filename = format!("receipt_{}_{}.{}", transaction_id, timestamp, extension);
     let image_path = receipts_dir.join(&filename);
 
     // Save the image
@@ -175,6 +182,8 @@ pub struct ReceiptParseResponse {
     pub receipt_id: i64,
     pub parsed: ParsedReceipt,
     pub raw_json: String,
+    /// How the receipt reached the model (vision, pdf_text, html, ...)
+    pub parse_path: ReceiptParsePath,
 }
 
 /// POST /api/receipts/:id/parse - Parse a receipt using AI
@@ -191,24 +200,30 @@ pub async fn parse_receipt(
         .get_receipt(id)?
         .ok_or_else(|| AppError::not_found("Receipt not found"))?;
 
-    // Get image data
+    // Get receipt file
     let image_path = receipt
         .image_path
-        .ok_or_else(|| AppError::bad_request("Receipt has no image to parse"))?;
+        .ok_or_else(|| AppError::bad_request("Receipt has no file to parse"))?;
 
-    let image_data = std::fs::read(&image_path)
-        .map_err(|e| AppError::internal(&format!("Failed to read receipt image: {}", e)))?;
+    let data = std::fs::read(&image_path)
+        .map_err(|e| AppError::internal(&format!("Failed to read receipt file: {}", e)))?;
 
     // Get Ollama client from state
     let ollama = state.ai.as_ref().ok_or_else(|| {
         AppError::bad_request("Ollama not configured. Set OLLAMA_HOST environment variable.")
     })?;
 
-    // Parse the receipt
-    let parsed = ollama
-        .parse_receipt(&image_data, None)
-        .await
-        .map_err(|e| AppError::internal(&format!("Failed to parse receipt: {}", e)))?;
+    // Parse the receipt (text first for PDFs and HTML, vision for images)
+    let parse = parse_receipt_document(
+        ollama,
+        &state.db,
+        &data,
+        ReceiptFormat::detect(&data),
+        receipt.transaction_id,
+    )
+    .await
+    .map_err(|e| AppError::internal(&format!("Failed to parse receipt: {}", e)))?;
+    let parsed = parse.receipt;
 
     // Store parsed JSON
     let raw_json = serde_json::to_string(&parsed)
@@ -221,16 +236,73 @@ pub async fn parse_receipt(
         "parse",
         Some("receipt"),
         Some(id),
-        Some(&format!("items={}", parsed.items.len())),
+        Some(&format!(
+            "items={}, via={}",
+            parsed.items.len(),
+            parse.path.as_str()
+        )),
     )?;
 
     Ok(Json(ReceiptParseResponse {
         receipt_id: id,
         parsed,
         raw_json,
+        parse_path: parse.path,
     }))
 }
 
+/// PUT /api/receipts/:id/parsed - Replace parsed receipt data with a user correction
+///
+/// The corrected data is also kept as training data for receipt parsing.
+pub async fn update_receipt_parsed(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Path(id): Path<i64>,
+    Json(parsed): Json<ParsedReceipt>,
+) -> Result<Json<Receipt>, AppError> {
+    let user_email = get_user_email(&headers);
+
+    state
+        .db
+        .get_receipt(id)?
+        .ok_or_else(|| AppError::not_found("Receipt not found"))?;
+
+    let date = match parsed.date.as_deref() {
+        Some(d) => Some(
+            chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                .map_err(|_| AppError::bad_request("date must be YYYY-MM-DD"))?,
+        ),
+        None => None,
+    };
+
+    let json = serde_json::to_string(&parsed)
+        .map_err(|e| AppError::internal(&format!("Failed to serialize parsed receipt: {}", e)))?;
+
+    state.db.correct_receipt_parsed_data(
+        id,
+        &json,
+        parsed.merchant.as_deref(),
+        date,
+        parsed.total,
+        Some(&user_email),
+    )?;
+
+    state.db.log_audit(
+        &user_email,
+        "correct_parse",
+        Some("receipt"),
+        Some(id),
+        Some(&format!("items={}", parsed.items.len())),
+    )?;
+
+    let updated = state
+        .db
+        .get_receipt(id)?
+        .ok_or_else(|| AppError::internal("Receipt not found after update"))?;
+
+    Ok(Json(updated))
+}
+
 /// Query params for listing receipts
 #[derive(Debug, Deserialize)]
 pub struct ListReceiptsQuery {
@@ -241,19 +313,21 @@ pub struct ListReceiptsQuery {
 /// GET /api/receipts - List receipts, optionally filtered by status
 pub async fn list_receipts(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Query(query): Query<ListReceiptsQuery>,
     request: Request,
 ) -> Result<Json<Vec<Receipt>>, AppError> {
     let user_email = get_user_email(request.headers());
 
+    let db = current_user.db(&state.db);
     let receipts = if let Some(status_str) = &query.status {
         let status: ReceiptStatus = status_str.parse().map_err(|_| {
             AppError::bad_request("Invalid status. Use: pending, matched, manual_review, orphaned")
         })?;
-        state.db.get_receipts_by_status(status)?
+        db.get_receipts_by_status(status)?
     } else {
         // Default to pending receipts for the workflow
-        state.db.get_pending_receipts()?
+        db.get_pending_receipts()?
     };
 
     state.db.log_audit(
@@ -277,6 +351,8 @@ pub struct PendingReceiptResponse {
     pub receipt: Receipt,
     pub image_path: String,
     pub parsed: Option<ParsedReceipt>,
+    /// How the receipt reached the model, when it was parsed
+    pub parse_path: Option<ReceiptParsePath>,
 }
 
 /// POST /api/receipts - Upload a receipt without transaction (receipt-first workflow)
@@ -292,7 +368,7 @@ pub async fn upload_pending_receipt(
         .map_err(|_| AppError::bad_request("Invalid request body or file too large (max 10MB)"))?;
 
     if bytes.is_empty() {
-        return Err(AppError::bad_request("No image data provided"));
+        return Err(AppError::bad_request("No receipt data provided"));
     }
 
     // Compute content hash for deduplication
@@ -318,7 +394,8 @@ pub async fn upload_pending_receipt(
 
     // Generate unique filename
     let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S_%3f");
-    let filename = format!("receipt_pending_{}.jpg", timestamp);
+    let format = ReceiptFormat::detect(&bytes);
+    let filename = format!("receipt_pending_{}.{}", timestamp, format.extension(&bytes));
     let image_path = receipts_dir.join(&filename);
 
     // Save the image
@@ -328,8 +405,8 @@ pub async fn upload_pending_receipt(
     let path_str = image_path.to_string_lossy().to_string();
 
     // Try to parse the receipt with Ollama if available
-    let parsed = if let Some(ref ollama) = state.ai {
-        match ollama.parse_receipt(&bytes, None).await {
+    let parse = if let Some(ref ollama) = state.ai {
+        match parse_receipt_document(ollama, &state.db, &bytes, format, None).await {
             Ok(p) => Some(p),
             Err(e) => {
                 warn!("Failed to parse receipt: {}", e);
@@ -339,6 +416,8 @@ pub async fn upload_pending_receipt(
     } else {
         None
     };
+    let parse_path = parse.as_ref().map(|p| p.path);
+    let parsed = parse.map(|p| p.receipt);
 
     // Create receipt with parsed data
     let new_receipt = NewReceipt {
@@ -372,7 +451,12 @@ pub async fn upload_pending_receipt(
         "upload",
         Some("pending_receipt"),
         Some(receipt_id),
-        Some(&format!("path={}, parsed={}", path_str, parsed.is_some())),
+        Some(&format!(
+            "path={}, format={}, parsed={}",
+            path_str,
+            format.as_str(),
+            parsed.is_some()
+        )),
     )?;
 
     let receipt = state
@@ -384,6 +468,7 @@ pub async fn upload_pending_receipt(
         receipt,
         image_path: path_str,
         parsed,
+        parse_path,
     }))
 }
 
@@ -424,6 +509,12 @@ pub async fn link_receipt_to_transaction(
     state
         .db
         .link_receipt_to_transaction(receipt_id, body.transaction_id)?;
+    state.db.record_receipt_match_feedback(
+        receipt_id,
+        body.transaction_id,
+        true,
+        Some(&user_email),
+    )?;
 
     state.db.log_audit(
         &user_email,
@@ -510,6 +601,14 @@ pub async fn unlink_receipt(
 
     // Unlink the receipt
     state.db.unlink_receipt(receipt_id)?;
+    if let Some(transaction_id) = receipt.transaction_id {
+        state.db.record_receipt_match_feedback(
+            receipt_id,
+            transaction_id,
+            false,
+            Some(&user_email),
+        )?;
+    }
 
     state.db.log_audit(
         &user_email,
@@ -536,18 +635,19 @@ const ALGO_WEIGHT: f64 = 0.6; // How much algorithmic score affects final score
 /// GET /api/receipts/:id/candidates - Get transaction match candidates for a receipt
 pub async fn get_receipt_match_candidates(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Path(receipt_id): Path<i64>,
     request: Request,
 ) -> Result<Json<Vec<ReceiptMatchCandidate>>, AppError> {
     let user_email = get_user_email(request.headers());
+    let db = current_user.db(&state.db);
 
     // Check receipt exists first for proper 404 response
-    let receipt = state
-        .db
+    let receipt = db
         .get_receipt(receipt_id)?
         .ok_or_else(|| AppError::not_found("Receipt not found"))?;
 
-    let mut candidates = state.db.find_matching_transactions(&receipt)?;
+    let mut candidates = db.find_matching_transactions(&receipt)?;
 
     // Enhance ambiguous matches with Ollama if available
     if let Some(ref ollama) = state.ai {
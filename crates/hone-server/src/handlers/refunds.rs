@@ -0,0 +1,162 @@
+//! Refund matching handlers
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, Request, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use super::AutoMatchResponse;
+use crate::{get_user_email, AppError, AppState, CurrentUser, SuccessResponse};
+use hone_core::models::{RefundCandidate, RefundMatch, RefundMatchWithTransactions};
+
+/// Map refund validation errors to client errors
+fn refund_error(e: hone_core::Error) -> AppError {
+    match e {
+        hone_core::Error::InvalidData(msg) => AppError::bad_request(&msg),
+        hone_core::Error::NotFound(msg) => AppError::not_found(&msg),
+        other => other.into(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefundListQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// GET /api/refunds - Refunds linked to their original purchases
+pub async fn list_refunds(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Query(params): Query<RefundListQuery>,
+) -> Result<Json<Vec<RefundMatchWithTransactions>>, AppError> {
+    let limit = params.limit.unwrap_or(50).clamp(1, 500);
+    let offset = params.offset.unwrap_or(0).max(0);
+    Ok(Json(
+        current_user
+            .db(&state.db)
+            .list_refund_matches(limit, offset)?,
+    ))
+}
+
+/// POST /api/refunds/auto-match - Link unmatched credits to their purchases
+pub async fn auto_match_refunds(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+) -> Result<Json<AutoMatchResponse>, AppError> {
+    let user_email = get_user_email(request.headers());
+
+    let (matched, checked) = state.db.auto_match_refunds()?;
+
+    state.db.log_audit(
+        &user_email,
+        "auto_match",
+        Some("refunds"),
+        None,
+        Some(&format!("matched={}, checked={}", matched, checked)),
+    )?;
+
+    Ok(Json(AutoMatchResponse { matched, checked }))
+}
+
+/// Refund links for one transaction
+#[derive(Debug, Serialize)]
+pub struct TransactionRefundsResponse {
+    /// The purchase this credit refunds, if it is a linked refund
+    pub refund_of: Option<RefundMatch>,
+    /// Refunds linked to this purchase
+    pub refunds: Vec<RefundMatch>,
+    /// Total refunded against this purchase
+    pub refunded_amount: f64,
+}
+
+/// GET /api/transactions/:id/refunds - Refund links for a transaction
+pub async fn get_transaction_refunds(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> Result<Json<TransactionRefundsResponse>, AppError> {
+    state
+        .db
+        .get_transaction(id)?
+        .ok_or_else(|| AppError::not_found("Transaction not found"))?;
+
+    Ok(Json(TransactionRefundsResponse {
+        refund_of: state.db.get_refund_match(id)?,
+        refunds: state.db.get_refunds_for_transaction(id)?,
+        refunded_amount: state.db.get_refunded_amount(id)?,
+    }))
+}
+
+/// GET /api/transactions/:id/refund-candidates - Purchases a credit may refund
+pub async fn get_refund_candidates(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Json<Vec<RefundCandidate>>, AppError> {
+    let candidates = current_user
+        .db(&state.db)
+        .find_refund_candidates(id)
+        .map_err(refund_error)?;
+    Ok(Json(candidates))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LinkRefundRequest {
+    pub original_transaction_id: i64,
+    /// Returned split item on the original purchase
+    pub split_id: Option<i64>,
+    /// Portion of the purchase refunded (defaults to the whole credit)
+    pub amount: Option<f64>,
+}
+
+/// POST /api/transactions/:id/refund - Link a credit to its original purchase
+pub async fn link_refund(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Path(id): Path<i64>,
+    Json(req): Json<LinkRefundRequest>,
+) -> Result<Json<RefundMatch>, AppError> {
+    let user_email = get_user_email(&headers);
+
+    let refund_match = state
+        .db
+        .link_refund(id, req.original_transaction_id, req.split_id, req.amount)
+        .map_err(refund_error)?;
+
+    state.db.log_audit(
+        &user_email,
+        "link",
+        Some("refund"),
+        Some(id),
+        Some(&format!(
+            "original_transaction_id={}, split_id={:?}, amount={:.2}",
+            refund_match.original_transaction_id, refund_match.split_id, refund_match.amount
+        )),
+    )?;
+
+    Ok(Json(refund_match))
+}
+
+/// DELETE /api/transactions/:id/refund - Unlink a refund
+///
+/// The matcher won't link this credit again on its own.
+pub async fn unlink_refund(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    request: Request,
+) -> Result<Json<SuccessResponse>, AppError> {
+    let user_email = get_user_email(request.headers());
+
+    if !state.db.unlink_refund(id)? {
+        return Err(AppError::not_found("Transaction is not a linked refund"));
+    }
+
+    state
+        .db
+        .log_audit(&user_email, "unlink", Some("refund"), Some(id), None)?;
+
+    Ok(Json(SuccessResponse { success: true }))
+}
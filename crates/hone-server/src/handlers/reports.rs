@@ -9,10 +9,11 @@ use axum::{
 use chrono::{Datelike, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::{get_user_email, AppError, AppState};
+use crate::{get_user_email, AppError, AppState, CurrentUser};
 use hone_core::models::{
-    Entity, Granularity, LocationSpending, MerchantsReport, PropertyExpenseSummary, SavingsReport,
-    SpendingSummary, SubscriptionSummaryReport, TagSpending, TrendsReport, VehicleCostSummary,
+    Entity, Granularity, ItemInflationReport, ItemPriceHistory, LocationSpending, MerchantsReport,
+    PropertyExpenseSummary, SavingsReport, SpendingSummary, SplitTypeReport,
+    SubscriptionSummaryReport, TagSpending, TrendsReport, VehicleCostSummary, WarrantyReport,
 };
 
 /// Query parameters for spending by tag report
@@ -25,6 +26,7 @@ pub struct SpendingByTagQuery {
 /// GET /api/reports/by-tag - Get spending report by tag
 pub async fn report_by_tag(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Query(params): Query<SpendingByTagQuery>,
     request: Request,
 ) -> Result<Json<Vec<TagSpending>>, AppError> {
@@ -44,7 +46,9 @@ pub async fn report_by_tag(
         .transpose()
         .map_err(|_| AppError::bad_request("Invalid to date format (use YYYY-MM-DD)"))?;
 
-    let spending = state.db.get_spending_by_tag(from_date, to_date)?;
+    let spending = current_user
+        .db(&state.db)
+        .get_spending_by_tag(from_date, to_date)?;
 
     state.db.log_audit(
         &user_email,
@@ -78,11 +82,14 @@ pub struct ReportSpendingQuery {
     pub entity_id: Option<i64>,
     /// Filter by card member name
     pub card_member: Option<String>,
+    /// Subtract linked refunds from the original purchases
+    pub net_refunds: Option<bool>,
 }
 
 /// GET /api/reports/spending - Spending summary by category
 pub async fn report_spending(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Query(params): Query<ReportSpendingQuery>,
     request: Request,
 ) -> Result<Json<SpendingSummary>, AppError> {
@@ -92,13 +99,14 @@ pub async fn report_spending(
     let (from_date, to_date) =
         resolve_period(period, params.from.as_deref(), params.to.as_deref())?;
 
-    let summary = state.db.get_spending_summary(
+    let summary = current_user.db(&state.db).get_spending_summary(
         from_date,
         to_date,
         params.tag.as_deref(),
         params.expand.unwrap_or(false),
         params.entity_id,
         params.card_member.as_deref(),
+        params.net_refunds.unwrap_or(false),
     )?;
 
     state.db.log_audit(
@@ -127,11 +135,14 @@ pub struct ReportTrendsQuery {
     pub entity_id: Option<i64>,
     /// Filter by card member name
     pub card_member: Option<String>,
+    /// Subtract linked refunds from the original purchases
+    pub net_refunds: Option<bool>,
 }
 
 /// GET /api/reports/trends - Spending trends over time
 pub async fn report_trends(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Query(params): Query<ReportTrendsQuery>,
     request: Request,
 ) -> Result<Json<TrendsReport>, AppError> {
@@ -147,13 +158,14 @@ pub async fn report_trends(
         .parse()
         .map_err(|e: String| AppError::bad_request(&e))?;
 
-    let report = state.db.get_spending_trends(
+    let report = current_user.db(&state.db).get_spending_trends(
         from_date,
         to_date,
         granularity,
         params.tag.as_deref(),
         params.entity_id,
         params.card_member.as_deref(),
+        params.net_refunds.unwrap_or(false),
     )?;
 
     state.db.log_audit(
@@ -191,6 +203,7 @@ pub struct ReportMerchantsQuery {
 /// GET /api/reports/merchants - Top merchants by spending
 pub async fn report_merchants(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Query(params): Query<ReportMerchantsQuery>,
     request: Request,
 ) -> Result<Json<MerchantsReport>, AppError> {
@@ -200,7 +213,7 @@ pub async fn report_merchants(
     let (from_date, to_date) = resolve_period(period, None, None)?;
     let limit = params.limit.unwrap_or(10).min(100); // Cap at 100
 
-    let report = state.db.get_top_merchants(
+    let report = current_user.db(&state.db).get_top_merchants(
         from_date,
         to_date,
         limit,
@@ -226,11 +239,12 @@ pub async fn report_merchants(
 /// GET /api/reports/subscriptions - Subscription summary
 pub async fn report_subscriptions(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     request: Request,
 ) -> Result<Json<SubscriptionSummaryReport>, AppError> {
     let user_email = get_user_email(request.headers());
 
-    let report = state.db.get_subscription_summary()?;
+    let report = current_user.db(&state.db).get_subscription_summary()?;
 
     state.db.log_audit(
         &user_email,
@@ -249,11 +263,12 @@ pub async fn report_subscriptions(
 /// GET /api/reports/savings - Savings from cancelled subscriptions
 pub async fn report_savings(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     request: Request,
 ) -> Result<Json<SavingsReport>, AppError> {
     let user_email = get_user_email(request.headers());
 
-    let report = state.db.get_savings_report()?;
+    let report = current_user.db(&state.db).get_savings_report()?;
 
     state.db.log_audit(
         &user_email,
@@ -299,6 +314,7 @@ pub struct EntitySpending {
 /// GET /api/reports/by-entity - Spending by entity
 pub async fn report_by_entity(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Query(params): Query<ReportByEntityQuery>,
     request: Request,
 ) -> Result<Json<EntitySpendingReport>, AppError> {
@@ -308,7 +324,9 @@ pub async fn report_by_entity(
     let (from_date, to_date) =
         resolve_period(period, params.from.as_deref(), params.to.as_deref())?;
 
-    let spending_data = state.db.get_spending_by_entity(from_date, to_date)?;
+    let spending_data = current_user
+        .db(&state.db)
+        .get_spending_by_entity(from_date, to_date)?;
 
     let mut total = 0.0;
     let entities: Vec<EntitySpending> = spending_data
@@ -359,6 +377,7 @@ pub struct ReportByLocationQuery {
 /// GET /api/reports/by-location - Spending by location
 pub async fn report_by_location(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Query(params): Query<ReportByLocationQuery>,
     request: Request,
 ) -> Result<Json<Vec<LocationSpending>>, AppError> {
@@ -368,7 +387,9 @@ pub async fn report_by_location(
     let (from_date, to_date) =
         resolve_period(period, params.from.as_deref(), params.to.as_deref())?;
 
-    let spending = state.db.get_spending_by_location(from_date, to_date)?;
+    let spending = current_user
+        .db(&state.db)
+        .get_spending_by_location(from_date, to_date)?;
 
     state.db.log_audit(
         &user_email,
@@ -401,6 +422,7 @@ pub struct ReportVehicleCostsQuery {
 /// GET /api/reports/vehicle-costs/:id - Get vehicle cost summary
 pub async fn report_vehicle_costs(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Path(id): Path<i64>,
     Query(params): Query<ReportVehicleCostsQuery>,
     request: Request,
@@ -411,7 +433,9 @@ pub async fn report_vehicle_costs(
     let (from_date, to_date) =
         resolve_period(period, params.from.as_deref(), params.to.as_deref())?;
 
-    let report = state.db.get_vehicle_cost_summary(id, from_date, to_date)?;
+    let report = current_user
+        .db(&state.db)
+        .get_vehicle_cost_summary(id, from_date, to_date)?;
 
     state.db.log_audit(
         &user_email,
@@ -441,6 +465,7 @@ pub struct ReportPropertyExpensesQuery {
 /// GET /api/reports/property-expenses/:id - Get property expense summary
 pub async fn report_property_expenses(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Path(id): Path<i64>,
     Query(params): Query<ReportPropertyExpensesQuery>,
     request: Request,
@@ -451,8 +476,8 @@ pub async fn report_property_expenses(
     let (from_date, to_date) =
         resolve_period(period, params.from.as_deref(), params.to.as_deref())?;
 
-    let report = state
-        .db
+    let report = current_user
+        .db(&state.db)
         .get_property_expense_summary(id, from_date, to_date)?;
 
     state.db.log_audit(
@@ -469,6 +494,174 @@ pub async fn report_property_expenses(
     Ok(Json(report))
 }
 
+/// GET /api/reports/items/:id/prices - Price history of a catalog item
+pub async fn report_item_prices(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Path(id): Path<i64>,
+    request: Request,
+) -> Result<Json<ItemPriceHistory>, AppError> {
+    let user_email = get_user_email(request.headers());
+
+    let report = current_user
+        .db(&state.db)
+        .get_item_price_history(id)
+        .map_err(|e| match e {
+            hone_core::Error::NotFound(msg) => AppError::not_found(&msg),
+            other => other.into(),
+        })?;
+
+    state.db.log_audit(
+        &user_email,
+        "report",
+        Some("item_prices"),
+        Some(id),
+        Some(&format!("points={}", report.points.len())),
+    )?;
+
+    Ok(Json(report))
+}
+
+/// Query parameters for item inflation report
+#[derive(Debug, Deserialize)]
+pub struct ReportItemInflationQuery {
+    /// Period preset
+    pub period: Option<String>,
+    /// Custom start date (YYYY-MM-DD)
+    pub from: Option<String>,
+    /// Custom end date (YYYY-MM-DD)
+    pub to: Option<String>,
+    /// Only items bought at this merchant
+    pub merchant: Option<String>,
+}
+
+/// GET /api/reports/item-inflation - Price changes of catalog items
+pub async fn report_item_inflation(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Query(params): Query<ReportItemInflationQuery>,
+    request: Request,
+) -> Result<Json<ItemInflationReport>, AppError> {
+    let user_email = get_user_email(request.headers());
+
+    let period = params.period.as_deref().unwrap_or("last-12-months");
+    let (from_date, to_date) =
+        resolve_period(period, params.from.as_deref(), params.to.as_deref())?;
+
+    let report = current_user.db(&state.db).get_item_inflation_report(
+        from_date,
+        to_date,
+        params.merchant.as_deref(),
+    )?;
+
+    state.db.log_audit(
+        &user_email,
+        "report",
+        Some("item_inflation"),
+        None,
+        Some(&format!(
+            "period={}, merchant={:?}, items={}",
+            period,
+            params.merchant,
+            report.items.len()
+        )),
+    )?;
+
+    Ok(Json(report))
+}
+
+/// Query parameters for split type report
+#[derive(Debug, Deserialize)]
+pub struct ReportSplitTypesQuery {
+    /// Period preset
+    pub period: Option<String>,
+    /// Custom start date (YYYY-MM-DD)
+    pub from: Option<String>,
+    /// Custom end date (YYYY-MM-DD)
+    pub to: Option<String>,
+    /// Top merchants to list per split type
+    pub merchant_limit: Option<usize>,
+}
+
+/// GET /api/reports/split-types - Split totals by type (tax, tip, fees, ...)
+pub async fn report_split_types(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Query(params): Query<ReportSplitTypesQuery>,
+    request: Request,
+) -> Result<Json<SplitTypeReport>, AppError> {
+    let user_email = get_user_email(request.headers());
+
+    let period = params.period.as_deref().unwrap_or("this-year");
+    let (from_date, to_date) =
+        resolve_period(period, params.from.as_deref(), params.to.as_deref())?;
+    let merchant_limit = params.merchant_limit.unwrap_or(5).min(50);
+
+    let report =
+        current_user
+            .db(&state.db)
+            .get_split_type_report(from_date, to_date, merchant_limit)?;
+
+    state.db.log_audit(
+        &user_email,
+        "report",
+        Some("split_types"),
+        None,
+        Some(&format!(
+            "period={}, from={}, to={}",
+            period, from_date, to_date
+        )),
+    )?;
+
+    Ok(Json(report))
+}
+
+/// Query parameters for warranty report
+#[derive(Debug, Deserialize)]
+pub struct ReportWarrantiesQuery {
+    /// Match item, merchant, serial number or notes
+    pub q: Option<String>,
+    /// Report date (YYYY-MM-DD, defaults to today)
+    pub as_of: Option<String>,
+}
+
+/// GET /api/reports/warranties - What we own under warranty or can still return
+pub async fn report_warranties(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Query(params): Query<ReportWarrantiesQuery>,
+    request: Request,
+) -> Result<Json<WarrantyReport>, AppError> {
+    let user_email = get_user_email(request.headers());
+
+    let as_of = params
+        .as_of
+        .as_ref()
+        .map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d"))
+        .transpose()
+        .map_err(|_| AppError::bad_request("Invalid as_of date format (use YYYY-MM-DD)"))?
+        .unwrap_or_else(|| Utc::now().date_naive());
+
+    let report = current_user
+        .db(&state.db)
+        .get_warranty_report(params.q.as_deref(), as_of)?;
+
+    state.db.log_audit(
+        &user_email,
+        "report",
+        Some("warranties"),
+        None,
+        Some(&format!(
+            "q={:?}, as_of={}, items={}",
+            params.q,
+            as_of,
+            report.items.len()
+        )),
+    )?;
+
+    Ok(Json(report))
+}
+
 /// Helper: Resolve period string to date range
 pub fn resolve_period(
     period: &str,
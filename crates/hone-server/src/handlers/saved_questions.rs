@@ -0,0 +1,243 @@
+//! Saved explore question handlers
+//!
+//! Saved questions are explore prompts that the digest scheduler runs on an
+//! interval. Each run is stored as a digest with its tool-call trace.
+//!
+//! Members see and run the questions they created; owners see every
+//! question. Runs only read the accounts the question's creator may see.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::Deserialize;
+
+use crate::{get_user_email, run_saved_question, AppError, AppState, CurrentUser, MAX_PAGE_LIMIT};
+use hone_core::models::{ExploreDigest, NewSavedQuestion, SavedQuestion, UserRole};
+
+/// Request body for creating a saved question
+#[derive(Debug, Deserialize)]
+pub struct CreateSavedQuestionRequest {
+    pub name: String,
+    pub query: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Hours between runs (default 24)
+    #[serde(default)]
+    pub interval_hours: Option<i64>,
+}
+
+/// Request body for updating a saved question
+#[derive(Debug, Deserialize)]
+pub struct UpdateSavedQuestionRequest {
+    pub name: Option<String>,
+    pub query: Option<String>,
+    pub model: Option<Option<String>>,
+    pub interval_hours: Option<i64>,
+    pub enabled: Option<bool>,
+}
+
+/// Query parameters for listing digests
+#[derive(Debug, Deserialize)]
+pub struct DigestQuery {
+    #[serde(default = "default_digest_limit")]
+    pub limit: i64,
+}
+
+fn default_digest_limit() -> i64 {
+    20
+}
+
+fn validate_interval(hours: i64) -> Result<i64, AppError> {
+    if !(1..=24 * 90).contains(&hours) {
+        return Err(AppError::bad_request(
+            "interval_hours must be between 1 and 2160 (90 days)",
+        ));
+    }
+    Ok(hours)
+}
+
+/// Creator whose questions the caller may access (None for owners, who see every question)
+pub(crate) fn question_creator(current_user: &CurrentUser) -> Option<&str> {
+    (current_user.role != UserRole::Owner).then_some(current_user.email.as_str())
+}
+
+/// Load a saved question the caller may access (404 if missing or another member's)
+fn visible_question(
+    state: &AppState,
+    current_user: &CurrentUser,
+    id: i64,
+) -> Result<SavedQuestion, AppError> {
+    state
+        .db
+        .get_saved_question(id)?
+        .filter(|q| match question_creator(current_user) {
+            Some(email) => q.created_by.as_deref() == Some(email),
+            None => true,
+        })
+        .ok_or_else(|| AppError::not_found("Saved question not found"))
+}
+
+/// GET /api/explore/saved - List saved questions
+pub async fn list_saved_questions(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+) -> Result<Json<Vec<SavedQuestion>>, AppError> {
+    Ok(Json(
+        state
+            .db
+            .list_saved_questions(question_creator(&current_user))?,
+    ))
+}
+
+/// POST /api/explore/saved - Create a saved question
+pub async fn create_saved_question(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<CreateSavedQuestionRequest>,
+) -> Result<Json<SavedQuestion>, AppError> {
+    let user_email = get_user_email(&headers);
+
+    let name = req.name.trim();
+    let query = req.query.trim();
+    if name.is_empty() || query.is_empty() {
+        return Err(AppError::bad_request("name and query are required"));
+    }
+    let interval_hours = validate_interval(req.interval_hours.unwrap_or(24))?;
+
+    let id = state.db.create_saved_question(&NewSavedQuestion {
+        name: name.to_string(),
+        query: query.to_string(),
+        model: req.model.filter(|m| !m.trim().is_empty()),
+        interval_hours,
+        created_by: Some(user_email.clone()),
+    })?;
+
+    state.db.log_audit(
+        &user_email,
+        "create",
+        Some("saved_question"),
+        Some(id),
+        Some(&format!("name={}, interval_hours={}", name, interval_hours)),
+    )?;
+
+    let question = state
+        .db
+        .get_saved_question(id)?
+        .ok_or_else(|| AppError::internal("Saved question not found after creation"))?;
+
+    Ok(Json(question))
+}
+
+/// GET /api/explore/saved/:id - Get a saved question
+pub async fn get_saved_question(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Json<SavedQuestion>, AppError> {
+    Ok(Json(visible_question(&state, &current_user, id)?))
+}
+
+/// PATCH /api/explore/saved/:id - Update a saved question
+pub async fn update_saved_question(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+    Json(req): Json<UpdateSavedQuestionRequest>,
+) -> Result<Json<SavedQuestion>, AppError> {
+    let user_email = get_user_email(&headers);
+
+    visible_question(&state, &current_user, id)?;
+    if req.name.as_deref().is_some_and(|n| n.trim().is_empty())
+        || req.query.as_deref().is_some_and(|q| q.trim().is_empty())
+    {
+        return Err(AppError::bad_request("name and query cannot be empty"));
+    }
+    let interval_hours = req.interval_hours.map(validate_interval).transpose()?;
+
+    state.db.update_saved_question(
+        id,
+        req.name.as_deref().map(str::trim),
+        req.query.as_deref().map(str::trim),
+        req.model.as_ref().map(|m| m.as_deref()),
+        interval_hours,
+        req.enabled,
+    )?;
+
+    state.db.log_audit(
+        &user_email,
+        "update",
+        Some("saved_question"),
+        Some(id),
+        None,
+    )?;
+
+    let question = state
+        .db
+        .get_saved_question(id)?
+        .ok_or_else(|| AppError::not_found("Saved question not found"))?;
+    Ok(Json(question))
+}
+
+/// DELETE /api/explore/saved/:id - Delete a saved question and its digests
+pub async fn delete_saved_question(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user_email = get_user_email(&headers);
+
+    visible_question(&state, &current_user, id)?;
+    let deleted = state.db.delete_saved_question(id)?;
+    if !deleted {
+        return Err(AppError::not_found("Saved question not found"));
+    }
+
+    state.db.log_audit(
+        &user_email,
+        "delete",
+        Some("saved_question"),
+        Some(id),
+        None,
+    )?;
+
+    Ok(Json(serde_json::json!({ "deleted": true })))
+}
+
+/// POST /api/explore/saved/:id/run - Run a saved question now
+pub async fn run_saved_question_now(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+) -> Result<Json<ExploreDigest>, AppError> {
+    let user_email = get_user_email(&headers);
+
+    let orchestrator = state.orchestrator.as_ref().ok_or_else(|| {
+        AppError::bad_request(
+            "Saved questions require AI backend. Set ANTHROPIC_COMPATIBLE_HOST and ANTHROPIC_COMPATIBLE_MODEL.",
+        )
+    })?;
+    let question = visible_question(&state, &current_user, id)?;
+
+    let digest = run_saved_question(&state.db, orchestrator, &question, &user_email).await?;
+
+    Ok(Json(digest))
+}
+
+/// GET /api/explore/saved/:id/digests - List digests for a saved question (newest first)
+pub async fn list_saved_question_digests(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Path(id): Path<i64>,
+    Query(params): Query<DigestQuery>,
+) -> Result<Json<Vec<ExploreDigest>>, AppError> {
+    visible_question(&state, &current_user, id)?;
+    let limit = params.limit.clamp(1, MAX_PAGE_LIMIT);
+    Ok(Json(state.db.list_explore_digests(id, limit)?))
+}
@@ -0,0 +1,55 @@
+//! Scheduled task handlers
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+use crate::{AppError, AppState, CurrentUser};
+use hone_core::models::{Job, UserRole};
+use hone_core::schedule::{ScheduleStatus, Scheduler};
+
+/// GET /api/schedules - List scheduled tasks with their next and last runs
+pub async fn list_schedules(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<ScheduleStatus>>, AppError> {
+    let scheduler = Scheduler::new(state.db.clone(), &state.config.schedules)?;
+    Ok(Json(scheduler.statuses()?))
+}
+
+/// POST /api/schedules/:name/run - Queue a scheduled task now
+///
+/// The task's regular schedule is unchanged.
+pub async fn run_schedule(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Path(name): Path<String>,
+) -> Result<Json<Job>, AppError> {
+    let task = state
+        .config
+        .schedules
+        .task(&name)
+        .ok_or_else(|| AppError::not_found("Scheduled task not found"))?;
+    if task.task.requires_owner() {
+        user.require(UserRole::Owner)?;
+    }
+
+    let scheduler = Scheduler::new(state.db.clone(), &state.config.schedules)?;
+    let id = scheduler.run_now(&name, &user.email)?;
+
+    state.db.log_audit(
+        &user.email,
+        "run",
+        Some("schedule"),
+        Some(id),
+        Some(&format!("name={}, type={}", name, task.task)),
+    )?;
+
+    let job = state
+        .db
+        .get_job(id)?
+        .ok_or_else(|| AppError::internal("Failed to retrieve queued job"))?;
+    Ok(Json(job))
+}
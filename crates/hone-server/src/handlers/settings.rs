@@ -0,0 +1,170 @@
+//! Settings handlers (detection thresholds)
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Serialize;
+
+use crate::{AppError, AppState, CurrentUser};
+use hone_core::db::DetectionScope;
+use hone_core::detect::{
+    DetectionConfig, DetectionOverrides, MerchantDetectionOverride, TagDetectionOverride,
+};
+
+/// Detection settings with the values in effect
+#[derive(Debug, Serialize)]
+pub struct DetectionSettingsResponse {
+    /// Built-in defaults
+    pub defaults: DetectionConfig,
+    /// Household-wide changes to the defaults
+    pub global: DetectionOverrides,
+    /// Household-wide values in effect (defaults + global)
+    pub effective: DetectionConfig,
+    pub merchants: Vec<MerchantDetectionOverride>,
+    pub tags: Vec<TagDetectionOverride>,
+}
+
+fn settings_response(state: &AppState) -> Result<DetectionSettingsResponse, AppError> {
+    let settings = state.db.get_detection_settings()?;
+    Ok(DetectionSettingsResponse {
+        defaults: DetectionConfig::default(),
+        effective: settings.config(),
+        global: settings.global,
+        merchants: settings.merchants,
+        tags: settings.tags,
+    })
+}
+
+/// Validate and save one scope's overrides, then audit the change
+fn save_overrides(
+    state: &AppState,
+    user: &CurrentUser,
+    scope: DetectionScope,
+    overrides: &DetectionOverrides,
+) -> Result<(), AppError> {
+    overrides
+        .validate()
+        .map_err(|e| AppError::bad_request(&e.to_string()))?;
+    if let DetectionScope::Merchant(merchant) = &scope {
+        if merchant.trim().is_empty() {
+            return Err(AppError::bad_request("Merchant name can't be empty"));
+        }
+    }
+    if let DetectionScope::Tag(id) = scope {
+        state
+            .db
+            .get_tag(id)?
+            .ok_or_else(|| AppError::not_found("Tag not found"))?;
+    }
+
+    state
+        .db
+        .set_detection_overrides(&scope, overrides, &user.email)?;
+    state.db.log_audit(
+        &user.email,
+        "update",
+        Some("detection_settings"),
+        None,
+        Some(&format!(
+            "{}, settings={}",
+            scope_label(&scope),
+            serde_json::to_string(overrides).unwrap_or_default()
+        )),
+    )?;
+    Ok(())
+}
+
+fn delete_overrides(
+    state: &AppState,
+    user: &CurrentUser,
+    scope: DetectionScope,
+) -> Result<(), AppError> {
+    if !state.db.delete_detection_overrides(&scope)? {
+        return Err(AppError::not_found("No detection overrides for this scope"));
+    }
+    state.db.log_audit(
+        &user.email,
+        "delete",
+        Some("detection_settings"),
+        None,
+        Some(&scope_label(&scope)),
+    )?;
+    Ok(())
+}
+
+fn scope_label(scope: &DetectionScope) -> String {
+    match scope {
+        DetectionScope::Global => "scope=global".to_string(),
+        DetectionScope::Merchant(m) => format!("scope=merchant, merchant={}", m),
+        DetectionScope::Tag(id) => format!("scope=tag, tag_id={}", id),
+    }
+}
+
+/// GET /api/settings/detection - Detection settings, overrides and effective values
+pub async fn get_detection_settings(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<DetectionSettingsResponse>, AppError> {
+    Ok(Json(settings_response(&state)?))
+}
+
+/// PUT /api/settings/detection - Replace the household-wide detection settings
+///
+/// Only the fields present are saved; omitted fields use the defaults.
+pub async fn update_detection_settings(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Json(overrides): Json<DetectionOverrides>,
+) -> Result<Json<DetectionSettingsResponse>, AppError> {
+    save_overrides(&state, &user, DetectionScope::Global, &overrides)?;
+    Ok(Json(settings_response(&state)?))
+}
+
+/// PUT /api/settings/detection/merchants/:merchant - Set a merchant's overrides
+pub async fn set_merchant_detection_overrides(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Path(merchant): Path<String>,
+    Json(overrides): Json<DetectionOverrides>,
+) -> Result<Json<DetectionSettingsResponse>, AppError> {
+    save_overrides(
+        &state,
+        &user,
+        DetectionScope::Merchant(merchant),
+        &overrides,
+    )?;
+    Ok(Json(settings_response(&state)?))
+}
+
+/// DELETE /api/settings/detection/merchants/:merchant - Remove a merchant's overrides
+pub async fn delete_merchant_detection_overrides(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Path(merchant): Path<String>,
+) -> Result<Json<DetectionSettingsResponse>, AppError> {
+    delete_overrides(&state, &user, DetectionScope::Merchant(merchant))?;
+    Ok(Json(settings_response(&state)?))
+}
+
+/// PUT /api/settings/detection/tags/:tag_id - Set overrides for a tag and its children
+pub async fn set_tag_detection_overrides(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Path(tag_id): Path<i64>,
+    Json(overrides): Json<DetectionOverrides>,
+) -> Result<Json<DetectionSettingsResponse>, AppError> {
+    save_overrides(&state, &user, DetectionScope::Tag(tag_id), &overrides)?;
+    Ok(Json(settings_response(&state)?))
+}
+
+/// DELETE /api/settings/detection/tags/:tag_id - Remove a tag's overrides
+pub async fn delete_tag_detection_overrides(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Path(tag_id): Path<i64>,
+) -> Result<Json<DetectionSettingsResponse>, AppError> {
+    delete_overrides(&state, &user, DetectionScope::Tag(tag_id))?;
+    Ok(Json(settings_response(&state)?))
+}
@@ -8,8 +8,10 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::{get_user_email, AppError, AppState, SuccessResponse};
-use hone_core::models::Subscription;
+use crate::{get_user_email, AppError, AppState, CurrentUser, SuccessResponse};
+use hone_core::models::{
+    CancellationStatus, NewSubscriptionCancellation, Subscription, SubscriptionCancellation,
+};
 
 /// Query params for listing subscriptions
 #[derive(Debug, Deserialize)]
@@ -21,12 +23,15 @@ pub struct ListSubscriptionsQuery {
 /// GET /api/subscriptions - List all subscriptions
 pub async fn list_subscriptions(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Query(query): Query<ListSubscriptionsQuery>,
     request: Request,
 ) -> Result<Json<Vec<Subscription>>, AppError> {
     let user_email = get_user_email(request.headers());
 
-    let subscriptions = state.db.list_subscriptions(query.account_id)?;
+    let subscriptions = current_user
+        .db(&state.db)
+        .list_subscriptions(query.account_id)?;
 
     // Audit log - read access
     state.db.log_audit(
@@ -71,23 +76,85 @@ pub async fn acknowledge_subscription(
 pub struct CancelResponse {
     pub success: bool,
     pub id: i64,
+    /// The tracked cancellation, verified by later detection runs
+    pub cancellation: SubscriptionCancellation,
 }
 
 /// POST /api/subscriptions/:id/cancel - Mark subscription as cancelled
+///
+/// The optional body records how it was cancelled (method, confirmation
+/// number, expected final charge and refund) for verification.
 pub async fn cancel_subscription(
     State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
     Path(id): Path<i64>,
-    request: Request,
+    body: Option<Json<NewSubscriptionCancellation>>,
 ) -> Result<Json<CancelResponse>, AppError> {
-    let user_email = get_user_email(request.headers());
-
-    state.db.cancel_subscription(id, None)?;
+    let user_email = get_user_email(&headers);
+    let details = body.map(|Json(details)| details).unwrap_or_default();
 
-    state
+    let cancellation = state
         .db
-        .log_audit(&user_email, "cancel", Some("subscription"), Some(id), None)?;
+        .record_cancellation(id, &details)
+        .map_err(|e| match e {
+            hone_core::Error::InvalidData(msg) => AppError::bad_request(&msg),
+            hone_core::Error::NotFound(msg) => AppError::not_found(&msg),
+            other => other.into(),
+        })?;
+
+    state.db.log_audit(
+        &user_email,
+        "cancel",
+        Some("subscription"),
+        Some(id),
+        Some(&format!(
+            "cancelled_on={}, method={}, final_charge={:?}",
+            cancellation.cancelled_on,
+            cancellation.method.map_or("unknown", |m| m.as_str()),
+            cancellation.expected_final_charge
+        )),
+    )?;
+
+    Ok(Json(CancelResponse {
+        success: true,
+        id,
+        cancellation,
+    }))
+}
+
+/// GET /api/subscriptions/:id/cancellation - Latest tracked cancellation
+pub async fn get_subscription_cancellation(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Json<SubscriptionCancellation>, AppError> {
+    let cancellation = current_user
+        .db(&state.db)
+        .get_subscription_cancellation(id)?
+        .ok_or_else(|| AppError::not_found("No tracked cancellation for this subscription"))?;
+    Ok(Json(cancellation))
+}
+
+/// Query params for listing cancellations
+#[derive(Debug, Deserialize)]
+pub struct ListCancellationsQuery {
+    /// Filter by verification status (pending, confirmed, charged_after)
+    pub status: Option<String>,
+}
 
-    Ok(Json(CancelResponse { success: true, id }))
+/// GET /api/cancellations - Tracked cancellations and their verification status
+pub async fn list_cancellations(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Query(query): Query<ListCancellationsQuery>,
+) -> Result<Json<Vec<SubscriptionCancellation>>, AppError> {
+    let status = query
+        .status
+        .as_deref()
+        .map(|s| s.parse::<CancellationStatus>())
+        .transpose()
+        .map_err(|e| AppError::bad_request(&e))?;
+    Ok(Json(current_user.db(&state.db).list_cancellations(status)?))
 }
 
 /// POST /api/subscriptions/:id/exclude - Exclude from detection (not a subscription)
@@ -20,7 +20,7 @@ use crate::{AppError, AppState};
 /// Query params for training export
 #[derive(Debug, Deserialize)]
 pub struct TrainingExportQuery {
-    /// Task to export: classify_merchant, normalize_merchant, classify_subscription
+    /// Task to export: classify_merchant, normalize_merchant, classify_subscription, suggest_entity, suggest_split, evaluate_receipt_match, parse_receipt
     pub task: String,
 }
 
@@ -104,7 +104,7 @@ pub async fn training_export(
 ) -> Result<Response, AppError> {
     let task = TrainingTask::from_str(&params.task).ok_or_else(|| {
         AppError::bad_request(&format!(
-            "Unknown task: {}. Valid tasks: classify_merchant, normalize_merchant, classify_subscription",
+            "Unknown task: {}. Valid tasks: classify_merchant, normalize_merchant, classify_subscription, suggest_entity, suggest_split, evaluate_receipt_match, parse_receipt",
             params.task
         ))
     })?;
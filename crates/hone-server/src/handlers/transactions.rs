@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 use tracing::warn;
 
 use super::reports::resolve_period;
-use crate::{get_user_email, AppError, AppState, MAX_PAGE_LIMIT};
+use crate::{get_user_email, AppError, AppState, CurrentUser, MAX_PAGE_LIMIT};
 use hone_core::models::{TagSource, Transaction, TransactionTagWithDetails};
 
 /// Query parameters for listing transactions
@@ -60,6 +60,7 @@ pub struct TransactionResponse {
 pub async fn list_transactions(
     State(state): State<Arc<AppState>>,
     Query(params): Query<TransactionQuery>,
+    current_user: CurrentUser,
     request: Request,
 ) -> Result<Json<TransactionResponse>, AppError> {
     let user_email = get_user_email(request.headers());
@@ -96,7 +97,8 @@ pub async fn list_transactions(
     let sort_field = params.sort.as_deref();
     let sort_order = params.order.as_deref();
     let untagged = params.untagged.unwrap_or(false);
-    let transactions = state.db.search_transactions_full(
+    let db = current_user.db(&state.db);
+    let transactions = db.search_transactions_full(
         params.account_id,
         params.entity_id,
         card_member,
@@ -110,7 +112,7 @@ pub async fn list_transactions(
         limit,
         offset,
     )?;
-    let total = state.db.count_transactions_full(
+    let total = db.count_transactions_full(
         params.account_id,
         params.entity_id,
         card_member,
@@ -9,7 +9,7 @@ use axum::{
 };
 use serde::Deserialize;
 
-use crate::{get_user_email, AppError, AppState};
+use crate::{get_user_email, AppError, AppState, CurrentUser};
 use hone_core::models::{NewTrip, Transaction, Trip, TripWithSpending};
 
 #[derive(Debug, Deserialize)]
@@ -235,12 +235,13 @@ pub async fn archive_trip(
 /// GET /api/trips/:id/transactions - Get transactions assigned to a trip
 pub async fn get_trip_transactions(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Path(id): Path<i64>,
     request: Request,
 ) -> Result<Json<Vec<Transaction>>, AppError> {
     let user_email = get_user_email(request.headers());
 
-    let transactions = state.db.get_trip_transactions(id)?;
+    let transactions = current_user.db(&state.db).get_trip_transactions(id)?;
 
     state.db.log_audit(
         &user_email,
@@ -256,6 +257,7 @@ pub async fn get_trip_transactions(
 /// GET /api/trips/:id/spending - Get spending summary for a trip
 pub async fn get_trip_spending(
     State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
     Path(id): Path<i64>,
     request: Request,
 ) -> Result<Json<TripWithSpending>, AppError> {
@@ -266,7 +268,7 @@ pub async fn get_trip_spending(
         .get_trip(id)?
         .ok_or_else(|| AppError::not_found("Trip not found"))?;
 
-    let (total_spent, transaction_count) = state.db.get_trip_spending(id)?;
+    let (total_spent, transaction_count) = current_user.db(&state.db).get_trip_spending(id)?;
 
     // Get location name if trip has a location
     let location_name = if let Some(loc_id) = trip.location_id {
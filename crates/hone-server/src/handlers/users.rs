@@ -0,0 +1,197 @@
+//! Household user management handlers (owner only)
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppError, AppState, CurrentUser};
+use hone_core::models::{User, UserRole};
+
+/// Request body for adding a user
+#[derive(Debug, Deserialize)]
+pub struct CreateUserRequest {
+    pub email: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    pub role: UserRole,
+}
+
+/// Request body for updating a user
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserRequest {
+    pub display_name: Option<Option<String>>,
+    pub role: Option<UserRole>,
+}
+
+/// Account visibility (empty `user_ids` = shared with the household)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountVisibility {
+    pub user_ids: Vec<i64>,
+}
+
+/// GET /api/users - List household users
+pub async fn list_users(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+) -> Result<Json<Vec<User>>, AppError> {
+    user.require(UserRole::Owner)?;
+    Ok(Json(state.db.list_users()?))
+}
+
+/// POST /api/users - Add a household user
+pub async fn create_user(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Json(req): Json<CreateUserRequest>,
+) -> Result<Json<User>, AppError> {
+    user.require(UserRole::Owner)?;
+
+    let email = req.email.trim();
+    if !email.contains('@') {
+        return Err(AppError::bad_request("A valid email is required"));
+    }
+    if state.db.get_user_by_email(email)?.is_some() {
+        return Err(AppError::conflict("User already exists"));
+    }
+    // The first user must be an owner, or nobody could manage the household afterwards
+    if state.db.count_users()? == 0 && req.role != UserRole::Owner {
+        return Err(AppError::bad_request("The first user must be an owner"));
+    }
+
+    let display_name = req
+        .display_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let id = state.db.create_user(email, display_name, req.role)?;
+
+    state.db.log_audit(
+        &user.email,
+        "create",
+        Some("user"),
+        Some(id),
+        Some(&format!("email={}, role={}", email, req.role)),
+    )?;
+
+    let created = state
+        .db
+        .get_user(id)?
+        .ok_or_else(|| AppError::internal("User not found after creation"))?;
+    Ok(Json(created))
+}
+
+/// PATCH /api/users/:id - Change a user's name or role
+pub async fn update_user(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+    Json(req): Json<UpdateUserRequest>,
+) -> Result<Json<User>, AppError> {
+    user.require(UserRole::Owner)?;
+
+    if state.db.get_user(id)?.is_none() {
+        return Err(AppError::not_found("User not found"));
+    }
+
+    state
+        .db
+        .update_user(
+            id,
+            req.display_name.as_ref().map(|n| n.as_deref()),
+            req.role,
+        )
+        .map_err(|e| match e {
+            hone_core::Error::InvalidData(msg) => AppError::bad_request(&msg),
+            other => other.into(),
+        })?;
+
+    state.db.log_audit(
+        &user.email,
+        "update",
+        Some("user"),
+        Some(id),
+        req.role.map(|r| format!("role={}", r)).as_deref(),
+    )?;
+
+    let updated = state
+        .db
+        .get_user(id)?
+        .ok_or_else(|| AppError::not_found("User not found"))?;
+    Ok(Json(updated))
+}
+
+/// DELETE /api/users/:id - Remove a user
+pub async fn delete_user(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    user.require(UserRole::Owner)?;
+
+    let deleted = state.db.delete_user(id).map_err(|e| match e {
+        hone_core::Error::InvalidData(msg) => AppError::bad_request(&msg),
+        other => other.into(),
+    })?;
+    if !deleted {
+        return Err(AppError::not_found("User not found"));
+    }
+
+    state
+        .db
+        .log_audit(&user.email, "delete", Some("user"), Some(id), None)?;
+
+    Ok(Json(serde_json::json!({ "deleted": true })))
+}
+
+/// GET /api/accounts/:id/visibility - Users an account is restricted to
+pub async fn get_account_visibility(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Json<AccountVisibility>, AppError> {
+    user.require(UserRole::Owner)?;
+
+    if state.db.get_account(id)?.is_none() {
+        return Err(AppError::not_found("Account not found"));
+    }
+    Ok(Json(AccountVisibility {
+        user_ids: state.db.get_account_visibility(id)?,
+    }))
+}
+
+/// PUT /api/accounts/:id/visibility - Restrict an account to some users (empty = shared)
+pub async fn set_account_visibility(
+    State(state): State<Arc<AppState>>,
+    user: CurrentUser,
+    Path(id): Path<i64>,
+    Json(req): Json<AccountVisibility>,
+) -> Result<Json<AccountVisibility>, AppError> {
+    user.require(UserRole::Owner)?;
+
+    if state.db.get_account(id)?.is_none() {
+        return Err(AppError::not_found("Account not found"));
+    }
+    for user_id in &req.user_ids {
+        if state.db.get_user(*user_id)?.is_none() {
+            return Err(AppError::bad_request(&format!("Unknown user: {}", user_id)));
+        }
+    }
+
+    state.db.set_account_visibility(id, &req.user_ids)?;
+
+    state.db.log_audit(
+        &user.email,
+        "set_visibility",
+        Some("account"),
+        Some(id),
+        Some(&format!("user_ids={:?}", req.user_ids)),
+    )?;
+
+    Ok(Json(AccountVisibility {
+        user_ids: state.db.get_account_visibility(id)?,
+    }))
+}
@@ -0,0 +1,125 @@
+//! Warranty and return-window handlers
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, Request, State},
+    Json,
+};
+use serde::Deserialize;
+
+use crate::{get_user_email, AppError, AppState, CurrentUser, SuccessResponse};
+use hone_core::models::{NewWarranty, Warranty, WarrantyUpdate};
+
+/// Map warranty validation errors to client errors
+fn warranty_error(e: hone_core::Error) -> AppError {
+    match e {
+        hone_core::Error::InvalidData(msg) => AppError::bad_request(&msg),
+        hone_core::Error::NotFound(msg) => AppError::not_found(&msg),
+        other => other.into(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WarrantyListQuery {
+    /// Match item, merchant, serial number or notes
+    pub q: Option<String>,
+    /// Only warranties for this receipt (or its warranty document)
+    pub receipt_id: Option<i64>,
+}
+
+/// GET /api/warranties - Tracked warranties, newest purchase first
+pub async fn list_warranties(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Query(params): Query<WarrantyListQuery>,
+) -> Result<Json<Vec<Warranty>>, AppError> {
+    Ok(Json(
+        current_user
+            .db(&state.db)
+            .list_warranties(params.q.as_deref(), params.receipt_id)?,
+    ))
+}
+
+/// POST /api/warranties - Track a warranty or return window
+///
+/// Blank fields are filled from the split, receipt or transaction given.
+pub async fn create_warranty(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Json(req): Json<NewWarranty>,
+) -> Result<Json<Warranty>, AppError> {
+    let user_email = get_user_email(&headers);
+
+    let warranty = state.db.create_warranty(&req).map_err(warranty_error)?;
+
+    state.db.log_audit(
+        &user_email,
+        "create",
+        Some("warranty"),
+        Some(warranty.id),
+        Some(&format!(
+            "receipt_id={:?}, split_id={:?}, return_by={:?}, warranty_expires={:?}",
+            warranty.receipt_id, warranty.split_id, warranty.return_by, warranty.warranty_expires
+        )),
+    )?;
+
+    Ok(Json(warranty))
+}
+
+/// GET /api/warranties/:id - Get a warranty
+pub async fn get_warranty(
+    State(state): State<Arc<AppState>>,
+    current_user: CurrentUser,
+    Path(id): Path<i64>,
+) -> Result<Json<Warranty>, AppError> {
+    let warranty = current_user
+        .db(&state.db)
+        .get_warranty(id)?
+        .ok_or_else(|| AppError::not_found("Warranty not found"))?;
+    Ok(Json(warranty))
+}
+
+/// PATCH /api/warranties/:id - Update a warranty
+pub async fn update_warranty(
+    State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
+    Path(id): Path<i64>,
+    Json(req): Json<WarrantyUpdate>,
+) -> Result<Json<Warranty>, AppError> {
+    let user_email = get_user_email(&headers);
+
+    let warranty = state.db.update_warranty(id, &req).map_err(warranty_error)?;
+
+    state.db.log_audit(
+        &user_email,
+        "update",
+        Some("warranty"),
+        Some(id),
+        Some(&format!(
+            "return_by={:?}, warranty_expires={:?}",
+            warranty.return_by, warranty.warranty_expires
+        )),
+    )?;
+
+    Ok(Json(warranty))
+}
+
+/// DELETE /api/warranties/:id - Stop tracking a warranty
+pub async fn delete_warranty(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+    request: Request,
+) -> Result<Json<SuccessResponse>, AppError> {
+    let user_email = get_user_email(request.headers());
+
+    if !state.db.delete_warranty(id)? {
+        return Err(AppError::not_found("Warranty not found"));
+    }
+
+    state
+        .db
+        .log_audit(&user_email, "delete", Some("warranty"), Some(id), None)?;
+
+    Ok(Json(SuccessResponse { success: true }))
+}
@@ -0,0 +1,348 @@
+//! Request identity and role checks
+//!
+//! The auth middleware resolves every authenticated request to a
+//! `CurrentUser` (stored in request extensions) and rejects requests whose
+//! role is below what the route requires.
+//!
+//! Until the first household user is added, the instance runs in single-user
+//! mode: every authenticated identity is treated as an owner. Once users exist,
+//! Cloudflare Access emails and user-scoped API keys must match a user record.
+//!
+//! Per-account visibility is resolved here too: handlers read through
+//! `CurrentUser::db`, a database handle that leaves hidden accounts out of
+//! every account-scoped query, and per-transaction, per-account and
+//! per-receipt paths for hidden rows are answered with 404 before routing.
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{request::Parts, Method},
+};
+use serde::Serialize;
+
+use hone_core::db::Database;
+use hone_core::models::{ApiKey, ApiKeyScope, User, UserRole};
+
+use crate::AppError;
+
+/// Header the auth middleware sets to the resolved identity (client-supplied values are stripped)
+pub(crate) const HONE_USER_HEADER: &str = "x-hone-user";
+
+/// The authenticated caller of a request
+#[derive(Debug, Clone, Serialize)]
+pub struct CurrentUser {
+    /// Identity used for audit logging (user email, "api-key" or "local-dev")
+    pub email: String,
+    /// Effective role for this request
+    pub role: UserRole,
+    /// Household user record (None in single-user mode, for unscoped API keys and trusted networks)
+    pub user: Option<User>,
+    /// How the caller authenticated
+    pub auth_method: &'static str,
+    /// Accounts hidden from this caller by per-account visibility
+    #[serde(skip)]
+    pub hidden_account_ids: Vec<i64>,
+}
+
+impl CurrentUser {
+    /// Identity with full access (no auth, trusted network, unscoped key in single-user mode)
+    pub fn owner(email: &str, auth_method: &'static str) -> Self {
+        Self {
+            email: email.to_string(),
+            role: UserRole::Owner,
+            user: None,
+            auth_method,
+            hidden_account_ids: Vec::new(),
+        }
+    }
+
+    /// Identity for a household user (role capped at `role`)
+    pub fn household(
+        db: &Database,
+        user: User,
+        role: UserRole,
+        auth_method: &'static str,
+    ) -> hone_core::Result<Self> {
+        Ok(Self {
+            email: user.email.clone(),
+            role,
+            hidden_account_ids: db.hidden_account_ids(&user)?,
+            user: Some(user),
+            auth_method,
+        })
+    }
+
+    /// Fail with 403 unless the caller has at least `role`
+    pub fn require(&self, role: UserRole) -> Result<(), AppError> {
+        if self.role.allows(role) {
+            Ok(())
+        } else {
+            Err(AppError::forbidden(&format!(
+                "This action requires the {} role",
+                role
+            )))
+        }
+    }
+
+    /// Database handle limited to the accounts this caller may see
+    ///
+    /// Use it for every read of account-scoped data (transactions, accounts,
+    /// reports, receipts, exports, explore).
+    pub fn db(&self, db: &Database) -> Database {
+        db.with_hidden_accounts(self.hidden_account_ids.clone())
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentUser>()
+            .cloned()
+            .ok_or_else(|| AppError::unauthorized("Authentication required"))
+    }
+}
+
+/// A configured API key (`HONE_API_KEYS` entry)
+///
+/// Entry format: `<key>`, `<key>:<email>`, `<key>:<role>` or `<key>:<email>:<role>`.
+/// A key scoped to an email acts as that user; a role caps what the key may do.
+/// Keys that contain `:` but no email or role (older entries) are kept whole.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiKeyEntry {
+    pub key: String,
+    pub email: Option<String>,
+    pub role: Option<UserRole>,
+}
+
+impl ApiKeyEntry {
+    /// Parse an entry, failing on an unknown role after an email
+    pub fn parse(entry: &str) -> Result<Self, String> {
+        let entry = entry.trim();
+        let parts: Vec<&str> = entry.splitn(3, ':').map(str::trim).collect();
+        let (key, email, role) = match parts.as_slice() {
+            [key, email] if email.contains('@') => (*key, Some(*email), None),
+            [key, email, role] if email.contains('@') => {
+                let role = role
+                    .parse::<UserRole>()
+                    .map_err(|e| format!("API key for {}: {}", email, e))?;
+                (*key, Some(*email), Some(role))
+            }
+            [key, role] => match role.parse::<UserRole>() {
+                Ok(role) => (*key, None, Some(role)),
+                Err(_) => (entry, None, None),
+            },
+            _ => (entry, None, None),
+        };
+        Ok(Self {
+            key: key.to_string(),
+            email: email.map(str::to_string),
+            role,
+        })
+    }
+}
+
+/// Why an authenticated identity was refused
+#[derive(Debug, PartialEq)]
+pub(crate) enum IdentityError {
+    /// Household mode and the email has no user record
+    UnknownUser(String),
+    /// The user's account visibility could not be loaded (fail closed)
+    Visibility(String),
+    /// The household users could not be read (fail closed)
+    Lookup(String),
+}
+
+/// Resolve an authenticated email to a `CurrentUser`
+///
+/// `role_cap` limits the role (for API keys configured with a role).
+pub(crate) fn resolve_email(
+    db: &Database,
+    email: &str,
+    auth_method: &'static str,
+    role_cap: Option<UserRole>,
+) -> Result<CurrentUser, IdentityError> {
+    let cap = |role: UserRole| role_cap.map_or(role, |cap| role.min(cap));
+
+    let user_count = db
+        .count_users()
+        .map_err(|e| IdentityError::Lookup(e.to_string()))?;
+    if user_count == 0 {
+        // Single-user mode: everyone who got through authentication is an owner
+        return Ok(CurrentUser {
+            role: cap(UserRole::Owner),
+            ..CurrentUser::owner(email, auth_method)
+        });
+    }
+
+    match db
+        .get_user_by_email(email)
+        .map_err(|e| IdentityError::Lookup(e.to_string()))?
+    {
+        Some(user) => {
+            if let Err(e) = db.touch_user(user.id) {
+                tracing::warn!(error = %e, "Failed to update user last_seen_at");
+            }
+            let role = cap(user.role);
+            CurrentUser::household(db, user, role, auth_method)
+                .map_err(|e| IdentityError::Visibility(e.to_string()))
+        }
+        None => Err(IdentityError::UnknownUser(email.to_string())),
+    }
+}
+
+/// Role and hidden accounts for a request made with a database API key
+///
+/// A key can do no more than its scope allows or than the household user who
+/// created it may do now, and reads what that user may see. Keys created from
+/// the CLI (or with no recorded creator) and in single-user mode are only
+/// limited by their scope. `None` means the creator is no longer a household
+/// user, so the key is refused.
+pub(crate) fn api_key_access(
+    db: &Database,
+    key: &ApiKey,
+) -> hone_core::Result<Option<(UserRole, Vec<i64>)>> {
+    let scope_role = key.scope.role().unwrap_or(UserRole::Viewer);
+    let creator = match key.created_by.as_deref() {
+        Some("cli") | None => return Ok(Some((scope_role, Vec::new()))),
+        Some(email) => db.get_user_by_email(email)?,
+    };
+    match creator {
+        Some(user) => Ok(Some((
+            scope_role.min(user.role),
+            db.hidden_account_ids(&user)?,
+        ))),
+        None if db.count_users()? == 0 => Ok(Some((scope_role, Vec::new()))),
+        None => Ok(None),
+    }
+}
+
+/// Accounts hidden from work run on behalf of `created_by` (saved questions)
+///
+/// Nothing is hidden in single-user mode (no household users). Otherwise the
+/// creator must still be a household user: `None` means they were removed
+/// (or were never one), and callers refuse rather than run unscoped.
+pub(crate) fn creator_hidden_accounts(
+    db: &Database,
+    created_by: Option<&str>,
+) -> hone_core::Result<Option<Vec<i64>>> {
+    let creator = match created_by {
+        Some(email) => db.get_user_by_email(email)?,
+        None => None,
+    };
+    match creator {
+        Some(user) => db.hidden_account_ids(&user).map(Some),
+        None if db.count_users()? == 0 => Ok(Some(Vec::new())),
+        None => Ok(None),
+    }
+}
+
+/// Reject requests addressing a transaction, account or receipt the caller can't see
+///
+/// Answers 404 (as if the row didn't exist) for `/transactions/:id/...`,
+/// `/accounts/:id/...` and `/receipts/:id/...`. Scoped handlers filter
+/// lists and reports themselves.
+pub(crate) fn require_visible_path(db: &Database, path: &str) -> Result<(), AppError> {
+    if db.hidden_accounts().is_empty() {
+        return Ok(());
+    }
+    let path = path.strip_prefix("/api").unwrap_or(path);
+    let mut segments = path.trim_start_matches('/').split('/');
+    let (Some(collection), Some(id)) = (segments.next(), segments.next()) else {
+        return Ok(());
+    };
+    let Ok(id) = id.parse::<i64>() else {
+        return Ok(());
+    };
+
+    let hidden = match collection {
+        "transactions" => db.get_transaction(id)?.is_none().then_some("Transaction"),
+        "accounts" => db.get_account(id)?.is_none().then_some("Account"),
+        "receipts" => db.get_receipt(id)?.is_none().then_some("Receipt"),
+        _ => None,
+    };
+    match hidden {
+        Some(kind) => Err(AppError::not_found(&format!("{} {} not found", kind, id))),
+        None => Ok(()),
+    }
+}
+
+/// Minimum role a request needs, by method and path
+///
+/// - Owner: household users, API keys, account visibility, backups, full export/import, audit log
+/// - Viewer: reads, plus explore queries, renaming or deleting their own explore
+///   sessions (the handlers check ownership) and rule dry-runs
+/// - Editor: every other write
+pub(crate) fn required_role(method: &Method, path: &str) -> UserRole {
+    let path = path.strip_prefix("/api").unwrap_or(path);
+
+    let owner_only = path.starts_with("/users")
+        || path.starts_with("/backup")
+        || path.starts_with("/audit")
+        || path.starts_with("/keys")
+        || path.starts_with("/notifications")
+        || path == "/import/full"
+        || path == "/export/full"
+        || (path.starts_with("/accounts/") && path.ends_with("/visibility"));
+    if owner_only {
+        return UserRole::Owner;
+    }
+
+    // Everyone manages their own credentials and sessions
+    if path.starts_with("/auth/") {
+        return UserRole::Viewer;
+    }
+
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return UserRole::Viewer;
+    }
+
+    let read_only_post = *method == Method::POST
+        && (path == "/explore/query"
+            || path == "/explore/query/stream"
+            || path == "/explore/session"
+            || (path.starts_with("/explore/session/") && path.ends_with("/cancel"))
+            || path == "/rules/test");
+    if read_only_post {
+        return UserRole::Viewer;
+    }
+
+    let own_session = matches!(*method, Method::PATCH | Method::DELETE)
+        && path
+            .strip_prefix("/explore/session/")
+            .is_some_and(|id| !id.is_empty() && !id.contains('/'));
+    if own_session {
+        return UserRole::Viewer;
+    }
+
+    UserRole::Editor
+}
+
+/// Whether a database API key's scope covers a request (role checks still apply)
+///
+/// - `mcp` keys only work against the MCP server
+/// - `import_only` keys can upload statements or push transactions, follow their import sessions
+///   and list accounts (to pick the import target)
+/// - `read_only` and `admin` are limited by their role alone
+pub(crate) fn api_key_scope_allows(scope: ApiKeyScope, method: &Method, path: &str) -> bool {
+    let path = path.strip_prefix("/api").unwrap_or(path);
+
+    match scope {
+        ApiKeyScope::Mcp => false,
+        ApiKeyScope::ImportOnly => {
+            let is_read = matches!(*method, Method::GET | Method::HEAD);
+            (path == "/import" || path == "/import/json" || path == "/import/transactions")
+                || (is_read && (path == "/accounts" || path.starts_with("/imports")))
+                || (*method == Method::POST
+                    && path.starts_with("/imports/")
+                    && path.ends_with("/cancel"))
+        }
+        ApiKeyScope::ReadOnly | ApiKeyScope::Admin => true,
+    }
+}
@@ -0,0 +1,511 @@
+//! Background job runner
+//!
+//! Long-running work (import processing, reprocessing, detection, insight
+//! refresh, backups, scheduled tasks) is queued in the `jobs` table and
+//! executed by two worker lanes:
+//!
+//! - the AI lane runs one AI-heavy job at a time so model backends aren't
+//!   flooded by concurrent imports, detection runs and explore digests
+//! - the general lane runs everything else (backups, reports, audit
+//!   archiving)
+//!
+//! Running jobs poll for cancellation requests and are dropped at the next
+//! await point when cancelled. Failed attempts are retried with exponential
+//! backoff (30s, 60s, 120s...) until the job's `max_attempts`. Jobs that were
+//! running when the server stopped are queued again on startup.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::time::interval;
+use tracing::{error, info, warn};
+
+use hone_core::ai::{AIClient, AIOrchestrator};
+use hone_core::backup::default_backup_dir;
+use hone_core::detect::{ProgressCallback, WasteDetector};
+use hone_core::insights::{AnalysisContext, InsightEngine};
+use hone_core::models::{Job, JobType};
+use hone_core::schedule::{default_reports_dir, write_spending_report};
+use hone_core::trainer::TrainerBackend;
+use hone_core::training::TrainingTask;
+use hone_core::training_pipeline::TrainingPipeline;
+use hone_core::Database;
+
+use crate::handlers::detection::run_async_import_processing;
+use crate::handlers::import_history::run_async_reprocess;
+use crate::scheduler::{run_audit_archive, run_due_saved_questions, run_scheduled_backup};
+
+/// How often idle workers look for queued jobs
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often a running job checks whether it was cancelled
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Base delay before retrying a failed job
+const RETRY_BASE_SECS: i64 = 30;
+
+/// Worker lane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobLane {
+    /// AI-heavy jobs, one at a time
+    Ai,
+    /// Everything else
+    General,
+}
+
+impl JobLane {
+    /// Job types this lane runs
+    pub fn job_types(&self) -> Vec<JobType> {
+        JobType::all()
+            .iter()
+            .copied()
+            .filter(|t| t.is_ai_heavy() == (*self == JobLane::Ai))
+            .collect()
+    }
+}
+
+/// Parameters for `import_processing` jobs
+#[derive(Debug, Deserialize)]
+struct ImportProcessingParams {
+    session_id: i64,
+    imported_count: i64,
+    #[serde(default)]
+    model: Option<String>,
+}
+
+/// Parameters for `reprocess` jobs
+#[derive(Debug, Deserialize)]
+struct ReprocessParams {
+    session_id: i64,
+    run_id: i64,
+    total_transactions: i64,
+    imported_count: i64,
+    skipped_count: i64,
+    receipts_matched: i64,
+    #[serde(default)]
+    model: Option<String>,
+}
+
+/// Parameters for `detection` jobs
+#[derive(Debug, Deserialize)]
+struct DetectionParams {
+    /// all, zombies, increases or duplicates
+    #[serde(default = "default_detection_kind")]
+    kind: String,
+}
+
+fn default_detection_kind() -> String {
+    "all".to_string()
+}
+
+/// Parameters for `backup` jobs
+#[derive(Debug, Deserialize)]
+struct BackupParams {
+    /// Number of backups to keep
+    #[serde(default = "default_backup_retention")]
+    retention: usize,
+}
+
+fn default_backup_retention() -> usize {
+    7
+}
+
+/// Parameters for `report` jobs
+#[derive(Debug, Deserialize)]
+struct ReportParams {
+    /// Period preset (last-month, this-month, last-30-days)
+    #[serde(default = "default_report_period")]
+    period: String,
+}
+
+fn default_report_period() -> String {
+    "last-month".to_string()
+}
+
+/// Parameters for `prune_metrics` jobs
+#[derive(Debug, Deserialize)]
+struct PruneMetricsParams {
+    /// AI call metrics older than this are deleted
+    #[serde(default = "default_metrics_keep_days")]
+    keep_days: i64,
+}
+
+fn default_metrics_keep_days() -> i64 {
+    90
+}
+
+/// Parameters for `training` jobs
+#[derive(Debug, Deserialize)]
+struct TrainingParams {
+    /// Tasks to consider (default: all)
+    #[serde(default)]
+    tasks: Option<Vec<String>>,
+    /// Trainer backend (default: `HONE_TRAINER` or the platform default)
+    #[serde(default)]
+    trainer: Option<String>,
+}
+
+/// Parameters for `audit_archive` jobs
+#[derive(Debug, Deserialize)]
+struct AuditArchiveParams {
+    /// Entries older than this many days are archived
+    retention_days: i64,
+}
+
+/// Executes queued jobs
+#[derive(Clone)]
+pub struct JobRunner {
+    db: Database,
+    ai: Option<AIClient>,
+    backup_dir: PathBuf,
+    reports_dir: PathBuf,
+}
+
+impl JobRunner {
+    /// Create a runner; backups go to `HONE_BACKUP_DIR` and reports to
+    /// `HONE_REPORTS_DIR` (or their default directories)
+    pub fn new(db: Database, ai: Option<AIClient>) -> Self {
+        let backup_dir = std::env::var("HONE_BACKUP_DIR")
+            .ok()
+            .map(PathBuf::from)
+            .unwrap_or_else(default_backup_dir);
+        Self {
+            db,
+            ai,
+            backup_dir,
+            reports_dir: default_reports_dir(),
+        }
+    }
+
+    pub fn with_backup_dir(mut self, backup_dir: PathBuf) -> Self {
+        self.backup_dir = backup_dir;
+        self
+    }
+
+    pub fn with_reports_dir(mut self, reports_dir: PathBuf) -> Self {
+        self.reports_dir = reports_dir;
+        self
+    }
+
+    /// Claim and run the next due job for a lane
+    ///
+    /// Returns the job ID, or None when nothing was queued.
+    pub async fn run_next(&self, lane: JobLane) -> hone_core::Result<Option<i64>> {
+        let Some(job) = self.db.claim_next_job(&lane.job_types(), Utc::now())? else {
+            return Ok(None);
+        };
+        let id = job.id;
+        info!(
+            "Running job {} ({}, attempt {}/{})",
+            id, job.job_type, job.attempts, job.max_attempts
+        );
+
+        let outcome = tokio::select! {
+            result = self.execute(&job) => Some(result),
+            _ = self.wait_for_cancel(id) => None,
+        };
+
+        match outcome {
+            Some(Ok(result)) => {
+                self.db.complete_job(id, &result)?;
+                info!("Job {} ({}) completed", id, job.job_type);
+            }
+            Some(Err(e)) => {
+                let message = e.to_string();
+                let retry_at = (job.attempts < job.max_attempts).then(|| {
+                    Utc::now()
+                        + chrono::Duration::seconds(
+                            RETRY_BASE_SECS << (job.attempts - 1).clamp(0, 10),
+                        )
+                });
+                match retry_at {
+                    Some(at) => warn!(
+                        "Job {} ({}) failed, retrying at {}: {}",
+                        id, job.job_type, at, message
+                    ),
+                    None => {
+                        error!("Job {} ({}) failed: {}", id, job.job_type, message);
+                        release_failed_job(&self.db, &job, &message);
+                    }
+                }
+                self.db.fail_job(id, &message, retry_at)?;
+            }
+            None => {
+                info!("Job {} ({}) cancelled", id, job.job_type);
+                self.db.mark_job_cancelled(id)?;
+                release_cancelled_job(&self.db, &job);
+            }
+        }
+        Ok(Some(id))
+    }
+
+    /// Resolve once cancellation is requested for a job
+    async fn wait_for_cancel(&self, id: i64) {
+        let mut ticker = interval(CANCEL_POLL_INTERVAL);
+        loop {
+            ticker.tick().await;
+            match self.db.is_job_cancel_requested(id) {
+                Ok(true) => return,
+                Ok(false) => {}
+                Err(e) => warn!("Failed to check cancellation for job {}: {}", id, e),
+            }
+        }
+    }
+
+    /// AI client with an optional model override
+    fn ai_for(&self, model: Option<&str>) -> Option<AIClient> {
+        match (self.ai.as_ref(), model) {
+            (Some(ai), Some(model)) => Some(ai.with_model(model)),
+            (Some(ai), None) => Some(ai.clone()),
+            _ => None,
+        }
+    }
+
+    /// Orchestrator with an optional model override
+    fn orchestrator_for(&self, model: Option<&str>) -> Option<AIOrchestrator> {
+        let orchestrator = AIOrchestrator::from_env(self.db.clone());
+        match model {
+            Some(model) => orchestrator.map(|o| o.with_model(model)),
+            None => orchestrator,
+        }
+    }
+
+    async fn execute(&self, job: &Job) -> hone_core::Result<serde_json::Value> {
+        let db = &self.db;
+        match job.job_type {
+            JobType::ImportProcessing => {
+                let params: ImportProcessingParams = parse_params(job)?;
+                let ai = self.ai_for(params.model.as_deref());
+                let orchestrator = self.orchestrator_for(params.model.as_deref());
+                run_async_import_processing(
+                    db,
+                    ai.as_ref(),
+                    orchestrator.as_ref(),
+                    params.session_id,
+                    params.imported_count,
+                )
+                .await?;
+                Ok(json!({ "session_id": params.session_id }))
+            }
+            JobType::Reprocess => {
+                let params: ReprocessParams = parse_params(job)?;
+                let ai = self.ai_for(params.model.as_deref());
+                let orchestrator = self.orchestrator_for(params.model.as_deref());
+                run_async_reprocess(
+                    db,
+                    ai.as_ref(),
+                    orchestrator.as_ref(),
+                    params.session_id,
+                    params.run_id,
+                    params.total_transactions,
+                    params.imported_count,
+                    params.skipped_count,
+                    params.receipts_matched,
+                )
+                .await?;
+                Ok(json!({ "session_id": params.session_id, "run_id": params.run_id }))
+            }
+            JobType::Detection => {
+                let params: DetectionParams = parse_params(job)?;
+                let ai = self.ai.as_ref();
+                let orchestrator = self.orchestrator_for(None);
+                let detector = match (orchestrator.as_ref(), ai) {
+                    (Some(orch), Some(ai)) => WasteDetector::with_ai_and_orchestrator(db, ai, orch),
+                    (Some(orch), None) => WasteDetector::with_orchestrator(db, orch),
+                    (None, Some(ai)) => WasteDetector::with_ai(db, ai),
+                    (None, None) => WasteDetector::new(db),
+                };
+
+                let job_id = job.id;
+                let progress_db = db.clone();
+                let progress: ProgressCallback = Box::new(move |phase, current, total| {
+                    if let Err(e) = progress_db.update_job_progress(job_id, phase, current, total) {
+                        warn!("Failed to update job progress: {}", e);
+                    }
+                });
+
+                let results = match params.kind.as_str() {
+                    "zombies" => detector.detect_zombies_only().await?,
+                    "increases" => detector.detect_increases_only().await?,
+                    "duplicates" => detector.detect_duplicates_only().await?,
+                    _ => detector.detect_all_with_progress(Some(&progress)).await?,
+                };
+                Ok(json!({
+                    "kind": params.kind,
+                    "subscriptions_found": results.subscriptions_found,
+                    "zombies_detected": results.zombies_detected,
+                    "price_increases_detected": results.price_increases_detected,
+                    "duplicates_detected": results.duplicates_detected,
+                    "spending_anomalies_detected": results.spending_anomalies_detected,
+                    "tip_discrepancies_detected": results.tip_discrepancies_detected,
+                    "trials_detected": results.trials_detected,
+                    "trials_converted": results.trials_converted,
+                    "cancellations_confirmed": results.cancellations_confirmed,
+                    "charges_after_cancel": results.charges_after_cancel,
+                    "transaction_anomalies_detected": results.transaction_anomalies_detected,
+                    "warranty_reminders": results.warranty_reminders,
+                }))
+            }
+            JobType::InsightRefresh => {
+                db.update_job_progress(job.id, "analyzing", 0, 1)?;
+                let ctx = AnalysisContext::current_month(db, self.ai.as_ref());
+                let count = InsightEngine::new().run_and_persist(&ctx).await?;
+                db.update_job_progress(job.id, "analyzing", 1, 1)?;
+                Ok(json!({ "count": count }))
+            }
+            JobType::Backup => {
+                let params: BackupParams = parse_params(job)?;
+                db.update_job_progress(job.id, "backup", 0, 1)?;
+                let db = db.clone();
+                let backup_dir = self.backup_dir.clone();
+                let name = tokio::task::spawn_blocking(move || {
+                    run_scheduled_backup(&db, &backup_dir, params.retention)
+                })
+                .await
+                .map_err(|e| hone_core::Error::Backup(format!("Backup task failed: {}", e)))?
+                .map_err(hone_core::Error::Backup)?;
+                self.db.update_job_progress(job.id, "backup", 1, 1)?;
+                Ok(json!({ "name": name }))
+            }
+            JobType::Report => {
+                let params: ReportParams = parse_params(job)?;
+                let path = write_spending_report(db, &params.period, &self.reports_dir)?;
+                info!("Spending report written to {}", path.display());
+                Ok(json!({ "period": params.period, "path": path.to_string_lossy() }))
+            }
+            JobType::PruneMetrics => {
+                let params: PruneMetricsParams = parse_params(job)?;
+                let deleted = db.prune_ollama_metrics(params.keep_days)?;
+                Ok(json!({ "keep_days": params.keep_days, "deleted": deleted }))
+            }
+            JobType::Training => {
+                let params: TrainingParams = parse_params(job)?;
+                let tasks = match params.tasks {
+                    Some(names) => names
+                        .iter()
+                        .map(|name| {
+                            TrainingTask::from_str(name).ok_or_else(|| {
+                                hone_core::Error::Training(format!("Unknown task: {}", name))
+                            })
+                        })
+                        .collect::<hone_core::Result<Vec<_>>>()?,
+                    None => TrainingTask::all(),
+                };
+                let trainer = match params.trainer.as_deref() {
+                    Some(name) => TrainerBackend::from_name(name).ok_or_else(|| {
+                        hone_core::Error::Training(format!("Unknown trainer: {}", name))
+                    })?,
+                    None => TrainerBackend::from_env(),
+                };
+                db.update_job_progress(job.id, "training", 0, tasks.len() as i64)?;
+                let db = db.clone();
+                let outcomes = tokio::task::spawn_blocking(move || {
+                    TrainingPipeline::new(&db).run_scheduled_training(&tasks, &trainer)
+                })
+                .await
+                .map_err(|e| {
+                    hone_core::Error::Training(format!("Training task failed: {}", e))
+                })??;
+                let trained = outcomes
+                    .iter()
+                    .filter(|o| o.experiment_id.is_some())
+                    .count();
+                self.db.update_job_progress(
+                    job.id,
+                    "training",
+                    outcomes.len() as i64,
+                    outcomes.len() as i64,
+                )?;
+                Ok(json!({ "trained": trained, "tasks": outcomes }))
+            }
+            JobType::AuditArchive => {
+                let params: AuditArchiveParams = parse_params(job)?;
+                let archived = run_audit_archive(db, params.retention_days)?;
+                Ok(json!({ "retention_days": params.retention_days, "archived": archived }))
+            }
+            JobType::ExploreDigests => {
+                let orchestrator = self.orchestrator_for(None).ok_or_else(|| {
+                    hone_core::Error::InvalidData(
+                        "Explore digests require the AI orchestrator".to_string(),
+                    )
+                })?;
+                let (ran, failed) = run_due_saved_questions(db, &orchestrator).await?;
+                Ok(json!({ "ran": ran, "failed": failed }))
+            }
+        }
+    }
+}
+
+/// Release the import session (and reprocess run) behind a job that failed for good
+fn release_failed_job(db: &Database, job: &Job, message: &str) {
+    if !matches!(job.job_type, JobType::ImportProcessing | JobType::Reprocess) {
+        return;
+    }
+    if let Some(session_id) = job.entity_id {
+        if let Err(e) = db.mark_import_failed(session_id, message) {
+            error!("Failed to mark import as failed: {}", e);
+        }
+    }
+    fail_reprocess_run(db, job);
+}
+
+/// Release the import session (and reprocess run) behind a cancelled job
+pub(crate) fn release_cancelled_job(db: &Database, job: &Job) {
+    if !matches!(job.job_type, JobType::ImportProcessing | JobType::Reprocess) {
+        return;
+    }
+    if let Some(session_id) = job.entity_id {
+        if let Err(e) = db.cancel_import(session_id) {
+            error!("Failed to mark import as cancelled: {}", e);
+        }
+    }
+    fail_reprocess_run(db, job);
+}
+
+fn fail_reprocess_run(db: &Database, job: &Job) {
+    if let Some(run_id) = job.params.get("run_id").and_then(|v| v.as_i64()) {
+        if let Err(e) = db.fail_reprocess_run(run_id) {
+            error!("Failed to mark reprocess run as failed: {}", e);
+        }
+    }
+}
+
+fn parse_params<T: serde::de::DeserializeOwned>(job: &Job) -> hone_core::Result<T> {
+    serde_json::from_value(job.params.clone()).map_err(|e| {
+        hone_core::Error::InvalidData(format!("Invalid {} job parameters: {}", job.job_type, e))
+    })
+}
+
+/// Start the job worker lanes as background tasks
+pub fn start_job_workers(runner: JobRunner) {
+    info!(
+        "Starting job workers (polling every {}s)",
+        POLL_INTERVAL.as_secs()
+    );
+
+    let runner = Arc::new(runner);
+    for lane in [JobLane::Ai, JobLane::General] {
+        let runner = runner.clone();
+        tokio::spawn(async move {
+            let mut ticker = interval(POLL_INTERVAL);
+            loop {
+                ticker.tick().await;
+                // Drain everything that's due before sleeping again
+                loop {
+                    match runner.run_next(lane).await {
+                        Ok(Some(_)) => {}
+                        Ok(None) => break,
+                        Err(e) => {
+                            error!("Job worker ({:?} lane) error: {}", lane, e);
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
@@ -13,7 +13,7 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Request, State},
-    http::{header, HeaderValue, Method, StatusCode},
+    http::{header, HeaderName, HeaderValue, Method, StatusCode},
     middleware::{self, Next},
     response::{IntoResponse, Response},
     routing::{delete, get, post, put},
@@ -27,12 +27,30 @@ use tracing::{error, info, warn};
 
 use hone_core::ai::{orchestrator::AIOrchestrator, AIBackend, AIClient};
 use hone_core::db::Database;
+use hone_core::models::UserRole;
+use hone_core::notify::NotifyConfig;
+use hone_core::schedule::ScheduleConfig;
+use hone_core::watch::WatchConfig;
 
 mod handlers;
+mod identity;
+mod jobs;
+mod local_auth;
 pub mod mcp;
+mod metrics;
 mod scheduler;
+mod webauthn;
 
-pub use scheduler::{start_backup_scheduler, BackupScheduleConfig};
+pub use identity::{ApiKeyEntry, CurrentUser};
+pub use jobs::{start_job_workers, JobLane, JobRunner};
+pub use local_auth::{LocalAuthConfig, LocalAuthState};
+pub use metrics::{HttpMetrics, MetricsConfig};
+pub use webauthn::RelyingParty;
+
+pub use scheduler::{
+    run_saved_question, start_import_watcher, start_notification_scheduler, start_task_scheduler,
+    AuditRetentionConfig, BackupScheduleConfig, DigestScheduleConfig,
+};
 
 /// Maximum file upload size (10 MB)
 pub const MAX_UPLOAD_SIZE: usize = 10 * 1024 * 1024;
@@ -79,7 +97,8 @@ pub struct ServerConfig {
     /// Allowed CORS origins (empty = same-origin only in production)
     pub allowed_origins: Vec<String>,
     /// API keys for internal service authentication (alternative to Cloudflare Access)
-    /// Format: "Bearer <key>" in Authorization header
+    /// Format: "Bearer <key>" in Authorization header. Entries may be scoped to a
+    /// user and/or role: `<key>:<email>:<role>` (see `ApiKeyEntry`)
     pub api_keys: Vec<String>,
     /// Cloudflare Access JWT validation config (optional but recommended)
     pub cf_jwt: CfJwtConfig,
@@ -89,6 +108,18 @@ pub struct ServerConfig {
     /// Trusted proxies whose X-Forwarded-For headers are trusted (e.g., "10.42.0.0/16" for k3s)
     /// When a request comes from a trusted proxy, the client IP is extracted from X-Forwarded-For
     pub trusted_proxies: Vec<ipnet::IpNet>,
+    /// Role granted to requests from trusted networks (default: owner)
+    pub trusted_network_role: UserRole,
+    /// Built-in password/passkey logins with session cookies (disabled by default)
+    pub local_auth: LocalAuthConfig,
+    /// `/metrics` endpoint and its access control (disabled by default)
+    pub metrics: MetricsConfig,
+    /// Outbound notification channels and routes (None = notifications off)
+    pub notifications: Option<NotifyConfig>,
+    /// Cron-scheduled tasks (empty = nothing scheduled)
+    pub schedules: ScheduleConfig,
+    /// Import folder watcher (None = not watching)
+    pub watch: Option<WatchConfig>,
 }
 
 impl Default for ServerConfig {
@@ -100,6 +131,12 @@ impl Default for ServerConfig {
             cf_jwt: CfJwtConfig::default(),
             trusted_networks: vec![],
             trusted_proxies: vec![],
+            trusted_network_role: UserRole::Owner,
+            local_auth: LocalAuthConfig::default(),
+            metrics: MetricsConfig::default(),
+            notifications: None,
+            schedules: ScheduleConfig::default(),
+            watch: None,
         }
     }
 }
@@ -117,15 +154,23 @@ pub struct AppState {
     pub receipts_dir: std::path::PathBuf,
     /// Session manager for explore mode conversations
     pub explore_sessions: handlers::ExploreSessionManager,
+    /// Login rate limits and pending passkey challenges
+    pub local_auth: LocalAuthState,
+    /// Per-route HTTP request counters and latencies for `/metrics`
+    pub http_metrics: HttpMetrics,
 }
 
 /// Authentication middleware - validates Cloudflare Access JWT, headers, API keys, or trusted networks
 ///
+/// Every authenticated request is resolved to a `CurrentUser` (see `identity`) and
+/// checked against the role the route requires before it reaches a handler.
+///
 /// # Security Notes
 ///
-/// **Trusted networks**: Requests from IPs in `trusted_networks` bypass all authentication.
-/// Use this for local network access (e.g., "192.168.1.0/24"). The client IP is determined
-/// from the TCP connection peer address only (headers are NOT trusted to prevent spoofing).
+/// **Trusted networks**: Requests from IPs in `trusted_networks` bypass all authentication
+/// and get `trusted_network_role`. Use this for local network access (e.g., "192.168.1.0/24").
+/// The client IP is determined from the TCP connection peer address only (headers are NOT
+/// trusted to prevent spoofing).
 ///
 /// **Cloudflare Access JWT** (recommended): The `Cf-Access-Jwt-Assertion` header contains a
 /// cryptographically signed JWT. When `CF_TEAM_NAME` and `CF_AUD_TAG` are configured, this
@@ -137,29 +182,96 @@ pub struct AppState {
 /// Cloudflare Tunnel (which strips/rewrites CF headers), but can be spoofed if the server
 /// is exposed directly to the internet.
 ///
-/// **API keys**: Compared using constant-time comparison to prevent timing attacks.
+/// **API keys**: Keys from `HONE_API_KEYS` are compared using constant-time comparison to
+/// prevent timing attacks. Keys created with `hone keys create` (or `POST /api/keys`) are
+/// stored hashed, carry a scope, and are recorded in the audit log by name.
+///
+/// **Session cookies** (built-in auth): Checked first when `local_auth` is enabled.
+/// State-changing requests must echo the CSRF token in `X-CSRF-Token`. The login
+/// endpoints themselves are public.
 async fn auth_middleware(
     State(state): State<Arc<AppState>>,
     connect_info: Option<axum::extract::ConnectInfo<std::net::SocketAddr>>,
-    request: Request,
+    mut request: Request,
     next: Next,
 ) -> Response {
-    if !state.config.require_auth {
+    // The identity header is ours to set; never trust a client-supplied one
+    request.headers_mut().remove(identity::HONE_USER_HEADER);
+
+    if state.config.local_auth.enabled
+        && local_auth::is_public_path(request.method(), request.uri().path())
+    {
         return next.run(request).await;
     }
 
+    let user = if state.config.require_auth {
+        match authenticate(&state, connect_info.as_ref(), &mut request).await {
+            Ok(user) => user,
+            Err(e) => return e.into_response(),
+        }
+    } else {
+        CurrentUser::owner(&get_user_email(request.headers()), "none")
+    };
+
+    let required = identity::required_role(request.method(), request.uri().path());
+    if !user.role.allows(required) {
+        warn!(
+            user = %user.email,
+            role = %user.role,
+            required = %required,
+            path = %request.uri().path(),
+            "Forbidden - role too low"
+        );
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": format!("This action requires the {} role", required)
+            })),
+        )
+            .into_response();
+    }
+
+    if let Err(e) = identity::require_visible_path(&user.db(&state.db), request.uri().path()) {
+        return e.into_response();
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&user.email) {
+        request
+            .headers_mut()
+            .insert(identity::HONE_USER_HEADER, value);
+    }
+    request.extensions_mut().insert(user);
+    next.run(request).await
+}
+
+/// Authenticate a request, returning the caller or the rejection response
+///
+/// Takes `&mut Request` only so the future stays `Send` (`Request` isn't `Sync`).
+async fn authenticate(
+    state: &AppState,
+    connect_info: Option<&axum::extract::ConnectInfo<std::net::SocketAddr>>,
+    request: &mut Request,
+) -> Result<CurrentUser, AppError> {
+    // Session cookies from the built-in login
+    if state.config.local_auth.enabled {
+        if let Some(result) = local_auth::authenticate_session(
+            state,
+            request.method(),
+            request.headers(),
+            request.uri().path(),
+        ) {
+            return result;
+        }
+    }
+
     // Check if request is from a trusted network
     if !state.config.trusted_networks.is_empty() {
-        let peer_ip = connect_info.as_ref().map(|ci| ci.0.ip());
+        let peer_ip = connect_info.map(|ci| ci.0.ip());
         let xff = request
             .headers()
             .get("x-forwarded-for")
             .and_then(|v| v.to_str().ok());
-        let client_ip = get_client_ip(
-            &request,
-            connect_info.as_ref(),
-            &state.config.trusted_proxies,
-        );
+        let client_ip = get_client_ip(request, connect_info, &state.config.trusted_proxies);
 
         // Debug logging for trusted network auth
         tracing::debug!(
@@ -175,7 +287,10 @@ async fn auth_middleware(
         if let Some(ip) = client_ip {
             if is_ip_trusted(&ip, &state.config.trusted_networks) {
                 info!(ip = %ip, path = %request.uri().path(), "Authenticated via trusted network");
-                return next.run(request).await;
+                return Ok(CurrentUser {
+                    role: state.config.trusted_network_role,
+                    ..CurrentUser::owner(&get_user_email(request.headers()), "trusted_network")
+                });
             }
         }
     }
@@ -190,7 +305,7 @@ async fn auth_middleware(
             match validate_cf_jwt(jwt, &state.config.cf_jwt).await {
                 Ok(email) => {
                     info!(user = %email, path = %request.uri().path(), "Authenticated via Cloudflare JWT");
-                    return next.run(request).await;
+                    return resolve_identity(state, &email, "cloudflare_jwt", None);
                 }
                 Err(e) => {
                     warn!(error = %e, path = %request.uri().path(), "Invalid Cloudflare JWT");
@@ -222,32 +337,90 @@ async fn auth_middleware(
         } else {
             info!(user = %email, path = %request.uri().path(), "Authenticated via Cloudflare Access header");
         }
-        return next.run(request).await;
+        return resolve_identity(state, email, "cloudflare_header", None);
     }
 
     // Check for API key in Authorization header (Bearer token)
     // Uses constant-time comparison to prevent timing attacks
-    let api_key_valid = request
+    let bearer = request
         .headers()
         .get(AUTHORIZATION_HEADER)
         .and_then(|v| v.to_str().ok())
-        .and_then(|auth| auth.strip_prefix("Bearer "))
-        .map(|key| validate_api_key(key, &state.config.api_keys))
-        .unwrap_or(false);
+        .and_then(|auth| auth.strip_prefix("Bearer "));
+    let api_key = bearer.and_then(|key| validate_api_key(key, &state.config.api_keys));
 
-    if api_key_valid {
-        info!(user = "api-key", path = %request.uri().path(), "Authenticated via API key");
-        return next.run(request).await;
+    if let Some(entry) = api_key {
+        info!(
+            user = entry.email.as_deref().unwrap_or("api-key"),
+            path = %request.uri().path(),
+            "Authenticated via API key"
+        );
+        return match entry.email {
+            Some(ref email) => resolve_identity(state, email, "api_key", entry.role),
+            None => Ok(CurrentUser {
+                role: entry.role.unwrap_or(UserRole::Owner),
+                ..CurrentUser::owner("api-key", "api_key")
+            }),
+        };
+    }
+
+    // Database-managed keys (hashed, so the lookup itself isn't timing-sensitive)
+    let db_key = match bearer {
+        Some(secret) => state.db.authenticate_api_key(secret).map_err(|e| {
+            error!(error = %e, "Failed to look up API key");
+            AppError::internal("Failed to check API key")
+        })?,
+        None => None,
+    };
+
+    if let Some(key) = db_key {
+        let path = request.uri().path();
+        if !identity::api_key_scope_allows(key.scope, request.method(), path) {
+            warn!(key = %key.name, scope = %key.scope, path = %path, "Forbidden - API key scope");
+            return Err(AppError::forbidden(&format!(
+                "API key scope '{}' does not allow this request",
+                key.scope
+            )));
+        }
+        let Some((role, hidden_account_ids)) = identity::api_key_access(&state.db, &key)? else {
+            warn!(key = %key.name, "Forbidden - API key creator is no longer a household user");
+            return Err(AppError::forbidden("API key creator is no longer a member"));
+        };
+        info!(key = %key.name, path = %path, "Authenticated via API key");
+        return Ok(CurrentUser {
+            email: key.identity(),
+            role,
+            user: None,
+            auth_method: "api_key",
+            hidden_account_ids,
+        });
     }
 
     warn!(path = %request.uri().path(), "Unauthorized request - no valid auth");
-    (
-        StatusCode::UNAUTHORIZED,
-        Json(serde_json::json!({
-            "error": "Authentication required"
-        })),
-    )
-        .into_response()
+    Err(AppError::unauthorized("Authentication required"))
+}
+
+/// Resolve an authenticated email to a household user, or reject it
+fn resolve_identity(
+    state: &AppState,
+    email: &str,
+    auth_method: &'static str,
+    role_cap: Option<UserRole>,
+) -> Result<CurrentUser, AppError> {
+    identity::resolve_email(&state.db, email, auth_method, role_cap).map_err(|e| match e {
+        identity::IdentityError::UnknownUser(_) => {
+            warn!(error = ?e, "Authenticated identity is not a household user");
+            AppError::forbidden("Not a member of this household")
+        }
+        identity::IdentityError::Visibility(msg) => {
+            error!(error = %msg, "Failed to load account visibility");
+            AppError::internal("Failed to load account visibility")
+        }
+        identity::IdentityError::Lookup(msg) => {
+            error!(error = %msg, "Failed to look up household user");
+            AppError::internal("Failed to look up household user")
+        }
+    })
 }
 
 /// Validate a Cloudflare Access JWT
@@ -334,22 +507,24 @@ async fn fetch_cf_public_keys(url: &str) -> Result<Vec<jsonwebtoken::jwk::Jwk>,
 }
 
 /// Validate an API key against the configured keys using constant-time comparison
-/// to prevent timing attacks.
-fn validate_api_key(provided: &str, valid_keys: &[String]) -> bool {
+/// to prevent timing attacks. Returns the matching entry.
+fn validate_api_key(provided: &str, valid_keys: &[String]) -> Option<ApiKeyEntry> {
     use subtle::ConstantTimeEq;
 
     let provided_bytes = provided.as_bytes();
 
-    for key in valid_keys {
-        let key_bytes = key.as_bytes();
+    for entry in valid_keys {
+        // Invalid entries are rejected at startup; never match one here
+        let Ok(entry) = ApiKeyEntry::parse(entry) else {
+            continue;
+        };
+        let key_bytes = entry.key.as_bytes();
         // Only compare if lengths match (constant-time for same-length keys)
-        if provided_bytes.len() == key_bytes.len() {
-            if provided_bytes.ct_eq(key_bytes).into() {
-                return true;
-            }
+        if provided_bytes.len() == key_bytes.len() && provided_bytes.ct_eq(key_bytes).into() {
+            return Some(entry);
         }
     }
-    false
+    None
 }
 
 /// Extract client IP address, respecting trusted proxies
@@ -445,8 +620,18 @@ pub fn parse_trusted_networks(input: &str) -> Vec<ipnet::IpNet> {
 }
 
 /// Extract user email from request headers (for audit logging)
-/// Returns CF Access email, "api-key" for API key auth, or "local-dev" for unauthenticated
+/// Returns the identity resolved by the auth middleware (the household user's email,
+/// "api-key" for unscoped API keys, or "local-dev" for unauthenticated requests)
 pub fn get_user_email(headers: &axum::http::HeaderMap) -> String {
+    // Identity resolved by the auth middleware
+    if let Some(user) = headers
+        .get(identity::HONE_USER_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|s| !s.is_empty())
+    {
+        return user.to_string();
+    }
+
     // Check for Cloudflare Access user first
     if let Some(email) = headers
         .get(CF_ACCESS_USER_HEADER)
@@ -528,11 +713,46 @@ pub fn create_router_with_options(
         backup_dir,
         receipts_dir,
         explore_sessions: handlers::ExploreSessionManager::new(),
+        local_auth: LocalAuthState::new(),
+        http_metrics: HttpMetrics::new(),
     });
 
+    build_router(state, static_dir, config)
+}
+
+/// Build the router around an existing application state
+fn build_router(state: Arc<AppState>, static_dir: Option<&str>, config: ServerConfig) -> Router {
     let api_routes = Router::new()
         // Auth
         .route("/me", get(handlers::get_me))
+        // Built-in authentication
+        .route("/auth/status", get(handlers::get_auth_status))
+        .route("/auth/login", post(handlers::login))
+        .route("/auth/logout", post(handlers::logout))
+        .route("/auth/password", post(handlers::change_password))
+        .route("/auth/totp/setup", post(handlers::setup_totp))
+        .route("/auth/totp/enable", post(handlers::enable_totp))
+        .route("/auth/totp/disable", post(handlers::disable_totp))
+        .route("/auth/sessions", get(handlers::list_sessions))
+        .route("/auth/sessions/:id", delete(handlers::revoke_session))
+        .route("/auth/passkeys", get(handlers::list_passkeys))
+        .route("/auth/passkeys/:id", delete(handlers::delete_passkey))
+        .route(
+            "/auth/passkeys/register/start",
+            post(handlers::start_passkey_registration),
+        )
+        .route(
+            "/auth/passkeys/register/finish",
+            post(handlers::finish_passkey_registration),
+        )
+        .route(
+            "/auth/passkeys/login/start",
+            post(handlers::start_passkey_login),
+        )
+        .route(
+            "/auth/passkeys/login/finish",
+            post(handlers::finish_passkey_login),
+        )
         // Dashboard
         .route("/dashboard", get(handlers::get_dashboard))
         // Accounts
@@ -550,6 +770,19 @@ pub fn create_router_with_options(
             "/accounts/:id/entity",
             axum::routing::patch(handlers::update_account_entity),
         )
+        .route(
+            "/accounts/:id/visibility",
+            get(handlers::get_account_visibility).put(handlers::set_account_visibility),
+        )
+        // Household users
+        .route(
+            "/users",
+            get(handlers::list_users).post(handlers::create_user),
+        )
+        .route(
+            "/users/:id",
+            axum::routing::patch(handlers::update_user).delete(handlers::delete_user),
+        )
         // Transactions
         .route("/transactions", get(handlers::list_transactions))
         .route(
@@ -566,6 +799,10 @@ pub fn create_router_with_options(
             "/subscriptions/:id/cancel",
             post(handlers::cancel_subscription),
         )
+        .route(
+            "/subscriptions/:id/cancellation",
+            get(handlers::get_subscription_cancellation),
+        )
         .route(
             "/subscriptions/:id/exclude",
             post(handlers::exclude_subscription),
@@ -575,6 +812,7 @@ pub fn create_router_with_options(
             post(handlers::unexclude_subscription),
         )
         .route("/subscriptions/:id", delete(handlers::delete_subscription))
+        .route("/cancellations", get(handlers::list_cancellations))
         // Alerts
         .route("/alerts", get(handlers::list_alerts))
         .route("/alerts/:id/dismiss", post(handlers::dismiss_alert))
@@ -605,6 +843,7 @@ pub fn create_router_with_options(
         // Import
         .route("/import", post(handlers::import_csv))
         .route("/import/json", post(handlers::import_csv_json))
+        .route("/import/transactions", post(handlers::import_transactions))
         // Import history
         .route("/imports", get(handlers::list_import_sessions))
         .route("/imports/:id", get(handlers::get_import_session))
@@ -635,8 +874,56 @@ pub fn create_router_with_options(
             "/imports/:session_id/runs/:run_id",
             get(handlers::get_reprocess_run),
         )
+        // Background jobs
+        .route("/jobs", get(handlers::list_jobs).post(handlers::create_job))
+        .route("/jobs/:id", get(handlers::get_job))
+        .route("/jobs/:id/cancel", post(handlers::cancel_job))
+        .route("/jobs/:id/retry", post(handlers::retry_job))
+        .route("/schedules", get(handlers::list_schedules))
+        .route("/schedules/:name/run", post(handlers::run_schedule))
+        // Settings
+        .route(
+            "/settings/detection",
+            get(handlers::get_detection_settings).put(handlers::update_detection_settings),
+        )
+        .route(
+            "/settings/detection/merchants/:merchant",
+            put(handlers::set_merchant_detection_overrides)
+                .delete(handlers::delete_merchant_detection_overrides),
+        )
+        .route(
+            "/settings/detection/tags/:tag_id",
+            put(handlers::set_tag_detection_overrides)
+                .delete(handlers::delete_tag_detection_overrides),
+        )
+        // API keys
+        .route(
+            "/keys",
+            get(handlers::list_api_keys).post(handlers::create_api_key),
+        )
+        .route("/keys/:id", delete(handlers::revoke_api_key))
+        // Notifications
+        .route(
+            "/notifications/channels",
+            get(handlers::list_notification_channels),
+        )
+        .route(
+            "/notifications/deliveries",
+            get(handlers::list_notification_deliveries),
+        )
+        .route(
+            "/notifications/deliveries/:id/retry",
+            post(handlers::retry_notification_delivery),
+        )
+        .route(
+            "/notifications/test",
+            post(handlers::send_test_notification),
+        )
         // Audit log
         .route("/audit", get(handlers::list_audit_log))
+        .route("/audit/export", get(handlers::export_audit_log))
+        .route("/audit/verify", get(handlers::verify_audit_log))
+        .route("/audit/archive", post(handlers::archive_audit_log))
         // Tags
         .route("/tags", get(handlers::list_tags).post(handlers::create_tag))
         .route("/tags/tree", get(handlers::get_tag_tree))
@@ -685,6 +972,30 @@ pub fn create_router_with_options(
             "/reports/property-expenses/:id",
             get(handlers::report_property_expenses),
         )
+        .route(
+            "/reports/items/:id/prices",
+            get(handlers::report_item_prices),
+        )
+        .route(
+            "/reports/item-inflation",
+            get(handlers::report_item_inflation),
+        )
+        .route("/reports/split-types", get(handlers::report_split_types))
+        .route("/reports/warranties", get(handlers::report_warranties))
+        // Item catalog
+        .route("/items", get(handlers::list_catalog_items))
+        .route("/items/:id/merge", post(handlers::merge_catalog_item))
+        // Warranties and return windows
+        .route(
+            "/warranties",
+            get(handlers::list_warranties).post(handlers::create_warranty),
+        )
+        .route(
+            "/warranties/:id",
+            get(handlers::get_warranty)
+                .patch(handlers::update_warranty)
+                .delete(handlers::delete_warranty),
+        )
         // Entities
         .route(
             "/entities",
@@ -751,6 +1062,7 @@ pub fn create_router_with_options(
             get(handlers::get_receipt).delete(handlers::delete_receipt),
         )
         .route("/receipts/:id/parse", post(handlers::parse_receipt))
+        .route("/receipts/:id/parsed", put(handlers::update_receipt_parsed))
         // Receipt-first workflow
         .route(
             "/receipts",
@@ -770,6 +1082,21 @@ pub fn create_router_with_options(
             get(handlers::get_receipt_match_candidates),
         )
         .route("/receipts/auto-match", post(handlers::auto_match_receipts))
+        // Refunds
+        .route("/refunds", get(handlers::list_refunds))
+        .route("/refunds/auto-match", post(handlers::auto_match_refunds))
+        .route(
+            "/transactions/:id/refunds",
+            get(handlers::get_transaction_refunds),
+        )
+        .route(
+            "/transactions/:id/refund-candidates",
+            get(handlers::get_refund_candidates),
+        )
+        .route(
+            "/transactions/:id/refund",
+            post(handlers::link_refund).delete(handlers::unlink_refund),
+        )
         // AI Suggestions
         .route(
             "/transactions/:id/suggest-entity",
@@ -870,11 +1197,44 @@ pub fn create_router_with_options(
         .route("/training/agent", get(handlers::training_agent))
         // Explore mode (conversational queries with session support)
         .route("/explore/query", post(handlers::query_explore))
+        .route(
+            "/explore/query/stream",
+            post(handlers::query_explore_stream),
+        )
         .route("/explore/models", get(handlers::list_explore_models))
         .route("/explore/session", post(handlers::create_explore_session))
+        .route("/explore/sessions", get(handlers::list_explore_sessions))
         .route(
             "/explore/session/:id",
-            get(handlers::get_explore_session).delete(handlers::delete_explore_session),
+            get(handlers::get_explore_session)
+                .patch(handlers::rename_explore_session)
+                .delete(handlers::delete_explore_session),
+        )
+        .route(
+            "/explore/session/:id/export",
+            get(handlers::export_explore_session),
+        )
+        .route(
+            "/explore/saved",
+            get(handlers::list_saved_questions).post(handlers::create_saved_question),
+        )
+        .route(
+            "/explore/saved/:id",
+            get(handlers::get_saved_question)
+                .patch(handlers::update_saved_question)
+                .delete(handlers::delete_saved_question),
+        )
+        .route(
+            "/explore/saved/:id/run",
+            post(handlers::run_saved_question_now),
+        )
+        .route(
+            "/explore/saved/:id/digests",
+            get(handlers::list_saved_question_digests),
+        )
+        .route(
+            "/explore/session/:id/cancel",
+            post(handlers::cancel_explore_query),
         );
 
     // Build CORS layer
@@ -888,7 +1248,11 @@ pub fn create_router_with_options(
                 Method::DELETE,
                 Method::OPTIONS,
             ])
-            .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
+            .allow_headers([
+                header::CONTENT_TYPE,
+                header::AUTHORIZATION,
+                HeaderName::from_static(local_auth::CSRF_HEADER),
+            ])
     } else {
         // Allow specified origins
         let origins: Vec<HeaderValue> = config
@@ -905,7 +1269,11 @@ pub fn create_router_with_options(
                 Method::DELETE,
                 Method::OPTIONS,
             ])
-            .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
+            .allow_headers([
+                header::CONTENT_TYPE,
+                header::AUTHORIZATION,
+                HeaderName::from_static(local_auth::CSRF_HEADER),
+            ])
     };
 
     // Security headers
@@ -920,6 +1288,12 @@ pub fn create_router_with_options(
             state.clone(),
             auth_middleware,
         ))
+        // Outside the auth middleware: scrapers have their own access control
+        .route("/metrics", get(metrics::get_metrics))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            metrics::track_http_metrics,
+        ))
         .with_state(state)
         .layer(TraceLayer::new_for_http())
         .layer(cors)
@@ -965,11 +1339,32 @@ pub async fn serve_with_config(
     host: &str,
     port: u16,
     static_dir: Option<&str>,
-    config: ServerConfig,
+    mut config: ServerConfig,
 ) -> anyhow::Result<()> {
     if !config.require_auth {
         warn!("⚠️  Authentication disabled - do not expose to network!");
     }
+    if config.metrics.enabled
+        && config.metrics.token.is_none()
+        && config.metrics.allowed_networks.is_empty()
+    {
+        warn!("⚠️  HONE_METRICS is on without HONE_METRICS_TOKEN or HONE_METRICS_NETWORKS - /metrics will refuse every scrape");
+    }
+
+    // Re-queue background jobs that were interrupted by server restart
+    // (before import recovery, so resumable imports aren't marked failed)
+    match db.recover_interrupted_jobs() {
+        Ok(count) if count > 0 => {
+            warn!(
+                "⚠️  Re-queued {} interrupted job(s) from previous server session",
+                count
+            );
+        }
+        Ok(_) => {}
+        Err(e) => {
+            warn!("Failed to recover interrupted jobs: {}", e);
+        }
+    }
 
     // Recover any imports that were interrupted by server restart
     match db.recover_stuck_imports() {
@@ -1002,9 +1397,51 @@ pub async fn serve_with_config(
     // Check Ollama connection
     check_ai_connection().await;
 
-    // Start backup scheduler if configured
-    if let Some(backup_config) = BackupScheduleConfig::from_env() {
-        start_backup_scheduler(db.clone(), backup_config);
+    // Start job workers (import processing, reprocess, detection, insights, backups,
+    // scheduled tasks)
+    let backup_config = BackupScheduleConfig::from_env();
+    let mut runner = JobRunner::new(db.clone(), AIClient::from_env());
+    if let Some(dir) = backup_config.as_ref().and_then(|c| c.backup_dir.clone()) {
+        runner = runner.with_backup_dir(dir);
+    }
+    start_job_workers(runner);
+
+    // HONE_BACKUP_SCHEDULE becomes a cron-scheduled backup task
+    if let Some(backup_config) = backup_config {
+        config
+            .schedules
+            .add_backup_interval(backup_config.interval_hours, backup_config.retention_count)?;
+    }
+
+    // Audit log retention archives daily
+    if let Some(retention_config) = AuditRetentionConfig::from_env() {
+        config
+            .schedules
+            .add_audit_retention(retention_config.retention_days)?;
+    }
+
+    // Saved explore questions are checked on an interval (needs the AI orchestrator)
+    if let Some(digest_config) = DigestScheduleConfig::from_env() {
+        if AIOrchestrator::from_env(db.clone()).is_some() {
+            config
+                .schedules
+                .add_explore_digests(digest_config.check_interval_minutes)?;
+        } else {
+            info!("ℹ️  Explore digests not scheduled (AI orchestrator not configured)");
+        }
+    }
+
+    // Start the task scheduler (it also prunes old finished jobs)
+    start_task_scheduler(db.clone(), config.schedules.clone())?;
+
+    // Start the import folder watcher if a folder was given
+    if let Some(watch_config) = config.watch.clone() {
+        start_import_watcher(db.clone(), watch_config)?;
+    }
+
+    // Start the notification dispatcher if channels are configured
+    if let Some(notify_config) = config.notifications.clone() {
+        start_notification_scheduler(db.clone(), notify_config);
     }
 
     let app = create_router(db, static_dir, config)
@@ -1082,6 +1519,22 @@ impl AppError {
         }
     }
 
+    pub fn unauthorized(msg: &str) -> Self {
+        Self {
+            status: StatusCode::UNAUTHORIZED,
+            message: msg.to_string(),
+            internal: None,
+        }
+    }
+
+    pub fn forbidden(msg: &str) -> Self {
+        Self {
+            status: StatusCode::FORBIDDEN,
+            message: msg.to_string(),
+            internal: None,
+        }
+    }
+
     pub fn conflict(msg: &str) -> Self {
         Self {
             status: StatusCode::CONFLICT,
@@ -0,0 +1,378 @@
+//! Built-in authentication: password/TOTP and passkey logins with session cookies
+//!
+//! For self-hosters without Cloudflare Access. Logins create a server-side
+//! session whose token is sent as an HTTP-only `hone_session` cookie. A
+//! separate, script-readable `hone_csrf` cookie carries the CSRF token that
+//! state-changing requests must echo in the `X-CSRF-Token` header.
+//!
+//! Session checks run inside `auth_middleware` alongside the other auth
+//! methods; the login endpoints themselves are public (see `is_public_path`).
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::http::{header, HeaderMap, HeaderValue, Method};
+use subtle::ConstantTimeEq;
+use tracing::{info, warn};
+
+use hone_core::models::User;
+
+use crate::webauthn::RelyingParty;
+use crate::{AppError, AppState, CurrentUser};
+
+/// Session cookie (HTTP-only)
+pub const SESSION_COOKIE: &str = "hone_session";
+
+/// CSRF cookie (readable by the UI, echoed back in `CSRF_HEADER`)
+pub const CSRF_COOKIE: &str = "hone_csrf";
+
+/// Header state-changing session requests must carry
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// Failed logins allowed per client IP or email within `LOGIN_WINDOW`
+const MAX_LOGIN_FAILURES: usize = 5;
+
+/// Rate limit window for failed logins
+const LOGIN_WINDOW: Duration = Duration::from_secs(15 * 60);
+
+/// How long a passkey challenge stays valid
+const CHALLENGE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Built-in auth configuration
+#[derive(Clone, Debug)]
+pub struct LocalAuthConfig {
+    /// Accept password/passkey logins and session cookies
+    pub enabled: bool,
+    /// Session lifetime
+    pub session_ttl_hours: i64,
+    /// Mark cookies `Secure` (disable only for plain-HTTP LAN setups)
+    pub secure_cookies: bool,
+    /// Passkey relying party (None = passkeys disabled)
+    pub relying_party: Option<RelyingParty>,
+}
+
+impl Default for LocalAuthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            session_ttl_hours: 24 * 7,
+            secure_cookies: true,
+            relying_party: None,
+        }
+    }
+}
+
+impl LocalAuthConfig {
+    /// Load from environment variables
+    ///
+    /// - `HONE_LOCAL_AUTH`: enable built-in auth (true/1)
+    /// - `HONE_SESSION_TTL_HOURS`: session lifetime (default 168)
+    /// - `HONE_INSECURE_COOKIES`: drop the `Secure` cookie flag for plain-HTTP access
+    /// - `HONE_WEBAUTHN_ORIGIN`: site origin for passkeys (e.g., https://hone.example.com)
+    /// - `HONE_WEBAUTHN_RP_ID`: passkey RP ID (default: the origin's host)
+    pub fn from_env() -> Self {
+        let flag = |name: &str| {
+            std::env::var(name)
+                .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false)
+        };
+
+        let relying_party = std::env::var("HONE_WEBAUTHN_ORIGIN")
+            .ok()
+            .map(|o| o.trim().trim_end_matches('/').to_string())
+            .filter(|o| !o.is_empty())
+            .map(|origin| {
+                let id = std::env::var("HONE_WEBAUTHN_RP_ID")
+                    .ok()
+                    .filter(|id| !id.trim().is_empty())
+                    .unwrap_or_else(|| origin_host(&origin));
+                RelyingParty { id, origin }
+            });
+
+        Self {
+            enabled: flag("HONE_LOCAL_AUTH"),
+            session_ttl_hours: std::env::var("HONE_SESSION_TTL_HOURS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|h: &i64| *h > 0)
+                .unwrap_or(24 * 7),
+            secure_cookies: !flag("HONE_INSECURE_COOKIES"),
+            relying_party,
+        }
+    }
+}
+
+/// Host part of an origin URL ("https://hone.example.com:8443" -> "hone.example.com")
+fn origin_host(origin: &str) -> String {
+    let without_scheme = origin.split("://").nth(1).unwrap_or(origin);
+    let host_port = without_scheme.split('/').next().unwrap_or_default();
+    host_port
+        .rsplit_once(':')
+        .filter(|(_, port)| port.chars().all(|c| c.is_ascii_digit()))
+        .map(|(host, _)| host)
+        .unwrap_or(host_port)
+        .to_string()
+}
+
+/// Requests that may reach their handler without authenticating
+pub(crate) fn is_public_path(method: &Method, path: &str) -> bool {
+    let path = path.strip_prefix("/api").unwrap_or(path);
+    match *method {
+        Method::GET => path == "/auth/status",
+        Method::POST => matches!(
+            path,
+            "/auth/login" | "/auth/passkeys/login/start" | "/auth/passkeys/login/finish"
+        ),
+        _ => false,
+    }
+}
+
+/// What a passkey challenge was issued for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ChallengePurpose {
+    Register { user_id: i64 },
+    Login,
+}
+
+/// In-memory state for built-in auth
+#[derive(Default)]
+pub struct LocalAuthState {
+    /// Recent failed login times by rate-limit key (`ip:...`, `email:...`)
+    failures: Mutex<HashMap<String, Vec<Instant>>>,
+    /// Outstanding passkey challenges (base64url) and when they were issued
+    challenges: Mutex<HashMap<String, (ChallengePurpose, Instant)>>,
+}
+
+impl LocalAuthState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seconds until a key may try again, if it has too many recent failures
+    pub(crate) fn retry_after(&self, key: &str) -> Option<u64> {
+        let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let times = failures.get_mut(key)?;
+        times.retain(|t| now.duration_since(*t) < LOGIN_WINDOW);
+        if times.len() >= MAX_LOGIN_FAILURES {
+            let oldest = times.iter().min().copied()?;
+            Some((LOGIN_WINDOW - now.duration_since(oldest)).as_secs().max(1))
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn record_failure(&self, key: &str) {
+        self.record_failure_at(key, Instant::now());
+    }
+
+    /// Record a failure, forgetting keys whose failures have all expired
+    ///
+    /// Sweeping here keeps the map from growing with every IP or email an
+    /// attacker tries.
+    fn record_failure_at(&self, key: &str, now: Instant) {
+        let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        failures.retain(|_, times| {
+            times.retain(|t| now.saturating_duration_since(*t) < LOGIN_WINDOW);
+            !times.is_empty()
+        });
+        failures.entry(key.to_string()).or_default().push(now);
+    }
+
+    pub(crate) fn clear_failures(&self, key: &str) {
+        let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        failures.remove(key);
+    }
+
+    /// Issue a new passkey challenge
+    pub(crate) fn issue_challenge(&self, purpose: ChallengePurpose) -> Result<String, AppError> {
+        let mut bytes = [0u8; 32];
+        hone_core::local_auth::random_bytes(&mut bytes)?;
+        let challenge = crate::webauthn::b64url_encode(&bytes);
+
+        let mut challenges = self.challenges.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        challenges.retain(|_, (_, issued)| now.duration_since(*issued) < CHALLENGE_TTL);
+        challenges.insert(challenge.clone(), (purpose, now));
+        Ok(challenge)
+    }
+
+    /// Consume a challenge (single use); None if unknown or expired
+    pub(crate) fn take_challenge(&self, challenge: &str) -> Option<ChallengePurpose> {
+        let mut challenges = self.challenges.lock().unwrap_or_else(|e| e.into_inner());
+        let (purpose, issued) = challenges.remove(challenge)?;
+        (issued.elapsed() < CHALLENGE_TTL).then_some(purpose)
+    }
+}
+
+/// Read a cookie value from request headers
+pub(crate) fn get_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(k, _)| *k == name)
+        .map(|(_, v)| v.to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// `Set-Cookie` values for a new session (session token + CSRF token)
+pub(crate) fn session_cookies(
+    config: &LocalAuthConfig,
+    token: &str,
+    csrf_token: &str,
+) -> [HeaderValue; 2] {
+    let max_age = config.session_ttl_hours * 3600;
+    let secure = if config.secure_cookies {
+        "; Secure"
+    } else {
+        ""
+    };
+    [
+        HeaderValue::from_str(&format!(
+            "{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Strict{}",
+            SESSION_COOKIE, token, max_age, secure
+        ))
+        .expect("hex token is a valid header value"),
+        HeaderValue::from_str(&format!(
+            "{}={}; Path=/; Max-Age={}; SameSite=Strict{}",
+            CSRF_COOKIE, csrf_token, max_age, secure
+        ))
+        .expect("hex token is a valid header value"),
+    ]
+}
+
+/// `Set-Cookie` values that clear the session cookies
+pub(crate) fn clear_session_cookies(config: &LocalAuthConfig) -> [HeaderValue; 2] {
+    let secure = if config.secure_cookies {
+        "; Secure"
+    } else {
+        ""
+    };
+    [SESSION_COOKIE, CSRF_COOKIE].map(|name| {
+        HeaderValue::from_str(&format!(
+            "{}=; Path=/; Max-Age=0; SameSite=Strict{}",
+            name, secure
+        ))
+        .expect("static cookie is a valid header value")
+    })
+}
+
+/// Authenticate a request by session cookie
+///
+/// Returns None when there is no valid session (so other auth methods are
+/// tried), or an error when the session is valid but the CSRF check fails.
+pub(crate) fn authenticate_session(
+    state: &AppState,
+    method: &Method,
+    headers: &HeaderMap,
+    path: &str,
+) -> Option<Result<CurrentUser, AppError>> {
+    let token = get_cookie(headers, SESSION_COOKIE)?;
+    let session = match state.db.get_auth_session(&token) {
+        Ok(session) => session?,
+        Err(e) => {
+            warn!(error = %e, "Failed to look up session");
+            return None;
+        }
+    };
+    let user: User = state.db.get_user(session.user_id).ok().flatten()?;
+
+    if !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        let provided = headers
+            .get(CSRF_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        let valid = provided.len() == session.csrf_token.len()
+            && bool::from(provided.as_bytes().ct_eq(session.csrf_token.as_bytes()));
+        if !valid {
+            warn!(user = %user.email, path = %path, "Rejected session request without valid CSRF token");
+            return Some(Err(AppError::forbidden("Missing or invalid CSRF token")));
+        }
+    }
+
+    if let Err(e) = state.db.touch_user(user.id) {
+        warn!(error = %e, "Failed to update user last_seen_at");
+    }
+    info!(user = %user.email, path = %path, "Authenticated via session");
+    let role = user.role;
+    Some(CurrentUser::household(&state.db, user, role, "session").map_err(AppError::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_origin_host() {
+        assert_eq!(origin_host("https://hone.example.com"), "hone.example.com");
+        assert_eq!(origin_host("http://localhost:3000"), "localhost");
+        assert_eq!(
+            origin_host("https://hone.example.com/app"),
+            "hone.example.com"
+        );
+    }
+
+    #[test]
+    fn test_get_cookie() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("theme=dark; hone_session=abc123; hone_csrf=xyz"),
+        );
+        assert_eq!(
+            get_cookie(&headers, SESSION_COOKIE).as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(get_cookie(&headers, CSRF_COOKIE).as_deref(), Some("xyz"));
+        assert_eq!(get_cookie(&headers, "missing"), None);
+    }
+
+    #[test]
+    fn test_login_rate_limit() {
+        let state = LocalAuthState::new();
+        for _ in 0..MAX_LOGIN_FAILURES - 1 {
+            state.record_failure("ip:10.0.0.1");
+        }
+        assert!(state.retry_after("ip:10.0.0.1").is_none());
+        state.record_failure("ip:10.0.0.1");
+        assert!(state.retry_after("ip:10.0.0.1").is_some());
+        assert!(state.retry_after("ip:10.0.0.2").is_none());
+
+        state.clear_failures("ip:10.0.0.1");
+        assert!(state.retry_after("ip:10.0.0.1").is_none());
+    }
+
+    #[test]
+    fn test_expired_failure_keys_are_swept() {
+        let state = LocalAuthState::new();
+        let now = Instant::now();
+        let Some(old) = now.checked_sub(LOGIN_WINDOW + Duration::from_secs(1)) else {
+            return;
+        };
+        for i in 0..100 {
+            state.record_failure_at(&format!("ip:10.0.1.{}", i), old);
+        }
+        state.record_failure_at("ip:10.0.0.1", old);
+        state.record_failure_at("ip:10.0.0.1", now);
+
+        let failures = state.failures.lock().unwrap();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures["ip:10.0.0.1"], vec![now]);
+    }
+
+    #[test]
+    fn test_challenges_are_single_use() {
+        let state = LocalAuthState::new();
+        let challenge = state.issue_challenge(ChallengePurpose::Login).ok().unwrap();
+        assert_eq!(
+            state.take_challenge(&challenge),
+            Some(ChallengePurpose::Login)
+        );
+        assert_eq!(state.take_challenge(&challenge), None);
+        assert_eq!(state.take_challenge("made-up"), None);
+    }
+}
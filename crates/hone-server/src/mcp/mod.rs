@@ -7,6 +7,9 @@
 //!
 //! The MCP server runs on a separate port from the main REST API,
 //! using HTTP/SSE (Streamable HTTP) transport for local network access.
+//! Once an `mcp`-scoped API key exists, requests must send an `mcp` or
+//! `admin` key as a Bearer token, and tools only read the accounts the
+//! key's creator may see.
 //!
 //! # Example
 //!
@@ -24,13 +27,16 @@
 //! - `compare_spending` - Period-over-period comparison
 //! - `get_merchants` - Top merchants by spending
 //! - `get_account_summary` - Account balances and activity
+//! - `get_item_prices` - Receipt item price changes and totals by line type
+//! - `get_warranties` - Items under warranty or within their return window
 
 mod tools;
 
 use std::sync::Arc;
 
+use axum::http::request::Parts;
 use rmcp::{
-    handler::server::router::tool::ToolRouter,
+    handler::server::{router::tool::ToolRouter, tool::Extension},
     model::{
         CallToolResult, Content, Implementation, ProtocolVersion, ServerCapabilities, ServerInfo,
     },
@@ -65,8 +71,21 @@ impl HoneMcpServer {
     pub(crate) async fn db(&self) -> tokio::sync::MutexGuard<'_, Database> {
         self.db.lock().await
     }
+
+    /// Database handle for one tool call, limited to what the request's API
+    /// key may see (see `mcp_auth`)
+    async fn db_for(&self, parts: &Parts) -> Database {
+        match parts.extensions.get::<ScopedDb>() {
+            Some(ScopedDb(db)) => db.clone(),
+            None => self.db().await.clone(),
+        }
+    }
 }
 
+/// Visibility-scoped database handle attached to authenticated MCP requests
+#[derive(Clone)]
+struct ScopedDb(Database);
+
 #[tool_handler]
 impl ServerHandler for HoneMcpServer {
     fn get_info(&self) -> ServerInfo {
@@ -96,9 +115,12 @@ impl HoneMcpServer {
     #[tool(
         description = "Search for transactions. Returns matching transactions with amount, date, merchant, and tags."
     )]
-    async fn search_transactions(&self) -> Result<CallToolResult, McpError> {
+    async fn search_transactions(
+        &self,
+        Extension(parts): Extension<Parts>,
+    ) -> Result<CallToolResult, McpError> {
         // For now, return a simple result - we'll add parameters later
-        let db = self.db().await;
+        let db = self.db_for(&parts).await;
         let params = SearchTransactionsParams::default();
         match tools::search_transactions(&db, params) {
             Ok(result) => Ok(CallToolResult::success(vec![Content::text(
@@ -112,8 +134,11 @@ impl HoneMcpServer {
     #[tool(
         description = "Get spending breakdown by category. Returns total spending per category with percentages."
     )]
-    async fn get_spending_summary(&self) -> Result<CallToolResult, McpError> {
-        let db = self.db().await;
+    async fn get_spending_summary(
+        &self,
+        Extension(parts): Extension<Parts>,
+    ) -> Result<CallToolResult, McpError> {
+        let db = self.db_for(&parts).await;
         let params = SpendingSummaryParams::default();
         match tools::get_spending_summary(&db, params) {
             Ok(result) => Ok(CallToolResult::success(vec![Content::text(
@@ -127,8 +152,11 @@ impl HoneMcpServer {
     #[tool(
         description = "List subscriptions. Shows recurring charges with amount, frequency, and status (active/cancelled/excluded)."
     )]
-    async fn get_subscriptions(&self) -> Result<CallToolResult, McpError> {
-        let db = self.db().await;
+    async fn get_subscriptions(
+        &self,
+        Extension(parts): Extension<Parts>,
+    ) -> Result<CallToolResult, McpError> {
+        let db = self.db_for(&parts).await;
         let params = SubscriptionsParams::default();
         match tools::get_subscriptions(&db, params) {
             Ok(result) => Ok(CallToolResult::success(vec![Content::text(
@@ -142,8 +170,11 @@ impl HoneMcpServer {
     #[tool(
         description = "Get waste detection alerts. Shows zombie subscriptions, price increases, duplicates, and spending anomalies."
     )]
-    async fn get_alerts(&self) -> Result<CallToolResult, McpError> {
-        let db = self.db().await;
+    async fn get_alerts(
+        &self,
+        Extension(parts): Extension<Parts>,
+    ) -> Result<CallToolResult, McpError> {
+        let db = self.db_for(&parts).await;
         let params = AlertsParams::default();
         match tools::get_alerts(&db, params) {
             Ok(result) => Ok(CallToolResult::success(vec![Content::text(
@@ -157,8 +188,11 @@ impl HoneMcpServer {
     #[tool(
         description = "Compare spending between two periods. Shows changes by category with increase/decrease amounts."
     )]
-    async fn compare_spending(&self) -> Result<CallToolResult, McpError> {
-        let db = self.db().await;
+    async fn compare_spending(
+        &self,
+        Extension(parts): Extension<Parts>,
+    ) -> Result<CallToolResult, McpError> {
+        let db = self.db_for(&parts).await;
         let params = CompareSpendingParams::default();
         match tools::compare_spending(&db, params) {
             Ok(result) => Ok(CallToolResult::success(vec![Content::text(
@@ -172,8 +206,11 @@ impl HoneMcpServer {
     #[tool(
         description = "Get top merchants by spending amount. Returns merchant name, total spent, and transaction count."
     )]
-    async fn get_merchants(&self) -> Result<CallToolResult, McpError> {
-        let db = self.db().await;
+    async fn get_merchants(
+        &self,
+        Extension(parts): Extension<Parts>,
+    ) -> Result<CallToolResult, McpError> {
+        let db = self.db_for(&parts).await;
         let params = MerchantsParams::default();
         match tools::get_merchants(&db, params) {
             Ok(result) => Ok(CallToolResult::success(vec![Content::text(
@@ -185,8 +222,11 @@ impl HoneMcpServer {
 
     /// Get account summary
     #[tool(description = "Get summary of all accounts with recent activity and totals.")]
-    async fn get_account_summary(&self) -> Result<CallToolResult, McpError> {
-        let db = self.db().await;
+    async fn get_account_summary(
+        &self,
+        Extension(parts): Extension<Parts>,
+    ) -> Result<CallToolResult, McpError> {
+        let db = self.db_for(&parts).await;
         let params = AccountSummaryParams::default();
         match tools::get_account_summary(&db, params) {
             Ok(result) => Ok(CallToolResult::success(vec![Content::text(
@@ -195,6 +235,42 @@ impl HoneMcpServer {
             Err(e) => Err(McpError::internal_error(e.to_string(), None)),
         }
     }
+
+    /// Get receipt item prices and split type totals
+    #[tool(
+        description = "Get item-level price changes from parsed receipts (e.g. grocery staples over time) and totals by line type: tax, tip, fees, discounts, rewards."
+    )]
+    async fn get_item_prices(
+        &self,
+        Extension(parts): Extension<Parts>,
+    ) -> Result<CallToolResult, McpError> {
+        let db = self.db_for(&parts).await;
+        let params = ItemPricesParams::default();
+        match tools::get_item_prices(&db, params) {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&result).unwrap_or_default(),
+            )])),
+            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+        }
+    }
+
+    /// Get items under warranty or still returnable
+    #[tool(
+        description = "Get what the user owns that is still under warranty or within its return window, soonest deadline first, with serial numbers."
+    )]
+    async fn get_warranties(
+        &self,
+        Extension(parts): Extension<Parts>,
+    ) -> Result<CallToolResult, McpError> {
+        let db = self.db_for(&parts).await;
+        let params = WarrantiesParams::default();
+        match tools::get_warranties(&db, params) {
+            Ok(result) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&result).unwrap_or_default(),
+            )])),
+            Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+        }
+    }
 }
 
 /// Start the MCP server on the given port
@@ -204,13 +280,16 @@ pub async fn start_mcp_server(db: Database, host: &str, port: u16) -> anyhow::Re
 
     info!("Starting MCP server at http://{}:{}/mcp", host, port);
 
+    let db_for_auth = db.clone();
     let service = StreamableHttpService::new(
         move || Ok(HoneMcpServer::new(db.clone())),
         LocalSessionManager::default().into(),
         Default::default(),
     );
 
-    let router = axum::Router::new().nest_service("/mcp", service);
+    let router = axum::Router::new()
+        .nest_service("/mcp", service)
+        .layer(axum::middleware::from_fn_with_state(db_for_auth, mcp_auth));
     let addr = format!("{}:{}", host, port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
 
@@ -225,3 +304,60 @@ pub async fn start_mcp_server(db: Database, host: &str, port: u16) -> anyhow::Re
 
     Ok(())
 }
+
+/// Require an `mcp` or `admin` scoped API key once any `mcp` key exists
+///
+/// Without `mcp` keys the server stays open, as before, for trusted local
+/// networks. Creating one (`hone keys create <name> --scope mcp`) turns
+/// authentication on.
+async fn mcp_auth(
+    axum::extract::State(db): axum::extract::State<Database>,
+    mut request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use axum::{http::StatusCode, response::IntoResponse};
+    use hone_core::models::ApiKeyScope;
+
+    match db.has_active_api_key_scope(ApiKeyScope::Mcp) {
+        Ok(false) => return next.run(request).await,
+        Ok(true) => {}
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to check MCP API keys");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+
+    let key = request
+        .headers()
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|auth| auth.strip_prefix("Bearer "))
+        .and_then(|secret| db.authenticate_api_key(secret).ok().flatten());
+
+    match key {
+        Some(key) if matches!(key.scope, ApiKeyScope::Mcp | ApiKeyScope::Admin) => {
+            tracing::debug!(key = %key.name, "MCP request authenticated via API key");
+            match crate::identity::api_key_access(&db, &key) {
+                Ok(Some((_, hidden))) => {
+                    request
+                        .extensions_mut()
+                        .insert(ScopedDb(db.with_hidden_accounts(hidden)));
+                    next.run(request).await
+                }
+                Ok(None) => {
+                    tracing::warn!(key = %key.name, "MCP key creator is no longer a household user");
+                    StatusCode::FORBIDDEN.into_response()
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to load account visibility for MCP key");
+                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                }
+            }
+        }
+        Some(key) => {
+            tracing::warn!(key = %key.name, scope = %key.scope, "API key scope does not allow MCP");
+            StatusCode::FORBIDDEN.into_response()
+        }
+        None => StatusCode::UNAUTHORIZED.into_response(),
+    }
+}
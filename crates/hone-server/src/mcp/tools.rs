@@ -10,9 +10,11 @@ pub use hone_core::tools::{
     compare_spending,
     get_account_summary,
     get_alerts,
+    get_item_prices,
     get_merchants,
     get_spending_summary,
     get_subscriptions,
+    get_warranties,
     resolve_period,
     search_transactions,
     // Result types
@@ -27,6 +29,9 @@ pub use hone_core::tools::{
     CategorySpending,
     CompareSpendingParams,
     CompareSpendingResult,
+    ItemPriceSummary,
+    ItemPricesParams,
+    ItemPricesResult,
     MerchantSummary,
     MerchantsParams,
     MerchantsResult,
@@ -34,8 +39,12 @@ pub use hone_core::tools::{
     SearchTransactionsResult,
     SpendingSummaryParams,
     SpendingSummaryResult,
+    SplitTypeTotal,
     SubscriptionSummary,
     SubscriptionsParams,
     SubscriptionsResult,
     TransactionSummary,
+    WarrantiesParams,
+    WarrantiesResult,
+    WarrantySummary,
 };
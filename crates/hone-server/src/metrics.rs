@@ -0,0 +1,489 @@
+//! Prometheus/OpenMetrics endpoint
+//!
+//! `GET /metrics` serves the Prometheus text format. It sits outside the
+//! `/api` auth middleware and has its own access control (see
+//! `MetricsConfig`), so a scraper never needs a household identity.
+//!
+//! HTTP request counters and latencies are kept in memory per matched route.
+//! Everything else (AI calls, imports, alerts, DB stats) is read from the
+//! database at scrape time, so those series survive restarts.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, MatchedPath, Request, State},
+    http::{header, Response, StatusCode},
+    middleware::Next,
+};
+use subtle::ConstantTimeEq;
+use tracing::warn;
+
+use hone_core::backup::{default_backup_dir, LocalDestination};
+use hone_core::models::AI_LATENCY_BUCKETS;
+use hone_core::Database;
+
+use crate::{get_client_ip, is_ip_trusted, AppError, AppState, AUTHORIZATION_HEADER};
+
+/// Upper bounds (seconds) of the HTTP request latency histogram buckets
+const HTTP_LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Route label for requests that matched no route (static files, 404s)
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Access control for `/metrics`
+///
+/// Scrapers are allowed by bearer token or by source network. With neither
+/// configured, every scrape is refused (a warning is logged at startup).
+#[derive(Clone, Debug, Default)]
+pub struct MetricsConfig {
+    /// Serve `/metrics` at all (404 otherwise)
+    pub enabled: bool,
+    /// Bearer token scrapers may send
+    pub token: Option<String>,
+    /// Networks allowed to scrape without a token
+    pub allowed_networks: Vec<ipnet::IpNet>,
+}
+
+impl MetricsConfig {
+    /// Load from environment variables
+    ///
+    /// - `HONE_METRICS`: enable the endpoint (true/1)
+    /// - `HONE_METRICS_TOKEN`: bearer token for scrapers
+    /// - `HONE_METRICS_NETWORKS`: comma-separated IPs/CIDRs allowed to scrape
+    pub fn from_env() -> Self {
+        Self {
+            enabled: std::env::var("HONE_METRICS")
+                .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            token: std::env::var("HONE_METRICS_TOKEN")
+                .ok()
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty()),
+            allowed_networks: crate::parse_trusted_networks(
+                &std::env::var("HONE_METRICS_NETWORKS").unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+/// Request totals and latency histogram for one method + route
+#[derive(Default)]
+struct RouteStats {
+    by_status: BTreeMap<u16, u64>,
+    count: u64,
+    latency_sum: f64,
+    /// Cumulative counts per `HTTP_LATENCY_BUCKETS` bound
+    buckets: [u64; HTTP_LATENCY_BUCKETS.len()],
+}
+
+/// In-memory HTTP request metrics, keyed by (method, route)
+#[derive(Default)]
+pub struct HttpMetrics {
+    routes: Mutex<BTreeMap<(String, String), RouteStats>>,
+}
+
+impl HttpMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&self, method: &str, route: &str, status: u16, seconds: f64) {
+        let mut routes = self.routes.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = routes
+            .entry((method.to_string(), route.to_string()))
+            .or_default();
+        *stats.by_status.entry(status).or_default() += 1;
+        stats.count += 1;
+        stats.latency_sum += seconds;
+        for (bucket, le) in stats.buckets.iter_mut().zip(HTTP_LATENCY_BUCKETS) {
+            if seconds <= le {
+                *bucket += 1;
+            }
+        }
+    }
+
+    fn render(&self, out: &mut MetricsWriter) {
+        let routes = self.routes.lock().unwrap_or_else(|e| e.into_inner());
+
+        out.family(
+            "hone_http_requests_total",
+            "counter",
+            "HTTP requests by method, route and status",
+        );
+        for ((method, route), stats) in routes.iter() {
+            for (status, count) in &stats.by_status {
+                out.sample(
+                    "hone_http_requests_total",
+                    &[
+                        ("method", method),
+                        ("route", route),
+                        ("status", &status.to_string()),
+                    ],
+                    *count as f64,
+                );
+            }
+        }
+
+        out.family(
+            "hone_http_request_duration_seconds",
+            "histogram",
+            "HTTP request latency by method and route",
+        );
+        for ((method, route), stats) in routes.iter() {
+            out.histogram(
+                "hone_http_request_duration_seconds",
+                &[("method", method), ("route", route)],
+                &HTTP_LATENCY_BUCKETS,
+                &stats.buckets,
+                stats.count,
+                stats.latency_sum,
+            );
+        }
+    }
+}
+
+/// Middleware recording request counts and latencies per matched route
+///
+/// Routes are labelled by their pattern (`/api/accounts/:id`), never the raw
+/// path, to keep label cardinality bounded.
+pub(crate) async fn track_http_metrics(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> axum::response::Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    state.http_metrics.record(
+        &method,
+        &route,
+        response.status().as_u16(),
+        started.elapsed().as_secs_f64(),
+    );
+    response
+}
+
+/// GET /metrics - Prometheus text exposition
+pub async fn get_metrics(
+    State(state): State<Arc<AppState>>,
+    connect_info: Option<ConnectInfo<std::net::SocketAddr>>,
+    request: Request,
+) -> Result<Response<Body>, AppError> {
+    let config = &state.config.metrics;
+    if !config.enabled {
+        return Err(AppError::not_found(
+            "Metrics are disabled (set HONE_METRICS)",
+        ));
+    }
+    if !scrape_allowed(&state, connect_info.as_ref(), &request) {
+        warn!(path = %request.uri().path(), "Unauthorized metrics scrape");
+        return Err(AppError::unauthorized(
+            "Metrics token or allowed network required",
+        ));
+    }
+
+    let mut out = MetricsWriter::default();
+    out.family("hone_build_info", "gauge", "Hone version");
+    out.sample(
+        "hone_build_info",
+        &[("version", env!("CARGO_PKG_VERSION"))],
+        1.0,
+    );
+    state.http_metrics.render(&mut out);
+    render_database_metrics(&state.db, &mut out)?;
+    render_backup_metrics(
+        &state.backup_dir.clone().unwrap_or_else(default_backup_dir),
+        &mut out,
+    );
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )
+        .body(Body::from(out.finish()))
+        .map_err(|e| AppError::internal(&e.to_string()))
+}
+
+/// Token first, then source network; nobody when neither is configured
+fn scrape_allowed(
+    state: &AppState,
+    connect_info: Option<&ConnectInfo<std::net::SocketAddr>>,
+    request: &Request,
+) -> bool {
+    let config = &state.config.metrics;
+
+    if let Some(token) = &config.token {
+        let provided = request
+            .headers()
+            .get(AUTHORIZATION_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|auth| auth.strip_prefix("Bearer "));
+        if let Some(provided) = provided {
+            if provided.len() == token.len()
+                && bool::from(provided.as_bytes().ct_eq(token.as_bytes()))
+            {
+                return true;
+            }
+        }
+    }
+
+    let client_ip = get_client_ip(request, connect_info, &state.config.trusted_proxies);
+    client_ip.is_some_and(|ip| is_ip_trusted(&ip, &config.allowed_networks))
+}
+
+fn render_database_metrics(db: &Database, out: &mut MetricsWriter) -> Result<(), AppError> {
+    let snapshot = db.metrics_snapshot()?;
+
+    out.family(
+        "hone_ai_calls_total",
+        "counter",
+        "AI calls by operation and model (from ollama_metrics)",
+    );
+    for call in &snapshot.ai_calls {
+        out.sample(
+            "hone_ai_calls_total",
+            &[("operation", &call.operation), ("model", &call.model)],
+            call.calls as f64,
+        );
+    }
+    out.family(
+        "hone_ai_call_errors_total",
+        "counter",
+        "Failed AI calls by operation and model",
+    );
+    for call in &snapshot.ai_calls {
+        out.sample(
+            "hone_ai_call_errors_total",
+            &[("operation", &call.operation), ("model", &call.model)],
+            call.errors as f64,
+        );
+    }
+    out.family(
+        "hone_ai_call_duration_seconds",
+        "histogram",
+        "AI call latency by operation and model",
+    );
+    for call in &snapshot.ai_calls {
+        let buckets: Vec<u64> = call.latency_buckets.iter().map(|&c| c as u64).collect();
+        out.histogram(
+            "hone_ai_call_duration_seconds",
+            &[("operation", &call.operation), ("model", &call.model)],
+            &AI_LATENCY_BUCKETS,
+            &buckets,
+            call.calls as u64,
+            call.latency_sum_ms as f64 / 1000.0,
+        );
+    }
+
+    let grouped = |out: &mut MetricsWriter, name, help, label, rows: &[(String, i64)]| {
+        out.family(name, "gauge", help);
+        for (value, count) in rows {
+            out.sample(name, &[(label, value)], *count as f64);
+        }
+    };
+    grouped(
+        out,
+        "hone_import_sessions",
+        "Import sessions by outcome",
+        "status",
+        &snapshot.import_sessions,
+    );
+    grouped(
+        out,
+        "hone_active_alerts",
+        "Undismissed alerts by type",
+        "type",
+        &snapshot.active_alerts,
+    );
+    grouped(
+        out,
+        "hone_active_insights",
+        "Active insights by type",
+        "type",
+        &snapshot.active_insights,
+    );
+
+    out.family("hone_db_size_bytes", "gauge", "Database file size");
+    out.sample("hone_db_size_bytes", &[], snapshot.db_size_bytes as f64);
+    out.family(
+        "hone_db_pool_connections",
+        "gauge",
+        "Open database pool connections",
+    );
+    out.sample(
+        "hone_db_pool_connections",
+        &[],
+        snapshot.pool_connections as f64,
+    );
+    out.family(
+        "hone_db_pool_idle_connections",
+        "gauge",
+        "Idle database pool connections",
+    );
+    out.sample(
+        "hone_db_pool_idle_connections",
+        &[],
+        snapshot.pool_idle_connections as f64,
+    );
+    out.family(
+        "hone_db_pool_max_connections",
+        "gauge",
+        "Database pool size limit",
+    );
+    out.sample(
+        "hone_db_pool_max_connections",
+        &[],
+        snapshot.pool_max_size as f64,
+    );
+
+    Ok(())
+}
+
+/// Newest local backup (a backup only lands in the directory once it succeeded)
+fn render_backup_metrics(backup_dir: &std::path::Path, out: &mut MetricsWriter) {
+    let backups = if backup_dir.exists() {
+        LocalDestination::new(backup_dir)
+            .and_then(|dest| Database::list_backups(&dest))
+            .unwrap_or_else(|e| {
+                warn!(error = %e, "Failed to list backups for metrics");
+                vec![]
+            })
+    } else {
+        vec![]
+    };
+
+    out.family("hone_backups", "gauge", "Local backups on disk");
+    out.sample("hone_backups", &[], backups.len() as f64);
+
+    if let Some(latest) = backups.iter().max_by_key(|b| b.created_at) {
+        out.family(
+            "hone_backup_last_success_timestamp_seconds",
+            "gauge",
+            "Creation time of the newest backup",
+        );
+        out.sample(
+            "hone_backup_last_success_timestamp_seconds",
+            &[],
+            latest.created_at.timestamp() as f64,
+        );
+        out.family(
+            "hone_backup_last_size_bytes",
+            "gauge",
+            "Size of the newest backup",
+        );
+        out.sample("hone_backup_last_size_bytes", &[], latest.size as f64);
+    }
+}
+
+/// Prometheus text format builder
+#[derive(Default)]
+struct MetricsWriter {
+    out: String,
+}
+
+impl MetricsWriter {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        let _ = writeln!(self.out, "{}{} {}", name, format_labels(labels), value);
+    }
+
+    /// `_bucket` (cumulative, plus `+Inf`), `_sum` and `_count` series
+    fn histogram(
+        &mut self,
+        name: &str,
+        labels: &[(&str, &str)],
+        bounds: &[f64],
+        buckets: &[u64],
+        count: u64,
+        sum: f64,
+    ) {
+        let bucket_name = format!("{}_bucket", name);
+        for (le, bucket) in bounds.iter().zip(buckets) {
+            let le = le.to_string();
+            let mut with_le = labels.to_vec();
+            with_le.push(("le", &le));
+            self.sample(&bucket_name, &with_le, *bucket as f64);
+        }
+        let mut with_le = labels.to_vec();
+        with_le.push(("le", "+Inf"));
+        self.sample(&bucket_name, &with_le, count as f64);
+        self.sample(&format!("{}_sum", name), labels, sum);
+        self.sample(&format!("{}_count", name), labels, count as f64);
+    }
+
+    fn finish(self) -> String {
+        self.out
+    }
+}
+
+fn format_labels(labels: &[(&str, &str)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| {
+            let escaped = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{}=\"{}\"", key, escaped)
+        })
+        .collect();
+    format!("{{{}}}", pairs.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_labels_escapes_values() {
+        assert_eq!(format_labels(&[]), "");
+        assert_eq!(
+            format_labels(&[("route", "/api/x"), ("model", "a\"b\\c")]),
+            r#"{route="/api/x",model="a\"b\\c"}"#
+        );
+    }
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let metrics = HttpMetrics::new();
+        metrics.record("GET", "/api/accounts", 200, 0.02);
+        metrics.record("GET", "/api/accounts", 500, 3.0);
+
+        let mut out = MetricsWriter::default();
+        metrics.render(&mut out);
+        let text = out.finish();
+        assert!(text.contains(
+            r#"hone_http_requests_total{method="GET",route="/api/accounts",status="500"} 1"#
+        ));
+        assert!(text.contains(
+            r#"hone_http_request_duration_seconds_bucket{method="GET",route="/api/accounts",le="0.025"} 1"#
+        ));
+        assert!(text.contains(
+            r#"hone_http_request_duration_seconds_bucket{method="GET",route="/api/accounts",le="+Inf"} 2"#
+        ));
+        assert!(text.contains(
+            r#"hone_http_request_duration_seconds_count{method="GET",route="/api/accounts"} 2"#
+        ));
+    }
+}
@@ -1,22 +1,52 @@
-//! Background task scheduler for automatic backups
+//! Background schedulers for recurring tasks and notifications
 //!
-//! Provides optional scheduled backup functionality that can be enabled
-//! via environment variables:
+//! Recurring tasks (backups, detection, insight refresh, reports, metrics
+//! pruning, training, audit archiving, explore digests) are
+//! configured with cron expressions in `schedules.toml` (see
+//! `hone_core::schedule`). The task scheduler checks every 30 seconds and
+//! queues a background job for each task that is due, so every task shows
+//! up in `/api/schedules` with its last run and outcome.
+//!
+//! Some tasks are still enabled with environment variables, which are
+//! translated into cron-scheduled tasks:
 //!
 //! - `HONE_BACKUP_SCHEDULE`: Interval in hours (e.g., "24" for daily, "168" for weekly)
 //! - `HONE_BACKUP_RETENTION`: Number of backups to keep (default: 7)
+//! - `HONE_AUDIT_RETENTION_DAYS`: daily `audit_archive` task moving older
+//!   entries to the archive table, keeping the hash chain intact
+//! - `HONE_DIGEST_CHECK_MINUTES`: how often the `explore_digests` task looks
+//!   for due saved questions (default: 15, "0" disables digests); only added
+//!   when the AI orchestrator is configured
+//!
+//! When a notifications config is loaded, the notification dispatcher runs
+//! in-process every minute to route new alerts/insights and send due
+//! deliveries. It doesn't go through the job table, so it leaves no job rows.
 //!
-//! The scheduler runs in the background and automatically creates backups
-//! and prunes old ones according to the retention policy.
+//! When `hone serve --watch <dir>` is used, the import watcher scans the
+//! folder every `poll_secs` and imports new statements (see `hone_core::watch`).
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use tokio::time::interval;
-use tracing::{error, info, warn};
+use chrono::Utc;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{debug, error, info, warn};
 
-use hone_core::backup::{default_backup_dir, LocalDestination, RetentionPolicy};
+use hone_core::ai::AIOrchestrator;
+use hone_core::backup::{LocalDestination, RetentionPolicy};
+use hone_core::db::JOB_RETENTION_DAYS;
+use hone_core::insights::{compare_digests, digest_finding};
+use hone_core::models::{
+    ExploreDigest, NewExploreDigest, NewOllamaMetric, OllamaOperation, SavedQuestion,
+};
+use hone_core::notify::{Notifier, NotifyConfig};
+use hone_core::schedule::{ScheduleConfig, Scheduler};
+use hone_core::tools::hone_tools;
+use hone_core::watch::{ImportWatcher, WatchConfig};
 use hone_core::Database;
 
+use crate::handlers::explore_system_prompt;
+use crate::identity::creator_hidden_accounts;
+
 /// Configuration for scheduled backups
 #[derive(Debug, Clone)]
 pub struct BackupScheduleConfig {
@@ -59,42 +89,51 @@ impl BackupScheduleConfig {
     }
 }
 
-/// Start the backup scheduler as a background task
+/// Start the task scheduler as a background task
 ///
-/// This function spawns a tokio task that runs indefinitely, creating
-/// backups at the configured interval.
-pub fn start_backup_scheduler(db: Database, config: BackupScheduleConfig) {
+/// Every 30 seconds, queues a job for each enabled task whose cron
+/// expression has come due. Runs missed while the server was down are
+/// queued once on the first check. Once a day it also deletes jobs that
+/// finished more than `JOB_RETENTION_DAYS` ago.
+pub fn start_task_scheduler(db: Database, config: ScheduleConfig) -> hone_core::Result<()> {
+    let scheduler = Scheduler::new(db.clone(), &config)?;
     info!(
-        "Starting backup scheduler: every {} hours, keeping {} backups",
-        config.interval_hours, config.retention_count
+        "Starting task scheduler: {} task(s)",
+        config.tasks.iter().filter(|t| t.enabled).count()
     );
 
     tokio::spawn(async move {
-        let backup_dir = config.backup_dir.unwrap_or_else(default_backup_dir);
-        let mut ticker = interval(Duration::from_secs(config.interval_hours * 3600));
-
-        // Skip the first immediate tick - we don't want to backup on startup
-        ticker.tick().await;
+        let mut ticker = interval(Duration::from_secs(30));
+        let mut last_prune: Option<Instant> = None;
 
         loop {
             ticker.tick().await;
 
-            info!("Running scheduled backup...");
+            if let Err(e) = scheduler.run_once(Utc::now()) {
+                error!("Task scheduler check failed: {}", e);
+            }
 
-            match run_scheduled_backup(&db, &backup_dir, config.retention_count) {
-                Ok(backup_name) => {
-                    info!("Scheduled backup completed: {}", backup_name);
-                }
-                Err(e) => {
-                    error!("Scheduled backup failed: {}", e);
-                }
+            if last_prune.is_none_or(|t| t.elapsed() >= Duration::from_secs(24 * 3600)) {
+                last_prune = Some(Instant::now());
+                prune_finished_jobs(&db);
             }
         }
     });
+    Ok(())
 }
 
-/// Run a single scheduled backup
-fn run_scheduled_backup(
+/// Delete jobs that finished more than `JOB_RETENTION_DAYS` ago
+fn prune_finished_jobs(db: &Database) {
+    let cutoff = Utc::now() - chrono::Duration::days(JOB_RETENTION_DAYS);
+    match db.prune_finished_jobs(cutoff) {
+        Ok(0) => debug!("No finished jobs to prune"),
+        Ok(deleted) => info!("Pruned {} finished job(s)", deleted),
+        Err(e) => error!("Failed to prune finished jobs: {}", e),
+    }
+}
+
+/// Run a single backup and prune old ones (executed by `backup` jobs)
+pub(crate) fn run_scheduled_backup(
     db: &Database,
     backup_dir: &std::path::Path,
     retention_count: usize,
@@ -145,6 +184,327 @@ fn run_scheduled_backup(
     Ok(backup_name)
 }
 
+/// Configuration for audit log retention
+#[derive(Debug, Clone)]
+pub struct AuditRetentionConfig {
+    /// Entries older than this many days are archived
+    pub retention_days: i64,
+}
+
+impl AuditRetentionConfig {
+    /// Parse configuration from environment variables
+    ///
+    /// Returns None if retention is not configured (HONE_AUDIT_RETENTION_DAYS not set)
+    pub fn from_env() -> Option<Self> {
+        let retention_days: i64 = std::env::var("HONE_AUDIT_RETENTION_DAYS")
+            .ok()
+            .and_then(|s| s.parse().ok())?;
+
+        if retention_days <= 0 {
+            warn!("HONE_AUDIT_RETENTION_DAYS must be positive, audit archiving disabled");
+            return None;
+        }
+
+        Some(Self { retention_days })
+    }
+}
+
+/// Archive audit entries older than `retention_days` (executed by `audit_archive` jobs)
+///
+/// Returns the number of entries archived.
+pub(crate) fn run_audit_archive(db: &Database, retention_days: i64) -> hone_core::Result<usize> {
+    let cutoff = chrono::Duration::try_days(retention_days)
+        .and_then(|age| Utc::now().checked_sub_signed(age))
+        .ok_or_else(|| {
+            hone_core::Error::InvalidData(format!(
+                "retention_days {} is out of range",
+                retention_days
+            ))
+        })?;
+    let archived = db.archive_audit_log(cutoff)?;
+    if archived == 0 {
+        debug!("No audit entries to archive");
+        return Ok(0);
+    }
+
+    info!("Archived {} audit log entries", archived);
+    if let Err(e) = db.log_audit(
+        "scheduler",
+        "archive",
+        Some("audit_log"),
+        None,
+        Some(&format!(
+            "older_than_days={}, archived={}",
+            retention_days, archived
+        )),
+    ) {
+        warn!("Failed to log audit archiving: {}", e);
+    }
+    Ok(archived)
+}
+
+/// Configuration for scheduled explore digests
+#[derive(Debug, Clone)]
+pub struct DigestScheduleConfig {
+    /// Minutes between checks for due saved questions
+    pub check_interval_minutes: u64,
+}
+
+impl DigestScheduleConfig {
+    /// Parse configuration from environment variables
+    ///
+    /// Returns None if digests are disabled (HONE_DIGEST_CHECK_MINUTES=0)
+    pub fn from_env() -> Option<Self> {
+        let check_interval_minutes: u64 = std::env::var("HONE_DIGEST_CHECK_MINUTES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(15);
+
+        if check_interval_minutes == 0 {
+            info!("HONE_DIGEST_CHECK_MINUTES is 0, scheduled explore digests disabled");
+            return None;
+        }
+
+        Some(Self {
+            check_interval_minutes,
+        })
+    }
+}
+
+/// Run every saved question that has come due (executed by `explore_digests` jobs)
+///
+/// Questions run sequentially, one AI-heavy query at a time. Questions that
+/// came due while the server was down run on the first check. Returns how
+/// many ran and how many of those failed.
+pub(crate) async fn run_due_saved_questions(
+    db: &Database,
+    orchestrator: &AIOrchestrator,
+) -> hone_core::Result<(usize, usize)> {
+    let due = db.get_due_saved_questions(Utc::now())?;
+    let mut failed = 0;
+
+    for question in &due {
+        info!("Running saved question: {}", question.name);
+        match run_saved_question(db, orchestrator, question, "scheduler").await {
+            Ok(digest) if digest.success => {
+                info!(
+                    "Saved question '{}' completed ({} tool calls, changed: {})",
+                    question.name,
+                    digest.tool_calls.len(),
+                    digest.changed
+                );
+            }
+            Ok(digest) => {
+                failed += 1;
+                warn!(
+                    "Saved question '{}' failed: {}",
+                    question.name,
+                    digest.error_message.unwrap_or_default()
+                );
+            }
+            Err(e) => {
+                failed += 1;
+                error!("Failed to run saved question '{}': {}", question.name, e);
+            }
+        }
+    }
+    Ok((due.len(), failed))
+}
+
+/// Run one saved question and store the result as a digest
+///
+/// `actor` is recorded in the audit log ("scheduler" for scheduled runs).
+///
+/// Tools only see the accounts the question's creator may see; a question
+/// whose creator is no longer a household user fails without running.
+///
+/// Failed AI runs are stored too (with `success = false`) so they show up in
+/// the question's history and still advance its schedule. When the answer
+/// differs meaningfully from the previous successful digest, an insight
+/// finding is raised and linked to the new digest.
+pub async fn run_saved_question(
+    db: &Database,
+    orchestrator: &AIOrchestrator,
+    question: &SavedQuestion,
+    actor: &str,
+) -> hone_core::Result<ExploreDigest> {
+    let hidden_accounts = creator_hidden_accounts(db, question.created_by.as_deref())?;
+    let orchestrator =
+        orchestrator.with_db(db.with_hidden_accounts(hidden_accounts.clone().unwrap_or_default()));
+    let orchestrator = match &question.model {
+        Some(model) => orchestrator.with_model(model),
+        None => orchestrator,
+    };
+    let model_name = orchestrator.model().to_string();
+    let system_prompt = explore_system_prompt()?;
+    let tools = hone_tools();
+
+    let start = Instant::now();
+    let result = match hidden_accounts {
+        Some(_) => {
+            orchestrator
+                .execute_with_tracking(&system_prompt, &question.query, &tools, Vec::new())
+                .await
+        }
+        None => Err(hone_core::Error::NotFound(format!(
+            "Household user {}",
+            question.created_by.as_deref().unwrap_or("(none)")
+        ))),
+    };
+    let latency_ms = start.elapsed().as_millis() as i64;
+
+    let previous = db.get_last_successful_digest(question.id)?;
+
+    let new_digest = match &result {
+        Ok(r) => {
+            let comparison = previous
+                .as_ref()
+                .map(|p| compare_digests(&p.response, &r.response));
+            NewExploreDigest {
+                saved_question_id: question.id,
+                success: true,
+                response: r.response.clone(),
+                error_message: None,
+                tool_calls: r.tool_calls.clone(),
+                iterations: r.iterations as i64,
+                model: Some(model_name.clone()),
+                latency_ms,
+                change_score: comparison.as_ref().map(|c| c.change_score),
+                changed: comparison.as_ref().is_some_and(|c| c.changed),
+            }
+        }
+        Err(e) => NewExploreDigest {
+            saved_question_id: question.id,
+            success: false,
+            response: String::new(),
+            error_message: Some(e.to_string()),
+            tool_calls: Vec::new(),
+            iterations: 0,
+            model: Some(model_name.clone()),
+            latency_ms,
+            change_score: None,
+            changed: false,
+        },
+    };
+
+    let metric = NewOllamaMetric {
+        operation: OllamaOperation::ExploreQuery,
+        model: model_name,
+        latency_ms,
+        success: new_digest.success,
+        error_message: new_digest.error_message.clone(),
+        confidence: None,
+        transaction_id: None,
+        input_text: Some(question.query.clone()),
+        result_text: result.as_ref().ok().map(|r| r.response.clone()),
+        metadata: serde_json::to_string(&serde_json::json!({
+            "saved_question_id": question.id,
+            "tool_calls": new_digest.tool_calls,
+            "iterations": new_digest.iterations,
+        }))
+        .ok(),
+    };
+    if let Err(e) = db.record_ollama_metric(&metric) {
+        warn!("Failed to record saved question metric: {}", e);
+    }
+
+    let digest_id = db.record_explore_digest(&new_digest)?;
+    let mut digest = db
+        .list_explore_digests(question.id, 1)?
+        .into_iter()
+        .find(|d| d.id == digest_id)
+        .ok_or_else(|| hone_core::Error::NotFound(format!("Explore digest {}", digest_id)))?;
+
+    if let (true, Some(previous)) = (digest.changed, previous.as_ref()) {
+        let comparison = compare_digests(&previous.response, &digest.response);
+        let finding = digest_finding(question, previous, &digest, &comparison);
+        let insight_id = db.upsert_insight_finding(&finding)?;
+        db.set_explore_digest_insight(digest.id, insight_id)?;
+        digest.insight_id = Some(insight_id);
+        debug!(
+            question_id = question.id,
+            insight_id = insight_id,
+            "Saved question answer changed"
+        );
+    }
+
+    if let Err(e) = db.log_audit(
+        actor,
+        "explore_digest",
+        Some("saved_question"),
+        Some(question.id),
+        Some(&format!("digest={}, success={}", digest.id, digest.success)),
+    ) {
+        warn!("Failed to log explore digest to audit: {}", e);
+    }
+
+    Ok(digest)
+}
+
+/// Start the notification dispatcher
+///
+/// Runs every minute: queues deliveries for new alerts and insights, then
+/// sends whatever is due (respecting quiet hours, digests and retry backoff).
+/// A slow dispatch delays the next tick instead of piling up runs.
+pub fn start_notification_scheduler(db: Database, config: NotifyConfig) {
+    info!(
+        "Starting notification dispatcher: {} channel(s), {} route(s)",
+        config.channels.len(),
+        config.routes.len()
+    );
+
+    tokio::spawn(async move {
+        let notifier = Notifier::new(db, config);
+        let mut ticker = interval(Duration::from_secs(60));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = notifier.run_once(Utc::now()).await {
+                error!("Notification dispatch failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Start the import folder watcher
+///
+/// Scans the folder every `poll_secs`; imported files queue
+/// `import_processing` jobs for the job workers.
+pub fn start_import_watcher(db: Database, config: WatchConfig) -> hone_core::Result<()> {
+    let poll_secs = config.poll_secs;
+    let watcher = std::sync::Arc::new(ImportWatcher::new(db, config)?);
+    info!(
+        "Watching {} for statements every {}s",
+        watcher.dir().display(),
+        poll_secs
+    );
+
+    tokio::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(poll_secs));
+
+        loop {
+            ticker.tick().await;
+
+            let watcher = watcher.clone();
+            match tokio::task::spawn_blocking(move || {
+                watcher.scan_once(std::time::SystemTime::now())
+            })
+            .await
+            {
+                Ok(Ok(handled)) if !handled.is_empty() => {
+                    debug!("Import watcher handled {} file(s)", handled.len());
+                }
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => error!("Import watcher scan failed: {}", e),
+                Err(e) => error!("Import watcher task panicked: {}", e),
+            }
+        }
+    });
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -163,4 +523,18 @@ mod tests {
         assert!(BackupScheduleConfig::from_env().is_none());
         std::env::remove_var("HONE_BACKUP_SCHEDULE");
     }
+
+    #[test]
+    fn test_digest_config_from_env_zero() {
+        // When HONE_DIGEST_CHECK_MINUTES is 0, digests are disabled
+        std::env::set_var("HONE_DIGEST_CHECK_MINUTES", "0");
+        assert!(DigestScheduleConfig::from_env().is_none());
+        std::env::remove_var("HONE_DIGEST_CHECK_MINUTES");
+        assert_eq!(
+            DigestScheduleConfig::from_env()
+                .unwrap()
+                .check_interval_minutes,
+            15
+        );
+    }
 }
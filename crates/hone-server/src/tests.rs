@@ -33,6 +33,20 @@ async fn get_body_json(response: axum::response::Response) -> serde_json::Value
     serde_json::from_slice(&bytes).unwrap()
 }
 
+/// Checks that `response` returned a queued job, runs it on the AI lane and
+/// returns its result
+async fn run_queued_job(db: &Database, response: axum::response::Response) -> serde_json::Value {
+    assert_eq!(response.status(), StatusCode::OK);
+    let job = get_body_json(response).await;
+    assert_eq!(job["status"], "queued");
+    let id = job["id"].as_i64().unwrap();
+    let runner = JobRunner::new(db.clone(), None);
+    assert_eq!(runner.run_next(JobLane::Ai).await.unwrap(), Some(id));
+    let job = db.get_job(id).unwrap().unwrap();
+    assert_eq!(job.status, hone_core::models::JobStatus::Completed);
+    job.result.unwrap()
+}
+
 // ========== Tag API Tests ==========
 
 #[tokio::test]
@@ -750,6 +764,118 @@ async fn test_report_merchants_with_limit() {
     assert_eq!(json["limit"], 5);
 }
 
+#[tokio::test]
+async fn test_item_catalog_endpoints() {
+    let db = Database::in_memory().unwrap();
+    let account_id = db
+        .upsert_account("Test Account", Bank::Chase, None)
+        .unwrap();
+    let today = chrono::Utc::now().date_naive();
+    for (i, (description, price)) in [("ORG WHL MILK", 5.0), ("Organic Whole Milk", 6.0)]
+        .into_iter()
+        .enumerate()
+    {
+        let tx_id = db
+            .insert_transaction(
+                account_id,
+                &hone_core::models::NewTransaction {
+                    date: today - chrono::Duration::days(60 - 30 * i as i64),
+                    description: "SAFEWAY".to_string(),
+                    amount: -(price + 0.5),
+                    category: None,
+                    import_hash: format!("item_catalog_{}", i),
+                    original_data: None,
+                    import_format: None,
+                    card_member: None,
+                    payment_method: None,
+                },
+            )
+            .unwrap()
+            .unwrap();
+        for (desc, amount, split_type) in [
+            (description, price, hone_core::models::SplitType::Item),
+            ("Tax", 0.5, hone_core::models::SplitType::Tax),
+        ] {
+            db.create_split(&hone_core::models::NewTransactionSplit {
+                transaction_id: tx_id,
+                amount,
+                description: Some(desc.to_string()),
+                split_type,
+                entity_id: None,
+                purchaser_id: None,
+            })
+            .unwrap();
+        }
+    }
+
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db.clone(), None, config);
+    let get = |uri: String| {
+        app.clone()
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+    };
+
+    let response = get("/api/items?q=milk".to_string()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let items = get_body_json(response).await;
+    assert_eq!(items.as_array().unwrap().len(), 1);
+    assert_eq!(items[0]["purchase_count"], 2);
+    let item_id = items[0]["id"].as_i64().unwrap();
+
+    let response = get(format!("/api/reports/items/{}/prices", item_id))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let history = get_body_json(response).await;
+    assert_eq!(history["points"].as_array().unwrap().len(), 2);
+    assert_eq!(history["latest_price"], 6.0);
+
+    let response = get("/api/reports/items/9999/prices".to_string())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = get("/api/reports/item-inflation?period=all".to_string())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let inflation = get_body_json(response).await;
+    assert_eq!(inflation["items"][0]["item_id"], item_id);
+    assert!((inflation["basket_change_percent"].as_f64().unwrap() - 20.0).abs() < 1e-9);
+
+    let response = get("/api/reports/split-types?period=all".to_string())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report = get_body_json(response).await;
+    let tax = report["split_types"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["split_type"] == "tax")
+        .unwrap();
+    assert_eq!(tax["amount"], 1.0);
+    assert_eq!(tax["transaction_count"], 2);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/items/{}/merge", item_id))
+                .header("content-type", "application/json")
+                .body(Body::from(format!(r#"{{"into_id": {}}}"#, item_id)))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_report_subscriptions() {
     let app = setup_test_app();
@@ -835,8 +961,6 @@ async fn test_cancel_subscription() {
 
 #[tokio::test]
 async fn test_cancel_subscription_nonexistent() {
-    // Note: Currently the API returns 200 OK even for non-existent subscriptions
-    // since the UPDATE just does nothing. This could be improved in the future.
     let app = setup_test_app();
 
     let response = app
@@ -850,7 +974,102 @@ async fn test_cancel_subscription_nonexistent() {
         .await
         .unwrap();
 
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_cancel_subscription_tracked() {
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    let sub_id = db
+        .upsert_subscription(
+            "Disney Plus",
+            None,
+            Some(13.99),
+            Some(hone_core::models::Frequency::Monthly),
+            None,
+            None,
+        )
+        .unwrap();
+
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db, None, config);
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            &format!("/api/subscriptions/{}/cancel", sub_id),
+            serde_json::json!({"expected_refund": -3.0}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            &format!("/api/subscriptions/{}/cancel", sub_id),
+            serde_json::json!({
+                "cancelled_on": "2024-06-10",
+                "method": "chat",
+                "confirmation_number": "DP-77120",
+                "expected_final_charge": "2024-06-28",
+                "expected_refund": 4.5
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["success"], true);
+    assert_eq!(json["cancellation"]["method"], "chat");
+    assert_eq!(json["cancellation"]["status"], "pending");
+    assert_eq!(json["cancellation"]["expected_final_charge"], "2024-06-28");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/subscriptions/{}/cancellation", sub_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["confirmation_number"], "DP-77120");
+    assert_eq!(json["merchant"], "Disney Plus");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/cancellations?status=pending")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json.as_array().unwrap().len(), 1);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/cancellations?status=maybe")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
 // ========== Entity API Tests ==========
@@ -2197,7 +2416,14 @@ async fn test_list_transactions_with_pagination() {
 
 #[tokio::test]
 async fn test_run_detection() {
-    let app = setup_test_app();
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db.clone(), None, config);
 
     let body = serde_json::json!({
         "kind": "all"
@@ -2215,9 +2441,7 @@ async fn test_run_detection() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let json = get_body_json(response).await;
+    let json = run_queued_job(&db, response).await;
     assert!(json.get("subscriptions_found").is_some());
 }
 
@@ -2238,6 +2462,78 @@ async fn test_audit_log() {
     assert_eq!(response.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn test_audit_log_filter_verify_and_export() {
+    let db = Database::in_memory().unwrap();
+    db.log_audit(
+        "a@example.com",
+        "update",
+        Some("transaction"),
+        Some(1),
+        None,
+    )
+    .unwrap();
+    db.log_audit("b@example.com", "delete", Some("tag"), Some(2), None)
+        .unwrap();
+    let app = create_router(
+        db.clone(),
+        None,
+        ServerConfig {
+            require_auth: false,
+            ..Default::default()
+        },
+    );
+    let get = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+    let response = app
+        .clone()
+        .oneshot(get("/api/audit?user=b@example.com&entity_type=tag"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert_eq!(json[0]["action"], "delete");
+
+    let response = app
+        .clone()
+        .oneshot(get("/api/audit?from=2020-13-01"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = app.clone().oneshot(get("/api/audit/verify")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["valid"], true);
+
+    let response = app
+        .clone()
+        .oneshot(get("/api/audit/export?format=csv&action=update"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/csv; charset=utf-8"
+    );
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    assert!(csv.starts_with("id,timestamp,user_email"));
+    assert!(csv.contains("a@example.com"));
+    assert!(!csv.contains("b@example.com"));
+
+    // Tampering is reported
+    db.conn()
+        .unwrap()
+        .execute("UPDATE audit_log SET details = 'edited' WHERE id = 1", [])
+        .unwrap();
+    let response = app.oneshot(get("/api/audit/verify")).await.unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json["valid"], false);
+    assert_eq!(json["first_invalid_id"], 1);
+}
+
 #[tokio::test]
 async fn test_dismiss_alert() {
     let db = Database::in_memory().unwrap();
@@ -2280,6 +2576,91 @@ async fn test_dismiss_alert() {
     assert_eq!(response.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn test_dismiss_anomaly_alert_records_feedback() {
+    let db = Database::in_memory().unwrap();
+    let account_id = db
+        .upsert_account("Test Account", Bank::Chase, None)
+        .unwrap();
+    let tx_id = db
+        .insert_transaction(
+            account_id,
+            &hone_core::models::NewTransaction {
+                date: chrono::Utc::now().date_naive(),
+                description: "BIG TV OUTLET".to_string(),
+                amount: -900.0,
+                category: None,
+                import_hash: "anomaly_alert_test".to_string(),
+                original_data: None,
+                import_format: None,
+                card_member: None,
+                payment_method: None,
+            },
+        )
+        .unwrap()
+        .unwrap();
+    let anomaly = db
+        .create_transaction_anomaly(
+            &hone_core::models::NewTransactionAnomaly {
+                transaction_id: tx_id,
+                related_transaction_id: None,
+                anomaly_type: hone_core::models::AlertType::NewMerchant,
+                account_id,
+                merchant: "Big Tv Outlet".to_string(),
+                amount: 900.0,
+                transaction_date: chrono::Utc::now().date_naive(),
+                baseline_amount: None,
+                threshold: 250.0,
+                detail: None,
+            },
+            "First charge from Big Tv Outlet",
+        )
+        .unwrap()
+        .unwrap();
+
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db.clone(), None, config);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/alerts/{}/dismiss", anomaly.alert_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let feedback = db.get_anomaly_feedback().unwrap();
+    assert_eq!(feedback.len(), 1);
+    assert_eq!(
+        feedback[0].anomaly_type,
+        hone_core::models::AlertType::NewMerchant
+    );
+    assert_eq!(feedback[0].dismissed, 1);
+
+    // Restoring the alert withdraws the dismissal
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/alerts/{}/restore", anomaly.alert_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(db.get_anomaly_feedback().unwrap().is_empty());
+}
+
 #[tokio::test]
 async fn test_restore_alert() {
     let db = Database::in_memory().unwrap();
@@ -3396,7 +3777,7 @@ async fn test_detection_with_recurring_transactions() {
         allowed_origins: vec![],
         ..Default::default()
     };
-    let app = create_router(db, None, config);
+    let app = create_router(db.clone(), None, config);
 
     let body = serde_json::json!({
         "kind": "all"
@@ -3414,9 +3795,7 @@ async fn test_detection_with_recurring_transactions() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let json = get_body_json(response).await;
+    let json = run_queued_job(&db, response).await;
     // Should detect the Netflix subscription
     assert!(json["subscriptions_found"].as_u64().unwrap() >= 1);
 }
@@ -3431,7 +3810,7 @@ async fn test_detection_zombies_only() {
         allowed_origins: vec![],
         ..Default::default()
     };
-    let app = create_router(db, None, config);
+    let app = create_router(db.clone(), None, config);
 
     let body = serde_json::json!({
         "kind": "zombies"
@@ -3449,9 +3828,7 @@ async fn test_detection_zombies_only() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let json = get_body_json(response).await;
+    let json = run_queued_job(&db, response).await;
     assert!(json.get("zombies_detected").is_some());
 }
 
@@ -3465,7 +3842,7 @@ async fn test_detection_increases_only() {
         allowed_origins: vec![],
         ..Default::default()
     };
-    let app = create_router(db, None, config);
+    let app = create_router(db.clone(), None, config);
 
     let body = serde_json::json!({
         "kind": "increases"
@@ -3483,9 +3860,7 @@ async fn test_detection_increases_only() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let json = get_body_json(response).await;
+    let json = run_queued_job(&db, response).await;
     assert!(json.get("price_increases_detected").is_some());
 }
 
@@ -3499,7 +3874,7 @@ async fn test_detection_duplicates_only() {
         allowed_origins: vec![],
         ..Default::default()
     };
-    let app = create_router(db, None, config);
+    let app = create_router(db.clone(), None, config);
 
     let body = serde_json::json!({
         "kind": "duplicates"
@@ -3517,9 +3892,7 @@ async fn test_detection_duplicates_only() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let json = get_body_json(response).await;
+    let json = run_queued_job(&db, response).await;
     assert!(json.get("duplicates_detected").is_some());
 }
 
@@ -3569,7 +3942,7 @@ async fn test_detection_with_price_increase() {
         allowed_origins: vec![],
         ..Default::default()
     };
-    let app = create_router(db, None, config);
+    let app = create_router(db.clone(), None, config);
 
     let body = serde_json::json!({
         "kind": "all"
@@ -3587,9 +3960,7 @@ async fn test_detection_with_price_increase() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let json = get_body_json(response).await;
+    let json = run_queued_job(&db, response).await;
     // Detection runs successfully - may or may not find subscriptions depending on algorithm thresholds
     assert!(json.get("subscriptions_found").is_some());
     assert!(json.get("price_increases_detected").is_some());
@@ -3597,7 +3968,14 @@ async fn test_detection_with_price_increase() {
 
 #[tokio::test]
 async fn test_detection_empty_body() {
-    let app = setup_test_app();
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db.clone(), None, config);
 
     // Empty body should use defaults
     let response = app
@@ -3612,9 +3990,7 @@ async fn test_detection_empty_body() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let json = get_body_json(response).await;
+    let json = run_queued_job(&db, response).await;
     // Should run "all" detection by default
     assert!(json.get("subscriptions_found").is_some());
     assert!(json.get("zombies_detected").is_some());
@@ -3624,7 +4000,14 @@ async fn test_detection_empty_body() {
 
 #[tokio::test]
 async fn test_detection_invalid_kind_defaults_to_all() {
-    let app = setup_test_app();
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db.clone(), None, config);
 
     let body = serde_json::json!({
         "kind": "invalid_kind"
@@ -3642,10 +4025,9 @@ async fn test_detection_invalid_kind_defaults_to_all() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let json = get_body_json(response).await;
+    let json = run_queued_job(&db, response).await;
     // Invalid kind should fall through to "all" detection
+    assert_eq!(json["kind"], "all");
     assert!(json.get("subscriptions_found").is_some());
 }
 
@@ -4570,6 +4952,78 @@ async fn test_upload_pending_receipt_no_data() {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 }
 
+#[tokio::test]
+async fn test_upload_html_receipt_parses_as_text() {
+    let db = Database::in_memory().unwrap();
+    let receipts_dir = TempDir::new().unwrap();
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let state = Arc::new(AppState {
+        db: db.clone(),
+        config: config.clone(),
+        ai: Some(hone_core::ai::AIClient::mock()),
+        orchestrator: None,
+        backup_dir: None,
+        receipts_dir: receipts_dir.path().to_path_buf(),
+        explore_sessions: handlers::ExploreSessionManager::new(),
+        local_auth: crate::LocalAuthState::new(),
+        http_metrics: crate::HttpMetrics::new(),
+    });
+    let app = build_router(state, None, config);
+
+    let html = "<!DOCTYPE html><html><body><p>Mock Store</p>\
+                <table><tr><td>Total</td><td>$10.80</td></tr></table></body></html>";
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/receipts")
+                .header("content-type", "application/octet-stream")
+                .body(Body::from(html))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["parse_path"], "html");
+    assert_eq!(json["parsed"]["merchant"], "Mock Store");
+    assert!(json["image_path"].as_str().unwrap().ends_with(".html"));
+    let receipt_id = json["receipt"]["id"].as_i64().unwrap();
+
+    // Re-parsing reads the stored file and takes the same text route
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/receipts/{}/parse", receipt_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["parse_path"], "html");
+
+    let metrics = db.get_recent_ollama_calls(10).unwrap();
+    assert_eq!(metrics.len(), 2);
+    for metric in metrics {
+        assert_eq!(
+            metric.operation,
+            hone_core::models::OllamaOperation::ParseReceipt
+        );
+        let metadata: serde_json::Value =
+            serde_json::from_str(metric.metadata.as_deref().unwrap()).unwrap();
+        assert_eq!(metadata["path"], "html");
+        assert_eq!(metadata["task"], "structured_extraction");
+    }
+}
+
 #[tokio::test]
 async fn test_parse_receipt_not_found() {
     let app = setup_test_app();
@@ -5653,7 +6107,14 @@ async fn test_import_full_invalid_json() {
 
 #[tokio::test]
 async fn test_run_detection_empty_db() {
-    let app = setup_test_app();
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db.clone(), None, config);
 
     let response = app
         .oneshot(
@@ -5666,9 +6127,7 @@ async fn test_run_detection_empty_db() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-
-    let json = get_body_json(response).await;
+    let json = run_queued_job(&db, response).await;
     assert!(json.get("subscriptions_found").is_some());
     assert!(json.get("zombies_detected").is_some());
 }
@@ -6347,6 +6806,174 @@ async fn test_unlink_receipt_not_found() {
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+async fn test_link_unlink_records_match_feedback() {
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+
+    let account_id = db
+        .upsert_account("Test Account", Bank::Chase, None)
+        .unwrap();
+    let tx_id = db
+        .insert_transaction(
+            account_id,
+            &hone_core::models::NewTransaction {
+                date: chrono::NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+                description: "STARBUCKS 1234".to_string(),
+                amount: -6.50,
+                category: None,
+                import_hash: "feedback_hash".to_string(),
+                original_data: None,
+                import_format: None,
+                card_member: None,
+                payment_method: None,
+            },
+        )
+        .unwrap()
+        .unwrap();
+    let receipt_id = db
+        .create_receipt_full(&hone_core::models::NewReceipt {
+            transaction_id: None,
+            image_path: None,
+            image_data: None,
+            status: hone_core::models::ReceiptStatus::Pending,
+            role: hone_core::models::ReceiptRole::Primary,
+            receipt_date: chrono::NaiveDate::from_ymd_opt(2024, 6, 1),
+            receipt_total: Some(6.50),
+            receipt_merchant: Some("Starbucks".to_string()),
+            content_hash: None,
+        })
+        .unwrap();
+
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db.clone(), None, config);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/receipts/{}/link", receipt_id))
+                .header("content-type", "application/json")
+                .body(Body::from(format!(r#"{{"transaction_id": {}}}"#, tx_id)))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let generator = hone_core::training::TrainingDataGenerator::new(&db);
+    let examples = generator
+        .generate(hone_core::training::TrainingTask::EvaluateReceiptMatch)
+        .unwrap();
+    assert_eq!(examples.len(), 1);
+    assert!(examples[0].output.contains(r#""is_match":true"#));
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/receipts/{}/unlink", receipt_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The unlink overrides the earlier link for this pair
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/training/export?task=evaluate_receipt_match")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let jsonl = String::from_utf8(body.to_vec()).unwrap();
+    assert_eq!(jsonl.lines().count(), 1);
+    assert!(jsonl.contains("STARBUCKS 1234"));
+    assert!(jsonl.contains(r#"\"is_match\":false"#));
+}
+
+#[tokio::test]
+async fn test_update_receipt_parsed() {
+    let db = Database::in_memory().unwrap();
+    let receipt_id = db
+        .create_receipt_full(&hone_core::models::NewReceipt {
+            transaction_id: None,
+            image_path: Some("/tmp/receipt_correct.jpg".to_string()),
+            image_data: None,
+            status: hone_core::models::ReceiptStatus::Pending,
+            role: hone_core::models::ReceiptRole::Primary,
+            receipt_date: None,
+            receipt_total: None,
+            receipt_merchant: None,
+            content_hash: None,
+        })
+        .unwrap();
+    db.update_receipt_parsed(receipt_id, r#"{"merchant":"Trget","items":[]}"#)
+        .unwrap();
+
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db.clone(), None, config);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/receipts/{}/parsed", receipt_id))
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    r#"{"merchant": "Target", "date": "2024-06-01", "items": [{"description": "Milk", "amount": 4.5, "split_type": "item"}], "total": 4.5}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["receipt_merchant"], "Target");
+    assert_eq!(json["receipt_date"], "2024-06-01");
+    assert_eq!(json["receipt_total"], 4.5);
+
+    let generator = hone_core::training::TrainingDataGenerator::new(&db);
+    let examples = generator
+        .generate(hone_core::training::TrainingTask::ParseReceipt)
+        .unwrap();
+    assert_eq!(examples.len(), 1);
+    assert_eq!(examples[0].input, "/tmp/receipt_correct.jpg");
+    assert!(examples[0].output.contains("Milk"));
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/receipts/{}/parsed", receipt_id))
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    r#"{"merchant": "Target", "date": "June 1", "items": []}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 // ========== Subscription API Tests (Extended) ==========
 
 #[tokio::test]
@@ -6387,21 +7014,30 @@ async fn test_list_subscriptions_by_account() {
 
 #[tokio::test]
 async fn test_cancel_subscription_returns_success() {
-    // Note: cancel_subscription doesn't validate existence, returns success even for missing IDs
-    let app = setup_test_app();
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    let sub_id = db
+        .upsert_subscription("Costco", None, Some(120.0), None, None, None)
+        .unwrap();
+
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db, None, config);
 
     let response = app
         .oneshot(
             Request::builder()
                 .method("POST")
-                .uri("/api/subscriptions/99999/cancel")
+                .uri(format!("/api/subscriptions/{}/cancel", sub_id))
                 .body(Body::empty())
                 .unwrap(),
         )
         .await
         .unwrap();
 
-    // Handler doesn't validate existence - returns 200 with success=true
     assert_eq!(response.status(), StatusCode::OK);
     let json = get_body_json(response).await;
     assert_eq!(json["success"], true);
@@ -8352,7 +8988,14 @@ async fn test_list_all_insights_empty() {
 
 #[tokio::test]
 async fn test_refresh_insights() {
-    let app = setup_test_app();
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db.clone(), None, config);
 
     let response = app
         .oneshot(
@@ -8365,8 +9008,7 @@ async fn test_refresh_insights() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
-    let json = get_body_json(response).await;
+    let json = run_queued_job(&db, response).await;
     // Should have run successfully with count of generated insights
     assert!(json.get("count").is_some());
 }
@@ -8603,3 +9245,3654 @@ async fn test_snooze_insight() {
     assert_eq!(insights[0]["status"], "snoozed");
     assert!(insights[0]["snoozed_until"].is_string());
 }
+
+// ========== Explore Streaming Tests ==========
+
+/// Build an app whose explore orchestrator talks to a mock Anthropic-compatible server
+fn setup_explore_app(ai_url: &str) -> Router {
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    setup_explore_app_with_db(ai_url, db)
+}
+
+/// Same as `setup_explore_app`, over an existing database (simulates a restart)
+fn setup_explore_app_with_db(ai_url: &str, db: Database) -> Router {
+    let config = ServerConfig {
+        require_auth: false,
+        ..Default::default()
+    };
+    explore_app_with_config(ai_url, db, config)
+}
+
+fn explore_app_with_config(ai_url: &str, db: Database, config: ServerConfig) -> Router {
+    let backend = hone_core::ai::AnthropicCompatBackend::new(ai_url, "mock-model");
+    let state = Arc::new(AppState {
+        db: db.clone(),
+        config: config.clone(),
+        ai: None,
+        orchestrator: Some(AIOrchestrator::new(backend, db)),
+        backup_dir: None,
+        receipts_dir: PathBuf::from("receipts"),
+        explore_sessions: handlers::ExploreSessionManager::new(),
+        local_auth: crate::LocalAuthState::new(),
+        http_metrics: crate::HttpMetrics::new(),
+    });
+    build_router(state, None, config)
+}
+
+/// Parse an SSE body into (event name, JSON data) pairs
+fn parse_sse_events(body: &str) -> Vec<(String, serde_json::Value)> {
+    body.split("\n\n")
+        .filter_map(|chunk| {
+            let mut name = None;
+            let mut data = None;
+            for line in chunk.lines() {
+                if let Some(n) = line.strip_prefix("event:") {
+                    name = Some(n.trim().to_string());
+                } else if let Some(d) = line.strip_prefix("data:") {
+                    data = serde_json::from_str(d.trim()).ok();
+                }
+            }
+            Some((name?, data?))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_explore_stream_requires_orchestrator() {
+    let app = setup_test_app();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/explore/query/stream")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"query": "Any alerts?"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_explore_stream_events() {
+    let server = hone_core::test_utils::MockOllamaServer::start().await;
+    let app = setup_explore_app(&server.url());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/explore/query/stream")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"query": "Any alerts?"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/event-stream"
+    );
+
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let events = parse_sse_events(&String::from_utf8_lossy(&bytes));
+    let names: Vec<&str> = events.iter().map(|(n, _)| n.as_str()).collect();
+
+    assert_eq!(names.first(), Some(&"session"));
+    assert_eq!(names.last(), Some(&"done"));
+    assert!(names.contains(&"text_delta"));
+
+    let started = names
+        .iter()
+        .position(|n| *n == "tool_call_started")
+        .unwrap();
+    let finished = names
+        .iter()
+        .position(|n| *n == "tool_call_finished")
+        .unwrap();
+    assert!(started < finished);
+    assert_eq!(events[finished].1["call"]["name"], "get_alerts");
+    assert_eq!(events[finished].1["call"]["success"], true);
+
+    let done = &events.last().unwrap().1;
+    assert_eq!(done["response"], "You have no active alerts.");
+    assert_eq!(done["model"], "mock-model");
+    assert_eq!(done["tool_calls"].as_array().unwrap().len(), 1);
+    assert_eq!(done["session_id"], events[0].1["session_id"]);
+}
+
+#[tokio::test]
+async fn test_explore_cancel_without_running_query() {
+    let app = setup_test_app();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/explore/session/exp_missing/cancel")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["cancelled"], false);
+}
+
+#[tokio::test]
+async fn test_explore_session_run_tracking() {
+    let manager = handlers::ExploreSessionManager::new();
+
+    let first = manager.start_run("exp_1").await;
+    let second = manager.start_run("exp_1").await;
+    // Starting a new run supersedes (cancels) the previous one
+    assert!(first.load(std::sync::atomic::Ordering::SeqCst));
+    assert!(!second.load(std::sync::atomic::Ordering::SeqCst));
+
+    // Finishing the stale run must not unregister the current one
+    manager.finish_run("exp_1", &first).await;
+    assert!(manager.cancel_run("exp_1").await);
+    assert!(second.load(std::sync::atomic::Ordering::SeqCst));
+
+    manager.finish_run("exp_1", &second).await;
+    assert!(!manager.cancel_run("exp_1").await);
+}
+
+// ========== Explore Session Persistence Tests ==========
+
+/// Run a non-streaming explore query and return the JSON response
+async fn run_explore_query(app: Router, body: serde_json::Value) -> serde_json::Value {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/explore/query")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    get_body_json(response).await
+}
+
+#[tokio::test]
+async fn test_explore_session_survives_restart() {
+    let server = hone_core::test_utils::MockOllamaServer::start().await;
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+
+    let app = setup_explore_app_with_db(&server.url(), db.clone());
+    let first = run_explore_query(app, serde_json::json!({ "query": "Any alerts?" })).await;
+    let session_id = first["session_id"].as_str().unwrap().to_string();
+
+    // A fresh router over the same database resumes the conversation
+    let app = setup_explore_app_with_db(&server.url(), db.clone());
+    let second = run_explore_query(
+        app,
+        serde_json::json!({ "query": "Thanks", "session_id": session_id }),
+    )
+    .await;
+    assert_eq!(second["session_id"], session_id.as_str());
+
+    let app = setup_explore_app_with_db(&server.url(), db);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/explore/session/{}", session_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["title"], "Any alerts?");
+    assert_eq!(json["turn_count"], 2);
+    assert_eq!(json["message_count"], 4);
+    let turns = json["turns"].as_array().unwrap();
+    assert_eq!(turns[0]["query"], "Any alerts?");
+    assert_eq!(turns[0]["tool_calls"][0]["name"], "get_alerts");
+    assert_eq!(turns[1]["query"], "Thanks");
+}
+
+#[tokio::test]
+async fn test_explore_sessions_list_rename_and_export() {
+    let server = hone_core::test_utils::MockOllamaServer::start().await;
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+
+    let app = setup_explore_app_with_db(&server.url(), db.clone());
+    let result = run_explore_query(app, serde_json::json!({ "query": "Any alerts?" })).await;
+    let session_id = result["session_id"].as_str().unwrap().to_string();
+    db.create_explore_session("exp_other", "Groceries", None)
+        .unwrap();
+
+    // Search matches conversation text, not just titles
+    let app = setup_explore_app_with_db(&server.url(), db.clone());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/explore/sessions?search=no%20active")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    let sessions = json.as_array().unwrap();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0]["session_id"], session_id.as_str());
+
+    let app = setup_explore_app_with_db(&server.url(), db.clone());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!("/api/explore/session/{}", session_id))
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"title": "Alert check"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(get_body_json(response).await["title"], "Alert check");
+
+    let app = setup_explore_app_with_db(&server.url(), db.clone());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/explore/session/{}/export", session_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/markdown; charset=utf-8"
+    );
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let markdown = String::from_utf8_lossy(&bytes);
+    assert!(markdown.starts_with("# Alert check"));
+    assert!(markdown.contains("**You:**\n\nAny alerts?"));
+    assert!(markdown.contains("`get_alerts` (ok)"));
+    assert!(markdown.contains("You have no active alerts."));
+
+    let app = setup_explore_app_with_db(&server.url(), db);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/explore/session/exp_missing/export")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_explore_session_compaction() {
+    let server = hone_core::test_utils::MockOllamaServer::start().await;
+    let db = Database::in_memory().unwrap();
+    db.create_explore_session("exp_long", "Long chat", None)
+        .unwrap();
+    for i in 0..12 {
+        db.add_explore_turn(
+            "exp_long",
+            &hone_core::models::NewExploreTurn {
+                query: format!("Question {}", i),
+                response: format!("Answer {}", i),
+                tool_calls: Vec::new(),
+                iterations: 1,
+                model: None,
+            },
+        )
+        .unwrap();
+    }
+
+    // Without a summary, only the most recent turns go to the model
+    let session = db.get_explore_session("exp_long").unwrap().unwrap();
+    let turns = db.get_explore_turns("exp_long").unwrap();
+    let history = handlers::build_history(&session, &turns);
+    assert_eq!(history.len(), 20);
+
+    let backend = hone_core::ai::AnthropicCompatBackend::new(&server.url(), "mock-model");
+    let compacted = handlers::compact_explore_session(&db, &backend, "exp_long")
+        .await
+        .unwrap();
+    assert!(compacted);
+
+    let session = db.get_explore_session("exp_long").unwrap().unwrap();
+    assert!(session.summary.is_some());
+    let turns = db.get_explore_turns("exp_long").unwrap();
+    assert_eq!(turns.iter().filter(|t| t.summarized).count(), 8);
+
+    // Summary pair plus the four turns kept verbatim
+    let history = handlers::build_history(&session, &turns);
+    assert_eq!(history.len(), 2 + 4 * 2);
+
+    // Nothing more to do until history grows again
+    let compacted = handlers::compact_explore_session(&db, &backend, "exp_long")
+        .await
+        .unwrap();
+    assert!(!compacted);
+}
+
+#[test]
+fn test_explore_title_from_query() {
+    assert_eq!(
+        handlers::title_from_query("  How much on gas?\nlast year"),
+        "How much on gas?"
+    );
+    assert_eq!(handlers::title_from_query("   "), "New conversation");
+    let long = "a".repeat(100);
+    let title = handlers::title_from_query(&long);
+    assert_eq!(title.chars().count(), 60);
+    assert!(title.ends_with('…'));
+}
+
+// ========== Saved Question Tests ==========
+
+#[tokio::test]
+async fn test_saved_question_crud() {
+    let app = setup_test_app();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/explore/saved")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    r#"{"name": "Dining", "query": "What changed in dining this month?", "interval_hours": 168}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let created = get_body_json(response).await;
+    let id = created["id"].as_i64().unwrap();
+    assert_eq!(created["interval_hours"], 168);
+    assert_eq!(created["enabled"], true);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!("/api/explore/saved/{}", id))
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"enabled": false}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(get_body_json(response).await["enabled"], false);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/explore/saved")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    r#"{"name": "Bad", "query": "x", "interval_hours": 0}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Running requires the AI orchestrator
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/explore/saved/{}/run", id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/api/explore/saved/{}", id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/explore/saved/{}/digests", id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_run_saved_question_stores_digest() {
+    let server = hone_core::test_utils::MockOllamaServer::start().await;
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    let question_id = db
+        .create_saved_question(&hone_core::models::NewSavedQuestion {
+            name: "Alerts".to_string(),
+            query: "Any alerts?".to_string(),
+            model: None,
+            interval_hours: 24,
+            created_by: None,
+        })
+        .unwrap();
+
+    let app = setup_explore_app_with_db(&server.url(), db.clone());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/explore/saved/{}/run", question_id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let digest = get_body_json(response).await;
+    assert_eq!(digest["success"], true);
+    assert_eq!(digest["response"], "You have no active alerts.");
+    assert_eq!(digest["tool_calls"][0]["name"], "get_alerts");
+    // First run has nothing to compare against
+    assert!(digest["change_score"].is_null());
+    assert_eq!(digest["changed"], false);
+
+    let question = db.get_saved_question(question_id).unwrap().unwrap();
+    assert!(question.last_run_at.is_some());
+
+    // The dashboard shows the latest answer
+    let app = setup_explore_app_with_db(&server.url(), db.clone());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/dashboard")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let stats = get_body_json(response).await;
+    assert_eq!(stats["recent_digests"][0]["name"], "Alerts");
+    assert_eq!(stats["recent_digests"][0]["tool_call_count"], 1);
+
+    // An identical answer is not a change
+    let orchestrator = AIOrchestrator::new(
+        hone_core::ai::AnthropicCompatBackend::new(&server.url(), "mock-model"),
+        db.clone(),
+    );
+    let question = db.get_saved_question(question_id).unwrap().unwrap();
+    let digest = run_saved_question(&db, &orchestrator, &question, "test")
+        .await
+        .unwrap();
+    assert_eq!(digest.change_score, Some(0.0));
+    assert!(!digest.changed);
+    assert!(digest.insight_id.is_none());
+    assert_eq!(db.count_active_insights().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_saved_question_change_raises_insight() {
+    let server = hone_core::test_utils::MockOllamaServer::start().await;
+    let db = Database::in_memory().unwrap();
+    let question_id = db
+        .create_saved_question(&hone_core::models::NewSavedQuestion {
+            name: "Alerts".to_string(),
+            query: "Any alerts?".to_string(),
+            model: None,
+            interval_hours: 24,
+            created_by: None,
+        })
+        .unwrap();
+    db.record_explore_digest(&hone_core::models::NewExploreDigest {
+        saved_question_id: question_id,
+        success: true,
+        response: "Netflix went up to $22.99 and Hulu looks unused.".to_string(),
+        error_message: None,
+        tool_calls: Vec::new(),
+        iterations: 1,
+        model: None,
+        latency_ms: 10,
+        change_score: None,
+        changed: false,
+    })
+    .unwrap();
+
+    let orchestrator = AIOrchestrator::new(
+        hone_core::ai::AnthropicCompatBackend::new(&server.url(), "mock-model"),
+        db.clone(),
+    );
+    let question = db.get_saved_question(question_id).unwrap().unwrap();
+    let digest = run_saved_question(&db, &orchestrator, &question, "scheduler")
+        .await
+        .unwrap();
+
+    assert!(digest.changed);
+    let insight_id = digest.insight_id.unwrap();
+    let insight = db.get_insight_finding(insight_id).unwrap().unwrap();
+    assert_eq!(
+        insight.insight_type,
+        hone_core::insights::InsightType::ExploreDigest
+    );
+    assert_eq!(insight.title, "Alerts changed");
+    assert_eq!(insight.data["saved_question_id"], question_id);
+}
+
+#[tokio::test]
+async fn test_saved_questions_only_read_what_their_creator_sees() {
+    use hone_core::models::AlertType;
+
+    let server = hone_core::test_utils::MockOllamaServer::start().await;
+    let db = household_db();
+    let owner = db.get_user_by_email("owner@example.com").unwrap().unwrap();
+    let editor = db.get_user_by_email("editor@example.com").unwrap().unwrap();
+    let private = db.upsert_account("Owner Card", Bank::Chase, None).unwrap();
+    db.set_account_visibility(private, &[owner.id]).unwrap();
+    let gym = db
+        .upsert_subscription("SECRET GYM", Some(private), Some(50.0), None, None, None)
+        .unwrap();
+    db.create_alert(
+        AlertType::Zombie,
+        Some(gym),
+        Some("SECRET GYM looks unused"),
+    )
+    .unwrap();
+    let mut ids = Vec::new();
+    for creator in ["editor@example.com", "owner@example.com"] {
+        ids.push(
+            db.create_saved_question(&hone_core::models::NewSavedQuestion {
+                name: format!("Alerts for {}", creator),
+                query: "Any alerts?".to_string(),
+                model: None,
+                interval_hours: 24,
+                created_by: Some(creator.to_string()),
+            })
+            .unwrap(),
+        );
+    }
+    let (editor_question, owner_question) = (ids[0], ids[1]);
+    let config = ServerConfig {
+        require_auth: true,
+        ..Default::default()
+    };
+    let app = explore_app_with_config(&server.url(), db.clone(), config);
+
+    // Members only see their own questions
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "GET",
+            "/api/explore/saved",
+            "editor@example.com",
+        ))
+        .await
+        .unwrap();
+    let list = get_body_json(response).await;
+    assert_eq!(list.as_array().unwrap().len(), 1);
+    assert_eq!(list[0]["id"], editor_question);
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "POST",
+            &format!("/api/explore/saved/{}/run", owner_question),
+            "editor@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // The editor's run leaves out the alert on the owner's card
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "POST",
+            &format!("/api/explore/saved/{}/run", editor_question),
+            "editor@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let digest = get_body_json(response).await;
+    assert_eq!(digest["success"], true);
+    assert_eq!(digest["tool_calls"][0]["name"], "get_alerts");
+    assert!(!digest["tool_calls"][0]["output"]
+        .as_str()
+        .unwrap()
+        .contains("SECRET GYM"));
+
+    // The owner's question sees every account
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "POST",
+            &format!("/api/explore/saved/{}/run", owner_question),
+            "owner@example.com",
+        ))
+        .await
+        .unwrap();
+    let digest = get_body_json(response).await;
+    assert!(digest["tool_calls"][0]["output"]
+        .as_str()
+        .unwrap()
+        .contains("SECRET GYM"));
+
+    // A removed creator's question fails instead of running unscoped
+    db.delete_user(editor.id).unwrap();
+    let response = app
+        .oneshot(household_request(
+            "POST",
+            &format!("/api/explore/saved/{}/run", editor_question),
+            "owner@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let digest = get_body_json(response).await;
+    assert_eq!(digest["success"], false);
+    assert!(digest["tool_calls"].as_array().unwrap().is_empty());
+}
+
+// ========== Household User Tests ==========
+
+fn household_app(db: Database, api_keys: Vec<String>) -> Router {
+    let config = ServerConfig {
+        require_auth: true,
+        allowed_origins: vec![],
+        api_keys,
+        ..Default::default()
+    };
+    create_router(db, None, config)
+}
+
+fn household_request(method: &str, uri: &str, email: &str) -> Request<Body> {
+    household_json_request(method, uri, email, serde_json::json!({}))
+}
+
+fn household_json_request(
+    method: &str,
+    uri: &str,
+    email: &str,
+    body: serde_json::Value,
+) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("cf-access-authenticated-user-email", email)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+fn household_db() -> Database {
+    use hone_core::models::UserRole;
+
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    db.create_user("owner@example.com", Some("Owner"), UserRole::Owner)
+        .unwrap();
+    db.create_user("editor@example.com", None, UserRole::Editor)
+        .unwrap();
+    db.create_user("viewer@example.com", None, UserRole::Viewer)
+        .unwrap();
+    db
+}
+
+#[test]
+fn test_api_key_entry_parse() {
+    use hone_core::models::UserRole;
+
+    let plain = ApiKeyEntry::parse("secret").unwrap();
+    assert_eq!(plain.key, "secret");
+    assert_eq!(plain.email, None);
+    assert_eq!(plain.role, None);
+
+    let scoped = ApiKeyEntry::parse("secret:kid@example.com").unwrap();
+    assert_eq!(scoped.email.as_deref(), Some("kid@example.com"));
+    assert_eq!(scoped.role, None);
+
+    let role_only = ApiKeyEntry::parse("secret:viewer").unwrap();
+    assert_eq!(role_only.email, None);
+    assert_eq!(role_only.role, Some(UserRole::Viewer));
+
+    let both = ApiKeyEntry::parse(" secret:kid@example.com:editor ").unwrap();
+    assert_eq!(both.key, "secret");
+    assert_eq!(both.email.as_deref(), Some("kid@example.com"));
+    assert_eq!(both.role, Some(UserRole::Editor));
+
+    // A mistyped role is an error, not a second email or a wider key
+    assert!(ApiKeyEntry::parse("secret:kid@example.com:editr").is_err());
+
+    // Older keys containing ':' stay whole
+    for legacy in ["abc:def", "abc:def:ghi"] {
+        let entry = ApiKeyEntry::parse(legacy).unwrap();
+        assert_eq!(entry.key, legacy);
+        assert_eq!(entry.email, None);
+        assert_eq!(entry.role, None);
+    }
+}
+
+#[test]
+fn test_required_role_policy() {
+    use axum::http::Method;
+    use hone_core::models::UserRole;
+
+    assert_eq!(
+        identity::required_role(&Method::GET, "/api/reports/spending"),
+        UserRole::Viewer
+    );
+    assert_eq!(
+        identity::required_role(&Method::POST, "/api/explore/query"),
+        UserRole::Viewer
+    );
+    for method in [Method::PATCH, Method::DELETE] {
+        assert_eq!(
+            identity::required_role(&method, "/api/explore/session/exp_1"),
+            UserRole::Viewer
+        );
+    }
+    assert_eq!(
+        identity::required_role(&Method::POST, "/api/accounts"),
+        UserRole::Editor
+    );
+    assert_eq!(
+        identity::required_role(&Method::POST, "/api/backup/x.db/restore"),
+        UserRole::Owner
+    );
+    assert_eq!(
+        identity::required_role(&Method::GET, "/api/users"),
+        UserRole::Owner
+    );
+    assert_eq!(
+        identity::required_role(&Method::PUT, "/api/accounts/1/visibility"),
+        UserRole::Owner
+    );
+    assert_eq!(
+        identity::required_role(&Method::GET, "/api/keys"),
+        UserRole::Owner
+    );
+}
+
+#[tokio::test]
+async fn test_single_user_mode_allows_everything() {
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    let app = household_app(db, vec![]);
+
+    let response = app
+        .oneshot(household_request("GET", "/api/me", "anyone@example.com"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["user"], "anyone@example.com");
+    assert_eq!(json["role"], "owner");
+    assert!(json["household_user"].is_null());
+}
+
+#[tokio::test]
+async fn test_viewer_can_read_but_not_write() {
+    let app = household_app(household_db(), vec![]);
+
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "GET",
+            "/api/reports/spending",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "POST",
+            "/api/backup/hone-backup.db/restore",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "POST",
+            "/api/accounts",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Editors can write but can't manage users
+    let response = app
+        .oneshot(household_request("GET", "/api/users", "editor@example.com"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_unknown_email_rejected_in_household_mode() {
+    let app = household_app(household_db(), vec![]);
+
+    let response = app
+        .oneshot(household_request(
+            "GET",
+            "/api/tags",
+            "stranger@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_user_lookup_failure_is_not_single_user_mode() {
+    let db = household_db();
+    db.conn().unwrap().execute("DROP TABLE users", []).unwrap();
+    let app = household_app(db, vec![]);
+
+    // A failed user count must not be mistaken for "no users" (owner access)
+    let response = app
+        .oneshot(household_request("GET", "/api/tags", "owner@example.com"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn test_scoped_api_key_resolves_to_user() {
+    let db = household_db();
+    let app = household_app(
+        db.clone(),
+        vec![
+            "viewkey:owner@example.com:viewer".to_string(),
+            "editkey:editor@example.com".to_string(),
+        ],
+    );
+
+    // Owner's key capped to viewer
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/me")
+                .header("authorization", "Bearer viewkey")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["user"], "owner@example.com");
+    assert_eq!(json["role"], "viewer");
+    assert_eq!(json["auth_method"], "api_key");
+    assert_eq!(json["household_user"]["email"], "owner@example.com");
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/backup/hone-backup.db/restore")
+                .header("authorization", "Bearer viewkey")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Requests through a scoped key count as the user's activity
+    let owner = db.get_user_by_email("owner@example.com").unwrap().unwrap();
+    assert!(owner.last_seen_at.is_some());
+}
+
+#[tokio::test]
+async fn test_client_supplied_user_header_ignored() {
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db, None, config);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/me")
+                .header("x-hone-user", "spoofed@example.com")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json["user"], "local-dev");
+}
+
+#[tokio::test]
+async fn test_account_visibility_filters_accounts_and_transactions() {
+    let db = household_db();
+    let editor = db.get_user_by_email("editor@example.com").unwrap().unwrap();
+    let shared = db.upsert_account("Joint", Bank::Chase, None).unwrap();
+    let private = db.upsert_account("Editor Card", Bank::Amex, None).unwrap();
+    for (account_id, hash) in [(shared, "vis_1"), (private, "vis_2")] {
+        let tx = hone_core::models::NewTransaction {
+            date: chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            description: "COFFEE".to_string(),
+            amount: -4.50,
+            category: None,
+            import_hash: hash.to_string(),
+            original_data: None,
+            import_format: None,
+            card_member: None,
+            payment_method: None,
+        };
+        db.insert_transaction(account_id, &tx).unwrap();
+    }
+    let app = household_app(db, vec![]);
+
+    // Restrict the card to the editor (owner only)
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/api/accounts/{}/visibility", private))
+                .header("cf-access-authenticated-user-email", "owner@example.com")
+                .header("content-type", "application/json")
+                .body(Body::from(format!(r#"{{"user_ids": [{}]}}"#, editor.id)))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "GET",
+            "/api/accounts",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    let accounts = json.as_array().unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0]["id"], shared);
+
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "GET",
+            &format!("/api/accounts/{}", private),
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "GET",
+            "/api/transactions",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json["total"], 1);
+
+    // The editor it was shared with sees both
+    let response = app
+        .oneshot(household_request(
+            "GET",
+            "/api/transactions",
+            "editor@example.com",
+        ))
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json["total"], 2);
+}
+
+#[tokio::test]
+async fn test_account_visibility_hides_transactions_reports_and_exports() {
+    let db = household_db();
+    let editor = db.get_user_by_email("editor@example.com").unwrap().unwrap();
+    let shared = db.upsert_account("Joint", Bank::Chase, None).unwrap();
+    let private = db.upsert_account("Editor Card", Bank::Amex, None).unwrap();
+    let mut ids = Vec::new();
+    for (account_id, description, amount, hash) in [
+        (shared, "COFFEE", -4.50, "vis_1"),
+        (private, "JEWELRY", -120.00, "vis_2"),
+    ] {
+        let tx = hone_core::models::NewTransaction {
+            date: chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            description: description.to_string(),
+            amount,
+            category: None,
+            import_hash: hash.to_string(),
+            original_data: None,
+            import_format: None,
+            card_member: None,
+            payment_method: None,
+        };
+        ids.push(db.insert_transaction(account_id, &tx).unwrap().unwrap());
+    }
+    db.set_account_visibility(private, &[editor.id]).unwrap();
+    let app = household_app(db, vec![]);
+
+    // A hidden account's transaction looks like it doesn't exist
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "GET",
+            &format!("/api/transactions/{}/tags", ids[1]),
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "GET",
+            &format!("/api/transactions/{}/tags", ids[0]),
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Reports leave its amounts out
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "GET",
+            "/api/reports/spending?from=2024-01-01&to=2024-01-31",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert!((json["total"].as_f64().unwrap().abs() - 4.50).abs() < 0.01);
+
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "GET",
+            "/api/reports/spending?from=2024-01-01&to=2024-01-31",
+            "editor@example.com",
+        ))
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert!((json["total"].as_f64().unwrap().abs() - 124.50).abs() < 0.01);
+
+    // So do exports
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "GET",
+            "/api/export/transactions",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let csv = String::from_utf8(body.to_vec()).unwrap();
+    assert!(csv.contains("COFFEE"));
+    assert!(!csv.contains("JEWELRY"));
+    assert!(!csv.contains("120"));
+
+    let response = app
+        .oneshot(household_request(
+            "GET",
+            "/api/export/transactions?format=json",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_account_visibility_hides_imports_trips_items_and_cancellations() {
+    use hone_core::db::TransactionInsertResult;
+    use hone_core::insights::{Finding, InsightType, Severity};
+    use hone_core::models::{NewImportSession, NewSubscriptionCancellation, NewTransaction};
+
+    let db = household_db();
+    let editor = db.get_user_by_email("editor@example.com").unwrap().unwrap();
+    let shared = db.upsert_account("Joint", Bank::Chase, None).unwrap();
+    let private = db.upsert_account("Editor Card", Bank::Amex, None).unwrap();
+    let trip_id = db
+        .create_trip(&NewTrip {
+            name: "Vacation".to_string(),
+            description: None,
+            start_date: None,
+            end_date: None,
+            location_id: None,
+            budget: None,
+        })
+        .unwrap();
+    let session_id = db
+        .create_import_session(&NewImportSession {
+            account_id: private,
+            filename: Some("card.csv".to_string()),
+            file_size_bytes: None,
+            bank: Bank::Amex,
+            user_email: Some("editor@example.com".to_string()),
+            ollama_model: None,
+        })
+        .unwrap();
+    let mut ids = Vec::new();
+    for (account_id, description, amount, hash) in [
+        (shared, "HOTEL", -200.00, "vis_1"),
+        (private, "JEWELRY", -120.00, "vis_2"),
+    ] {
+        let tx = NewTransaction {
+            date: chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            description: description.to_string(),
+            amount,
+            category: None,
+            import_hash: hash.to_string(),
+            original_data: None,
+            import_format: None,
+            card_member: None,
+            payment_method: None,
+        };
+        let TransactionInsertResult::Inserted(id) = db
+            .insert_transaction_with_session(account_id, &tx, session_id)
+            .unwrap()
+        else {
+            panic!("expected an inserted transaction");
+        };
+        db.assign_transaction_to_trip(id, Some(trip_id)).unwrap();
+        ids.push(id);
+    }
+    db.record_skipped_transaction(
+        session_id,
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+        "JEWELRY",
+        -120.00,
+        "vis_2",
+        Some(ids[1]),
+    )
+    .unwrap();
+    db.create_split(&NewTransactionSplit {
+        transaction_id: ids[1],
+        amount: 120.00,
+        description: Some("Gold ring".to_string()),
+        split_type: SplitType::Item,
+        entity_id: None,
+        purchaser_id: None,
+    })
+    .unwrap();
+    let sub_id = db
+        .upsert_subscription("Jewelry Club", Some(private), Some(20.00), None, None, None)
+        .unwrap();
+    db.record_cancellation(sub_id, &NewSubscriptionCancellation::default())
+        .unwrap();
+    db.upsert_insight_finding(&Finding::new(
+        InsightType::SpendingExplainer,
+        "test:visibility:1",
+        Severity::Attention,
+        "Jewelry spending is up",
+        "You spent $120 on jewelry",
+    ))
+    .unwrap();
+    db.set_account_visibility(private, &[editor.id]).unwrap();
+    let app = household_app(db, vec![]);
+
+    let get = |uri: String, email: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(household_request("GET", &uri, email))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            get_body_json(response).await
+        }
+    };
+
+    // The viewer sees nothing from the hidden card
+    let json = get(
+        format!("/api/imports/{}/transactions", session_id),
+        "viewer@example.com",
+    )
+    .await;
+    assert_eq!(json["total"], 1);
+    assert_eq!(json["transactions"][0]["id"], ids[0]);
+    let json = get(
+        format!("/api/imports/{}/skipped", session_id),
+        "viewer@example.com",
+    )
+    .await;
+    assert!(json.as_array().unwrap().is_empty());
+
+    let json = get(
+        format!("/api/trips/{}/transactions", trip_id),
+        "viewer@example.com",
+    )
+    .await;
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    let json = get(
+        format!("/api/trips/{}/spending", trip_id),
+        "viewer@example.com",
+    )
+    .await;
+    assert_eq!(json["transaction_count"], 1);
+    assert!((json["total_spent"].as_f64().unwrap() - 200.00).abs() < 0.01);
+
+    let json = get("/api/items".to_string(), "viewer@example.com").await;
+    assert!(json.as_array().unwrap().is_empty());
+    let json = get("/api/cancellations".to_string(), "viewer@example.com").await;
+    assert!(json.as_array().unwrap().is_empty());
+
+    // Insights are built from every account, so they're withheld entirely
+    let json = get("/api/insights".to_string(), "viewer@example.com").await;
+    assert!(json.as_array().unwrap().is_empty());
+    let json = get("/api/insights/count".to_string(), "viewer@example.com").await;
+    assert_eq!(json, 0);
+
+    // The editor it was shared with sees all of it
+    let json = get(
+        format!("/api/imports/{}/transactions", session_id),
+        "editor@example.com",
+    )
+    .await;
+    assert_eq!(json["total"], 2);
+    let json = get(
+        format!("/api/imports/{}/skipped", session_id),
+        "editor@example.com",
+    )
+    .await;
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    let json = get(
+        format!("/api/trips/{}/spending", trip_id),
+        "editor@example.com",
+    )
+    .await;
+    assert_eq!(json["transaction_count"], 2);
+    let json = get("/api/items".to_string(), "editor@example.com").await;
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    let json = get("/api/cancellations".to_string(), "editor@example.com").await;
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    let json = get("/api/insights".to_string(), "editor@example.com").await;
+    assert_eq!(json.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_explore_sessions_limited_to_their_creator() {
+    let db = household_db();
+    db.create_explore_session("exp_editor", "Gift ideas", Some("editor@example.com"))
+        .unwrap();
+    db.create_explore_session("exp_viewer", "Allowance", Some("viewer@example.com"))
+        .unwrap();
+    let app = household_app(db, vec![]);
+
+    let list = |email: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(household_request("GET", "/api/explore/sessions", email))
+                .await
+                .unwrap();
+            let json = get_body_json(response).await;
+            json.as_array()
+                .unwrap()
+                .iter()
+                .map(|s| s["session_id"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+    assert_eq!(list("viewer@example.com").await, vec!["exp_viewer"]);
+    assert_eq!(list("editor@example.com").await, vec!["exp_editor"]);
+    assert_eq!(list("owner@example.com").await.len(), 2);
+
+    // Another member's session can't be read, exported, renamed or deleted
+    for (method, uri) in [
+        ("GET", "/api/explore/session/exp_viewer"),
+        ("GET", "/api/explore/session/exp_viewer/export"),
+        ("DELETE", "/api/explore/session/exp_viewer"),
+    ] {
+        let response = app
+            .clone()
+            .oneshot(household_request(method, uri, "editor@example.com"))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            StatusCode::NOT_FOUND,
+            "{} {}",
+            method,
+            uri
+        );
+    }
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri("/api/explore/session/exp_viewer")
+                .header("cf-access-authenticated-user-email", "editor@example.com")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"title": "Mine now"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(list("viewer@example.com").await, vec!["exp_viewer"]);
+
+    // The creator and owners can open it
+    for email in ["viewer@example.com", "owner@example.com"] {
+        let response = app
+            .clone()
+            .oneshot(household_request(
+                "GET",
+                "/api/explore/session/exp_viewer",
+                email,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    // Viewers rename and delete their own sessions, but nobody else's
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri("/api/explore/session/exp_viewer")
+                .header("cf-access-authenticated-user-email", "viewer@example.com")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"title": "Weekly allowance"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "DELETE",
+            "/api/explore/session/exp_editor",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "DELETE",
+            "/api/explore/session/exp_viewer",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(list("viewer@example.com").await.is_empty());
+    assert_eq!(list("editor@example.com").await, vec!["exp_editor"]);
+}
+
+#[tokio::test]
+async fn test_manage_users_api() {
+    let db = household_db();
+    let app = household_app(db, vec![]);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/users")
+                .header("cf-access-authenticated-user-email", "owner@example.com")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    r#"{"email": "teen@example.com", "role": "viewer"}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["role"], "viewer");
+
+    let response = app
+        .clone()
+        .oneshot(household_request("GET", "/api/users", "owner@example.com"))
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json.as_array().unwrap().len(), 4);
+
+    // Can't demote the only owner
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri("/api/users/1")
+                .header("cf-access-authenticated-user-email", "owner@example.com")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"role": "editor"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+// ========== Built-in Authentication Tests ==========
+
+fn local_auth_app(db: Database) -> Router {
+    let config = ServerConfig {
+        require_auth: true,
+        local_auth: crate::LocalAuthConfig {
+            enabled: true,
+            relying_party: Some(crate::RelyingParty {
+                id: "hone.example.com".to_string(),
+                origin: "https://hone.example.com".to_string(),
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    create_router(db, None, config)
+}
+
+fn local_auth_db() -> Database {
+    use hone_core::local_auth::hash_password;
+    use hone_core::models::UserRole;
+
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    let id = db
+        .create_user("owner@example.com", None, UserRole::Owner)
+        .unwrap();
+    db.set_user_password_hash(id, Some(&hash_password("correct horse battery").unwrap()))
+        .unwrap();
+    db
+}
+
+fn json_request(method: &str, uri: &str, body: serde_json::Value) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+/// Session cookie header value and CSRF token from a login response
+fn session_from(response: &axum::response::Response) -> (String, String) {
+    let cookies: Vec<String> = response
+        .headers()
+        .get_all("set-cookie")
+        .iter()
+        .map(|v| v.to_str().unwrap().to_string())
+        .collect();
+    let value = |name: &str| {
+        cookies
+            .iter()
+            .find_map(|c| c.strip_prefix(&format!("{}=", name)))
+            .and_then(|c| c.split(';').next())
+            .unwrap()
+            .to_string()
+    };
+    let session = value("hone_session");
+    let csrf = value("hone_csrf");
+    (
+        format!("hone_session={}; hone_csrf={}", session, csrf),
+        csrf,
+    )
+}
+
+async fn login_owner(app: &Router) -> (String, String) {
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            "/api/auth/login",
+            serde_json::json!({"email": "owner@example.com", "password": "correct horse battery"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    session_from(&response)
+}
+
+#[tokio::test]
+async fn test_local_auth_status_is_public() {
+    let app = local_auth_app(local_auth_db());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/auth/status")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["local_auth_enabled"], true);
+    assert_eq!(json["passkeys_enabled"], true);
+}
+
+#[tokio::test]
+async fn test_local_auth_login_disabled_by_default() {
+    let app = household_app(local_auth_db(), vec![]);
+    let response = app
+        .oneshot(json_request(
+            "POST",
+            "/api/auth/login",
+            serde_json::json!({"email": "owner@example.com", "password": "correct horse battery"}),
+        ))
+        .await
+        .unwrap();
+    // Not public when built-in auth is off
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_local_auth_login_and_session() {
+    let app = local_auth_app(local_auth_db());
+    let (cookie, csrf) = login_owner(&app).await;
+
+    // Session cookie authenticates reads
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/me")
+                .header("cookie", &cookie)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["user"], "owner@example.com");
+    assert_eq!(json["auth_method"], "session");
+
+    // Writes need the CSRF header
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/tags")
+                .header("cookie", &cookie)
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"name":"Hobbies"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/tags")
+                .header("cookie", &cookie)
+                .header("x-csrf-token", &csrf)
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"name":"Hobbies"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    // Logout ends the session
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/logout")
+                .header("cookie", &cookie)
+                .header("x-csrf-token", &csrf)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/me")
+                .header("cookie", &cookie)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_local_auth_login_rate_limited() {
+    let db = local_auth_db();
+    let app = local_auth_app(db.clone());
+
+    for _ in 0..5 {
+        let response = app
+            .clone()
+            .oneshot(json_request(
+                "POST",
+                "/api/auth/login",
+                serde_json::json!({"email": "owner@example.com", "password": "wrong password"}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    // Even the right password is refused until the window passes
+    let response = app
+        .oneshot(json_request(
+            "POST",
+            "/api/auth/login",
+            serde_json::json!({"email": "owner@example.com", "password": "correct horse battery"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.headers().contains_key("retry-after"));
+
+    let failures = db
+        .list_audit_log(100)
+        .unwrap()
+        .into_iter()
+        .filter(|e| e.action == "login_failed")
+        .count();
+    assert_eq!(failures, 5);
+}
+
+#[tokio::test]
+async fn test_local_auth_totp_required() {
+    use hone_core::local_auth::{generate_totp_secret, totp_code};
+
+    let db = local_auth_db();
+    let user = db.get_user_by_email("owner@example.com").unwrap().unwrap();
+    let secret = generate_totp_secret().unwrap();
+    db.set_user_totp(user.id, Some(&secret), true).unwrap();
+    let app = local_auth_app(db.clone());
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            "/api/auth/login",
+            serde_json::json!({"email": "owner@example.com", "password": "correct horse battery"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(get_body_json(response).await["totp_required"], true);
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            "/api/auth/login",
+            serde_json::json!({
+                "email": "owner@example.com",
+                "password": "correct horse battery",
+                "totp_code": "000000",
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let code = totp_code(&secret, chrono::Utc::now().timestamp()).unwrap();
+    let login = serde_json::json!({
+        "email": "owner@example.com",
+        "password": "correct horse battery",
+        "totp_code": code,
+    });
+    let response = app
+        .clone()
+        .oneshot(json_request("POST", "/api/auth/login", login.clone()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let (cookie, csrf) = session_from(&response);
+
+    // The same code can't be replayed within its window, to log in...
+    let response = app
+        .clone()
+        .oneshot(json_request("POST", "/api/auth/login", login))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let audit = db.list_audit_log(50).unwrap();
+    assert!(audit.iter().any(|e| e.action == "login_failed"
+        && e.details
+            .as_deref()
+            .unwrap_or_default()
+            .contains("totp_replay")));
+
+    // ...or to turn TOTP off
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/auth/totp/disable")
+                .header("cookie", &cookie)
+                .header("x-csrf-token", &csrf)
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::json!({ "code": code }).to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(db.get_user_totp(user.id).unwrap().unwrap().1);
+}
+
+#[tokio::test]
+async fn test_local_auth_passkey_register_and_login() {
+    use crate::webauthn::b64url_encode;
+    use crate::webauthn::test_authenticator::TestAuthenticator;
+
+    let app = local_auth_app(local_auth_db());
+    let (cookie, csrf) = login_owner(&app).await;
+    let origin = "https://hone.example.com";
+    let mut authenticator = TestAuthenticator::new();
+
+    let session_post = |uri: &str, body: serde_json::Value| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("cookie", &cookie)
+            .header("x-csrf-token", &csrf)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    // Register
+    let response = app
+        .clone()
+        .oneshot(session_post(
+            "/api/auth/passkeys/register/start",
+            serde_json::json!({}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let options = get_body_json(response).await;
+    let challenge = options["challenge"].as_str().unwrap();
+
+    let client_data = TestAuthenticator::client_data("webauthn.create", challenge, origin);
+    let response = app
+        .clone()
+        .oneshot(session_post(
+            "/api/auth/passkeys/register/finish",
+            serde_json::json!({
+                "name": "Laptop",
+                "client_data_json": b64url_encode(&client_data),
+                "attestation_object": b64url_encode(&authenticator.attestation_object("hone.example.com")),
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(get_body_json(response).await["name"], "Laptop");
+
+    // Log in with it (no cookie needed)
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            "/api/auth/passkeys/login/start",
+            serde_json::json!({}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let challenge = get_body_json(response).await["challenge"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let client_data = TestAuthenticator::client_data("webauthn.get", &challenge, origin);
+    let (auth_data, signature) = authenticator.assert("hone.example.com", &client_data);
+    let body = serde_json::json!({
+        "credential_id": b64url_encode(&authenticator.credential_id),
+        "client_data_json": b64url_encode(&client_data),
+        "authenticator_data": b64url_encode(&auth_data),
+        "signature": b64url_encode(&signature),
+    });
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            "/api/auth/passkeys/login/finish",
+            body.clone(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        get_body_json(response).await["user"]["email"],
+        "owner@example.com"
+    );
+
+    // Challenges are single-use, so a replay fails
+    let response = app
+        .oneshot(json_request(
+            "POST",
+            "/api/auth/passkeys/login/finish",
+            body,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+// ========== Database API Key Tests ==========
+
+fn bearer_request(method: &str, uri: &str, secret: &str) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("authorization", format!("Bearer {}", secret))
+        .header("content-type", "application/json")
+        .body(Body::from("{}"))
+        .unwrap()
+}
+
+#[test]
+fn test_api_key_scope_policy() {
+    use axum::http::Method;
+    use hone_core::models::ApiKeyScope;
+
+    let allows = identity::api_key_scope_allows;
+    assert!(allows(
+        ApiKeyScope::ImportOnly,
+        &Method::POST,
+        "/api/import"
+    ));
+    assert!(allows(
+        ApiKeyScope::ImportOnly,
+        &Method::POST,
+        "/api/import/transactions"
+    ));
+    assert!(allows(
+        ApiKeyScope::ImportOnly,
+        &Method::GET,
+        "/api/accounts"
+    ));
+    assert!(allows(
+        ApiKeyScope::ImportOnly,
+        &Method::GET,
+        "/api/imports/3"
+    ));
+    assert!(!allows(
+        ApiKeyScope::ImportOnly,
+        &Method::POST,
+        "/api/import/full"
+    ));
+    assert!(!allows(
+        ApiKeyScope::ImportOnly,
+        &Method::POST,
+        "/api/accounts"
+    ));
+    assert!(!allows(
+        ApiKeyScope::ImportOnly,
+        &Method::GET,
+        "/api/transactions"
+    ));
+    assert!(!allows(ApiKeyScope::Mcp, &Method::GET, "/api/accounts"));
+    assert!(allows(
+        ApiKeyScope::ReadOnly,
+        &Method::GET,
+        "/api/transactions"
+    ));
+    assert!(allows(ApiKeyScope::Admin, &Method::POST, "/api/users"));
+}
+
+#[tokio::test]
+async fn test_api_key_limited_by_its_creator() {
+    use hone_core::models::{ApiKeyScope, UserRole};
+
+    let db = household_db();
+    let co_owner = db
+        .create_user("co@example.com", None, UserRole::Owner)
+        .unwrap();
+    let (_, secret) = db
+        .create_api_key("admin", ApiKeyScope::Admin, None, Some("co@example.com"))
+        .unwrap();
+    let (_, orphan) = db
+        .create_api_key("orphan", ApiKeyScope::Admin, None, Some("gone@example.com"))
+        .unwrap();
+    let app = household_app(db.clone(), vec![]);
+
+    let response = app
+        .clone()
+        .oneshot(bearer_request("GET", "/api/users", &secret))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Demoting the creator demotes their key
+    db.update_user(co_owner, None, Some(UserRole::Viewer))
+        .unwrap();
+    let response = app
+        .clone()
+        .oneshot(bearer_request("GET", "/api/users", &secret))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = app
+        .clone()
+        .oneshot(bearer_request("GET", "/api/accounts", &secret))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // A key whose creator isn't a household user is refused
+    let response = app
+        .clone()
+        .oneshot(bearer_request("GET", "/api/accounts", &orphan))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Removing the creator revokes their keys
+    db.delete_user(co_owner).unwrap();
+    let response = app
+        .oneshot(bearer_request("GET", "/api/accounts", &secret))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_api_keys_create_use_and_revoke() {
+    let db = household_db();
+    let app = household_app(db.clone(), vec![]);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/keys")
+                .header("cf-access-authenticated-user-email", "owner@example.com")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    r#"{"name": "dashboard", "scope": "read_only", "expires_in_days": 30}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    let secret = json["secret"].as_str().unwrap().to_string();
+    let key_id = json["key"]["id"].as_i64().unwrap();
+    assert_eq!(json["key"]["scope"], "read_only");
+    assert_eq!(json["key"]["created_by"], "owner@example.com");
+    assert!(json["key"]["expires_at"].is_string());
+
+    // An expiry too far out is rejected instead of overflowing
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/keys")
+                .header("cf-access-authenticated-user-email", "owner@example.com")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    r#"{"name": "forever", "scope": "read_only", "expires_in_days": 9223372036854775807}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Only owners manage keys
+    let response = app
+        .clone()
+        .oneshot(household_request("GET", "/api/keys", "editor@example.com"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Read-only keys read as a viewer, under their own name in the audit log
+    let response = app
+        .clone()
+        .oneshot(bearer_request("GET", "/api/accounts", &secret))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let entries = db.list_audit_log(1).unwrap();
+    assert_eq!(entries[0].user_email, "api-key:dashboard");
+
+    let response = app
+        .clone()
+        .oneshot(bearer_request("POST", "/api/accounts", &secret))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "DELETE",
+            &format!("/api/keys/{}", key_id),
+            "owner@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .oneshot(bearer_request("GET", "/api/accounts", &secret))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let actions: Vec<String> = db
+        .query_audit_log(&hone_core::AuditFilter {
+            entity_type: Some("api_key".to_string()),
+            ..Default::default()
+        })
+        .unwrap()
+        .into_iter()
+        .map(|e| e.action)
+        .collect();
+    assert_eq!(actions, vec!["revoke", "create"]);
+}
+
+#[tokio::test]
+async fn test_api_key_scopes_enforced() {
+    use hone_core::models::ApiKeyScope;
+
+    let db = household_db();
+    let (_, import_secret) = db
+        .create_api_key("bank-sync", ApiKeyScope::ImportOnly, None, None)
+        .unwrap();
+    let (_, mcp_secret) = db
+        .create_api_key("assistant", ApiKeyScope::Mcp, None, None)
+        .unwrap();
+    let (_, admin_secret) = db
+        .create_api_key("ops", ApiKeyScope::Admin, None, None)
+        .unwrap();
+    let app = household_app(db, vec![]);
+
+    let status = |method: &'static str, uri: &'static str, secret: String| {
+        let app = app.clone();
+        async move {
+            app.oneshot(bearer_request(method, uri, &secret))
+                .await
+                .unwrap()
+                .status()
+        }
+    };
+
+    assert_eq!(
+        status("GET", "/api/accounts", import_secret.clone()).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status("GET", "/api/transactions", import_secret).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status("GET", "/api/accounts", mcp_secret).await,
+        StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        status("GET", "/api/users", admin_secret).await,
+        StatusCode::OK
+    );
+}
+
+// ========== Metrics Tests ==========
+
+fn metrics_app(db: Database, metrics: crate::MetricsConfig) -> Router {
+    let config = ServerConfig {
+        require_auth: true,
+        api_keys: vec!["api-secret".to_string()],
+        metrics,
+        ..Default::default()
+    };
+    create_router(db, None, config)
+}
+
+#[tokio::test]
+async fn test_metrics_disabled_by_default() {
+    let app = metrics_app(Database::in_memory().unwrap(), Default::default());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/metrics")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_metrics_without_token_or_networks_refuses_localhost() {
+    let app = metrics_app(
+        Database::in_memory().unwrap(),
+        crate::MetricsConfig {
+            enabled: true,
+            token: None,
+            allowed_networks: vec![],
+        },
+    );
+
+    let mut request = Request::builder()
+        .uri("/metrics")
+        .body(Body::empty())
+        .unwrap();
+    request
+        .extensions_mut()
+        .insert(axum::extract::ConnectInfo(std::net::SocketAddr::from((
+            [127, 0, 0, 1],
+            40000,
+        ))));
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_metrics_requires_its_own_token() {
+    let app = metrics_app(
+        Database::in_memory().unwrap(),
+        crate::MetricsConfig {
+            enabled: true,
+            token: Some("scrape-token".to_string()),
+            allowed_networks: vec![],
+        },
+    );
+
+    // The main API key doesn't grant metrics access
+    for auth in [None, Some("Bearer api-secret")] {
+        let mut request = Request::builder().uri("/metrics");
+        if let Some(auth) = auth {
+            request = request.header("authorization", auth);
+        }
+        let response = app
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    // ...and the metrics token doesn't grant API access
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/accounts")
+                .header("authorization", "Bearer scrape-token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_metrics_exposition() {
+    use hone_core::models::{NewOllamaMetric, OllamaOperation};
+
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    for (latency_ms, success) in [(400, true), (3000, false)] {
+        db.record_ollama_metric(&NewOllamaMetric {
+            operation: OllamaOperation::ClassifyMerchant,
+            model: "llama3".to_string(),
+            latency_ms,
+            success,
+            error_message: None,
+            confidence: None,
+            transaction_id: None,
+            input_text: None,
+            result_text: None,
+            metadata: None,
+        })
+        .unwrap();
+    }
+    let backup_dir = tempfile::tempdir().unwrap();
+    let app = create_router_with_options(
+        db,
+        None,
+        ServerConfig {
+            require_auth: true,
+            api_keys: vec!["api-secret".to_string()],
+            metrics: crate::MetricsConfig {
+                enabled: true,
+                token: Some("scrape-token".to_string()),
+                allowed_networks: vec![],
+            },
+            ..Default::default()
+        },
+        Some(backup_dir.path().to_path_buf()),
+    );
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/accounts/42")
+                .header("authorization", "Bearer api-secret")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/metrics")
+                .header("authorization", "Bearer scrape-token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response
+        .headers()
+        .get("content-type")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain; version=0.0.4"));
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+
+    // Routes are labelled by pattern, not by raw path
+    assert!(text.contains(
+        r#"hone_http_requests_total{method="GET",route="/api/accounts/:id",status="404"} 1"#
+    ));
+    assert!(text.contains(r#"hone_ai_calls_total{operation="classify_merchant",model="llama3"} 2"#));
+    assert!(text
+        .contains(r#"hone_ai_call_errors_total{operation="classify_merchant",model="llama3"} 1"#));
+    assert!(text.contains(
+        r#"hone_ai_call_duration_seconds_bucket{operation="classify_merchant",model="llama3",le="0.5"} 1"#
+    ));
+    assert!(text.contains(
+        r#"hone_ai_call_duration_seconds_sum{operation="classify_merchant",model="llama3"} 3.4"#
+    ));
+    assert!(text.contains("# TYPE hone_db_size_bytes gauge"));
+    assert!(text.contains("hone_db_pool_max_connections "));
+    assert!(text.contains("hone_backups 0"));
+    assert!(!text.contains("hone_backup_last_success_timestamp_seconds"));
+}
+
+// ========== Notification Tests ==========
+
+fn notifications_app(db: Database, server_url: &str) -> Router {
+    let notifications = hone_core::notify::NotifyConfig::parse(&format!(
+        r#"
+        [[channels]]
+        name = "hook"
+        type = "webhook"
+        url = "{}/hook"
+        secret = "s3cret"
+
+        [[routes]]
+        events = ["alert:*"]
+        channels = ["hook"]
+        "#,
+        server_url
+    ))
+    .unwrap();
+    let config = ServerConfig {
+        require_auth: false,
+        notifications: Some(notifications),
+        ..Default::default()
+    };
+    create_router(db, None, config)
+}
+
+#[tokio::test]
+async fn test_notification_channels_hide_secrets() {
+    let server = hone_core::test_utils::MockNotificationServer::start().await;
+    let app = notifications_app(Database::in_memory().unwrap(), &server.url());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/notifications/channels")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["configured"], true);
+    assert_eq!(json["channels"][0]["name"], "hook");
+    assert_eq!(json["channels"][0]["type"], "webhook");
+    assert!(!json.to_string().contains("s3cret"));
+    assert_eq!(json["routes"][0]["events"][0], "alert:*");
+}
+
+#[tokio::test]
+async fn test_notification_channels_unconfigured() {
+    let app = setup_test_app();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/notifications/channels")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json["configured"], false);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/notifications/test")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"channel": "hook"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_notification_test_and_retry() {
+    let server = hone_core::test_utils::MockNotificationServer::start().await;
+    let db = Database::in_memory().unwrap();
+    let app = notifications_app(db.clone(), &server.url());
+
+    let send_test = |channel: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/notifications/test")
+            .header("content-type", "application/json")
+            .body(Body::from(format!(r#"{{"channel": "{}"}}"#, channel)))
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(send_test("hook")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["status"], "sent");
+    assert_eq!(server.requests().len(), 1);
+
+    let response = app.clone().oneshot(send_test("nope")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // A failed test delivery can be re-queued
+    server.fail_next(1);
+    let response = app.clone().oneshot(send_test("hook")).await.unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json["status"], "failed");
+    let failed_id = json["id"].as_i64().unwrap();
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/notifications/deliveries?status=failed")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert_eq!(json[0]["id"], failed_id);
+
+    let retry = |id: i64| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/api/notifications/deliveries/{}/retry", id))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.clone().oneshot(retry(failed_id)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["status"], "pending");
+    assert_eq!(json["attempts"], 0);
+
+    // Only failed deliveries can be retried
+    let response = app.clone().oneshot(retry(failed_id)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = app.oneshot(retry(9999)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let audit = db.list_audit_log(10).unwrap();
+    assert!(audit
+        .iter()
+        .any(|e| e.action == "retry" && e.entity_type.as_deref() == Some("notification")));
+}
+
+#[tokio::test]
+async fn test_notifications_owner_only() {
+    let app = household_app(household_db(), vec![]);
+
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "GET",
+            "/api/notifications/deliveries",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app
+        .oneshot(household_request(
+            "GET",
+            "/api/notifications/deliveries",
+            "owner@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+// ========== Background Job Tests ==========
+
+#[tokio::test]
+async fn test_import_processing_runs_as_job() {
+    use base64::Engine;
+    use hone_core::models::{ImportStatus, JobStatus};
+
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    let account_id = db
+        .upsert_account("Test Account", Bank::Chase, None)
+        .unwrap();
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db.clone(), None, config);
+
+    let csv_data = "Transaction Date,Post Date,Description,Category,Type,Amount,Memo\n\
+        01/15/2024,01/16/2024,NETFLIX,-15.99,Sale,-15.99,\n\
+        12/15/2023,12/16/2023,NETFLIX,-15.99,Sale,-15.99,\n\
+        11/15/2023,11/16/2023,NETFLIX,-15.99,Sale,-15.99,";
+    let body = serde_json::json!({
+        "account_id": account_id,
+        "csv_data": base64::engine::general_purpose::STANDARD.encode(csv_data)
+    });
+    let response = app
+        .clone()
+        .oneshot(json_request("POST", "/api/import/json", body))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let session_id = get_body_json(response).await["import_session_id"]
+        .as_i64()
+        .unwrap();
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/jobs?type=import_processing")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert_eq!(json[0]["status"], "queued");
+    assert_eq!(json[0]["entity_type"], "import_session");
+    assert_eq!(json[0]["entity_id"], session_id);
+    let job_id = json[0]["id"].as_i64().unwrap();
+
+    // The general lane doesn't pick up AI-heavy work
+    let runner = JobRunner::new(db.clone(), None);
+    assert_eq!(runner.run_next(JobLane::General).await.unwrap(), None);
+    assert_eq!(runner.run_next(JobLane::Ai).await.unwrap(), Some(job_id));
+
+    let job = db.get_job(job_id).unwrap().unwrap();
+    assert_eq!(job.status, JobStatus::Completed);
+    let session = db.get_import_session(session_id).unwrap().unwrap();
+    assert_eq!(session.session.status, ImportStatus::Completed);
+    assert_eq!(session.session.subscriptions_found, 1);
+}
+
+#[tokio::test]
+async fn test_jobs_api_create_cancel_retry() {
+    let db = Database::in_memory().unwrap();
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db.clone(), None, config);
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            "/api/jobs",
+            serde_json::json!({"type": "detection", "params": {"kind": "zombies"}}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["job_type"], "detection");
+    assert_eq!(json["status"], "queued");
+    assert_eq!(json["params"]["kind"], "zombies");
+    let id = json["id"].as_i64().unwrap();
+
+    // Import jobs and unknown detection kinds are rejected
+    for body in [
+        serde_json::json!({"type": "import_processing", "params": {"session_id": 1}}),
+        serde_json::json!({"type": "detection", "params": {"kind": "everything"}}),
+    ] {
+        let response = app
+            .clone()
+            .oneshot(json_request("POST", "/api/jobs", body))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            &format!("/api/jobs/{}/cancel", id),
+            serde_json::json!({}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(get_body_json(response).await["status"], "cancelled");
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            &format!("/api/jobs/{}/cancel", id),
+            serde_json::json!({}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            &format!("/api/jobs/{}/retry", id),
+            serde_json::json!({}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(get_body_json(response).await["status"], "queued");
+
+    // Detection reports progress and stores its result
+    let runner = JobRunner::new(db.clone(), None);
+    assert_eq!(runner.run_next(JobLane::Ai).await.unwrap(), Some(id));
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/jobs/{}", id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json["status"], "completed");
+    assert_eq!(json["result"]["zombies_detected"], 0);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/jobs/9999")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let audit = db.list_audit_log(10).unwrap();
+    assert!(audit
+        .iter()
+        .any(|e| e.action == "cancel" && e.entity_type.as_deref() == Some("job")));
+}
+
+#[tokio::test]
+async fn test_backup_jobs_owner_only_and_run_on_general_lane() {
+    use hone_core::models::JobStatus;
+
+    let db = household_db();
+    let app = household_app(db.clone(), vec![]);
+    let backup_request = |email: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/jobs")
+            .header("cf-access-authenticated-user-email", email)
+            .header("content-type", "application/json")
+            .body(Body::from(
+                r#"{"type": "backup", "params": {"retention": 2}}"#,
+            ))
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(backup_request("editor@example.com"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app
+        .clone()
+        .oneshot(backup_request("owner@example.com"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["created_by"], "owner@example.com");
+    let id = json["id"].as_i64().unwrap();
+
+    // Viewers can watch jobs
+    let response = app
+        .oneshot(household_request(
+            "GET",
+            &format!("/api/jobs/{}", id),
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let temp_dir = TempDir::new().unwrap();
+    let runner = JobRunner::new(db.clone(), None).with_backup_dir(temp_dir.path().to_path_buf());
+    assert_eq!(runner.run_next(JobLane::Ai).await.unwrap(), None);
+    assert_eq!(runner.run_next(JobLane::General).await.unwrap(), Some(id));
+
+    let job = db.get_job(id).unwrap().unwrap();
+    assert_eq!(job.status, JobStatus::Completed);
+    assert_eq!((job.progress_current, job.progress_total), (1, 1));
+    let name = job.result.unwrap()["name"].as_str().unwrap().to_string();
+    assert!(temp_dir.path().join(name).exists());
+}
+
+#[tokio::test]
+async fn test_owner_only_jobs_cant_be_retried_or_cancelled_by_editors() {
+    use hone_core::models::{JobType, NewJob};
+
+    let db = household_db();
+    let id = db
+        .create_job(&NewJob::new(
+            JobType::AuditArchive,
+            serde_json::json!({ "retention_days": 30 }),
+        ))
+        .unwrap();
+    db.fail_job(id, "disk full", None).unwrap();
+    let app = household_app(db, vec![]);
+
+    for action in ["retry", "cancel"] {
+        let response = app
+            .clone()
+            .oneshot(household_request(
+                "POST",
+                &format!("/api/jobs/{}/{}", id, action),
+                "editor@example.com",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{}", action);
+    }
+
+    let response = app
+        .oneshot(household_request(
+            "POST",
+            &format!("/api/jobs/{}/retry", id),
+            "owner@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["status"], "queued");
+}
+
+// ========== Schedule Tests ==========
+
+fn schedules_app(db: Database) -> Router {
+    let schedules = hone_core::schedule::ScheduleConfig::parse(
+        r#"
+        [[tasks]]
+        name = "monthly-report"
+        task = "report"
+        cron = "0 6 1 * *"
+
+        [[tasks]]
+        name = "prune-metrics"
+        task = "prune_metrics"
+        cron = "@weekly"
+        params = { keep_days = 30 }
+
+        [[tasks]]
+        name = "nightly-backup"
+        task = "backup"
+        cron = "0 2 * * *"
+        "#,
+    )
+    .unwrap();
+    let config = ServerConfig {
+        require_auth: true,
+        schedules,
+        ..Default::default()
+    };
+    create_router(db, None, config)
+}
+
+#[tokio::test]
+async fn test_schedules_list_and_run_now() {
+    use hone_core::models::JobStatus;
+
+    let db = household_db();
+    let app = schedules_app(db.clone());
+
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "GET",
+            "/api/schedules",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    let schedules = json.as_array().unwrap();
+    assert_eq!(schedules.len(), 3);
+    assert_eq!(schedules[0]["name"], "monthly-report");
+    assert_eq!(schedules[0]["task"], "report");
+    assert!(schedules[0]["next_run_at"].is_string());
+    assert!(schedules[0]["last_run_at"].is_null());
+
+    // Viewers can't run tasks, editors can't run backups
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "POST",
+            "/api/schedules/monthly-report/run",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "POST",
+            "/api/schedules/nightly-backup/run",
+            "editor@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "POST",
+            "/api/schedules/missing/run",
+            "editor@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "POST",
+            "/api/schedules/monthly-report/run",
+            "editor@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["job_type"], "report");
+    assert_eq!(json["created_by"], "editor@example.com");
+    let id = json["id"].as_i64().unwrap();
+
+    // Scheduler-only job types can't be queued through /api/jobs
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/jobs")
+                .header("cf-access-authenticated-user-email", "editor@example.com")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"type": "prune_metrics"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let reports_dir = TempDir::new().unwrap();
+    let runner =
+        JobRunner::new(db.clone(), None).with_reports_dir(reports_dir.path().to_path_buf());
+    assert_eq!(runner.run_next(JobLane::General).await.unwrap(), Some(id));
+    let job = db.get_job(id).unwrap().unwrap();
+    assert_eq!(job.status, JobStatus::Completed);
+    let path = job.result.unwrap()["path"].as_str().unwrap().to_string();
+    assert!(std::path::Path::new(&path).starts_with(reports_dir.path()));
+    assert!(std::path::Path::new(&path).exists());
+
+    let response = app
+        .oneshot(household_request(
+            "GET",
+            "/api/schedules",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json[0]["last_job_id"], id);
+    assert_eq!(json[0]["last_status"], "completed");
+    assert!(json[0]["last_run_at"].is_string());
+
+    let audit = db.list_audit_log(50).unwrap();
+    assert!(audit
+        .iter()
+        .any(|e| e.action == "run" && e.entity_type.as_deref() == Some("schedule")));
+}
+
+#[tokio::test]
+async fn test_audit_archive_job() {
+    use hone_core::models::{JobStatus, JobType, NewJob};
+
+    let db = Database::in_memory().unwrap();
+    let archive_id = db
+        .create_job(&NewJob::new(
+            JobType::AuditArchive,
+            serde_json::json!({ "retention_days": 30 }),
+        ))
+        .unwrap();
+
+    // Not AI-heavy
+    let runner = JobRunner::new(db.clone(), None);
+    assert_eq!(runner.run_next(JobLane::Ai).await.unwrap(), None);
+    assert_eq!(
+        runner.run_next(JobLane::General).await.unwrap(),
+        Some(archive_id)
+    );
+    let job = db.get_job(archive_id).unwrap().unwrap();
+    assert_eq!(job.status, JobStatus::Completed);
+    assert_eq!(job.result.unwrap()["archived"], 0);
+}
+
+// ========== JSON Transaction Import Tests ==========
+
+#[tokio::test]
+async fn test_import_transactions_rows_and_idempotency() {
+    use hone_core::models::ImportStatus;
+
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    let account_id = db.upsert_account("Checking", Bank::Chase, None).unwrap();
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db.clone(), None, config);
+
+    let body = serde_json::json!({
+        "account_id": account_id,
+        "transactions": [
+            {"date": "2024-01-15", "description": "NETFLIX", "amount": -15.99, "external_id": "n1"},
+            {"date": "not a date", "description": "BROKEN", "amount": -1.0},
+            {"date": "2023-12-15T09:00:00Z", "description": "NETFLIX", "amount": -15.99, "external_id": "n0"},
+            {"date": "2023-11-15", "description": "NETFLIX", "amount": -15.99, "external_id": "n-1"},
+        ]
+    });
+    let keyed = |body: &serde_json::Value, key: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/import/transactions")
+            .header("content-type", "application/json")
+            .header("idempotency-key", key)
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(keyed(&body, "sync-1")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let first = get_body_json(response).await;
+    assert_eq!(first["imported"], 3);
+    assert_eq!(first["invalid"], 1);
+    assert_eq!(first["replayed"], false);
+    let rows = first["rows"].as_array().unwrap();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0]["status"], "imported");
+    assert_eq!(rows[0]["external_id"], "n1");
+    assert_eq!(rows[1]["status"], "invalid");
+    assert!(rows[1]["error"].as_str().unwrap().contains("date"));
+    assert_eq!(rows[2]["status"], "imported");
+    let session_id = first["import_session_id"].as_i64().unwrap();
+    let job_id = first["job_id"].as_i64().unwrap();
+
+    // Retry with the same key: same response, nothing imported again
+    let response = app.clone().oneshot(keyed(&body, "sync-1")).await.unwrap();
+    let replay = get_body_json(response).await;
+    assert_eq!(replay["replayed"], true);
+    assert_eq!(replay["import_session_id"], session_id);
+    assert_eq!(db.list_import_sessions(None, 10, 0).unwrap().len(), 1);
+
+    // Same key, different payload
+    let mut changed = body.clone();
+    changed["transactions"][0]["amount"] = serde_json::json!(-17.99);
+    let response = app
+        .clone()
+        .oneshot(keyed(&changed, "sync-1"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // A new key re-sending the rows: duplicates by external ID
+    let response = app.clone().oneshot(keyed(&body, "sync-2")).await.unwrap();
+    let second = get_body_json(response).await;
+    assert_eq!(second["imported"], 0);
+    assert_eq!(second["skipped"], 3);
+    assert_eq!(second["rows"][0]["status"], "duplicate");
+    assert_eq!(
+        second["rows"][0]["transaction_id"],
+        rows[0]["transaction_id"]
+    );
+    assert!(second["job_id"].is_null());
+
+    // Imported rows go through the normal import processing job
+    let runner = JobRunner::new(db.clone(), None);
+    assert_eq!(runner.run_next(JobLane::Ai).await.unwrap(), Some(job_id));
+    let session = db.get_import_session(session_id).unwrap().unwrap();
+    assert_eq!(session.session.status, ImportStatus::Completed);
+    assert_eq!(session.session.subscriptions_found, 1);
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            "/api/import/transactions",
+            serde_json::json!({"account_id": 999, "transactions": [
+                {"date": "2024-01-15", "description": "X", "amount": -1.0}
+            ]}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = app
+        .oneshot(json_request(
+            "POST",
+            "/api/import/transactions",
+            serde_json::json!({"account_id": account_id, "transactions": []}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_import_into_hidden_account_is_not_found() {
+    use base64::Engine;
+
+    let db = household_db();
+    let owner = db.get_user_by_email("owner@example.com").unwrap().unwrap();
+    let private = db.upsert_account("Owner Card", Bank::Chase, None).unwrap();
+    db.set_account_visibility(private, &[owner.id]).unwrap();
+    let app = household_app(db.clone(), vec![]);
+
+    let response = app
+        .clone()
+        .oneshot(household_json_request(
+            "POST",
+            "/api/import/transactions",
+            "editor@example.com",
+            serde_json::json!({"account_id": private, "transactions": [
+                {"date": "2024-01-15", "description": "X", "amount": -1.0}
+            ]}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let json = get_body_json(response).await;
+    assert!(!json.to_string().contains("Owner Card"));
+
+    let csv = base64::engine::general_purpose::STANDARD
+        .encode("Transaction Date,Post Date,Description,Category,Type,Amount,Memo\n");
+    let response = app
+        .clone()
+        .oneshot(household_json_request(
+            "POST",
+            "/api/import/json",
+            "editor@example.com",
+            serde_json::json!({"account_id": private, "csv_data": csv}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(db.list_import_sessions(None, 10, 0).unwrap().is_empty());
+
+    // Owners see every account
+    let response = app
+        .oneshot(household_json_request(
+            "POST",
+            "/api/import/transactions",
+            "owner@example.com",
+            serde_json::json!({"account_id": private, "transactions": [
+                {"date": "2024-01-15", "description": "X", "amount": -1.0}
+            ]}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_import_idempotency_keys_are_per_caller() {
+    let db = household_db();
+    let account_id = db.upsert_account("Joint", Bank::Chase, None).unwrap();
+    let app = household_app(db.clone(), vec![]);
+    let keyed = |email: &str, description: &str| {
+        let mut request = household_json_request(
+            "POST",
+            "/api/import/transactions",
+            email,
+            serde_json::json!({"account_id": account_id, "transactions": [
+                {"date": "2024-01-15", "description": description, "amount": -1.0}
+            ]}),
+        );
+        request
+            .headers_mut()
+            .insert("idempotency-key", "shared-key".parse().unwrap());
+        request
+    };
+
+    let response = app
+        .clone()
+        .oneshot(keyed("owner@example.com", "OWNER ROW"))
+        .await
+        .unwrap();
+    let owner = get_body_json(response).await;
+    assert_eq!(owner["imported"], 1);
+
+    // The same key from another caller neither replays nor conflicts
+    let response = app
+        .clone()
+        .oneshot(keyed("editor@example.com", "EDITOR ROW"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let editor = get_body_json(response).await;
+    assert_eq!(editor["replayed"], false);
+    assert_eq!(editor["imported"], 1);
+    assert_ne!(editor["import_session_id"], owner["import_session_id"]);
+
+    let response = app
+        .oneshot(keyed("editor@example.com", "EDITOR ROW"))
+        .await
+        .unwrap();
+    let replay = get_body_json(response).await;
+    assert_eq!(replay["replayed"], true);
+    assert_eq!(replay["import_session_id"], editor["import_session_id"]);
+}
+
+// ========== Detection Settings Tests ==========
+
+#[tokio::test]
+async fn test_detection_settings_api() {
+    let db = household_db();
+    let utilities = db.create_tag("Utilities", None, None, None, None).unwrap();
+    let app = household_app(db.clone(), vec![]);
+
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "GET",
+            "/api/settings/detection",
+            "viewer@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["defaults"]["zombie_min_months"], 3);
+    assert_eq!(json["effective"]["zombie_min_months"], 3);
+    assert!(json["merchants"].as_array().unwrap().is_empty());
+
+    // Viewers can't change settings
+    let response = app
+        .clone()
+        .oneshot(household_json_request(
+            "PUT",
+            "/api/settings/detection",
+            "viewer@example.com",
+            serde_json::json!({"zombie_min_months": 6}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app
+        .clone()
+        .oneshot(household_json_request(
+            "PUT",
+            "/api/settings/detection",
+            "editor@example.com",
+            serde_json::json!({"zombie_min_months": 6}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["global"]["zombie_min_months"], 6);
+    assert_eq!(json["effective"]["zombie_min_months"], 6);
+    assert_eq!(json["defaults"]["zombie_min_months"], 3);
+
+    // Ignore price increases under 10% for utilities
+    let response = app
+        .clone()
+        .oneshot(household_json_request(
+            "PUT",
+            &format!("/api/settings/detection/tags/{}", utilities),
+            "editor@example.com",
+            serde_json::json!({"price_increase_percent": 10.0, "price_increase_absolute": 1000.0}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["tags"][0]["tag"], "Utilities");
+
+    let response = app
+        .clone()
+        .oneshot(household_json_request(
+            "PUT",
+            "/api/settings/detection/merchants/Netflix",
+            "editor@example.com",
+            serde_json::json!({"acknowledgment_stale_days": 0}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let settings = db.get_detection_settings().unwrap();
+    assert_eq!(settings.merchants[0].merchant, "Netflix");
+    assert_eq!(
+        settings
+            .config_for(Some("netflix"), &[])
+            .acknowledgment_stale_days,
+        0
+    );
+
+    // Out-of-range values, unknown settings and unknown tags
+    let response = app
+        .clone()
+        .oneshot(household_json_request(
+            "PUT",
+            "/api/settings/detection",
+            "editor@example.com",
+            serde_json::json!({"ollama_confidence_threshold": 2.0}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = app
+        .clone()
+        .oneshot(household_json_request(
+            "PUT",
+            "/api/settings/detection",
+            "editor@example.com",
+            serde_json::json!({"zombie_months": 2}),
+        ))
+        .await
+        .unwrap();
+    assert!(response.status().is_client_error());
+    let response = app
+        .clone()
+        .oneshot(household_json_request(
+            "PUT",
+            "/api/settings/detection/tags/9999",
+            "editor@example.com",
+            serde_json::json!({"zombie_min_months": 2}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .clone()
+        .oneshot(household_request(
+            "DELETE",
+            "/api/settings/detection/merchants/Netflix",
+            "editor@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert!(json["merchants"].as_array().unwrap().is_empty());
+    let response = app
+        .oneshot(household_request(
+            "DELETE",
+            "/api/settings/detection/merchants/Netflix",
+            "editor@example.com",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let logs = db.list_audit_log(50).unwrap();
+    assert!(logs
+        .iter()
+        .any(|l| l.entity_type.as_deref() == Some("detection_settings")));
+}
+
+// ========== Refund Matching Tests ==========
+
+#[tokio::test]
+async fn test_refund_matching_api() {
+    use chrono::NaiveDate;
+    use hone_core::models::NewTransaction;
+
+    let db = Database::in_memory().unwrap();
+    db.seed_root_tags().unwrap();
+    let account_id = db.upsert_account("Card", Bank::Chase, None).unwrap();
+    let insert = |date: &str, description: &str, amount: f64| {
+        db.insert_transaction(
+            account_id,
+            &NewTransaction {
+                date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+                description: description.to_string(),
+                amount,
+                category: None,
+                import_hash: format!("{}-{}", date, description),
+                original_data: None,
+                import_format: None,
+                card_member: None,
+                payment_method: None,
+            },
+        )
+        .unwrap()
+        .unwrap()
+    };
+    let purchase = insert("2024-01-10", "NIKE.COM 12345", -120.00);
+    let refund = insert("2024-01-20", "NIKE.COM REFUND", 120.00);
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db.clone(), None, config);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/transactions/{}/refund-candidates", refund))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json[0]["transaction"]["id"], purchase);
+
+    // Purchases aren't refunds
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/transactions/{}/refund-candidates", purchase))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            "/api/refunds/auto-match",
+            serde_json::json!({}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["matched"], 1);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/transactions/{}/refunds", purchase))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json["refunded_amount"], 120.0);
+    assert_eq!(json["refunds"][0]["refund_transaction_id"], refund);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/reports/spending?from=2024-01-01&to=2024-01-31&net_refunds=true")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json["total"], 0.0);
+
+    // Unlink, then re-link by hand with a partial amount
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/api/transactions/{}/refund", refund))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            &format!("/api/transactions/{}/refund", refund),
+            serde_json::json!({"original_transaction_id": purchase, "amount": 500.0}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            &format!("/api/transactions/{}/refund", refund),
+            serde_json::json!({"original_transaction_id": purchase, "amount": 60.0}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = get_body_json(response).await;
+    assert_eq!(json["source"], "manual");
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/refunds")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let json = get_body_json(response).await;
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert_eq!(json[0]["original"]["id"], purchase);
+    assert_eq!(json[0]["amount"], 60.0);
+}
+
+#[tokio::test]
+async fn test_warranty_api() {
+    use chrono::{Duration, Utc};
+    use hone_core::models::{NewTransaction, NewTransactionSplit, SplitType};
+
+    let db = Database::in_memory().unwrap();
+    let account_id = db.upsert_account("Card", Bank::Chase, None).unwrap();
+    let today = Utc::now().date_naive();
+    let tx_id = db
+        .insert_transaction(
+            account_id,
+            &NewTransaction {
+                date: today - Duration::days(25),
+                description: "APPLE STORE R123".to_string(),
+                amount: -1099.00,
+                category: None,
+                import_hash: "warranty_api".to_string(),
+                original_data: None,
+                import_format: None,
+                card_member: None,
+                payment_method: None,
+            },
+        )
+        .unwrap()
+        .unwrap();
+    let split_id = db
+        .create_split(&NewTransactionSplit {
+            transaction_id: tx_id,
+            amount: 999.00,
+            description: Some("iPhone 15".to_string()),
+            split_type: SplitType::Item,
+            entity_id: None,
+            purchaser_id: None,
+        })
+        .unwrap();
+    let config = ServerConfig {
+        require_auth: false,
+        allowed_origins: vec![],
+        ..Default::default()
+    };
+    let app = create_router(db.clone(), None, config);
+
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            "/api/warranties",
+            serde_json::json!({
+                "split_id": split_id,
+                "return_days": 30,
+                "warranty_months": 12,
+                "serial_number": "F2LX9"
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let warranty = get_body_json(response).await;
+    assert_eq!(warranty["item"], "iPhone 15");
+    assert_eq!(warranty["merchant"], "APPLE STORE R123");
+    let warranty_id = warranty["id"].as_i64().unwrap();
+
+    // A window is required
+    let response = app
+        .clone()
+        .oneshot(json_request(
+            "POST",
+            "/api/warranties",
+            serde_json::json!({"split_id": split_id}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Detection raises the return reminder, with the warranty attached
+    let response = app
+        .clone()
+        .oneshot(json_request("POST", "/api/detect", serde_json::json!({})))
+        .await
+        .unwrap();
+    assert_eq!(run_queued_job(&db, response).await["warranty_reminders"], 1);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/alerts")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let alerts = get_body_json(response).await;
+    let reminder = alerts
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["alert_type"] == "return_window_closing")
+        .unwrap();
+    assert_eq!(reminder["warranty"]["id"], warranty_id);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/reports/warranties?q=iphone")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let report = get_body_json(response).await;
+    assert_eq!(report["returnable_count"], 1);
+    assert_eq!(report["items"][0]["return_days_left"], 5);
+    assert_eq!(report["items"][0]["warranty"]["serial_number"], "F2LX9");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("PATCH")
+                .uri(format!("/api/warranties/{}", warranty_id))
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"notes": "AppleCare+"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(get_body_json(response).await["notes"], "AppleCare+");
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/warranties?q=applecare")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_body_json(response).await.as_array().unwrap().len(), 1);
+
+    let delete = || {
+        Request::builder()
+            .method("DELETE")
+            .uri(format!("/api/warranties/{}", warranty_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.clone().oneshot(delete()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app.oneshot(delete()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,528 @@
+//! Minimal WebAuthn (passkey) verification
+//!
+//! Supports what browsers and platform authenticators use by default: ES256
+//! (P-256) credentials with `attestation: "none"`. Attestation statements are
+//! not verified; we only need the credential's public key, and every login is
+//! checked against it (challenge, origin, RP ID hash, user presence and
+//! verification, signature and signature counter).
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ciborium::value::Value;
+use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+/// COSE algorithm identifier for ES256
+pub const COSE_ALG_ES256: i64 = -7;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+/// Relying party settings (the site passkeys are bound to)
+#[derive(Debug, Clone)]
+pub struct RelyingParty {
+    /// RP ID, normally the host name (e.g., "hone.example.com")
+    pub id: String,
+    /// Exact origin the browser reports (e.g., "https://hone.example.com")
+    pub origin: String,
+}
+
+/// A credential extracted from a registration response
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegisteredCredential {
+    /// Credential ID (base64url)
+    pub credential_id: String,
+    /// SEC1 uncompressed P-256 public key
+    pub public_key: Vec<u8>,
+    pub sign_count: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+    origin: String,
+}
+
+/// Parsed authenticator data
+struct AuthenticatorData<'a> {
+    rp_id_hash: &'a [u8],
+    flags: u8,
+    sign_count: u32,
+    /// Attested credential data (registration only)
+    attested: Option<(Vec<u8>, &'a [u8])>,
+}
+
+pub fn b64url_encode(data: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(data)
+}
+
+pub fn b64url_decode(data: &str) -> Result<Vec<u8>, String> {
+    URL_SAFE_NO_PAD
+        .decode(data.trim_end_matches('='))
+        .map_err(|e| format!("Invalid base64url: {}", e))
+}
+
+/// The challenge a client data blob answers (to find the pending challenge)
+pub fn client_data_challenge(client_data_json: &[u8]) -> Option<String> {
+    serde_json::from_slice::<ClientData>(client_data_json)
+        .ok()
+        .map(|c| c.challenge.trim_end_matches('=').to_string())
+}
+
+/// Verify a registration (`navigator.credentials.create`) response
+pub fn verify_registration(
+    rp: &RelyingParty,
+    expected_challenge: &str,
+    client_data_json: &[u8],
+    attestation_object: &[u8],
+) -> Result<RegisteredCredential, String> {
+    check_client_data(rp, "webauthn.create", expected_challenge, client_data_json)?;
+
+    let attestation: Value = ciborium::de::from_reader(attestation_object)
+        .map_err(|e| format!("Invalid attestation object: {}", e))?;
+    let auth_data = map_get(&attestation, |k| k.as_text() == Some("authData"))
+        .and_then(Value::as_bytes)
+        .ok_or("Attestation object has no authData")?;
+
+    let data = parse_authenticator_data(auth_data)?;
+    check_rp_and_user(rp, &data)?;
+
+    let (credential_id, cose_key) = data
+        .attested
+        .ok_or("Registration response has no credential")?;
+    let public_key = cose_to_sec1(cose_key)?;
+
+    Ok(RegisteredCredential {
+        credential_id: b64url_encode(&credential_id),
+        public_key,
+        sign_count: data.sign_count,
+    })
+}
+
+/// Verify an authentication (`navigator.credentials.get`) response
+///
+/// Returns the new signature counter to store.
+pub fn verify_assertion(
+    rp: &RelyingParty,
+    expected_challenge: &str,
+    client_data_json: &[u8],
+    authenticator_data: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+    stored_sign_count: u32,
+) -> Result<u32, String> {
+    check_client_data(rp, "webauthn.get", expected_challenge, client_data_json)?;
+
+    let data = parse_authenticator_data(authenticator_data)?;
+    check_rp_and_user(rp, &data)?;
+
+    let key = VerifyingKey::from_sec1_bytes(public_key)
+        .map_err(|_| "Stored public key is invalid".to_string())?;
+    let signature =
+        Signature::from_der(signature).map_err(|_| "Signature is not valid DER".to_string())?;
+    let mut signed = authenticator_data.to_vec();
+    signed.extend_from_slice(&Sha256::digest(client_data_json));
+    key.verify(&signed, &signature)
+        .map_err(|_| "Signature verification failed".to_string())?;
+
+    // Authenticators that keep a counter must increase it; a stale value means a cloned key
+    if (data.sign_count != 0 || stored_sign_count != 0) && data.sign_count <= stored_sign_count {
+        return Err("Signature counter did not increase (possible cloned credential)".to_string());
+    }
+
+    Ok(data.sign_count)
+}
+
+fn check_client_data(
+    rp: &RelyingParty,
+    expected_type: &str,
+    expected_challenge: &str,
+    client_data_json: &[u8],
+) -> Result<(), String> {
+    let client_data: ClientData = serde_json::from_slice(client_data_json)
+        .map_err(|e| format!("Invalid clientDataJSON: {}", e))?;
+    if client_data.kind != expected_type {
+        return Err(format!("Unexpected client data type: {}", client_data.kind));
+    }
+    if client_data.challenge.trim_end_matches('=') != expected_challenge {
+        return Err("Challenge mismatch".to_string());
+    }
+    if client_data.origin != rp.origin {
+        return Err(format!("Unexpected origin: {}", client_data.origin));
+    }
+    Ok(())
+}
+
+/// A passkey stands in for the password, so the authenticator must have
+/// verified the user (PIN or biometric), not just seen a touch
+fn check_rp_and_user(rp: &RelyingParty, data: &AuthenticatorData) -> Result<(), String> {
+    if data.rp_id_hash != Sha256::digest(rp.id.as_bytes()).as_slice() {
+        return Err("RP ID hash mismatch".to_string());
+    }
+    if data.flags & FLAG_USER_PRESENT == 0 {
+        return Err("User presence flag not set".to_string());
+    }
+    if data.flags & FLAG_USER_VERIFIED == 0 {
+        return Err("User verification flag not set".to_string());
+    }
+    Ok(())
+}
+
+fn parse_authenticator_data(data: &[u8]) -> Result<AuthenticatorData<'_>, String> {
+    if data.len() < 37 {
+        return Err("Authenticator data too short".to_string());
+    }
+    let flags = data[32];
+    let sign_count = u32::from_be_bytes([data[33], data[34], data[35], data[36]]);
+
+    let attested = if flags & FLAG_ATTESTED_CREDENTIAL != 0 {
+        // aaguid (16) + credential ID length (2) + credential ID + COSE key
+        let rest = &data[37..];
+        if rest.len() < 18 {
+            return Err("Attested credential data too short".to_string());
+        }
+        let id_len = u16::from_be_bytes([rest[16], rest[17]]) as usize;
+        let id_end = 18 + id_len;
+        if rest.len() <= id_end {
+            return Err("Credential ID overruns authenticator data".to_string());
+        }
+        Some((rest[18..id_end].to_vec(), &rest[id_end..]))
+    } else {
+        None
+    };
+
+    Ok(AuthenticatorData {
+        rp_id_hash: &data[..32],
+        flags,
+        sign_count,
+        attested,
+    })
+}
+
+/// Convert a COSE EC2 P-256 key to a SEC1 uncompressed point
+fn cose_to_sec1(cose_key: &[u8]) -> Result<Vec<u8>, String> {
+    let key: Value =
+        ciborium::de::from_reader(cose_key).map_err(|e| format!("Invalid COSE key: {}", e))?;
+    let int_param = |label: i64| {
+        map_get(&key, |k| {
+            k.as_integer().map(i128::from) == Some(i128::from(label))
+        })
+    };
+
+    let alg = int_param(3)
+        .and_then(Value::as_integer)
+        .map(i128::from)
+        .ok_or("COSE key has no algorithm")?;
+    if alg != i128::from(COSE_ALG_ES256) {
+        return Err(format!(
+            "Unsupported passkey algorithm {} (only ES256)",
+            alg
+        ));
+    }
+
+    let x = int_param(-2)
+        .and_then(Value::as_bytes)
+        .ok_or("COSE key has no x")?;
+    let y = int_param(-3)
+        .and_then(Value::as_bytes)
+        .ok_or("COSE key has no y")?;
+    if x.len() != 32 || y.len() != 32 {
+        return Err("COSE key coordinates must be 32 bytes".to_string());
+    }
+
+    let mut point = Vec::with_capacity(65);
+    point.push(0x04);
+    point.extend_from_slice(x);
+    point.extend_from_slice(y);
+    VerifyingKey::from_sec1_bytes(&point).map_err(|_| "COSE key is not on P-256".to_string())?;
+    Ok(point)
+}
+
+fn map_get(map: &Value, matches: impl Fn(&Value) -> bool) -> Option<&Value> {
+    map.as_map()?
+        .iter()
+        .find(|(k, _)| matches(k))
+        .map(|(_, v)| v)
+}
+
+#[cfg(test)]
+pub(crate) mod test_authenticator {
+    //! A software authenticator for tests
+
+    use super::*;
+    use p256::ecdsa::{signature::Signer, SigningKey};
+
+    pub struct TestAuthenticator {
+        pub key: SigningKey,
+        pub credential_id: Vec<u8>,
+        pub counter: u32,
+        /// Whether responses carry the user verification flag
+        pub user_verified: bool,
+    }
+
+    impl TestAuthenticator {
+        pub fn new() -> Self {
+            Self {
+                key: SigningKey::from_slice(&[7u8; 32]).unwrap(),
+                credential_id: vec![1, 2, 3, 4, 5, 6, 7, 8],
+                counter: 0,
+                user_verified: true,
+            }
+        }
+
+        pub fn client_data(kind: &str, challenge: &str, origin: &str) -> Vec<u8> {
+            serde_json::to_vec(&serde_json::json!({
+                "type": kind,
+                "challenge": challenge,
+                "origin": origin,
+            }))
+            .unwrap()
+        }
+
+        fn auth_data(&self, rp_id: &str, mut flags: u8, attested: Option<Vec<u8>>) -> Vec<u8> {
+            if self.user_verified {
+                flags |= FLAG_USER_VERIFIED;
+            }
+            let mut data = Sha256::digest(rp_id.as_bytes()).to_vec();
+            data.push(flags);
+            data.extend_from_slice(&self.counter.to_be_bytes());
+            if let Some(attested) = attested {
+                data.extend_from_slice(&attested);
+            }
+            data
+        }
+
+        /// Attestation object for a registration ("none" format)
+        pub fn attestation_object(&self, rp_id: &str) -> Vec<u8> {
+            let point = self.key.verifying_key().to_encoded_point(false);
+            let cose_key = Value::Map(vec![
+                (Value::Integer(1.into()), Value::Integer(2.into())),
+                (Value::Integer(3.into()), Value::Integer((-7).into())),
+                (Value::Integer((-1).into()), Value::Integer(1.into())),
+                (
+                    Value::Integer((-2).into()),
+                    Value::Bytes(point.x().unwrap().to_vec()),
+                ),
+                (
+                    Value::Integer((-3).into()),
+                    Value::Bytes(point.y().unwrap().to_vec()),
+                ),
+            ]);
+            let mut attested = vec![0u8; 16];
+            attested.extend_from_slice(&(self.credential_id.len() as u16).to_be_bytes());
+            attested.extend_from_slice(&self.credential_id);
+            ciborium::ser::into_writer(&cose_key, &mut attested).unwrap();
+
+            let auth_data = self.auth_data(
+                rp_id,
+                FLAG_USER_PRESENT | FLAG_ATTESTED_CREDENTIAL,
+                Some(attested),
+            );
+            let object = Value::Map(vec![
+                (Value::Text("fmt".into()), Value::Text("none".into())),
+                (Value::Text("attStmt".into()), Value::Map(vec![])),
+                (Value::Text("authData".into()), Value::Bytes(auth_data)),
+            ]);
+            let mut out = Vec::new();
+            ciborium::ser::into_writer(&object, &mut out).unwrap();
+            out
+        }
+
+        /// Authenticator data and DER signature for a login
+        pub fn assert(&mut self, rp_id: &str, client_data: &[u8]) -> (Vec<u8>, Vec<u8>) {
+            self.counter += 1;
+            let auth_data = self.auth_data(rp_id, FLAG_USER_PRESENT, None);
+            let mut signed = auth_data.clone();
+            signed.extend_from_slice(&Sha256::digest(client_data));
+            let signature: Signature = self.key.sign(&signed);
+            (auth_data, signature.to_der().as_bytes().to_vec())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_authenticator::TestAuthenticator;
+    use super::*;
+
+    fn rp() -> RelyingParty {
+        RelyingParty {
+            id: "hone.example.com".to_string(),
+            origin: "https://hone.example.com".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_registration_and_assertion() {
+        let rp = rp();
+        let mut authenticator = TestAuthenticator::new();
+
+        let client_data =
+            TestAuthenticator::client_data("webauthn.create", "reg-challenge", &rp.origin);
+        let credential = verify_registration(
+            &rp,
+            "reg-challenge",
+            &client_data,
+            &authenticator.attestation_object(&rp.id),
+        )
+        .unwrap();
+        assert_eq!(
+            credential.credential_id,
+            b64url_encode(&authenticator.credential_id)
+        );
+        assert_eq!(credential.public_key.len(), 65);
+
+        let client_data =
+            TestAuthenticator::client_data("webauthn.get", "login-challenge", &rp.origin);
+        let (auth_data, signature) = authenticator.assert(&rp.id, &client_data);
+        let count = verify_assertion(
+            &rp,
+            "login-challenge",
+            &client_data,
+            &auth_data,
+            &signature,
+            &credential.public_key,
+            credential.sign_count,
+        )
+        .unwrap();
+        assert_eq!(count, 1);
+
+        // Replaying the same assertion fails the counter check
+        assert!(verify_assertion(
+            &rp,
+            "login-challenge",
+            &client_data,
+            &auth_data,
+            &signature,
+            &credential.public_key,
+            count,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_assertion_rejects_wrong_challenge_origin_and_rp() {
+        let rp = rp();
+        let mut authenticator = TestAuthenticator::new();
+        let credential = verify_registration(
+            &rp,
+            "c",
+            &TestAuthenticator::client_data("webauthn.create", "c", &rp.origin),
+            &authenticator.attestation_object(&rp.id),
+        )
+        .unwrap();
+
+        let client_data = TestAuthenticator::client_data("webauthn.get", "other", &rp.origin);
+        let (auth_data, signature) = authenticator.assert(&rp.id, &client_data);
+        let err = verify_assertion(
+            &rp,
+            "expected",
+            &client_data,
+            &auth_data,
+            &signature,
+            &credential.public_key,
+            0,
+        )
+        .unwrap_err();
+        assert!(err.contains("Challenge"));
+
+        let client_data =
+            TestAuthenticator::client_data("webauthn.get", "expected", "https://evil.example");
+        let (auth_data, signature) = authenticator.assert(&rp.id, &client_data);
+        assert!(verify_assertion(
+            &rp,
+            "expected",
+            &client_data,
+            &auth_data,
+            &signature,
+            &credential.public_key,
+            0,
+        )
+        .unwrap_err()
+        .contains("origin"));
+
+        let client_data = TestAuthenticator::client_data("webauthn.get", "expected", &rp.origin);
+        let (auth_data, signature) = authenticator.assert("evil.example", &client_data);
+        assert!(verify_assertion(
+            &rp,
+            "expected",
+            &client_data,
+            &auth_data,
+            &signature,
+            &credential.public_key,
+            0,
+        )
+        .unwrap_err()
+        .contains("RP ID"));
+    }
+
+    #[test]
+    fn test_assertion_requires_user_verification() {
+        let rp = rp();
+        let mut authenticator = TestAuthenticator::new();
+        let credential = verify_registration(
+            &rp,
+            "c",
+            &TestAuthenticator::client_data("webauthn.create", "c", &rp.origin),
+            &authenticator.attestation_object(&rp.id),
+        )
+        .unwrap();
+
+        // Present but not verified (a touch without PIN or biometric)
+        authenticator.user_verified = false;
+        let client_data = TestAuthenticator::client_data("webauthn.get", "c2", &rp.origin);
+        let (auth_data, signature) = authenticator.assert(&rp.id, &client_data);
+        assert!(verify_assertion(
+            &rp,
+            "c2",
+            &client_data,
+            &auth_data,
+            &signature,
+            &credential.public_key,
+            0,
+        )
+        .unwrap_err()
+        .contains("verification"));
+
+        // Registration needs it too
+        assert!(verify_registration(
+            &rp,
+            "c3",
+            &TestAuthenticator::client_data("webauthn.create", "c3", &rp.origin),
+            &authenticator.attestation_object(&rp.id),
+        )
+        .unwrap_err()
+        .contains("verification"));
+    }
+
+    #[test]
+    fn test_assertion_rejects_bad_signature() {
+        let rp = rp();
+        let mut authenticator = TestAuthenticator::new();
+        let credential = verify_registration(
+            &rp,
+            "c",
+            &TestAuthenticator::client_data("webauthn.create", "c", &rp.origin),
+            &authenticator.attestation_object(&rp.id),
+        )
+        .unwrap();
+
+        let client_data = TestAuthenticator::client_data("webauthn.get", "c2", &rp.origin);
+        let (auth_data, _) = authenticator.assert(&rp.id, &client_data);
+        let other = TestAuthenticator::client_data("webauthn.get", "c3", &rp.origin);
+        let (_, wrong_signature) = authenticator.assert(&rp.id, &other);
+        assert!(verify_assertion(
+            &rp,
+            "c2",
+            &client_data,
+            &auth_data,
+            &wrong_signature,
+            &credential.public_key,
+            0,
+        )
+        .is_err());
+    }
+}
@@ -49,7 +49,8 @@ Hone Model Training Script
 Usage: $0 [OPTIONS]
 
 Options:
-  --task TASK          Training task (classify_merchant, normalize_merchant, classify_subscription)
+  --task TASK          Training task (classify_merchant, normalize_merchant, classify_subscription,
+                       suggest_entity, suggest_split, evaluate_receipt_match, parse_receipt)
   --branch NAME        Experiment branch name (default: main)
   --base-model MODEL   Base Ollama model to fine-tune (default: gemma3:12b)
   --list               List available tasks and their training data counts
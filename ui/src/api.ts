@@ -6,8 +6,9 @@ import type {
   Bank,
   BulkTagsResponse,
   CancelImportResponse,
+  CatalogItem,
+  CancellationStatus,
   DashboardStats,
-  DetectionResults,
   Entity,
   EntityType,
   ExploreModelsResponse,
@@ -23,14 +24,18 @@ import type {
   ImportSessionWithAccount,
   ImportTransactionsResponse,
   InsightFinding,
-  InsightRefreshResponse,
   InsightStatus,
   InsightType,
+  ItemInflationReport,
+  ItemPriceHistory,
+  Job,
   Location,
   LocationType,
   MerchantsReport,
   ModelComparisonStats,
   ModelRecommendation,
+  NewSubscriptionCancellation,
+  NewWarranty,
   OllamaHealthStatus,
   OllamaMetric,
   OllamaStats,
@@ -48,8 +53,10 @@ import type {
   SavingsReport,
   SkippedTransaction,
   SplitType,
+  SplitTypeReport,
   SpendingSummary,
   Subscription,
+  SubscriptionCancellation,
   SubscriptionSummaryReport,
   Tag,
   TagRule,
@@ -60,6 +67,8 @@ import type {
   TransactionTag,
   TrendsReport,
   UserFeedback,
+  Warranty,
+  WarrantyReport,
 } from "./types";
 
 const API_BASE = "/api";
@@ -71,11 +80,18 @@ class ApiError extends Error {
   }
 }
 
+/** CSRF header for built-in session auth (the hone_csrf cookie is set at login) */
+function csrfHeaders(): Record<string, string> {
+  const match = document.cookie.match(/(?:^|;\s*)hone_csrf=([^;]+)/);
+  return match ? { "X-CSRF-Token": match[1] } : {};
+}
+
 async function fetchJson<T>(url: string, options?: RequestInit): Promise<T> {
   const response = await fetch(`${API_BASE}${url}`, {
     ...options,
     headers: {
       "Content-Type": "application/json",
+      ...csrfHeaders(),
       ...options?.headers,
     },
   });
@@ -88,14 +104,91 @@ async function fetchJson<T>(url: string, options?: RequestInit): Promise<T> {
   return response.json();
 }
 
+export type UserRole = "owner" | "editor" | "viewer";
+
+export interface HouseholdUser {
+  id: number;
+  email: string;
+  display_name: string | null;
+  role: UserRole;
+  created_at: string;
+  last_seen_at: string | null;
+  has_password: boolean;
+  totp_enabled: boolean;
+}
+
+export interface AuthStatus {
+  local_auth_enabled: boolean;
+  passkeys_enabled: boolean;
+}
+
+export interface LoginResponse {
+  user: HouseholdUser;
+  csrf_token: string;
+  expires_at: string;
+}
+
+export interface AuthSession {
+  id: number;
+  user_id: number;
+  auth_method: string;
+  ip_address: string | null;
+  user_agent: string | null;
+  created_at: string;
+  last_used_at: string;
+  expires_at: string;
+}
+
+export interface Passkey {
+  id: number;
+  user_id: number;
+  credential_id: string;
+  sign_count: number;
+  name: string;
+  created_at: string;
+  last_used_at: string | null;
+}
+
 export interface MeResponse {
   user: string;
   auth_method: string;
+  role: UserRole;
+  household_user: HouseholdUser | null;
 }
 
 export const api = {
   // Auth
   getMe: () => fetchJson<MeResponse>("/me"),
+  getAuthStatus: () => fetchJson<AuthStatus>("/auth/status"),
+  login: (email: string, password: string, totpCode?: string) =>
+    fetchJson<LoginResponse>("/auth/login", {
+      method: "POST",
+      body: JSON.stringify({ email, password, totp_code: totpCode }),
+    }),
+  logout: () => fetchJson<{ success: boolean }>("/auth/logout", { method: "POST" }),
+  changePassword: (newPassword: string, currentPassword?: string) =>
+    fetchJson<{ success: boolean }>("/auth/password", {
+      method: "POST",
+      body: JSON.stringify({ new_password: newPassword, current_password: currentPassword }),
+    }),
+  setupTotp: () =>
+    fetchJson<{ secret: string; otpauth_uri: string }>("/auth/totp/setup", { method: "POST" }),
+  enableTotp: (code: string) =>
+    fetchJson<{ success: boolean }>("/auth/totp/enable", {
+      method: "POST",
+      body: JSON.stringify({ code }),
+    }),
+  disableTotp: (code: string) =>
+    fetchJson<{ success: boolean }>("/auth/totp/disable", {
+      method: "POST",
+      body: JSON.stringify({ code }),
+    }),
+  getSessions: () => fetchJson<AuthSession[]>("/auth/sessions"),
+  revokeSession: (id: number) =>
+    fetchJson<{ success: boolean }>(`/auth/sessions/${id}`, { method: "DELETE" }),
+  getPasskeys: () => fetchJson<Passkey[]>("/auth/passkeys"),
+  deletePasskey: (id: number) =>
+    fetchJson<{ success: boolean }>(`/auth/passkeys/${id}`, { method: "DELETE" }),
 
   // Dashboard
   getDashboard: () => fetchJson<DashboardStats>("/dashboard"),
@@ -196,10 +289,20 @@ export const api = {
       method: "POST",
     }),
 
-  cancelSubscription: (id: number) =>
-    fetchJson<{ success: boolean }>(`/subscriptions/${id}/cancel`, {
-      method: "POST",
-    }),
+  cancelSubscription: (id: number, details?: NewSubscriptionCancellation) =>
+    fetchJson<{ success: boolean; id: number; cancellation: SubscriptionCancellation }>(
+      `/subscriptions/${id}/cancel`,
+      {
+        method: "POST",
+        body: JSON.stringify(details ?? {}),
+      },
+    ),
+
+  getSubscriptionCancellation: (id: number) =>
+    fetchJson<SubscriptionCancellation>(`/subscriptions/${id}/cancellation`),
+
+  getCancellations: (status?: CancellationStatus) =>
+    fetchJson<SubscriptionCancellation[]>(`/cancellations${status ? `?status=${status}` : ""}`),
 
   excludeSubscription: (id: number) =>
     fetchJson<{ success: boolean }>(`/subscriptions/${id}/exclude`, {
@@ -239,13 +342,17 @@ export const api = {
       method: "POST",
     }),
 
-  // Detection
+  // Detection (queued as a background job)
   runDetection: (kind: "all" | "zombies" | "increases" | "duplicates" = "all") =>
-    fetchJson<DetectionResults>("/detect", {
+    fetchJson<Job>("/detect", {
       method: "POST",
       body: JSON.stringify({ kind }),
     }),
 
+  // Background jobs
+  getJob: (id: number) =>
+    fetchJson<Job>(`/jobs/${id}`),
+
   // Insights
   getTopInsights: (limit = 5) =>
     fetchJson<InsightFinding[]>(`/insights?limit=${limit}`),
@@ -284,7 +391,7 @@ export const api = {
     }),
 
   refreshInsights: () =>
-    fetchJson<InsightRefreshResponse>("/insights/refresh", {
+    fetchJson<Job>("/insights/refresh", {
       method: "POST",
     }),
 
@@ -302,6 +409,7 @@ export const api = {
 
     const response = await fetch(`${API_BASE}/import`, {
       method: "POST",
+      headers: csrfHeaders(),
       body: formData,
     });
 
@@ -485,6 +593,44 @@ export const api = {
 
   getSavingsReport: () => fetchJson<SavingsReport>("/reports/savings"),
 
+  getItemPriceHistory: (id: number) => fetchJson<ItemPriceHistory>(`/reports/items/${id}/prices`),
+
+  getItemInflationReport: (params?: { period?: string; from?: string; to?: string; merchant?: string }) => {
+    const searchParams = new URLSearchParams();
+    if (params?.period) searchParams.set("period", params.period);
+    if (params?.from) searchParams.set("from", params.from);
+    if (params?.to) searchParams.set("to", params.to);
+    if (params?.merchant) searchParams.set("merchant", params.merchant);
+    const query = searchParams.toString();
+    return fetchJson<ItemInflationReport>(`/reports/item-inflation${query ? `?${query}` : ""}`);
+  },
+
+  getSplitTypeReport: (params?: { period?: string; from?: string; to?: string; merchant_limit?: number }) => {
+    const searchParams = new URLSearchParams();
+    if (params?.period) searchParams.set("period", params.period);
+    if (params?.from) searchParams.set("from", params.from);
+    if (params?.to) searchParams.set("to", params.to);
+    if (params?.merchant_limit) searchParams.set("merchant_limit", params.merchant_limit.toString());
+    const query = searchParams.toString();
+    return fetchJson<SplitTypeReport>(`/reports/split-types${query ? `?${query}` : ""}`);
+  },
+
+  // ========== Item Catalog ==========
+  getCatalogItems: (params?: { merchant?: string; q?: string; limit?: number }) => {
+    const searchParams = new URLSearchParams();
+    if (params?.merchant) searchParams.set("merchant", params.merchant);
+    if (params?.q) searchParams.set("q", params.q);
+    if (params?.limit) searchParams.set("limit", params.limit.toString());
+    const query = searchParams.toString();
+    return fetchJson<CatalogItem[]>(`/items${query ? `?${query}` : ""}`);
+  },
+
+  mergeCatalogItem: (id: number, intoId: number) =>
+    fetchJson<CatalogItem>(`/items/${id}/merge`, {
+      method: "POST",
+      body: JSON.stringify({ into_id: intoId }),
+    }),
+
   // ========== Entities ==========
   getEntities: (params?: { entity_type?: EntityType; include_archived?: boolean }) => {
     const searchParams = new URLSearchParams();
@@ -617,6 +763,7 @@ export const api = {
       method: "POST",
       headers: {
         "Content-Type": "application/octet-stream",
+        ...csrfHeaders(),
       },
       body: imageData,
     });
@@ -634,6 +781,7 @@ export const api = {
       method: "POST",
       headers: {
         "Content-Type": "application/octet-stream",
+        ...csrfHeaders(),
       },
       body: imageData,
     });
@@ -673,6 +821,40 @@ export const api = {
       method: "DELETE",
     }),
 
+  // ========== Warranties ==========
+  getWarranties: (params?: { q?: string; receipt_id?: number }) => {
+    const searchParams = new URLSearchParams();
+    if (params?.q) searchParams.set("q", params.q);
+    if (params?.receipt_id) searchParams.set("receipt_id", params.receipt_id.toString());
+    const query = searchParams.toString();
+    return fetchJson<Warranty[]>(`/warranties${query ? `?${query}` : ""}`);
+  },
+
+  createWarranty: (data: NewWarranty) =>
+    fetchJson<Warranty>("/warranties", {
+      method: "POST",
+      body: JSON.stringify(data),
+    }),
+
+  updateWarranty: (id: number, data: Partial<Omit<NewWarranty, "return_days" | "warranty_months">>) =>
+    fetchJson<Warranty>(`/warranties/${id}`, {
+      method: "PATCH",
+      body: JSON.stringify(data),
+    }),
+
+  deleteWarranty: (id: number) =>
+    fetchJson<{ success: boolean }>(`/warranties/${id}`, {
+      method: "DELETE",
+    }),
+
+  getWarrantyReport: (params?: { q?: string; as_of?: string }) => {
+    const searchParams = new URLSearchParams();
+    if (params?.q) searchParams.set("q", params.q);
+    if (params?.as_of) searchParams.set("as_of", params.as_of);
+    const query = searchParams.toString();
+    return fetchJson<WarrantyReport>(`/reports/warranties${query ? `?${query}` : ""}`);
+  },
+
   // ========== Ollama Metrics ==========
   getOllamaStats: (period?: string) => {
     const query = period ? `?period=${period}` : "";
@@ -1,4 +1,4 @@
-import { Ghost, TrendingUp, Users, RotateCcw, ChevronRight, BarChart3, Coins } from "lucide-react";
+import { Ghost, TrendingUp, Users, RotateCcw, ChevronRight, BarChart3, Coins, Hourglass, ShieldAlert, AlertTriangle, Store, Globe, Copy, Undo2, ShieldCheck } from "lucide-react";
 import { useState } from "react";
 import type { Alert } from "../../types";
 import { AlertDetailModal } from "./AlertDetailModal";
@@ -32,6 +32,19 @@ export function AlertCard({ alert, onDismiss, onAcknowledge, onRestore, onExclud
         return "alert-card-zombie cursor-pointer"; // Same style as zombie for now
       case "tip_discrepancy":
         return "alert-card-increase cursor-pointer"; // Same style as price increase for now
+      case "trial_ending":
+        return "alert-card-zombie cursor-pointer";
+      case "charged_after_cancel":
+        return "alert-card-resume cursor-pointer"; // Same style as resume
+      case "unusual_amount":
+      case "unusual_activity":
+      case "duplicate_charge":
+        return "alert-card-increase cursor-pointer";
+      case "new_merchant":
+        return "alert-card-zombie cursor-pointer";
+      case "return_window_closing":
+      case "warranty_expiring":
+        return "alert-card-zombie cursor-pointer";
     }
   };
 
@@ -49,6 +62,22 @@ export function AlertCard({ alert, onDismiss, onAcknowledge, onRestore, onExclud
         return <BarChart3 className={`w-5 h-5 ${alert.dismissed ? "text-hone-400" : "text-attention"}`} />;
       case "tip_discrepancy":
         return <Coins className={`w-5 h-5 ${alert.dismissed ? "text-hone-400" : "text-attention"}`} />;
+      case "trial_ending":
+        return <Hourglass className={`w-5 h-5 ${alert.dismissed ? "text-hone-400" : "text-attention"}`} />;
+      case "charged_after_cancel":
+        return <ShieldAlert className={`w-5 h-5 ${alert.dismissed ? "text-hone-400" : "text-waste"}`} />;
+      case "unusual_amount":
+        return <AlertTriangle className={`w-5 h-5 ${alert.dismissed ? "text-hone-400" : "text-waste"}`} />;
+      case "new_merchant":
+        return <Store className={`w-5 h-5 ${alert.dismissed ? "text-hone-400" : "text-attention"}`} />;
+      case "unusual_activity":
+        return <Globe className={`w-5 h-5 ${alert.dismissed ? "text-hone-400" : "text-waste"}`} />;
+      case "duplicate_charge":
+        return <Copy className={`w-5 h-5 ${alert.dismissed ? "text-hone-400" : "text-waste"}`} />;
+      case "return_window_closing":
+        return <Undo2 className={`w-5 h-5 ${alert.dismissed ? "text-hone-400" : "text-attention"}`} />;
+      case "warranty_expiring":
+        return <ShieldCheck className={`w-5 h-5 ${alert.dismissed ? "text-hone-400" : "text-attention"}`} />;
     }
   };
 
@@ -66,6 +95,22 @@ export function AlertCard({ alert, onDismiss, onAcknowledge, onRestore, onExclud
         return "Spending Change";
       case "tip_discrepancy":
         return "Tip Discrepancy";
+      case "trial_ending":
+        return "Trial Ending";
+      case "charged_after_cancel":
+        return "Charged After Cancelling";
+      case "unusual_amount":
+        return "Unusual Amount";
+      case "new_merchant":
+        return "New Merchant";
+      case "unusual_activity":
+        return "Unusual Activity";
+      case "duplicate_charge":
+        return "Duplicate Charge";
+      case "return_window_closing":
+        return "Return Window Closing";
+      case "warranty_expiring":
+        return "Warranty Expiring";
     }
   };
 
@@ -88,6 +133,8 @@ export function AlertCard({ alert, onDismiss, onAcknowledge, onRestore, onExclud
             <span className={`font-semibold ${alert.dismissed ? "text-hone-500 dark:text-hone-400" : "text-hone-900 dark:text-hone-50"}`}>{getLabel()}</span>
             {alert.subscription && <span className="badge-neutral">{alert.subscription.merchant}</span>}
             {alert.spending_anomaly && <span className="badge-neutral">{alert.spending_anomaly.tag_name}</span>}
+            {alert.trial && <span className="badge-neutral">{alert.trial.merchant}</span>}
+            {alert.warranty && <span className="badge-neutral">{alert.warranty.item}</span>}
             {isAcknowledged && <span className="inline-flex items-center px-2.5 py-0.5 rounded-full text-xs font-medium bg-savings/20 text-savings dark:text-savings-light">Acknowledged</span>}
             {alert.dismissed && !isAcknowledged && <span className="inline-flex items-center px-2.5 py-0.5 rounded-full text-xs font-medium bg-hone-200 dark:bg-hone-600 text-hone-600 dark:text-hone-200">Dismissed</span>}
           </div>
@@ -1,7 +1,8 @@
-import { Ghost, TrendingUp, Users, RotateCcw, X, RefreshCw, BarChart3 } from "lucide-react";
+import { Ghost, TrendingUp, Users, RotateCcw, X, RefreshCw, BarChart3, Hourglass, ShieldAlert, AlertTriangle, Store, Globe, Copy, Undo2, ShieldCheck } from "lucide-react";
 import { useEffect, useState } from "react";
 import { api } from "../../api";
 import type { Alert, Transaction } from "../../types";
+import { formatFrequencySuffix } from "../../utils/frequency";
 import { SplitsModal } from "../Transactions/SplitsModal";
 import { FeedbackButton } from "../common/FeedbackButton";
 
@@ -82,6 +83,22 @@ export function AlertDetailModal({ alert: initialAlert, onClose, onDismiss, onAc
         return <RotateCcw className="w-6 h-6 text-waste" />;
       case "spending_anomaly":
         return <BarChart3 className="w-6 h-6 text-attention" />;
+      case "trial_ending":
+        return <Hourglass className="w-6 h-6 text-attention" />;
+      case "charged_after_cancel":
+        return <ShieldAlert className="w-6 h-6 text-waste" />;
+      case "unusual_amount":
+        return <AlertTriangle className="w-6 h-6 text-waste" />;
+      case "new_merchant":
+        return <Store className="w-6 h-6 text-attention" />;
+      case "unusual_activity":
+        return <Globe className="w-6 h-6 text-waste" />;
+      case "duplicate_charge":
+        return <Copy className="w-6 h-6 text-waste" />;
+      case "return_window_closing":
+        return <Undo2 className="w-6 h-6 text-attention" />;
+      case "warranty_expiring":
+        return <ShieldCheck className="w-6 h-6 text-attention" />;
     }
   };
 
@@ -97,6 +114,22 @@ export function AlertDetailModal({ alert: initialAlert, onClose, onDismiss, onAc
         return "Subscription Resumed";
       case "spending_anomaly":
         return "Spending Change";
+      case "trial_ending":
+        return "Trial Ending";
+      case "charged_after_cancel":
+        return "Charged After Cancelling";
+      case "unusual_amount":
+        return "Unusual Amount";
+      case "new_merchant":
+        return "New Merchant";
+      case "unusual_activity":
+        return "Unusual Activity";
+      case "duplicate_charge":
+        return "Duplicate Charge";
+      case "return_window_closing":
+        return "Return Window Closing";
+      case "warranty_expiring":
+        return "Warranty Expiring";
     }
   };
 
@@ -112,6 +145,22 @@ export function AlertDetailModal({ alert: initialAlert, onClose, onDismiss, onAc
         return "This subscription that was previously cancelled has resumed. Review if this was intentional.";
       case "spending_anomaly":
         return "Your spending in this category changed significantly compared to your 3-month average.";
+      case "trial_ending":
+        return "This looks like a free trial or intro price. Cancel before the conversion date if you don't plan to keep it.";
+      case "charged_after_cancel":
+        return "You cancelled this subscription, but it charged again after the expected final charge. Contact the merchant with your confirmation number and dispute the charge if needed.";
+      case "unusual_amount":
+        return "This charge is much higher than you usually pay this merchant. Check the receipt, or dismiss the alert if it's expected.";
+      case "new_merchant":
+        return "This is a large first charge from a merchant you haven't used before. Make sure you recognize it.";
+      case "unusual_activity":
+        return "This charge was made abroad or without the card present, which is rare for this account. Contact your bank if you don't recognize it.";
+      case "duplicate_charge":
+        return "This merchant charged the same amount twice on the same day. If you only made one purchase, ask the merchant to refund the duplicate.";
+      case "return_window_closing":
+        return "The return window for this purchase closes soon. Return it before then if you don't plan to keep it.";
+      case "warranty_expiring":
+        return "The warranty on this item ends soon. Get any repairs or claims in before then, or consider extending coverage.";
     }
   };
 
@@ -236,7 +285,7 @@ export function AlertDetailModal({ alert: initialAlert, onClose, onDismiss, onAc
                   ${alert.subscription.amount?.toFixed(2) || "—"}
                   {alert.subscription.frequency && (
                     <span className="text-sm text-hone-500 dark:text-hone-400">
-                      /{alert.subscription.frequency.replace("ly", "")}
+                      {formatFrequencySuffix(alert.subscription.frequency)}
                     </span>
                   )}
                 </p>
@@ -312,6 +361,84 @@ export function AlertDetailModal({ alert: initialAlert, onClose, onDismiss, onAc
             </div>
           )}
 
+          {/* Trial Details */}
+          {alert.alert_type === "trial_ending" && alert.trial && (
+            <div className="grid grid-cols-3 gap-4 p-4 bg-hone-50 dark:bg-hone-800/50 rounded-lg">
+              <div>
+                <p className="text-xs text-hone-400 uppercase tracking-wide">Started</p>
+                <p className="text-lg font-semibold text-hone-900 dark:text-hone-50">
+                  {formatDate(alert.trial.started_on)}
+                </p>
+                <p className="text-xs text-hone-500">${alert.trial.trial_amount.toFixed(2)}</p>
+              </div>
+              <div>
+                <p className="text-xs text-hone-400 uppercase tracking-wide">Converts</p>
+                <p className="text-lg font-semibold text-attention">{formatDate(alert.trial.expected_conversion)}</p>
+              </div>
+              <div>
+                <p className="text-xs text-hone-400 uppercase tracking-wide">Full Price</p>
+                <p className="text-lg font-semibold text-hone-600 dark:text-hone-300">
+                  {alert.trial.expected_amount !== null ? `$${alert.trial.expected_amount.toFixed(2)}` : "Unknown"}
+                </p>
+              </div>
+            </div>
+          )}
+
+          {/* Warranty Details */}
+          {alert.warranty && (
+            <div className="grid grid-cols-3 gap-4 p-4 bg-hone-50 dark:bg-hone-800/50 rounded-lg">
+              <div>
+                <p className="text-xs text-hone-400 uppercase tracking-wide">Item</p>
+                <p className="text-lg font-semibold text-hone-900 dark:text-hone-50 truncate">{alert.warranty.item}</p>
+                <p className="text-xs text-hone-500">
+                  {alert.warranty.merchant ? `${alert.warranty.merchant}, ` : ""}
+                  {formatDate(alert.warranty.purchase_date)}
+                </p>
+              </div>
+              <div>
+                <p className="text-xs text-hone-400 uppercase tracking-wide">
+                  {alert.alert_type === "return_window_closing" ? "Return By" : "Warranty Ends"}
+                </p>
+                <p className="text-lg font-semibold text-attention">
+                  {formatDate((alert.alert_type === "return_window_closing" ? alert.warranty.return_by : alert.warranty.warranty_expires) ?? alert.warranty.purchase_date)}
+                </p>
+              </div>
+              <div>
+                <p className="text-xs text-hone-400 uppercase tracking-wide">Price</p>
+                <p className="text-lg font-semibold text-hone-600 dark:text-hone-300">
+                  {alert.warranty.price !== null ? `$${alert.warranty.price.toFixed(2)}` : "Unknown"}
+                </p>
+                {alert.warranty.serial_number && <p className="text-xs text-hone-500 truncate">S/N {alert.warranty.serial_number}</p>}
+              </div>
+            </div>
+          )}
+
+          {/* Transaction Anomaly Details */}
+          {alert.transaction_anomaly && (
+            <div className="grid grid-cols-3 gap-4 p-4 bg-hone-50 dark:bg-hone-800/50 rounded-lg">
+              <div>
+                <p className="text-xs text-hone-400 uppercase tracking-wide">Charged</p>
+                <p className="text-lg font-semibold text-waste">${alert.transaction_anomaly.amount.toFixed(2)}</p>
+                <p className="text-xs text-hone-500">{formatDate(alert.transaction_anomaly.transaction_date)}</p>
+              </div>
+              <div>
+                <p className="text-xs text-hone-400 uppercase tracking-wide">
+                  {alert.transaction_anomaly.baseline_amount !== null ? "Usually" : "Threshold"}
+                </p>
+                <p className="text-lg font-semibold text-hone-900 dark:text-hone-50">
+                  ${(alert.transaction_anomaly.baseline_amount ?? alert.transaction_anomaly.threshold).toFixed(2)}
+                </p>
+              </div>
+              <div>
+                <p className="text-xs text-hone-400 uppercase tracking-wide">Merchant</p>
+                <p className="text-lg font-semibold text-hone-600 dark:text-hone-300 truncate">{alert.transaction_anomaly.merchant}</p>
+                {alert.transaction_anomaly.detail && (
+                  <p className="text-xs text-hone-500">{alert.transaction_anomaly.detail.replace(/card_not_present/g, "card not present").replace(/,/g, ", ")}</p>
+                )}
+              </div>
+            </div>
+          )}
+
           {/* Spending Anomaly Details */}
           {alert.alert_type === "spending_anomaly" && alert.spending_anomaly && (
             <div className="space-y-4">
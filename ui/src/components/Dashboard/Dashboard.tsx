@@ -14,6 +14,7 @@ import { StatCard } from "../common";
 import { AlertItem } from "./AlertItem";
 import { InsightsWidget } from "../Insights/InsightsWidget";
 import { RecentActivity } from "./RecentActivity";
+import { SavedDigests } from "./SavedDigests";
 import { SpendingSnapshot } from "./SpendingSnapshot";
 import { TopCategories } from "./TopCategories";
 import { UpcomingCharges } from "./UpcomingCharges";
@@ -89,6 +90,9 @@ export function Dashboard({
       {/* Insights Widget - proactive financial insights */}
       {hasData && <InsightsWidget limit={5} />}
 
+      {/* Latest answers to scheduled explore questions */}
+      <SavedDigests digests={stats.recent_digests ?? []} onNavigate={onNavigate} />
+
       {/* Active Alerts Section */}
       {alerts.length > 0 && (
         <div className="card">
@@ -0,0 +1,50 @@
+import { ArrowRight, MessageSquare } from "lucide-react";
+import type { RecentDigest } from "../../types";
+import type { View } from "../../hooks";
+
+interface SavedDigestsProps {
+  digests: RecentDigest[];
+  onNavigate: (view: View, subview?: string | null, params?: Record<string, string>) => void;
+}
+
+export function SavedDigests({ digests, onNavigate }: SavedDigestsProps) {
+  if (digests.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className="card">
+      <div className="card-header flex items-center justify-between">
+        <h2 className="text-lg font-semibold flex items-center gap-2">
+          <MessageSquare className="w-5 h-5 text-hone-500" />
+          Saved Questions
+        </h2>
+        <button onClick={() => onNavigate("explore")} className="btn-ghost text-sm">
+          Explore
+          <ArrowRight className="w-4 h-4 ml-1" />
+        </button>
+      </div>
+      <div className="divide-y divide-hone-100 dark:divide-hone-700">
+        {digests.map((digest) => (
+          <div key={digest.digest_id} className="px-4 py-3">
+            <div className="flex items-center justify-between gap-2">
+              <div className="font-medium text-hone-900 dark:text-hone-100 truncate">{digest.name}</div>
+              <div className="text-xs text-hone-500 whitespace-nowrap">
+                {digest.changed && <span className="text-attention font-medium mr-2">Changed</span>}
+                {new Date(digest.created_at).toLocaleDateString("en-US", { month: "short", day: "numeric" })}
+              </div>
+            </div>
+            <p className="text-sm text-hone-600 dark:text-hone-300 mt-1 line-clamp-3 whitespace-pre-line">
+              {digest.response}
+            </p>
+            {digest.tool_call_count > 0 && (
+              <div className="text-xs text-hone-400 mt-1">
+                {digest.tool_call_count} tool call{digest.tool_call_count === 1 ? "" : "s"}
+              </div>
+            )}
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -1,6 +1,8 @@
 import { ArrowRight, Calendar, RefreshCw } from "lucide-react";
 import type { Subscription } from "../../types";
 import type { View } from "../../hooks";
+import { parseLocalDate } from "../../utils/date";
+import { nextChargeDate } from "../../utils/frequency";
 
 interface UpcomingCharge {
   subscription: Subscription;
@@ -16,28 +18,12 @@ interface UpcomingChargesProps {
 function calculateNextChargeDate(subscription: Subscription): Date | null {
   if (!subscription.last_seen || !subscription.frequency) return null;
 
-  const lastSeen = new Date(subscription.last_seen);
   const today = new Date();
   today.setHours(0, 0, 0, 0);
 
-  // Calculate the next expected charge based on frequency
-  const nextCharge = new Date(lastSeen);
-
-  while (nextCharge <= today) {
-    switch (subscription.frequency) {
-      case "weekly":
-        nextCharge.setDate(nextCharge.getDate() + 7);
-        break;
-      case "monthly":
-        nextCharge.setMonth(nextCharge.getMonth() + 1);
-        break;
-      case "yearly":
-        nextCharge.setFullYear(nextCharge.getFullYear() + 1);
-        break;
-    }
-  }
-
-  return nextCharge;
+  // Next expected charge based on frequency (monthly and longer cadences
+  // keep their day of the month; weekly and every-N-days step by days)
+  return nextChargeDate(parseLocalDate(subscription.last_seen), subscription.frequency, today);
 }
 
 export function UpcomingCharges({ subscriptions, onNavigate }: UpcomingChargesProps) {
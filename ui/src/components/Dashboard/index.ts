@@ -1,6 +1,7 @@
 export { Dashboard } from "./Dashboard";
 export { AlertItem } from "./AlertItem";
 export { RecentActivity } from "./RecentActivity";
+export { SavedDigests } from "./SavedDigests";
 export { SpendingSnapshot } from "./SpendingSnapshot";
 export { TopCategories } from "./TopCategories";
 export { UpcomingCharges } from "./UpcomingCharges";
@@ -35,7 +35,12 @@ export function InsightsWidget({ limit = 5, onRefresh }: InsightsWidgetProps) {
   const handleRefresh = async () => {
     setRefreshing(true);
     try {
-      await api.refreshInsights();
+      // Refresh runs as a background job; wait for it before refetching
+      let job = await api.refreshInsights();
+      while (job.status === "queued" || job.status === "running") {
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+        job = await api.getJob(job.id);
+      }
       await fetchInsights();
       onRefresh?.();
     } catch (err) {
@@ -104,7 +104,7 @@ export function ReceiptsPage() {
           <input
             ref={fileInputRef}
             type="file"
-            accept="image/*"
+            accept="image/*,.pdf,.html,.htm"
             onChange={handleFileSelect}
             className="hidden"
           />
@@ -2,6 +2,7 @@ import { useState, useEffect } from "react";
 import { RefreshCw, TrendingUp, Ghost, Copy, TrendingDown, Check, X } from "lucide-react";
 import { api } from "../../api";
 import type { SubscriptionSummaryReport, SavingsReport, Alert } from "../../types";
+import { formatFrequencyLabel, formatFrequencySuffix, frequencyIntervalDays } from "../../utils/frequency";
 
 export function SubscriptionsTab() {
   const [subData, setSubData] = useState<SubscriptionSummaryReport | null>(null);
@@ -98,15 +99,6 @@ export function SubscriptionsTab() {
   // Get price increase alerts
   const priceIncreaseAlerts = alerts.filter((a) => a.alert_type === "price_increase" && !a.dismissed);
 
-  const formatFrequency = (freq: string) => {
-    switch (freq) {
-      case "monthly": return "/mo";
-      case "yearly": return "/yr";
-      case "weekly": return "/wk";
-      default: return "";
-    }
-  };
-
   return (
     <div className="space-y-6">
       {/* Summary cards */}
@@ -147,6 +139,11 @@ export function SubscriptionsTab() {
               <div className="text-sm text-hone-600">
                 By cancelling {savingsData.cancelled_count} subscription{savingsData.cancelled_count !== 1 ? "s" : ""}, you save ${savingsData.total_monthly_saved.toLocaleString(undefined, { minimumFractionDigits: 2 })}/month
               </div>
+              {savingsData.confirmed_count > 0 && (
+                <div className="text-sm text-hone-500">
+                  ${savingsData.confirmed_savings.toLocaleString(undefined, { minimumFractionDigits: 2, maximumFractionDigits: 2 })} confirmed by {savingsData.confirmed_count} verified cancellation{savingsData.confirmed_count !== 1 ? "s" : ""}
+                </div>
+              )}
             </div>
           </div>
         </div>
@@ -179,7 +176,9 @@ export function SubscriptionsTab() {
               const lastSeenDate = parseLocalDate(sub.last_seen);
               const now = new Date();
               const daysSinceLastSeen = Math.floor((now.getTime() - lastSeenDate.getTime()) / (1000 * 60 * 60 * 24));
-              const expectedInterval = sub.frequency === "monthly" ? 45 : sub.frequency === "weekly" ? 14 : 400;
+              // One interval plus half an interval of slack (7-35 days)
+              const interval = frequencyIntervalDays(sub.frequency) ?? 365;
+              const expectedInterval = interval + Math.min(Math.max(interval / 2, 7), 35);
               const likelyCancelled = daysSinceLastSeen > expectedInterval;
 
               return (
@@ -199,7 +198,7 @@ export function SubscriptionsTab() {
                   <div className="flex items-center gap-3">
                     <div className="text-right">
                       <div className="font-semibold text-hone-900 dark:text-hone-100">
-                        ${sub.amount.toFixed(2)}{formatFrequency(sub.frequency)}
+                        ${sub.amount.toFixed(2)}{formatFrequencySuffix(sub.frequency)}
                       </div>
                     </div>
                     <button
@@ -246,7 +245,7 @@ export function SubscriptionsTab() {
                 <div className="text-hone-900 dark:text-hone-100">{alert.message}</div>
                 {alert.subscription && (
                   <div className="text-sm text-hone-500 mt-1">
-                    {alert.subscription.merchant} - ${alert.subscription.amount?.toFixed(2)}{formatFrequency(alert.subscription.frequency || "")}
+                    {alert.subscription.merchant} - ${alert.subscription.amount?.toFixed(2)}{formatFrequencySuffix(alert.subscription.frequency || "")}
                   </div>
                 )}
               </div>
@@ -276,7 +275,7 @@ export function SubscriptionsTab() {
                 <div className="text-hone-900 dark:text-hone-100">{alert.message}</div>
                 {alert.subscription && (
                   <div className="text-sm text-hone-500 mt-1">
-                    Now ${alert.subscription.amount?.toFixed(2)}{formatFrequency(alert.subscription.frequency || "")}
+                    Now ${alert.subscription.amount?.toFixed(2)}{formatFrequencySuffix(alert.subscription.frequency || "")}
                   </div>
                 )}
               </div>
@@ -311,9 +310,9 @@ export function SubscriptionsTab() {
                       return new Date(year, month - 1, day, 12, 0, 0).toLocaleDateString("en-US", { month: "short", year: "numeric" });
                     })()}
                   </td>
-                  <td className="px-4 py-3 text-hone-600 capitalize">{sub.frequency}</td>
+                  <td className="px-4 py-3 text-hone-600">{formatFrequencyLabel(sub.frequency)}</td>
                   <td className="px-4 py-3 text-right font-medium text-hone-900 dark:text-hone-100">
-                    ${sub.amount.toFixed(2)}{formatFrequency(sub.frequency)}
+                    ${sub.amount.toFixed(2)}{formatFrequencySuffix(sub.frequency)}
                   </td>
                 </tr>
               ))}
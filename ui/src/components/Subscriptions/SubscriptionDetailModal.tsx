@@ -2,6 +2,7 @@ import { AlertTriangle, Ban, Calendar, CheckCircle, CreditCard, Ghost, RefreshCw
 import { useEffect, useState } from "react";
 import { api } from "../../api";
 import type { Alert, Subscription, Transaction } from "../../types";
+import { formatFrequencyLabel, formatFrequencySuffix } from "../../utils/frequency";
 import { SplitsModal } from "../Transactions/SplitsModal";
 
 interface SubscriptionDetailModalProps {
@@ -72,32 +73,6 @@ export function SubscriptionDetailModal({
     }
   };
 
-  const formatFrequency = (freq: string | null) => {
-    switch (freq) {
-      case "weekly":
-        return "Weekly";
-      case "monthly":
-        return "Monthly";
-      case "yearly":
-        return "Yearly";
-      default:
-        return "Unknown";
-    }
-  };
-
-  const formatFrequencySuffix = (freq: string | null) => {
-    switch (freq) {
-      case "weekly":
-        return "/week";
-      case "monthly":
-        return "/mo";
-      case "yearly":
-        return "/year";
-      default:
-        return "";
-    }
-  };
-
   const getStatusBadge = () => {
     switch (subscription.status) {
       case "zombie":
@@ -311,7 +286,7 @@ export function SubscriptionDetailModal({
                 <div>
                   <div className="text-sm text-hone-500 dark:text-hone-400">Frequency</div>
                   <div className="text-lg font-semibold text-hone-900 dark:text-hone-100">
-                    {formatFrequency(subscription.frequency)}
+                    {formatFrequencyLabel(subscription.frequency)}
                   </div>
                 </div>
 
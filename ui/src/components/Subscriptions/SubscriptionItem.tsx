@@ -1,5 +1,6 @@
 import { useState } from "react";
 import type { Alert, Subscription } from "../../types";
+import { formatFrequencySuffix } from "../../utils/frequency";
 import { SubscriptionDetailModal } from "./SubscriptionDetailModal";
 
 interface SubscriptionItemProps {
@@ -23,19 +24,6 @@ export function SubscriptionItem({
 }: SubscriptionItemProps) {
   const [showModal, setShowModal] = useState(false);
 
-  const formatFrequency = (freq: string | null) => {
-    switch (freq) {
-      case "weekly":
-        return "/week";
-      case "monthly":
-        return "/mo";
-      case "yearly":
-        return "/year";
-      default:
-        return "";
-    }
-  };
-
   const handleClick = (e: React.MouseEvent) => {
     // Don't open modal if clicking on a button
     if ((e.target as HTMLElement).closest("button")) {
@@ -82,7 +70,7 @@ export function SubscriptionItem({
               <div className="amount-negative font-semibold">
                 ${subscription.amount.toFixed(2)}
                 <span className="text-hone-400 text-sm font-normal">
-                  {formatFrequency(subscription.frequency)}
+                  {formatFrequencySuffix(subscription.frequency)}
                 </span>
               </div>
             </div>
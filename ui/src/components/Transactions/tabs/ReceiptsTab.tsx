@@ -75,7 +75,7 @@ export function ReceiptsTab({
         <input
           ref={fileInputRef}
           type="file"
-          accept="image/*,.pdf"
+          accept="image/*,.pdf,.html,.htm"
           onChange={handleUploadReceipt}
           className="hidden"
         />
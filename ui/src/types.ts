@@ -38,7 +38,14 @@ export interface Transaction {
   tags?: TransactionTag[];
 }
 
-export type Frequency = "weekly" | "monthly" | "yearly";
+export type Frequency =
+  | "weekly"
+  | "biweekly"
+  | "monthly"
+  | "quarterly"
+  | "semiannual"
+  | "yearly"
+  | `every_${number}_days`;
 
 export type SubscriptionStatus = "active" | "cancelled" | "zombie" | "excluded";
 
@@ -48,6 +55,8 @@ export interface Subscription {
   account_id: number | null;
   amount: number | null;
   frequency: Frequency | null;
+  /** Grace window learned from how far this subscription's charges drift */
+  grace_days?: number | null;
   first_seen: string | null;
   last_seen: string | null;
   status: SubscriptionStatus;
@@ -56,7 +65,21 @@ export interface Subscription {
   created_at: string;
 }
 
-export type AlertType = "zombie" | "price_increase" | "duplicate" | "resume" | "spending_anomaly" | "tip_discrepancy";
+export type AlertType =
+  | "zombie"
+  | "price_increase"
+  | "duplicate"
+  | "resume"
+  | "spending_anomaly"
+  | "tip_discrepancy"
+  | "trial_ending"
+  | "charged_after_cancel"
+  | "unusual_amount"
+  | "new_merchant"
+  | "unusual_activity"
+  | "duplicate_charge"
+  | "return_window_closing"
+  | "warranty_expiring";
 
 export interface ServiceFeature {
   service: string;
@@ -84,6 +107,41 @@ export interface SpendingAnomalyData {
   explanation?: SpendingChangeExplanation;
 }
 
+export type TrialSignal = "authorization" | "intro_price" | "known_merchant";
+export type TrialStatus = "pending" | "converted" | "lapsed";
+
+export interface TrialForecast {
+  id: number;
+  merchant: string;
+  account_id: number;
+  transaction_id: number;
+  signal: TrialSignal;
+  trial_amount: number;
+  started_on: string;
+  expected_conversion: string;
+  expected_amount: number | null;
+  status: TrialStatus;
+  alert_id: number | null;
+  converted_transaction_id: number | null;
+  created_at: string;
+}
+
+export interface TransactionAnomaly {
+  id: number;
+  alert_id: number;
+  transaction_id: number;
+  related_transaction_id: number | null;
+  anomaly_type: AlertType;
+  account_id: number;
+  merchant: string;
+  amount: number;
+  transaction_date: string;
+  baseline_amount: number | null;
+  threshold: number;
+  detail: string | null;
+  created_at: string;
+}
+
 export interface Alert {
   id: number;
   alert_type: AlertType;
@@ -93,6 +151,9 @@ export interface Alert {
   created_at: string;
   ollama_analysis?: DuplicateAnalysis;
   spending_anomaly?: SpendingAnomalyData;
+  trial?: TrialForecast;
+  transaction_anomaly?: TransactionAnomaly;
+  warranty?: Warranty;
   subscription?: Subscription;
 }
 
@@ -105,6 +166,17 @@ export interface DashboardStats {
   potential_monthly_savings: number;
   recent_imports: RecentImport[];
   untagged_transactions: number;
+  recent_digests: RecentDigest[];
+}
+
+export interface RecentDigest {
+  saved_question_id: number;
+  name: string;
+  digest_id: number;
+  response: string;
+  changed: boolean;
+  tool_call_count: number;
+  created_at: string;
 }
 
 export type TagSource = "manual" | "pattern" | "ollama" | "rule" | "bank_category" | "learned";
@@ -169,14 +241,42 @@ export interface TransactionResponse {
   offset: number;
 }
 
-export interface DetectionResults {
-  subscriptions_found: number;
-  zombies_detected: number;
-  price_increases_detected: number;
-  duplicates_detected: number;
-  auto_cancelled: number;
-  resumes_detected: number;
-  tip_discrepancies_detected: number;
+// Background job types
+export type JobType =
+  | "import_processing"
+  | "reprocess"
+  | "detection"
+  | "insight_refresh"
+  | "backup"
+  | "report"
+  | "prune_metrics"
+  | "training"
+  | "audit_archive"
+  | "explore_digests"
+  | "notifications";
+
+export type JobStatus = "queued" | "running" | "completed" | "failed" | "cancelled";
+
+export interface Job {
+  id: number;
+  job_type: JobType;
+  status: JobStatus;
+  params: Record<string, unknown>;
+  entity_type?: string;
+  entity_id?: number;
+  progress_phase?: string;
+  progress_current: number;
+  progress_total: number;
+  result?: Record<string, unknown>;
+  error?: string;
+  attempts: number;
+  max_attempts: number;
+  cancel_requested: boolean;
+  created_by?: string;
+  created_at: string;
+  run_after: string;
+  started_at?: string;
+  finished_at?: string;
 }
 
 export interface ImportTaggingBreakdown {
@@ -508,17 +608,123 @@ export interface CancelledSubscriptionInfo {
   merchant: string;
   monthly_amount: number;
   cancelled_at: string;
+  final_charge: string;
   months_counted: number;
+  months_remaining: number;
+  refund: number;
   savings: number;
+  verification: CancellationStatus | null;
 }
 
 export interface SavingsReport {
   total_savings: number;
   total_monthly_saved: number;
   cancelled_count: number;
+  confirmed_savings: number;
+  confirmed_count: number;
   cancelled: CancelledSubscriptionInfo[];
 }
 
+// ========== Item Catalog Types ==========
+
+export interface CatalogItem {
+  id: number;
+  merchant: string;
+  name: string;
+  normalized_name: string;
+  purchase_count: number;
+  first_seen: string | null;
+  last_seen: string | null;
+  last_price: number | null;
+  avg_price: number | null;
+  created_at: string;
+}
+
+export interface ItemPricePoint {
+  date: string;
+  price: number;
+  transaction_id: number;
+  split_id: number;
+  description: string;
+}
+
+export interface ItemPriceHistory {
+  item: CatalogItem;
+  points: ItemPricePoint[];
+  first_price: number | null;
+  latest_price: number | null;
+  min_price: number | null;
+  max_price: number | null;
+  change_percent: number | null;
+}
+
+export interface ItemInflation {
+  item_id: number;
+  merchant: string;
+  name: string;
+  purchase_count: number;
+  start_date: string;
+  start_price: number;
+  end_date: string;
+  end_price: number;
+  change_percent: number;
+  annualized_percent: number | null;
+}
+
+export interface ItemInflationReport {
+  period: ReportPeriod;
+  merchant?: string;
+  basket_change_percent: number | null;
+  items: ItemInflation[];
+}
+
+export interface SplitTypeSummary {
+  split_type: SplitType;
+  amount: number;
+  split_count: number;
+  transaction_count: number;
+  avg_amount: number;
+  top_merchants: MerchantSummary[];
+}
+
+export interface SplitTypeReport {
+  period: ReportPeriod;
+  split_types: SplitTypeSummary[];
+}
+
+// ========== Cancellation Tracking Types ==========
+
+export type CancellationMethod = "website" | "app" | "phone" | "email" | "chat" | "mail" | "in_person" | "bank" | "other";
+
+export type CancellationStatus = "pending" | "confirmed" | "charged_after";
+
+export interface SubscriptionCancellation {
+  id: number;
+  subscription_id: number;
+  merchant: string;
+  cancelled_on: string;
+  method: CancellationMethod | null;
+  confirmation_number: string | null;
+  expected_final_charge: string | null;
+  expected_refund: number | null;
+  refund_transaction_id: number | null;
+  refund_amount: number | null;
+  status: CancellationStatus;
+  late_charge_transaction_id: number | null;
+  verified_at: string | null;
+  notes: string | null;
+  created_at: string;
+}
+
+export interface NewSubscriptionCancellation {
+  cancelled_on?: string;
+  method?: CancellationMethod;
+  confirmation_number?: string;
+  expected_final_charge?: string;
+  expected_refund?: number;
+  notes?: string;
+}
+
 // ========== Entity Types ==========
 
 export type EntityType = "person" | "pet" | "vehicle" | "property";
@@ -616,16 +822,80 @@ export interface ReceiptUploadResponse {
   image_path: string;
 }
 
+/** How a receipt reached the model: photos and scanned PDFs use vision, the rest text */
+export type ReceiptParsePath = "vision" | "pdf_text" | "pdf_raster" | "html" | "text";
+
 export interface PendingReceiptResponse {
   receipt: Receipt;
   image_path: string;
   parsed: ParsedReceipt | null;
+  parse_path: ReceiptParsePath | null;
 }
 
 export interface ReceiptParseResponse {
   receipt_id: number;
   parsed: ParsedReceipt;
   raw_json: string;
+  parse_path: ReceiptParsePath;
+}
+
+// ========== Warranty Types ==========
+
+export type WarrantyStatus = "returnable" | "covered" | "expired";
+
+export interface Warranty {
+  id: number;
+  item: string;
+  merchant: string | null;
+  purchase_date: string;
+  price: number | null;
+  return_by: string | null;
+  warranty_expires: string | null;
+  serial_number: string | null;
+  receipt_id: number | null;
+  split_id: number | null;
+  transaction_id: number | null;
+  /** Supplementary receipt holding the warranty document */
+  document_receipt_id: number | null;
+  notes: string | null;
+  return_alert_id: number | null;
+  warranty_alert_id: number | null;
+  created_at: string;
+}
+
+/** Blank fields are filled from the split, receipt or transaction */
+export interface NewWarranty {
+  item?: string;
+  merchant?: string;
+  purchase_date?: string;
+  price?: number;
+  return_by?: string;
+  return_days?: number;
+  warranty_expires?: string;
+  warranty_months?: number;
+  serial_number?: string;
+  receipt_id?: number;
+  split_id?: number;
+  transaction_id?: number;
+  document_receipt_id?: number;
+  notes?: string;
+}
+
+export interface WarrantyReportItem {
+  warranty: Warranty;
+  status: WarrantyStatus;
+  return_days_left: number | null;
+  warranty_days_left: number | null;
+}
+
+export interface WarrantyReport {
+  as_of: string;
+  query?: string;
+  items: WarrantyReportItem[];
+  returnable_count: number;
+  covered_count: number;
+  expired_count: number;
+  covered_value: number;
 }
 
 // ========== Ollama Metrics Types ==========
@@ -809,7 +1079,7 @@ export interface FeedbackResponse {
 
 // Insight Engine types
 
-export type InsightType = "spending_explainer" | "expense_forecaster" | "savings_opportunity";
+export type InsightType = "spending_explainer" | "expense_forecaster" | "savings_opportunity" | "explore_digest";
 
 export type InsightSeverity = "info" | "attention" | "warning" | "alert";
 
@@ -832,7 +1102,7 @@ export interface SpendingExplainerData {
   top_merchants: MerchantContribution[];
 }
 
-export type ForecastItemType = "subscription" | "estimate" | "large_expense";
+export type ForecastItemType = "subscription" | "estimate" | "large_expense" | "trial";
 
 export interface ForecastItem {
   item_type: ForecastItemType;
@@ -876,9 +1146,6 @@ export interface InsightFinding {
   user_feedback?: string;
 }
 
-export interface InsightRefreshResponse {
-  count: number;
-}
 
 // Explore mode types
 export interface ExploreResponse {
@@ -0,0 +1,69 @@
+import type { Frequency } from "../types";
+
+/** Named cadences: calendar months for day-of-month billing, days otherwise */
+const CADENCES: Record<string, { days: number; months?: number; label: string; suffix: string }> = {
+  weekly: { days: 7, label: "Weekly", suffix: "/week" },
+  biweekly: { days: 14, label: "Every 2 weeks", suffix: "/2 wks" },
+  monthly: { days: 30, months: 1, label: "Monthly", suffix: "/mo" },
+  quarterly: { days: 91, months: 3, label: "Quarterly", suffix: "/qtr" },
+  semiannual: { days: 182, months: 6, label: "Every 6 months", suffix: "/6 mo" },
+  yearly: { days: 365, months: 12, label: "Yearly", suffix: "/year" },
+};
+
+/**
+ * Parse a subscription frequency ("monthly", "every_45_days", ...) into its
+ * billing step. Returns null for a missing or unrecognised frequency.
+ */
+export function parseFrequency(freq: Frequency | string | null): { days: number; months?: number } | null {
+  if (!freq) return null;
+  const named = CADENCES[freq];
+  if (named) return { days: named.days, months: named.months };
+  const match = /^every_(\d+)_days$/.exec(freq);
+  const days = match ? Number(match[1]) : 0;
+  return days > 0 ? { days } : null;
+}
+
+/** Typical days between charges, or null if unknown */
+export function frequencyIntervalDays(freq: Frequency | string | null): number | null {
+  return parseFrequency(freq)?.days ?? null;
+}
+
+/** Human-readable cadence ("Monthly", "Every 45 days") */
+export function formatFrequencyLabel(freq: Frequency | string | null): string {
+  if (freq && CADENCES[freq]) return CADENCES[freq].label;
+  const step = parseFrequency(freq);
+  return step ? `Every ${step.days} days` : "Unknown";
+}
+
+/** Price suffix for a cadence ("/mo", "/45 days"), empty if unknown */
+export function formatFrequencySuffix(freq: Frequency | string | null): string {
+  if (freq && CADENCES[freq]) return CADENCES[freq].suffix;
+  const step = parseFrequency(freq);
+  return step ? `/${step.days} days` : "";
+}
+
+/**
+ * Next expected charge after `today`, stepping from the last charge by the
+ * subscription's cadence. Returns null if the frequency is unknown.
+ */
+export function nextChargeDate(lastSeen: Date, freq: Frequency | string | null, today: Date): Date | null {
+  const step = parseFrequency(freq);
+  if (!step) return null;
+
+  const next = new Date(lastSeen);
+  const billingDay = lastSeen.getDate();
+  let months = 0;
+  while (next <= today) {
+    if (step.months) {
+      // Step from the original billing day so Jan 31 -> Feb 28 -> Mar 31
+      months += step.months;
+      const target = new Date(lastSeen.getFullYear(), lastSeen.getMonth() + months, 1, lastSeen.getHours());
+      const daysInMonth = new Date(target.getFullYear(), target.getMonth() + 1, 0).getDate();
+      target.setDate(Math.min(billingDay, daysInMonth));
+      next.setTime(target.getTime());
+    } else {
+      next.setDate(next.getDate() + step.days);
+    }
+  }
+  return next;
+}